@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+
+/// Token-bucket rate limits for tunneled service traffic, in bytes per second per direction.
+/// A missing value means the path is unlimited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct BandwidthLimit {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub direct_bytes_per_sec: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relayed_bytes_per_sec: Option<u64>,
+}
+
+impl BandwidthLimit {
+    pub fn is_unlimited(&self) -> bool {
+        self.direct_bytes_per_sec.is_none() && self.relayed_bytes_per_sec.is_none()
+    }
+
+    pub fn for_path(&self, relayed: bool) -> Option<u64> {
+        if relayed {
+            self.relayed_bytes_per_sec
+        } else {
+            self.direct_bytes_per_sec
+        }
+    }
+}
+
+/// Serving-side limit for one published service port.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PublishedPortBandwidthLimit {
+    pub service_name: String,
+    pub port_name: String,
+    #[serde(flatten)]
+    pub limit: BandwidthLimit,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Bandwidth {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub published_ports: Vec<PublishedPortBandwidthLimit>,
+}
+
+impl Bandwidth {
+    pub fn published_port_limit(&self, service_name: &str, port_name: &str) -> BandwidthLimit {
+        self.published_ports
+            .iter()
+            .find(|entry| entry.service_name == service_name && entry.port_name == port_name)
+            .map(|entry| entry.limit)
+            .unwrap_or_default()
+    }
+
+    /// Replaces the limit for a published port; an unlimited value removes the entry.
+    pub fn set_published_port_limit(
+        &mut self,
+        service_name: &str,
+        port_name: &str,
+        limit: BandwidthLimit,
+    ) {
+        self.published_ports
+            .retain(|entry| !(entry.service_name == service_name && entry.port_name == port_name));
+        if !limit.is_unlimited() {
+            self.published_ports.push(PublishedPortBandwidthLimit {
+                service_name: service_name.to_string(),
+                port_name: port_name.to_string(),
+                limit,
+            });
+            self.published_ports.sort_by(|left, right| {
+                left.service_name
+                    .cmp(&right.service_name)
+                    .then(left.port_name.cmp(&right.port_name))
+            });
+        }
+    }
+}
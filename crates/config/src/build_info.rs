@@ -7,6 +7,15 @@ pub const NIGHTLY_FUNGI_DIR: &str = ".fungi-nightly";
 pub const STABLE_RPC_ADDRESS: &str = "127.0.0.1:5405";
 pub const NIGHTLY_RPC_ADDRESS: &str = "127.0.0.1:5406";
 
+pub const STABLE_HTTP_GATEWAY_ADDRESS: &str = "127.0.0.1:5480";
+pub const NIGHTLY_HTTP_GATEWAY_ADDRESS: &str = "127.0.0.1:5481";
+
+pub const STABLE_SERVICE_PROXY_ADDRESS: &str = "127.0.0.1:5490";
+pub const NIGHTLY_SERVICE_PROXY_ADDRESS: &str = "127.0.0.1:5491";
+
+pub const STABLE_DNS_RESPONDER_ADDRESS: &str = "127.0.0.1:5453";
+pub const NIGHTLY_DNS_RESPONDER_ADDRESS: &str = "127.0.0.1:5454";
+
 pub fn dist_channel() -> &'static str {
     match option_env!("FUNGI_DIST_CHANNEL").unwrap_or(NIGHTLY_CHANNEL) {
         NIGHTLY_CHANNEL | "dev" => NIGHTLY_CHANNEL,
@@ -35,6 +44,30 @@ pub fn default_rpc_address() -> &'static str {
     }
 }
 
+pub fn default_http_gateway_address() -> &'static str {
+    if is_nightly() {
+        NIGHTLY_HTTP_GATEWAY_ADDRESS
+    } else {
+        STABLE_HTTP_GATEWAY_ADDRESS
+    }
+}
+
+pub fn default_service_proxy_address() -> &'static str {
+    if is_nightly() {
+        NIGHTLY_SERVICE_PROXY_ADDRESS
+    } else {
+        STABLE_SERVICE_PROXY_ADDRESS
+    }
+}
+
+pub fn default_dns_responder_address() -> &'static str {
+    if is_nightly() {
+        NIGHTLY_DNS_RESPONDER_ADDRESS
+    } else {
+        STABLE_DNS_RESPONDER_ADDRESS
+    }
+}
+
 pub fn build_commit() -> &'static str {
     option_env!("FUNGI_BUILD_COMMIT").unwrap_or("unknown")
 }
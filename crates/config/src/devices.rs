@@ -76,6 +76,9 @@ pub struct DeviceInfo {
     #[serde(default)]
     pub multiaddrs: Vec<String>,
     pub private_ips: Vec<String>,
+    /// Hardware addresses of the LAN interfaces, used to send Wake-on-LAN packets.
+    #[serde(default)]
+    pub mac_addresses: Vec<String>,
     pub os: Os,
     pub version: String,
 
@@ -98,6 +101,7 @@ impl DeviceInfo {
             os,
             public_ip: None,
             private_ips: local_ip.map(|ip| vec![ip]).unwrap_or_default(),
+            mac_addresses: fungi_util::get_local_mac_addresses(),
             version,
             created_at: SystemTime::now(),
             last_connected: SystemTime::now(),
@@ -140,6 +144,7 @@ impl DeviceInfo {
             os: Os::Unknown,
             public_ip: None,
             private_ips: vec![],
+            mac_addresses: vec![],
             version: String::new(),
             created_at: SystemTime::now(),
             last_connected: SystemTime::now(),
@@ -174,6 +179,16 @@ impl TryFrom<&mdns_sd::TxtProperties> for DeviceInfo {
             .get("private_ips")
             .map(|s| s.val_str().split(',').map(String::from).collect())
             .unwrap_or_default();
+        let mac_addresses = properties
+            .get("mac_addresses")
+            .map(|s| {
+                s.val_str()
+                    .split(',')
+                    .filter(|value| !value.is_empty())
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default();
         // TODO duplicate with libp2p-mdns?
         let multiaddrs = properties
             .get("multiaddrs")
@@ -194,6 +209,7 @@ impl TryFrom<&mdns_sd::TxtProperties> for DeviceInfo {
             os,
             public_ip,
             private_ips,
+            mac_addresses,
             version,
             created_at: SystemTime::now(),
             last_connected: SystemTime::now(),
@@ -342,6 +358,36 @@ impl DevicesConfig {
             .collect()
     }
 
+    /// Records the LAN addresses and MAC addresses last seen over mDNS for a saved device, so
+    /// they are still known once the device goes to sleep. Returns `None` when nothing changed.
+    pub fn record_lan_details(
+        &self,
+        peer_id: &PeerId,
+        private_ips: &[String],
+        mac_addresses: &[String],
+    ) -> Result<Option<Self>> {
+        let Some(device) = self.get_device_info(peer_id) else {
+            return Ok(None);
+        };
+        let ips_changed = !private_ips.is_empty() && device.private_ips != private_ips;
+        let macs_changed = !mac_addresses.is_empty() && device.mac_addresses != mac_addresses;
+        if !ips_changed && !macs_changed {
+            return Ok(None);
+        }
+
+        self.update_and_save(|config| {
+            if let Some(device) = config.devices.iter_mut().find(|p| p.peer_id == *peer_id) {
+                if ips_changed {
+                    device.private_ips = private_ips.to_vec();
+                }
+                if macs_changed {
+                    device.mac_addresses = mac_addresses.to_vec();
+                }
+            }
+        })
+        .map(Some)
+    }
+
     pub fn remove_device(&self, peer_id: &PeerId) -> Result<Self> {
         self.update_and_save(|config| {
             config.devices.retain(|p| p.peer_id != *peer_id);
@@ -388,6 +434,7 @@ mod tests {
             os: Os::this_device(),
             public_ip: None,
             private_ips: vec![],
+            mac_addresses: vec![],
             version: "1.0.0".to_string(),
             created_at: SystemTime::now(),
             last_connected: SystemTime::now(),
@@ -413,6 +460,7 @@ mod tests {
             os: Os::this_device(),
             public_ip: None,
             private_ips: vec![],
+            mac_addresses: vec![],
             version: "1.0.0".to_string(),
             created_at: SystemTime::now(),
             last_connected: SystemTime::now(),
@@ -427,6 +475,7 @@ mod tests {
             os: Os::this_device(),
             public_ip: None,
             private_ips: vec![],
+            mac_addresses: vec![],
             version: "1.0.1".to_string(),
             created_at: SystemTime::now(),
             last_connected: SystemTime::now(),
@@ -452,6 +501,7 @@ mod tests {
             os: Os::this_device(),
             public_ip: None,
             private_ips: vec![],
+            mac_addresses: vec![],
             version: "1.0.0".to_string(),
             created_at: SystemTime::now(),
             last_connected: SystemTime::now(),
@@ -465,6 +515,7 @@ mod tests {
             os: Os::this_device(),
             public_ip: None,
             private_ips: vec![],
+            mac_addresses: vec![],
             version: "1.0.0".to_string(),
             created_at: SystemTime::now(),
             last_connected: SystemTime::now(),
@@ -485,6 +536,7 @@ mod tests {
             os: Os::this_device(),
             public_ip: None,
             private_ips: vec![],
+            mac_addresses: vec![],
             version: "1.0.0".to_string(),
             created_at: SystemTime::now(),
             last_connected: SystemTime::now(),
@@ -504,6 +556,7 @@ mod tests {
             os: Os::this_device(),
             public_ip: None,
             private_ips: vec![],
+            mac_addresses: vec![],
             version: "1.0.0".to_string(),
             created_at: SystemTime::now(),
             last_connected: SystemTime::now(),
@@ -530,6 +583,7 @@ mod tests {
             os: Os::this_device(),
             public_ip: None,
             private_ips: vec![],
+            mac_addresses: vec![],
             version: "1.0.0".to_string(),
             created_at: SystemTime::now(),
             last_connected: SystemTime::now(),
@@ -543,4 +597,36 @@ mod tests {
             Some("work-laptop".to_string())
         );
     }
+
+    #[test]
+    fn test_record_lan_details_updates_saved_device_once() {
+        let (config, _temp_dir) = create_temp_devices_config();
+        let peer_id = PeerId::random();
+        let mut device_info = DeviceInfo::new_unknown(peer_id);
+        device_info.name = Some("nas".to_string());
+        let config = config.add_or_update_device(device_info).unwrap();
+        let ips = vec!["192.168.1.20".to_string()];
+        let macs = vec!["aa:bb:cc:dd:ee:ff".to_string()];
+
+        let updated = config
+            .record_lan_details(&peer_id, &ips, &macs)
+            .unwrap()
+            .expect("first observation should update the device");
+        let device = updated.get_device_info(&peer_id).unwrap();
+        assert_eq!(device.private_ips, ips);
+        assert_eq!(device.mac_addresses, macs);
+
+        assert!(
+            updated
+                .record_lan_details(&peer_id, &ips, &macs)
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            updated
+                .record_lan_details(&PeerId::random(), &ips, &macs)
+                .unwrap()
+                .is_none()
+        );
+    }
 }
@@ -214,6 +214,7 @@ mod tests {
                 hostname: Some("nas.local".to_string()),
                 multiaddrs: vec!["/ip4/192.168.1.10/tcp/4001".to_string()],
                 private_ips: vec![],
+                mac_addresses: vec![],
                 os: Os::Unknown,
                 version: "1.0.0".to_string(),
                 public_ip: None,
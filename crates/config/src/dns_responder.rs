@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_DNS_RESPONDER_DOMAIN: &str = "fungi";
+
+/// Loopback DNS server answering A/AAAA and SRV queries for `<device>.fungi` and
+/// `<service>.<device>.fungi`, meant to be chained from systemd-resolved or dnsmasq.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DnsResponder {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_dns_responder_listen_address")]
+    pub listen_address: String,
+    #[serde(default = "default_dns_responder_domain")]
+    pub domain: String,
+}
+
+impl Default for DnsResponder {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            listen_address: default_dns_responder_listen_address(),
+            domain: default_dns_responder_domain(),
+        }
+    }
+}
+
+fn default_dns_responder_listen_address() -> String {
+    crate::default_dns_responder_address().to_string()
+}
+
+fn default_dns_responder_domain() -> String {
+    DEFAULT_DNS_RESPONDER_DOMAIN.to_string()
+}
@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_HTTP_GATEWAY_DOMAIN: &str = "fungi.localhost";
+
+/// Local HTTP gateway that serves attached web services as
+/// `http://<service>.<device>.fungi.localhost:<port>`. Off by default: anything on this machine
+/// that can reach the listen address can use the services of every trusted device through it.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct HttpGateway {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_http_gateway_listen_address")]
+    pub listen_address: String,
+    /// Browsers resolve every `*.localhost` name to loopback, so no DNS setup is needed
+    /// as long as the domain stays under `localhost`.
+    #[serde(default = "default_http_gateway_domain")]
+    pub domain: String,
+}
+
+impl Default for HttpGateway {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            listen_address: default_http_gateway_listen_address(),
+            domain: default_http_gateway_domain(),
+        }
+    }
+}
+
+fn default_http_gateway_listen_address() -> String {
+    crate::default_http_gateway_address().to_string()
+}
+
+fn default_http_gateway_domain() -> String {
+    DEFAULT_HTTP_GATEWAY_DOMAIN.to_string()
+}
@@ -1,22 +1,33 @@
+pub mod bandwidth;
 mod build_info;
 pub mod devices;
 pub mod direct_addresses;
+pub mod dns_responder;
+pub mod http_gateway;
 mod init;
 mod libp2p;
 pub mod local_preferences;
 pub mod paths;
 pub mod recipe_cache;
+pub mod recipe_sources;
 mod rpc;
 pub mod runtime;
+pub mod service_backups;
 pub mod service_cache;
+pub mod service_exec;
+pub mod service_proxy;
+pub mod service_transfer;
 pub mod tcp_tunneling;
 pub mod trusted_devices;
 
 pub use crate::libp2p::*;
 pub use build_info::{
-    NIGHTLY_CHANNEL, NIGHTLY_FUNGI_DIR, NIGHTLY_RPC_ADDRESS, STABLE_CHANNEL, STABLE_FUNGI_DIR,
-    STABLE_RPC_ADDRESS, build_commit, build_time, default_fungi_dir_name, default_rpc_address,
-    dist_channel,
+    NIGHTLY_CHANNEL, NIGHTLY_DNS_RESPONDER_ADDRESS, NIGHTLY_FUNGI_DIR,
+    NIGHTLY_HTTP_GATEWAY_ADDRESS, NIGHTLY_RPC_ADDRESS, NIGHTLY_SERVICE_PROXY_ADDRESS,
+    STABLE_CHANNEL, STABLE_DNS_RESPONDER_ADDRESS, STABLE_FUNGI_DIR, STABLE_HTTP_GATEWAY_ADDRESS,
+    STABLE_RPC_ADDRESS, STABLE_SERVICE_PROXY_ADDRESS, build_commit, build_time,
+    default_dns_responder_address, default_fungi_dir_name, default_http_gateway_address,
+    default_rpc_address, default_service_proxy_address, dist_channel,
 };
 pub use fungi_config_migrate::{
     DetectedVersion as FungiDirDetectedVersion, MigrationReport, migrate_if_needed,
@@ -49,6 +60,22 @@ pub struct FungiConfig {
     pub network: Network,
     #[serde(default)]
     pub runtime: Runtime,
+    #[serde(default)]
+    pub bandwidth: bandwidth::Bandwidth,
+    #[serde(default)]
+    pub http_gateway: http_gateway::HttpGateway,
+    #[serde(default)]
+    pub service_proxy: service_proxy::ServiceProxy,
+    #[serde(default)]
+    pub dns_responder: dns_responder::DnsResponder,
+    #[serde(default)]
+    pub recipes: recipe_sources::RecipeSources,
+    #[serde(default)]
+    pub service_backups: service_backups::ServiceBackups,
+    #[serde(default)]
+    pub service_exec: service_exec::ServiceExec,
+    #[serde(default)]
+    pub service_transfer: service_transfer::ServiceTransfer,
 
     #[serde(default)]
     custom_hostname: Option<String>,
@@ -64,6 +91,14 @@ impl Default for FungiConfig {
             rpc: rpc::Rpc::default(),
             network: Network::default(),
             runtime: Runtime::default(),
+            bandwidth: bandwidth::Bandwidth::default(),
+            http_gateway: http_gateway::HttpGateway::default(),
+            service_proxy: service_proxy::ServiceProxy::default(),
+            dns_responder: dns_responder::DnsResponder::default(),
+            recipes: recipe_sources::RecipeSources::default(),
+            service_backups: service_backups::ServiceBackups::default(),
+            service_exec: service_exec::ServiceExec::default(),
+            service_transfer: service_transfer::ServiceTransfer::default(),
             custom_hostname: None,
             config_file: PathBuf::new(),
         }
@@ -122,6 +157,7 @@ impl FungiConfig {
 
     pub fn parse_toml(s: &str) -> Result<Self> {
         let config: Self = toml::from_str(s).context("Failed to parse config file")?;
+        config.recipes.validate()?;
         Ok(config)
     }
 
@@ -186,6 +222,19 @@ impl FungiConfig {
         })
     }
 
+    pub fn set_published_port_bandwidth_limit(
+        &self,
+        service_name: &str,
+        port_name: &str,
+        limit: bandwidth::BandwidthLimit,
+    ) -> Result<Self> {
+        self.update_and_save(|config| {
+            config
+                .bandwidth
+                .set_published_port_limit(service_name, port_name, limit);
+        })
+    }
+
     pub fn set_relay_enabled(&self, enabled: bool) -> Result<Self> {
         self.update_and_save(|config| {
             config.network.relay_enabled = enabled;
@@ -270,6 +319,38 @@ mod tests {
         assert!(config.network.custom_relay_addresses.is_empty());
     }
 
+    #[test]
+    fn test_service_exec_allows_only_listed_peers() {
+        let (config, _temp_dir) = create_temp_config();
+        let peer_id = libp2p_identity::PeerId::random();
+        assert!(!config.service_exec.allows(&peer_id));
+
+        let parsed: FungiConfig = toml::from_str(&format!(
+            "[service_exec]\nallowed_peers = [\"{peer_id}\"]\n"
+        ))
+        .unwrap();
+
+        assert!(parsed.service_exec.allows(&peer_id));
+        assert!(
+            !parsed
+                .service_exec
+                .allows(&libp2p_identity::PeerId::random())
+        );
+    }
+
+    #[test]
+    fn test_service_transfer_allows_only_listed_peers() {
+        let (config, _temp_dir) = create_temp_config();
+        let peer_id = libp2p_identity::PeerId::random();
+        assert!(!config.service_transfer.allows(&peer_id));
+
+        let parsed: FungiConfig = toml::from_str(&format!(
+            "[service_transfer]\nallowed_peers = [\"{peer_id}\"]\n"
+        ))
+        .unwrap();
+        assert!(parsed.service_transfer.allows(&peer_id));
+    }
+
     #[test]
     fn test_set_relay_enabled_persists() {
         let (config, _temp_dir) = create_temp_config();
@@ -319,6 +400,32 @@ mod tests {
         assert!(!content.contains(&address.to_string()));
     }
 
+    #[test]
+    fn test_set_published_port_bandwidth_limit_persists() {
+        let (config, _temp_dir) = create_temp_config();
+        let limit = bandwidth::BandwidthLimit {
+            direct_bytes_per_sec: None,
+            relayed_bytes_per_sec: Some(256 * 1024),
+        };
+
+        let updated = config
+            .set_published_port_bandwidth_limit("files", "web", limit)
+            .unwrap();
+        assert_eq!(
+            updated.bandwidth.published_port_limit("files", "web"),
+            limit
+        );
+        let content = std::fs::read_to_string(&config.config_file).unwrap();
+        assert!(content.contains("relayed_bytes_per_sec = 262144"));
+
+        let cleared = updated
+            .set_published_port_bandwidth_limit("files", "web", Default::default())
+            .unwrap();
+        assert!(cleared.bandwidth.published_ports.is_empty());
+        let content = std::fs::read_to_string(&config.config_file).unwrap();
+        assert!(!content.contains("published_ports"));
+    }
+
     #[test]
     fn test_effective_relay_addresses_follow_flags() {
         let mut network = Network::default();
@@ -345,4 +452,39 @@ mod tests {
         let effective = network.effective_relay_addresses(&[community]);
         assert!(effective.is_empty());
     }
+
+    #[test]
+    fn test_network_listen_and_announce_settings_parse() {
+        let network: Network = toml::from_str(
+            r#"
+listen_addresses = ["/ip6/::/tcp/4001", "/ip4/192.168.1.10/udp/4001/quic-v1"]
+announce_addresses = ["/ip4/203.0.113.7/tcp/4001"]
+no_announce = ["100.64.0.0/10", "fd7a:115c:a1e0::/48"]
+exclude_interfaces = ["docker*"]
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(network.listen_addresses.len(), 2);
+        assert_eq!(
+            network.announce_addresses,
+            vec!["/ip4/203.0.113.7/tcp/4001".parse::<Multiaddr>().unwrap()]
+        );
+        assert!(network.no_announce[0].contains(&"100.101.102.103".parse().unwrap()));
+        assert_eq!(network.exclude_interfaces, vec!["docker*".to_string()]);
+
+        let defaults: Network = toml::from_str("").unwrap();
+        assert!(defaults.listen_addresses.is_empty());
+        assert!(defaults.exclude_interfaces.contains(&"docker*".to_string()));
+        assert!(
+            !defaults
+                .excluded_interface_patterns()
+                .contains(&"tailscale*".to_string())
+        );
+
+        let with_vpn: Network = toml::from_str("exclude_vpn_interfaces = true").unwrap();
+        let patterns = with_vpn.excluded_interface_patterns();
+        assert!(patterns.contains(&"docker*".to_string()));
+        assert!(patterns.contains(&"tailscale*".to_string()));
+    }
 }
@@ -1,3 +1,4 @@
+use fungi_util::net::IpCidr;
 use multiaddr::Multiaddr;
 use serde::{Deserialize, Serialize};
 
@@ -15,6 +16,18 @@ const fn default_relay_enabled() -> bool {
     true
 }
 
+/// Container and VM bridges, whose addresses other devices cannot reach.
+fn default_exclude_interfaces() -> Vec<String> {
+    ["docker*", "br-*", "veth*", "virbr*", "cni*", "podman*"]
+        .into_iter()
+        .map(str::to_string)
+        .collect()
+}
+
+/// VPN tunnels, excluded only with `exclude_vpn_interfaces`: for devices that reach each other
+/// over the VPN, these are the addresses that work.
+const VPN_INTERFACES: [&str; 4] = ["tailscale*", "utun*", "wg*", "zt*"];
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum RelayAddressSource {
     Community,
@@ -33,6 +46,25 @@ pub struct Network {
     pub listen_tcp_port: u16,
     #[serde(default)]
     pub listen_udp_port: u16,
+    /// Explicit listen multiaddrs such as `/ip6/::/tcp/4001` or
+    /// `/ip4/192.168.1.10/udp/4001/quic-v1`. When empty the daemon listens on every IPv4 and
+    /// IPv6 interface using `listen_tcp_port` and `listen_udp_port`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub listen_addresses: Vec<Multiaddr>,
+    /// Extra addresses advertised to peers, e.g. a port-forwarded public address.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub announce_addresses: Vec<Multiaddr>,
+    /// Networks whose addresses are never advertised to peers, e.g. `100.64.0.0/10`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub no_announce: Vec<IpCidr>,
+    /// Interfaces whose addresses are kept out of mDNS and identify. A trailing `*` matches any
+    /// suffix. Only announcements are filtered: libp2p's mDNS still sends its queries on these
+    /// interfaces, and connections arriving on them are accepted.
+    #[serde(default = "default_exclude_interfaces")]
+    pub exclude_interfaces: Vec<String>,
+    /// Also exclude VPN tunnels (`tailscale*`, `utun*`, `wg*`, `zt*`).
+    #[serde(default)]
+    pub exclude_vpn_interfaces: bool,
     #[serde(default = "default_relay_enabled")]
     pub relay_enabled: bool,
     #[serde(default = "default_use_community_relays")]
@@ -40,7 +72,8 @@ pub struct Network {
     /// Relay multiaddrs kept in one user-facing list.
     ///
     /// Candidates are grouped by relay peer. Each group is tried UDP/QUIC
-    /// first, then TCP for reservation and circuit availability.
+    /// first, then TCP, then WebSocket (`/tcp/443/tls/ws`) for reservation
+    /// and circuit availability.
     #[serde(default)]
     pub custom_relay_addresses: Vec<Multiaddr>,
     #[serde(default = "default_idle_connection_timeout_secs")]
@@ -52,6 +85,15 @@ const fn default_use_community_relays() -> bool {
 }
 
 impl Network {
+    /// `exclude_interfaces`, plus the VPN tunnels when `exclude_vpn_interfaces` is set.
+    pub fn excluded_interface_patterns(&self) -> Vec<String> {
+        let mut patterns = self.exclude_interfaces.clone();
+        if self.exclude_vpn_interfaces {
+            patterns.extend(VPN_INTERFACES.map(str::to_string));
+        }
+        patterns
+    }
+
     pub fn effective_relay_addresses(
         &self,
         community_relays: &[Multiaddr],
@@ -101,6 +143,11 @@ impl Default for Network {
         Self {
             listen_tcp_port: 0,
             listen_udp_port: 0,
+            listen_addresses: Vec::new(),
+            announce_addresses: Vec::new(),
+            no_announce: Vec::new(),
+            exclude_interfaces: default_exclude_interfaces(),
+            exclude_vpn_interfaces: false,
             relay_enabled: default_relay_enabled(),
             use_community_relays: default_use_community_relays(),
             custom_relay_addresses: Vec::new(),
@@ -1,8 +1,13 @@
-use std::path::{Path, PathBuf};
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+};
 
 use anyhow::{Context as _, Result, bail};
 use serde::{Deserialize, Serialize};
 
+use crate::{bandwidth::BandwidthLimit, tcp_tunneling::ServiceRouting};
+
 const LOCAL_PREFERENCES_FILE: &str = "cache/local_preferences.json";
 
 #[derive(Debug, Clone, Default)]
@@ -21,6 +26,21 @@ pub struct LocalServicePreference {
     pub local_port: u16,
     #[serde(default)]
     pub local_port_source: LocalPortSource,
+    #[serde(default, skip_serializing_if = "BandwidthLimit::is_unlimited")]
+    pub bandwidth_limit: BandwidthLimit,
+    /// Other devices running the same service definition, reached through the same local port.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub group: Vec<ServiceGroupMember>,
+    #[serde(default, skip_serializing_if = "ServiceRouting::is_failover")]
+    pub routing: ServiceRouting,
+}
+
+/// A device in a service group together with the name its copy of the service runs under.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ServiceGroupMember {
+    pub peer_id: String,
+    pub service_name: String,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
@@ -155,6 +175,56 @@ impl LocalPreferenceCache {
         Ok(updated)
     }
 
+    /// Points records of `remote_service_name` on `from_peer_id` at the same service on
+    /// `to_peer_id`, including where the service is a member of another record's group. Records
+    /// that would duplicate an existing one for the target device are dropped.
+    pub fn repoint_service_records(
+        &self,
+        remote_service_name: &str,
+        from_peer_id: &str,
+        to_peer_id: &str,
+    ) -> Result<Self> {
+        let mut updated = self.clone();
+        updated.records = self
+            .records
+            .iter()
+            .filter(|record| {
+                !(record.remote_peer_id == from_peer_id
+                    && record.remote_service_name == remote_service_name
+                    && self
+                        .find_record(
+                            to_peer_id,
+                            remote_service_name,
+                            &record.remote_service_port_name,
+                        )
+                        .is_some())
+            })
+            .cloned()
+            .map(|mut record| {
+                if record.remote_peer_id == from_peer_id
+                    && record.remote_service_name == remote_service_name
+                {
+                    record.remote_peer_id = to_peer_id.to_string();
+                }
+                for member in &mut record.group {
+                    if member.peer_id == from_peer_id && member.service_name == remote_service_name
+                    {
+                        member.peer_id = to_peer_id.to_string();
+                    }
+                }
+                let mut seen = BTreeSet::new();
+                let primary = record.remote_peer_id.clone();
+                record.group.retain(|member| {
+                    member.peer_id != primary && seen.insert(member.peer_id.clone())
+                });
+                record
+            })
+            .collect();
+        updated.sort_records();
+        updated.save_to_file()?;
+        Ok(updated)
+    }
+
     fn local_port_used_by_other_record(&self, record: &LocalServicePreference) -> bool {
         self.records.iter().any(|existing| {
             existing.local_host == record.local_host
@@ -206,6 +276,13 @@ impl LocalServicePreference {
         if self.local_port == 0 {
             bail!("local_port must be greater than 0");
         }
+        for member in &self.group {
+            validate_non_empty("group.peer_id", &member.peer_id)?;
+            validate_non_empty("group.service_name", &member.service_name)?;
+            if member.peer_id == self.remote_peer_id {
+                bail!("service group must not repeat the primary device");
+            }
+        }
         Ok(())
     }
 }
@@ -259,6 +336,40 @@ mod tests {
         assert_eq!(updated.records[0].local_port, 3333);
     }
 
+    #[test]
+    fn persists_service_group_members_and_routing() {
+        let dir = TempDir::new().unwrap();
+        let config = LocalPreferenceCache::apply_from_dir(dir.path()).unwrap();
+        let mut grouped = record("peer-a", "files", "main", 2222);
+        grouped.group = vec![ServiceGroupMember {
+            peer_id: "peer-b".to_string(),
+            service_name: "files-backup".to_string(),
+        }];
+        grouped.routing = ServiceRouting::RoundRobin;
+
+        config.upsert_record(grouped.clone()).unwrap();
+        let reloaded = LocalPreferenceCache::apply_from_dir(dir.path()).unwrap();
+
+        assert_eq!(reloaded.records, vec![grouped]);
+        let raw = std::fs::read_to_string(dir.path().join("cache").join("local_preferences.json"))
+            .unwrap();
+        assert!(raw.contains("\"round_robin\""));
+    }
+
+    #[test]
+    fn rejects_group_repeating_the_primary_device() {
+        let dir = TempDir::new().unwrap();
+        let config = LocalPreferenceCache::apply_from_dir(dir.path()).unwrap();
+        let mut grouped = record("peer-a", "files", "main", 2222);
+        grouped.group = vec![ServiceGroupMember {
+            peer_id: "peer-a".to_string(),
+            service_name: "files".to_string(),
+        }];
+
+        let error = config.upsert_record(grouped).unwrap_err();
+        assert!(error.to_string().contains("primary device"));
+    }
+
     #[test]
     fn keeps_same_endpoint_names_for_different_services() {
         let dir = TempDir::new().unwrap();
@@ -368,6 +479,51 @@ mod tests {
         assert!(updated.find_record("peer-b", "beta", "main").is_some());
     }
 
+    #[test]
+    fn repoints_service_records_and_group_members() {
+        let dir = TempDir::new().unwrap();
+        let config = LocalPreferenceCache::apply_from_dir(dir.path()).unwrap();
+        let mut grouped = record("peer-c", "notes", "main", 3333);
+        grouped.group = vec![
+            ServiceGroupMember {
+                peer_id: "peer-a".to_string(),
+                service_name: "notes".to_string(),
+            },
+            ServiceGroupMember {
+                peer_id: "peer-b".to_string(),
+                service_name: "notes".to_string(),
+            },
+        ];
+
+        let updated = config
+            .upsert_record(record("peer-a", "notes", "main", 2222))
+            .unwrap()
+            .upsert_record(record("peer-a", "other", "main", 4444))
+            .unwrap()
+            .upsert_record(grouped)
+            .unwrap()
+            .repoint_service_records("notes", "peer-a", "peer-b")
+            .unwrap();
+
+        assert!(updated.find_record("peer-a", "notes", "main").is_none());
+        assert_eq!(
+            updated
+                .find_record("peer-b", "notes", "main")
+                .map(|record| record.local_port),
+            Some(2222)
+        );
+        assert!(updated.find_record("peer-a", "other", "main").is_some());
+        let group = &updated
+            .find_record("peer-c", "notes", "main")
+            .unwrap()
+            .group;
+        assert_eq!(group.len(), 1);
+        assert_eq!(group[0].peer_id, "peer-b");
+
+        let reloaded = LocalPreferenceCache::apply_from_dir(dir.path()).unwrap();
+        assert_eq!(reloaded.records, updated.records);
+    }
+
     fn record(
         remote_peer_id: &str,
         remote_service_name: &str,
@@ -381,6 +537,9 @@ mod tests {
             local_host: "127.0.0.1".to_string(),
             local_port,
             local_port_source: LocalPortSource::Auto,
+            bandwidth_limit: BandwidthLimit::default(),
+            group: Vec::new(),
+            routing: ServiceRouting::default(),
         }
     }
 }
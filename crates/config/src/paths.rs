@@ -67,6 +67,19 @@ impl FungiPaths {
     pub fn service_artifacts_dir(&self, local_service_id: &str) -> PathBuf {
         self.service_artifacts_root().join(local_service_id)
     }
+
+    pub fn backups_root(&self) -> PathBuf {
+        self.fungi_home.join("backups")
+    }
+
+    pub fn service_backups_root(&self) -> PathBuf {
+        self.backups_root().join("services")
+    }
+
+    /// Manifest digests of backups this device pushed to backup targets, checked on restore.
+    pub fn service_backup_receipts_root(&self) -> PathBuf {
+        self.backups_root().join("receipts")
+    }
 }
 
 pub fn user_root_dir_name_for_channel(channel: &str) -> &'static str {
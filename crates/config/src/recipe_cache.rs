@@ -7,8 +7,9 @@ use anyhow::{Context as _, Result, bail};
 use serde::{Deserialize, Serialize};
 use ulid::Ulid;
 
+use crate::recipe_sources::{OFFICIAL_RECIPE_NAMESPACE, validate_recipe_namespace};
+
 const RECIPE_CACHE_DIR: &str = "cache/recipes";
-const OFFICIAL_SOURCE_DIR: &str = "official";
 const LATEST_RELEASE_FILE: &str = "latest.json";
 
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
@@ -25,7 +26,13 @@ pub struct CachedOfficialRelease {
 
 impl RecipeCache {
     pub fn apply_from_dir(fungi_dir: &Path) -> Result<Self> {
-        let root_dir = fungi_dir.join(RECIPE_CACHE_DIR).join(OFFICIAL_SOURCE_DIR);
+        Self::for_source(fungi_dir, OFFICIAL_RECIPE_NAMESPACE)
+    }
+
+    /// Opens the cache of one recipe source; every source namespace gets its own directory.
+    pub fn for_source(fungi_dir: &Path, namespace: &str) -> Result<Self> {
+        validate_recipe_namespace(namespace)?;
+        let root_dir = fungi_dir.join(RECIPE_CACHE_DIR).join(namespace);
         std::fs::create_dir_all(&root_dir).with_context(|| {
             format!(
                 "failed to create recipe cache directory: {}",
@@ -110,6 +117,10 @@ impl RecipeCache {
         Ok(path)
     }
 
+    pub fn asset_dir(&self, release_version: &str) -> PathBuf {
+        self.assets_dir(release_version)
+    }
+
     pub fn ensure_asset_dir(&self, release_version: &str) -> Result<PathBuf> {
         let dir = self.assets_dir(release_version);
         std::fs::create_dir_all(&dir).with_context(|| {
@@ -207,6 +218,23 @@ mod tests {
         assert!(resolved_path.exists());
     }
 
+    #[test]
+    fn keeps_sources_in_separate_directories() {
+        let dir = TempDir::new().unwrap();
+        let official = RecipeCache::apply_from_dir(dir.path()).unwrap();
+        let team = RecipeCache::for_source(dir.path(), "team").unwrap();
+
+        official.set_latest_release_version("v0.3.1").unwrap();
+        team.set_latest_release_version("3f2a9c").unwrap();
+
+        assert_ne!(official.index_path("v1"), team.index_path("v1"));
+        assert_eq!(
+            team.latest_release_version().unwrap().as_deref(),
+            Some("3f2a9c")
+        );
+        assert!(RecipeCache::for_source(dir.path(), "../escape").is_err());
+    }
+
     #[test]
     fn rejects_asset_names_with_path_traversal() {
         let dir = TempDir::new().unwrap();
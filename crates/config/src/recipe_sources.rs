@@ -0,0 +1,214 @@
+use std::path::PathBuf;
+
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+
+/// Namespace of the built-in `enbop/fungi-service-recipes` GitHub releases source.
+pub const OFFICIAL_RECIPE_NAMESPACE: &str = "official";
+
+/// Recipe repositories consulted by `service recipe` and `service apply --recipe`.
+///
+/// Recipes are addressed as `namespace/recipe-id`. A bare `recipe-id` resolves against sources in
+/// descending `priority`; the official source has priority 0 and wins ties with configured sources,
+/// which need a positive priority to take precedence over it.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct RecipeSources {
+    #[serde(default = "default_official_enabled")]
+    pub official: bool,
+    /// Publisher keys the official index must be signed with; empty accepts it unsigned.
+    #[serde(default)]
+    pub official_trusted_keys: Vec<String>,
+    #[serde(default)]
+    pub sources: Vec<RecipeSource>,
+}
+
+impl Default for RecipeSources {
+    fn default() -> Self {
+        Self {
+            official: default_official_enabled(),
+            official_trusted_keys: Vec::new(),
+            sources: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct RecipeSource {
+    pub namespace: String,
+    #[serde(default)]
+    pub priority: i32,
+    /// Hex-encoded Ed25519 publisher keys. When set, the source's `index.json` must come with an
+    /// `index.json.sig` holding a signature by one of them.
+    #[serde(default)]
+    pub trusted_keys: Vec<String>,
+    #[serde(flatten)]
+    pub location: RecipeSourceLocation,
+}
+
+/// Every location serves the official layout: an `index.json` next to the manifest and readme
+/// assets it names.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RecipeSourceLocation {
+    /// HTTP(S) URL of an `index.json`; assets are fetched relative to it.
+    Index { url: String },
+    /// Local directory read in place, which works fully offline.
+    Directory { path: PathBuf },
+    /// `.tar.gz` archive, by HTTP(S) URL or local path, unpacked into the recipe cache.
+    Tarball { url: String },
+}
+
+fn default_official_enabled() -> bool {
+    true
+}
+
+impl RecipeSources {
+    pub fn validate(&self) -> Result<()> {
+        validate_trusted_keys(OFFICIAL_RECIPE_NAMESPACE, &self.official_trusted_keys)?;
+        let mut namespaces = Vec::new();
+        for source in &self.sources {
+            validate_recipe_namespace(&source.namespace)?;
+            validate_trusted_keys(&source.namespace, &source.trusted_keys)?;
+            if source.namespace == OFFICIAL_RECIPE_NAMESPACE {
+                bail!("recipe source namespace `{OFFICIAL_RECIPE_NAMESPACE}` is reserved");
+            }
+            if namespaces.contains(&source.namespace.as_str()) {
+                bail!("duplicate recipe source namespace `{}`", source.namespace);
+            }
+            namespaces.push(source.namespace.as_str());
+
+            match &source.location {
+                RecipeSourceLocation::Index { url } if !is_http_url(url) => {
+                    bail!(
+                        "recipe source `{}` index url must start with http:// or https://",
+                        source.namespace
+                    );
+                }
+                RecipeSourceLocation::Directory { path } if !path.is_absolute() => {
+                    bail!(
+                        "recipe source `{}` directory must be an absolute path",
+                        source.namespace
+                    );
+                }
+                RecipeSourceLocation::Tarball { url } if url.trim().is_empty() => {
+                    bail!("recipe source `{}` tarball url is empty", source.namespace);
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn validate_recipe_namespace(namespace: &str) -> Result<()> {
+    if namespace.is_empty()
+        || !namespace
+            .chars()
+            .all(|ch| ch.is_ascii_lowercase() || ch.is_ascii_digit() || ch == '-' || ch == '_')
+    {
+        bail!(
+            "recipe source namespace `{namespace}` may only contain lowercase letters, digits, '-' and '_'"
+        );
+    }
+    Ok(())
+}
+
+fn validate_trusted_keys(namespace: &str, keys: &[String]) -> Result<()> {
+    for key in keys {
+        if key.len() != 64 || !key.chars().all(|ch| ch.is_ascii_hexdigit()) {
+            bail!(
+                "recipe source `{namespace}` trusted key `{key}` must be a hex-encoded Ed25519 public key"
+            );
+        }
+    }
+    Ok(())
+}
+
+pub fn is_http_url(value: &str) -> bool {
+    value.starts_with("http://") || value.starts_with("https://")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_sources_of_every_kind() {
+        let config: RecipeSources = toml::from_str(
+            r#"
+official = false
+
+[[sources]]
+namespace = "team"
+priority = 10
+trusted_keys = ["8f1c4ad0d1e5b7a4b2f9e3c6a7d8e9f00112233445566778899aabbccddeeff0"]
+kind = "directory"
+path = "/srv/fungi-recipes"
+
+[[sources]]
+namespace = "lab"
+kind = "index"
+url = "https://recipes.example.com/index.json"
+
+[[sources]]
+namespace = "vendor"
+kind = "tarball"
+url = "https://example.com/recipes.tar.gz"
+"#,
+        )
+        .unwrap();
+
+        assert!(!config.official);
+        assert_eq!(config.sources[0].priority, 10);
+        assert_eq!(config.sources[1].priority, 0);
+        assert_eq!(config.sources[0].trusted_keys.len(), 1);
+        assert!(config.sources[1].trusted_keys.is_empty());
+        assert_eq!(
+            config.sources[0].location,
+            RecipeSourceLocation::Directory {
+                path: PathBuf::from("/srv/fungi-recipes")
+            }
+        );
+        config.validate().unwrap();
+    }
+
+    #[test]
+    fn rejects_reserved_and_duplicate_namespaces() {
+        let source = |namespace: &str| RecipeSource {
+            namespace: namespace.to_string(),
+            priority: 0,
+            trusted_keys: Vec::new(),
+            location: RecipeSourceLocation::Index {
+                url: "https://example.com/index.json".to_string(),
+            },
+        };
+
+        let reserved = RecipeSources {
+            official: true,
+            official_trusted_keys: Vec::new(),
+            sources: vec![source(OFFICIAL_RECIPE_NAMESPACE)],
+        };
+        assert!(reserved.validate().is_err());
+
+        let duplicate = RecipeSources {
+            official: true,
+            official_trusted_keys: Vec::new(),
+            sources: vec![source("team"), source("team")],
+        };
+        assert!(duplicate.validate().is_err());
+
+        let invalid = RecipeSources {
+            official: true,
+            official_trusted_keys: Vec::new(),
+            sources: vec![source("Team/A")],
+        };
+        assert!(invalid.validate().is_err());
+
+        let bad_key = RecipeSources {
+            official: true,
+            official_trusted_keys: vec!["not-a-key".to_string()],
+            sources: Vec::new(),
+        };
+        assert!(bad_key.validate().is_err());
+    }
+}
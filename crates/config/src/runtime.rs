@@ -11,9 +11,97 @@ pub struct Runtime {
     #[serde(default)]
     pub disable_wasmtime: bool,
     #[serde(default)]
+    pub disable_process: bool,
+    /// How process services react when the host kernel lacks a sandbox feature.
+    #[serde(default, skip_serializing_if = "ProcessSandboxMode::is_default")]
+    pub process_sandbox: ProcessSandboxMode,
+    #[serde(default)]
     pub docker_socket_path: Option<PathBuf>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub allowed_host_paths: Vec<PathBuf>,
+    /// Credentials for pulling Docker images from private registries.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub docker_registries: Vec<DockerRegistryCredential>,
+    /// Docker CLI `config.json` whose inline `auths` are used for registries not listed in
+    /// `docker_registries`. Credential helpers (`credsStore`) are not consulted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub docker_config_path: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "DockerSecurity::is_default")]
+    pub docker_security: DockerSecurity,
+}
+
+/// A registry login whose password lives in the encrypted secrets store.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct DockerRegistryCredential {
+    /// Registry host as it appears in image references, e.g. `ghcr.io`; `docker.io` for Docker Hub.
+    pub registry: String,
+    pub username: String,
+    /// Name of the secret holding the password or access token.
+    pub password_secret: String,
+    /// Also use this login for services applied by remote peers. Off by default so a peer
+    /// cannot pull private images with this node's credentials.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub allow_remote: bool,
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+/// Node-wide limits on the privileges of Docker containers, split by who applied the service.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct DockerSecurity {
+    /// Services applied from this node's own CLI or API.
+    pub local: DockerSecurityRules,
+    /// Services applied by remote peers over service control.
+    pub remote: DockerSecurityRules,
+}
+
+impl DockerSecurity {
+    pub fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// Unset `allow_*` flags are allowed for local services and denied for remote ones.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct DockerSecurityRules {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_privileged: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_host_network: Option<bool>,
+    /// Whether `seccomp_profile`/`apparmor_profile` may be `unconfined`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_unconfined: Option<bool>,
+    /// Whether `volumes` may name engine volumes, which are shared by every container.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_named_volumes: Option<bool>,
+    /// Require `cap_drop: [ALL]`.
+    pub require_drop_all_caps: bool,
+    /// Capabilities `cap_add` may name; unset allows any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allowed_cap_add: Option<Vec<String>>,
+}
+
+/// Process services are always confined with namespaces, Landlock and seccomp where the kernel
+/// supports them; the mode decides what happens where it does not.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessSandboxMode {
+    /// Apply whatever the kernel supports and start the service anyway; the layers it goes
+    /// without are shown in the service status.
+    #[default]
+    BestEffort,
+    /// Refuse to start a service unless every sandbox layer is enforced.
+    Strict,
+}
+
+impl ProcessSandboxMode {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
 }
 
 impl Default for Runtime {
@@ -21,8 +109,13 @@ impl Default for Runtime {
         Self {
             disable_docker: false,
             disable_wasmtime: false,
+            disable_process: false,
+            process_sandbox: ProcessSandboxMode::default(),
             docker_socket_path: None,
             allowed_host_paths: Vec::new(),
+            docker_registries: Vec::new(),
+            docker_config_path: None,
+            docker_security: DockerSecurity::default(),
         }
     }
 }
@@ -36,6 +129,10 @@ impl Runtime {
         !self.disable_wasmtime
     }
 
+    pub fn process_enabled(&self) -> bool {
+        !self.disable_process
+    }
+
     pub fn validate_allowed_host_path(path: &Path, fungi_dir: &Path) -> Result<PathBuf> {
         let normalized = normalize_absolute_path(path)?;
         if is_sensitive_fungi_path(&normalized, fungi_dir)? {
@@ -105,6 +202,30 @@ mod tests {
         assert!(runtime.docker_enabled());
         assert!(runtime.wasmtime_enabled());
         assert!(runtime.allowed_host_paths.is_empty());
+        assert_eq!(runtime.docker_security, DockerSecurity::default());
+    }
+
+    #[test]
+    fn parses_remote_docker_security_overrides() {
+        let runtime: Runtime = toml::from_str(
+            r#"
+            [docker_security.remote]
+            require_drop_all_caps = true
+            allowed_cap_add = ["NET_BIND_SERVICE"]
+            "#,
+        )
+        .unwrap();
+        let remote = &runtime.docker_security.remote;
+        assert!(remote.require_drop_all_caps);
+        assert_eq!(
+            remote.allowed_cap_add.as_deref(),
+            Some(&["NET_BIND_SERVICE".to_string()][..])
+        );
+        assert_eq!(remote.allow_privileged, None);
+        assert_eq!(
+            runtime.docker_security.local,
+            DockerSecurityRules::default()
+        );
     }
 
     #[test]
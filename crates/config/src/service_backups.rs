@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_SERVICE_BACKUP_KEEP_LAST: usize = 7;
+pub const DEFAULT_SERVICE_BACKUP_MAX_ARCHIVE_MB: u64 = 4096;
+pub const DEFAULT_SERVICE_BACKUP_MAX_SERVICES_PER_DEVICE: usize = 32;
+
+/// Retention for service backups kept on this device, and whether trusted devices may push
+/// their backups here. Each device's backups are kept and pruned separately.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ServiceBackups {
+    /// Accept backups pushed by trusted devices, making this device a backup target.
+    #[serde(default)]
+    pub accept_from_devices: bool,
+    /// Backups kept per service; older ones are pruned when a new one is stored.
+    #[serde(default = "default_keep_last")]
+    pub keep_last: usize,
+    /// Also prune backups older than this many days. The newest backup is always kept.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_age_days: Option<u32>,
+    /// Largest backup archive, in MiB, accepted from another device.
+    #[serde(default = "default_max_archive_mb")]
+    pub max_archive_mb: u64,
+    /// Services another device may keep backups of here; backups of further services are
+    /// refused until old ones are removed.
+    #[serde(default = "default_max_services_per_device")]
+    pub max_services_per_device: usize,
+}
+
+impl ServiceBackups {
+    pub fn max_archive_bytes(&self) -> u64 {
+        self.max_archive_mb.saturating_mul(1024 * 1024)
+    }
+}
+
+impl Default for ServiceBackups {
+    fn default() -> Self {
+        Self {
+            accept_from_devices: false,
+            keep_last: DEFAULT_SERVICE_BACKUP_KEEP_LAST,
+            max_age_days: None,
+            max_archive_mb: DEFAULT_SERVICE_BACKUP_MAX_ARCHIVE_MB,
+            max_services_per_device: DEFAULT_SERVICE_BACKUP_MAX_SERVICES_PER_DEVICE,
+        }
+    }
+}
+
+fn default_keep_last() -> usize {
+    DEFAULT_SERVICE_BACKUP_KEEP_LAST
+}
+
+fn default_max_archive_mb() -> u64 {
+    DEFAULT_SERVICE_BACKUP_MAX_ARCHIVE_MB
+}
+
+fn default_max_services_per_device() -> usize {
+    DEFAULT_SERVICE_BACKUP_MAX_SERVICES_PER_DEVICE
+}
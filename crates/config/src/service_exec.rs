@@ -0,0 +1,17 @@
+use libp2p_identity::PeerId;
+use serde::{Deserialize, Serialize};
+
+/// Devices allowed to run commands inside services of this device. Exec hands out a shell in the
+/// service with its environment, so being trusted is not enough: a device must be listed here.
+/// Commands run from this device's own CLI are not affected.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ServiceExec {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_peers: Vec<PeerId>,
+}
+
+impl ServiceExec {
+    pub fn allows(&self, peer_id: &PeerId) -> bool {
+        self.allowed_peers.contains(peer_id)
+    }
+}
@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_SERVICE_PROXY_DOMAIN: &str = "fungi";
+
+/// Local SOCKS5 and HTTP CONNECT proxy that reaches remote services by name, e.g.
+/// `ssh.ssh.nas.fungi` for the `ssh` entry of the `ssh` service on device `nas`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ServiceProxy {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_service_proxy_listen_address")]
+    pub listen_address: String,
+    #[serde(default = "default_service_proxy_domain")]
+    pub domain: String,
+}
+
+impl Default for ServiceProxy {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            listen_address: default_service_proxy_listen_address(),
+            domain: default_service_proxy_domain(),
+        }
+    }
+}
+
+fn default_service_proxy_listen_address() -> String {
+    crate::default_service_proxy_address().to_string()
+}
+
+fn default_service_proxy_domain() -> String {
+    DEFAULT_SERVICE_PROXY_DOMAIN.to_string()
+}
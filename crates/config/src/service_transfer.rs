@@ -0,0 +1,17 @@
+use libp2p_identity::PeerId;
+use serde::{Deserialize, Serialize};
+
+/// Devices allowed to take services off this device. A transfer hands over the service's appdata
+/// and artifacts, so being trusted is not enough: a device must be listed here. Moves started
+/// from this device's own CLI towards another device still need that device listed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ServiceTransfer {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_peers: Vec<PeerId>,
+}
+
+impl ServiceTransfer {
+    pub fn allows(&self, peer_id: &PeerId) -> bool {
+        self.allowed_peers.contains(peer_id)
+    }
+}
@@ -2,6 +2,8 @@ use std::net::{AddrParseError, SocketAddr};
 
 use serde::{Deserialize, Serialize};
 
+use crate::bandwidth::BandwidthLimit;
+
 impl TryInto<SocketAddr> for &ListeningRule {
     type Error = AddrParseError;
 
@@ -34,6 +36,44 @@ pub struct ForwardingRule {
     pub remote_service_name: Option<String>,
     #[serde(default)]
     pub remote_service_port_name: Option<String>,
+    #[serde(default, skip_serializing_if = "BandwidthLimit::is_unlimited")]
+    pub bandwidth_limit: BandwidthLimit,
+    /// Other devices serving the same service, tried when the primary target can't be reached.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fallback_targets: Vec<ForwardingTarget>,
+    #[serde(default, skip_serializing_if = "ServiceRouting::is_failover")]
+    pub routing: ServiceRouting,
+}
+
+/// An additional peer and stream protocol a forwarding rule can open its streams to.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ForwardingTarget {
+    pub peer_id: String,
+    pub protocol: String,
+}
+
+/// How a forwarding rule with several targets picks one for each new connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ServiceRouting {
+    /// Always prefer the first reachable target.
+    #[default]
+    Failover,
+    /// Start from the next target on every connection, skipping unreachable ones.
+    RoundRobin,
+}
+
+impl ServiceRouting {
+    pub fn is_failover(&self) -> bool {
+        *self == Self::Failover
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Failover => "failover",
+            Self::RoundRobin => "round_robin",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
@@ -42,6 +82,8 @@ pub struct ListeningRule {
     pub port: u16,
     #[serde(default)]
     pub protocol: Option<String>,
+    #[serde(default, skip_serializing_if = "BandwidthLimit::is_unlimited")]
+    pub bandwidth_limit: BandwidthLimit,
     // #[serde(default)]
     // pub allowed_peers: Vec<String>,
 }
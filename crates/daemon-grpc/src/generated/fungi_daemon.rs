@@ -91,6 +91,35 @@ pub struct RuntimeAllowedHostPathRequest {
     pub path: ::prost::alloc::string::String,
 }
 #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct SetSecretRequest {
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub value: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct SecretNameRequest {
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct SecretInfo {
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub updated_at: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListSecretsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub secrets: ::prost::alloc::vec::Vec<SecretInfo>,
+}
+#[derive(Clone, Copy, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct RemoveSecretResponse {
+    #[prost(bool, tag = "1")]
+    pub removed: bool,
+}
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
 pub struct RuntimeConfigResponse {
     #[prost(bool, tag = "1")]
     pub disable_docker: bool,
@@ -98,6 +127,8 @@ pub struct RuntimeConfigResponse {
     pub disable_wasmtime: bool,
     #[prost(string, repeated, tag = "3")]
     pub allowed_host_paths: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    #[prost(bool, tag = "4")]
+    pub disable_process: bool,
 }
 #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
 pub struct RuntimeAvailabilityStatus {
@@ -109,6 +140,13 @@ pub struct RuntimeAvailabilityStatus {
     pub active: bool,
     #[prost(string, tag = "4")]
     pub endpoint: ::prost::alloc::string::String,
+    /// "docker" or "podman"; empty until the engine has been detected.
+    #[prost(string, tag = "5")]
+    pub engine: ::prost::alloc::string::String,
+    #[prost(string, tag = "6")]
+    pub engine_version: ::prost::alloc::string::String,
+    #[prost(bool, tag = "7")]
+    pub rootless: bool,
 }
 #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
 pub struct LocalRuntimeStatusResponse {
@@ -116,6 +154,8 @@ pub struct LocalRuntimeStatusResponse {
     pub docker: ::core::option::Option<RuntimeAvailabilityStatus>,
     #[prost(message, optional, tag = "2")]
     pub wasmtime: ::core::option::Option<RuntimeAvailabilityStatus>,
+    #[prost(message, optional, tag = "3")]
+    pub process: ::core::option::Option<RuntimeAvailabilityStatus>,
 }
 #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
 pub struct DeviceInfo {
@@ -139,6 +179,8 @@ pub struct DeviceInfo {
     pub version: ::prost::alloc::string::String,
     #[prost(string, repeated, tag = "10")]
     pub multiaddrs: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    #[prost(string, repeated, tag = "11")]
+    pub mac_addresses: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct DeviceInfoListResponse {
@@ -166,6 +208,33 @@ pub struct RemoveDeviceRequest {
     pub peer_id: ::prost::alloc::string::String,
 }
 #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct WakeDeviceRequest {
+    #[prost(string, tag = "1")]
+    pub peer_id: ::prost::alloc::string::String,
+    #[prost(bool, tag = "2")]
+    pub wait: bool,
+}
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct WakeAttempt {
+    #[prost(string, tag = "1")]
+    pub relay_peer_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub relay_name: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub error: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct WakeDeviceResponse {
+    #[prost(string, tag = "1")]
+    pub peer_id: ::prost::alloc::string::String,
+    #[prost(string, repeated, tag = "2")]
+    pub mac_addresses: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    #[prost(message, repeated, tag = "3")]
+    pub attempts: ::prost::alloc::vec::Vec<WakeAttempt>,
+    #[prost(bool, tag = "4")]
+    pub online: bool,
+}
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
 pub struct PingPeerRequest {
     #[prost(string, tag = "1")]
     pub peer_id: ::prost::alloc::string::String,
@@ -394,6 +463,37 @@ pub struct ListPeerAddressesResponse {
     pub addresses: ::prost::alloc::vec::Vec<PeerAddressSnapshot>,
 }
 #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct DiagnoseRequest {
+    #[prost(string, tag = "1")]
+    pub peer_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct DiagnosticCheck {
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub status: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub summary: ::prost::alloc::string::String,
+    #[prost(string, repeated, tag = "4")]
+    pub details: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DiagnoseResponse {
+    #[prost(string, tag = "1")]
+    pub peer_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub peer_name: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag = "3")]
+    pub checks: ::prost::alloc::vec::Vec<DiagnosticCheck>,
+    #[prost(string, tag = "4")]
+    pub nat_type: ::prost::alloc::string::String,
+    #[prost(string, tag = "5")]
+    pub verdict: ::prost::alloc::string::String,
+    #[prost(string, repeated, tag = "6")]
+    pub remediation: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
 pub struct PullServiceRequest {
     #[prost(string, tag = "1")]
     pub manifest_yaml: ::prost::alloc::string::String,
@@ -422,12 +522,150 @@ pub struct ServiceInstanceResponse {
     pub instance_json: ::prost::alloc::string::String,
 }
 #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct PullServiceEvent {
+    #[prost(oneof = "pull_service_event::Event", tags = "1, 2")]
+    pub event: ::core::option::Option<pull_service_event::Event>,
+}
+/// Nested message and enum types in `PullServiceEvent`.
+pub mod pull_service_event {
+    #[derive(Clone, PartialEq, Eq, Hash, ::prost::Oneof)]
+    pub enum Event {
+        #[prost(message, tag = "1")]
+        Progress(super::ImagePullProgress),
+        #[prost(message, tag = "2")]
+        Instance(super::ServiceInstanceResponse),
+    }
+}
+/// layer_id is empty for image-level messages.
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct ImagePullProgress {
+    #[prost(string, tag = "1")]
+    pub image: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub layer_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub status: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub progress: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "5")]
+    pub current: u64,
+    #[prost(uint64, tag = "6")]
+    pub total: u64,
+}
+#[derive(Clone, Copy, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct PruneServiceImagesRequest {
+    #[prost(bool, tag = "1")]
+    pub dry_run: bool,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PruneServiceImagesResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub images: ::prost::alloc::vec::Vec<PrunedImage>,
+    #[prost(bool, tag = "2")]
+    pub dry_run: bool,
+}
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct PrunedImage {
+    #[prost(string, tag = "1")]
+    pub image: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "2")]
+    pub size_bytes: u64,
+}
+/// revision 0 restores the revision before the current one.
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct RollbackServiceRequest {
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "2")]
+    pub revision: u64,
+}
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct ServiceHistoryResponse {
+    #[prost(string, tag = "1")]
+    pub revisions_json: ::prost::alloc::string::String,
+}
+/// A job run for RunServiceJob, or the job status for RemoteServiceJobStatus.
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct ServiceJobResponse {
+    #[prost(string, tag = "1")]
+    pub job_json: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
 pub struct ServiceLogsResponse {
     #[prost(bytes = "vec", tag = "1")]
     pub raw: ::prost::alloc::vec::Vec<u8>,
     #[prost(string, tag = "2")]
     pub text: ::prost::alloc::string::String,
 }
+/// peer_id is empty for a local service; rows/cols of 0 leave the terminal size unset.
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct ServiceExecStart {
+    #[prost(string, tag = "1")]
+    pub peer_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(string, repeated, tag = "3")]
+    pub command: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    #[prost(bool, tag = "4")]
+    pub tty: bool,
+    #[prost(uint32, tag = "5")]
+    pub rows: u32,
+    #[prost(uint32, tag = "6")]
+    pub cols: u32,
+}
+#[derive(Clone, Copy, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct ServiceExecResize {
+    #[prost(uint32, tag = "1")]
+    pub rows: u32,
+    #[prost(uint32, tag = "2")]
+    pub cols: u32,
+}
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct ServiceExecInput {
+    #[prost(oneof = "service_exec_input::Input", tags = "1, 2, 3, 4")]
+    pub input: ::core::option::Option<service_exec_input::Input>,
+}
+/// Nested message and enum types in `ServiceExecInput`.
+pub mod service_exec_input {
+    #[derive(Clone, PartialEq, Eq, Hash, ::prost::Oneof)]
+    pub enum Input {
+        #[prost(message, tag = "1")]
+        Start(super::ServiceExecStart),
+        #[prost(bytes, tag = "2")]
+        Stdin(::prost::alloc::vec::Vec<u8>),
+        #[prost(message, tag = "3")]
+        Resize(super::ServiceExecResize),
+        #[prost(bool, tag = "4")]
+        CloseStdin(bool),
+    }
+}
+/// exit_code is only meaningful with has_exit_code; error is set when the session broke off.
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct ServiceExecExit {
+    #[prost(bool, tag = "1")]
+    pub has_exit_code: bool,
+    #[prost(int32, tag = "2")]
+    pub exit_code: i32,
+    #[prost(string, tag = "3")]
+    pub error: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct ServiceExecOutput {
+    #[prost(oneof = "service_exec_output::Output", tags = "1, 2, 3")]
+    pub output: ::core::option::Option<service_exec_output::Output>,
+}
+/// Nested message and enum types in `ServiceExecOutput`.
+pub mod service_exec_output {
+    #[derive(Clone, PartialEq, Eq, Hash, ::prost::Oneof)]
+    pub enum Output {
+        #[prost(bytes, tag = "1")]
+        Stdout(::prost::alloc::vec::Vec<u8>),
+        #[prost(bytes, tag = "2")]
+        Stderr(::prost::alloc::vec::Vec<u8>),
+        #[prost(message, tag = "3")]
+        Exit(super::ServiceExecExit),
+    }
+}
 #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
 pub struct ListServicesResponse {
     #[prost(string, tag = "1")]
@@ -489,7 +727,7 @@ pub struct GetRecipeResponse {
     #[prost(message, optional, tag = "1")]
     pub detail: ::core::option::Option<RecipeDetail>,
 }
-#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ResolveRecipeRequest {
     #[prost(string, tag = "1")]
     pub recipe_id: ::prost::alloc::string::String,
@@ -499,6 +737,11 @@ pub struct ResolveRecipeRequest {
     pub peer_id: ::prost::alloc::string::String,
     #[prost(bool, tag = "4")]
     pub refresh: bool,
+    /// Values for inputs declared by the recipe manifest; undeclared names are
+    /// rejected and omitted inputs fall back to their defaults.
+    #[prost(map = "string, string", tag = "5")]
+    pub inputs:
+        ::std::collections::HashMap<::prost::alloc::string::String, ::prost::alloc::string::String>,
 }
 #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
 pub struct ResolveRecipeResponse {
@@ -559,6 +802,81 @@ pub struct RemotePeerRequest {
     pub peer_id: ::prost::alloc::string::String,
 }
 #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct RemoteRollbackServiceRequest {
+    #[prost(string, tag = "1")]
+    pub peer_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "3")]
+    pub revision: u64,
+}
+/// An empty peer id means this device.
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct MoveServiceRequest {
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub from_peer_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub to_peer_id: ::prost::alloc::string::String,
+    #[prost(bool, tag = "4")]
+    pub clone: bool,
+}
+/// Saved devices that could not be reached to repoint their accesses to the moved service.
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct MoveServiceResponse {
+    #[prost(string, repeated, tag = "1")]
+    pub unreachable_peer_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+/// An empty target peer id keeps the backup on this device.
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct BackupServiceRequest {
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(bool, tag = "2")]
+    pub stop: bool,
+    #[prost(string, repeated, tag = "3")]
+    pub pre_backup_hook: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    #[prost(string, tag = "4")]
+    pub target_peer_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct ServiceBackupInfo {
+    #[prost(string, tag = "1")]
+    pub service: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub backup_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub created_at: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "4")]
+    pub size_bytes: u64,
+}
+/// An empty name lists backups of every service; an empty peer id means this device.
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct ListServiceBackupsRequest {
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub peer_id: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListServiceBackupsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub backups: ::prost::alloc::vec::Vec<ServiceBackupInfo>,
+}
+/// An empty backup id restores the newest backup; an empty peer id means this device.
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct RestoreServiceBackupRequest {
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub peer_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub backup_id: ::prost::alloc::string::String,
+    #[prost(bool, tag = "4")]
+    pub start: bool,
+}
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
 pub struct RemoteServiceControlResponse {
     #[prost(string, tag = "1")]
     pub service_name: ::prost::alloc::string::String,
@@ -575,6 +893,12 @@ pub struct AttachServiceAccessRequest {
     pub entry: ::prost::alloc::string::String,
     #[prost(int32, tag = "4")]
     pub local_port: i32,
+    #[prost(bool, tag = "5")]
+    pub wake: bool,
+    /// Seconds to wait for a woken device to come online; 0 uses the daemon default (30s). The
+    /// daemon caps it at 300s.
+    #[prost(uint32, tag = "6")]
+    pub wake_timeout_secs: u32,
 }
 #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
 pub struct DetachServiceAccessRequest {
@@ -595,6 +919,70 @@ pub struct ListServiceAccessesRequest {
     #[prost(string, tag = "1")]
     pub peer_id: ::prost::alloc::string::String,
 }
+/// Runs on peer_id; publisher_peer_id is the device publishing the service.
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct RemoteAttachServiceAccessRequest {
+    #[prost(string, tag = "1")]
+    pub peer_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub publisher_peer_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub service_name: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub entry: ::prost::alloc::string::String,
+    #[prost(int32, tag = "5")]
+    pub local_port: i32,
+}
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct RemoteForgetServiceAccessRequest {
+    #[prost(string, tag = "1")]
+    pub peer_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub publisher_peer_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub service_name: ::prost::alloc::string::String,
+}
+/// Bytes per second in each direction; 0 leaves the path unlimited.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct BandwidthLimit {
+    #[prost(uint64, tag = "1")]
+    pub direct_bytes_per_sec: u64,
+    #[prost(uint64, tag = "2")]
+    pub relayed_bytes_per_sec: u64,
+}
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct SetServiceAccessBandwidthLimitRequest {
+    #[prost(string, tag = "1")]
+    pub peer_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub service_name: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub entry: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "4")]
+    pub limit: ::core::option::Option<BandwidthLimit>,
+}
+/// Member devices share the service definition of the primary one; an empty list
+/// removes the group.
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct SetServiceAccessGroupRequest {
+    #[prost(string, tag = "1")]
+    pub peer_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub service_name: ::prost::alloc::string::String,
+    #[prost(string, repeated, tag = "3")]
+    pub member_peer_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    #[prost(bool, tag = "4")]
+    pub round_robin: bool,
+}
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct SetServicePortBandwidthLimitRequest {
+    #[prost(string, tag = "1")]
+    pub service_name: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub port_name: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "3")]
+    pub limit: ::core::option::Option<BandwidthLimit>,
+}
 #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
 pub struct ServiceAccessResponse {
     #[prost(string, tag = "1")]
@@ -605,12 +993,33 @@ pub struct ServiceAccessesResponse {
     #[prost(string, tag = "1")]
     pub service_accesses_json: ::prost::alloc::string::String,
 }
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct HttpGatewayService {
+    #[prost(string, tag = "1")]
+    pub peer_id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub device_name: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub service_name: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub entry: ::prost::alloc::string::String,
+    #[prost(string, tag = "5")]
+    pub url: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HttpGatewayServicesResponse {
+    #[prost(string, tag = "1")]
+    pub index_url: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag = "2")]
+    pub services: ::prost::alloc::vec::Vec<HttpGatewayService>,
+}
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
 pub enum ServiceRuntimeKind {
     Unspecified = 0,
     Docker = 1,
     Wasmtime = 2,
+    Process = 3,
 }
 impl ServiceRuntimeKind {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -622,6 +1031,7 @@ impl ServiceRuntimeKind {
             Self::Unspecified => "SERVICE_RUNTIME_KIND_UNSPECIFIED",
             Self::Docker => "SERVICE_RUNTIME_KIND_DOCKER",
             Self::Wasmtime => "SERVICE_RUNTIME_KIND_WASMTIME",
+            Self::Process => "SERVICE_RUNTIME_KIND_PROCESS",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -630,6 +1040,7 @@ impl ServiceRuntimeKind {
             "SERVICE_RUNTIME_KIND_UNSPECIFIED" => Some(Self::Unspecified),
             "SERVICE_RUNTIME_KIND_DOCKER" => Some(Self::Docker),
             "SERVICE_RUNTIME_KIND_WASMTIME" => Some(Self::Wasmtime),
+            "SERVICE_RUNTIME_KIND_PROCESS" => Some(Self::Process),
             _ => None,
         }
     }
@@ -1051,6 +1462,56 @@ pub mod fungi_daemon_client {
             ));
             self.inner.unary(req, path, codec).await
         }
+        /// Stores an encrypted secret for service env references like
+        /// `${secret:name}`. Secret values are never returned by the daemon.
+        pub async fn set_secret(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SetSecretRequest>,
+        ) -> std::result::Result<tonic::Response<super::SecretInfo>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/fungi_daemon.FungiDaemon/SetSecret");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("fungi_daemon.FungiDaemon", "SetSecret"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Lists stored secret names and when they were last set.
+        pub async fn list_secrets(
+            &mut self,
+            request: impl tonic::IntoRequest<super::Empty>,
+        ) -> std::result::Result<tonic::Response<super::ListSecretsResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/fungi_daemon.FungiDaemon/ListSecrets");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("fungi_daemon.FungiDaemon", "ListSecrets"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Removes a stored secret.
+        pub async fn remove_secret(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SecretNameRequest>,
+        ) -> std::result::Result<tonic::Response<super::RemoveSecretResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/fungi_daemon.FungiDaemon/RemoveSecret");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("fungi_daemon.FungiDaemon", "RemoveSecret"));
+            self.inner.unary(req, path, codec).await
+        }
         /// Returns latest metadata for devices discovered via mDNS.
         pub async fn list_mdns_devices(
             &mut self,
@@ -1135,6 +1596,22 @@ pub mod fungi_daemon_client {
                 .insert(GrpcMethod::new("fungi_daemon.FungiDaemon", "RemoveDevice"));
             self.inner.unary(req, path, codec).await
         }
+        /// Sends Wake-on-LAN packets for a saved device, relaying through devices on its LAN.
+        pub async fn wake_device(
+            &mut self,
+            request: impl tonic::IntoRequest<super::WakeDeviceRequest>,
+        ) -> std::result::Result<tonic::Response<super::WakeDeviceResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/fungi_daemon.FungiDaemon/WakeDevice");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("fungi_daemon.FungiDaemon", "WakeDevice"));
+            self.inner.unary(req, path, codec).await
+        }
         /// Continuously pings all active connections to a peer and streams results.
         pub async fn ping_peer(
             &mut self,
@@ -1255,6 +1732,21 @@ pub mod fungi_daemon_client {
             ));
             self.inner.unary(req, path, codec).await
         }
+        /// Runs connectivity checks against a peer and reports a verdict with remediation hints.
+        pub async fn diagnose(
+            &mut self,
+            request: impl tonic::IntoRequest<super::DiagnoseRequest>,
+        ) -> std::result::Result<tonic::Response<super::DiagnoseResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/fungi_daemon.FungiDaemon/Diagnose");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("fungi_daemon.FungiDaemon", "Diagnose"));
+            self.inner.unary(req, path, codec).await
+        }
         /// Pulls a service from a serialized manifest payload.
         pub async fn pull_service(
             &mut self,
@@ -1272,6 +1764,49 @@ pub mod fungi_daemon_client {
                 .insert(GrpcMethod::new("fungi_daemon.FungiDaemon", "PullService"));
             self.inner.unary(req, path, codec).await
         }
+        /// Pulls a service like PullService, streaming Docker image pull progress before the
+        /// resulting instance.
+        pub async fn pull_service_with_progress(
+            &mut self,
+            request: impl tonic::IntoRequest<super::PullServiceRequest>,
+        ) -> std::result::Result<
+            tonic::Response<tonic::codec::Streaming<super::PullServiceEvent>>,
+            tonic::Status,
+        > {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/fungi_daemon.FungiDaemon/PullServiceWithProgress",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "fungi_daemon.FungiDaemon",
+                "PullServiceWithProgress",
+            ));
+            self.inner.server_streaming(req, path, codec).await
+        }
+        /// Removes Docker images pulled for services that no container uses anymore.
+        pub async fn prune_service_images(
+            &mut self,
+            request: impl tonic::IntoRequest<super::PruneServiceImagesRequest>,
+        ) -> std::result::Result<tonic::Response<super::PruneServiceImagesResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/fungi_daemon.FungiDaemon/PruneServiceImages",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "fungi_daemon.FungiDaemon",
+                "PruneServiceImages",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
         /// Starts a pulled service.
         pub async fn start_service(
             &mut self,
@@ -1339,6 +1874,61 @@ pub mod fungi_daemon_client {
             ));
             self.inner.unary(req, path, codec).await
         }
+        /// Lists recorded manifest revisions of a pulled service, oldest first.
+        pub async fn service_history(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ServiceNameRequest>,
+        ) -> std::result::Result<tonic::Response<super::ServiceHistoryResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/fungi_daemon.FungiDaemon/ServiceHistory");
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "fungi_daemon.FungiDaemon",
+                "ServiceHistory",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Runs a pulled job service once and waits for the run to finish.
+        pub async fn run_service_job(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ServiceNameRequest>,
+        ) -> std::result::Result<tonic::Response<super::ServiceJobResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/fungi_daemon.FungiDaemon/RunServiceJob");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("fungi_daemon.FungiDaemon", "RunServiceJob"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Restores a recorded manifest revision of a pulled service.
+        pub async fn rollback_service(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RollbackServiceRequest>,
+        ) -> std::result::Result<tonic::Response<super::ServiceInstanceResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/fungi_daemon.FungiDaemon/RollbackService");
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "fungi_daemon.FungiDaemon",
+                "RollbackService",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
         /// Gets logs for a pulled service.
         pub async fn get_service_logs(
             &mut self,
@@ -1358,6 +1948,26 @@ pub mod fungi_daemon_client {
             ));
             self.inner.unary(req, path, codec).await
         }
+        /// Runs a command inside a running service, on this device or on peer_id. The first input
+        /// message must be `start`; the last output message is always `exit`.
+        pub async fn exec_service(
+            &mut self,
+            request: impl tonic::IntoStreamingRequest<Message = super::ServiceExecInput>,
+        ) -> std::result::Result<
+            tonic::Response<tonic::codec::Streaming<super::ServiceExecOutput>>,
+            tonic::Status,
+        > {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/fungi_daemon.FungiDaemon/ExecService");
+            let mut req = request.into_streaming_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("fungi_daemon.FungiDaemon", "ExecService"));
+            self.inner.streaming(req, path, codec).await
+        }
         /// Lists all pulled services on the local node, including stopped ones.
         pub async fn list_services(
             &mut self,
@@ -1375,7 +1985,7 @@ pub mod fungi_daemon_client {
                 .insert(GrpcMethod::new("fungi_daemon.FungiDaemon", "ListServices"));
             self.inner.unary(req, path, codec).await
         }
-        /// Lists official service recipes known to the local daemon.
+        /// Lists service recipes from every configured recipe source.
         pub async fn list_recipes(
             &mut self,
             request: impl tonic::IntoRequest<super::ListRecipesRequest>,
@@ -1392,7 +2002,7 @@ pub mod fungi_daemon_client {
                 .insert(GrpcMethod::new("fungi_daemon.FungiDaemon", "ListRecipes"));
             self.inner.unary(req, path, codec).await
         }
-        /// Returns detailed metadata and audit paths for one official service recipe.
+        /// Returns detailed metadata and audit paths for one service recipe.
         pub async fn get_recipe(
             &mut self,
             request: impl tonic::IntoRequest<super::GetRecipeRequest>,
@@ -1407,7 +2017,7 @@ pub mod fungi_daemon_client {
                 .insert(GrpcMethod::new("fungi_daemon.FungiDaemon", "GetRecipe"));
             self.inner.unary(req, path, codec).await
         }
-        /// Resolves one official recipe into a concrete manifest for local or remote pull.
+        /// Resolves one recipe into a concrete manifest for local or remote pull.
         pub async fn resolve_recipe(
             &mut self,
             request: impl tonic::IntoRequest<super::ResolveRecipeRequest>,
@@ -1544,6 +2154,158 @@ pub mod fungi_daemon_client {
             ));
             self.inner.unary(req, path, codec).await
         }
+        /// Lists recorded manifest revisions of a service on a remote peer.
+        pub async fn remote_service_history(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RemoteServiceNameRequest>,
+        ) -> std::result::Result<tonic::Response<super::ServiceHistoryResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/fungi_daemon.FungiDaemon/RemoteServiceHistory",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "fungi_daemon.FungiDaemon",
+                "RemoteServiceHistory",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Runs a job service on a remote peer once and waits for the run to finish.
+        pub async fn remote_run_service_job(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RemoteServiceNameRequest>,
+        ) -> std::result::Result<tonic::Response<super::ServiceJobResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/fungi_daemon.FungiDaemon/RemoteRunServiceJob",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "fungi_daemon.FungiDaemon",
+                "RemoteRunServiceJob",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Returns the schedule and run history of a job service on a remote peer.
+        pub async fn remote_service_job_status(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RemoteServiceNameRequest>,
+        ) -> std::result::Result<tonic::Response<super::ServiceJobResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/fungi_daemon.FungiDaemon/RemoteServiceJobStatus",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "fungi_daemon.FungiDaemon",
+                "RemoteServiceJobStatus",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Restores a recorded manifest revision of a service on a remote peer.
+        pub async fn remote_rollback_service(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RemoteRollbackServiceRequest>,
+        ) -> std::result::Result<tonic::Response<super::RemoteServiceControlResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/fungi_daemon.FungiDaemon/RemoteRollbackService",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "fungi_daemon.FungiDaemon",
+                "RemoteRollbackService",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Moves or clones a service and its appdata between devices, then repoints saved accesses.
+        pub async fn move_service(
+            &mut self,
+            request: impl tonic::IntoRequest<super::MoveServiceRequest>,
+        ) -> std::result::Result<tonic::Response<super::MoveServiceResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/fungi_daemon.FungiDaemon/MoveService");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("fungi_daemon.FungiDaemon", "MoveService"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Archives a local service's appdata, manifest and state here or on a backup target.
+        pub async fn backup_service(
+            &mut self,
+            request: impl tonic::IntoRequest<super::BackupServiceRequest>,
+        ) -> std::result::Result<tonic::Response<super::ServiceBackupInfo>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path =
+                http::uri::PathAndQuery::from_static("/fungi_daemon.FungiDaemon/BackupService");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("fungi_daemon.FungiDaemon", "BackupService"));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Lists this device's service backups kept here or on a backup target.
+        pub async fn list_service_backups(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListServiceBackupsRequest>,
+        ) -> std::result::Result<tonic::Response<super::ListServiceBackupsResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/fungi_daemon.FungiDaemon/ListServiceBackups",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "fungi_daemon.FungiDaemon",
+                "ListServiceBackups",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Restores a service backup kept here or on a backup target onto this device.
+        pub async fn restore_service_backup(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RestoreServiceBackupRequest>,
+        ) -> std::result::Result<tonic::Response<super::ServiceBackupInfo>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/fungi_daemon.FungiDaemon/RestoreServiceBackup",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "fungi_daemon.FungiDaemon",
+                "RestoreServiceBackup",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
         /// Forgets a cached service record for a device without mutating the remote device.
         pub async fn forget_device_service(
             &mut self,
@@ -1662,53 +2424,191 @@ pub mod fungi_daemon_client {
             ));
             self.inner.unary(req, path, codec).await
         }
-    }
-}
-/// Generated server implementations.
-pub mod fungi_daemon_server {
-    #![allow(
-        unused_variables,
-        dead_code,
-        missing_docs,
-        clippy::wildcard_imports,
-        clippy::let_unit_value
-    )]
-    use tonic::codegen::*;
-    /// Generated trait containing gRPC methods that should be implemented for use with FungiDaemonServer.
-    #[async_trait]
-    pub trait FungiDaemon: std::marker::Send + std::marker::Sync + 'static {
-        /// Returns the running daemon version as a simple health check.
-        async fn version(
-            &self,
-            request: tonic::Request<super::Empty>,
-        ) -> std::result::Result<tonic::Response<super::VersionResponse>, tonic::Status>;
-        /// Returns the libp2p peer ID of the daemon.
-        async fn peer_id(
-            &self,
-            request: tonic::Request<super::Empty>,
-        ) -> std::result::Result<tonic::Response<super::PeerIdResponse>, tonic::Status>;
-        /// Reports the absolute path of the active configuration file.
-        async fn config_file_path(
-            &self,
-            request: tonic::Request<super::Empty>,
-        ) -> std::result::Result<tonic::Response<super::ConfigFilePathResponse>, tonic::Status>;
-        /// Returns the hostname advertised by the daemon.
-        async fn hostname(
-            &self,
-            request: tonic::Request<super::Empty>,
-        ) -> std::result::Result<tonic::Response<super::HostnameResponse>, tonic::Status>;
-        /// Lists devices trusted to initiate incoming access.
-        async fn list_trusted_devices(
-            &self,
-            request: tonic::Request<super::Empty>,
-        ) -> std::result::Result<tonic::Response<super::TrustedDevicesListResponse>, tonic::Status>;
-        /// Trusts a saved device for incoming access and persists it.
-        async fn trust_device(
-            &self,
-            request: tonic::Request<super::TrustDeviceRequest>,
-        ) -> std::result::Result<tonic::Response<super::Empty>, tonic::Status>;
-        /// Removes incoming access trust from a device.
-        async fn untrust_device(
+        /// Creates or updates a remote peer's own access to a service published by another device.
+        pub async fn remote_attach_service_access(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RemoteAttachServiceAccessRequest>,
+        ) -> std::result::Result<tonic::Response<super::RemoteServiceControlResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/fungi_daemon.FungiDaemon/RemoteAttachServiceAccess",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "fungi_daemon.FungiDaemon",
+                "RemoteAttachServiceAccess",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Deletes a remote peer's saved access to a service published by another device.
+        pub async fn remote_forget_service_access(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RemoteForgetServiceAccessRequest>,
+        ) -> std::result::Result<tonic::Response<super::RemoteServiceControlResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/fungi_daemon.FungiDaemon/RemoteForgetServiceAccess",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "fungi_daemon.FungiDaemon",
+                "RemoteForgetServiceAccess",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Lists the service accesses saved on a remote peer.
+        pub async fn remote_list_service_accesses(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RemotePeerRequest>,
+        ) -> std::result::Result<tonic::Response<super::ServiceAccessesResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/fungi_daemon.FungiDaemon/RemoteListServiceAccesses",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "fungi_daemon.FungiDaemon",
+                "RemoteListServiceAccesses",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Sets token-bucket limits for a saved remote service access and its active listeners.
+        pub async fn set_service_access_bandwidth_limit(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SetServiceAccessBandwidthLimitRequest>,
+        ) -> std::result::Result<tonic::Response<super::Empty>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/fungi_daemon.FungiDaemon/SetServiceAccessBandwidthLimit",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "fungi_daemon.FungiDaemon",
+                "SetServiceAccessBandwidthLimit",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Groups a saved remote service access with the same service on other devices.
+        pub async fn set_service_access_group(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SetServiceAccessGroupRequest>,
+        ) -> std::result::Result<tonic::Response<super::ServiceAccessResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/fungi_daemon.FungiDaemon/SetServiceAccessGroup",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "fungi_daemon.FungiDaemon",
+                "SetServiceAccessGroup",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Sets serving-side token-bucket limits for a published local service port.
+        pub async fn set_service_port_bandwidth_limit(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SetServicePortBandwidthLimitRequest>,
+        ) -> std::result::Result<tonic::Response<super::Empty>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/fungi_daemon.FungiDaemon/SetServicePortBandwidthLimit",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "fungi_daemon.FungiDaemon",
+                "SetServicePortBandwidthLimit",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+        /// Lists web services of saved devices with their URLs on the local HTTP gateway.
+        pub async fn list_http_gateway_services(
+            &mut self,
+            request: impl tonic::IntoRequest<super::Empty>,
+        ) -> std::result::Result<tonic::Response<super::HttpGatewayServicesResponse>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/fungi_daemon.FungiDaemon/ListHttpGatewayServices",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new(
+                "fungi_daemon.FungiDaemon",
+                "ListHttpGatewayServices",
+            ));
+            self.inner.unary(req, path, codec).await
+        }
+    }
+}
+/// Generated server implementations.
+pub mod fungi_daemon_server {
+    #![allow(
+        unused_variables,
+        dead_code,
+        missing_docs,
+        clippy::wildcard_imports,
+        clippy::let_unit_value
+    )]
+    use tonic::codegen::*;
+    /// Generated trait containing gRPC methods that should be implemented for use with FungiDaemonServer.
+    #[async_trait]
+    pub trait FungiDaemon: std::marker::Send + std::marker::Sync + 'static {
+        /// Returns the running daemon version as a simple health check.
+        async fn version(
+            &self,
+            request: tonic::Request<super::Empty>,
+        ) -> std::result::Result<tonic::Response<super::VersionResponse>, tonic::Status>;
+        /// Returns the libp2p peer ID of the daemon.
+        async fn peer_id(
+            &self,
+            request: tonic::Request<super::Empty>,
+        ) -> std::result::Result<tonic::Response<super::PeerIdResponse>, tonic::Status>;
+        /// Reports the absolute path of the active configuration file.
+        async fn config_file_path(
+            &self,
+            request: tonic::Request<super::Empty>,
+        ) -> std::result::Result<tonic::Response<super::ConfigFilePathResponse>, tonic::Status>;
+        /// Returns the hostname advertised by the daemon.
+        async fn hostname(
+            &self,
+            request: tonic::Request<super::Empty>,
+        ) -> std::result::Result<tonic::Response<super::HostnameResponse>, tonic::Status>;
+        /// Lists devices trusted to initiate incoming access.
+        async fn list_trusted_devices(
+            &self,
+            request: tonic::Request<super::Empty>,
+        ) -> std::result::Result<tonic::Response<super::TrustedDevicesListResponse>, tonic::Status>;
+        /// Trusts a saved device for incoming access and persists it.
+        async fn trust_device(
+            &self,
+            request: tonic::Request<super::TrustDeviceRequest>,
+        ) -> std::result::Result<tonic::Response<super::Empty>, tonic::Status>;
+        /// Removes incoming access trust from a device.
+        async fn untrust_device(
             &self,
             request: tonic::Request<super::UntrustDeviceRequest>,
         ) -> std::result::Result<tonic::Response<super::Empty>, tonic::Status>;
@@ -1760,6 +2660,22 @@ pub mod fungi_daemon_server {
             &self,
             request: tonic::Request<super::RuntimeAllowedHostPathRequest>,
         ) -> std::result::Result<tonic::Response<super::Empty>, tonic::Status>;
+        /// Stores an encrypted secret for service env references like
+        /// `${secret:name}`. Secret values are never returned by the daemon.
+        async fn set_secret(
+            &self,
+            request: tonic::Request<super::SetSecretRequest>,
+        ) -> std::result::Result<tonic::Response<super::SecretInfo>, tonic::Status>;
+        /// Lists stored secret names and when they were last set.
+        async fn list_secrets(
+            &self,
+            request: tonic::Request<super::Empty>,
+        ) -> std::result::Result<tonic::Response<super::ListSecretsResponse>, tonic::Status>;
+        /// Removes a stored secret.
+        async fn remove_secret(
+            &self,
+            request: tonic::Request<super::SecretNameRequest>,
+        ) -> std::result::Result<tonic::Response<super::RemoveSecretResponse>, tonic::Status>;
         /// Returns latest metadata for devices discovered via mDNS.
         async fn list_mdns_devices(
             &self,
@@ -1785,6 +2701,11 @@ pub mod fungi_daemon_server {
             &self,
             request: tonic::Request<super::RemoveDeviceRequest>,
         ) -> std::result::Result<tonic::Response<super::Empty>, tonic::Status>;
+        /// Sends Wake-on-LAN packets for a saved device, relaying through devices on its LAN.
+        async fn wake_device(
+            &self,
+            request: tonic::Request<super::WakeDeviceRequest>,
+        ) -> std::result::Result<tonic::Response<super::WakeDeviceResponse>, tonic::Status>;
         /// Server streaming response type for the PingPeer method.
         type PingPeerStream: tonic::codegen::tokio_stream::Stream<
                 Item = std::result::Result<super::PingPeerEvent, tonic::Status>,
@@ -1827,11 +2748,32 @@ pub mod fungi_daemon_server {
             &self,
             request: tonic::Request<super::Empty>,
         ) -> std::result::Result<tonic::Response<super::ListPeerAddressesResponse>, tonic::Status>;
+        /// Runs connectivity checks against a peer and reports a verdict with remediation hints.
+        async fn diagnose(
+            &self,
+            request: tonic::Request<super::DiagnoseRequest>,
+        ) -> std::result::Result<tonic::Response<super::DiagnoseResponse>, tonic::Status>;
         /// Pulls a service from a serialized manifest payload.
         async fn pull_service(
             &self,
             request: tonic::Request<super::PullServiceRequest>,
         ) -> std::result::Result<tonic::Response<super::ServiceInstanceResponse>, tonic::Status>;
+        /// Server streaming response type for the PullServiceWithProgress method.
+        type PullServiceWithProgressStream: tonic::codegen::tokio_stream::Stream<
+                Item = std::result::Result<super::PullServiceEvent, tonic::Status>,
+            > + std::marker::Send
+            + 'static;
+        /// Pulls a service like PullService, streaming Docker image pull progress before the
+        /// resulting instance.
+        async fn pull_service_with_progress(
+            &self,
+            request: tonic::Request<super::PullServiceRequest>,
+        ) -> std::result::Result<tonic::Response<Self::PullServiceWithProgressStream>, tonic::Status>;
+        /// Removes Docker images pulled for services that no container uses anymore.
+        async fn prune_service_images(
+            &self,
+            request: tonic::Request<super::PruneServiceImagesRequest>,
+        ) -> std::result::Result<tonic::Response<super::PruneServiceImagesResponse>, tonic::Status>;
         /// Starts a pulled service.
         async fn start_service(
             &self,
@@ -1852,27 +2794,53 @@ pub mod fungi_daemon_server {
             &self,
             request: tonic::Request<super::ServiceNameRequest>,
         ) -> std::result::Result<tonic::Response<super::ServiceInstanceResponse>, tonic::Status>;
+        /// Lists recorded manifest revisions of a pulled service, oldest first.
+        async fn service_history(
+            &self,
+            request: tonic::Request<super::ServiceNameRequest>,
+        ) -> std::result::Result<tonic::Response<super::ServiceHistoryResponse>, tonic::Status>;
+        /// Runs a pulled job service once and waits for the run to finish.
+        async fn run_service_job(
+            &self,
+            request: tonic::Request<super::ServiceNameRequest>,
+        ) -> std::result::Result<tonic::Response<super::ServiceJobResponse>, tonic::Status>;
+        /// Restores a recorded manifest revision of a pulled service.
+        async fn rollback_service(
+            &self,
+            request: tonic::Request<super::RollbackServiceRequest>,
+        ) -> std::result::Result<tonic::Response<super::ServiceInstanceResponse>, tonic::Status>;
         /// Gets logs for a pulled service.
         async fn get_service_logs(
             &self,
             request: tonic::Request<super::GetServiceLogsRequest>,
         ) -> std::result::Result<tonic::Response<super::ServiceLogsResponse>, tonic::Status>;
+        /// Server streaming response type for the ExecService method.
+        type ExecServiceStream: tonic::codegen::tokio_stream::Stream<
+                Item = std::result::Result<super::ServiceExecOutput, tonic::Status>,
+            > + std::marker::Send
+            + 'static;
+        /// Runs a command inside a running service, on this device or on peer_id. The first input
+        /// message must be `start`; the last output message is always `exit`.
+        async fn exec_service(
+            &self,
+            request: tonic::Request<tonic::Streaming<super::ServiceExecInput>>,
+        ) -> std::result::Result<tonic::Response<Self::ExecServiceStream>, tonic::Status>;
         /// Lists all pulled services on the local node, including stopped ones.
         async fn list_services(
             &self,
             request: tonic::Request<super::Empty>,
         ) -> std::result::Result<tonic::Response<super::ListServicesResponse>, tonic::Status>;
-        /// Lists official service recipes known to the local daemon.
+        /// Lists service recipes from every configured recipe source.
         async fn list_recipes(
             &self,
             request: tonic::Request<super::ListRecipesRequest>,
         ) -> std::result::Result<tonic::Response<super::ListRecipesResponse>, tonic::Status>;
-        /// Returns detailed metadata and audit paths for one official service recipe.
+        /// Returns detailed metadata and audit paths for one service recipe.
         async fn get_recipe(
             &self,
             request: tonic::Request<super::GetRecipeRequest>,
         ) -> std::result::Result<tonic::Response<super::GetRecipeResponse>, tonic::Status>;
-        /// Resolves one official recipe into a concrete manifest for local or remote pull.
+        /// Resolves one recipe into a concrete manifest for local or remote pull.
         async fn resolve_recipe(
             &self,
             request: tonic::Request<super::ResolveRecipeRequest>,
@@ -1910,6 +2878,46 @@ pub mod fungi_daemon_server {
             &self,
             request: tonic::Request<super::RemoteServiceNameRequest>,
         ) -> std::result::Result<tonic::Response<super::RemoteServiceControlResponse>, tonic::Status>;
+        /// Lists recorded manifest revisions of a service on a remote peer.
+        async fn remote_service_history(
+            &self,
+            request: tonic::Request<super::RemoteServiceNameRequest>,
+        ) -> std::result::Result<tonic::Response<super::ServiceHistoryResponse>, tonic::Status>;
+        /// Runs a job service on a remote peer once and waits for the run to finish.
+        async fn remote_run_service_job(
+            &self,
+            request: tonic::Request<super::RemoteServiceNameRequest>,
+        ) -> std::result::Result<tonic::Response<super::ServiceJobResponse>, tonic::Status>;
+        /// Returns the schedule and run history of a job service on a remote peer.
+        async fn remote_service_job_status(
+            &self,
+            request: tonic::Request<super::RemoteServiceNameRequest>,
+        ) -> std::result::Result<tonic::Response<super::ServiceJobResponse>, tonic::Status>;
+        /// Restores a recorded manifest revision of a service on a remote peer.
+        async fn remote_rollback_service(
+            &self,
+            request: tonic::Request<super::RemoteRollbackServiceRequest>,
+        ) -> std::result::Result<tonic::Response<super::RemoteServiceControlResponse>, tonic::Status>;
+        /// Moves or clones a service and its appdata between devices, then repoints saved accesses.
+        async fn move_service(
+            &self,
+            request: tonic::Request<super::MoveServiceRequest>,
+        ) -> std::result::Result<tonic::Response<super::MoveServiceResponse>, tonic::Status>;
+        /// Archives a local service's appdata, manifest and state here or on a backup target.
+        async fn backup_service(
+            &self,
+            request: tonic::Request<super::BackupServiceRequest>,
+        ) -> std::result::Result<tonic::Response<super::ServiceBackupInfo>, tonic::Status>;
+        /// Lists this device's service backups kept here or on a backup target.
+        async fn list_service_backups(
+            &self,
+            request: tonic::Request<super::ListServiceBackupsRequest>,
+        ) -> std::result::Result<tonic::Response<super::ListServiceBackupsResponse>, tonic::Status>;
+        /// Restores a service backup kept here or on a backup target onto this device.
+        async fn restore_service_backup(
+            &self,
+            request: tonic::Request<super::RestoreServiceBackupRequest>,
+        ) -> std::result::Result<tonic::Response<super::ServiceBackupInfo>, tonic::Status>;
         /// Forgets a cached service record for a device without mutating the remote device.
         async fn forget_device_service(
             &self,
@@ -1940,6 +2948,41 @@ pub mod fungi_daemon_server {
             &self,
             request: tonic::Request<super::ListServiceAccessesRequest>,
         ) -> std::result::Result<tonic::Response<super::ServiceAccessesResponse>, tonic::Status>;
+        /// Creates or updates a remote peer's own access to a service published by another device.
+        async fn remote_attach_service_access(
+            &self,
+            request: tonic::Request<super::RemoteAttachServiceAccessRequest>,
+        ) -> std::result::Result<tonic::Response<super::RemoteServiceControlResponse>, tonic::Status>;
+        /// Deletes a remote peer's saved access to a service published by another device.
+        async fn remote_forget_service_access(
+            &self,
+            request: tonic::Request<super::RemoteForgetServiceAccessRequest>,
+        ) -> std::result::Result<tonic::Response<super::RemoteServiceControlResponse>, tonic::Status>;
+        /// Lists the service accesses saved on a remote peer.
+        async fn remote_list_service_accesses(
+            &self,
+            request: tonic::Request<super::RemotePeerRequest>,
+        ) -> std::result::Result<tonic::Response<super::ServiceAccessesResponse>, tonic::Status>;
+        /// Sets token-bucket limits for a saved remote service access and its active listeners.
+        async fn set_service_access_bandwidth_limit(
+            &self,
+            request: tonic::Request<super::SetServiceAccessBandwidthLimitRequest>,
+        ) -> std::result::Result<tonic::Response<super::Empty>, tonic::Status>;
+        /// Groups a saved remote service access with the same service on other devices.
+        async fn set_service_access_group(
+            &self,
+            request: tonic::Request<super::SetServiceAccessGroupRequest>,
+        ) -> std::result::Result<tonic::Response<super::ServiceAccessResponse>, tonic::Status>;
+        /// Sets serving-side token-bucket limits for a published local service port.
+        async fn set_service_port_bandwidth_limit(
+            &self,
+            request: tonic::Request<super::SetServicePortBandwidthLimitRequest>,
+        ) -> std::result::Result<tonic::Response<super::Empty>, tonic::Status>;
+        /// Lists web services of saved devices with their URLs on the local HTTP gateway.
+        async fn list_http_gateway_services(
+            &self,
+            request: tonic::Request<super::Empty>,
+        ) -> std::result::Result<tonic::Response<super::HttpGatewayServicesResponse>, tonic::Status>;
     }
     /// Fungi daemon control API.
     ///
@@ -2638,16 +3681,19 @@ pub mod fungi_daemon_server {
                     };
                     Box::pin(fut)
                 }
-                "/fungi_daemon.FungiDaemon/ListMdnsDevices" => {
+                "/fungi_daemon.FungiDaemon/SetSecret" => {
                     #[allow(non_camel_case_types)]
-                    struct ListMdnsDevicesSvc<T: FungiDaemon>(pub Arc<T>);
-                    impl<T: FungiDaemon> tonic::server::UnaryService<super::Empty> for ListMdnsDevicesSvc<T> {
-                        type Response = super::DeviceInfoListResponse;
+                    struct SetSecretSvc<T: FungiDaemon>(pub Arc<T>);
+                    impl<T: FungiDaemon> tonic::server::UnaryService<super::SetSecretRequest> for SetSecretSvc<T> {
+                        type Response = super::SecretInfo;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
-                        fn call(&mut self, request: tonic::Request<super::Empty>) -> Self::Future {
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SetSecretRequest>,
+                        ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as FungiDaemon>::list_mdns_devices(&inner, request).await
+                                <T as FungiDaemon>::set_secret(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -2658,7 +3704,7 @@ pub mod fungi_daemon_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = ListMdnsDevicesSvc(inner);
+                        let method = SetSecretSvc(inner);
                         let codec = tonic_prost::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -2674,16 +3720,16 @@ pub mod fungi_daemon_server {
                     };
                     Box::pin(fut)
                 }
-                "/fungi_daemon.FungiDaemon/ListDevices" => {
+                "/fungi_daemon.FungiDaemon/ListSecrets" => {
                     #[allow(non_camel_case_types)]
-                    struct ListDevicesSvc<T: FungiDaemon>(pub Arc<T>);
-                    impl<T: FungiDaemon> tonic::server::UnaryService<super::Empty> for ListDevicesSvc<T> {
-                        type Response = super::DeviceInfoListResponse;
+                    struct ListSecretsSvc<T: FungiDaemon>(pub Arc<T>);
+                    impl<T: FungiDaemon> tonic::server::UnaryService<super::Empty> for ListSecretsSvc<T> {
+                        type Response = super::ListSecretsResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(&mut self, request: tonic::Request<super::Empty>) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as FungiDaemon>::list_devices(&inner, request).await
+                                <T as FungiDaemon>::list_secrets(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -2694,7 +3740,7 @@ pub mod fungi_daemon_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = ListDevicesSvc(inner);
+                        let method = ListSecretsSvc(inner);
                         let codec = tonic_prost::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -2710,21 +3756,19 @@ pub mod fungi_daemon_server {
                     };
                     Box::pin(fut)
                 }
-                "/fungi_daemon.FungiDaemon/UpdateDevice" => {
+                "/fungi_daemon.FungiDaemon/RemoveSecret" => {
                     #[allow(non_camel_case_types)]
-                    struct UpdateDeviceSvc<T: FungiDaemon>(pub Arc<T>);
-                    impl<T: FungiDaemon> tonic::server::UnaryService<super::UpdateDeviceRequest>
-                        for UpdateDeviceSvc<T>
-                    {
-                        type Response = super::Empty;
+                    struct RemoveSecretSvc<T: FungiDaemon>(pub Arc<T>);
+                    impl<T: FungiDaemon> tonic::server::UnaryService<super::SecretNameRequest> for RemoveSecretSvc<T> {
+                        type Response = super::RemoveSecretResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::UpdateDeviceRequest>,
+                            request: tonic::Request<super::SecretNameRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as FungiDaemon>::update_device(&inner, request).await
+                                <T as FungiDaemon>::remove_secret(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -2735,7 +3779,7 @@ pub mod fungi_daemon_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = UpdateDeviceSvc(inner);
+                        let method = RemoveSecretSvc(inner);
                         let codec = tonic_prost::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -2751,19 +3795,16 @@ pub mod fungi_daemon_server {
                     };
                     Box::pin(fut)
                 }
-                "/fungi_daemon.FungiDaemon/GetDevice" => {
+                "/fungi_daemon.FungiDaemon/ListMdnsDevices" => {
                     #[allow(non_camel_case_types)]
-                    struct GetDeviceSvc<T: FungiDaemon>(pub Arc<T>);
-                    impl<T: FungiDaemon> tonic::server::UnaryService<super::GetDeviceRequest> for GetDeviceSvc<T> {
-                        type Response = super::DeviceInfoResponse;
+                    struct ListMdnsDevicesSvc<T: FungiDaemon>(pub Arc<T>);
+                    impl<T: FungiDaemon> tonic::server::UnaryService<super::Empty> for ListMdnsDevicesSvc<T> {
+                        type Response = super::DeviceInfoListResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
-                        fn call(
-                            &mut self,
-                            request: tonic::Request<super::GetDeviceRequest>,
-                        ) -> Self::Future {
+                        fn call(&mut self, request: tonic::Request<super::Empty>) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as FungiDaemon>::get_device(&inner, request).await
+                                <T as FungiDaemon>::list_mdns_devices(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -2774,7 +3815,7 @@ pub mod fungi_daemon_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = GetDeviceSvc(inner);
+                        let method = ListMdnsDevicesSvc(inner);
                         let codec = tonic_prost::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -2790,21 +3831,137 @@ pub mod fungi_daemon_server {
                     };
                     Box::pin(fut)
                 }
-                "/fungi_daemon.FungiDaemon/RemoveDevice" => {
+                "/fungi_daemon.FungiDaemon/ListDevices" => {
                     #[allow(non_camel_case_types)]
-                    struct RemoveDeviceSvc<T: FungiDaemon>(pub Arc<T>);
-                    impl<T: FungiDaemon> tonic::server::UnaryService<super::RemoveDeviceRequest>
-                        for RemoveDeviceSvc<T>
-                    {
-                        type Response = super::Empty;
+                    struct ListDevicesSvc<T: FungiDaemon>(pub Arc<T>);
+                    impl<T: FungiDaemon> tonic::server::UnaryService<super::Empty> for ListDevicesSvc<T> {
+                        type Response = super::DeviceInfoListResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
-                        fn call(
-                            &mut self,
-                            request: tonic::Request<super::RemoveDeviceRequest>,
-                        ) -> Self::Future {
+                        fn call(&mut self, request: tonic::Request<super::Empty>) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as FungiDaemon>::remove_device(&inner, request).await
+                                <T as FungiDaemon>::list_devices(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ListDevicesSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/fungi_daemon.FungiDaemon/UpdateDevice" => {
+                    #[allow(non_camel_case_types)]
+                    struct UpdateDeviceSvc<T: FungiDaemon>(pub Arc<T>);
+                    impl<T: FungiDaemon> tonic::server::UnaryService<super::UpdateDeviceRequest>
+                        for UpdateDeviceSvc<T>
+                    {
+                        type Response = super::Empty;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UpdateDeviceRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as FungiDaemon>::update_device(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = UpdateDeviceSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/fungi_daemon.FungiDaemon/GetDevice" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetDeviceSvc<T: FungiDaemon>(pub Arc<T>);
+                    impl<T: FungiDaemon> tonic::server::UnaryService<super::GetDeviceRequest> for GetDeviceSvc<T> {
+                        type Response = super::DeviceInfoResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetDeviceRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as FungiDaemon>::get_device(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetDeviceSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/fungi_daemon.FungiDaemon/RemoveDevice" => {
+                    #[allow(non_camel_case_types)]
+                    struct RemoveDeviceSvc<T: FungiDaemon>(pub Arc<T>);
+                    impl<T: FungiDaemon> tonic::server::UnaryService<super::RemoveDeviceRequest>
+                        for RemoveDeviceSvc<T>
+                    {
+                        type Response = super::Empty;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::RemoveDeviceRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as FungiDaemon>::remove_device(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -2831,6 +3988,45 @@ pub mod fungi_daemon_server {
                     };
                     Box::pin(fut)
                 }
+                "/fungi_daemon.FungiDaemon/WakeDevice" => {
+                    #[allow(non_camel_case_types)]
+                    struct WakeDeviceSvc<T: FungiDaemon>(pub Arc<T>);
+                    impl<T: FungiDaemon> tonic::server::UnaryService<super::WakeDeviceRequest> for WakeDeviceSvc<T> {
+                        type Response = super::WakeDeviceResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::WakeDeviceRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as FungiDaemon>::wake_device(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = WakeDeviceSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/fungi_daemon.FungiDaemon/PingPeer" => {
                     #[allow(non_camel_case_types)]
                     struct PingPeerSvc<T: FungiDaemon>(pub Arc<T>);
@@ -3071,6 +4267,44 @@ pub mod fungi_daemon_server {
                     };
                     Box::pin(fut)
                 }
+                "/fungi_daemon.FungiDaemon/Diagnose" => {
+                    #[allow(non_camel_case_types)]
+                    struct DiagnoseSvc<T: FungiDaemon>(pub Arc<T>);
+                    impl<T: FungiDaemon> tonic::server::UnaryService<super::DiagnoseRequest> for DiagnoseSvc<T> {
+                        type Response = super::DiagnoseResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::DiagnoseRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut =
+                                async move { <T as FungiDaemon>::diagnose(&inner, request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = DiagnoseSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/fungi_daemon.FungiDaemon/PullService" => {
                     #[allow(non_camel_case_types)]
                     struct PullServiceSvc<T: FungiDaemon>(pub Arc<T>);
@@ -3083,7 +4317,870 @@ pub mod fungi_daemon_server {
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as FungiDaemon>::pull_service(&inner, request).await
+                                <T as FungiDaemon>::pull_service(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = PullServiceSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/fungi_daemon.FungiDaemon/PullServiceWithProgress" => {
+                    #[allow(non_camel_case_types)]
+                    struct PullServiceWithProgressSvc<T: FungiDaemon>(pub Arc<T>);
+                    impl<T: FungiDaemon>
+                        tonic::server::ServerStreamingService<super::PullServiceRequest>
+                        for PullServiceWithProgressSvc<T>
+                    {
+                        type Response = super::PullServiceEvent;
+                        type ResponseStream = T::PullServiceWithProgressStream;
+                        type Future =
+                            BoxFuture<tonic::Response<Self::ResponseStream>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::PullServiceRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as FungiDaemon>::pull_service_with_progress(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = PullServiceWithProgressSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/fungi_daemon.FungiDaemon/PruneServiceImages" => {
+                    #[allow(non_camel_case_types)]
+                    struct PruneServiceImagesSvc<T: FungiDaemon>(pub Arc<T>);
+                    impl<T: FungiDaemon>
+                        tonic::server::UnaryService<super::PruneServiceImagesRequest>
+                        for PruneServiceImagesSvc<T>
+                    {
+                        type Response = super::PruneServiceImagesResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::PruneServiceImagesRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as FungiDaemon>::prune_service_images(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = PruneServiceImagesSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/fungi_daemon.FungiDaemon/StartService" => {
+                    #[allow(non_camel_case_types)]
+                    struct StartServiceSvc<T: FungiDaemon>(pub Arc<T>);
+                    impl<T: FungiDaemon> tonic::server::UnaryService<super::ServiceNameRequest> for StartServiceSvc<T> {
+                        type Response = super::Empty;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ServiceNameRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as FungiDaemon>::start_service(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = StartServiceSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/fungi_daemon.FungiDaemon/StopService" => {
+                    #[allow(non_camel_case_types)]
+                    struct StopServiceSvc<T: FungiDaemon>(pub Arc<T>);
+                    impl<T: FungiDaemon> tonic::server::UnaryService<super::ServiceNameRequest> for StopServiceSvc<T> {
+                        type Response = super::Empty;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ServiceNameRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as FungiDaemon>::stop_service(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = StopServiceSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/fungi_daemon.FungiDaemon/RemoveService" => {
+                    #[allow(non_camel_case_types)]
+                    struct RemoveServiceSvc<T: FungiDaemon>(pub Arc<T>);
+                    impl<T: FungiDaemon> tonic::server::UnaryService<super::ServiceNameRequest>
+                        for RemoveServiceSvc<T>
+                    {
+                        type Response = super::Empty;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ServiceNameRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as FungiDaemon>::remove_service(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = RemoveServiceSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/fungi_daemon.FungiDaemon/InspectService" => {
+                    #[allow(non_camel_case_types)]
+                    struct InspectServiceSvc<T: FungiDaemon>(pub Arc<T>);
+                    impl<T: FungiDaemon> tonic::server::UnaryService<super::ServiceNameRequest>
+                        for InspectServiceSvc<T>
+                    {
+                        type Response = super::ServiceInstanceResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ServiceNameRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as FungiDaemon>::inspect_service(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = InspectServiceSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/fungi_daemon.FungiDaemon/ServiceHistory" => {
+                    #[allow(non_camel_case_types)]
+                    struct ServiceHistorySvc<T: FungiDaemon>(pub Arc<T>);
+                    impl<T: FungiDaemon> tonic::server::UnaryService<super::ServiceNameRequest>
+                        for ServiceHistorySvc<T>
+                    {
+                        type Response = super::ServiceHistoryResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ServiceNameRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as FungiDaemon>::service_history(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ServiceHistorySvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/fungi_daemon.FungiDaemon/RunServiceJob" => {
+                    #[allow(non_camel_case_types)]
+                    struct RunServiceJobSvc<T: FungiDaemon>(pub Arc<T>);
+                    impl<T: FungiDaemon> tonic::server::UnaryService<super::ServiceNameRequest>
+                        for RunServiceJobSvc<T>
+                    {
+                        type Response = super::ServiceJobResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ServiceNameRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as FungiDaemon>::run_service_job(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = RunServiceJobSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/fungi_daemon.FungiDaemon/RollbackService" => {
+                    #[allow(non_camel_case_types)]
+                    struct RollbackServiceSvc<T: FungiDaemon>(pub Arc<T>);
+                    impl<T: FungiDaemon> tonic::server::UnaryService<super::RollbackServiceRequest>
+                        for RollbackServiceSvc<T>
+                    {
+                        type Response = super::ServiceInstanceResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::RollbackServiceRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as FungiDaemon>::rollback_service(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = RollbackServiceSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/fungi_daemon.FungiDaemon/GetServiceLogs" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetServiceLogsSvc<T: FungiDaemon>(pub Arc<T>);
+                    impl<T: FungiDaemon> tonic::server::UnaryService<super::GetServiceLogsRequest>
+                        for GetServiceLogsSvc<T>
+                    {
+                        type Response = super::ServiceLogsResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetServiceLogsRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as FungiDaemon>::get_service_logs(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetServiceLogsSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/fungi_daemon.FungiDaemon/ExecService" => {
+                    #[allow(non_camel_case_types)]
+                    struct ExecServiceSvc<T: FungiDaemon>(pub Arc<T>);
+                    impl<T: FungiDaemon> tonic::server::StreamingService<super::ServiceExecInput>
+                        for ExecServiceSvc<T>
+                    {
+                        type Response = super::ServiceExecOutput;
+                        type ResponseStream = T::ExecServiceStream;
+                        type Future =
+                            BoxFuture<tonic::Response<Self::ResponseStream>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<tonic::Streaming<super::ServiceExecInput>>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as FungiDaemon>::exec_service(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ExecServiceSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/fungi_daemon.FungiDaemon/ListServices" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListServicesSvc<T: FungiDaemon>(pub Arc<T>);
+                    impl<T: FungiDaemon> tonic::server::UnaryService<super::Empty> for ListServicesSvc<T> {
+                        type Response = super::ListServicesResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(&mut self, request: tonic::Request<super::Empty>) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as FungiDaemon>::list_services(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ListServicesSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/fungi_daemon.FungiDaemon/ListRecipes" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListRecipesSvc<T: FungiDaemon>(pub Arc<T>);
+                    impl<T: FungiDaemon> tonic::server::UnaryService<super::ListRecipesRequest> for ListRecipesSvc<T> {
+                        type Response = super::ListRecipesResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListRecipesRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as FungiDaemon>::list_recipes(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ListRecipesSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/fungi_daemon.FungiDaemon/GetRecipe" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetRecipeSvc<T: FungiDaemon>(pub Arc<T>);
+                    impl<T: FungiDaemon> tonic::server::UnaryService<super::GetRecipeRequest> for GetRecipeSvc<T> {
+                        type Response = super::GetRecipeResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetRecipeRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as FungiDaemon>::get_recipe(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetRecipeSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/fungi_daemon.FungiDaemon/ResolveRecipe" => {
+                    #[allow(non_camel_case_types)]
+                    struct ResolveRecipeSvc<T: FungiDaemon>(pub Arc<T>);
+                    impl<T: FungiDaemon> tonic::server::UnaryService<super::ResolveRecipeRequest>
+                        for ResolveRecipeSvc<T>
+                    {
+                        type Response = super::ResolveRecipeResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ResolveRecipeRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as FungiDaemon>::resolve_recipe(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ResolveRecipeSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/fungi_daemon.FungiDaemon/GetDeviceServiceSnapshot" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetDeviceServiceSnapshotSvc<T: FungiDaemon>(pub Arc<T>);
+                    impl<T: FungiDaemon>
+                        tonic::server::UnaryService<super::DeviceServiceSnapshotRequest>
+                        for GetDeviceServiceSnapshotSvc<T>
+                    {
+                        type Response = super::DeviceServiceSnapshotResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::DeviceServiceSnapshotRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as FungiDaemon>::get_device_service_snapshot(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetDeviceServiceSnapshotSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/fungi_daemon.FungiDaemon/GetPeerCapabilitySummary" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetPeerCapabilitySummarySvc<T: FungiDaemon>(pub Arc<T>);
+                    impl<T: FungiDaemon>
+                        tonic::server::UnaryService<super::GetPeerCapabilitySummaryRequest>
+                        for GetPeerCapabilitySummarySvc<T>
+                    {
+                        type Response = super::GetPeerCapabilitySummaryResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetPeerCapabilitySummaryRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as FungiDaemon>::get_peer_capability_summary(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = GetPeerCapabilitySummarySvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/fungi_daemon.FungiDaemon/RemotePullService" => {
+                    #[allow(non_camel_case_types)]
+                    struct RemotePullServiceSvc<T: FungiDaemon>(pub Arc<T>);
+                    impl<T: FungiDaemon>
+                        tonic::server::UnaryService<super::RemotePullServiceRequest>
+                        for RemotePullServiceSvc<T>
+                    {
+                        type Response = super::RemoteServiceControlResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::RemotePullServiceRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as FungiDaemon>::remote_pull_service(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = RemotePullServiceSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/fungi_daemon.FungiDaemon/RemoteStartService" => {
+                    #[allow(non_camel_case_types)]
+                    struct RemoteStartServiceSvc<T: FungiDaemon>(pub Arc<T>);
+                    impl<T: FungiDaemon>
+                        tonic::server::UnaryService<super::RemoteServiceNameRequest>
+                        for RemoteStartServiceSvc<T>
+                    {
+                        type Response = super::RemoteServiceControlResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::RemoteServiceNameRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as FungiDaemon>::remote_start_service(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = RemoteStartServiceSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/fungi_daemon.FungiDaemon/RemoteStopService" => {
+                    #[allow(non_camel_case_types)]
+                    struct RemoteStopServiceSvc<T: FungiDaemon>(pub Arc<T>);
+                    impl<T: FungiDaemon>
+                        tonic::server::UnaryService<super::RemoteServiceNameRequest>
+                        for RemoteStopServiceSvc<T>
+                    {
+                        type Response = super::RemoteServiceControlResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::RemoteServiceNameRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as FungiDaemon>::remote_stop_service(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = RemoteStopServiceSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/fungi_daemon.FungiDaemon/RemoteRemoveService" => {
+                    #[allow(non_camel_case_types)]
+                    struct RemoteRemoveServiceSvc<T: FungiDaemon>(pub Arc<T>);
+                    impl<T: FungiDaemon>
+                        tonic::server::UnaryService<super::RemoteServiceNameRequest>
+                        for RemoteRemoveServiceSvc<T>
+                    {
+                        type Response = super::RemoteServiceControlResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::RemoteServiceNameRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as FungiDaemon>::remote_remove_service(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -3094,7 +5191,7 @@ pub mod fungi_daemon_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = PullServiceSvc(inner);
+                        let method = RemoteRemoveServiceSvc(inner);
                         let codec = tonic_prost::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -3110,19 +5207,22 @@ pub mod fungi_daemon_server {
                     };
                     Box::pin(fut)
                 }
-                "/fungi_daemon.FungiDaemon/StartService" => {
+                "/fungi_daemon.FungiDaemon/RemoteServiceHistory" => {
                     #[allow(non_camel_case_types)]
-                    struct StartServiceSvc<T: FungiDaemon>(pub Arc<T>);
-                    impl<T: FungiDaemon> tonic::server::UnaryService<super::ServiceNameRequest> for StartServiceSvc<T> {
-                        type Response = super::Empty;
+                    struct RemoteServiceHistorySvc<T: FungiDaemon>(pub Arc<T>);
+                    impl<T: FungiDaemon>
+                        tonic::server::UnaryService<super::RemoteServiceNameRequest>
+                        for RemoteServiceHistorySvc<T>
+                    {
+                        type Response = super::ServiceHistoryResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::ServiceNameRequest>,
+                            request: tonic::Request<super::RemoteServiceNameRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as FungiDaemon>::start_service(&inner, request).await
+                                <T as FungiDaemon>::remote_service_history(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -3133,7 +5233,7 @@ pub mod fungi_daemon_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = StartServiceSvc(inner);
+                        let method = RemoteServiceHistorySvc(inner);
                         let codec = tonic_prost::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -3149,19 +5249,22 @@ pub mod fungi_daemon_server {
                     };
                     Box::pin(fut)
                 }
-                "/fungi_daemon.FungiDaemon/StopService" => {
+                "/fungi_daemon.FungiDaemon/RemoteRunServiceJob" => {
                     #[allow(non_camel_case_types)]
-                    struct StopServiceSvc<T: FungiDaemon>(pub Arc<T>);
-                    impl<T: FungiDaemon> tonic::server::UnaryService<super::ServiceNameRequest> for StopServiceSvc<T> {
-                        type Response = super::Empty;
+                    struct RemoteRunServiceJobSvc<T: FungiDaemon>(pub Arc<T>);
+                    impl<T: FungiDaemon>
+                        tonic::server::UnaryService<super::RemoteServiceNameRequest>
+                        for RemoteRunServiceJobSvc<T>
+                    {
+                        type Response = super::ServiceJobResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::ServiceNameRequest>,
+                            request: tonic::Request<super::RemoteServiceNameRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as FungiDaemon>::stop_service(&inner, request).await
+                                <T as FungiDaemon>::remote_run_service_job(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -3172,7 +5275,7 @@ pub mod fungi_daemon_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = StopServiceSvc(inner);
+                        let method = RemoteRunServiceJobSvc(inner);
                         let codec = tonic_prost::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -3188,21 +5291,22 @@ pub mod fungi_daemon_server {
                     };
                     Box::pin(fut)
                 }
-                "/fungi_daemon.FungiDaemon/RemoveService" => {
+                "/fungi_daemon.FungiDaemon/RemoteServiceJobStatus" => {
                     #[allow(non_camel_case_types)]
-                    struct RemoveServiceSvc<T: FungiDaemon>(pub Arc<T>);
-                    impl<T: FungiDaemon> tonic::server::UnaryService<super::ServiceNameRequest>
-                        for RemoveServiceSvc<T>
+                    struct RemoteServiceJobStatusSvc<T: FungiDaemon>(pub Arc<T>);
+                    impl<T: FungiDaemon>
+                        tonic::server::UnaryService<super::RemoteServiceNameRequest>
+                        for RemoteServiceJobStatusSvc<T>
                     {
-                        type Response = super::Empty;
+                        type Response = super::ServiceJobResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::ServiceNameRequest>,
+                            request: tonic::Request<super::RemoteServiceNameRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as FungiDaemon>::remove_service(&inner, request).await
+                                <T as FungiDaemon>::remote_service_job_status(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -3213,7 +5317,7 @@ pub mod fungi_daemon_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = RemoveServiceSvc(inner);
+                        let method = RemoteServiceJobStatusSvc(inner);
                         let codec = tonic_prost::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -3229,21 +5333,22 @@ pub mod fungi_daemon_server {
                     };
                     Box::pin(fut)
                 }
-                "/fungi_daemon.FungiDaemon/InspectService" => {
+                "/fungi_daemon.FungiDaemon/RemoteRollbackService" => {
                     #[allow(non_camel_case_types)]
-                    struct InspectServiceSvc<T: FungiDaemon>(pub Arc<T>);
-                    impl<T: FungiDaemon> tonic::server::UnaryService<super::ServiceNameRequest>
-                        for InspectServiceSvc<T>
+                    struct RemoteRollbackServiceSvc<T: FungiDaemon>(pub Arc<T>);
+                    impl<T: FungiDaemon>
+                        tonic::server::UnaryService<super::RemoteRollbackServiceRequest>
+                        for RemoteRollbackServiceSvc<T>
                     {
-                        type Response = super::ServiceInstanceResponse;
+                        type Response = super::RemoteServiceControlResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::ServiceNameRequest>,
+                            request: tonic::Request<super::RemoteRollbackServiceRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as FungiDaemon>::inspect_service(&inner, request).await
+                                <T as FungiDaemon>::remote_rollback_service(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -3254,7 +5359,7 @@ pub mod fungi_daemon_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = InspectServiceSvc(inner);
+                        let method = RemoteRollbackServiceSvc(inner);
                         let codec = tonic_prost::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -3270,21 +5375,19 @@ pub mod fungi_daemon_server {
                     };
                     Box::pin(fut)
                 }
-                "/fungi_daemon.FungiDaemon/GetServiceLogs" => {
+                "/fungi_daemon.FungiDaemon/MoveService" => {
                     #[allow(non_camel_case_types)]
-                    struct GetServiceLogsSvc<T: FungiDaemon>(pub Arc<T>);
-                    impl<T: FungiDaemon> tonic::server::UnaryService<super::GetServiceLogsRequest>
-                        for GetServiceLogsSvc<T>
-                    {
-                        type Response = super::ServiceLogsResponse;
+                    struct MoveServiceSvc<T: FungiDaemon>(pub Arc<T>);
+                    impl<T: FungiDaemon> tonic::server::UnaryService<super::MoveServiceRequest> for MoveServiceSvc<T> {
+                        type Response = super::MoveServiceResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::GetServiceLogsRequest>,
+                            request: tonic::Request<super::MoveServiceRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as FungiDaemon>::get_service_logs(&inner, request).await
+                                <T as FungiDaemon>::move_service(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -3295,7 +5398,7 @@ pub mod fungi_daemon_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = GetServiceLogsSvc(inner);
+                        let method = MoveServiceSvc(inner);
                         let codec = tonic_prost::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -3311,16 +5414,21 @@ pub mod fungi_daemon_server {
                     };
                     Box::pin(fut)
                 }
-                "/fungi_daemon.FungiDaemon/ListServices" => {
+                "/fungi_daemon.FungiDaemon/BackupService" => {
                     #[allow(non_camel_case_types)]
-                    struct ListServicesSvc<T: FungiDaemon>(pub Arc<T>);
-                    impl<T: FungiDaemon> tonic::server::UnaryService<super::Empty> for ListServicesSvc<T> {
-                        type Response = super::ListServicesResponse;
+                    struct BackupServiceSvc<T: FungiDaemon>(pub Arc<T>);
+                    impl<T: FungiDaemon> tonic::server::UnaryService<super::BackupServiceRequest>
+                        for BackupServiceSvc<T>
+                    {
+                        type Response = super::ServiceBackupInfo;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
-                        fn call(&mut self, request: tonic::Request<super::Empty>) -> Self::Future {
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::BackupServiceRequest>,
+                        ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as FungiDaemon>::list_services(&inner, request).await
+                                <T as FungiDaemon>::backup_service(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -3331,7 +5439,7 @@ pub mod fungi_daemon_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = ListServicesSvc(inner);
+                        let method = BackupServiceSvc(inner);
                         let codec = tonic_prost::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -3347,19 +5455,22 @@ pub mod fungi_daemon_server {
                     };
                     Box::pin(fut)
                 }
-                "/fungi_daemon.FungiDaemon/ListRecipes" => {
+                "/fungi_daemon.FungiDaemon/ListServiceBackups" => {
                     #[allow(non_camel_case_types)]
-                    struct ListRecipesSvc<T: FungiDaemon>(pub Arc<T>);
-                    impl<T: FungiDaemon> tonic::server::UnaryService<super::ListRecipesRequest> for ListRecipesSvc<T> {
-                        type Response = super::ListRecipesResponse;
+                    struct ListServiceBackupsSvc<T: FungiDaemon>(pub Arc<T>);
+                    impl<T: FungiDaemon>
+                        tonic::server::UnaryService<super::ListServiceBackupsRequest>
+                        for ListServiceBackupsSvc<T>
+                    {
+                        type Response = super::ListServiceBackupsResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::ListRecipesRequest>,
+                            request: tonic::Request<super::ListServiceBackupsRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as FungiDaemon>::list_recipes(&inner, request).await
+                                <T as FungiDaemon>::list_service_backups(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -3370,7 +5481,7 @@ pub mod fungi_daemon_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = ListRecipesSvc(inner);
+                        let method = ListServiceBackupsSvc(inner);
                         let codec = tonic_prost::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -3386,19 +5497,22 @@ pub mod fungi_daemon_server {
                     };
                     Box::pin(fut)
                 }
-                "/fungi_daemon.FungiDaemon/GetRecipe" => {
+                "/fungi_daemon.FungiDaemon/RestoreServiceBackup" => {
                     #[allow(non_camel_case_types)]
-                    struct GetRecipeSvc<T: FungiDaemon>(pub Arc<T>);
-                    impl<T: FungiDaemon> tonic::server::UnaryService<super::GetRecipeRequest> for GetRecipeSvc<T> {
-                        type Response = super::GetRecipeResponse;
+                    struct RestoreServiceBackupSvc<T: FungiDaemon>(pub Arc<T>);
+                    impl<T: FungiDaemon>
+                        tonic::server::UnaryService<super::RestoreServiceBackupRequest>
+                        for RestoreServiceBackupSvc<T>
+                    {
+                        type Response = super::ServiceBackupInfo;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::GetRecipeRequest>,
+                            request: tonic::Request<super::RestoreServiceBackupRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as FungiDaemon>::get_recipe(&inner, request).await
+                                <T as FungiDaemon>::restore_service_backup(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -3409,7 +5523,7 @@ pub mod fungi_daemon_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = GetRecipeSvc(inner);
+                        let method = RestoreServiceBackupSvc(inner);
                         let codec = tonic_prost::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -3425,21 +5539,22 @@ pub mod fungi_daemon_server {
                     };
                     Box::pin(fut)
                 }
-                "/fungi_daemon.FungiDaemon/ResolveRecipe" => {
+                "/fungi_daemon.FungiDaemon/ForgetDeviceService" => {
                     #[allow(non_camel_case_types)]
-                    struct ResolveRecipeSvc<T: FungiDaemon>(pub Arc<T>);
-                    impl<T: FungiDaemon> tonic::server::UnaryService<super::ResolveRecipeRequest>
-                        for ResolveRecipeSvc<T>
+                    struct ForgetDeviceServiceSvc<T: FungiDaemon>(pub Arc<T>);
+                    impl<T: FungiDaemon>
+                        tonic::server::UnaryService<super::RemoteServiceNameRequest>
+                        for ForgetDeviceServiceSvc<T>
                     {
-                        type Response = super::ResolveRecipeResponse;
+                        type Response = super::RemoteServiceControlResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::ResolveRecipeRequest>,
+                            request: tonic::Request<super::RemoteServiceNameRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as FungiDaemon>::resolve_recipe(&inner, request).await
+                                <T as FungiDaemon>::forget_device_service(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -3450,7 +5565,7 @@ pub mod fungi_daemon_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = ResolveRecipeSvc(inner);
+                        let method = ForgetDeviceServiceSvc(inner);
                         let codec = tonic_prost::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -3466,23 +5581,21 @@ pub mod fungi_daemon_server {
                     };
                     Box::pin(fut)
                 }
-                "/fungi_daemon.FungiDaemon/GetDeviceServiceSnapshot" => {
+                "/fungi_daemon.FungiDaemon/RemoteListServices" => {
                     #[allow(non_camel_case_types)]
-                    struct GetDeviceServiceSnapshotSvc<T: FungiDaemon>(pub Arc<T>);
-                    impl<T: FungiDaemon>
-                        tonic::server::UnaryService<super::DeviceServiceSnapshotRequest>
-                        for GetDeviceServiceSnapshotSvc<T>
+                    struct RemoteListServicesSvc<T: FungiDaemon>(pub Arc<T>);
+                    impl<T: FungiDaemon> tonic::server::UnaryService<super::RemotePeerRequest>
+                        for RemoteListServicesSvc<T>
                     {
-                        type Response = super::DeviceServiceSnapshotResponse;
+                        type Response = super::ListServicesResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::DeviceServiceSnapshotRequest>,
+                            request: tonic::Request<super::RemotePeerRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as FungiDaemon>::get_device_service_snapshot(&inner, request)
-                                    .await
+                                <T as FungiDaemon>::remote_list_services(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -3493,7 +5606,7 @@ pub mod fungi_daemon_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = GetDeviceServiceSnapshotSvc(inner);
+                        let method = RemoteListServicesSvc(inner);
                         let codec = tonic_prost::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -3509,23 +5622,22 @@ pub mod fungi_daemon_server {
                     };
                     Box::pin(fut)
                 }
-                "/fungi_daemon.FungiDaemon/GetPeerCapabilitySummary" => {
+                "/fungi_daemon.FungiDaemon/AttachServiceAccess" => {
                     #[allow(non_camel_case_types)]
-                    struct GetPeerCapabilitySummarySvc<T: FungiDaemon>(pub Arc<T>);
+                    struct AttachServiceAccessSvc<T: FungiDaemon>(pub Arc<T>);
                     impl<T: FungiDaemon>
-                        tonic::server::UnaryService<super::GetPeerCapabilitySummaryRequest>
-                        for GetPeerCapabilitySummarySvc<T>
+                        tonic::server::UnaryService<super::AttachServiceAccessRequest>
+                        for AttachServiceAccessSvc<T>
                     {
-                        type Response = super::GetPeerCapabilitySummaryResponse;
+                        type Response = super::ServiceAccessResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::GetPeerCapabilitySummaryRequest>,
+                            request: tonic::Request<super::AttachServiceAccessRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as FungiDaemon>::get_peer_capability_summary(&inner, request)
-                                    .await
+                                <T as FungiDaemon>::attach_service_access(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -3536,7 +5648,7 @@ pub mod fungi_daemon_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = GetPeerCapabilitySummarySvc(inner);
+                        let method = AttachServiceAccessSvc(inner);
                         let codec = tonic_prost::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -3552,22 +5664,22 @@ pub mod fungi_daemon_server {
                     };
                     Box::pin(fut)
                 }
-                "/fungi_daemon.FungiDaemon/RemotePullService" => {
+                "/fungi_daemon.FungiDaemon/DetachServiceAccess" => {
                     #[allow(non_camel_case_types)]
-                    struct RemotePullServiceSvc<T: FungiDaemon>(pub Arc<T>);
+                    struct DetachServiceAccessSvc<T: FungiDaemon>(pub Arc<T>);
                     impl<T: FungiDaemon>
-                        tonic::server::UnaryService<super::RemotePullServiceRequest>
-                        for RemotePullServiceSvc<T>
+                        tonic::server::UnaryService<super::DetachServiceAccessRequest>
+                        for DetachServiceAccessSvc<T>
                     {
-                        type Response = super::RemoteServiceControlResponse;
+                        type Response = super::Empty;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::RemotePullServiceRequest>,
+                            request: tonic::Request<super::DetachServiceAccessRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as FungiDaemon>::remote_pull_service(&inner, request).await
+                                <T as FungiDaemon>::detach_service_access(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -3578,7 +5690,7 @@ pub mod fungi_daemon_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = RemotePullServiceSvc(inner);
+                        let method = DetachServiceAccessSvc(inner);
                         let codec = tonic_prost::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -3594,22 +5706,22 @@ pub mod fungi_daemon_server {
                     };
                     Box::pin(fut)
                 }
-                "/fungi_daemon.FungiDaemon/RemoteStartService" => {
+                "/fungi_daemon.FungiDaemon/ForgetServiceAccess" => {
                     #[allow(non_camel_case_types)]
-                    struct RemoteStartServiceSvc<T: FungiDaemon>(pub Arc<T>);
+                    struct ForgetServiceAccessSvc<T: FungiDaemon>(pub Arc<T>);
                     impl<T: FungiDaemon>
-                        tonic::server::UnaryService<super::RemoteServiceNameRequest>
-                        for RemoteStartServiceSvc<T>
+                        tonic::server::UnaryService<super::ForgetServiceAccessRequest>
+                        for ForgetServiceAccessSvc<T>
                     {
-                        type Response = super::RemoteServiceControlResponse;
+                        type Response = super::Empty;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::RemoteServiceNameRequest>,
+                            request: tonic::Request<super::ForgetServiceAccessRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as FungiDaemon>::remote_start_service(&inner, request).await
+                                <T as FungiDaemon>::forget_service_access(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -3620,7 +5732,7 @@ pub mod fungi_daemon_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = RemoteStartServiceSvc(inner);
+                        let method = ForgetServiceAccessSvc(inner);
                         let codec = tonic_prost::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -3636,22 +5748,22 @@ pub mod fungi_daemon_server {
                     };
                     Box::pin(fut)
                 }
-                "/fungi_daemon.FungiDaemon/RemoteStopService" => {
+                "/fungi_daemon.FungiDaemon/ListServiceAccesses" => {
                     #[allow(non_camel_case_types)]
-                    struct RemoteStopServiceSvc<T: FungiDaemon>(pub Arc<T>);
+                    struct ListServiceAccessesSvc<T: FungiDaemon>(pub Arc<T>);
                     impl<T: FungiDaemon>
-                        tonic::server::UnaryService<super::RemoteServiceNameRequest>
-                        for RemoteStopServiceSvc<T>
+                        tonic::server::UnaryService<super::ListServiceAccessesRequest>
+                        for ListServiceAccessesSvc<T>
                     {
-                        type Response = super::RemoteServiceControlResponse;
+                        type Response = super::ServiceAccessesResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::RemoteServiceNameRequest>,
+                            request: tonic::Request<super::ListServiceAccessesRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as FungiDaemon>::remote_stop_service(&inner, request).await
+                                <T as FungiDaemon>::list_service_accesses(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -3662,7 +5774,7 @@ pub mod fungi_daemon_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = RemoteStopServiceSvc(inner);
+                        let method = ListServiceAccessesSvc(inner);
                         let codec = tonic_prost::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -3678,22 +5790,23 @@ pub mod fungi_daemon_server {
                     };
                     Box::pin(fut)
                 }
-                "/fungi_daemon.FungiDaemon/RemoteRemoveService" => {
+                "/fungi_daemon.FungiDaemon/RemoteAttachServiceAccess" => {
                     #[allow(non_camel_case_types)]
-                    struct RemoteRemoveServiceSvc<T: FungiDaemon>(pub Arc<T>);
+                    struct RemoteAttachServiceAccessSvc<T: FungiDaemon>(pub Arc<T>);
                     impl<T: FungiDaemon>
-                        tonic::server::UnaryService<super::RemoteServiceNameRequest>
-                        for RemoteRemoveServiceSvc<T>
+                        tonic::server::UnaryService<super::RemoteAttachServiceAccessRequest>
+                        for RemoteAttachServiceAccessSvc<T>
                     {
                         type Response = super::RemoteServiceControlResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::RemoteServiceNameRequest>,
+                            request: tonic::Request<super::RemoteAttachServiceAccessRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as FungiDaemon>::remote_remove_service(&inner, request).await
+                                <T as FungiDaemon>::remote_attach_service_access(&inner, request)
+                                    .await
                             };
                             Box::pin(fut)
                         }
@@ -3704,7 +5817,7 @@ pub mod fungi_daemon_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = RemoteRemoveServiceSvc(inner);
+                        let method = RemoteAttachServiceAccessSvc(inner);
                         let codec = tonic_prost::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -3720,22 +5833,23 @@ pub mod fungi_daemon_server {
                     };
                     Box::pin(fut)
                 }
-                "/fungi_daemon.FungiDaemon/ForgetDeviceService" => {
+                "/fungi_daemon.FungiDaemon/RemoteForgetServiceAccess" => {
                     #[allow(non_camel_case_types)]
-                    struct ForgetDeviceServiceSvc<T: FungiDaemon>(pub Arc<T>);
+                    struct RemoteForgetServiceAccessSvc<T: FungiDaemon>(pub Arc<T>);
                     impl<T: FungiDaemon>
-                        tonic::server::UnaryService<super::RemoteServiceNameRequest>
-                        for ForgetDeviceServiceSvc<T>
+                        tonic::server::UnaryService<super::RemoteForgetServiceAccessRequest>
+                        for RemoteForgetServiceAccessSvc<T>
                     {
                         type Response = super::RemoteServiceControlResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::RemoteServiceNameRequest>,
+                            request: tonic::Request<super::RemoteForgetServiceAccessRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as FungiDaemon>::forget_device_service(&inner, request).await
+                                <T as FungiDaemon>::remote_forget_service_access(&inner, request)
+                                    .await
                             };
                             Box::pin(fut)
                         }
@@ -3746,7 +5860,7 @@ pub mod fungi_daemon_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = ForgetDeviceServiceSvc(inner);
+                        let method = RemoteForgetServiceAccessSvc(inner);
                         let codec = tonic_prost::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -3762,13 +5876,13 @@ pub mod fungi_daemon_server {
                     };
                     Box::pin(fut)
                 }
-                "/fungi_daemon.FungiDaemon/RemoteListServices" => {
+                "/fungi_daemon.FungiDaemon/RemoteListServiceAccesses" => {
                     #[allow(non_camel_case_types)]
-                    struct RemoteListServicesSvc<T: FungiDaemon>(pub Arc<T>);
+                    struct RemoteListServiceAccessesSvc<T: FungiDaemon>(pub Arc<T>);
                     impl<T: FungiDaemon> tonic::server::UnaryService<super::RemotePeerRequest>
-                        for RemoteListServicesSvc<T>
+                        for RemoteListServiceAccessesSvc<T>
                     {
-                        type Response = super::ListServicesResponse;
+                        type Response = super::ServiceAccessesResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
@@ -3776,7 +5890,8 @@ pub mod fungi_daemon_server {
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as FungiDaemon>::remote_list_services(&inner, request).await
+                                <T as FungiDaemon>::remote_list_service_accesses(&inner, request)
+                                    .await
                             };
                             Box::pin(fut)
                         }
@@ -3787,7 +5902,7 @@ pub mod fungi_daemon_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = RemoteListServicesSvc(inner);
+                        let method = RemoteListServiceAccessesSvc(inner);
                         let codec = tonic_prost::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -3803,22 +5918,25 @@ pub mod fungi_daemon_server {
                     };
                     Box::pin(fut)
                 }
-                "/fungi_daemon.FungiDaemon/AttachServiceAccess" => {
+                "/fungi_daemon.FungiDaemon/SetServiceAccessBandwidthLimit" => {
                     #[allow(non_camel_case_types)]
-                    struct AttachServiceAccessSvc<T: FungiDaemon>(pub Arc<T>);
+                    struct SetServiceAccessBandwidthLimitSvc<T: FungiDaemon>(pub Arc<T>);
                     impl<T: FungiDaemon>
-                        tonic::server::UnaryService<super::AttachServiceAccessRequest>
-                        for AttachServiceAccessSvc<T>
+                        tonic::server::UnaryService<super::SetServiceAccessBandwidthLimitRequest>
+                        for SetServiceAccessBandwidthLimitSvc<T>
                     {
-                        type Response = super::ServiceAccessResponse;
+                        type Response = super::Empty;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::AttachServiceAccessRequest>,
+                            request: tonic::Request<super::SetServiceAccessBandwidthLimitRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as FungiDaemon>::attach_service_access(&inner, request).await
+                                <T as FungiDaemon>::set_service_access_bandwidth_limit(
+                                    &inner, request,
+                                )
+                                .await
                             };
                             Box::pin(fut)
                         }
@@ -3829,7 +5947,7 @@ pub mod fungi_daemon_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = AttachServiceAccessSvc(inner);
+                        let method = SetServiceAccessBandwidthLimitSvc(inner);
                         let codec = tonic_prost::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -3845,22 +5963,22 @@ pub mod fungi_daemon_server {
                     };
                     Box::pin(fut)
                 }
-                "/fungi_daemon.FungiDaemon/DetachServiceAccess" => {
+                "/fungi_daemon.FungiDaemon/SetServiceAccessGroup" => {
                     #[allow(non_camel_case_types)]
-                    struct DetachServiceAccessSvc<T: FungiDaemon>(pub Arc<T>);
+                    struct SetServiceAccessGroupSvc<T: FungiDaemon>(pub Arc<T>);
                     impl<T: FungiDaemon>
-                        tonic::server::UnaryService<super::DetachServiceAccessRequest>
-                        for DetachServiceAccessSvc<T>
+                        tonic::server::UnaryService<super::SetServiceAccessGroupRequest>
+                        for SetServiceAccessGroupSvc<T>
                     {
-                        type Response = super::Empty;
+                        type Response = super::ServiceAccessResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::DetachServiceAccessRequest>,
+                            request: tonic::Request<super::SetServiceAccessGroupRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as FungiDaemon>::detach_service_access(&inner, request).await
+                                <T as FungiDaemon>::set_service_access_group(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -3871,7 +5989,7 @@ pub mod fungi_daemon_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = DetachServiceAccessSvc(inner);
+                        let method = SetServiceAccessGroupSvc(inner);
                         let codec = tonic_prost::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -3887,22 +6005,25 @@ pub mod fungi_daemon_server {
                     };
                     Box::pin(fut)
                 }
-                "/fungi_daemon.FungiDaemon/ForgetServiceAccess" => {
+                "/fungi_daemon.FungiDaemon/SetServicePortBandwidthLimit" => {
                     #[allow(non_camel_case_types)]
-                    struct ForgetServiceAccessSvc<T: FungiDaemon>(pub Arc<T>);
+                    struct SetServicePortBandwidthLimitSvc<T: FungiDaemon>(pub Arc<T>);
                     impl<T: FungiDaemon>
-                        tonic::server::UnaryService<super::ForgetServiceAccessRequest>
-                        for ForgetServiceAccessSvc<T>
+                        tonic::server::UnaryService<super::SetServicePortBandwidthLimitRequest>
+                        for SetServicePortBandwidthLimitSvc<T>
                     {
                         type Response = super::Empty;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::ForgetServiceAccessRequest>,
+                            request: tonic::Request<super::SetServicePortBandwidthLimitRequest>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as FungiDaemon>::forget_service_access(&inner, request).await
+                                <T as FungiDaemon>::set_service_port_bandwidth_limit(
+                                    &inner, request,
+                                )
+                                .await
                             };
                             Box::pin(fut)
                         }
@@ -3913,7 +6034,7 @@ pub mod fungi_daemon_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = ForgetServiceAccessSvc(inner);
+                        let method = SetServicePortBandwidthLimitSvc(inner);
                         let codec = tonic_prost::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -3929,22 +6050,17 @@ pub mod fungi_daemon_server {
                     };
                     Box::pin(fut)
                 }
-                "/fungi_daemon.FungiDaemon/ListServiceAccesses" => {
+                "/fungi_daemon.FungiDaemon/ListHttpGatewayServices" => {
                     #[allow(non_camel_case_types)]
-                    struct ListServiceAccessesSvc<T: FungiDaemon>(pub Arc<T>);
-                    impl<T: FungiDaemon>
-                        tonic::server::UnaryService<super::ListServiceAccessesRequest>
-                        for ListServiceAccessesSvc<T>
-                    {
-                        type Response = super::ServiceAccessesResponse;
+                    struct ListHttpGatewayServicesSvc<T: FungiDaemon>(pub Arc<T>);
+                    impl<T: FungiDaemon> tonic::server::UnaryService<super::Empty> for ListHttpGatewayServicesSvc<T> {
+                        type Response = super::HttpGatewayServicesResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
-                        fn call(
-                            &mut self,
-                            request: tonic::Request<super::ListServiceAccessesRequest>,
-                        ) -> Self::Future {
+                        fn call(&mut self, request: tonic::Request<super::Empty>) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as FungiDaemon>::list_service_accesses(&inner, request).await
+                                <T as FungiDaemon>::list_http_gateway_services(&inner, request)
+                                    .await
                             };
                             Box::pin(fut)
                         }
@@ -3955,7 +6071,7 @@ pub mod fungi_daemon_server {
                     let max_encoding_message_size = self.max_encoding_message_size;
                     let inner = self.inner.clone();
                     let fut = async move {
-                        let method = ListServiceAccessesSvc(inner);
+                        let method = ListHttpGatewayServicesSvc(inner);
                         let codec = tonic_prost::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -4,7 +4,7 @@ pub mod fungi_daemon_grpc {
     pub use crate::generated::*;
 }
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::pin::Pin;
@@ -14,6 +14,7 @@ use std::time::Duration;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use fungi_config::RelayAddressSource;
+use fungi_config::tcp_tunneling::ServiceRouting;
 use fungi_daemon_grpc::fungi_daemon_server::FungiDaemon;
 use fungi_daemon_grpc::*;
 use fungi_swarm::ConnectionDirection;
@@ -58,11 +59,80 @@ fn ping_event(
     }
 }
 
+fn pull_progress_event(progress: fungi_daemon::ImagePullProgress) -> PullServiceEvent {
+    PullServiceEvent {
+        event: Some(pull_service_event::Event::Progress(ImagePullProgress {
+            image: progress.image,
+            layer_id: progress.layer_id,
+            status: progress.status,
+            progress: progress.progress,
+            current: progress.current,
+            total: progress.total,
+        })),
+    }
+}
+
+fn exec_size(rows: u32, cols: u32) -> Option<fungi_daemon::ServiceExecSize> {
+    Some(fungi_daemon::ServiceExecSize {
+        rows: u16::try_from(rows).ok().filter(|rows| *rows > 0)?,
+        cols: u16::try_from(cols).ok().filter(|cols| *cols > 0)?,
+    })
+}
+
+fn exec_input(input: service_exec_input::Input) -> Option<fungi_daemon::ServiceExecInput> {
+    match input {
+        service_exec_input::Input::Stdin(data) => Some(fungi_daemon::ServiceExecInput::Stdin(data)),
+        service_exec_input::Input::Resize(size) => {
+            exec_size(size.rows, size.cols).map(fungi_daemon::ServiceExecInput::Resize)
+        }
+        service_exec_input::Input::CloseStdin(true) => {
+            Some(fungi_daemon::ServiceExecInput::CloseStdin)
+        }
+        service_exec_input::Input::CloseStdin(false) | service_exec_input::Input::Start(_) => None,
+    }
+}
+
+fn exec_output(output: fungi_daemon::ServiceExecOutput) -> ServiceExecOutput {
+    let output = match output {
+        fungi_daemon::ServiceExecOutput::Stdout(data) => service_exec_output::Output::Stdout(data),
+        fungi_daemon::ServiceExecOutput::Stderr(data) => service_exec_output::Output::Stderr(data),
+        fungi_daemon::ServiceExecOutput::Exit(exit) => {
+            service_exec_output::Output::Exit(ServiceExecExit {
+                has_exit_code: exit.exit_code.is_some(),
+                exit_code: exit.exit_code.unwrap_or_default(),
+                error: exit.error.unwrap_or_default(),
+            })
+        }
+    };
+    ServiceExecOutput {
+        output: Some(output),
+    }
+}
+
+fn runtime_availability_status(
+    status: fungi_daemon::LocalRuntimeAvailability,
+) -> RuntimeAvailabilityStatus {
+    let (engine, engine_version, rootless) = match status.engine {
+        Some(engine) => (engine.flavor.to_string(), engine.version, engine.rootless),
+        None => (String::new(), String::new(), false),
+    };
+    RuntimeAvailabilityStatus {
+        config_enabled: status.config_enabled,
+        detected: status.detected,
+        active: status.active,
+        endpoint: status.endpoint.unwrap_or_default(),
+        engine,
+        engine_version,
+        rootless,
+    }
+}
+
 fn proto_runtime_kind(kind: i32) -> Result<Option<fungi_daemon::RuntimeKind>, Status> {
     match ServiceRuntimeKind::try_from(kind) {
         Ok(ServiceRuntimeKind::Unspecified) => Ok(None),
         Ok(ServiceRuntimeKind::Docker) => Ok(Some(fungi_daemon::RuntimeKind::Docker)),
         Ok(ServiceRuntimeKind::Wasmtime) => Ok(Some(fungi_daemon::RuntimeKind::Wasmtime)),
+        Ok(ServiceRuntimeKind::Process) => Ok(Some(fungi_daemon::RuntimeKind::Process)),
         _ => Err(Status::invalid_argument("Invalid runtime kind")),
     }
 }
@@ -102,6 +172,24 @@ fn proto_recipe_detail(detail: fungi_daemon::ServiceRecipeDetail) -> RecipeDetai
     }
 }
 
+fn proto_service_backup(info: fungi_daemon::ServiceBackupInfo) -> ServiceBackupInfo {
+    ServiceBackupInfo {
+        service: info.service,
+        backup_id: info.backup_id,
+        created_at: info.created_at,
+        size_bytes: info.size_bytes,
+    }
+}
+
+fn optional_peer_id(peer_id: &str) -> Result<Option<PeerId>, Status> {
+    if peer_id.trim().is_empty() {
+        return Ok(None);
+    }
+    PeerId::from_str(peer_id)
+        .map(Some)
+        .map_err(|e| Status::invalid_argument(format!("Invalid peer_id: {}", e)))
+}
+
 impl PingPeerError {
     fn new(
         connection_id: impl Into<String>,
@@ -162,6 +250,10 @@ impl FungiDaemonRpcImpl {
 impl FungiDaemon for FungiDaemonRpcImpl {
     type PingPeerStream =
         Pin<Box<dyn tokio_stream::Stream<Item = Result<PingPeerEvent, Status>> + Send>>;
+    type PullServiceWithProgressStream =
+        Pin<Box<dyn tokio_stream::Stream<Item = Result<PullServiceEvent, Status>> + Send>>;
+    type ExecServiceStream =
+        Pin<Box<dyn tokio_stream::Stream<Item = Result<ServiceExecOutput, Status>> + Send>>;
 
     async fn version(&self, _request: Request<Empty>) -> Result<Response<VersionResponse>, Status> {
         Ok(Response::new(VersionResponse {
@@ -341,6 +433,7 @@ impl FungiDaemon for FungiDaemonRpcImpl {
         Ok(Response::new(RuntimeConfigResponse {
             disable_docker: config.disable_docker,
             disable_wasmtime: config.disable_wasmtime,
+            disable_process: config.disable_process,
             allowed_host_paths: config
                 .effective_allowed_host_paths(&fungi_dir)
                 .into_iter()
@@ -355,18 +448,9 @@ impl FungiDaemon for FungiDaemonRpcImpl {
     ) -> Result<Response<LocalRuntimeStatusResponse>, Status> {
         let status = self.inner.local_runtime_status();
         Ok(Response::new(LocalRuntimeStatusResponse {
-            docker: Some(RuntimeAvailabilityStatus {
-                config_enabled: status.docker.config_enabled,
-                detected: status.docker.detected,
-                active: status.docker.active,
-                endpoint: status.docker.endpoint.unwrap_or_default(),
-            }),
-            wasmtime: Some(RuntimeAvailabilityStatus {
-                config_enabled: status.wasmtime.config_enabled,
-                detected: status.wasmtime.detected,
-                active: status.wasmtime.active,
-                endpoint: status.wasmtime.endpoint.unwrap_or_default(),
-            }),
+            docker: Some(runtime_availability_status(status.docker)),
+            wasmtime: Some(runtime_availability_status(status.wasmtime)),
+            process: Some(runtime_availability_status(status.process)),
         }))
     }
 
@@ -396,6 +480,49 @@ impl FungiDaemon for FungiDaemonRpcImpl {
         Ok(Response::new(Empty {}))
     }
 
+    async fn set_secret(
+        &self,
+        request: Request<SetSecretRequest>,
+    ) -> Result<Response<SecretInfo>, Status> {
+        let req = request.into_inner();
+        let secret = self
+            .inner
+            .set_secret(&req.name, &req.value)
+            .map_err(|e| Status::invalid_argument(format!("Failed to set secret: {e:#}")))?;
+        Ok(Response::new(SecretInfo {
+            name: secret.name,
+            updated_at: secret.updated_at,
+        }))
+    }
+
+    async fn list_secrets(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<ListSecretsResponse>, Status> {
+        let secrets = self
+            .inner
+            .list_secrets()
+            .map_err(|e| Status::internal(format!("Failed to list secrets: {e:#}")))?
+            .into_iter()
+            .map(|secret| SecretInfo {
+                name: secret.name,
+                updated_at: secret.updated_at,
+            })
+            .collect();
+        Ok(Response::new(ListSecretsResponse { secrets }))
+    }
+
+    async fn remove_secret(
+        &self,
+        request: Request<SecretNameRequest>,
+    ) -> Result<Response<RemoveSecretResponse>, Status> {
+        let removed = self
+            .inner
+            .remove_secret(&request.into_inner().name)
+            .map_err(|e| Status::internal(format!("Failed to remove secret: {e:#}")))?;
+        Ok(Response::new(RemoveSecretResponse { removed }))
+    }
+
     async fn list_mdns_devices(
         &self,
         _request: Request<Empty>,
@@ -478,6 +605,35 @@ impl FungiDaemon for FungiDaemonRpcImpl {
         Ok(Response::new(Empty {}))
     }
 
+    async fn wake_device(
+        &self,
+        request: Request<WakeDeviceRequest>,
+    ) -> Result<Response<WakeDeviceResponse>, Status> {
+        let req = request.into_inner();
+        let peer_id = PeerId::from_str(&req.peer_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid peer_id: {}", e)))?;
+        let report = self
+            .inner
+            .wake_device(peer_id, req.wait)
+            .await
+            .map_err(|e| Status::internal(format!("Failed to wake device: {e}")))?;
+
+        Ok(Response::new(WakeDeviceResponse {
+            peer_id: report.peer_id,
+            mac_addresses: report.mac_addresses,
+            attempts: report
+                .attempts
+                .into_iter()
+                .map(|attempt| WakeAttempt {
+                    relay_peer_id: attempt.relay_peer_id.unwrap_or_default(),
+                    relay_name: attempt.relay_name.unwrap_or_default(),
+                    error: attempt.error.unwrap_or_default(),
+                })
+                .collect(),
+            online: report.online,
+        }))
+    }
+
     async fn ping_peer(
         &self,
         request: Request<PingPeerRequest>,
@@ -811,6 +967,38 @@ impl FungiDaemon for FungiDaemonRpcImpl {
         Ok(Response::new(ListPeerAddressesResponse { addresses }))
     }
 
+    async fn diagnose(
+        &self,
+        request: Request<DiagnoseRequest>,
+    ) -> Result<Response<DiagnoseResponse>, Status> {
+        let req = request.into_inner();
+        let peer_id = PeerId::from_str(&req.peer_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid peer_id: {}", e)))?;
+        let report = self
+            .inner
+            .diagnose_peer(peer_id)
+            .await
+            .map_err(|e| Status::internal(format!("Failed to diagnose peer: {e}")))?;
+
+        Ok(Response::new(DiagnoseResponse {
+            peer_id: report.peer_id,
+            peer_name: report.peer_name,
+            checks: report
+                .checks
+                .into_iter()
+                .map(|check| DiagnosticCheck {
+                    name: check.name,
+                    status: check.status.as_str().to_string(),
+                    summary: check.summary,
+                    details: check.details,
+                })
+                .collect(),
+            nat_type: report.nat_kind.as_str().to_string(),
+            verdict: report.verdict.as_str().to_string(),
+            remediation: report.remediation,
+        }))
+    }
+
     async fn pull_service(
         &self,
         request: Request<PullServiceRequest>,
@@ -834,6 +1022,81 @@ impl FungiDaemon for FungiDaemonRpcImpl {
         Ok(Response::new(ServiceInstanceResponse { instance_json }))
     }
 
+    async fn pull_service_with_progress(
+        &self,
+        request: Request<PullServiceRequest>,
+    ) -> Result<Response<Self::PullServiceWithProgressStream>, Status> {
+        let req = request.into_inner();
+        let manifest_base_dir = if req.manifest_base_dir.trim().is_empty() {
+            None
+        } else {
+            Some(std::path::PathBuf::from(req.manifest_base_dir))
+        };
+
+        let daemon = self.inner.clone();
+        let (tx, rx) = mpsc::channel::<Result<PullServiceEvent, Status>>(128);
+        tokio::spawn(async move {
+            let (progress_tx, mut progress_rx) = mpsc::unbounded_channel();
+            let pull = daemon.pull_service_from_manifest_yaml_with_progress(
+                req.manifest_yaml,
+                manifest_base_dir,
+                progress_tx,
+            );
+            tokio::pin!(pull);
+            let result = loop {
+                tokio::select! {
+                    result = &mut pull => break result,
+                    Some(progress) = progress_rx.recv() => {
+                        let _ = tx.send(Ok(pull_progress_event(progress))).await;
+                    }
+                }
+            };
+            while let Ok(progress) = progress_rx.try_recv() {
+                let _ = tx.send(Ok(pull_progress_event(progress))).await;
+            }
+
+            let event = result
+                .map_err(|e| Status::internal(format!("Failed to pull service: {e}")))
+                .and_then(|instance| {
+                    serde_json::to_string(&instance).map_err(|e| {
+                        Status::internal(format!("Failed to serialize service instance: {e}"))
+                    })
+                })
+                .map(|instance_json| PullServiceEvent {
+                    event: Some(pull_service_event::Event::Instance(
+                        ServiceInstanceResponse { instance_json },
+                    )),
+                });
+            let _ = tx.send(event).await;
+        });
+
+        Ok(Response::new(
+            Box::pin(ReceiverStream::new(rx)) as Self::PullServiceWithProgressStream
+        ))
+    }
+
+    async fn prune_service_images(
+        &self,
+        request: Request<PruneServiceImagesRequest>,
+    ) -> Result<Response<PruneServiceImagesResponse>, Status> {
+        let req = request.into_inner();
+        let images = self
+            .inner
+            .prune_service_images(req.dry_run)
+            .await
+            .map_err(|e| Status::internal(format!("Failed to prune service images: {e}")))?;
+        Ok(Response::new(PruneServiceImagesResponse {
+            images: images
+                .into_iter()
+                .map(|image| PrunedImage {
+                    image: image.image,
+                    size_bytes: image.size_bytes,
+                })
+                .collect(),
+            dry_run: req.dry_run,
+        }))
+    }
+
     async fn start_service(
         &self,
         request: Request<ServiceNameRequest>,
@@ -920,6 +1183,98 @@ impl FungiDaemon for FungiDaemonRpcImpl {
         Ok(Response::new(ServiceInstanceResponse { instance_json }))
     }
 
+    async fn service_history(
+        &self,
+        request: Request<ServiceNameRequest>,
+    ) -> Result<Response<ServiceHistoryResponse>, Status> {
+        let req = request.into_inner();
+        let revisions = self
+            .inner
+            .service_history(&req.name)
+            .map_err(|e| Status::internal(format!("Failed to read service history: {e}")))?;
+        let revisions_json = serde_json::to_string(&revisions)
+            .map_err(|e| Status::internal(format!("Failed to serialize service history: {e}")))?;
+        Ok(Response::new(ServiceHistoryResponse { revisions_json }))
+    }
+
+    async fn run_service_job(
+        &self,
+        request: Request<ServiceNameRequest>,
+    ) -> Result<Response<ServiceJobResponse>, Status> {
+        let req = request.into_inner();
+        let run = self
+            .inner
+            .run_service_job(req.name)
+            .await
+            .map_err(|e| Status::internal(format!("Failed to run job: {e}")))?;
+        let job_json = serde_json::to_string(&run)
+            .map_err(|e| Status::internal(format!("Failed to serialize job run: {e}")))?;
+        Ok(Response::new(ServiceJobResponse { job_json }))
+    }
+
+    async fn exec_service(
+        &self,
+        request: Request<tonic::Streaming<ServiceExecInput>>,
+    ) -> Result<Response<Self::ExecServiceStream>, Status> {
+        let mut inputs = request.into_inner();
+        let Some(service_exec_input::Input::Start(start)) =
+            inputs.message().await?.and_then(|message| message.input)
+        else {
+            return Err(Status::invalid_argument(
+                "exec stream must begin with a start message",
+            ));
+        };
+
+        let exec_request = fungi_daemon::ServiceExecRequest {
+            service: start.name,
+            command: start.command,
+            tty: start.tty,
+            size: exec_size(start.rows, start.cols),
+        };
+        let session = if start.peer_id.trim().is_empty() {
+            self.inner.exec_service(exec_request).await
+        } else {
+            let peer_id = PeerId::from_str(&start.peer_id)
+                .map_err(|e| Status::invalid_argument(format!("Invalid peer_id: {}", e)))?;
+            self.inner.remote_exec_service(peer_id, exec_request).await
+        }
+        .map_err(|e| Status::internal(format!("Failed to exec into service: {e}")))?;
+
+        let fungi_daemon::ServiceExecSession { input, output } = session;
+        tokio::spawn(async move {
+            // Ending the request stream closes the command's stdin.
+            while let Ok(Some(message)) = inputs.message().await {
+                let Some(message) = message.input.and_then(exec_input) else {
+                    continue;
+                };
+                if input.send(message).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        // Dropping the response stream drops the session output, which ends the command.
+        let stream = tokio_stream::StreamExt::map(ReceiverStream::new(output), |message| {
+            Ok(exec_output(message))
+        });
+        Ok(Response::new(Box::pin(stream) as Self::ExecServiceStream))
+    }
+
+    async fn rollback_service(
+        &self,
+        request: Request<RollbackServiceRequest>,
+    ) -> Result<Response<ServiceInstanceResponse>, Status> {
+        let req = request.into_inner();
+        let instance = self
+            .inner
+            .rollback_service(req.name, (req.revision > 0).then_some(req.revision))
+            .await
+            .map_err(|e| Status::internal(format!("Failed to roll back service: {e}")))?;
+        let instance_json = serde_json::to_string(&instance)
+            .map_err(|e| Status::internal(format!("Failed to serialize service instance: {e}")))?;
+        Ok(Response::new(ServiceInstanceResponse { instance_json }))
+    }
+
     async fn get_service_logs(
         &self,
         request: Request<GetServiceLogsRequest>,
@@ -1012,9 +1367,10 @@ impl FungiDaemon for FungiDaemonRpcImpl {
         } else {
             Some(req.service_name.as_str())
         };
+        let inputs = req.inputs.into_iter().collect::<BTreeMap<_, _>>();
         let resolved = self
             .inner
-            .resolve_service_recipe(&req.recipe_id, service_name, peer_id, req.refresh)
+            .resolve_service_recipe(&req.recipe_id, service_name, &inputs, peer_id, req.refresh)
             .await
             .map_err(|e| Status::internal(format!("Failed to resolve recipe: {e}")))?;
         Ok(Response::new(ResolveRecipeResponse {
@@ -1114,6 +1470,87 @@ impl FungiDaemon for FungiDaemonRpcImpl {
         }))
     }
 
+    async fn remote_service_history(
+        &self,
+        request: Request<RemoteServiceNameRequest>,
+    ) -> Result<Response<ServiceHistoryResponse>, Status> {
+        let req = request.into_inner();
+        let peer_id = PeerId::from_str(&req.peer_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid peer_id: {}", e)))?;
+
+        let revisions = self
+            .inner
+            .remote_service_history(peer_id, req.name)
+            .await
+            .map_err(|e| Status::internal(format!("Failed to read remote service history: {e}")))?;
+        let revisions_json = serde_json::to_string(&revisions)
+            .map_err(|e| Status::internal(format!("Failed to serialize service history: {e}")))?;
+        Ok(Response::new(ServiceHistoryResponse { revisions_json }))
+    }
+
+    async fn remote_run_service_job(
+        &self,
+        request: Request<RemoteServiceNameRequest>,
+    ) -> Result<Response<ServiceJobResponse>, Status> {
+        let req = request.into_inner();
+        let peer_id = PeerId::from_str(&req.peer_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid peer_id: {}", e)))?;
+
+        let run = self
+            .inner
+            .remote_run_service_job(peer_id, req.name)
+            .await
+            .map_err(|e| Status::internal(format!("Failed to run remote job: {e}")))?;
+        let job_json = serde_json::to_string(&run)
+            .map_err(|e| Status::internal(format!("Failed to serialize job run: {e}")))?;
+        Ok(Response::new(ServiceJobResponse { job_json }))
+    }
+
+    async fn remote_service_job_status(
+        &self,
+        request: Request<RemoteServiceNameRequest>,
+    ) -> Result<Response<ServiceJobResponse>, Status> {
+        let req = request.into_inner();
+        let peer_id = PeerId::from_str(&req.peer_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid peer_id: {}", e)))?;
+
+        let status = self
+            .inner
+            .remote_service_job_status(peer_id, req.name)
+            .await
+            .map_err(|e| Status::internal(format!("Failed to read remote job status: {e}")))?;
+        let job_json = serde_json::to_string(&status)
+            .map_err(|e| Status::internal(format!("Failed to serialize job status: {e}")))?;
+        Ok(Response::new(ServiceJobResponse { job_json }))
+    }
+
+    async fn remote_rollback_service(
+        &self,
+        request: Request<RemoteRollbackServiceRequest>,
+    ) -> Result<Response<RemoteServiceControlResponse>, Status> {
+        let req = request.into_inner();
+        let peer_id = PeerId::from_str(&req.peer_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid peer_id: {}", e)))?;
+
+        let response = self
+            .inner
+            .remote_rollback_service(
+                peer_id,
+                req.name,
+                (req.revision > 0).then_some(req.revision),
+            )
+            .await
+            .map_err(|e| Status::internal(format!("Failed to roll back remote service: {e}")))?;
+
+        Ok(Response::new(RemoteServiceControlResponse {
+            service_name: response
+                .service
+                .map(|service| service.name)
+                .unwrap_or_default(),
+            forgotten_locally: response.forgotten_locally,
+        }))
+    }
+
     async fn remote_stop_service(
         &self,
         request: Request<RemoteServiceNameRequest>,
@@ -1160,6 +1597,87 @@ impl FungiDaemon for FungiDaemonRpcImpl {
         }))
     }
 
+    async fn move_service(
+        &self,
+        request: Request<MoveServiceRequest>,
+    ) -> Result<Response<MoveServiceResponse>, Status> {
+        let req = request.into_inner();
+        let local_peer_id = self.inner.swarm_control().local_peer_id();
+        let device = |peer_id: &str| {
+            if peer_id.trim().is_empty() {
+                return Ok(local_peer_id);
+            }
+            PeerId::from_str(peer_id)
+                .map_err(|e| Status::invalid_argument(format!("Invalid peer_id: {}", e)))
+        };
+        let from = device(&req.from_peer_id)?;
+        let to = device(&req.to_peer_id)?;
+
+        let unreachable = self
+            .inner
+            .move_service(req.name, from, to, req.clone)
+            .await
+            .map_err(|e| Status::internal(format!("Failed to move service: {e:#}")))?;
+
+        Ok(Response::new(MoveServiceResponse {
+            unreachable_peer_ids: unreachable
+                .into_iter()
+                .map(|peer_id| peer_id.to_string())
+                .collect(),
+        }))
+    }
+
+    async fn backup_service(
+        &self,
+        request: Request<BackupServiceRequest>,
+    ) -> Result<Response<ServiceBackupInfo>, Status> {
+        let req = request.into_inner();
+        let options = fungi_daemon::ServiceBackupOptions {
+            stop: req.stop,
+            pre_backup_hook: req.pre_backup_hook,
+            target: optional_peer_id(&req.target_peer_id)?,
+        };
+        let info = self
+            .inner
+            .backup_service(req.name, options)
+            .await
+            .map_err(|e| Status::internal(format!("Failed to back up service: {e:#}")))?;
+        Ok(Response::new(proto_service_backup(info)))
+    }
+
+    async fn list_service_backups(
+        &self,
+        request: Request<ListServiceBackupsRequest>,
+    ) -> Result<Response<ListServiceBackupsResponse>, Status> {
+        let req = request.into_inner();
+        let backups = self
+            .inner
+            .list_service_backups(empty_to_none(req.name), optional_peer_id(&req.peer_id)?)
+            .await
+            .map_err(|e| Status::internal(format!("Failed to list service backups: {e:#}")))?;
+        Ok(Response::new(ListServiceBackupsResponse {
+            backups: backups.into_iter().map(proto_service_backup).collect(),
+        }))
+    }
+
+    async fn restore_service_backup(
+        &self,
+        request: Request<RestoreServiceBackupRequest>,
+    ) -> Result<Response<ServiceBackupInfo>, Status> {
+        let req = request.into_inner();
+        let info = self
+            .inner
+            .restore_service_backup(
+                req.name,
+                optional_peer_id(&req.peer_id)?,
+                empty_to_none(req.backup_id),
+                req.start,
+            )
+            .await
+            .map_err(|e| Status::internal(format!("Failed to restore service backup: {e:#}")))?;
+        Ok(Response::new(proto_service_backup(info)))
+    }
+
     async fn forget_device_service(
         &self,
         request: Request<RemoteServiceNameRequest>,
@@ -1210,6 +1728,17 @@ impl FungiDaemon for FungiDaemonRpcImpl {
         let peer_id = PeerId::from_str(&req.peer_id)
             .map_err(|e| Status::invalid_argument(format!("Invalid peer_id: {}", e)))?;
 
+        if req.wake {
+            self.inner
+                .ensure_device_awake(
+                    peer_id,
+                    (req.wake_timeout_secs > 0)
+                        .then(|| Duration::from_secs(req.wake_timeout_secs.into())),
+                )
+                .await
+                .map_err(|e| Status::unavailable(format!("Failed to wake device: {e}")))?;
+        }
+
         let service_access = self
             .inner
             .attach_service_access(
@@ -1264,6 +1793,193 @@ impl FungiDaemon for FungiDaemonRpcImpl {
         Ok(Response::new(Empty {}))
     }
 
+    async fn remote_attach_service_access(
+        &self,
+        request: Request<RemoteAttachServiceAccessRequest>,
+    ) -> Result<Response<RemoteServiceControlResponse>, Status> {
+        let req = request.into_inner();
+        let peer_id = PeerId::from_str(&req.peer_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid peer_id: {}", e)))?;
+        let publisher_peer_id = PeerId::from_str(&req.publisher_peer_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid publisher_peer_id: {}", e)))?;
+
+        let response = self
+            .inner
+            .remote_attach_service_access(
+                peer_id,
+                publisher_peer_id,
+                req.service_name,
+                empty_to_none(req.entry),
+                if req.local_port > 0 {
+                    Some(req.local_port as u16)
+                } else {
+                    None
+                },
+            )
+            .await
+            .map_err(|e| {
+                Status::internal(format!("Failed to attach service access on remote: {e}"))
+            })?;
+
+        Ok(Response::new(RemoteServiceControlResponse {
+            service_name: response
+                .service
+                .map(|service| service.name)
+                .unwrap_or_default(),
+            forgotten_locally: response.forgotten_locally,
+        }))
+    }
+
+    async fn remote_forget_service_access(
+        &self,
+        request: Request<RemoteForgetServiceAccessRequest>,
+    ) -> Result<Response<RemoteServiceControlResponse>, Status> {
+        let req = request.into_inner();
+        let peer_id = PeerId::from_str(&req.peer_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid peer_id: {}", e)))?;
+        let publisher_peer_id = PeerId::from_str(&req.publisher_peer_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid publisher_peer_id: {}", e)))?;
+
+        let response = self
+            .inner
+            .remote_forget_service_access(peer_id, publisher_peer_id, req.service_name)
+            .await
+            .map_err(|e| {
+                Status::internal(format!("Failed to forget service access on remote: {e}"))
+            })?;
+
+        Ok(Response::new(RemoteServiceControlResponse {
+            service_name: response
+                .service
+                .map(|service| service.name)
+                .unwrap_or_default(),
+            forgotten_locally: response.forgotten_locally,
+        }))
+    }
+
+    async fn remote_list_service_accesses(
+        &self,
+        request: Request<RemotePeerRequest>,
+    ) -> Result<Response<ServiceAccessesResponse>, Status> {
+        let req = request.into_inner();
+        let peer_id = PeerId::from_str(&req.peer_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid peer_id: {}", e)))?;
+
+        let service_accesses = self
+            .inner
+            .remote_list_service_accesses(peer_id)
+            .await
+            .map_err(|e| {
+                Status::internal(format!("Failed to list service accesses on remote: {e}"))
+            })?;
+        let service_accesses_json = serde_json::to_string(&service_accesses)
+            .map_err(|e| Status::internal(format!("Failed to serialize service accesses: {e}")))?;
+
+        Ok(Response::new(ServiceAccessesResponse {
+            service_accesses_json,
+        }))
+    }
+
+    async fn set_service_access_bandwidth_limit(
+        &self,
+        request: Request<SetServiceAccessBandwidthLimitRequest>,
+    ) -> Result<Response<Empty>, Status> {
+        let req = request.into_inner();
+        let peer_id = PeerId::from_str(&req.peer_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid peer_id: {}", e)))?;
+
+        self.inner
+            .set_service_access_bandwidth_limit(
+                peer_id,
+                req.service_name,
+                empty_to_none(req.entry),
+                bandwidth_limit_from_proto(req.limit),
+            )
+            .await
+            .map_err(|e| {
+                Status::internal(format!("Failed to set service access bandwidth limit: {e}"))
+            })?;
+
+        Ok(Response::new(Empty {}))
+    }
+
+    async fn set_service_access_group(
+        &self,
+        request: Request<SetServiceAccessGroupRequest>,
+    ) -> Result<Response<ServiceAccessResponse>, Status> {
+        let req = request.into_inner();
+        let peer_id = PeerId::from_str(&req.peer_id)
+            .map_err(|e| Status::invalid_argument(format!("Invalid peer_id: {}", e)))?;
+        let members = req
+            .member_peer_ids
+            .iter()
+            .map(|member| PeerId::from_str(member))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| Status::invalid_argument(format!("Invalid member peer_id: {}", e)))?;
+        let routing = if req.round_robin {
+            ServiceRouting::RoundRobin
+        } else {
+            ServiceRouting::Failover
+        };
+
+        let service_access = self
+            .inner
+            .set_service_access_group(peer_id, req.service_name, members, routing)
+            .await
+            .map_err(|e| Status::internal(format!("Failed to set service access group: {e}")))?;
+
+        let service_access_json = serde_json::to_string(&service_access)
+            .map_err(|e| Status::internal(format!("Failed to serialize service access: {e}")))?;
+
+        Ok(Response::new(ServiceAccessResponse {
+            service_access_json,
+        }))
+    }
+
+    async fn set_service_port_bandwidth_limit(
+        &self,
+        request: Request<SetServicePortBandwidthLimitRequest>,
+    ) -> Result<Response<Empty>, Status> {
+        let req = request.into_inner();
+
+        self.inner
+            .set_service_port_bandwidth_limit(
+                &req.service_name,
+                &req.port_name,
+                bandwidth_limit_from_proto(req.limit),
+            )
+            .map_err(|e| {
+                Status::internal(format!("Failed to set service port bandwidth limit: {e}"))
+            })?;
+
+        Ok(Response::new(Empty {}))
+    }
+
+    async fn list_http_gateway_services(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<HttpGatewayServicesResponse>, Status> {
+        let listing = self
+            .inner
+            .list_http_gateway_services()
+            .map_err(|e| Status::failed_precondition(e.to_string()))?;
+
+        Ok(Response::new(HttpGatewayServicesResponse {
+            index_url: listing.index_url,
+            services: listing
+                .services
+                .into_iter()
+                .map(|service| HttpGatewayService {
+                    peer_id: service.peer_id,
+                    device_name: service.device_name,
+                    service_name: service.service_name,
+                    entry: service.entry,
+                    url: service.url,
+                })
+                .collect(),
+        }))
+    }
+
     async fn list_service_accesses(
         &self,
         request: Request<ListServiceAccessesRequest>,
@@ -1301,6 +2017,18 @@ fn empty_to_none(value: String) -> Option<String> {
 }
 
 // Helper functions to convert between domain and proto types
+
+fn bandwidth_limit_from_proto(
+    limit: Option<BandwidthLimit>,
+) -> fungi_config::bandwidth::BandwidthLimit {
+    let limit = limit.unwrap_or_default();
+    fungi_config::bandwidth::BandwidthLimit {
+        direct_bytes_per_sec: (limit.direct_bytes_per_sec > 0)
+            .then_some(limit.direct_bytes_per_sec),
+        relayed_bytes_per_sec: (limit.relayed_bytes_per_sec > 0)
+            .then_some(limit.relayed_bytes_per_sec),
+    }
+}
 fn device_info_to_proto(info: fungi_config::devices::DeviceInfo) -> DeviceInfo {
     DeviceInfo {
         peer_id: info.peer_id.to_string(),
@@ -1309,6 +2037,7 @@ fn device_info_to_proto(info: fungi_config::devices::DeviceInfo) -> DeviceInfo {
         os: os_to_string(info.os),
         public_ip: info.public_ip.unwrap_or_default(),
         private_ips: info.private_ips,
+        mac_addresses: info.mac_addresses,
         created_at: system_time_to_i64(info.created_at),
         last_connected: system_time_to_i64(info.last_connected),
         version: info.version,
@@ -1340,6 +2069,7 @@ fn proto_to_device_info(proto: DeviceInfo) -> Result<fungi_config::devices::Devi
             Some(proto.public_ip)
         },
         private_ips: proto.private_ips,
+        mac_addresses: proto.mac_addresses,
         created_at: i64_to_system_time(proto.created_at),
         last_connected: i64_to_system_time(proto.last_connected),
         version: proto.version,
@@ -0,0 +1,723 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    net::IpAddr,
+    time::{Duration, SystemTime},
+};
+
+use anyhow::{Result, bail};
+use fungi_swarm::{
+    AddressFreshness, AddressTransportKind, DialProbeOutcome, DirectDialProbe,
+    ExternalAddressCandidateRecord, RelayCircuitProbe, RelayEndpointStatusRecord,
+};
+use libp2p::{Multiaddr, PeerId, multiaddr::Protocol};
+
+use crate::FungiDaemon;
+
+use super::types::{
+    ConnectivityVerdict, DiagnosticCheck, DiagnosticStatus, NatKind, PeerDiagnosticsReport,
+};
+
+const DIRECT_PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+const DIRECT_PROBE_LIMIT: usize = 8;
+const RELAY_PROBE_TIMEOUT: Duration = Duration::from_secs(6);
+const HOLE_PUNCH_TIMEOUT: Duration = Duration::from_secs(5);
+
+impl FungiDaemon {
+    /// Runs local and active connectivity checks against `peer_id`.
+    ///
+    /// Direct candidates and the relay circuit are dialed for real, so the report reflects what
+    /// the swarm can do right now rather than what cached state suggests.
+    pub async fn diagnose_peer(&self, peer_id: PeerId) -> Result<PeerDiagnosticsReport> {
+        let swarm_control = self.swarm_control();
+        if peer_id == swarm_control.local_peer_id() {
+            bail!("Cannot diagnose connectivity to the local device");
+        }
+
+        let state = swarm_control.state();
+        let now = SystemTime::now();
+        let mut checks = Vec::new();
+
+        let listen_addresses = swarm_control.listen_addresses().await;
+        checks.push(listeners_check(&listen_addresses));
+        let listen_addresses = listen_addresses.unwrap_or_default();
+
+        let external_candidates = state.list_external_address_candidates();
+        checks.push(external_addresses_check(&external_candidates, now));
+
+        let nat_kind = infer_nat_kind(&listen_addresses, &external_candidates, now);
+        checks.push(nat_check(nat_kind));
+
+        let relay_enabled = self.relay_enabled();
+        let relay_statuses = state.list_relay_endpoint_statuses();
+        checks.push(relay_reservations_check(relay_enabled, &relay_statuses));
+
+        let direct_probes = swarm_control
+            .probe_direct_dial_candidates(peer_id, DIRECT_PROBE_TIMEOUT, DIRECT_PROBE_LIMIT)
+            .await;
+        checks.push(direct_dial_check(&direct_probes));
+
+        let relay_probe = swarm_control
+            .probe_relay_circuit(peer_id, RELAY_PROBE_TIMEOUT, HOLE_PUNCH_TIMEOUT)
+            .await;
+        checks.push(relay_circuit_check(relay_probe.as_ref()));
+        checks.push(hole_punch_check(relay_probe.as_ref()));
+
+        let verdict = connectivity_verdict(&direct_probes, relay_probe.as_ref());
+        let remediation = remediation_hints(&RemediationInputs {
+            verdict,
+            nat_kind,
+            has_listeners: !listen_addresses.is_empty(),
+            has_direct_candidates: !direct_probes.is_empty(),
+            relay_enabled,
+            relay_ready: relay_statuses.iter().any(relay_status_ready),
+        });
+
+        let peer_name = self
+            .devices_get_peer(peer_id)
+            .and_then(|peer| peer.name)
+            .unwrap_or_default();
+
+        Ok(PeerDiagnosticsReport {
+            peer_id: peer_id.to_string(),
+            peer_name,
+            checks,
+            nat_kind,
+            verdict,
+            remediation,
+        })
+    }
+}
+
+fn check(
+    name: &str,
+    status: DiagnosticStatus,
+    summary: impl Into<String>,
+    details: Vec<String>,
+) -> DiagnosticCheck {
+    DiagnosticCheck {
+        name: name.to_string(),
+        status,
+        summary: summary.into(),
+        details,
+    }
+}
+
+fn listeners_check(listen_addresses: &Result<Vec<Multiaddr>>) -> DiagnosticCheck {
+    match listen_addresses {
+        Ok(addresses) if addresses.is_empty() => check(
+            "local-listeners",
+            DiagnosticStatus::Fail,
+            "daemon is not listening on any address",
+            Vec::new(),
+        ),
+        Ok(addresses) => check(
+            "local-listeners",
+            DiagnosticStatus::Pass,
+            format!("listening on {} address(es)", addresses.len()),
+            addresses.iter().map(ToString::to_string).collect(),
+        ),
+        Err(error) => check(
+            "local-listeners",
+            DiagnosticStatus::Fail,
+            format!("failed to query listeners: {error}"),
+            Vec::new(),
+        ),
+    }
+}
+
+fn external_addresses_check(
+    candidates: &[ExternalAddressCandidateRecord],
+    now: SystemTime,
+) -> DiagnosticCheck {
+    let usable = usable_external_candidates(candidates, now).collect::<Vec<_>>();
+    let details = usable
+        .iter()
+        .map(|candidate| {
+            format!(
+                "{} [{}{}]",
+                candidate.address,
+                candidate.freshness(now).as_str(),
+                if candidate.confirmed_at.is_some() {
+                    ", confirmed"
+                } else {
+                    ""
+                }
+            )
+        })
+        .collect();
+
+    if usable.is_empty() {
+        return check(
+            "external-addresses",
+            DiagnosticStatus::Warn,
+            "no external address has been observed yet",
+            details,
+        );
+    }
+
+    let confirmed = usable
+        .iter()
+        .filter(|candidate| candidate.confirmed_at.is_some())
+        .count();
+    if confirmed > 0 {
+        check(
+            "external-addresses",
+            DiagnosticStatus::Pass,
+            format!("{confirmed} of {} candidate(s) confirmed", usable.len()),
+            details,
+        )
+    } else {
+        check(
+            "external-addresses",
+            DiagnosticStatus::Warn,
+            format!("{} candidate(s) observed, none confirmed", usable.len()),
+            details,
+        )
+    }
+}
+
+fn nat_check(nat_kind: NatKind) -> DiagnosticCheck {
+    let (status, summary) = match nat_kind {
+        NatKind::Public => (DiagnosticStatus::Pass, "publicly reachable"),
+        NatKind::EndpointIndependent => (
+            DiagnosticStatus::Pass,
+            "behind NAT with a stable external port; hole punching should work",
+        ),
+        NatKind::EndpointDependent => (
+            DiagnosticStatus::Warn,
+            "behind NAT that changes the external port per remote; hole punching is unlikely",
+        ),
+        NatKind::Unknown => (
+            DiagnosticStatus::Skip,
+            "not enough external observations to infer NAT behaviour",
+        ),
+    };
+    check(
+        "nat-type",
+        status,
+        format!("{} ({})", nat_kind.as_str(), summary),
+        Vec::new(),
+    )
+}
+
+fn relay_reservations_check(
+    relay_enabled: bool,
+    statuses: &[RelayEndpointStatusRecord],
+) -> DiagnosticCheck {
+    if !relay_enabled {
+        return check(
+            "relay-reservations",
+            DiagnosticStatus::Skip,
+            "relay is disabled",
+            Vec::new(),
+        );
+    }
+    if statuses.is_empty() {
+        return check(
+            "relay-reservations",
+            DiagnosticStatus::Fail,
+            "relay is enabled but no relay endpoints are configured",
+            Vec::new(),
+        );
+    }
+
+    let details = statuses
+        .iter()
+        .map(|status| {
+            let mut line = format!(
+                "{} [{}] listener={} connection={}",
+                status.relay_addr,
+                if relay_status_ready(status) {
+                    "ready"
+                } else {
+                    "not-ready"
+                },
+                status.listener_registered,
+                status.current_direct_connection_id.is_some()
+            );
+            if let Some(error) = &status.last_error {
+                line.push_str(&format!(" last_error={error}"));
+            }
+            line
+        })
+        .collect();
+
+    let ready = statuses
+        .iter()
+        .filter(|status| relay_status_ready(status))
+        .count();
+    if ready > 0 {
+        check(
+            "relay-reservations",
+            DiagnosticStatus::Pass,
+            format!("{ready} of {} relay endpoint(s) ready", statuses.len()),
+            details,
+        )
+    } else {
+        check(
+            "relay-reservations",
+            DiagnosticStatus::Fail,
+            format!(
+                "none of {} relay endpoint(s) hold a reservation",
+                statuses.len()
+            ),
+            details,
+        )
+    }
+}
+
+fn direct_dial_check(probes: &[DirectDialProbe]) -> DiagnosticCheck {
+    if probes.is_empty() {
+        return check(
+            "direct-dial",
+            DiagnosticStatus::Skip,
+            "no direct address is known for this device",
+            Vec::new(),
+        );
+    }
+
+    let details = probes
+        .iter()
+        .map(|probe| {
+            format!(
+                "{} [{}, {}, {}] {} in {}ms",
+                probe.address,
+                probe.transport_kind.as_str(),
+                probe.source.as_str(),
+                probe.freshness.as_str(),
+                describe_outcome(&probe.outcome),
+                probe.elapsed.as_millis()
+            )
+        })
+        .collect();
+    let connected = probes
+        .iter()
+        .filter(|probe| probe.outcome.is_connected())
+        .count();
+
+    if connected > 0 {
+        check(
+            "direct-dial",
+            DiagnosticStatus::Pass,
+            format!("{connected} of {} candidate(s) connected", probes.len()),
+            details,
+        )
+    } else {
+        check(
+            "direct-dial",
+            DiagnosticStatus::Fail,
+            format!("all {} candidate(s) failed", probes.len()),
+            details,
+        )
+    }
+}
+
+fn relay_circuit_check(probe: Option<&RelayCircuitProbe>) -> DiagnosticCheck {
+    let Some(probe) = probe else {
+        return check(
+            "relay-circuit",
+            DiagnosticStatus::Skip,
+            "no relay is configured",
+            Vec::new(),
+        );
+    };
+
+    let details = probe
+        .circuit_addresses
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    let status = if probe.outcome.is_connected() {
+        DiagnosticStatus::Pass
+    } else {
+        DiagnosticStatus::Fail
+    };
+    check(
+        "relay-circuit",
+        status,
+        format!(
+            "{} in {}ms",
+            describe_outcome(&probe.outcome),
+            probe.elapsed.as_millis()
+        ),
+        details,
+    )
+}
+
+fn hole_punch_check(probe: Option<&RelayCircuitProbe>) -> DiagnosticCheck {
+    let Some(probe) = probe.filter(|probe| probe.outcome.is_connected()) else {
+        return check(
+            "hole-punch",
+            DiagnosticStatus::Skip,
+            "no relayed connection to upgrade",
+            Vec::new(),
+        );
+    };
+
+    match &probe.hole_punch {
+        Some(record) if record.succeeded => check(
+            "hole-punch",
+            DiagnosticStatus::Pass,
+            "DCUtR upgraded the relayed connection to a direct one",
+            Vec::new(),
+        ),
+        Some(record) => check(
+            "hole-punch",
+            DiagnosticStatus::Warn,
+            "DCUtR hole punch failed",
+            record.error.iter().cloned().collect(),
+        ),
+        None => check(
+            "hole-punch",
+            DiagnosticStatus::Warn,
+            "DCUtR reported no outcome before the timeout",
+            Vec::new(),
+        ),
+    }
+}
+
+fn describe_outcome(outcome: &DialProbeOutcome) -> String {
+    match outcome {
+        DialProbeOutcome::Connected => "connected".to_string(),
+        DialProbeOutcome::Failed(error) => format!("failed ({error})"),
+        DialProbeOutcome::TimedOut => "timed out".to_string(),
+    }
+}
+
+fn relay_status_ready(status: &RelayEndpointStatusRecord) -> bool {
+    status.listener_registered && status.current_direct_connection_id.is_some()
+}
+
+fn usable_external_candidates(
+    candidates: &[ExternalAddressCandidateRecord],
+    now: SystemTime,
+) -> impl Iterator<Item = &ExternalAddressCandidateRecord> {
+    candidates.iter().filter(move |candidate| {
+        candidate.transport_kind != AddressTransportKind::Relayed
+            && candidate.freshness(now) != AddressFreshness::Expired
+    })
+}
+
+fn infer_nat_kind(
+    listen_addresses: &[Multiaddr],
+    candidates: &[ExternalAddressCandidateRecord],
+    now: SystemTime,
+) -> NatKind {
+    if listen_addresses
+        .iter()
+        .filter_map(multiaddr_ip)
+        .any(is_public_ip)
+    {
+        return NatKind::Public;
+    }
+
+    let usable = usable_external_candidates(candidates, now).collect::<Vec<_>>();
+    if usable
+        .iter()
+        .any(|candidate| candidate.confirmed_at.is_some())
+    {
+        return NatKind::Public;
+    }
+
+    let mut ports_by_endpoint = HashMap::<(IpAddr, &str), BTreeSet<u16>>::new();
+    for candidate in &usable {
+        let (Some(ip), Some(port)) = (
+            multiaddr_ip(&candidate.address),
+            multiaddr_port(&candidate.address),
+        ) else {
+            continue;
+        };
+        ports_by_endpoint
+            .entry((ip, candidate.transport_kind.as_str()))
+            .or_default()
+            .insert(port);
+    }
+
+    if ports_by_endpoint.is_empty() {
+        NatKind::Unknown
+    } else if ports_by_endpoint.values().any(|ports| ports.len() > 1) {
+        NatKind::EndpointDependent
+    } else {
+        NatKind::EndpointIndependent
+    }
+}
+
+fn connectivity_verdict(
+    direct_probes: &[DirectDialProbe],
+    relay_probe: Option<&RelayCircuitProbe>,
+) -> ConnectivityVerdict {
+    if direct_probes
+        .iter()
+        .any(|probe| probe.outcome.is_connected())
+    {
+        return ConnectivityVerdict::Direct;
+    }
+
+    match relay_probe {
+        Some(probe) if probe.outcome.is_connected() => {
+            if probe
+                .hole_punch
+                .as_ref()
+                .is_some_and(|record| record.succeeded)
+            {
+                ConnectivityVerdict::HolePunched
+            } else {
+                ConnectivityVerdict::RelayOnly
+            }
+        }
+        _ => ConnectivityVerdict::Unreachable,
+    }
+}
+
+struct RemediationInputs {
+    verdict: ConnectivityVerdict,
+    nat_kind: NatKind,
+    has_listeners: bool,
+    has_direct_candidates: bool,
+    relay_enabled: bool,
+    relay_ready: bool,
+}
+
+fn remediation_hints(inputs: &RemediationInputs) -> Vec<String> {
+    let mut hints = Vec::new();
+
+    if !inputs.has_listeners {
+        hints.push(
+            "The daemon has no listen address; check listen_tcp_port/listen_udp_port in config.toml and restart the daemon."
+                .to_string(),
+        );
+    }
+
+    match inputs.verdict {
+        ConnectivityVerdict::Direct => {}
+        ConnectivityVerdict::HolePunched => {
+            hints.push(
+                "Direct connectivity depends on hole punching; forwarding the listen ports on the router makes it reliable."
+                    .to_string(),
+            );
+        }
+        ConnectivityVerdict::RelayOnly | ConnectivityVerdict::Unreachable => {
+            if !inputs.has_direct_candidates {
+                hints.push(
+                    "No direct address is known for the device; make sure it is online, or add one with `fungi device address add <device> <multiaddr>`."
+                        .to_string(),
+                );
+            }
+            if inputs.nat_kind == NatKind::EndpointDependent {
+                hints.push(
+                    "The local NAT maps each remote to a different port, so hole punching is unlikely; forward the listen ports on the router or use IPv6."
+                        .to_string(),
+                );
+            }
+        }
+    }
+
+    if inputs.verdict == ConnectivityVerdict::Unreachable {
+        if !inputs.relay_enabled {
+            hints.push(
+                "Relay is disabled; enable it with `fungi relay enable` so devices behind NAT can still reach each other."
+                    .to_string(),
+            );
+        } else if !inputs.relay_ready {
+            hints.push(
+                "No relay reservation is active; check `fungi connection relay-status` and that the relay addresses are reachable."
+                    .to_string(),
+            );
+        } else {
+            hints.push(
+                "The relay is healthy but the device did not answer through it; check that the device's daemon is running and has relay enabled."
+                    .to_string(),
+            );
+        }
+    }
+
+    hints
+}
+
+fn multiaddr_ip(addr: &Multiaddr) -> Option<IpAddr> {
+    addr.iter().find_map(|protocol| match protocol {
+        Protocol::Ip4(ip) => Some(IpAddr::V4(ip)),
+        Protocol::Ip6(ip) => Some(IpAddr::V6(ip)),
+        _ => None,
+    })
+}
+
+fn multiaddr_port(addr: &Multiaddr) -> Option<u16> {
+    addr.iter().find_map(|protocol| match protocol {
+        Protocol::Tcp(port) | Protocol::Udp(port) => Some(port),
+        _ => None,
+    })
+}
+
+fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            !ip.is_private()
+                && !ip.is_loopback()
+                && !ip.is_link_local()
+                && !ip.is_unspecified()
+                && !ip.is_broadcast()
+                && !ip.is_multicast()
+                // 100.64.0.0/10 carrier-grade NAT space.
+                && !(ip.octets()[0] == 100 && (ip.octets()[1] & 0xc0) == 64)
+        }
+        IpAddr::V6(ip) => {
+            !ip.is_loopback()
+                && !ip.is_unspecified()
+                && !ip.is_multicast()
+                && !ip.is_unicast_link_local()
+                && !ip.is_unique_local()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fungi_swarm::ExternalAddressSource;
+
+    fn candidate(address: &str, confirmed: bool) -> ExternalAddressCandidateRecord {
+        let address: Multiaddr = address.parse().unwrap();
+        let now = SystemTime::now();
+        ExternalAddressCandidateRecord {
+            transport_kind: fungi_swarm::address_transport_kind(&address),
+            address,
+            first_observed_at: now,
+            last_observed_at: now,
+            confirmed_at: confirmed.then_some(now),
+            expired_at: None,
+            observation_count: 1,
+            sources: vec![ExternalAddressSource::SwarmCandidate],
+        }
+    }
+
+    #[test]
+    fn nat_kind_is_public_for_public_listen_address() {
+        let listen = vec!["/ip4/203.0.113.10/tcp/4001".parse().unwrap()];
+        assert_eq!(
+            infer_nat_kind(&listen, &[], SystemTime::now()),
+            NatKind::Public
+        );
+    }
+
+    #[test]
+    fn nat_kind_ignores_private_and_cgnat_listen_addresses() {
+        let listen = vec![
+            "/ip4/192.168.1.10/tcp/4001".parse().unwrap(),
+            "/ip4/100.100.1.2/tcp/4001".parse().unwrap(),
+        ];
+        assert_eq!(
+            infer_nat_kind(&listen, &[], SystemTime::now()),
+            NatKind::Unknown
+        );
+    }
+
+    #[test]
+    fn nat_kind_detects_port_changing_mapping() {
+        let candidates = vec![
+            candidate("/ip4/198.51.100.7/udp/40001/quic-v1", false),
+            candidate("/ip4/198.51.100.7/udp/40517/quic-v1", false),
+        ];
+        assert_eq!(
+            infer_nat_kind(&[], &candidates, SystemTime::now()),
+            NatKind::EndpointDependent
+        );
+    }
+
+    #[test]
+    fn nat_kind_treats_stable_port_as_endpoint_independent() {
+        let candidates = vec![
+            candidate("/ip4/198.51.100.7/udp/40001/quic-v1", false),
+            candidate("/ip4/198.51.100.7/tcp/40001", false),
+        ];
+        assert_eq!(
+            infer_nat_kind(&[], &candidates, SystemTime::now()),
+            NatKind::EndpointIndependent
+        );
+    }
+
+    #[test]
+    fn nat_kind_ignores_relayed_candidates() {
+        let candidates = vec![candidate(
+            "/ip4/198.51.100.1/tcp/7001/p2p/12D3KooWQjN7A4xA7bP9g4fC1Qm2nG1k5eYvG8K2Qw4p1D6sZ7Qx/p2p-circuit",
+            true,
+        )];
+        assert_eq!(
+            infer_nat_kind(&[], &candidates, SystemTime::now()),
+            NatKind::Unknown
+        );
+    }
+
+    #[test]
+    fn unreachable_without_relay_suggests_enabling_relay() {
+        let hints = remediation_hints(&RemediationInputs {
+            verdict: ConnectivityVerdict::Unreachable,
+            nat_kind: NatKind::Unknown,
+            has_listeners: true,
+            has_direct_candidates: false,
+            relay_enabled: false,
+            relay_ready: false,
+        });
+        assert!(hints.iter().any(|hint| hint.contains("fungi relay enable")));
+        assert!(
+            hints
+                .iter()
+                .any(|hint| hint.contains("fungi device address add"))
+        );
+    }
+
+    #[test]
+    fn direct_verdict_needs_no_remediation() {
+        let hints = remediation_hints(&RemediationInputs {
+            verdict: ConnectivityVerdict::Direct,
+            nat_kind: NatKind::EndpointDependent,
+            has_listeners: true,
+            has_direct_candidates: true,
+            relay_enabled: true,
+            relay_ready: true,
+        });
+        assert!(hints.is_empty());
+    }
+
+    #[test]
+    fn verdict_prefers_direct_then_hole_punch_then_relay() {
+        let connected_relay = RelayCircuitProbe {
+            circuit_addresses: Vec::new(),
+            outcome: DialProbeOutcome::Connected,
+            elapsed: Duration::ZERO,
+            hole_punch: None,
+        };
+        assert_eq!(
+            connectivity_verdict(&[], Some(&connected_relay)),
+            ConnectivityVerdict::RelayOnly
+        );
+
+        let punched_relay = RelayCircuitProbe {
+            hole_punch: Some(fungi_swarm::HolePunchRecord {
+                peer_id: PeerId::random(),
+                succeeded: true,
+                connection_id: None,
+                error: None,
+                observed_at: SystemTime::now(),
+            }),
+            ..connected_relay.clone()
+        };
+        assert_eq!(
+            connectivity_verdict(&[], Some(&punched_relay)),
+            ConnectivityVerdict::HolePunched
+        );
+
+        let failed_relay = RelayCircuitProbe {
+            outcome: DialProbeOutcome::TimedOut,
+            ..connected_relay
+        };
+        assert_eq!(
+            connectivity_verdict(&[], Some(&failed_relay)),
+            ConnectivityVerdict::Unreachable
+        );
+        assert_eq!(
+            connectivity_verdict(&[], None),
+            ConnectivityVerdict::Unreachable
+        );
+    }
+}
@@ -0,0 +1,38 @@
+use anyhow::{Result, bail};
+
+use crate::FungiDaemon;
+
+use super::types::{HttpGatewayListing, HttpGatewayService};
+
+impl FungiDaemon {
+    /// Web services of saved devices with their URLs on the local HTTP gateway.
+    pub fn list_http_gateway_services(&self) -> Result<HttpGatewayListing> {
+        let Some(gateway) = self.http_gateway_control() else {
+            bail!(
+                "the HTTP gateway is disabled or failed to start; see http_gateway in config.toml"
+            );
+        };
+        let Some(local_addr) = gateway.local_addr() else {
+            bail!("the HTTP gateway is not listening");
+        };
+
+        let services = gateway
+            .routes()
+            .into_iter()
+            .filter_map(|route| {
+                let url = gateway.route_url(&route)?;
+                Some(HttpGatewayService {
+                    peer_id: route.peer_id.to_string(),
+                    device_name: route.device_name,
+                    service_name: route.service_name,
+                    entry: route.entry,
+                    url,
+                })
+            })
+            .collect();
+        Ok(HttpGatewayListing {
+            index_url: format!("http://{}:{}/", gateway.domain(), local_addr.port()),
+            services,
+        })
+    }
+}
@@ -1,8 +1,13 @@
 mod devices;
+mod diagnostics;
+mod http_gateway;
 mod peer;
 mod relay;
 mod runtime;
 mod service_access;
+mod service_backup;
 mod types;
+mod wake;
 
+pub use service_backup::ServiceBackupOptions;
 pub use types::{ServiceAccess, ServiceAccessEndpoint};
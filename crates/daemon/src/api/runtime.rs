@@ -1,24 +1,33 @@
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     path::{Path, PathBuf},
-    time::SystemTime,
+    time::{Duration, SystemTime},
 };
 
 use anyhow::{Context as _, Result};
-use fungi_config::runtime::Runtime as RuntimeConfig;
+use fungi_config::{bandwidth::BandwidthLimit, runtime::Runtime as RuntimeConfig};
+use fungi_docker_agent::ImagePullProgress;
 use libp2p::PeerId;
+use tokio::sync::{broadcast, mpsc};
 
+use crate::controls::PrunedImage;
 use crate::runtime::{
-    DeviceService, DeviceServiceSnapshot, RuntimeKind, ServiceInstance, ServiceLogs,
-    ServiceLogsOptions, ServiceManifest, service_expose_endpoint_bindings,
+    AppliedService, DeviceService, DeviceServiceSnapshot, RuntimeKind, ServiceExecRequest,
+    ServiceExecSession, ServiceInstance, ServiceJobRun, ServiceJobStatus, ServiceJobTrigger,
+    ServiceLogs, ServiceLogsOptions, ServiceManifest, ServiceRevision, ServiceSource,
+    parse_service_manifest_yaml, service_expose_endpoint_bindings,
 };
 use crate::service_state::DesiredServiceState;
 use crate::{
     FungiDaemon, LocalRuntimeStatus, ManifestResolutionPolicy, NodeCapabilities,
-    ResolvedServiceRecipe, ServiceControlResponse, ServiceRecipeDetail, ServiceRecipeRuntime,
-    ServiceRecipeSummary, build_local_node_capabilities, build_local_runtime_status,
+    ResolvedServiceRecipe, SecretInfo, ServiceControlResponse, ServiceRecipeDetail,
+    ServiceRecipeRuntime, ServiceRecipeSummary, build_local_node_capabilities,
+    build_local_runtime_status,
 };
 
+/// How long a saved device gets to repoint its accesses after a service moved.
+const SERVICE_ACCESS_REPOINT_TIMEOUT: Duration = Duration::from_secs(5);
+
 pub struct DeviceServiceSnapshotLookup {
     pub snapshot: DeviceServiceSnapshot,
     pub source: DeviceServiceSnapshotSource,
@@ -62,6 +71,50 @@ impl FungiDaemon {
         self.apply_runtime_config_update(updated_config)
     }
 
+    /// Stores an encrypted secret that service `env` values can reference as `${secret:name}`.
+    pub fn set_secret(&self, name: &str, value: &str) -> Result<SecretInfo> {
+        self.runtime_control().secrets().set(name, value)
+    }
+
+    pub fn list_secrets(&self) -> Result<Vec<SecretInfo>> {
+        self.runtime_control().secrets().list()
+    }
+
+    /// Returns whether the secret existed. Services referencing it fail on their next launch.
+    pub fn remove_secret(&self, name: &str) -> Result<bool> {
+        self.runtime_control().secrets().remove(name)
+    }
+
+    /// Persists the serving-side bandwidth limit for a published service port and applies it to
+    /// the live endpoint listener.
+    pub fn set_service_port_bandwidth_limit(
+        &self,
+        service_name: &str,
+        port_name: &str,
+        limit: BandwidthLimit,
+    ) -> Result<()> {
+        let manifest = self
+            .runtime_control()
+            .get_service_manifest(service_name)
+            .ok_or_else(|| anyhow::anyhow!("service not found: {service_name}"))?;
+        let endpoint = service_expose_endpoint_bindings(&manifest)
+            .into_iter()
+            .find(|endpoint| endpoint.name == port_name)
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "service {service_name} does not publish a TCP endpoint named {port_name}"
+                )
+            })?;
+
+        let current_config = self.config().lock().clone();
+        let updated_config =
+            current_config.set_published_port_bandwidth_limit(service_name, port_name, limit)?;
+        *self.config().lock() = updated_config;
+        self.tcp_tunneling_control()
+            .set_protocol_bandwidth_limit(&endpoint.protocol, limit);
+        Ok(())
+    }
+
     async fn sync_service_endpoint_listeners_by_name(
         &self,
         name: &str,
@@ -100,6 +153,7 @@ impl FungiDaemon {
                             host: "127.0.0.1".to_string(),
                             port: endpoint.host_port,
                             protocol: Some(endpoint.protocol),
+                            bandwidth_limit: Default::default(),
                         })
                         .await?;
                 }
@@ -109,6 +163,7 @@ impl FungiDaemon {
             }
         }
 
+        self.on_demand_control().sync_manifest(manifest, enabled);
         Ok(())
     }
 
@@ -140,16 +195,11 @@ impl FungiDaemon {
     }
 
     pub async fn pull_service(&self, manifest: ServiceManifest) -> Result<ServiceInstance> {
-        let applied = self.runtime_control().apply(&manifest).await?;
-        if applied.desired_state == DesiredServiceState::Running {
-            self.sync_service_endpoint_listeners_for_manifest(
-                applied.previous_manifest.as_ref(),
-                false,
-            )
+        let applied = self
+            .runtime_control()
+            .apply(&manifest, Some(self.swarm_control().local_peer_id()))
             .await?;
-            self.sync_service_endpoint_listeners_by_name(&applied.instance.name, true)
-                .await?;
-        }
+        self.sync_applied_service_listeners(&applied).await?;
         Ok(applied.instance)
     }
 
@@ -163,8 +213,95 @@ impl FungiDaemon {
         let policy = self.manifest_resolution_policy();
         let applied = self
             .runtime_control()
-            .apply_manifest_yaml(&manifest_yaml, &base_dir, &fungi_home, &policy)
+            .apply_manifest_yaml(
+                &manifest_yaml,
+                &base_dir,
+                &fungi_home,
+                &policy,
+                Some(self.swarm_control().local_peer_id()),
+            )
+            .await?;
+        self.sync_applied_service_listeners(&applied).await?;
+        Ok(applied.instance)
+    }
+
+    /// Like [`FungiDaemon::pull_service_from_manifest_yaml`], but forwards the progress of the
+    /// service's Docker image pull, if one happens, to `progress`.
+    pub async fn pull_service_from_manifest_yaml_with_progress(
+        &self,
+        manifest_yaml: String,
+        manifest_base_dir: Option<PathBuf>,
+        progress: mpsc::UnboundedSender<ImagePullProgress>,
+    ) -> Result<ServiceInstance> {
+        let fungi_home = self.fungi_home_dir();
+        let base_dir = manifest_base_dir.as_deref().unwrap_or(&fungi_home);
+        let image = parse_service_manifest_yaml(&manifest_yaml, base_dir, &fungi_home)
+            .ok()
+            .and_then(|manifest| match manifest.source {
+                ServiceSource::Docker { image } => Some(image),
+                _ => None,
+            });
+        let (Some(image), Some(docker)) = (image, self.docker_control()) else {
+            return self
+                .pull_service_from_manifest_yaml(manifest_yaml, manifest_base_dir)
+                .await;
+        };
+
+        let mut events = docker.subscribe_pull_progress();
+        let pull = self.pull_service_from_manifest_yaml(manifest_yaml, manifest_base_dir);
+        tokio::pin!(pull);
+        loop {
+            tokio::select! {
+                result = &mut pull => return result,
+                event = events.recv() => match event {
+                    Ok(event) if event.image == image => {
+                        let _ = progress.send(event);
+                    }
+                    Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {}
+                    Err(broadcast::error::RecvError::Closed) => return pull.await,
+                },
+            }
+        }
+    }
+
+    /// Removes Docker images fungi pulled for services that no container uses anymore.
+    pub async fn prune_service_images(&self, dry_run: bool) -> Result<Vec<PrunedImage>> {
+        let docker = self
+            .docker_control()
+            .context("Docker runtime is not available on this node")?;
+        docker.prune_images(dry_run).await
+    }
+
+    pub fn service_history(&self, name: &str) -> Result<Vec<ServiceRevision>> {
+        self.runtime_control().service_revisions(name)
+    }
+
+    /// Runs a local job once and waits for it to finish.
+    pub async fn run_service_job(&self, name: String) -> Result<ServiceJobRun> {
+        self.runtime_control()
+            .run_job(&name, ServiceJobTrigger::Manual)
+            .await
+    }
+
+    pub async fn exec_service(&self, request: ServiceExecRequest) -> Result<ServiceExecSession> {
+        self.runtime_control().exec(&request).await
+    }
+
+    /// Restores a recorded revision of a local service, by default the previous one.
+    pub async fn rollback_service(
+        &self,
+        name: String,
+        revision: Option<u64>,
+    ) -> Result<ServiceInstance> {
+        let applied = self
+            .runtime_control()
+            .rollback(&name, revision, Some(self.swarm_control().local_peer_id()))
             .await?;
+        self.sync_applied_service_listeners(&applied).await?;
+        Ok(applied.instance)
+    }
+
+    async fn sync_applied_service_listeners(&self, applied: &AppliedService) -> Result<()> {
         if applied.desired_state == DesiredServiceState::Running {
             self.sync_service_endpoint_listeners_for_manifest(
                 applied.previous_manifest.as_ref(),
@@ -174,7 +311,7 @@ impl FungiDaemon {
             self.sync_service_endpoint_listeners_by_name(&applied.instance.name, true)
                 .await?;
         }
-        Ok(applied.instance)
+        Ok(())
     }
 
     pub async fn start_service(&self, runtime: RuntimeKind, name: String) -> Result<()> {
@@ -220,11 +357,15 @@ impl FungiDaemon {
         runtime: RuntimeKind,
         name: String,
     ) -> Result<ServiceInstance> {
-        self.runtime_control().inspect(runtime, &name).await
+        let control = self.runtime_control();
+        let instance = control.inspect(runtime, &name).await?;
+        Ok(control.with_resources(instance).await)
     }
 
     pub async fn inspect_service_by_name(&self, name: String) -> Result<ServiceInstance> {
-        self.runtime_control().inspect_by_name(&name).await
+        let control = self.runtime_control();
+        let instance = control.inspect_by_name(&name).await?;
+        Ok(control.with_resources(instance).await)
     }
 
     pub async fn get_service_logs(
@@ -234,7 +375,7 @@ impl FungiDaemon {
         tail: Option<String>,
     ) -> Result<ServiceLogs> {
         self.runtime_control()
-            .logs(runtime, &name, &ServiceLogsOptions { tail })
+            .logs(runtime, &name, &ServiceLogsOptions { tail, since: None })
             .await
     }
 
@@ -244,12 +385,12 @@ impl FungiDaemon {
         tail: Option<String>,
     ) -> Result<ServiceLogs> {
         self.runtime_control()
-            .logs_by_name(&name, &ServiceLogsOptions { tail })
+            .logs_by_name(&name, &ServiceLogsOptions { tail, since: None })
             .await
     }
 
     pub async fn list_services(&self) -> Result<Vec<ServiceInstance>> {
-        self.runtime_control().list_services().await
+        self.runtime_control().list_services_with_resources().await
     }
 
     pub async fn list_exposed_services(&self) -> Result<Vec<DeviceService>> {
@@ -266,7 +407,8 @@ impl FungiDaemon {
 
     pub async fn list_service_recipes(&self, refresh: bool) -> Result<Vec<ServiceRecipeSummary>> {
         let fungi_dir = self.config_fungi_dir()?;
-        crate::recipes::list_official_service_recipes(&fungi_dir, refresh).await
+        let sources = self.config().lock().recipes.clone();
+        crate::recipes::list_service_recipes(&fungi_dir, &sources, refresh).await
     }
 
     pub async fn get_service_recipe(
@@ -275,21 +417,26 @@ impl FungiDaemon {
         refresh: bool,
     ) -> Result<ServiceRecipeDetail> {
         let fungi_dir = self.config_fungi_dir()?;
-        crate::recipes::get_official_service_recipe(&fungi_dir, recipe_id, refresh).await
+        let sources = self.config().lock().recipes.clone();
+        crate::recipes::get_service_recipe(&fungi_dir, &sources, recipe_id, refresh).await
     }
 
     pub async fn resolve_service_recipe(
         &self,
         recipe_id: &str,
         service_name: Option<&str>,
+        inputs: &BTreeMap<String, String>,
         target_peer_id: Option<PeerId>,
         refresh: bool,
     ) -> Result<ResolvedServiceRecipe> {
         let fungi_dir = self.config_fungi_dir()?;
-        let mut resolved = crate::recipes::resolve_official_service_recipe(
+        let sources = self.config().lock().recipes.clone();
+        let mut resolved = crate::recipes::resolve_service_recipe(
             &fungi_dir,
+            &sources,
             recipe_id,
             service_name,
+            inputs,
             refresh,
         )
         .await?;
@@ -578,6 +725,75 @@ impl FungiDaemon {
         Ok(response)
     }
 
+    pub async fn remote_service_history(
+        &self,
+        peer_id: PeerId,
+        name: String,
+    ) -> Result<Vec<ServiceRevision>> {
+        let response = self
+            .service_control_protocol_control()
+            .peer_service_history(peer_id, name)
+            .await?;
+        let revisions_json = response
+            .revisions_json
+            .ok_or_else(|| anyhow::anyhow!("remote device returned no service history"))?;
+        serde_json::from_str(&revisions_json).context("failed to decode remote service history")
+    }
+
+    pub async fn remote_run_service_job(
+        &self,
+        peer_id: PeerId,
+        name: String,
+    ) -> Result<ServiceJobRun> {
+        let response = self
+            .service_control_protocol_control()
+            .run_peer_job(peer_id, name)
+            .await?;
+        let run_json = response
+            .job_json
+            .ok_or_else(|| anyhow::anyhow!("remote device returned no job run"))?;
+        serde_json::from_str(&run_json).context("failed to decode remote job run")
+    }
+
+    pub async fn remote_exec_service(
+        &self,
+        peer_id: PeerId,
+        request: ServiceExecRequest,
+    ) -> Result<ServiceExecSession> {
+        self.service_exec_protocol_control()
+            .exec_on_peer(peer_id, request)
+            .await
+    }
+
+    pub async fn remote_service_job_status(
+        &self,
+        peer_id: PeerId,
+        name: String,
+    ) -> Result<ServiceJobStatus> {
+        let response = self
+            .service_control_protocol_control()
+            .peer_job_status(peer_id, name)
+            .await?;
+        let status_json = response
+            .job_json
+            .ok_or_else(|| anyhow::anyhow!("remote device returned no job status"))?;
+        serde_json::from_str(&status_json).context("failed to decode remote job status")
+    }
+
+    pub async fn remote_rollback_service(
+        &self,
+        peer_id: PeerId,
+        name: String,
+        revision: Option<u64>,
+    ) -> Result<ServiceControlResponse> {
+        let response = self
+            .service_control_protocol_control()
+            .rollback_peer_service(peer_id, name, revision)
+            .await?;
+        self.refresh_or_keep_device_service_snapshot(peer_id).await;
+        Ok(response)
+    }
+
     pub async fn remote_list_services(&self, peer_id: PeerId) -> Result<ServiceControlResponse> {
         let lookup = self.get_device_service_snapshot(peer_id, true).await?;
         Ok(ServiceControlResponse::success_services(
@@ -640,6 +856,165 @@ impl FungiDaemon {
         self.refresh_or_keep_device_service_snapshot(peer_id).await;
         Ok(response)
     }
+
+    /// Moves service `name` from device `from` to device `to` together with its appdata, or
+    /// copies it when `clone` is set; either device may be this one. The service is stopped on
+    /// `from` for the transfer and runs on `to` afterwards if it was running before. After a
+    /// move the service is removed from `from`, whose appdata directory stays on disk, and saved
+    /// accesses on this and every saved device are pointed at `to`.
+    ///
+    /// Returns the saved devices that could not be reached to repoint their accesses.
+    pub async fn move_service(
+        &self,
+        name: String,
+        from: PeerId,
+        to: PeerId,
+        clone: bool,
+    ) -> Result<Vec<PeerId>> {
+        if from == to {
+            anyhow::bail!("source and target device are the same");
+        }
+        let local_peer_id = self.swarm_control().local_peer_id();
+        let was_running = if from == local_peer_id {
+            self.runtime_control()
+                .inspect_by_name(&name)
+                .await?
+                .status
+                .is_running()
+        } else {
+            self.get_device_service_snapshot(from, true)
+                .await?
+                .snapshot
+                .services
+                .into_iter()
+                .find(|service| service.name == name)
+                .ok_or_else(|| anyhow::anyhow!("service {name} not found on device {from}"))?
+                .status
+                .is_running()
+        };
+
+        if was_running {
+            self.stop_service_on_device(from, &name).await?;
+        }
+        let imported = if to == local_peer_id {
+            self.service_control_protocol_control()
+                .import_service(from, &name, was_running, Some(from))
+                .await
+                .map(|_| ())
+        } else {
+            self.service_control_protocol_control()
+                .import_peer_service(to, from, name.clone(), was_running)
+                .await
+                .map(|_| ())
+        };
+        if let Err(error) = imported {
+            if was_running
+                && let Err(restart_error) = self.start_service_on_device(from, &name).await
+            {
+                anyhow::bail!(
+                    "{error:#}; restarting the service on {from} also failed: {restart_error:#}"
+                );
+            }
+            return Err(error);
+        }
+        if to != local_peer_id {
+            self.refresh_or_keep_device_service_snapshot(to).await;
+        }
+
+        if clone {
+            if was_running {
+                self.start_service_on_device(from, &name)
+                    .await
+                    .with_context(|| {
+                        format!("service was cloned to {to}, but restarting it on {from} failed")
+                    })?;
+            }
+            return Ok(Vec::new());
+        }
+
+        let removed = if from == local_peer_id {
+            self.remove_service_by_name(name.clone()).await
+        } else {
+            self.service_control_protocol_control()
+                .remove_peer_service(from, name.clone())
+                .await
+                .map(|_| ())
+        };
+        removed.with_context(|| {
+            format!("service was moved to {to}, but removing it from {from} failed")
+        })?;
+        if from != local_peer_id {
+            self.refresh_or_keep_device_service_snapshot(from).await;
+        }
+
+        self.service_control_protocol_control()
+            .repoint_service_access(&name, from, to)
+            .await
+            .with_context(|| {
+                format!("service was moved to {to}, but repointing local accesses failed")
+            })?;
+        // Stopping a remote service disconnects this device's listeners; bring them back at `to`.
+        if was_running
+            && to != local_peer_id
+            && let Err(error) = self.restore_saved_service_access(to, name.clone()).await
+        {
+            log::warn!("Failed to restore local accesses to moved service {name}: {error}");
+        }
+        Ok(self.repoint_peer_service_accesses(&name, from, to).await)
+    }
+
+    async fn stop_service_on_device(&self, peer_id: PeerId, name: &str) -> Result<()> {
+        if peer_id == self.swarm_control().local_peer_id() {
+            self.stop_service_by_name(name.to_string()).await
+        } else {
+            self.remote_stop_service(peer_id, name.to_string())
+                .await
+                .map(|_| ())
+        }
+    }
+
+    async fn start_service_on_device(&self, peer_id: PeerId, name: &str) -> Result<()> {
+        if peer_id == self.swarm_control().local_peer_id() {
+            self.start_service_by_name(name.to_string()).await
+        } else {
+            self.remote_start_service(peer_id, name.to_string())
+                .await
+                .map(|_| ())
+        }
+    }
+
+    async fn repoint_peer_service_accesses(
+        &self,
+        name: &str,
+        from: PeerId,
+        to: PeerId,
+    ) -> Vec<PeerId> {
+        let local_peer_id = self.swarm_control().local_peer_id();
+        let peer_ids = self
+            .devices_get_all()
+            .into_iter()
+            .map(|device| device.peer_id)
+            .filter(|peer_id| *peer_id != local_peer_id)
+            .collect::<BTreeSet<_>>();
+
+        let mut unreachable = Vec::new();
+        for peer_id in peer_ids {
+            let result = tokio::time::timeout(
+                SERVICE_ACCESS_REPOINT_TIMEOUT,
+                self.service_control_protocol_control()
+                    .repoint_peer_service_access(peer_id, name.to_string(), from, to),
+            )
+            .await;
+            let error = match result {
+                Ok(Ok(_)) => continue,
+                Ok(Err(error)) => error.to_string(),
+                Err(_) => "timed out".to_string(),
+            };
+            log::warn!("Failed to repoint accesses to service {name} on device {peer_id}: {error}");
+            unreachable.push(peer_id);
+        }
+        unreachable
+    }
 }
 
 fn merge_device_service_snapshot(
@@ -673,10 +1048,12 @@ fn merge_device_service_snapshot(
 fn device_service_from_instance(instance: ServiceInstance) -> DeviceService {
     DeviceService {
         name: instance.name,
+        definition_id: instance.definition_id,
         runtime: instance.runtime,
         metadata: Default::default(),
         endpoints: Vec::new(),
         status: instance.status,
+        resources: instance.resources,
     }
 }
 
@@ -783,10 +1160,12 @@ mod tests {
             peer_id: peer_id.to_string(),
             services: vec![DeviceService {
                 name: "svc-a".to_string(),
+                definition_id: None,
                 runtime: RuntimeKind::External,
                 metadata: Default::default(),
                 endpoints: Vec::new(),
                 status: ServiceStatus::running(),
+                resources: None,
             }],
             updated_at: SystemTime::now(),
         };
@@ -829,11 +1208,14 @@ mod tests {
             ports: vec![service_port("web")],
             exposed_endpoints: Vec::new(),
             status,
+            job: None,
+            resources: None,
         }
     }
 
     fn published_service(name: &str, status: ServiceStatus) -> DeviceService {
         DeviceService {
+            definition_id: None,
             name: name.to_string(),
             runtime: RuntimeKind::External,
             metadata: crate::DeviceServiceMetadata {
@@ -848,6 +1230,7 @@ mod tests {
                 protocol: format!("/fungi/service/{name}/web/0.2.0"),
             }],
             status,
+            resources: None,
         }
     }
 
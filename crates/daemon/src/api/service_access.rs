@@ -6,12 +6,19 @@ use std::{
 
 use anyhow::{Result, bail};
 use fungi_config::{
-    local_preferences::{LocalPortSource, LocalPreferenceCache, LocalServicePreference},
-    tcp_tunneling::ForwardingRule,
+    bandwidth::BandwidthLimit,
+    local_preferences::{
+        LocalPortSource, LocalPreferenceCache, LocalServicePreference, ServiceGroupMember,
+    },
+    tcp_tunneling::{ForwardingRule, ForwardingTarget, ServiceRouting},
 };
+use fungi_util::protocols::service_port_protocol;
 use libp2p::PeerId;
 
-use crate::{DeviceServiceEndpoint, FungiDaemon};
+use crate::{
+    DeviceService, DeviceServiceEndpoint, FungiDaemon, ServiceControlResponse,
+    controls::ServiceAccessOperations,
+};
 
 use super::types::{ServiceAccess, ServiceAccessEndpoint};
 
@@ -40,6 +47,9 @@ impl FungiDaemon {
             remote_service_id: None,
             remote_service_name: Some(record.remote_service_name.clone()),
             remote_service_port_name: Some(record.remote_service_port_name.clone()),
+            bandwidth_limit: record.bandwidth_limit,
+            fallback_targets: group_forwarding_targets(record),
+            routing: record.routing,
         };
         self.add_service_access_forwarding_rule_internal(rule).await
     }
@@ -56,11 +66,9 @@ impl FungiDaemon {
             &record.remote_service_port_name,
         );
 
-        let active_rule_matches = existing_active_rule.as_ref().is_some_and(|(_, rule)| {
-            rule.local_host == record.local_host
-                && rule.local_port == record.local_port
-                && rule.remote_protocol.as_deref() == Some(endpoint.protocol.as_str())
-        });
+        let active_rule_matches = existing_active_rule
+            .as_ref()
+            .is_some_and(|(_, rule)| forwarding_rule_matches(rule, record, &endpoint.protocol));
         if active_rule_matches {
             return Ok(());
         }
@@ -133,6 +141,8 @@ impl FungiDaemon {
             .chain(active_rules.iter().map(|(_, rule)| rule.local_port))
             .collect::<BTreeSet<_>>();
         let mut enabled_endpoints = Vec::new();
+        let mut group = Vec::new();
+        let mut routing = ServiceRouting::default();
         let endpoints = service
             .endpoints
             .into_iter()
@@ -181,10 +191,29 @@ impl FungiDaemon {
                     .unwrap_or_default()
             };
 
+            let record = LocalServicePreference {
+                remote_peer_id: peer_id_string.clone(),
+                remote_service_name: service.name.clone(),
+                remote_service_port_name: endpoint.name.clone(),
+                local_host: "127.0.0.1".to_string(),
+                local_port: selected_local_port,
+                local_port_source,
+                bandwidth_limit: existing_record
+                    .as_ref()
+                    .map(|record| record.bandwidth_limit)
+                    .unwrap_or_default(),
+                group: existing_record
+                    .as_ref()
+                    .map(|record| record.group.clone())
+                    .unwrap_or_default(),
+                routing: existing_record
+                    .as_ref()
+                    .map(|record| record.routing)
+                    .unwrap_or_default(),
+            };
+
             let active_rule_matches = existing_active_rule.as_ref().is_some_and(|(_, rule)| {
-                rule.local_host == "127.0.0.1"
-                    && rule.local_port == selected_local_port
-                    && rule.remote_protocol.as_deref() == Some(endpoint.protocol.as_str())
+                forwarding_rule_matches(rule, &record, &endpoint.protocol)
             });
             let active_rule_owns_selected_port =
                 existing_active_rule.as_ref().is_some_and(|(_, rule)| {
@@ -195,15 +224,6 @@ impl FungiDaemon {
                 ensure_local_port_available(selected_local_port, &reserved_local_ports)?;
             }
 
-            let record = LocalServicePreference {
-                remote_peer_id: peer_id_string.clone(),
-                remote_service_name: service.name.clone(),
-                remote_service_port_name: endpoint.name.clone(),
-                local_host: "127.0.0.1".to_string(),
-                local_port: selected_local_port,
-                local_port_source,
-            };
-
             let updated_local_preferences =
                 local_preferences.with_upserted_record(record.clone())?;
 
@@ -253,11 +273,14 @@ impl FungiDaemon {
             }
 
             reserved_local_ports.insert(selected_local_port);
+            group = record.group.clone();
+            routing = record.routing;
             enabled_endpoints.push(ServiceAccessEndpoint {
                 name: endpoint.name,
                 protocol: endpoint.protocol,
                 local_host: record.local_host,
                 local_port: record.local_port,
+                bandwidth_limit: record.bandwidth_limit,
             });
         }
 
@@ -266,6 +289,8 @@ impl FungiDaemon {
             peer_id: peer_id_string,
             service_name: service.name,
             endpoints: enabled_endpoints,
+            group,
+            routing,
         })
     }
 
@@ -490,6 +515,208 @@ impl FungiDaemon {
         Ok(())
     }
 
+    /// Updates the bandwidth limit saved for a service access and applies it to active listeners.
+    /// Without an `entry`, every saved entry of the service is updated.
+    pub async fn set_service_access_bandwidth_limit(
+        &self,
+        peer_id: PeerId,
+        service_name: String,
+        entry: Option<String>,
+        limit: BandwidthLimit,
+    ) -> Result<()> {
+        let local_preferences_lock = self.local_preferences_lock();
+        let _local_preferences_guard = local_preferences_lock.lock().await;
+
+        let peer_id_string = peer_id.to_string();
+        let mut local_preferences = self.local_preferences()?;
+        let records = local_preferences
+            .records
+            .iter()
+            .filter(|record| {
+                record.remote_peer_id == peer_id_string
+                    && record.remote_service_name == service_name
+                    && entry
+                        .as_deref()
+                        .map(|entry| record.remote_service_port_name == entry)
+                        .unwrap_or(true)
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        if records.is_empty() {
+            bail!("service access not found: {}", service_name);
+        }
+
+        for mut record in records.iter().cloned() {
+            record.bandwidth_limit = limit;
+            local_preferences = local_preferences.with_upserted_record(record)?;
+        }
+        local_preferences.save_to_file()?;
+
+        let active_rules = self.get_service_access_forwarding_rules();
+        for record in &records {
+            if let Some((rule_id, _)) = find_active_rule(
+                &active_rules,
+                &record.remote_peer_id,
+                &record.remote_service_name,
+                &record.remote_service_port_name,
+            ) {
+                self.tcp_tunneling_control()
+                    .set_forwarding_rule_bandwidth_limit(&rule_id, limit)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Groups a saved service access with copies of the same service definition on `members`.
+    /// New connections to its local ports are then spread over the group according to
+    /// `routing`, falling back to the next device when a stream can't be opened. An empty
+    /// `members` list turns the access back into a single-device one.
+    pub async fn set_service_access_group(
+        &self,
+        peer_id: PeerId,
+        service_name: String,
+        members: Vec<PeerId>,
+        routing: ServiceRouting,
+    ) -> Result<ServiceAccess> {
+        let mut member_services = Vec::<(PeerId, DeviceService)>::new();
+        if !members.is_empty() {
+            let primary = self.find_device_service(peer_id, &service_name).await?;
+            let definition = service_definition_key(&primary);
+            for member in members {
+                if member == peer_id {
+                    bail!("service group must not repeat the primary device: {member}");
+                }
+                if member_services
+                    .iter()
+                    .any(|(existing, _)| *existing == member)
+                {
+                    continue;
+                }
+                let snapshot = self.get_device_service_snapshot(member, true).await?;
+                let service = snapshot
+                    .snapshot
+                    .services
+                    .into_iter()
+                    .find(|service| service_definition_key(service) == definition)
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("device {member} does not offer service {definition}")
+                    })?;
+                member_services.push((member, service));
+            }
+        }
+
+        let local_preferences_lock = self.local_preferences_lock();
+        let _local_preferences_guard = local_preferences_lock.lock().await;
+
+        let peer_id_string = peer_id.to_string();
+        let mut local_preferences = self.local_preferences()?;
+        let records = local_preferences
+            .records
+            .iter()
+            .filter(|record| {
+                record.remote_peer_id == peer_id_string
+                    && record.remote_service_name == service_name
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        if records.is_empty() {
+            bail!("service access not found: {}", service_name);
+        }
+
+        for (member, service) in &member_services {
+            for record in &records {
+                if !service
+                    .endpoints
+                    .iter()
+                    .any(|endpoint| endpoint.name == record.remote_service_port_name)
+                {
+                    bail!(
+                        "service {} on device {} has no entry {}",
+                        service.name,
+                        member,
+                        record.remote_service_port_name
+                    );
+                }
+            }
+        }
+
+        let group = member_services
+            .iter()
+            .map(|(member, service)| ServiceGroupMember {
+                peer_id: member.to_string(),
+                service_name: service.name.clone(),
+            })
+            .collect::<Vec<_>>();
+        let records = records
+            .into_iter()
+            .map(|mut record| {
+                record.group = group.clone();
+                record.routing = routing;
+                record
+            })
+            .collect::<Vec<_>>();
+        for record in &records {
+            local_preferences = local_preferences.with_upserted_record(record.clone())?;
+        }
+        local_preferences.save_to_file()?;
+
+        let active_rules = self.get_service_access_forwarding_rules();
+        let mut endpoints = Vec::new();
+        for record in &records {
+            let active_rule = find_active_rule(
+                &active_rules,
+                &record.remote_peer_id,
+                &record.remote_service_name,
+                &record.remote_service_port_name,
+            );
+            let protocol = active_rule
+                .as_ref()
+                .and_then(|(_, rule)| rule.remote_protocol.clone())
+                .unwrap_or_default();
+            if let Some((rule_id, rule)) = active_rule {
+                self.remove_service_access_forwarding_rule_internal(&rule_id)?;
+                if let Err(error) = self
+                    .start_service_access_forwarding_rule(record, protocol.clone())
+                    .await
+                {
+                    self.restore_service_access_forwarding_rule(rule).await;
+                    return Err(error);
+                }
+            }
+            endpoints.push(ServiceAccessEndpoint {
+                name: record.remote_service_port_name.clone(),
+                protocol,
+                local_host: record.local_host.clone(),
+                local_port: record.local_port,
+                bandwidth_limit: record.bandwidth_limit,
+            });
+        }
+
+        endpoints.sort_by(|left, right| left.name.cmp(&right.name));
+        Ok(ServiceAccess {
+            peer_id: peer_id_string,
+            service_name,
+            endpoints,
+            group,
+            routing,
+        })
+    }
+
+    async fn find_device_service(
+        &self,
+        peer_id: PeerId,
+        service_name: &str,
+    ) -> Result<DeviceService> {
+        self.get_device_service_snapshot(peer_id, true)
+            .await?
+            .snapshot
+            .services
+            .into_iter()
+            .find(|service| service.name == service_name)
+            .ok_or_else(|| anyhow::anyhow!("remote service not found: {}", service_name))
+    }
+
     pub async fn list_service_accesses(
         &self,
         peer_id: Option<PeerId>,
@@ -498,7 +725,7 @@ impl FungiDaemon {
         let _local_preferences_guard = local_preferences_lock.lock().await;
 
         let peer_filter = peer_id.map(|peer_id| peer_id.to_string());
-        let mut grouped = BTreeMap::<(String, String), Vec<ServiceAccessEndpoint>>::new();
+        let mut grouped = BTreeMap::<(String, String), ServiceAccess>::new();
 
         for record in self.local_preferences()?.records {
             if let Some(peer_filter) = &peer_filter
@@ -512,24 +739,30 @@ impl FungiDaemon {
                     record.remote_peer_id.clone(),
                     record.remote_service_name.clone(),
                 ))
-                .or_default()
+                .or_insert_with(|| ServiceAccess {
+                    peer_id: record.remote_peer_id.clone(),
+                    service_name: record.remote_service_name.clone(),
+                    endpoints: Vec::new(),
+                    group: record.group.clone(),
+                    routing: record.routing,
+                })
+                .endpoints
                 .push(ServiceAccessEndpoint {
                     name: record.remote_service_port_name,
                     protocol: String::new(),
                     local_host: record.local_host,
                     local_port: record.local_port,
+                    bandwidth_limit: record.bandwidth_limit,
                 });
         }
 
         let mut services = grouped
-            .into_iter()
-            .map(|((peer_id, service_name), mut endpoints)| {
-                endpoints.sort_by(|left, right| left.name.cmp(&right.name));
-                ServiceAccess {
-                    peer_id,
-                    service_name,
-                    endpoints,
-                }
+            .into_values()
+            .map(|mut access| {
+                access
+                    .endpoints
+                    .sort_by(|left, right| left.name.cmp(&right.name));
+                access
             })
             .collect::<Vec<_>>();
         services.sort_by(|left, right| {
@@ -540,12 +773,105 @@ impl FungiDaemon {
         Ok(services)
     }
 
+    /// Creates or updates `device_id`'s own access to `service_name` published by `peer_id`.
+    pub async fn remote_attach_service_access(
+        &self,
+        device_id: PeerId,
+        peer_id: PeerId,
+        service_name: String,
+        entry: Option<String>,
+        local_port: Option<u16>,
+    ) -> Result<ServiceControlResponse> {
+        self.service_control_protocol_control()
+            .attach_peer_service_access(device_id, peer_id, service_name, entry, local_port)
+            .await
+    }
+
+    /// Forgets `device_id`'s own saved access to `service_name` published by `peer_id`.
+    pub async fn remote_forget_service_access(
+        &self,
+        device_id: PeerId,
+        peer_id: PeerId,
+        service_name: String,
+    ) -> Result<ServiceControlResponse> {
+        self.service_control_protocol_control()
+            .forget_peer_service_access(device_id, peer_id, service_name)
+            .await
+    }
+
+    /// Lists the service accesses saved on `device_id`.
+    pub async fn remote_list_service_accesses(
+        &self,
+        device_id: PeerId,
+    ) -> Result<Vec<ServiceAccess>> {
+        let response = self
+            .service_control_protocol_control()
+            .list_peer_service_accesses(device_id)
+            .await?;
+        let service_accesses_json = response
+            .service_accesses_json
+            .ok_or_else(|| anyhow::anyhow!("remote device returned no service accesses"))?;
+        serde_json::from_str(&service_accesses_json)
+            .map_err(|error| anyhow::anyhow!("failed to decode remote service accesses: {error}"))
+    }
+
     fn local_preferences(&self) -> Result<LocalPreferenceCache> {
         let fungi_dir = self.config_fungi_dir()?;
         LocalPreferenceCache::apply_from_dir(&fungi_dir)
     }
 }
 
+#[async_trait::async_trait]
+impl ServiceAccessOperations for FungiDaemon {
+    async fn attach(
+        &self,
+        peer_id: PeerId,
+        service: String,
+        entry: Option<String>,
+        local_port: Option<u16>,
+    ) -> Result<ServiceAccess> {
+        self.attach_service_access(peer_id, service, entry, local_port)
+            .await
+    }
+
+    async fn forget(&self, peer_id: PeerId, service: String) -> Result<()> {
+        self.forget_service_access(peer_id, service).await
+    }
+
+    async fn list(&self) -> Result<Vec<ServiceAccess>> {
+        self.list_service_accesses(None).await
+    }
+}
+
+/// Services installed from the same recipe share a definition id; older devices only report
+/// names, so those match by name.
+fn service_definition_key(service: &DeviceService) -> &str {
+    service.definition_id.as_deref().unwrap_or(&service.name)
+}
+
+fn group_forwarding_targets(record: &LocalServicePreference) -> Vec<ForwardingTarget> {
+    record
+        .group
+        .iter()
+        .map(|member| ForwardingTarget {
+            peer_id: member.peer_id.clone(),
+            protocol: service_port_protocol(&member.service_name, &record.remote_service_port_name),
+        })
+        .collect()
+}
+
+fn forwarding_rule_matches(
+    rule: &ForwardingRule,
+    record: &LocalServicePreference,
+    remote_protocol: &str,
+) -> bool {
+    rule.local_host == record.local_host
+        && rule.local_port == record.local_port
+        && rule.remote_protocol.as_deref() == Some(remote_protocol)
+        && rule.fallback_targets == group_forwarding_targets(record)
+        && rule.routing == record.routing
+}
+
 fn find_active_rule(
     active_rules: &[(String, ForwardingRule)],
     remote_peer_id: &str,
@@ -716,6 +1042,97 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn service_group_rejects_primary_device_and_clears_to_single_target() -> Result<()> {
+        let service_name = "grouped";
+        let (client, server) =
+            setup_access_test_pair(service_name, vec![("main", free_tcp_port()?)]).await?;
+        let peer_id = server.peer_id();
+
+        client
+            .daemon()
+            .attach_service_access(peer_id, service_name.to_string(), None, None)
+            .await?;
+
+        let error = client
+            .daemon()
+            .set_service_access_group(
+                peer_id,
+                service_name.to_string(),
+                vec![peer_id],
+                ServiceRouting::Failover,
+            )
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("primary device"));
+
+        let access = client
+            .daemon()
+            .set_service_access_group(
+                peer_id,
+                service_name.to_string(),
+                Vec::new(),
+                ServiceRouting::RoundRobin,
+            )
+            .await?;
+        assert!(access.group.is_empty());
+        assert_eq!(access.routing, ServiceRouting::RoundRobin);
+
+        let rules = client
+            .daemon()
+            .get_service_access_forwarding_rules()
+            .into_iter()
+            .filter(|(_, rule)| rule.remote_service_name.as_deref() == Some(service_name))
+            .map(|(_, rule)| rule)
+            .collect::<Vec<_>>();
+        assert_eq!(rules.len(), 1);
+        assert!(rules[0].fallback_targets.is_empty());
+        assert_eq!(rules[0].routing, ServiceRouting::RoundRobin);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn trusted_peer_manages_accesses_saved_on_another_device() -> Result<()> {
+        let service_name = "managed-access";
+        let (client, server) =
+            setup_access_test_pair(service_name, vec![("main", free_tcp_port()?)]).await?;
+        let (client_peer_id, server_peer_id) = (client.peer_id(), server.peer_id());
+        let local_port = free_tcp_port()?;
+
+        server
+            .daemon()
+            .remote_attach_service_access(
+                client_peer_id,
+                server_peer_id,
+                service_name.to_string(),
+                None,
+                Some(local_port),
+            )
+            .await?;
+
+        let accesses = server
+            .daemon()
+            .remote_list_service_accesses(client_peer_id)
+            .await?;
+        assert_eq!(accesses.len(), 1);
+        assert_eq!(accesses[0].peer_id, server_peer_id.to_string());
+        assert_eq!(accesses[0].endpoints[0].local_port, local_port);
+        assert_eq!(client.daemon().list_service_accesses(None).await?.len(), 1);
+
+        server
+            .daemon()
+            .remote_forget_service_access(client_peer_id, server_peer_id, service_name.to_string())
+            .await?;
+        assert!(
+            client
+                .daemon()
+                .list_service_accesses(None)
+                .await?
+                .is_empty()
+        );
+        Ok(())
+    }
+
     async fn setup_access_test_pair(
         service_name: &str,
         entries: Vec<(&str, u16)>,
@@ -790,6 +1207,9 @@ mod tests {
             entrypoint: Vec::new(),
             working_dir: None,
             labels: BTreeMap::new(),
+            on_demand: None,
+            container: Default::default(),
+            job: Default::default(),
         }
     }
 
@@ -0,0 +1,141 @@
+use anyhow::{Result, bail};
+use libp2p::PeerId;
+
+use crate::FungiDaemon;
+use crate::controls::ServiceBackupInfo;
+use crate::runtime::{ServiceExecInput, ServiceExecRequest, collect_exec_output};
+
+#[derive(Debug, Clone, Default)]
+pub struct ServiceBackupOptions {
+    /// Stop the service while its data is archived and start it again afterwards.
+    pub stop: bool,
+    /// Command run inside the service before archiving, e.g. to dump a database into appdata.
+    pub pre_backup_hook: Vec<String>,
+    /// Device to keep the backup on; this device when unset.
+    pub target: Option<PeerId>,
+}
+
+impl FungiDaemon {
+    /// Archives the appdata, manifest and persisted state of a local service and keeps the
+    /// archive on this device or on a backup target.
+    pub async fn backup_service(
+        &self,
+        name: String,
+        options: ServiceBackupOptions,
+    ) -> Result<ServiceBackupInfo> {
+        let running = self
+            .runtime_control()
+            .inspect_by_name(&name)
+            .await?
+            .status
+            .is_running();
+        if !options.pre_backup_hook.is_empty() {
+            if !running {
+                bail!("service '{name}' must be running to run its pre-backup hook");
+            }
+            self.run_pre_backup_hook(&name, options.pre_backup_hook)
+                .await?;
+        }
+
+        let fungi_home = self.config_fungi_dir()?;
+        let manifest_yaml = self
+            .runtime_control()
+            .portable_manifest_yaml(&name, &fungi_home)?;
+        let local_service_id = self.runtime_control().local_service_id(&name)?;
+        let stop = options.stop && running;
+        if stop {
+            self.stop_service_by_name(name.clone()).await?;
+        }
+        let packed = self
+            .service_backup_protocol_control()
+            .pack(&local_service_id, &manifest_yaml)
+            .await;
+        let restarted = if stop {
+            self.start_service_by_name(name.clone()).await
+        } else {
+            Ok(())
+        };
+
+        let device = options
+            .target
+            .unwrap_or_else(|| self.swarm_control().local_peer_id());
+        let info = self
+            .service_backup_protocol_control()
+            .store(device, &name, packed?)
+            .await?;
+        if let Err(error) = restarted {
+            bail!(
+                "backup {} was stored but service '{name}' failed to start again: {error:#}",
+                info.backup_id
+            );
+        }
+        Ok(info)
+    }
+
+    /// Backups this device keeps on `device`, or on itself when unset, newest first.
+    pub async fn list_service_backups(
+        &self,
+        name: Option<String>,
+        device: Option<PeerId>,
+    ) -> Result<Vec<ServiceBackupInfo>> {
+        let device = device.unwrap_or_else(|| self.swarm_control().local_peer_id());
+        self.service_backup_protocol_control()
+            .list(device, name.as_deref())
+            .await
+    }
+
+    /// Restores a backup of `name` kept on `device`, the newest one unless `backup_id` is given.
+    pub async fn restore_service_backup(
+        &self,
+        name: String,
+        device: Option<PeerId>,
+        backup_id: Option<String>,
+        start: bool,
+    ) -> Result<ServiceBackupInfo> {
+        let local_peer_id = self.swarm_control().local_peer_id();
+        let device = device.unwrap_or(local_peer_id);
+        let backup = self
+            .service_backup_protocol_control()
+            .fetch(device, &name, backup_id.as_deref())
+            .await?;
+        // A manifest this device cannot vouch for is applied as one sent by the backup target,
+        // under the rules for remote services.
+        let applied_by = if backup.manifest_verified {
+            local_peer_id
+        } else {
+            log::warn!(
+                "Restoring backup {} of service '{name}' from {device} without a matching receipt",
+                backup.info.backup_id
+            );
+            device
+        };
+        self.service_control_protocol_control()
+            .restore_service_backup(&backup, start, Some(applied_by))
+            .await?;
+        Ok(backup.info)
+    }
+
+    async fn run_pre_backup_hook(&self, name: &str, command: Vec<String>) -> Result<()> {
+        let session = self
+            .exec_service(ServiceExecRequest {
+                service: name.to_string(),
+                command,
+                tty: false,
+                size: None,
+            })
+            .await?;
+        let _ = session.input.send(ServiceExecInput::CloseStdin).await;
+        let (_stdout, stderr, exit) = collect_exec_output(session).await;
+        if let Some(error) = exit.error {
+            bail!("pre-backup hook failed: {error}");
+        }
+        match exit.exit_code {
+            Some(0) => Ok(()),
+            code => bail!(
+                "pre-backup hook exited with {}: {}",
+                code.map_or_else(|| "no status".to_string(), |code| format!("code {code}")),
+                String::from_utf8_lossy(&stderr).trim()
+            ),
+        }
+    }
+}
@@ -1,5 +1,8 @@
 use std::time::SystemTime;
 
+use fungi_config::{
+    bandwidth::BandwidthLimit, local_preferences::ServiceGroupMember, tcp_tunneling::ServiceRouting,
+};
 use fungi_swarm::{ExternalAddressCandidateRecord, PeerAddressRecord, RelayEndpointStatusRecord};
 use serde::{Deserialize, Serialize};
 
@@ -153,6 +156,10 @@ pub struct ServiceAccess {
     pub peer_id: String,
     pub service_name: String,
     pub endpoints: Vec<ServiceAccessEndpoint>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub group: Vec<ServiceGroupMember>,
+    #[serde(default, skip_serializing_if = "ServiceRouting::is_failover")]
+    pub routing: ServiceRouting,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -161,4 +168,117 @@ pub struct ServiceAccessEndpoint {
     pub protocol: String,
     pub local_host: String,
     pub local_port: u16,
+    #[serde(default, skip_serializing_if = "BandwidthLimit::is_unlimited")]
+    pub bandwidth_limit: BandwidthLimit,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum DiagnosticStatus {
+    Pass,
+    Warn,
+    Fail,
+    Skip,
+}
+
+impl DiagnosticStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DiagnosticStatus::Pass => "pass",
+            DiagnosticStatus::Warn => "warn",
+            DiagnosticStatus::Fail => "fail",
+            DiagnosticStatus::Skip => "skip",
+        }
+    }
+}
+
+/// Local NAT behaviour inferred from external address observations.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum NatKind {
+    /// A listen address or confirmed external address is publicly reachable.
+    Public,
+    /// Every remote observed the same external port, so hole punching is likely to work.
+    EndpointIndependent,
+    /// Remotes observed different external ports for the same IP, so hole punching is unlikely.
+    EndpointDependent,
+    Unknown,
+}
+
+impl NatKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NatKind::Public => "public",
+            NatKind::EndpointIndependent => "endpoint-independent",
+            NatKind::EndpointDependent => "endpoint-dependent",
+            NatKind::Unknown => "unknown",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ConnectivityVerdict {
+    Direct,
+    HolePunched,
+    RelayOnly,
+    Unreachable,
+}
+
+impl ConnectivityVerdict {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConnectivityVerdict::Direct => "direct",
+            ConnectivityVerdict::HolePunched => "hole-punched",
+            ConnectivityVerdict::RelayOnly => "relay-only",
+            ConnectivityVerdict::Unreachable => "unreachable",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DiagnosticCheck {
+    pub name: String,
+    pub status: DiagnosticStatus,
+    pub summary: String,
+    pub details: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PeerDiagnosticsReport {
+    pub peer_id: String,
+    pub peer_name: String,
+    pub checks: Vec<DiagnosticCheck>,
+    pub nat_kind: NatKind,
+    pub verdict: ConnectivityVerdict,
+    pub remediation: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct WakeAttempt {
+    /// Device that broadcast the packet; `None` when this node sent it directly.
+    pub relay_peer_id: Option<String>,
+    pub relay_name: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct WakeDeviceReport {
+    pub peer_id: String,
+    pub mac_addresses: Vec<String>,
+    pub attempts: Vec<WakeAttempt>,
+    /// Whether the device reconnected; only checked when the caller asked to wait.
+    pub online: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct HttpGatewayListing {
+    pub index_url: String,
+    pub services: Vec<HttpGatewayService>,
+}
+
+#[derive(Debug, Clone)]
+pub struct HttpGatewayService {
+    pub peer_id: String,
+    pub device_name: String,
+    pub service_name: String,
+    pub entry: String,
+    pub url: String,
 }
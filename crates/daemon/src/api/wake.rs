@@ -0,0 +1,255 @@
+use std::time::Duration;
+
+use anyhow::{Result, bail};
+use libp2p::PeerId;
+
+use crate::FungiDaemon;
+use crate::controls::wake_on_lan::{known_mac_addresses, send_magic_packets, shares_lan};
+
+use super::types::{WakeAttempt, WakeDeviceReport};
+
+/// How long a woken device gets to boot and reconnect before we give up.
+const WAKE_ONLINE_TIMEOUT: Duration = Duration::from_secs(90);
+/// Waking before attaching a service access holds up the caller, so it gives up sooner unless
+/// asked to wait longer, up to [`MAX_ATTACH_WAKE_TIMEOUT`].
+const DEFAULT_ATTACH_WAKE_TIMEOUT: Duration = Duration::from_secs(30);
+const MAX_ATTACH_WAKE_TIMEOUT: Duration = Duration::from_secs(300);
+const WAKE_RECONNECT_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum WakeRoute {
+    Local,
+    Relay {
+        peer_id: PeerId,
+        name: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct WakeRelayCandidate {
+    peer_id: PeerId,
+    name: Option<String>,
+    private_ips: Vec<String>,
+}
+
+impl FungiDaemon {
+    /// Sends Wake-on-LAN packets for a saved device, directly when this node shares its LAN and
+    /// otherwise through connected trusted devices on that LAN.
+    pub async fn wake_device(&self, peer_id: PeerId, wait: bool) -> Result<WakeDeviceReport> {
+        self.wake_device_within(peer_id, wait.then_some(WAKE_ONLINE_TIMEOUT))
+            .await
+    }
+
+    /// Like [`Self::wake_device`], waiting up to `wait` for the device to come online.
+    async fn wake_device_within(
+        &self,
+        peer_id: PeerId,
+        wait: Option<Duration>,
+    ) -> Result<WakeDeviceReport> {
+        if peer_id == self.swarm_control().local_peer_id() {
+            bail!("cannot wake the local device");
+        }
+
+        let saved = self.devices_get_peer(peer_id);
+        let discovered = self.mdns_control().get_device(&peer_id);
+        let mac_addresses = known_mac_addresses(saved.as_ref(), discovered.as_ref());
+        if mac_addresses.is_empty() {
+            bail!(
+                "device {peer_id} has not advertised a MAC address; it must be seen on the LAN while awake first"
+            );
+        }
+
+        let target_ips = discovered
+            .as_ref()
+            .map(|device| device.private_ips.clone())
+            .filter(|ips| !ips.is_empty())
+            .or_else(|| saved.as_ref().map(|device| device.private_ips.clone()))
+            .unwrap_or_default();
+        let local_ips = fungi_util::get_local_ip().into_iter().collect::<Vec<_>>();
+        let state = self.swarm_control().state();
+        let relays = self
+            .list_trusted_devices()
+            .into_iter()
+            .filter(|device| device.peer_id != peer_id)
+            .filter(|device| state.connection_len_for_peer(&device.peer_id) > 0)
+            .map(|device| WakeRelayCandidate {
+                peer_id: device.peer_id,
+                name: device.name,
+                private_ips: device.private_ips,
+            })
+            .collect::<Vec<_>>();
+
+        let routes = plan_wake_routes(&target_ips, discovered.is_some(), &local_ips, relays);
+        let mut attempts = Vec::new();
+        for route in routes {
+            let (relay_peer_id, relay_name, result) = match route {
+                WakeRoute::Local => (None, None, send_magic_packets(&mac_addresses).await),
+                WakeRoute::Relay {
+                    peer_id: relay_peer_id,
+                    name,
+                } => {
+                    let result = self
+                        .service_control_protocol_control()
+                        .wake_via_peer(relay_peer_id, peer_id)
+                        .await
+                        .map(|_| mac_addresses.len());
+                    (Some(relay_peer_id.to_string()), name, result)
+                }
+            };
+            attempts.push(WakeAttempt {
+                relay_peer_id,
+                relay_name,
+                error: result.err().map(|error| error.to_string()),
+            });
+        }
+
+        if attempts.iter().all(|attempt| attempt.error.is_some()) {
+            let errors = attempts
+                .iter()
+                .filter_map(|attempt| attempt.error.as_deref())
+                .collect::<Vec<_>>()
+                .join("; ");
+            bail!("failed to send wake packets for device {peer_id}: {errors}");
+        }
+
+        let online = match wait {
+            Some(timeout) => self.wait_for_device_online(peer_id, timeout).await,
+            None => false,
+        };
+        Ok(WakeDeviceReport {
+            peer_id: peer_id.to_string(),
+            mac_addresses,
+            attempts,
+            online,
+        })
+    }
+
+    /// Wakes `peer_id` if it can't be reached and waits for it to reconnect, by default for
+    /// [`DEFAULT_ATTACH_WAKE_TIMEOUT`].
+    pub async fn ensure_device_awake(
+        &self,
+        peer_id: PeerId,
+        timeout: Option<Duration>,
+    ) -> Result<()> {
+        if self.try_connect_device(peer_id).await {
+            return Ok(());
+        }
+
+        let timeout = timeout
+            .unwrap_or(DEFAULT_ATTACH_WAKE_TIMEOUT)
+            .min(MAX_ATTACH_WAKE_TIMEOUT);
+        let report = self.wake_device_within(peer_id, Some(timeout)).await?;
+        if !report.online {
+            bail!(
+                "device {peer_id} did not come online within {}s of being woken",
+                timeout.as_secs()
+            );
+        }
+        Ok(())
+    }
+
+    /// A dial in flight when `timeout` runs out is abandoned, so the wait never overruns it.
+    async fn wait_for_device_online(&self, peer_id: PeerId, timeout: Duration) -> bool {
+        let reconnect = async {
+            loop {
+                tokio::time::sleep(WAKE_RECONNECT_INTERVAL).await;
+                if self.try_connect_device(peer_id).await {
+                    return;
+                }
+            }
+        };
+        tokio::time::timeout(timeout, reconnect).await.is_ok()
+    }
+
+    async fn try_connect_device(&self, peer_id: PeerId) -> bool {
+        self.swarm_control()
+            .connect(peer_id)
+            .await
+            .is_ok_and(|connections| !connections.is_empty())
+    }
+}
+
+/// Picks who should broadcast the magic packet. Routes known to share the target's LAN win;
+/// when none is known to, every route is tried since a stray packet on another LAN is harmless.
+fn plan_wake_routes(
+    target_ips: &[String],
+    target_seen_locally: bool,
+    local_ips: &[String],
+    relays: Vec<WakeRelayCandidate>,
+) -> Vec<WakeRoute> {
+    let local_on_lan = target_seen_locally || shares_lan(local_ips, target_ips);
+    let (lan_relays, other_relays): (Vec<_>, Vec<_>) = relays
+        .into_iter()
+        .partition(|relay| shares_lan(&relay.private_ips, target_ips));
+
+    let lan_known = local_on_lan || !lan_relays.is_empty();
+    let mut routes = Vec::new();
+    if local_on_lan || !lan_known {
+        routes.push(WakeRoute::Local);
+    }
+    let relays = if lan_known { lan_relays } else { other_relays };
+    routes.extend(relays.into_iter().map(|relay| WakeRoute::Relay {
+        peer_id: relay.peer_id,
+        name: relay.name,
+    }));
+    routes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn relay(ip: &str) -> WakeRelayCandidate {
+        WakeRelayCandidate {
+            peer_id: PeerId::random(),
+            name: None,
+            private_ips: vec![ip.to_string()],
+        }
+    }
+
+    fn ips(ip: &str) -> Vec<String> {
+        vec![ip.to_string()]
+    }
+
+    #[test]
+    fn local_node_on_target_lan_sends_directly() {
+        let routes = plan_wake_routes(
+            &ips("192.168.1.20"),
+            false,
+            &ips("192.168.1.5"),
+            vec![relay("10.0.0.3")],
+        );
+
+        assert_eq!(routes, vec![WakeRoute::Local]);
+    }
+
+    #[test]
+    fn remote_node_relays_through_device_on_target_lan() {
+        let home_relay = relay("192.168.1.8");
+        let home_relay_id = home_relay.peer_id;
+        let routes = plan_wake_routes(
+            &ips("192.168.1.20"),
+            false,
+            &ips("10.8.0.2"),
+            vec![relay("172.16.0.4"), home_relay],
+        );
+
+        assert_eq!(
+            routes,
+            vec![WakeRoute::Relay {
+                peer_id: home_relay_id,
+                name: None
+            }]
+        );
+    }
+
+    #[test]
+    fn unknown_lan_tries_every_route() {
+        let first = relay("172.16.0.4");
+        let second = relay("10.0.0.3");
+        let routes = plan_wake_routes(&[], false, &ips("10.8.0.2"), vec![first, second]);
+
+        assert_eq!(routes.len(), 3);
+        assert_eq!(routes[0], WakeRoute::Local);
+    }
+}
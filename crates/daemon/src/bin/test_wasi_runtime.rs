@@ -59,9 +59,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     )?;
 
     let source = if let Some(path) = args.wasm_path.clone() {
-        ServiceSource::WasmtimeFile { component: path }
+        ServiceSource::WasmtimeFile {
+            component: path,
+            sha256: None,
+        }
     } else if let Some(url) = args.wasm_url.clone() {
-        ServiceSource::WasmtimeUrl { url }
+        ServiceSource::WasmtimeUrl { url, sha256: None }
     } else {
         return Err("either --wasm-path or --wasm-url is required".into());
     };
@@ -77,6 +80,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         mounts: vec![ServiceMount {
             host_path: args.mount_dir.clone(),
             runtime_path: args.mount_target.clone(),
+            read_only: false,
         }],
         ports: vec![ServicePort {
             name: None,
@@ -89,6 +93,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         entrypoint: Vec::new(),
         working_dir: None,
         labels: BTreeMap::new(),
+        on_demand: None,
+        container: Default::default(),
+        job: Default::default(),
     };
 
     let _ = runtime.remove(RuntimeKind::Wasmtime, &args.name).await;
@@ -112,6 +119,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             &args.name,
             &ServiceLogsOptions {
                 tail: Some("50".into()),
+                since: None,
             },
         )
         .await?;
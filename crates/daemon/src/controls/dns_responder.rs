@@ -0,0 +1,471 @@
+use std::{
+    collections::BTreeMap,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use anyhow::{Context, Result};
+use fungi_config::{
+    devices::{DeviceInfo, DevicesConfig},
+    local_preferences::{LocalPreferenceCache, LocalServicePreference},
+};
+use hickory_proto::{
+    op::{Message, MessageType, OpCode, ResponseCode},
+    rr::{
+        Name, RData, Record, RecordType,
+        rdata::{A, AAAA, SRV},
+    },
+};
+use parking_lot::Mutex;
+use tokio::{net::UdpSocket, task::JoinHandle};
+
+use super::{
+    http_gateway::{HttpGatewayControl, HttpGatewayRoute},
+    service_names::dns_label,
+};
+
+/// Answers are rebuilt from local state on every query, so clients should not hold on to them
+/// for long after an access is re-attached on a different port.
+const RECORD_TTL: u32 = 5;
+
+/// Large enough for any query a stub resolver sends over UDP with EDNS.
+const MAX_DATAGRAM_BYTES: usize = 4096;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ZoneRecord {
+    Address(IpAddr),
+    Service { port: u16, target: String },
+}
+
+/// Snapshot of every name the responder can answer, keyed by lowercase name without the
+/// trailing dot.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct DnsZone {
+    domain: String,
+    records: BTreeMap<String, Vec<ZoneRecord>>,
+}
+
+/// Where the HTTP gateway serves a web service, used as the SRV fallback for services that
+/// have no local access attached.
+struct GatewayRoutes<'a> {
+    domain: &'a str,
+    listen_address: SocketAddr,
+    routes: &'a [HttpGatewayRoute],
+}
+
+/// Loopback UDP DNS server for `<device>.<domain>` and `<service>.<device>.<domain>`.
+///
+/// Device names resolve to the LAN addresses saved for the device. Service names resolve to
+/// the local host of their attached access, with SRV records carrying the local ports recorded
+/// in the local preference cache, or the HTTP gateway for web services without an access.
+#[derive(Clone)]
+pub struct DnsResponderControl {
+    domain: String,
+    devices: Arc<Mutex<DevicesConfig>>,
+    fungi_dir: PathBuf,
+    http_gateway: Option<HttpGatewayControl>,
+    local_addr: Arc<Mutex<Option<SocketAddr>>>,
+}
+
+impl DnsResponderControl {
+    pub(crate) fn new(
+        domain: String,
+        devices: Arc<Mutex<DevicesConfig>>,
+        fungi_dir: PathBuf,
+        http_gateway: Option<HttpGatewayControl>,
+    ) -> Self {
+        Self {
+            domain: domain.trim_matches('.').to_ascii_lowercase(),
+            devices,
+            fungi_dir,
+            http_gateway,
+            local_addr: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    pub async fn start(&self, listen_address: &str) -> Result<JoinHandle<()>> {
+        let socket = UdpSocket::bind(listen_address)
+            .await
+            .with_context(|| format!("failed to bind DNS responder to {listen_address}"))?;
+        let local_addr = socket.local_addr()?;
+        *self.local_addr.lock() = Some(local_addr);
+        log::info!(
+            "DNS responder for .{} listening on udp://{local_addr}",
+            self.domain
+        );
+
+        let this = self.clone();
+        Ok(tokio::spawn(async move {
+            let mut buf = vec![0u8; MAX_DATAGRAM_BYTES];
+            loop {
+                let (len, client_addr) = match socket.recv_from(&mut buf).await {
+                    Ok(received) => received,
+                    Err(error) => {
+                        log::error!("DNS responder failed to receive query: {error}");
+                        continue;
+                    }
+                };
+                let Some(response) = this.respond(&buf[..len]) else {
+                    log::debug!("DNS responder dropped malformed query from {client_addr}");
+                    continue;
+                };
+                if let Err(error) = socket.send_to(&response, client_addr).await {
+                    log::debug!("DNS responder failed to answer {client_addr}: {error}");
+                }
+            }
+        }))
+    }
+
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        *self.local_addr.lock()
+    }
+
+    pub fn domain(&self) -> &str {
+        &self.domain
+    }
+
+    fn respond(&self, query: &[u8]) -> Option<Vec<u8>> {
+        let request = Message::from_vec(query).ok()?;
+        if request.message_type() != MessageType::Query {
+            return None;
+        }
+        self.zone().answer(&request).to_vec().ok()
+    }
+
+    fn zone(&self) -> DnsZone {
+        let devices = self.devices.lock().get_all_devices().to_vec();
+        let preferences = match LocalPreferenceCache::apply_from_dir(&self.fungi_dir) {
+            Ok(cache) => cache.records,
+            Err(error) => {
+                log::warn!("DNS responder failed to read local preferences: {error:#}");
+                Vec::new()
+            }
+        };
+        let gateway_routes = self
+            .http_gateway
+            .as_ref()
+            .and_then(|gateway| Some((gateway.domain(), gateway.local_addr()?, gateway.routes())));
+
+        DnsZone::build(
+            &self.domain,
+            &devices,
+            &preferences,
+            gateway_routes
+                .as_ref()
+                .map(|(domain, listen_address, routes)| GatewayRoutes {
+                    domain,
+                    listen_address: *listen_address,
+                    routes,
+                }),
+        )
+    }
+}
+
+impl DnsZone {
+    fn build(
+        domain: &str,
+        devices: &[DeviceInfo],
+        preferences: &[LocalServicePreference],
+        gateway: Option<GatewayRoutes<'_>>,
+    ) -> Self {
+        let mut zone = Self {
+            domain: domain.to_string(),
+            records: BTreeMap::new(),
+        };
+
+        let mut device_labels = BTreeMap::new();
+        for device in devices {
+            let Some(label) = device.name.as_deref().map(dns_label) else {
+                continue;
+            };
+            if label.is_empty() {
+                continue;
+            }
+            device_labels.insert(device.peer_id.to_string(), label.clone());
+            let name = format!("{label}.{domain}");
+            for ip in &device.private_ips {
+                if let Ok(ip) = ip.parse::<IpAddr>() {
+                    zone.insert(&name, ZoneRecord::Address(ip));
+                }
+            }
+        }
+
+        for preference in preferences {
+            let Some(device_label) = device_labels.get(&preference.remote_peer_id) else {
+                continue;
+            };
+            let service_label = dns_label(&preference.remote_service_name);
+            let entry_label = dns_label(&preference.remote_service_port_name);
+            if service_label.is_empty() || entry_label.is_empty() {
+                continue;
+            }
+            let host = format!("{service_label}.{device_label}.{domain}");
+            zone.insert(
+                &host,
+                ZoneRecord::Address(local_host_ip(&preference.local_host)),
+            );
+            let srv = ZoneRecord::Service {
+                port: preference.local_port,
+                target: host.clone(),
+            };
+            zone.insert(&format!("_{entry_label}._tcp.{host}"), srv.clone());
+            zone.insert(&host, srv);
+        }
+
+        if let Some(gateway) = gateway {
+            for route in gateway.routes {
+                let host = format!("{}.{domain}", route.host_label);
+                let name = format!("_http._tcp.{host}");
+                if zone.records.contains_key(&name) {
+                    continue;
+                }
+                zone.insert(
+                    &name,
+                    ZoneRecord::Service {
+                        port: gateway.listen_address.port(),
+                        target: format!("{}.{}", route.host_label, gateway.domain),
+                    },
+                );
+            }
+        }
+
+        zone
+    }
+
+    fn insert(&mut self, name: &str, record: ZoneRecord) {
+        let records = self.records.entry(name.to_string()).or_default();
+        if !records.contains(&record) {
+            records.push(record);
+        }
+    }
+
+    /// Whether `name` is the zone apex, holds records, or has descendants that do; such names
+    /// answer NODATA rather than NXDOMAIN when the requested type is missing.
+    fn contains_name(&self, name: &str) -> bool {
+        name == self.domain
+            || self.records.keys().any(|owner| {
+                owner == name
+                    || owner
+                        .strip_suffix(name)
+                        .is_some_and(|prefix| prefix.ends_with('.'))
+            })
+    }
+
+    fn answer(&self, request: &Message) -> Message {
+        if request.op_code() != OpCode::Query {
+            return Message::error_msg(request.id(), request.op_code(), ResponseCode::NotImp);
+        }
+        let [query] = request.queries() else {
+            return Message::error_msg(request.id(), request.op_code(), ResponseCode::FormErr);
+        };
+
+        let mut response = Message::new();
+        response
+            .set_id(request.id())
+            .set_message_type(MessageType::Response)
+            .set_op_code(OpCode::Query)
+            .set_recursion_desired(request.recursion_desired())
+            .add_query(query.clone());
+
+        let name = query.name().to_lowercase().to_ascii();
+        let name = name.trim_end_matches('.');
+        let in_zone = name == self.domain
+            || name
+                .strip_suffix(self.domain.as_str())
+                .is_some_and(|prefix| prefix.ends_with('.'));
+        if !in_zone {
+            response.set_response_code(ResponseCode::Refused);
+            return response;
+        }
+
+        response.set_authoritative(true);
+        if !self.contains_name(name) {
+            response.set_response_code(ResponseCode::NXDomain);
+            return response;
+        }
+
+        let query_type = query.query_type();
+        for record in self.records.get(name).into_iter().flatten() {
+            if let Some(answer) = to_record(query.name(), record, query_type) {
+                response.add_answer(answer);
+            }
+            if let ZoneRecord::Service { target, .. } = record
+                && matches!(query_type, RecordType::SRV | RecordType::ANY)
+            {
+                for additional in self.records.get(target.as_str()).into_iter().flatten() {
+                    if let Ok(target_name) = Name::from_ascii(format!("{target}."))
+                        && let Some(additional) =
+                            to_record(&target_name, additional, RecordType::ANY)
+                        && additional.record_type() != RecordType::SRV
+                    {
+                        response.add_additional(additional);
+                    }
+                }
+            }
+        }
+        response
+    }
+}
+
+fn to_record(name: &Name, record: &ZoneRecord, query_type: RecordType) -> Option<Record> {
+    let rdata = match (record, query_type) {
+        (ZoneRecord::Address(IpAddr::V4(ip)), RecordType::A | RecordType::ANY) => {
+            RData::A(A::from(*ip))
+        }
+        (ZoneRecord::Address(IpAddr::V6(ip)), RecordType::AAAA | RecordType::ANY) => {
+            RData::AAAA(AAAA::from(*ip))
+        }
+        (ZoneRecord::Service { port, target }, RecordType::SRV | RecordType::ANY) => {
+            let target = Name::from_ascii(format!("{target}.")).ok()?;
+            RData::SRV(SRV::new(0, 0, *port, target))
+        }
+        _ => return None,
+    };
+    Some(Record::from_rdata(name.clone(), RECORD_TTL, rdata))
+}
+
+/// Accesses bind to an IP in practice; anything else (e.g. `localhost`) is treated as loopback.
+fn local_host_ip(local_host: &str) -> IpAddr {
+    local_host
+        .parse()
+        .unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use fungi_config::{bandwidth::BandwidthLimit, local_preferences::LocalPortSource};
+    use hickory_proto::op::Query;
+    use libp2p::PeerId;
+
+    use super::*;
+
+    fn device(name: &str, ips: &[&str]) -> DeviceInfo {
+        let mut device = DeviceInfo::new_unknown(PeerId::random());
+        device.name = Some(name.to_string());
+        device.private_ips = ips.iter().map(|ip| ip.to_string()).collect();
+        device
+    }
+
+    fn preference(
+        device: &DeviceInfo,
+        service: &str,
+        entry: &str,
+        port: u16,
+    ) -> LocalServicePreference {
+        LocalServicePreference {
+            remote_peer_id: device.peer_id.to_string(),
+            remote_service_name: service.to_string(),
+            remote_service_port_name: entry.to_string(),
+            local_host: "127.0.0.1".to_string(),
+            local_port: port,
+            local_port_source: LocalPortSource::Auto,
+            bandwidth_limit: BandwidthLimit::default(),
+            group: Vec::new(),
+            routing: Default::default(),
+        }
+    }
+
+    fn query(zone: &DnsZone, name: &str, record_type: RecordType) -> Message {
+        let mut request = Message::new();
+        request
+            .set_id(7)
+            .add_query(Query::query(Name::from_str(name).unwrap(), record_type));
+        zone.answer(&request)
+    }
+
+    fn sample_zone() -> DnsZone {
+        let nas = device("Home NAS", &["192.168.1.20", "fd00::20"]);
+        let routes = [HttpGatewayRoute {
+            host_label: "photos.home-nas".to_string(),
+            peer_id: nas.peer_id,
+            device_name: "Home NAS".to_string(),
+            service_name: "photos".to_string(),
+            entry: "web".to_string(),
+            protocol: "/fungi/service/photos/web/0.1.0".to_string(),
+            path: None,
+        }];
+        DnsZone::build(
+            "fungi",
+            std::slice::from_ref(&nas),
+            &[preference(&nas, "ssh", "ssh", 2222)],
+            Some(GatewayRoutes {
+                domain: "fungi.localhost",
+                listen_address: "127.0.0.1:5480".parse().unwrap(),
+                routes: &routes,
+            }),
+        )
+    }
+
+    #[test]
+    fn device_names_resolve_to_saved_lan_addresses() {
+        let zone = sample_zone();
+
+        let response = query(&zone, "home-nas.fungi.", RecordType::A);
+        assert_eq!(response.response_code(), ResponseCode::NoError);
+        assert!(response.authoritative());
+        assert_eq!(
+            response.answers()[0].data(),
+            &RData::A(A::new(192, 168, 1, 20))
+        );
+
+        let response = query(&zone, "HOME-NAS.fungi.", RecordType::AAAA);
+        assert_eq!(
+            response.answers()[0].data(),
+            &RData::AAAA(AAAA::from_str("fd00::20").unwrap())
+        );
+    }
+
+    #[test]
+    fn service_srv_points_at_local_access_port() {
+        let zone = sample_zone();
+
+        let response = query(&zone, "_ssh._tcp.ssh.home-nas.fungi.", RecordType::SRV);
+        let RData::SRV(srv) = response.answers()[0].data() else {
+            panic!("expected SRV answer");
+        };
+        assert_eq!(srv.port(), 2222);
+        assert_eq!(srv.target().to_ascii(), "ssh.home-nas.fungi.");
+        assert_eq!(
+            response.additionals()[0].data(),
+            &RData::A(A::new(127, 0, 0, 1))
+        );
+
+        let response = query(&zone, "ssh.home-nas.fungi.", RecordType::A);
+        assert_eq!(
+            response.answers()[0].data(),
+            &RData::A(A::new(127, 0, 0, 1))
+        );
+    }
+
+    #[test]
+    fn web_services_without_access_fall_back_to_the_gateway() {
+        let zone = sample_zone();
+
+        let response = query(&zone, "_http._tcp.photos.home-nas.fungi.", RecordType::SRV);
+        let RData::SRV(srv) = response.answers()[0].data() else {
+            panic!("expected SRV answer");
+        };
+        assert_eq!(srv.port(), 5480);
+        assert_eq!(srv.target().to_ascii(), "photos.home-nas.fungi.localhost.");
+
+        // The service name only has descendants, so it exists without holding an address.
+        let response = query(&zone, "photos.home-nas.fungi.", RecordType::A);
+        assert_eq!(response.response_code(), ResponseCode::NoError);
+        assert!(response.answers().is_empty());
+    }
+
+    #[test]
+    fn unknown_and_foreign_names_are_rejected() {
+        let zone = sample_zone();
+
+        let response = query(&zone, "laptop.fungi.", RecordType::A);
+        assert_eq!(response.response_code(), ResponseCode::NXDomain);
+
+        let response = query(&zone, "example.com.", RecordType::A);
+        assert_eq!(response.response_code(), ResponseCode::Refused);
+        assert!(!response.authoritative());
+    }
+}
@@ -1,23 +1,67 @@
 use std::{
-    env,
+    collections::{BTreeMap, BTreeSet},
+    env, fs,
     path::{Path, PathBuf},
     sync::Arc,
 };
 
-use anyhow::Result;
-use fungi_config::runtime::Runtime as RuntimeConfig;
+use anyhow::{Context, Result};
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use fungi_config::{
+    paths::FungiPaths,
+    runtime::{
+        DockerRegistryCredential, DockerSecurity, DockerSecurityRules, Runtime as RuntimeConfig,
+    },
+};
 use fungi_docker_agent::{
-    AgentPolicy, ContainerDetails, ContainerLogs, ContainerSpec, DockerAgent, LogsOptions,
+    AgentPolicy, ContainerDetails, ContainerLogs, ContainerSpec, ContainerStats, DockerAgent,
+    EngineFlavor, EngineInfo, ExecSession, ExecSpec, ImagePullOptions, ImagePullProgress,
+    LogsOptions, RegistryAuth, SecurityRules,
 };
 use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use crate::{runtime::ServiceOrigin, secrets::SecretStore};
 
 const MANAGED_LABEL_KEY: &str = "managed_by";
 const MANAGED_LABEL_VALUE: &str = "fungi";
+const MANAGED_IMAGES_FILE: &str = "docker-images.json";
+const DOCKER_HUB_REGISTRY: &str = "docker.io";
+const PULL_PROGRESS_CAPACITY: usize = 256;
 
 #[derive(Clone)]
 pub struct DockerControl {
     policy: Arc<Mutex<AgentPolicy>>,
     default_allowed_host_paths: Vec<PathBuf>,
+    registries: Arc<Mutex<RegistrySettings>>,
+    security: Arc<Mutex<DockerSecurity>>,
+    secrets: SecretStore,
+    managed_images: ManagedImages,
+    pull_progress: broadcast::Sender<ImagePullProgress>,
+    engine: Arc<Mutex<Option<EngineInfo>>>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct RegistrySettings {
+    credentials: Vec<DockerRegistryCredential>,
+    docker_config_path: Option<PathBuf>,
+}
+
+impl RegistrySettings {
+    fn from_config(config: &RuntimeConfig) -> Self {
+        Self {
+            credentials: config.docker_registries.clone(),
+            docker_config_path: config.docker_config_path.clone(),
+        }
+    }
+}
+
+/// An image removed (or, on a dry run, removable) by [`DockerControl::prune_images`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PrunedImage {
+    pub image: String,
+    pub size_bytes: u64,
 }
 
 impl DockerControl {
@@ -34,6 +78,7 @@ impl DockerControl {
         };
 
         let default_allowed_host_paths = RuntimeConfig::default_allowed_host_paths(fungi_home);
+        let (pull_progress, _) = broadcast::channel(PULL_PROGRESS_CAPACITY);
 
         Ok(Some(Self {
             policy: Arc::new(Mutex::new(build_agent_policy(
@@ -42,6 +87,16 @@ impl DockerControl {
                 &default_allowed_host_paths,
             ))),
             default_allowed_host_paths,
+            registries: Arc::new(Mutex::new(RegistrySettings::from_config(config))),
+            security: Arc::new(Mutex::new(config.docker_security.clone())),
+            secrets: SecretStore::new(fungi_home),
+            managed_images: ManagedImages::new(
+                FungiPaths::from_fungi_home(fungi_home)
+                    .artifacts_root()
+                    .join(MANAGED_IMAGES_FILE),
+            ),
+            pull_progress,
+            engine: Arc::new(Mutex::new(None)),
         }))
     }
 
@@ -49,15 +104,156 @@ impl DockerControl {
         let socket_path = self.policy.lock().socket_path.clone();
         *self.policy.lock() =
             build_agent_policy(config, socket_path, &self.default_allowed_host_paths);
+        *self.registries.lock() = RegistrySettings::from_config(config);
+        *self.security.lock() = config.docker_security.clone();
         Ok(())
     }
 
     fn agent(&self) -> DockerAgent {
-        DockerAgent::new(self.policy.lock().clone())
+        self.agent_with_policy(self.policy.lock().clone())
+    }
+
+    fn agent_with_policy(&self, policy: AgentPolicy) -> DockerAgent {
+        let agent = DockerAgent::new(policy);
+        match self.engine.lock().as_ref() {
+            Some(engine) => agent.with_flavor(engine.flavor),
+            None => agent,
+        }
+    }
+
+    /// Asks the engine behind the socket what it is and remembers the answer, so later requests
+    /// account for Podman's compat API differences.
+    pub async fn detect_engine(&self) -> Result<EngineInfo> {
+        let engine = self.agent().detect_engine().await?;
+        *self.engine.lock() = Some(engine.clone());
+        Ok(engine)
+    }
+
+    /// The engine found by [`DockerControl::detect_engine`], if it has run successfully.
+    pub fn engine(&self) -> Option<EngineInfo> {
+        self.engine.lock().clone()
+    }
+
+    /// The detected engine flavour, or a guess from the socket path before detection.
+    pub fn engine_flavor(&self) -> EngineFlavor {
+        self.engine()
+            .map(|engine| engine.flavor)
+            .unwrap_or_else(|| {
+                EngineFlavor::guess_from_socket_path(&self.policy.lock().socket_path)
+            })
     }
 
-    pub async fn create_container(&self, spec: &ContainerSpec) -> Result<ContainerDetails> {
-        Ok(self.agent().create_container(spec).await?)
+    /// Receives progress of every image pull this control performs, tagged with the image.
+    pub fn subscribe_pull_progress(&self) -> broadcast::Receiver<ImagePullProgress> {
+        self.pull_progress.subscribe()
+    }
+
+    /// Pulls the spec's image when it is missing locally, then creates the container under the
+    /// security rules for `origin`. Images pulled here are recorded so
+    /// [`DockerControl::prune_images`] can clean them up later.
+    pub async fn create_container(
+        &self,
+        spec: &ContainerSpec,
+        origin: ServiceOrigin,
+    ) -> Result<ContainerDetails> {
+        let mut policy = self.policy.lock().clone();
+        policy.security = {
+            let security = self.security.lock();
+            match origin {
+                ServiceOrigin::Local => security_rules(&security.local, true),
+                ServiceOrigin::Remote => security_rules(&security.remote, false),
+            }
+        };
+        // Reject the spec before pulling an image for it.
+        policy.validate_create_spec(spec)?;
+        if self.engine().is_none()
+            && let Err(error) = self.detect_engine().await
+        {
+            log::debug!("Failed to detect container engine: {error:#}");
+        }
+        let agent = self.agent_with_policy(policy);
+        let sender = self.pull_progress.clone();
+        let options = ImagePullOptions {
+            auth: self.registry_auth(&spec.image, origin)?,
+            progress: Some(Arc::new(move |progress| {
+                let _ = sender.send(progress);
+            })),
+        };
+        if agent.ensure_image(&spec.image, &options).await? {
+            self.managed_images.insert(&spec.image)?;
+        }
+        Ok(agent.create_container(spec).await?)
+    }
+
+    /// Removes fungi-pulled images that no container uses anymore.
+    pub async fn prune_images(&self, dry_run: bool) -> Result<Vec<PrunedImage>> {
+        let agent = self.agent();
+        let mut pruned = Vec::new();
+        for image in self.managed_images.list()? {
+            if agent.image_in_use(&image).await? {
+                continue;
+            }
+            let details = match agent.inspect_image(&image).await {
+                Ok(details) => details,
+                Err(error) if error.is_not_found() => {
+                    if !dry_run {
+                        self.managed_images.remove(&image)?;
+                    }
+                    continue;
+                }
+                Err(error) => return Err(error.into()),
+            };
+            if !dry_run {
+                agent.remove_image(&image).await?;
+                self.managed_images.remove(&image)?;
+            }
+            pruned.push(PrunedImage {
+                image,
+                size_bytes: details.size,
+            });
+        }
+        Ok(pruned)
+    }
+
+    /// Remote services only get credentials that opted in with `allow_remote`; the docker
+    /// config fallback is local-only.
+    fn registry_auth(&self, image: &str, origin: ServiceOrigin) -> Result<Option<RegistryAuth>> {
+        let settings = self.registries.lock().clone();
+        let registry = image_registry(image);
+        if let Some(credential) = settings
+            .credentials
+            .iter()
+            .find(|credential| normalize_registry(&credential.registry) == registry)
+        {
+            if origin == ServiceOrigin::Remote && !credential.allow_remote {
+                return Ok(None);
+            }
+            let password = self
+                .secrets
+                .get(&credential.password_secret)?
+                .with_context(|| {
+                    format!(
+                        "registry {registry} uses unknown secret '{}'",
+                        credential.password_secret
+                    )
+                })?;
+            return Ok(Some(RegistryAuth {
+                username: credential.username.clone(),
+                password,
+                server_address: registry,
+            }));
+        }
+
+        let Some(config_path) = settings.docker_config_path else {
+            return Ok(None);
+        };
+        if origin == ServiceOrigin::Remote {
+            return Ok(None);
+        }
+        let bytes = fs::read(&config_path)
+            .with_context(|| format!("Failed to read docker config: {}", config_path.display()))?;
+        docker_config_auth(&bytes, &registry)
+            .with_context(|| format!("Failed to parse docker config: {}", config_path.display()))
     }
 
     pub async fn start_container(&self, id_or_name: &str) -> Result<()> {
@@ -83,6 +279,150 @@ impl DockerControl {
     ) -> Result<ContainerLogs> {
         Ok(self.agent().container_logs(id_or_name, options).await?)
     }
+
+    pub async fn container_stats(&self, id_or_name: &str) -> Result<ContainerStats> {
+        Ok(self.agent().container_stats(id_or_name).await?)
+    }
+
+    pub async fn exec(&self, id_or_name: &str, spec: &ExecSpec) -> Result<ExecSession> {
+        Ok(self.agent().exec(id_or_name, spec).await?)
+    }
+
+    pub async fn resize_exec(&self, exec_id: &str, rows: u16, cols: u16) -> Result<()> {
+        Ok(self.agent().resize_exec(exec_id, rows, cols).await?)
+    }
+
+    pub async fn exec_exit_code(&self, exec_id: &str) -> Result<Option<i32>> {
+        Ok(self.agent().exec_exit_code(exec_id).await?)
+    }
+}
+
+/// Resolves configured rules; unset `allow_*` flags fall back to `allow_by_default`.
+fn security_rules(rules: &DockerSecurityRules, allow_by_default: bool) -> SecurityRules {
+    SecurityRules {
+        allow_privileged: rules.allow_privileged.unwrap_or(allow_by_default),
+        allow_host_network: rules.allow_host_network.unwrap_or(allow_by_default),
+        allow_unconfined: rules.allow_unconfined.unwrap_or(allow_by_default),
+        allow_named_volumes: rules.allow_named_volumes.unwrap_or(allow_by_default),
+        require_drop_all_caps: rules.require_drop_all_caps,
+        allowed_cap_add: rules.allowed_cap_add.clone(),
+    }
+}
+
+/// Image references pulled on behalf of fungi-managed containers, persisted as a JSON list.
+#[derive(Clone)]
+struct ManagedImages {
+    path: PathBuf,
+    lock: Arc<Mutex<()>>,
+}
+
+impl ManagedImages {
+    fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    fn list(&self) -> Result<Vec<String>> {
+        let _guard = self.lock.lock();
+        Ok(self.load()?.into_iter().collect())
+    }
+
+    fn insert(&self, image: &str) -> Result<()> {
+        let _guard = self.lock.lock();
+        let mut images = self.load()?;
+        if images.insert(image.to_string()) {
+            self.save(&images)?;
+        }
+        Ok(())
+    }
+
+    fn remove(&self, image: &str) -> Result<()> {
+        let _guard = self.lock.lock();
+        let mut images = self.load()?;
+        if images.remove(image) {
+            self.save(&images)?;
+        }
+        Ok(())
+    }
+
+    fn load(&self) -> Result<BTreeSet<String>> {
+        if !self.path.exists() {
+            return Ok(BTreeSet::new());
+        }
+        let bytes = fs::read(&self.path)
+            .with_context(|| format!("Failed to read managed images: {}", self.path.display()))?;
+        serde_json::from_slice(&bytes)
+            .with_context(|| format!("Failed to parse managed images: {}", self.path.display()))
+    }
+
+    fn save(&self, images: &BTreeSet<String>) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, serde_json::to_vec_pretty(images)?)
+            .with_context(|| format!("Failed to write managed images: {}", self.path.display()))
+    }
+}
+
+/// Registry host an image reference is pulled from, following Docker's rule that the first path
+/// component is a registry only if it looks like a host name.
+fn image_registry(image: &str) -> String {
+    match image.split_once('/') {
+        Some((first, _)) if first.contains('.') || first.contains(':') || first == "localhost" => {
+            normalize_registry(first)
+        }
+        _ => DOCKER_HUB_REGISTRY.to_string(),
+    }
+}
+
+fn normalize_registry(registry: &str) -> String {
+    let host = registry
+        .trim()
+        .trim_start_matches("https://")
+        .trim_start_matches("http://");
+    let host = host.split('/').next().unwrap_or(host);
+    match host {
+        "index.docker.io" | "registry-1.docker.io" | "registry.hub.docker.com" => {
+            DOCKER_HUB_REGISTRY.to_string()
+        }
+        host => host.to_string(),
+    }
+}
+
+/// Looks up inline `auths` credentials for `registry` in a Docker CLI `config.json`.
+fn docker_config_auth(bytes: &[u8], registry: &str) -> Result<Option<RegistryAuth>> {
+    #[derive(Deserialize)]
+    struct DockerConfigFile {
+        #[serde(default)]
+        auths: BTreeMap<String, DockerConfigAuth>,
+    }
+
+    #[derive(Deserialize)]
+    struct DockerConfigAuth {
+        #[serde(default)]
+        auth: Option<String>,
+    }
+
+    let config: DockerConfigFile = serde_json::from_slice(bytes)?;
+    let Some(encoded) = config
+        .auths
+        .into_iter()
+        .find(|(key, _)| normalize_registry(key) == registry)
+        .and_then(|(_, entry)| entry.auth)
+    else {
+        return Ok(None);
+    };
+    let decoded = String::from_utf8(STANDARD.decode(encoded.trim())?)?;
+    let (username, password) = decoded
+        .split_once(':')
+        .context("docker config auth entry is not `user:password`")?;
+    Ok(Some(RegistryAuth {
+        username: username.to_string(),
+        password: password.to_string(),
+        server_address: registry.to_string(),
+    }))
 }
 
 pub fn detect_socket_path(config: &RuntimeConfig) -> Option<PathBuf> {
@@ -105,6 +445,7 @@ fn build_agent_policy(
         managed_label_value: MANAGED_LABEL_VALUE.into(),
         allowed_host_paths,
         allowed_ports: Vec::new(),
+        security: SecurityRules::default(),
     }
 }
 
@@ -115,13 +456,16 @@ fn resolve_socket_path(explicit: Option<&Path>) -> Option<PathBuf> {
 
     let docker_host = env::var("DOCKER_HOST").ok();
 
-    if let Some(path) = docker_host
-        .as_deref()
-        .and_then(|host| host.strip_prefix("unix://"))
+    // Podman documents CONTAINER_HOST for the same purpose as DOCKER_HOST.
+    for host in [docker_host.clone(), env::var("CONTAINER_HOST").ok()]
+        .into_iter()
+        .flatten()
     {
-        let candidate = PathBuf::from(path);
-        if docker_endpoint_available(&candidate) {
-            return Some(candidate);
+        if let Some(path) = host.strip_prefix("unix://") {
+            let candidate = PathBuf::from(path);
+            if docker_endpoint_available(&candidate) {
+                return Some(candidate);
+            }
         }
     }
 
@@ -134,14 +478,10 @@ fn resolve_socket_path(explicit: Option<&Path>) -> Option<PathBuf> {
 
     #[cfg(unix)]
     {
-        let home_socket = env::var("HOME")
-            .ok()
-            .map(PathBuf::from)
-            .map(|home| home.join(".docker/run/docker.sock"));
-
-        [home_socket, Some(PathBuf::from("/var/run/docker.sock"))]
+        let home = env::var("HOME").ok().map(PathBuf::from);
+        let runtime_dir = env::var("XDG_RUNTIME_DIR").ok().map(PathBuf::from);
+        unix_socket_candidates(home.as_deref(), runtime_dir.as_deref())
             .into_iter()
-            .flatten()
             .find(|candidate| docker_endpoint_available(candidate))
     }
 
@@ -152,6 +492,23 @@ fn resolve_socket_path(explicit: Option<&Path>) -> Option<PathBuf> {
     }
 }
 
+/// Default sockets in order of preference: Docker Desktop, the system Docker daemon, rootless
+/// Docker, rootless Podman and finally the system Podman service.
+#[cfg(unix)]
+fn unix_socket_candidates(home: Option<&Path>, runtime_dir: Option<&Path>) -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+    if let Some(home) = home {
+        candidates.push(home.join(".docker/run/docker.sock"));
+    }
+    candidates.push(PathBuf::from("/var/run/docker.sock"));
+    if let Some(runtime_dir) = runtime_dir {
+        candidates.push(runtime_dir.join("docker.sock"));
+        candidates.push(runtime_dir.join("podman/podman.sock"));
+    }
+    candidates.push(PathBuf::from("/run/podman/podman.sock"));
+    candidates
+}
+
 #[cfg(unix)]
 fn docker_endpoint_available(path: &Path) -> bool {
     path.exists()
@@ -205,6 +562,169 @@ mod tests {
     use fungi_config::runtime::Runtime;
     use tempfile::TempDir;
 
+    #[test]
+    fn resolves_image_registries() {
+        assert_eq!(image_registry("nginx:1.27"), "docker.io");
+        assert_eq!(image_registry("library/nginx"), "docker.io");
+        assert_eq!(image_registry("ghcr.io/enbop/app:1"), "ghcr.io");
+        assert_eq!(image_registry("localhost:5000/app"), "localhost:5000");
+        assert_eq!(
+            normalize_registry("https://index.docker.io/v1/"),
+            "docker.io"
+        );
+    }
+
+    #[test]
+    fn reads_inline_docker_config_auths() {
+        let config = format!(
+            r#"{{"auths":{{"https://index.docker.io/v1/":{{"auth":"{}"}},"ghcr.io":{{}}}}}}"#,
+            STANDARD.encode("me:hub-token")
+        );
+        let auth = docker_config_auth(config.as_bytes(), "docker.io")
+            .unwrap()
+            .unwrap();
+        assert_eq!(auth.username, "me");
+        assert_eq!(auth.password, "hub-token");
+        assert_eq!(auth.server_address, "docker.io");
+        assert!(
+            docker_config_auth(config.as_bytes(), "ghcr.io")
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn remote_services_only_get_opted_in_registry_credentials() {
+        let temp_dir = TempDir::new().unwrap();
+        let socket_path = temp_dir.path().join("fungi-docker-test.sock");
+        std::fs::File::create(&socket_path).unwrap();
+        let docker_config = temp_dir.path().join("config.json");
+        std::fs::write(
+            &docker_config,
+            format!(
+                r#"{{"auths":{{"docker.io":{{"auth":"{}"}}}}}}"#,
+                STANDARD.encode("me:hub-token")
+            ),
+        )
+        .unwrap();
+        let credential = |allow_remote| DockerRegistryCredential {
+            registry: "ghcr.io".into(),
+            username: "me".into(),
+            password_secret: "missing-secret".into(),
+            allow_remote,
+        };
+        let config = Runtime {
+            docker_socket_path: Some(socket_path),
+            docker_registries: vec![credential(false)],
+            docker_config_path: Some(docker_config),
+            ..Runtime::default()
+        };
+        let control = DockerControl::from_config(&config, temp_dir.path())
+            .unwrap()
+            .unwrap();
+
+        let image = "ghcr.io/enbop/private:1";
+        assert!(control.registry_auth(image, ServiceOrigin::Local).is_err());
+        assert!(
+            control
+                .registry_auth(image, ServiceOrigin::Remote)
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            control
+                .registry_auth("nginx:1.27", ServiceOrigin::Local)
+                .unwrap()
+                .is_some()
+        );
+        assert!(
+            control
+                .registry_auth("nginx:1.27", ServiceOrigin::Remote)
+                .unwrap()
+                .is_none()
+        );
+
+        control.registries.lock().credentials = vec![credential(true)];
+        assert!(control.registry_auth(image, ServiceOrigin::Remote).is_err());
+    }
+
+    #[test]
+    fn remote_rules_deny_unset_privileges() {
+        let rules = DockerSecurityRules {
+            allow_host_network: Some(true),
+            require_drop_all_caps: true,
+            ..DockerSecurityRules::default()
+        };
+        let remote = security_rules(&rules, false);
+        assert!(!remote.allow_privileged);
+        assert!(remote.allow_host_network);
+        assert!(remote.require_drop_all_caps);
+        assert!(security_rules(&DockerSecurityRules::default(), true).allow_privileged);
+    }
+
+    #[tokio::test]
+    async fn remote_services_cannot_mount_named_volumes() {
+        let temp_dir = TempDir::new().unwrap();
+        let socket_path = temp_dir.path().join("fungi-docker-test.sock");
+        std::fs::File::create(&socket_path).unwrap();
+        let config = Runtime {
+            docker_socket_path: Some(socket_path),
+            ..Runtime::default()
+        };
+        let control = DockerControl::from_config(&config, temp_dir.path())
+            .unwrap()
+            .unwrap();
+        let spec = ContainerSpec {
+            image: "nginx:1.27".into(),
+            volumes: vec![fungi_docker_agent::VolumeMount {
+                name: "other-service-data".into(),
+                container_path: "/data".into(),
+                read_only: true,
+            }],
+            ..Default::default()
+        };
+
+        let error = control
+            .create_container(&spec, ServiceOrigin::Remote)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("named volumes are not allowed"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn falls_back_to_podman_sockets_after_docker() {
+        let candidates = unix_socket_candidates(
+            Some(Path::new("/home/me")),
+            Some(Path::new("/run/user/1000")),
+        );
+        assert_eq!(
+            candidates,
+            vec![
+                PathBuf::from("/home/me/.docker/run/docker.sock"),
+                PathBuf::from("/var/run/docker.sock"),
+                PathBuf::from("/run/user/1000/docker.sock"),
+                PathBuf::from("/run/user/1000/podman/podman.sock"),
+                PathBuf::from("/run/podman/podman.sock"),
+            ]
+        );
+        assert_eq!(
+            EngineFlavor::guess_from_socket_path(&candidates[3]),
+            EngineFlavor::Podman
+        );
+    }
+
+    #[test]
+    fn tracks_managed_images() {
+        let temp_dir = TempDir::new().unwrap();
+        let images = ManagedImages::new(temp_dir.path().join("artifacts/docker-images.json"));
+        images.insert("nginx:1.27").unwrap();
+        images.insert("nginx:1.27").unwrap();
+        images.insert("redis:7").unwrap();
+        images.remove("nginx:1.27").unwrap();
+        assert_eq!(images.list().unwrap(), vec!["redis:7".to_string()]);
+    }
+
     #[test]
     fn disabled_docker_returns_no_control() {
         let temp_dir = TempDir::new().unwrap();
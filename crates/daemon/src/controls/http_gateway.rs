@@ -0,0 +1,518 @@
+use std::{
+    fmt::Write as _,
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result, bail};
+use fungi_swarm::SwarmControl;
+use libp2p::{PeerId, StreamProtocol};
+use parking_lot::Mutex;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    task::JoinHandle,
+};
+use tokio_util::compat::FuturesAsyncReadCompatExt;
+
+use crate::ServiceExposeUsageKind;
+
+use super::service_names::{DeviceServicesSource, NamedDeviceServices, dns_label, strip_port};
+
+/// Upper bound for the request line plus headers we buffer before picking a route.
+const MAX_REQUEST_HEAD_BYTES: usize = 16 * 1024;
+/// How long routes built from the device snapshot cache are reused. A page load opens many
+/// connections, and each would otherwise re-read every cached snapshot.
+const ROUTES_TTL: Duration = Duration::from_secs(3);
+
+/// A remote web service reachable through the gateway as `<host_label>.<domain>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpGatewayRoute {
+    pub host_label: String,
+    pub peer_id: PeerId,
+    pub device_name: String,
+    pub service_name: String,
+    pub entry: String,
+    pub protocol: String,
+    pub path: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum GatewayTarget {
+    Index,
+    Service(String),
+    Unknown,
+}
+
+/// Routes plain HTTP requests by their Host header to remote web services, opening a fresh
+/// stream to the device for every client connection.
+#[derive(Clone)]
+pub struct HttpGatewayControl {
+    swarm_control: SwarmControl,
+    domain: String,
+    routes: Arc<RouteCache>,
+    local_addr: Arc<Mutex<Option<SocketAddr>>>,
+}
+
+/// Gateway routes, rebuilt from the device services at most once per [`ROUTES_TTL`].
+struct RouteCache {
+    device_services: DeviceServicesSource,
+    built: Mutex<Option<(Instant, Vec<HttpGatewayRoute>)>>,
+}
+
+impl RouteCache {
+    fn new(device_services: DeviceServicesSource) -> Self {
+        Self {
+            device_services,
+            built: Mutex::new(None),
+        }
+    }
+
+    fn routes(&self) -> Vec<HttpGatewayRoute> {
+        let mut built = self.built.lock();
+        if let Some((built_at, routes)) = built.as_ref()
+            && built_at.elapsed() < ROUTES_TTL
+        {
+            return routes.clone();
+        }
+        let mut routes = (self.device_services)()
+            .iter()
+            .flat_map(device_web_routes)
+            .collect::<Vec<_>>();
+        routes.sort_by(|left, right| left.host_label.cmp(&right.host_label));
+        routes.dedup_by(|left, right| left.host_label == right.host_label);
+        *built = Some((Instant::now(), routes.clone()));
+        routes
+    }
+}
+
+impl HttpGatewayControl {
+    pub(crate) fn new(
+        swarm_control: SwarmControl,
+        domain: String,
+        device_services: DeviceServicesSource,
+    ) -> Self {
+        Self {
+            swarm_control,
+            domain: domain.trim_matches('.').to_ascii_lowercase(),
+            routes: Arc::new(RouteCache::new(device_services)),
+            local_addr: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    pub async fn start(&self, listen_address: &str) -> Result<JoinHandle<()>> {
+        let listener = TcpListener::bind(listen_address)
+            .await
+            .with_context(|| format!("failed to bind HTTP gateway to {listen_address}"))?;
+        let local_addr = listener.local_addr()?;
+        *self.local_addr.lock() = Some(local_addr);
+        log::info!(
+            "HTTP gateway listening on http://{}:{}",
+            self.domain,
+            local_addr.port()
+        );
+
+        let this = self.clone();
+        Ok(tokio::spawn(async move {
+            loop {
+                let (tcp_stream, client_addr) = match listener.accept().await {
+                    Ok(accepted) => accepted,
+                    Err(error) => {
+                        log::error!("HTTP gateway failed to accept connection: {error}");
+                        continue;
+                    }
+                };
+                let this = this.clone();
+                tokio::spawn(async move {
+                    if let Err(error) = this.handle_connection(tcp_stream).await {
+                        log::debug!("HTTP gateway connection from {client_addr} failed: {error}");
+                    }
+                });
+            }
+        }))
+    }
+
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        *self.local_addr.lock()
+    }
+
+    pub fn domain(&self) -> &str {
+        &self.domain
+    }
+
+    pub fn routes(&self) -> Vec<HttpGatewayRoute> {
+        self.routes.routes()
+    }
+
+    /// The browser URL of `route`, or `None` while the gateway isn't listening.
+    pub fn route_url(&self, route: &HttpGatewayRoute) -> Option<String> {
+        let port = self.local_addr()?.port();
+        Some(route_url(route, &self.domain, &format!(":{port}")))
+    }
+
+    async fn handle_connection(&self, mut tcp_stream: TcpStream) -> Result<()> {
+        let head = read_request_head(&mut tcp_stream).await?;
+        let Some(host) = request_host(&head) else {
+            return respond(&mut tcp_stream, 400, "Bad Request", "Missing Host header.").await;
+        };
+        let port_suffix = host_port_suffix(&host);
+
+        let label = match gateway_target(&host, &self.domain) {
+            GatewayTarget::Index => {
+                let body = index_page(&self.routes(), &self.domain, &port_suffix);
+                return respond_html(&mut tcp_stream, 200, "OK", &body).await;
+            }
+            GatewayTarget::Service(label) => label,
+            GatewayTarget::Unknown => {
+                let message = format!("{host} is not served by this gateway.");
+                return respond(&mut tcp_stream, 404, "Not Found", &message).await;
+            }
+        };
+
+        let Some(route) = self
+            .routes()
+            .into_iter()
+            .find(|route| route.host_label == label)
+        else {
+            let message = format!(
+                "No web service is known as {label}. Open http://{}{port_suffix}/ for the list.",
+                self.domain
+            );
+            return respond(&mut tcp_stream, 404, "Not Found", &message).await;
+        };
+        // Same rule as the service proxy: only devices allowed to connect in can be reached.
+        if !self
+            .swarm_control
+            .state()
+            .incoming_allowed_peers()
+            .read()
+            .contains(&route.peer_id)
+        {
+            let message = format!("Device {} is not trusted.", route.device_name);
+            return respond(&mut tcp_stream, 403, "Forbidden", &message).await;
+        }
+
+        let protocol = StreamProtocol::try_from_owned(route.protocol.clone())
+            .map_err(|error| anyhow::anyhow!("invalid service protocol: {error}"))?;
+        let p2p_stream = match self
+            .swarm_control
+            .open_stream(route.peer_id, protocol)
+            .await
+        {
+            Ok((stream, _stream_observation_handle, _connection_id)) => stream,
+            Err(error) => {
+                let message = format!(
+                    "Could not reach {} on {}: {error}",
+                    route.service_name, route.device_name
+                );
+                return respond(&mut tcp_stream, 502, "Bad Gateway", &message).await;
+            }
+        };
+
+        let mut p2p_stream = p2p_stream.compat();
+        p2p_stream.write_all(&head).await?;
+        tokio::io::copy_bidirectional(&mut p2p_stream, &mut tcp_stream).await?;
+        Ok(())
+    }
+}
+
+fn device_web_routes(device: &NamedDeviceServices) -> Vec<HttpGatewayRoute> {
+    let device_label = device.device_label();
+    if device_label.is_empty() {
+        return Vec::new();
+    }
+
+    device
+        .services
+        .iter()
+        .filter_map(|service| {
+            let usage = service.metadata.usage.as_ref()?;
+            if usage.kind != ServiceExposeUsageKind::Web {
+                return None;
+            }
+            let endpoint = service
+                .endpoints
+                .iter()
+                .find(|endpoint| {
+                    matches!(
+                        endpoint.name.to_ascii_lowercase().as_str(),
+                        "web" | "http" | "https"
+                    )
+                })
+                .or_else(|| service.endpoints.first())?;
+            let service_label = dns_label(&service.name);
+            if service_label.is_empty() {
+                return None;
+            }
+            Some(HttpGatewayRoute {
+                host_label: format!("{service_label}.{device_label}"),
+                peer_id: device.peer_id,
+                device_name: device.device_name.clone(),
+                service_name: service.name.clone(),
+                entry: endpoint.name.clone(),
+                protocol: endpoint.protocol.clone(),
+                path: usage.path.clone().filter(|path| !path.is_empty()),
+            })
+        })
+        .collect()
+}
+
+fn route_url(route: &HttpGatewayRoute, domain: &str, port_suffix: &str) -> String {
+    let mut url = format!("http://{}.{domain}{port_suffix}", route.host_label);
+    match route.path.as_deref() {
+        Some(path) if path.starts_with('/') => url.push_str(path),
+        Some(path) => {
+            url.push('/');
+            url.push_str(path);
+        }
+        None => url.push('/'),
+    }
+    url
+}
+
+fn gateway_target(host: &str, domain: &str) -> GatewayTarget {
+    let host = strip_port(host).trim_end_matches('.').to_ascii_lowercase();
+    if host == domain
+        || host == "localhost"
+        || host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse::<IpAddr>()
+            .is_ok()
+    {
+        return GatewayTarget::Index;
+    }
+    match host
+        .strip_suffix(domain)
+        .and_then(|label| label.strip_suffix('.'))
+    {
+        Some(label) if !label.is_empty() => GatewayTarget::Service(label.to_string()),
+        _ => GatewayTarget::Unknown,
+    }
+}
+
+fn host_port_suffix(host: &str) -> String {
+    host[strip_port(host).len()..].to_string()
+}
+
+pub(super) async fn read_request_head(tcp_stream: &mut TcpStream) -> Result<Vec<u8>> {
+    let mut head = Vec::with_capacity(1024);
+    let mut chunk = [0u8; 4096];
+    loop {
+        let read = tcp_stream.read(&mut chunk).await?;
+        if read == 0 {
+            bail!("connection closed before the request headers ended");
+        }
+        head.extend_from_slice(&chunk[..read]);
+        if head.windows(4).any(|window| window == b"\r\n\r\n") {
+            return Ok(head);
+        }
+        if head.len() > MAX_REQUEST_HEAD_BYTES {
+            respond(
+                tcp_stream,
+                431,
+                "Request Header Fields Too Large",
+                "Request headers are too large.",
+            )
+            .await?;
+            bail!("request headers exceed {MAX_REQUEST_HEAD_BYTES} bytes");
+        }
+    }
+}
+
+fn request_host(head: &[u8]) -> Option<String> {
+    let head = String::from_utf8_lossy(head);
+    head.split("\r\n")
+        .skip(1)
+        .take_while(|line| !line.is_empty())
+        .find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            name.trim()
+                .eq_ignore_ascii_case("host")
+                .then(|| value.trim().to_string())
+        })
+        .filter(|host| !host.is_empty())
+}
+
+fn index_page(routes: &[HttpGatewayRoute], domain: &str, port_suffix: &str) -> String {
+    let mut body = String::from(
+        "<!doctype html>\n<html><head><meta charset=\"utf-8\"><title>Fungi services</title></head>\n<body>\n<h1>Fungi web services</h1>\n",
+    );
+    if routes.is_empty() {
+        body.push_str(
+            "<p>No web services are known yet. List a device's services with <code>fungi service list --device NAME</code> to discover them.</p>\n",
+        );
+    } else {
+        body.push_str("<ul>\n");
+        for route in routes {
+            let _ = writeln!(
+                body,
+                "<li><a href=\"{url}\">{service}</a> on {device}</li>",
+                url = html_escape(&route_url(route, domain, port_suffix)),
+                service = html_escape(&route.service_name),
+                device = html_escape(&route.device_name),
+            );
+        }
+        body.push_str("</ul>\n");
+    }
+    body.push_str("</body></html>\n");
+    body
+}
+
+fn html_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub(super) async fn respond(
+    tcp_stream: &mut TcpStream,
+    status: u16,
+    reason: &str,
+    message: &str,
+) -> Result<()> {
+    let body = format!(
+        "<!doctype html>\n<html><body><h1>{status} {reason}</h1><p>{}</p></body></html>\n",
+        html_escape(message)
+    );
+    respond_html(tcp_stream, status, reason, &body).await
+}
+
+async fn respond_html(
+    tcp_stream: &mut TcpStream,
+    status: u16,
+    reason: &str,
+    body: &str,
+) -> Result<()> {
+    let response = format!(
+        "HTTP/1.1 {status} {reason}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    tcp_stream.write_all(response.as_bytes()).await?;
+    tcp_stream.shutdown().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        DeviceService, DeviceServiceEndpoint, DeviceServiceMetadata, RuntimeKind,
+        ServiceExposeUsage, ServiceStatus,
+    };
+
+    fn service(name: &str, kind: ServiceExposeUsageKind, entries: &[&str]) -> DeviceService {
+        DeviceService {
+            name: name.to_string(),
+            definition_id: None,
+            runtime: RuntimeKind::Docker,
+            metadata: DeviceServiceMetadata {
+                usage: Some(ServiceExposeUsage {
+                    kind,
+                    path: Some("/ui".to_string()),
+                }),
+                icon_url: None,
+            },
+            endpoints: entries
+                .iter()
+                .map(|entry| DeviceServiceEndpoint {
+                    name: entry.to_string(),
+                    protocol: format!("/fungi/service/{name}/{entry}/0.1.0"),
+                })
+                .collect(),
+            status: ServiceStatus::running(),
+            resources: None,
+        }
+    }
+
+    #[test]
+    fn routes_only_web_services_and_prefer_http_entries() {
+        let routes = device_web_routes(&NamedDeviceServices {
+            peer_id: PeerId::random(),
+            device_name: "Home NAS".to_string(),
+            services: vec![
+                service("Files", ServiceExposeUsageKind::Web, &["admin", "http"]),
+                service("shell", ServiceExposeUsageKind::Ssh, &["ssh"]),
+                service("empty", ServiceExposeUsageKind::Web, &[]),
+            ],
+        });
+
+        assert_eq!(routes.len(), 1);
+        assert_eq!(routes[0].host_label, "files.home-nas");
+        assert_eq!(routes[0].entry, "http");
+        assert_eq!(
+            route_url(&routes[0], "fungi.localhost", ":5480"),
+            "http://files.home-nas.fungi.localhost:5480/ui"
+        );
+    }
+
+    #[test]
+    fn reuses_routes_until_they_expire() {
+        let builds = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counted = builds.clone();
+        let peer_id = PeerId::random();
+        let cache = RouteCache::new(Arc::new(move || {
+            counted.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            vec![NamedDeviceServices {
+                peer_id,
+                device_name: "nas".to_string(),
+                services: vec![service("files", ServiceExposeUsageKind::Web, &["http"])],
+            }]
+        }));
+
+        assert_eq!(cache.routes().len(), 1);
+        assert_eq!(cache.routes().len(), 1);
+        assert_eq!(builds.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        if let Some((built_at, _)) = cache.built.lock().as_mut() {
+            *built_at -= ROUTES_TTL;
+        }
+        assert_eq!(cache.routes().len(), 1);
+        assert_eq!(builds.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn host_header_selects_index_or_service() {
+        let domain = "fungi.localhost";
+        assert_eq!(
+            gateway_target("files.nas.fungi.localhost:5480", domain),
+            GatewayTarget::Service("files.nas".to_string())
+        );
+        assert_eq!(
+            gateway_target("Files.NAS.fungi.localhost.", domain),
+            GatewayTarget::Service("files.nas".to_string())
+        );
+        assert_eq!(
+            gateway_target("fungi.localhost:5480", domain),
+            GatewayTarget::Index
+        );
+        assert_eq!(
+            gateway_target("127.0.0.1:5480", domain),
+            GatewayTarget::Index
+        );
+        assert_eq!(gateway_target("[::1]:5480", domain), GatewayTarget::Index);
+        assert_eq!(
+            gateway_target("example.com", domain),
+            GatewayTarget::Unknown
+        );
+        assert_eq!(
+            gateway_target("notfungi.localhost", domain),
+            GatewayTarget::Unknown
+        );
+    }
+
+    #[test]
+    fn parses_host_header_case_insensitively() {
+        let head =
+            b"GET / HTTP/1.1\r\nUser-Agent: test\r\nhOsT:  files.nas.fungi.localhost:5480 \r\n\r\n";
+
+        let host = request_host(head).unwrap();
+
+        assert_eq!(host, "files.nas.fungi.localhost:5480");
+        assert_eq!(host_port_suffix(&host), ":5480");
+        assert_eq!(request_host(b"GET / HTTP/1.0\r\n\r\n"), None);
+    }
+}
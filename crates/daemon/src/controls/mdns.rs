@@ -9,7 +9,7 @@ use anyhow::Result;
 use fungi_config::devices::DeviceInfo;
 use fungi_swarm::{PeerAddressSource, State};
 use libp2p::PeerId;
-use mdns_sd::{ResolvedService, ServiceDaemon, ServiceEvent, ServiceInfo};
+use mdns_sd::{IfKind, ResolvedService, ServiceDaemon, ServiceEvent, ServiceInfo};
 use parking_lot::Mutex;
 
 const FUNGI_SERVICE_TYPE: &str = "_fungi._tcp.local.";
@@ -30,7 +30,14 @@ impl MdnsControl {
         }
     }
 
-    pub fn start(&self, device_info: DeviceInfo, state: State) -> Result<()> {
+    /// Advertises this device and browses for others on every interface except those matching
+    /// `exclude_interfaces`.
+    pub fn start(
+        &self,
+        device_info: DeviceInfo,
+        state: State,
+        exclude_interfaces: Vec<String>,
+    ) -> Result<()> {
         self.stop();
 
         let (shutdown_tx, shutdown_rx) = mpsc::channel();
@@ -40,7 +47,13 @@ impl MdnsControl {
         let task_handle = Arc::clone(&self.task);
 
         let handle = std::thread::spawn(move || {
-            if let Err(e) = Self::run_mdns_service(device_info, state, local_devices, shutdown_rx) {
+            if let Err(e) = Self::run_mdns_service(
+                device_info,
+                state,
+                exclude_interfaces,
+                local_devices,
+                shutdown_rx,
+            ) {
                 log::error!("mDNS service error: {}", e);
             }
         });
@@ -83,10 +96,16 @@ impl MdnsControl {
     fn run_mdns_service(
         device_info: DeviceInfo,
         state: State,
+        exclude_interfaces: Vec<String>,
         local_devices: Arc<Mutex<HashMap<PeerId, DeviceInfo>>>,
         shutdown_rx: mpsc::Receiver<()>,
     ) -> Result<()> {
         let mdns = ServiceDaemon::new()?;
+        let excluded = fungi_util::net::matching_interface_names(&exclude_interfaces);
+        if !excluded.is_empty() {
+            log::info!("mDNS ignoring interfaces: {}", excluded.join(", "));
+            mdns.disable_interface(excluded.into_iter().map(IfKind::Name).collect::<Vec<_>>())?;
+        }
         let service_type = FUNGI_SERVICE_TYPE;
 
         let current_peer_id = device_info.peer_id;
@@ -105,6 +124,9 @@ impl MdnsControl {
         if !device_info.multiaddrs.is_empty() {
             properties.push(("multiaddrs", device_info.multiaddrs.join(",")));
         }
+        if !device_info.mac_addresses.is_empty() {
+            properties.push(("mac_addresses", device_info.mac_addresses.join(",")));
+        }
 
         let service_info = ServiceInfo::new(
             service_type,
@@ -1,12 +1,30 @@
+mod dns_responder;
 mod docker;
+mod http_gateway;
 pub mod mdns;
 mod node_capabilities;
+mod on_demand;
+mod service_backup;
 mod service_control;
 mod service_discovery;
+mod service_exec;
+mod service_names;
+mod service_proxy;
+mod service_transfer;
 mod tcp_tunneling;
+pub(crate) mod wake_on_lan;
 
-pub use docker::{DockerControl, detect_socket_path};
+pub use dns_responder::DnsResponderControl;
+pub use docker::{DockerControl, PrunedImage, detect_socket_path};
+pub use http_gateway::HttpGatewayControl;
 pub use node_capabilities::NodeCapabilitiesControl;
+pub use on_demand::OnDemandControl;
+pub use service_backup::{ServiceBackupArchive, ServiceBackupInfo, ServiceBackupProtocolControl};
+pub(crate) use service_control::ServiceAccessOperations;
 pub use service_control::ServiceControlProtocolControl;
 pub use service_discovery::ServiceDiscoveryControl;
+pub use service_exec::ServiceExecProtocolControl;
+pub(crate) use service_names::{DeviceServicesSource, cached_named_device_services};
+pub use service_proxy::ServiceProxyControl;
+pub use service_transfer::ServiceTransferProtocolControl;
 pub use tcp_tunneling::TcpTunnelingControl;
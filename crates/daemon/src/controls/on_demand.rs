@@ -0,0 +1,266 @@
+use std::{
+    collections::HashMap,
+    net::{Ipv4Addr, SocketAddr},
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::{Result, bail};
+use fungi_swarm::State;
+use libp2p::StreamProtocol;
+use parking_lot::Mutex;
+use tokio::{
+    net::TcpStream,
+    sync::Mutex as AsyncMutex,
+    task::JoinHandle,
+    time::{Instant, sleep},
+};
+
+use crate::runtime::{RuntimeControl, ServiceManifest, service_expose_endpoint_bindings};
+
+use super::tcp_tunneling::{StreamActivator, TcpTunnelingControl};
+
+/// How long a woken service may take before its port accepts connections.
+const START_TIMEOUT: Duration = Duration::from_secs(60);
+const START_POLL_INTERVAL: Duration = Duration::from_millis(200);
+/// Upper bound for how often the idle watcher samples the stream counts.
+const MAX_IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(15);
+
+struct OnDemandService {
+    protocols: Vec<String>,
+    watcher: JoinHandle<()>,
+}
+
+/// Scale-to-zero for services whose manifest sets `on_demand`.
+///
+/// Every endpoint listener of such a service gets a stream activator that starts the service
+/// through [`RuntimeControl`] and waits for its port before the stream is spliced. A watcher
+/// per service stops it again once `State` has reported no open stream on any of its endpoint
+/// protocols for the configured idle timeout.
+#[derive(Clone)]
+pub struct OnDemandControl {
+    runtime_control: RuntimeControl,
+    tcp_tunneling_control: TcpTunnelingControl,
+    state: State,
+    services: Arc<Mutex<HashMap<String, OnDemandService>>>,
+}
+
+impl OnDemandControl {
+    pub fn new(
+        runtime_control: RuntimeControl,
+        tcp_tunneling_control: TcpTunnelingControl,
+        state: State,
+    ) -> Self {
+        Self {
+            runtime_control,
+            tcp_tunneling_control,
+            state,
+            services: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Mirrors the endpoint listeners of `manifest`: registers the service while its listeners
+    /// are enabled and it is on-demand, and unregisters it otherwise. Listeners are enabled right
+    /// after the service starts, so it counts as awake until its first idle period ends.
+    pub(crate) fn sync_manifest(&self, manifest: &ServiceManifest, enabled: bool) {
+        if enabled {
+            self.register(manifest, true);
+        } else {
+            self.unregister(&manifest.name);
+        }
+    }
+
+    /// Registers a desired-running service whose listeners were restored at startup, where
+    /// `running` reflects whether the runtime still has it up from before the restart.
+    pub(crate) fn restore_manifest(&self, manifest: &ServiceManifest, running: bool) {
+        self.register(manifest, running);
+    }
+
+    fn register(&self, manifest: &ServiceManifest, awake: bool) {
+        self.unregister(&manifest.name);
+        let Some(on_demand) = manifest.on_demand else {
+            return;
+        };
+
+        let endpoints = service_expose_endpoint_bindings(manifest);
+        if endpoints.is_empty() {
+            return;
+        }
+
+        let lifecycle = Arc::new(AsyncMutex::new(awake));
+        for endpoint in &endpoints {
+            let target_addr = SocketAddr::from((Ipv4Addr::LOCALHOST, endpoint.host_port));
+            self.tcp_tunneling_control.set_stream_activator(
+                &endpoint.protocol,
+                Some(self.activator(&manifest.name, target_addr, lifecycle.clone())),
+            );
+        }
+
+        let protocols = endpoints
+            .into_iter()
+            .map(|endpoint| endpoint.protocol)
+            .collect::<Vec<_>>();
+        let watcher = tokio::spawn(self.clone().watch_idle(
+            manifest.name.clone(),
+            protocols.clone(),
+            Duration::from_secs(on_demand.idle_timeout_secs),
+            lifecycle,
+        ));
+        log::info!(
+            "Service '{}' scales to zero after {}s without streams",
+            manifest.name,
+            on_demand.idle_timeout_secs
+        );
+        self.services.lock().insert(
+            manifest.name.clone(),
+            OnDemandService { protocols, watcher },
+        );
+    }
+
+    fn unregister(&self, service_name: &str) {
+        let Some(service) = self.services.lock().remove(service_name) else {
+            return;
+        };
+        service.watcher.abort();
+        for protocol in &service.protocols {
+            self.tcp_tunneling_control
+                .set_stream_activator(protocol, None);
+        }
+    }
+
+    fn activator(
+        &self,
+        service_name: &str,
+        target_addr: SocketAddr,
+        lifecycle: Arc<AsyncMutex<bool>>,
+    ) -> StreamActivator {
+        let this = self.clone();
+        let service_name = service_name.to_string();
+        Arc::new(move || {
+            let this = this.clone();
+            let service_name = service_name.clone();
+            let lifecycle = lifecycle.clone();
+            Box::pin(async move { this.wake(&service_name, target_addr, &lifecycle).await })
+        })
+    }
+
+    async fn wake(
+        &self,
+        service_name: &str,
+        target_addr: SocketAddr,
+        lifecycle: &AsyncMutex<bool>,
+    ) -> Result<()> {
+        // Held until the port accepts so the idle watcher can't stop a service mid-start.
+        let mut awake = lifecycle.lock().await;
+        if *awake {
+            return Ok(());
+        }
+
+        let running = self
+            .runtime_control
+            .inspect_by_name(service_name)
+            .await
+            .is_ok_and(|instance| instance.status.is_running());
+        if !running {
+            log::info!("Starting on-demand service '{service_name}' for an incoming stream");
+            self.runtime_control.start_on_demand(service_name).await?;
+        }
+        wait_for_port(target_addr, START_TIMEOUT).await?;
+        *awake = true;
+        Ok(())
+    }
+
+    async fn watch_idle(
+        self,
+        service_name: String,
+        protocols: Vec<String>,
+        idle_timeout: Duration,
+        lifecycle: Arc<AsyncMutex<bool>>,
+    ) {
+        let check_interval = idle_timeout.min(MAX_IDLE_CHECK_INTERVAL);
+        let mut last_active = Instant::now();
+        loop {
+            sleep(check_interval).await;
+            if self.has_active_streams(&protocols) {
+                last_active = Instant::now();
+                continue;
+            }
+            if last_active.elapsed() < idle_timeout {
+                continue;
+            }
+
+            let mut awake = lifecycle.lock().await;
+            // A stream may have arrived while waiting for the lock.
+            if *awake && !self.has_active_streams(&protocols) {
+                log::info!("Stopping idle on-demand service '{service_name}'");
+                match self.runtime_control.stop_idle(&service_name).await {
+                    Ok(()) => *awake = false,
+                    Err(error) => {
+                        log::warn!("Failed to stop idle service '{service_name}': {error:#}")
+                    }
+                }
+            }
+            last_active = Instant::now();
+        }
+    }
+
+    fn has_active_streams(&self, protocols: &[String]) -> bool {
+        protocols.iter().any(|protocol| {
+            StreamProtocol::try_from_owned(protocol.clone())
+                .is_ok_and(|protocol| !self.state.active_streams_by_protocol(&protocol).is_empty())
+        })
+    }
+}
+
+async fn wait_for_port(target_addr: SocketAddr, timeout: Duration) -> Result<()> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if TcpStream::connect(target_addr).await.is_ok() {
+            return Ok(());
+        }
+        if Instant::now() >= deadline {
+            bail!(
+                "service did not accept connections on {target_addr} within {}s",
+                timeout.as_secs()
+            );
+        }
+        sleep(START_POLL_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn waits_until_the_port_accepts() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let target_addr = listener.local_addr().unwrap();
+        drop(listener);
+
+        let late_listener = tokio::spawn(async move {
+            sleep(Duration::from_millis(300)).await;
+            let listener = TcpListener::bind(target_addr).await.unwrap();
+            let _ = listener.accept().await;
+        });
+
+        wait_for_port(target_addr, Duration::from_secs(5))
+            .await
+            .unwrap();
+        late_listener.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn gives_up_when_the_port_never_opens() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let target_addr = listener.local_addr().unwrap();
+        drop(listener);
+
+        let error = wait_for_port(target_addr, Duration::from_millis(300))
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("did not accept connections"));
+    }
+}
@@ -0,0 +1,1032 @@
+use std::{
+    fs,
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{Context as _, Result, bail};
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use fungi_config::{FungiConfig, paths::FungiPaths, service_backups::ServiceBackups};
+use fungi_stream::IncomingStreams;
+use fungi_swarm::SwarmControl;
+use fungi_util::protocols::FUNGI_SERVICE_BACKUP_PROTOCOL;
+use futures::{AsyncRead, AsyncWrite, StreamExt};
+use libp2p::{
+    PeerId,
+    futures::{AsyncReadExt, AsyncWriteExt},
+};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use tempfile::NamedTempFile;
+use ulid::Ulid;
+
+use super::service_transfer::{append_service_data, unpack_service_archive};
+use crate::integrity::sha256_hex;
+
+const MAX_BACKUP_FRAME_LEN: usize = 2 * 1024 * 1024;
+const BACKUP_CHUNK: usize = 64 * 1024;
+const BACKUP_FILE_EXTENSION: &str = ".tar.gz";
+const BACKUP_MANIFEST_ENTRY: &str = "manifest.yaml";
+const BACKUP_STATE_DIR: &str = "state";
+const SERVICE_JOB_RUNS_FILE: &str = "runs.json";
+const RECEIPT_FILE_EXTENSION: &str = ".sha256";
+
+/// Keeps service backups on this device and exchanges them with backup targets. Every device's
+/// backups live under `backups/services/<peer id>/<service>/<backup id>.tar.gz`, and a target
+/// only ever lists, stores or returns the backups of the device asking.
+#[derive(Clone)]
+pub struct ServiceBackupProtocolControl {
+    swarm_control: SwarmControl,
+    fungi_home: PathBuf,
+    config: Arc<Mutex<FungiConfig>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServiceBackupInfo {
+    pub service: String,
+    /// A ULID, so backups sort by the time they were taken.
+    pub backup_id: String,
+    pub created_at: String,
+    pub size_bytes: u64,
+}
+
+/// A backup fetched for restoring on this device.
+pub struct ServiceBackupArchive {
+    pub info: ServiceBackupInfo,
+    pub manifest_yaml: String,
+    /// Whether the manifest is known to be the one this device backed up: the backup was kept
+    /// here, or its digest matches the receipt recorded when it was pushed to a backup target.
+    pub manifest_verified: bool,
+    path: PathBuf,
+    _download: Option<NamedTempFile>,
+}
+
+impl ServiceBackupArchive {
+    /// Unpacks the backed up appdata, artifacts and job run history as those of
+    /// `local_service_id`, replacing whatever is there.
+    pub async fn unpack(&self, fungi_home: &Path, local_service_id: &str) -> Result<()> {
+        let archive = self.path.clone();
+        let fungi_home = fungi_home.to_path_buf();
+        let local_service_id = local_service_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            unpack_service_archive(&archive, &fungi_home, &local_service_id)?;
+            restore_job_runs(&archive, &fungi_home, &local_service_id)
+        })
+        .await
+        .context("service backup unpack task failed")?
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ServiceBackupRequest {
+    /// Followed by `archive_len` bytes of archive.
+    Store { service: String, archive_len: u64 },
+    List {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        service: Option<String>,
+    },
+    Fetch {
+        service: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        backup_id: Option<String>,
+    },
+}
+
+/// Answers every request; a fetched archive follows it on the stream.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ServiceBackupResponse {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    #[serde(default)]
+    backups: Vec<ServiceBackupInfo>,
+    #[serde(default)]
+    manifest_yaml: String,
+}
+
+impl ServiceBackupProtocolControl {
+    pub fn new(
+        swarm_control: SwarmControl,
+        fungi_home: PathBuf,
+        config: Arc<Mutex<FungiConfig>>,
+    ) -> Self {
+        Self {
+            swarm_control,
+            fungi_home,
+            config,
+        }
+    }
+
+    pub fn start(&self) -> Result<()> {
+        let incoming_streams = self
+            .swarm_control
+            .accept_incoming_streams(FUNGI_SERVICE_BACKUP_PROTOCOL)
+            .map_err(anyhow::Error::from)?;
+        let this = self.clone();
+        tokio::spawn(async move {
+            this.listen_from_incoming_streams(incoming_streams).await;
+        });
+        Ok(())
+    }
+
+    /// Packs a consistent archive of `local_service_id` only when the service isn't writing to
+    /// it, i.e. stopped or quiesced by a pre-backup hook.
+    pub async fn pack(&self, local_service_id: &str, manifest_yaml: &str) -> Result<NamedTempFile> {
+        let fungi_home = self.fungi_home.clone();
+        let local_service_id = local_service_id.to_string();
+        let manifest_yaml = manifest_yaml.to_string();
+        tokio::task::spawn_blocking(move || {
+            pack_service_backup(&fungi_home, &local_service_id, &manifest_yaml)
+        })
+        .await
+        .context("service backup task failed")?
+    }
+
+    /// Keeps `archive` as the newest backup of `service` on `device`, which is either this device
+    /// or a backup target, and applies that device's retention.
+    pub async fn store(
+        &self,
+        device: PeerId,
+        service: &str,
+        archive: NamedTempFile,
+    ) -> Result<ServiceBackupInfo> {
+        if device == self.swarm_control.local_peer_id() {
+            return self
+                .local_store(device)
+                .store(service, archive, &self.retention(), Utc::now());
+        }
+
+        let manifest_yaml = read_backup_manifest(archive.path())?;
+        let archive_len = archive.as_file().metadata()?.len();
+        let request = ServiceBackupRequest::Store {
+            service: service.to_string(),
+            archive_len,
+        };
+        let (mut stream, response) = self
+            .send_request(device, &request, Some(archive.path()))
+            .await?;
+        let _ = stream.close().await;
+        let info =
+            response.backups.into_iter().next().ok_or_else(|| {
+                anyhow::anyhow!("peer {device} did not confirm the stored backup")
+            })?;
+        self.receipts(device)
+            .record(&info, &manifest_yaml)
+            .context("Failed to record the backup receipt")?;
+        Ok(info)
+    }
+
+    /// Backups this device has on `device`, newest first.
+    pub async fn list(
+        &self,
+        device: PeerId,
+        service: Option<&str>,
+    ) -> Result<Vec<ServiceBackupInfo>> {
+        if device == self.swarm_control.local_peer_id() {
+            return self.local_store(device).list(service);
+        }
+
+        let request = ServiceBackupRequest::List {
+            service: service.map(str::to_string),
+        };
+        let (mut stream, response) = self.send_request(device, &request, None).await?;
+        let _ = stream.close().await;
+        Ok(response.backups)
+    }
+
+    /// Fetches backup `backup_id` of `service` from `device`, or its newest one.
+    pub async fn fetch(
+        &self,
+        device: PeerId,
+        service: &str,
+        backup_id: Option<&str>,
+    ) -> Result<ServiceBackupArchive> {
+        if device == self.swarm_control.local_peer_id() {
+            let (info, path) = self.local_store(device).find(service, backup_id)?;
+            let manifest_yaml = read_backup_manifest(&path)?;
+            return Ok(ServiceBackupArchive {
+                info,
+                manifest_yaml,
+                manifest_verified: true,
+                path,
+                _download: None,
+            });
+        }
+
+        let request = ServiceBackupRequest::Fetch {
+            service: service.to_string(),
+            backup_id: backup_id.map(str::to_string),
+        };
+        let (mut stream, response) = self.send_request(device, &request, None).await?;
+        let info = response
+            .backups
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("peer {device} did not describe the backup"))?;
+        let download = NamedTempFile::new_in(&self.fungi_home)
+            .context("Failed to create service backup download file")?;
+        read_stream_to_file(&mut stream, info.size_bytes, download.path())
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to download backup from peer {device}: {e}"))?;
+        let _ = stream.close().await;
+
+        // The target could hand back any manifest; only trust one this device pushed.
+        let manifest_verified = self.receipts(device).verify(&info, &response.manifest_yaml);
+        Ok(ServiceBackupArchive {
+            info,
+            manifest_yaml: response.manifest_yaml,
+            manifest_verified,
+            path: download.path().to_path_buf(),
+            _download: Some(download),
+        })
+    }
+
+    fn local_store(&self, device: PeerId) -> ServiceBackupStore {
+        ServiceBackupStore::for_device(&self.fungi_home, device)
+    }
+
+    fn receipts(&self, device: PeerId) -> ServiceBackupReceipts {
+        ServiceBackupReceipts::for_device(&self.fungi_home, device)
+    }
+
+    fn retention(&self) -> ServiceBackups {
+        self.config.lock().service_backups.clone()
+    }
+
+    async fn send_request(
+        &self,
+        peer_id: PeerId,
+        request: &ServiceBackupRequest,
+        upload: Option<&Path>,
+    ) -> Result<(libp2p::Stream, ServiceBackupResponse)> {
+        let (mut stream, _handle, _connection_id) = self
+            .swarm_control
+            .open_stream(peer_id, FUNGI_SERVICE_BACKUP_PROTOCOL)
+            .await
+            .map_err(|e| {
+                anyhow::anyhow!("Failed to open service-backup stream to peer {peer_id}: {e}")
+            })?;
+        write_frame(&mut stream, request).await.map_err(|e| {
+            anyhow::anyhow!("Failed to write service-backup request to peer {peer_id}: {e}")
+        })?;
+        if let Some(upload) = upload {
+            write_file_to_stream(&mut stream, upload)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to upload backup to peer {peer_id}: {e}"))?;
+        }
+        let response = read_frame::<_, ServiceBackupResponse>(&mut stream)
+            .await
+            .map_err(|e| {
+                anyhow::anyhow!("Failed to read service-backup response from peer {peer_id}: {e}")
+            })?;
+        if let Some(error) = response.error {
+            bail!("backup target {peer_id} refused the request: {error}");
+        }
+        Ok((stream, response))
+    }
+
+    async fn listen_from_incoming_streams(self, mut incoming_streams: IncomingStreams) {
+        while let Some(incoming_stream) = incoming_streams.next().await {
+            let peer_id = incoming_stream.peer_id;
+            let mut stream = incoming_stream.stream;
+            let this = self.clone();
+            tokio::spawn(async move {
+                if let Err(error) = this.serve_request(peer_id, &mut stream).await {
+                    log::warn!("Failed to serve backup request from peer {peer_id}: {error}");
+                }
+                let _ = stream.close().await;
+            });
+        }
+    }
+
+    async fn serve_request<S>(&self, peer_id: PeerId, stream: &mut S) -> Result<()>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        let request = read_frame::<_, ServiceBackupRequest>(stream).await?;
+        let retention = self.retention();
+        let store = self.local_store(peer_id);
+        let refusal = if !retention.accept_from_devices {
+            Some(anyhow::anyhow!(
+                "this device does not accept backups from other devices"
+            ))
+        } else if let ServiceBackupRequest::Store {
+            service,
+            archive_len,
+        } = &request
+        {
+            store.check_upload(service, *archive_len, &retention).err()
+        } else {
+            None
+        };
+        if let Some(refusal) = refusal {
+            // An upload still has to be read off the stream before the refusal is seen, unless
+            // it is too large to bother; then the stream is dropped under the sender.
+            if let ServiceBackupRequest::Store { archive_len, .. } = request
+                && archive_len <= retention.max_archive_bytes()
+            {
+                discard_stream_bytes(stream, archive_len).await?;
+            }
+            return write_frame(stream, &error_response(refusal)).await;
+        }
+
+        match request {
+            ServiceBackupRequest::Store {
+                service,
+                archive_len,
+            } => {
+                let upload = NamedTempFile::new_in(&self.fungi_home)
+                    .context("Failed to create service backup upload file")?;
+                read_stream_to_file(stream, archive_len, upload.path()).await?;
+                let validated = upload.path().to_path_buf();
+                let validated =
+                    tokio::task::spawn_blocking(move || validate_backup_archive(&validated))
+                        .await
+                        .context("service backup validation task failed")?;
+                let stored =
+                    validated.and_then(|()| store.store(&service, upload, &retention, Utc::now()));
+                let response = match stored {
+                    Ok(info) => {
+                        log::info!(
+                            "Stored backup {} of service '{service}' for peer {peer_id}",
+                            info.backup_id
+                        );
+                        ServiceBackupResponse {
+                            backups: vec![info],
+                            ..Default::default()
+                        }
+                    }
+                    Err(error) => error_response(error),
+                };
+                write_frame(stream, &response).await
+            }
+            ServiceBackupRequest::List { service } => {
+                let response = match store.list(service.as_deref()) {
+                    Ok(backups) => ServiceBackupResponse {
+                        backups,
+                        ..Default::default()
+                    },
+                    Err(error) => error_response(error),
+                };
+                write_frame(stream, &response).await
+            }
+            ServiceBackupRequest::Fetch { service, backup_id } => {
+                let found = store
+                    .find(&service, backup_id.as_deref())
+                    .and_then(|(info, path)| Ok((info, read_backup_manifest(&path)?, path)));
+                let (info, manifest_yaml, path) = match found {
+                    Ok(found) => found,
+                    Err(error) => return write_frame(stream, &error_response(error)).await,
+                };
+                let response = ServiceBackupResponse {
+                    backups: vec![info],
+                    manifest_yaml,
+                    ..Default::default()
+                };
+                write_frame(stream, &response).await?;
+                write_file_to_stream(stream, &path).await
+            }
+        }
+    }
+}
+
+fn error_response(error: anyhow::Error) -> ServiceBackupResponse {
+    ServiceBackupResponse {
+        error: Some(format!("{error:#}")),
+        ..Default::default()
+    }
+}
+
+/// The backups one device keeps of its services in a single directory.
+struct ServiceBackupStore {
+    root: PathBuf,
+}
+
+impl ServiceBackupStore {
+    fn for_device(fungi_home: &Path, device: PeerId) -> Self {
+        Self {
+            root: FungiPaths::from_fungi_home(fungi_home)
+                .service_backups_root()
+                .join(device.to_string()),
+        }
+    }
+
+    fn store(
+        &self,
+        service: &str,
+        archive: NamedTempFile,
+        retention: &ServiceBackups,
+        now: DateTime<Utc>,
+    ) -> Result<ServiceBackupInfo> {
+        let service_dir = self.service_dir(service)?;
+        fs::create_dir_all(&service_dir).with_context(|| {
+            format!(
+                "Failed to create backup directory: {}",
+                service_dir.display()
+            )
+        })?;
+        let backup_id = Ulid::from_datetime(now.into()).to_string();
+        let path = service_dir.join(format!("{backup_id}{BACKUP_FILE_EXTENSION}"));
+        archive
+            .persist(&path)
+            .map_err(|error| error.error)
+            .with_context(|| format!("Failed to store backup {}", path.display()))?;
+
+        let backups = self.list(Some(service))?;
+        for expired in expired_backups(&backups, retention, now) {
+            let path = service_dir.join(format!("{}{BACKUP_FILE_EXTENSION}", expired.backup_id));
+            if let Err(error) = fs::remove_file(&path) {
+                log::warn!("Failed to prune backup {}: {}", path.display(), error);
+            }
+        }
+        backups
+            .into_iter()
+            .find(|backup| backup.backup_id == backup_id)
+            .ok_or_else(|| anyhow::anyhow!("stored backup {backup_id} is missing"))
+    }
+
+    /// Refuses an upload from another device before it is read: too large, or of a service
+    /// beyond the number the device may keep backups of.
+    fn check_upload(
+        &self,
+        service: &str,
+        archive_len: u64,
+        retention: &ServiceBackups,
+    ) -> Result<()> {
+        self.service_dir(service)?;
+        if archive_len > retention.max_archive_bytes() {
+            bail!(
+                "backup is {archive_len} bytes, larger than the {} MiB this device accepts",
+                retention.max_archive_mb
+            );
+        }
+        let services = self.services()?;
+        if !services.iter().any(|known| known == service)
+            && services.len() >= retention.max_services_per_device
+        {
+            bail!(
+                "this device already keeps backups of {} services for you",
+                services.len()
+            );
+        }
+        Ok(())
+    }
+
+    fn services(&self) -> Result<Vec<String>> {
+        if !self.root.is_dir() {
+            return Ok(Vec::new());
+        }
+        Ok(fs::read_dir(&self.root)?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .collect())
+    }
+
+    /// Backups of `service`, or of every service, newest first.
+    fn list(&self, service: Option<&str>) -> Result<Vec<ServiceBackupInfo>> {
+        let services = match service {
+            Some(service) => vec![service.to_string()],
+            None => self.services()?,
+        };
+
+        let mut backups = Vec::new();
+        for service in services {
+            let service_dir = self.service_dir(&service)?;
+            if !service_dir.is_dir() {
+                continue;
+            }
+            for entry in fs::read_dir(&service_dir)? {
+                let entry = entry?;
+                let file_name = entry.file_name();
+                let Some(backup_id) = file_name
+                    .to_str()
+                    .and_then(|name| name.strip_suffix(BACKUP_FILE_EXTENSION))
+                else {
+                    continue;
+                };
+                let Some(created_at) = backup_created_at(backup_id) else {
+                    continue;
+                };
+                backups.push(ServiceBackupInfo {
+                    service: service.clone(),
+                    backup_id: backup_id.to_string(),
+                    created_at: created_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+                    size_bytes: entry.metadata()?.len(),
+                });
+            }
+        }
+        backups.sort_by(|left, right| {
+            right
+                .backup_id
+                .cmp(&left.backup_id)
+                .then(left.service.cmp(&right.service))
+        });
+        Ok(backups)
+    }
+
+    fn find(&self, service: &str, backup_id: Option<&str>) -> Result<(ServiceBackupInfo, PathBuf)> {
+        let backups = self.list(Some(service))?;
+        let info = match backup_id {
+            Some(backup_id) => backups
+                .into_iter()
+                .find(|backup| backup.backup_id.eq_ignore_ascii_case(backup_id))
+                .ok_or_else(|| {
+                    anyhow::anyhow!("backup {backup_id} of service '{service}' not found")
+                })?,
+            None => backups
+                .into_iter()
+                .next()
+                .ok_or_else(|| anyhow::anyhow!("service '{service}' has no backups"))?,
+        };
+        let path = self
+            .service_dir(service)?
+            .join(format!("{}{BACKUP_FILE_EXTENSION}", info.backup_id));
+        Ok((info, path))
+    }
+
+    fn service_dir(&self, service: &str) -> Result<PathBuf> {
+        let valid = !service.is_empty()
+            && service
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+            && !service.starts_with('.');
+        if !valid {
+            bail!("invalid service name for a backup: {service:?}");
+        }
+        Ok(self.root.join(service))
+    }
+}
+
+/// Manifest digests of the backups this device pushed to one backup target.
+struct ServiceBackupReceipts {
+    root: PathBuf,
+}
+
+impl ServiceBackupReceipts {
+    fn for_device(fungi_home: &Path, device: PeerId) -> Self {
+        Self {
+            root: FungiPaths::from_fungi_home(fungi_home)
+                .service_backup_receipts_root()
+                .join(device.to_string()),
+        }
+    }
+
+    fn record(&self, info: &ServiceBackupInfo, manifest_yaml: &str) -> Result<()> {
+        let path = self.receipt_path(info)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, sha256_hex(manifest_yaml.as_bytes()))
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    fn verify(&self, info: &ServiceBackupInfo, manifest_yaml: &str) -> bool {
+        self.receipt_path(info)
+            .and_then(|path| Ok(fs::read_to_string(path)?))
+            .is_ok_and(|digest| digest.trim() == sha256_hex(manifest_yaml.as_bytes()))
+    }
+
+    fn receipt_path(&self, info: &ServiceBackupInfo) -> Result<PathBuf> {
+        // The info comes from the target, so it must not steer the path out of the receipts.
+        if backup_created_at(&info.backup_id).is_none() {
+            bail!("invalid backup id: {:?}", info.backup_id);
+        }
+        Ok(ServiceBackupStore {
+            root: self.root.clone(),
+        }
+        .service_dir(&info.service)?
+        .join(format!("{}{RECEIPT_FILE_EXTENSION}", info.backup_id)))
+    }
+}
+
+fn backup_created_at(backup_id: &str) -> Option<DateTime<Utc>> {
+    let ulid = Ulid::from_string(backup_id).ok()?;
+    Some(ulid.datetime().into())
+}
+
+/// Backups `retention` drops from `backups`, which are sorted newest first.
+fn expired_backups<'a>(
+    backups: &'a [ServiceBackupInfo],
+    retention: &ServiceBackups,
+    now: DateTime<Utc>,
+) -> Vec<&'a ServiceBackupInfo> {
+    let max_age = retention
+        .max_age_days
+        .map(|days| Duration::days(i64::from(days)));
+    backups
+        .iter()
+        .enumerate()
+        .skip(1)
+        .filter(|(index, backup)| {
+            *index >= retention.keep_last
+                || max_age.is_some_and(|max_age| {
+                    backup_created_at(&backup.backup_id)
+                        .is_some_and(|created_at| now - created_at > max_age)
+                })
+        })
+        .map(|(_, backup)| backup)
+        .collect()
+}
+
+fn pack_service_backup(
+    fungi_home: &Path,
+    local_service_id: &str,
+    manifest_yaml: &str,
+) -> Result<NamedTempFile> {
+    let archive =
+        NamedTempFile::new_in(fungi_home).context("Failed to create service backup file")?;
+    let encoder = flate2::write::GzEncoder::new(archive.reopen()?, flate2::Compression::default());
+    let mut builder = tar::Builder::new(encoder);
+
+    let mut header = tar::Header::new_gnu();
+    header.set_size(manifest_yaml.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(Utc::now().timestamp().max(0) as u64);
+    builder.append_data(&mut header, BACKUP_MANIFEST_ENTRY, manifest_yaml.as_bytes())?;
+
+    append_service_data(&mut builder, fungi_home, local_service_id)?;
+    let state_dir = FungiPaths::from_fungi_home(fungi_home)
+        .services_root()
+        .join(local_service_id);
+    if state_dir.is_dir() {
+        builder
+            .append_dir_all(BACKUP_STATE_DIR, &state_dir)
+            .with_context(|| format!("Failed to archive {}", state_dir.display()))?;
+    }
+    builder.into_inner()?.finish()?.flush()?;
+    Ok(archive)
+}
+
+/// Checks that an uploaded archive is a gzipped tar holding a manifest and only plain files and
+/// directories, before it is kept for another device.
+fn validate_backup_archive(archive: &Path) -> Result<()> {
+    let file = fs::File::open(archive)
+        .with_context(|| format!("Failed to open backup {}", archive.display()))?;
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(file));
+    let mut has_manifest = false;
+    for entry in archive
+        .entries()
+        .context("backup is not a gzipped tar archive")?
+    {
+        let mut entry = entry.context("backup is not a valid gzipped tar archive")?;
+        let path = entry.path()?.into_owned();
+        if !path
+            .components()
+            .all(|component| matches!(component, std::path::Component::Normal(_)))
+        {
+            bail!("backup entry escapes the archive: {}", path.display());
+        }
+        match entry.header().entry_type() {
+            tar::EntryType::Regular | tar::EntryType::Directory => {}
+            other => bail!(
+                "backup entry {} has unsupported type {other:?}",
+                path.display()
+            ),
+        }
+        if path == Path::new(BACKUP_MANIFEST_ENTRY) {
+            let mut manifest = String::new();
+            entry
+                .read_to_string(&mut manifest)
+                .context("backup manifest is not valid UTF-8")?;
+            has_manifest = true;
+        } else {
+            std::io::copy(&mut entry, &mut std::io::sink())
+                .context("backup is not a valid gzipped tar archive")?;
+        }
+    }
+    if !has_manifest {
+        bail!("backup has no {BACKUP_MANIFEST_ENTRY}");
+    }
+    Ok(())
+}
+
+fn read_backup_manifest(archive: &Path) -> Result<String> {
+    let manifest = read_archive_entry(archive, BACKUP_MANIFEST_ENTRY)?
+        .ok_or_else(|| anyhow::anyhow!("backup {} has no manifest", archive.display()))?;
+    String::from_utf8(manifest).context("backup manifest is not valid UTF-8")
+}
+
+/// Brings back the job run history. Revisions stay behind: they describe the service where it
+/// was backed up, not where it is restored.
+fn restore_job_runs(archive: &Path, fungi_home: &Path, local_service_id: &str) -> Result<()> {
+    let entry = format!("{BACKUP_STATE_DIR}/{SERVICE_JOB_RUNS_FILE}");
+    let Some(runs) = read_archive_entry(archive, &entry)? else {
+        return Ok(());
+    };
+    let state_dir = FungiPaths::from_fungi_home(fungi_home)
+        .services_root()
+        .join(local_service_id);
+    fs::create_dir_all(&state_dir)?;
+    fs::write(state_dir.join(SERVICE_JOB_RUNS_FILE), runs)
+        .context("Failed to restore job run history")
+}
+
+fn read_archive_entry(archive: &Path, name: &str) -> Result<Option<Vec<u8>>> {
+    let file = fs::File::open(archive)
+        .with_context(|| format!("Failed to open backup {}", archive.display()))?;
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(file));
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if entry.path()? == Path::new(name) {
+            let mut content = Vec::new();
+            entry.read_to_end(&mut content)?;
+            return Ok(Some(content));
+        }
+    }
+    Ok(None)
+}
+
+async fn write_file_to_stream<S>(stream: &mut S, path: &Path) -> Result<()>
+where
+    S: AsyncWrite + Unpin,
+{
+    let mut file = fs::File::open(path)?;
+    let mut buffer = vec![0u8; BACKUP_CHUNK];
+    loop {
+        let size = file.read(&mut buffer)?;
+        if size == 0 {
+            break;
+        }
+        stream
+            .write_all(&buffer[..size])
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to write backup archive: {e}"))?;
+    }
+    stream
+        .flush()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to flush backup archive: {e}"))
+}
+
+async fn read_stream_to_file<S>(stream: &mut S, len: u64, path: &Path) -> Result<()>
+where
+    S: AsyncRead + Unpin,
+{
+    let mut file = fs::File::create(path)?;
+    let mut remaining = len;
+    let mut buffer = vec![0u8; BACKUP_CHUNK];
+    while remaining > 0 {
+        let chunk = remaining.min(BACKUP_CHUNK as u64) as usize;
+        stream
+            .read_exact(&mut buffer[..chunk])
+            .await
+            .map_err(|e| anyhow::anyhow!("Lost backup stream: {e}"))?;
+        file.write_all(&buffer[..chunk])
+            .context("Failed to write backup file")?;
+        remaining -= chunk as u64;
+    }
+    file.flush()?;
+    Ok(())
+}
+
+async fn discard_stream_bytes<S>(stream: &mut S, len: u64) -> Result<()>
+where
+    S: AsyncRead + Unpin,
+{
+    let mut remaining = len;
+    let mut buffer = vec![0u8; BACKUP_CHUNK];
+    while remaining > 0 {
+        let chunk = remaining.min(BACKUP_CHUNK as u64) as usize;
+        stream
+            .read_exact(&mut buffer[..chunk])
+            .await
+            .map_err(|e| anyhow::anyhow!("Lost backup stream: {e}"))?;
+        remaining -= chunk as u64;
+    }
+    Ok(())
+}
+
+async fn write_frame<S, T>(stream: &mut S, value: &T) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+    T: Serialize,
+{
+    let payload = serde_json::to_vec(value)
+        .map_err(|e| anyhow::anyhow!("Failed to serialize service-backup frame: {e}"))?;
+    let payload_len = u32::try_from(payload.len())
+        .map_err(|_| anyhow::anyhow!("Service-backup frame is too large"))?;
+    stream
+        .write_all(&payload_len.to_be_bytes())
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to write frame length: {e}"))?;
+    stream
+        .write_all(&payload)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to write frame payload: {e}"))?;
+    stream
+        .flush()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to flush frame payload: {e}"))?;
+    Ok(())
+}
+
+async fn read_frame<S, T>(stream: &mut S) -> Result<T>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+    T: DeserializeOwned,
+{
+    let mut len_buf = [0u8; 4];
+    stream
+        .read_exact(&mut len_buf)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to read frame length: {e}"))?;
+    let payload_len = u32::from_be_bytes(len_buf) as usize;
+    if payload_len > MAX_BACKUP_FRAME_LEN {
+        anyhow::bail!(
+            "Service-backup frame too large: {} bytes (max {})",
+            payload_len,
+            MAX_BACKUP_FRAME_LEN
+        );
+    }
+
+    let mut payload = vec![0u8; payload_len];
+    stream
+        .read_exact(&mut payload)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to read frame payload: {e}"))?;
+    serde_json::from_slice(&payload)
+        .map_err(|e| anyhow::anyhow!("Failed to decode service-backup frame: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn backup_file(home: &Path) -> NamedTempFile {
+        NamedTempFile::new_in(home).unwrap()
+    }
+
+    #[test]
+    fn backup_archive_keeps_manifest_appdata_and_job_runs() -> Result<()> {
+        let source_home = tempfile::tempdir()?;
+        let source_paths = FungiPaths::from_fungi_home(source_home.path());
+        let appdata = source_paths.service_appdata_dir("source-id");
+        fs::create_dir_all(&appdata)?;
+        fs::write(appdata.join("notes.sqlite"), b"notes")?;
+        let state_dir = source_paths.services_root().join("source-id");
+        fs::create_dir_all(&state_dir)?;
+        fs::write(state_dir.join(SERVICE_JOB_RUNS_FILE), b"[]")?;
+
+        let archive = pack_service_backup(source_home.path(), "source-id", "name: notes\n")?;
+        assert_eq!(read_backup_manifest(archive.path())?, "name: notes\n");
+
+        let target_home = tempfile::tempdir()?;
+        unpack_service_archive(archive.path(), target_home.path(), "target-id")?;
+        restore_job_runs(archive.path(), target_home.path(), "target-id")?;
+        let target_paths = FungiPaths::from_fungi_home(target_home.path());
+        assert_eq!(
+            fs::read(
+                target_paths
+                    .service_appdata_dir("target-id")
+                    .join("notes.sqlite")
+            )?,
+            b"notes"
+        );
+        assert_eq!(
+            fs::read(
+                target_paths
+                    .services_root()
+                    .join("target-id")
+                    .join(SERVICE_JOB_RUNS_FILE)
+            )?,
+            b"[]"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn store_prunes_backups_beyond_retention() -> Result<()> {
+        let home = tempfile::tempdir()?;
+        let store = ServiceBackupStore::for_device(home.path(), PeerId::random());
+        let retention = ServiceBackups {
+            keep_last: 2,
+            ..Default::default()
+        };
+        let start = Utc::now();
+        let mut stored = Vec::new();
+        for day in 0..3 {
+            let now = start + Duration::days(day);
+            stored.push(store.store("notes", backup_file(home.path()), &retention, now)?);
+        }
+
+        let backups = store.list(Some("notes"))?;
+        assert_eq!(
+            backups
+                .iter()
+                .map(|backup| backup.backup_id.as_str())
+                .collect::<Vec<_>>(),
+            vec![stored[2].backup_id.as_str(), stored[1].backup_id.as_str()]
+        );
+        assert_eq!(store.find("notes", None)?.0, stored[2]);
+        assert!(store.find("notes", Some(&stored[0].backup_id)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn max_age_prunes_old_backups_but_keeps_the_newest() -> Result<()> {
+        let home = tempfile::tempdir()?;
+        let store = ServiceBackupStore::for_device(home.path(), PeerId::random());
+        let start = Utc::now();
+        let retention = ServiceBackups {
+            max_age_days: Some(7),
+            ..Default::default()
+        };
+        store.store("notes", backup_file(home.path()), &retention, start)?;
+        let newest = store.store(
+            "notes",
+            backup_file(home.path()),
+            &retention,
+            start + Duration::days(30),
+        )?;
+        assert_eq!(store.list(None)?, vec![newest.clone()]);
+
+        let later = start + Duration::days(60);
+        let backups = store.list(Some("notes"))?;
+        assert!(expired_backups(&backups, &retention, later).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn validates_uploaded_archives() -> Result<()> {
+        let home = tempfile::tempdir()?;
+        let packed = pack_service_backup(home.path(), "source-id", "name: notes\n")?;
+        validate_backup_archive(packed.path())?;
+
+        let garbage = backup_file(home.path());
+        fs::write(garbage.path(), b"not an archive")?;
+        assert!(validate_backup_archive(garbage.path()).is_err());
+
+        let without_manifest = backup_file(home.path());
+        let encoder = flate2::write::GzEncoder::new(
+            without_manifest.reopen()?,
+            flate2::Compression::default(),
+        );
+        let mut builder = tar::Builder::new(encoder);
+        let mut header = tar::Header::new_gnu();
+        header.set_size(5);
+        header.set_mode(0o644);
+        builder.append_data(&mut header, "appdata/notes.sqlite", &b"notes"[..])?;
+        builder.into_inner()?.finish()?;
+        let error = validate_backup_archive(without_manifest.path()).unwrap_err();
+        assert!(error.to_string().contains("no manifest.yaml"));
+        Ok(())
+    }
+
+    #[test]
+    fn refuses_uploads_beyond_size_and_service_limits() -> Result<()> {
+        let home = tempfile::tempdir()?;
+        let store = ServiceBackupStore::for_device(home.path(), PeerId::random());
+        let retention = ServiceBackups {
+            accept_from_devices: true,
+            max_archive_mb: 1,
+            max_services_per_device: 1,
+            ..Default::default()
+        };
+        assert!(
+            store
+                .check_upload("notes", 2 * 1024 * 1024, &retention)
+                .is_err()
+        );
+        store.check_upload("notes", 1024, &retention)?;
+        store.store("notes", backup_file(home.path()), &retention, Utc::now())?;
+
+        store.check_upload("notes", 1024, &retention)?;
+        assert!(store.check_upload("photos", 1024, &retention).is_err());
+        assert!(store.check_upload("../notes", 1024, &retention).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn receipts_only_vouch_for_the_pushed_manifest() -> Result<()> {
+        let home = tempfile::tempdir()?;
+        let receipts = ServiceBackupReceipts::for_device(home.path(), PeerId::random());
+        let info = ServiceBackupInfo {
+            service: "notes".into(),
+            backup_id: Ulid::new().to_string(),
+            created_at: String::new(),
+            size_bytes: 0,
+        };
+        assert!(!receipts.verify(&info, "name: notes\n"));
+
+        receipts.record(&info, "name: notes\n")?;
+        assert!(receipts.verify(&info, "name: notes\n"));
+        assert!(!receipts.verify(&info, "name: notes\nprivileged: true\n"));
+
+        let escaping = ServiceBackupInfo {
+            backup_id: "../../receipts".into(),
+            ..info
+        };
+        assert!(!receipts.verify(&escaping, "name: notes\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_service_names_that_escape_the_store() {
+        let store = ServiceBackupStore::for_device(Path::new("/tmp/fungi"), PeerId::random());
+        assert!(store.service_dir("../notes").is_err());
+        assert!(store.service_dir("..").is_err());
+        assert!(store.service_dir("notes").is_ok());
+    }
+}
@@ -1,6 +1,12 @@
-use std::path::PathBuf;
+use std::{
+    path::PathBuf,
+    sync::{Arc, OnceLock},
+};
 
 use anyhow::Result;
+use async_trait::async_trait;
+use fungi_config::tcp_tunneling::{ForwardingRule, ForwardingTarget};
+use fungi_config::{local_preferences::LocalPreferenceCache, paths::FungiPaths};
 use fungi_stream::IncomingStreams;
 use fungi_swarm::SwarmControl;
 use fungi_util::protocols::FUNGI_SERVICE_CONTROL_PROTOCOL;
@@ -11,21 +17,46 @@ use libp2p::{
     futures::{AsyncReadExt, AsyncWriteExt},
 };
 use serde::{Serialize, de::DeserializeOwned};
+use tokio::sync::Mutex as AsyncMutex;
 
-use crate::controls::TcpTunnelingControl;
+use crate::controls::{
+    OnDemandControl, ServiceBackupArchive, ServiceTransferProtocolControl, TcpTunnelingControl,
+    wake_on_lan::KnownMacAddressesSource,
+};
 use crate::{
-    ManifestResolutionPolicy, RuntimeControl, ServiceControlRequest, ServiceControlResponse,
-    ServiceManifest, service_expose_endpoint_bindings, service_state::DesiredServiceState,
+    ManifestResolutionPolicy, RuntimeControl, ServiceAccess, ServiceControlRequest,
+    ServiceControlResponse, ServiceJobTrigger, ServiceManifest, runtime::AppliedService,
+    service_expose_endpoint_bindings, service_state::DesiredServiceState,
 };
 
 const MAX_CONTROL_FRAME_LEN: usize = 2 * 1024 * 1024;
 
+/// This device's own service accesses, which trusted peers manage through service control.
+#[async_trait]
+pub(crate) trait ServiceAccessOperations: Send + Sync {
+    async fn attach(
+        &self,
+        peer_id: PeerId,
+        service: String,
+        entry: Option<String>,
+        local_port: Option<u16>,
+    ) -> Result<ServiceAccess>;
+    async fn forget(&self, peer_id: PeerId, service: String) -> Result<()>;
+    async fn list(&self) -> Result<Vec<ServiceAccess>>;
+}
+
 #[derive(Clone)]
 pub struct ServiceControlProtocolControl {
     swarm_control: SwarmControl,
     fungi_home: PathBuf,
     runtime_control: RuntimeControl,
     tcp_tunneling_control: TcpTunnelingControl,
+    on_demand_control: OnDemandControl,
+    service_transfer_control: ServiceTransferProtocolControl,
+    local_preferences_lock: Arc<AsyncMutex<()>>,
+    known_mac_addresses: Option<KnownMacAddressesSource>,
+    /// Set by the daemon once it is up; shared by every clone of this control.
+    service_accesses: Arc<OnceLock<Arc<dyn ServiceAccessOperations>>>,
 }
 
 impl ServiceControlProtocolControl {
@@ -34,12 +65,35 @@ impl ServiceControlProtocolControl {
         fungi_home: PathBuf,
         runtime_control: RuntimeControl,
         tcp_tunneling_control: TcpTunnelingControl,
+        on_demand_control: OnDemandControl,
+        service_transfer_control: ServiceTransferProtocolControl,
+        local_preferences_lock: Arc<AsyncMutex<()>>,
     ) -> Self {
         Self {
             swarm_control,
             fungi_home,
             runtime_control,
             tcp_tunneling_control,
+            on_demand_control,
+            service_transfer_control,
+            local_preferences_lock,
+            known_mac_addresses: None,
+            service_accesses: Arc::new(OnceLock::new()),
+        }
+    }
+
+    /// Lets trusted peers relay Wake-on-LAN through this device, for the devices `source` knows
+    /// MAC addresses of. Without it, relayed wake requests are refused.
+    pub(crate) fn with_known_mac_addresses(mut self, source: KnownMacAddressesSource) -> Self {
+        self.known_mac_addresses = Some(source);
+        self
+    }
+
+    /// Lets trusted peers attach, forget and list this device's service accesses through
+    /// `operations`. Until then, those requests are refused.
+    pub(crate) fn set_service_accesses(&self, operations: Arc<dyn ServiceAccessOperations>) {
+        if self.service_accesses.set(operations).is_err() {
+            log::warn!("Service access operations were already set for service control");
         }
     }
 
@@ -123,6 +177,370 @@ impl ServiceControlProtocolControl {
         .await
     }
 
+    pub async fn peer_service_history(
+        &self,
+        peer_id: PeerId,
+        service: String,
+    ) -> Result<ServiceControlResponse> {
+        self.send_request(
+            peer_id,
+            ServiceControlRequest::ServiceHistory {
+                request_id: None,
+                service,
+            },
+        )
+        .await
+    }
+
+    pub async fn rollback_peer_service(
+        &self,
+        peer_id: PeerId,
+        service: String,
+        revision: Option<u64>,
+    ) -> Result<ServiceControlResponse> {
+        self.send_request(
+            peer_id,
+            ServiceControlRequest::RollbackService {
+                request_id: None,
+                service,
+                revision,
+            },
+        )
+        .await
+    }
+
+    pub async fn run_peer_job(
+        &self,
+        peer_id: PeerId,
+        service: String,
+    ) -> Result<ServiceControlResponse> {
+        self.send_request(
+            peer_id,
+            ServiceControlRequest::RunJob {
+                request_id: None,
+                service,
+            },
+        )
+        .await
+    }
+
+    pub async fn peer_job_status(
+        &self,
+        peer_id: PeerId,
+        service: String,
+    ) -> Result<ServiceControlResponse> {
+        self.send_request(
+            peer_id,
+            ServiceControlRequest::JobStatus {
+                request_id: None,
+                service,
+            },
+        )
+        .await
+    }
+
+    pub async fn import_peer_service(
+        &self,
+        peer_id: PeerId,
+        from_peer_id: PeerId,
+        service: String,
+        start: bool,
+    ) -> Result<ServiceControlResponse> {
+        self.send_request(
+            peer_id,
+            ServiceControlRequest::ImportService {
+                request_id: None,
+                service,
+                from_peer_id: from_peer_id.to_string(),
+                start,
+            },
+        )
+        .await
+    }
+
+    pub async fn repoint_peer_service_access(
+        &self,
+        peer_id: PeerId,
+        service: String,
+        from_peer_id: PeerId,
+        to_peer_id: PeerId,
+    ) -> Result<ServiceControlResponse> {
+        self.send_request(
+            peer_id,
+            ServiceControlRequest::RepointServiceAccess {
+                request_id: None,
+                service,
+                from_peer_id: from_peer_id.to_string(),
+                to_peer_id: to_peer_id.to_string(),
+            },
+        )
+        .await
+    }
+
+    /// Asks `peer_id` to create or update its access to `service` published by
+    /// `publisher_peer_id`.
+    pub async fn attach_peer_service_access(
+        &self,
+        peer_id: PeerId,
+        publisher_peer_id: PeerId,
+        service: String,
+        entry: Option<String>,
+        local_port: Option<u16>,
+    ) -> Result<ServiceControlResponse> {
+        self.send_request(
+            peer_id,
+            ServiceControlRequest::AttachServiceAccess {
+                request_id: None,
+                peer_id: publisher_peer_id.to_string(),
+                service,
+                entry,
+                local_port,
+            },
+        )
+        .await
+    }
+
+    pub async fn forget_peer_service_access(
+        &self,
+        peer_id: PeerId,
+        publisher_peer_id: PeerId,
+        service: String,
+    ) -> Result<ServiceControlResponse> {
+        self.send_request(
+            peer_id,
+            ServiceControlRequest::ForgetServiceAccess {
+                request_id: None,
+                peer_id: publisher_peer_id.to_string(),
+                service,
+            },
+        )
+        .await
+    }
+
+    pub async fn list_peer_service_accesses(
+        &self,
+        peer_id: PeerId,
+    ) -> Result<ServiceControlResponse> {
+        self.send_request(
+            peer_id,
+            ServiceControlRequest::ListServiceAccesses { request_id: None },
+        )
+        .await
+    }
+
+    /// Fetches the stopped `service` from `from_peer_id` together with its appdata and applies it
+    /// here under a fresh local service id, starting it when `start` is set. Nothing is left
+    /// behind when the import fails.
+    pub async fn import_service(
+        &self,
+        from_peer_id: PeerId,
+        service: &str,
+        start: bool,
+        applied_by: Option<PeerId>,
+    ) -> Result<String> {
+        let local_service_id = self.runtime_control.unused_local_service_id(service)?;
+        let snapshot = self
+            .service_transfer_control
+            .fetch_from_peer(from_peer_id, service)
+            .await?;
+        let manifest_yaml = snapshot.manifest_yaml.clone();
+        let result = async {
+            snapshot.unpack(&self.fungi_home, &local_service_id).await?;
+            self.apply_imported_manifest(&manifest_yaml, &local_service_id, start, applied_by)
+                .await
+        }
+        .await;
+
+        if result.is_err() {
+            self.discard_imported_service(service, &local_service_id)
+                .await;
+        }
+        result
+    }
+
+    /// Restores `backup` over the service of the same name, stopping it first, or recreates the
+    /// service when this device doesn't have it. The service is started afterwards when `start`
+    /// is set or it was running before.
+    pub async fn restore_service_backup(
+        &self,
+        backup: &ServiceBackupArchive,
+        start: bool,
+        applied_by: Option<PeerId>,
+    ) -> Result<String> {
+        let service = backup.info.service.as_str();
+        if self.runtime_control.get_service_manifest(service).is_none() {
+            let local_service_id = self.runtime_control.unused_local_service_id(service)?;
+            let result = async {
+                backup.unpack(&self.fungi_home, &local_service_id).await?;
+                self.apply_imported_manifest(
+                    &backup.manifest_yaml,
+                    &local_service_id,
+                    start,
+                    applied_by,
+                )
+                .await
+            }
+            .await;
+            if result.is_err() {
+                self.discard_imported_service(service, &local_service_id)
+                    .await;
+            }
+            return result;
+        }
+
+        let local_service_id = self.runtime_control.local_service_id(service)?;
+        let was_running = self
+            .runtime_control
+            .inspect_by_name(service)
+            .await?
+            .status
+            .is_running();
+        if was_running {
+            self.runtime_control.stop_by_name(service).await?;
+            self.sync_service_endpoint_listeners_by_name(service, false)
+                .await?;
+        }
+        backup.unpack(&self.fungi_home, &local_service_id).await?;
+        self.apply_imported_manifest(
+            &backup.manifest_yaml,
+            &local_service_id,
+            start || was_running,
+            applied_by,
+        )
+        .await
+    }
+
+    async fn apply_imported_manifest(
+        &self,
+        manifest_yaml: &str,
+        local_service_id: &str,
+        start: bool,
+        applied_by: Option<PeerId>,
+    ) -> Result<String> {
+        let applied = self
+            .runtime_control
+            .import_manifest_yaml(
+                manifest_yaml,
+                &self.fungi_home,
+                local_service_id,
+                &self.manifest_resolution_policy(),
+                applied_by,
+            )
+            .await?;
+        let name = self.sync_applied_service(&applied).await?;
+        if start {
+            self.runtime_control.start_by_name(&name).await?;
+            self.sync_service_endpoint_listeners_by_name(&name, true)
+                .await?;
+        }
+        Ok(name)
+    }
+
+    async fn discard_imported_service(&self, service: &str, local_service_id: &str) {
+        if let Some(manifest) = self.runtime_control.get_service_manifest(service) {
+            if let Err(error) = self.runtime_control.remove_by_name(service).await {
+                log::warn!("Failed to remove partially imported service '{service}': {error}");
+            }
+            let _ = self
+                .sync_service_endpoint_listeners_for_manifest(Some(&manifest), false)
+                .await;
+        }
+        let paths = FungiPaths::from_fungi_home(&self.fungi_home);
+        for dir in [
+            paths.service_appdata_dir(local_service_id),
+            paths.service_artifacts_dir(local_service_id),
+        ] {
+            if dir.exists()
+                && let Err(error) = std::fs::remove_dir_all(&dir)
+            {
+                log::warn!(
+                    "Failed to clean up imported service data {}: {}",
+                    dir.display(),
+                    error
+                );
+            }
+        }
+    }
+
+    /// Points this device's saved accesses to `service` on `from_peer_id` at `to_peer_id`, and
+    /// moves active listeners along without changing their local addresses.
+    pub async fn repoint_service_access(
+        &self,
+        service: &str,
+        from_peer_id: PeerId,
+        to_peer_id: PeerId,
+    ) -> Result<()> {
+        let _local_preferences_guard = self.local_preferences_lock.lock().await;
+        let (from, to) = (from_peer_id.to_string(), to_peer_id.to_string());
+        LocalPreferenceCache::apply_from_dir(&self.fungi_home)?
+            .repoint_service_records(service, &from, &to)?;
+
+        let rules = self.tcp_tunneling_control.get_forwarding_rules();
+        for (rule_id, rule) in &rules {
+            let Some(repointed) = repoint_forwarding_rule(rule, service, &from, &to) else {
+                continue;
+            };
+            self.tcp_tunneling_control.remove_forwarding_rule(rule_id)?;
+            // The target device already had its own listener for this entry; keep that one.
+            let duplicate = rules.iter().any(|(_, existing)| {
+                existing.remote_peer_id == repointed.remote_peer_id
+                    && existing.remote_service_name == repointed.remote_service_name
+                    && existing.remote_service_port_name == repointed.remote_service_port_name
+            });
+            if duplicate {
+                continue;
+            }
+            if let Err(error) = self
+                .tcp_tunneling_control
+                .add_forwarding_rule(repointed)
+                .await
+            {
+                log::warn!(
+                    "Failed to move local listener {}:{} for service {service} to {to}: {error}",
+                    rule.local_host,
+                    rule.local_port
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Asks `relay_peer_id` to wake `target`, which the relay must know the MAC addresses of.
+    pub async fn wake_via_peer(
+        &self,
+        relay_peer_id: PeerId,
+        target: PeerId,
+    ) -> Result<ServiceControlResponse> {
+        self.send_request(
+            relay_peer_id,
+            ServiceControlRequest::WakeDevice {
+                request_id: None,
+                peer_id: target.to_string(),
+            },
+        )
+        .await
+    }
+
+    async fn relay_wake(&self, target: &str) -> Result<usize> {
+        let Some(known_mac_addresses) = &self.known_mac_addresses else {
+            anyhow::bail!("this device does not relay Wake-on-LAN");
+        };
+        let target = target
+            .parse::<PeerId>()
+            .map_err(|error| anyhow::anyhow!("invalid peer_id: {error}"))?;
+        let mac_addresses = known_mac_addresses(&target);
+        if mac_addresses.is_empty() {
+            anyhow::bail!("no MAC address is known for device {target} on this device");
+        }
+        super::wake_on_lan::send_magic_packets(&mac_addresses).await
+    }
+
+    fn service_access_operations(&self) -> Result<&Arc<dyn ServiceAccessOperations>> {
+        self.service_accesses
+            .get()
+            .ok_or_else(|| anyhow::anyhow!("this device does not manage service accesses yet"))
+    }
+
     async fn send_request(
         &self,
         peer_id: PeerId,
@@ -167,7 +585,7 @@ impl ServiceControlProtocolControl {
                     }
                 };
 
-                let response = this.handle_request(request).await;
+                let response = this.handle_request(peer_id, request).await;
 
                 if let Err(error) = write_frame(&mut stream, &response).await {
                     log::warn!(
@@ -184,10 +602,95 @@ impl ServiceControlProtocolControl {
         }
     }
 
-    async fn handle_request(&self, request: ServiceControlRequest) -> ServiceControlResponse {
+    async fn handle_request(
+        &self,
+        peer_id: PeerId,
+        request: ServiceControlRequest,
+    ) -> ServiceControlResponse {
         let request_id = request.request_id().map(str::to_string);
 
         let result = match request {
+            ServiceControlRequest::WakeDevice {
+                peer_id: target, ..
+            } => {
+                return match self.relay_wake(&target).await {
+                    Ok(_) => ServiceControlResponse::acknowledged(request_id),
+                    Err(error) => {
+                        ServiceControlResponse::error(request_id, "wake_failed", error.to_string())
+                    }
+                };
+            }
+            ServiceControlRequest::ListServiceAccesses { .. } => {
+                return match self.list_service_accesses().await {
+                    Ok(service_accesses_json) => ServiceControlResponse::success_service_accesses(
+                        request_id,
+                        service_accesses_json,
+                    ),
+                    Err(error) => ServiceControlResponse::error(
+                        request_id,
+                        "execution_failed",
+                        error.to_string(),
+                    ),
+                };
+            }
+            ServiceControlRequest::AttachServiceAccess {
+                peer_id: publisher_peer_id,
+                service,
+                entry,
+                local_port,
+                ..
+            } => match (
+                publisher_peer_id.parse::<PeerId>(),
+                self.service_access_operations(),
+            ) {
+                (Ok(publisher_peer_id), Ok(operations)) => operations
+                    .attach(publisher_peer_id, service, entry, local_port)
+                    .await
+                    .map(|access| access.service_name),
+                (Err(error), _) => Err(anyhow::anyhow!("invalid peer_id: {error}")),
+                (_, Err(error)) => Err(error),
+            },
+            ServiceControlRequest::ForgetServiceAccess {
+                peer_id: publisher_peer_id,
+                service,
+                ..
+            } => match (
+                publisher_peer_id.parse::<PeerId>(),
+                self.service_access_operations(),
+            ) {
+                (Ok(publisher_peer_id), Ok(operations)) => operations
+                    .forget(publisher_peer_id, service.clone())
+                    .await
+                    .map(|()| service),
+                (Err(error), _) => Err(anyhow::anyhow!("invalid peer_id: {error}")),
+                (_, Err(error)) => Err(error),
+            },
+            ServiceControlRequest::ImportService {
+                service,
+                from_peer_id,
+                start,
+                ..
+            } => match from_peer_id.parse::<PeerId>() {
+                Ok(from_peer_id) => {
+                    self.import_service(from_peer_id, &service, start, Some(peer_id))
+                        .await
+                }
+                Err(error) => Err(anyhow::anyhow!("invalid from_peer_id: {error}")),
+            },
+            ServiceControlRequest::RepointServiceAccess {
+                service,
+                from_peer_id,
+                to_peer_id,
+                ..
+            } => match (from_peer_id.parse::<PeerId>(), to_peer_id.parse::<PeerId>()) {
+                (Ok(from_peer_id), Ok(to_peer_id)) => self
+                    .repoint_service_access(&service, from_peer_id, to_peer_id)
+                    .await
+                    .map(|()| service),
+                (Err(error), _) | (_, Err(error)) => {
+                    Err(anyhow::anyhow!("invalid peer id: {error}"))
+                }
+            },
             ServiceControlRequest::PullService { manifest_yaml, .. } => {
                 let policy = self.manifest_resolution_policy();
                 match self
@@ -197,51 +700,80 @@ impl ServiceControlProtocolControl {
                         &self.fungi_home,
                         &self.fungi_home,
                         &policy,
+                        Some(peer_id),
                     )
                     .await
                 {
-                    Ok(applied) => {
-                        if applied.desired_state == DesiredServiceState::Running {
-                            match self
-                                .sync_service_endpoint_listeners_for_manifest(
-                                    applied.previous_manifest.as_ref(),
-                                    false,
-                                )
-                                .await
-                            {
-                                Ok(()) => {}
-                                Err(error) => {
-                                    return ServiceControlResponse::error(
-                                        request_id.clone(),
-                                        "execution_failed",
-                                        error.to_string(),
-                                    );
-                                }
-                            }
-                            match self
-                                .sync_service_endpoint_listeners_by_name(
-                                    &applied.instance.name,
-                                    true,
-                                )
-                                .await
-                            {
-                                Ok(()) => {}
-                                Err(error) => {
-                                    return ServiceControlResponse::error(
-                                        request_id.clone(),
-                                        "execution_failed",
-                                        error.to_string(),
-                                    );
-                                }
-                            }
-                        }
-                        Ok(applied.instance.name)
-                    }
+                    Ok(applied) => self.sync_applied_service(&applied).await,
                     Err(error) => Err(error),
                 }
             }
+            ServiceControlRequest::ServiceHistory { service, .. } => {
+                return match self
+                    .runtime_control
+                    .service_revisions(&service)
+                    .and_then(|revisions| Ok(serde_json::to_string(&revisions)?))
+                {
+                    Ok(revisions_json) => ServiceControlResponse::success_revisions(
+                        request_id,
+                        service,
+                        revisions_json,
+                    ),
+                    Err(error) => ServiceControlResponse::error(
+                        request_id,
+                        "execution_failed",
+                        error.to_string(),
+                    ),
+                };
+            }
+            ServiceControlRequest::RunJob { service, .. } => {
+                return match self
+                    .runtime_control
+                    .run_job(&service, ServiceJobTrigger::Manual)
+                    .await
+                    .and_then(|run| Ok(serde_json::to_string(&run)?))
+                {
+                    Ok(run_json) => {
+                        ServiceControlResponse::success_job(request_id, service, run_json)
+                    }
+                    Err(error) => ServiceControlResponse::error(
+                        request_id,
+                        "execution_failed",
+                        error.to_string(),
+                    ),
+                };
+            }
+            ServiceControlRequest::JobStatus { service, .. } => {
+                return match self
+                    .runtime_control
+                    .job_status(&service)
+                    .and_then(|status| {
+                        let status = status
+                            .ok_or_else(|| anyhow::anyhow!("service '{service}' is not a job"))?;
+                        Ok(serde_json::to_string(&status)?)
+                    }) {
+                    Ok(status_json) => {
+                        ServiceControlResponse::success_job(request_id, service, status_json)
+                    }
+                    Err(error) => ServiceControlResponse::error(
+                        request_id,
+                        "execution_failed",
+                        error.to_string(),
+                    ),
+                };
+            }
+            ServiceControlRequest::RollbackService {
+                service, revision, ..
+            } => match self
+                .runtime_control
+                .rollback(&service, revision, Some(peer_id))
+                .await
+            {
+                Ok(applied) => self.sync_applied_service(&applied).await,
+                Err(error) => Err(error),
+            },
             ServiceControlRequest::ListServices { .. } => {
-                let services = self.runtime_control.list_services().await;
+                let services = self.runtime_control.list_services_with_resources().await;
                 match services {
                     Ok(services) => match serde_json::to_string(&services) {
                         Ok(services_json) => {
@@ -304,6 +836,24 @@ impl ServiceControlProtocolControl {
         }
     }
 
+    async fn list_service_accesses(&self) -> Result<String> {
+        let accesses = self.service_access_operations()?.list().await?;
+        Ok(serde_json::to_string(&accesses)?)
+    }
+
+    async fn sync_applied_service(&self, applied: &AppliedService) -> Result<String> {
+        if applied.desired_state == DesiredServiceState::Running {
+            self.sync_service_endpoint_listeners_for_manifest(
+                applied.previous_manifest.as_ref(),
+                false,
+            )
+            .await?;
+            self.sync_service_endpoint_listeners_by_name(&applied.instance.name, true)
+                .await?;
+        }
+        Ok(applied.instance.name.clone())
+    }
+
     fn manifest_resolution_policy(&self) -> ManifestResolutionPolicy {
         ManifestResolutionPolicy::default()
     }
@@ -346,6 +896,7 @@ impl ServiceControlProtocolControl {
                             host: "127.0.0.1".to_string(),
                             port: endpoint.host_port,
                             protocol: Some(endpoint.protocol),
+                            bandwidth_limit: Default::default(),
                         })
                         .await?;
                 }
@@ -354,10 +905,52 @@ impl ServiceControlProtocolControl {
             }
         }
 
+        self.on_demand_control.sync_manifest(manifest, enabled);
         Ok(())
     }
 }
 
+/// The rule `rule` becomes once `service` moved from `from` to `to`, or `None` when it doesn't
+/// reach that service on `from`.
+fn repoint_forwarding_rule(
+    rule: &ForwardingRule,
+    service: &str,
+    from: &str,
+    to: &str,
+) -> Option<ForwardingRule> {
+    let port_name = rule.remote_service_port_name.as_deref()?;
+    let member_protocol = fungi_util::protocols::service_port_protocol(service, port_name);
+    let primary_matches =
+        rule.remote_peer_id == from && rule.remote_service_name.as_deref() == Some(service);
+    let member_matches = rule
+        .fallback_targets
+        .iter()
+        .any(|target| target.peer_id == from && target.protocol == member_protocol);
+    if !primary_matches && !member_matches {
+        return None;
+    }
+
+    let mut repointed = rule.clone();
+    if primary_matches {
+        repointed.remote_peer_id = to.to_string();
+    }
+    let mut fallback_targets = Vec::<ForwardingTarget>::new();
+    for mut target in repointed.fallback_targets {
+        if target.peer_id == from && target.protocol == member_protocol {
+            target.peer_id = to.to_string();
+        }
+        if target.peer_id != repointed.remote_peer_id
+            && !fallback_targets
+                .iter()
+                .any(|existing| existing.peer_id == target.peer_id)
+        {
+            fallback_targets.push(target);
+        }
+    }
+    repointed.fallback_targets = fallback_targets;
+    Some(repointed)
+}
+
 async fn write_frame<S, T>(stream: &mut S, value: &T) -> Result<()>
 where
     S: AsyncRead + AsyncWrite + Unpin,
@@ -410,3 +1003,45 @@ where
     serde_json::from_slice(&payload)
         .map_err(|e| anyhow::anyhow!("Failed to decode service-control frame: {e}"))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repoints_primary_target_and_group_members() {
+        let protocol = fungi_util::protocols::service_port_protocol("notes", "main");
+        let rule = ForwardingRule {
+            local_host: "127.0.0.1".to_string(),
+            local_port: 2222,
+            remote_peer_id: "peer-a".to_string(),
+            remote_protocol: Some(protocol.clone()),
+            remote_service_name: Some("notes".to_string()),
+            remote_service_port_name: Some("main".to_string()),
+            fallback_targets: vec![ForwardingTarget {
+                peer_id: "peer-b".to_string(),
+                protocol: protocol.clone(),
+            }],
+            ..Default::default()
+        };
+
+        let repointed = repoint_forwarding_rule(&rule, "notes", "peer-a", "peer-b").unwrap();
+        assert_eq!(repointed.remote_peer_id, "peer-b");
+        assert!(repointed.fallback_targets.is_empty());
+        assert_eq!(repointed.local_port, 2222);
+
+        let grouped = ForwardingRule {
+            remote_peer_id: "peer-c".to_string(),
+            fallback_targets: vec![ForwardingTarget {
+                peer_id: "peer-a".to_string(),
+                protocol,
+            }],
+            ..rule.clone()
+        };
+        let repointed = repoint_forwarding_rule(&grouped, "notes", "peer-a", "peer-b").unwrap();
+        assert_eq!(repointed.remote_peer_id, "peer-c");
+        assert_eq!(repointed.fallback_targets[0].peer_id, "peer-b");
+
+        assert!(repoint_forwarding_rule(&rule, "other", "peer-a", "peer-b").is_none());
+    }
+}
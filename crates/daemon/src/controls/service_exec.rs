@@ -0,0 +1,403 @@
+use std::sync::Arc;
+
+use anyhow::{Result, bail};
+use fungi_config::FungiConfig;
+use fungi_stream::IncomingStreams;
+use fungi_swarm::SwarmControl;
+use fungi_util::protocols::FUNGI_SERVICE_EXEC_PROTOCOL;
+use futures::{AsyncRead, AsyncWrite, StreamExt};
+use libp2p::{
+    PeerId,
+    futures::{AsyncReadExt, AsyncWriteExt},
+};
+use parking_lot::Mutex;
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::{
+    RuntimeControl, ServiceExecExit, ServiceExecInput, ServiceExecOutput, ServiceExecRequest,
+    ServiceExecSession, ServiceExecSize,
+    runtime::{ServiceExecBackend, exec_session_channels},
+};
+
+const MAX_EXEC_FRAME_LEN: usize = 1024 * 1024;
+
+/// Runs commands inside services of remote devices. A session is one stream: the caller sends a
+/// request, the serving device answers with `Started` or an early `Exit`, then stdin/resize
+/// frames flow one way and output frames the other until the final `Exit`. Only peers listed in
+/// `service_exec.allowed_peers` are served.
+#[derive(Clone)]
+pub struct ServiceExecProtocolControl {
+    swarm_control: SwarmControl,
+    runtime_control: RuntimeControl,
+    config: Arc<Mutex<FungiConfig>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ExecFrame {
+    Request(ServiceExecRequest),
+    Started,
+    Stdin(Vec<u8>),
+    CloseStdin,
+    Resize(ServiceExecSize),
+    Stdout(Vec<u8>),
+    Stderr(Vec<u8>),
+    Exit(ServiceExecExit),
+}
+
+impl ServiceExecProtocolControl {
+    pub fn new(
+        swarm_control: SwarmControl,
+        runtime_control: RuntimeControl,
+        config: Arc<Mutex<FungiConfig>>,
+    ) -> Self {
+        Self {
+            swarm_control,
+            runtime_control,
+            config,
+        }
+    }
+
+    pub fn start(&self) -> Result<()> {
+        let incoming_streams = self
+            .swarm_control
+            .accept_incoming_streams(FUNGI_SERVICE_EXEC_PROTOCOL)
+            .map_err(anyhow::Error::from)?;
+        let this = self.clone();
+        tokio::spawn(async move {
+            this.listen_from_incoming_streams(incoming_streams).await;
+        });
+        Ok(())
+    }
+
+    pub async fn exec_on_peer(
+        &self,
+        peer_id: PeerId,
+        request: ServiceExecRequest,
+    ) -> Result<ServiceExecSession> {
+        let (stream, _handle, _connection_id) = self
+            .swarm_control
+            .open_stream(peer_id, FUNGI_SERVICE_EXEC_PROTOCOL)
+            .await
+            .map_err(|e| {
+                anyhow::anyhow!("Failed to open service-exec stream to peer {peer_id}: {e}")
+            })?;
+        let (mut reader, mut writer) = stream.split();
+
+        write_exec_frame(&mut writer, &ExecFrame::Request(request))
+            .await
+            .map_err(|e| {
+                anyhow::anyhow!("Failed to write service-exec request to peer {peer_id}: {e}")
+            })?;
+        match read_exec_frame(&mut reader).await.map_err(|e| {
+            anyhow::anyhow!("Failed to read service-exec reply from peer {peer_id}: {e}")
+        })? {
+            ExecFrame::Started => {}
+            ExecFrame::Exit(exit) => bail!(
+                "{}",
+                exit.error
+                    .unwrap_or_else(|| "remote device refused the exec session".to_string())
+            ),
+            frame => bail!("unexpected service-exec reply from peer {peer_id}: {frame:?}"),
+        }
+
+        let (session, backend) = exec_session_channels();
+        let ServiceExecBackend { mut input, output } = backend;
+
+        tokio::spawn(async move {
+            while let Some(message) = input.recv().await {
+                let frame = match message {
+                    ServiceExecInput::Stdin(data) => ExecFrame::Stdin(data),
+                    ServiceExecInput::CloseStdin => ExecFrame::CloseStdin,
+                    ServiceExecInput::Resize(size) => ExecFrame::Resize(size),
+                };
+                if write_exec_frame(&mut writer, &frame).await.is_err() {
+                    return;
+                }
+            }
+            let _ = writer.close().await;
+        });
+
+        tokio::spawn(async move {
+            loop {
+                let message = match read_exec_frame(&mut reader).await {
+                    Ok(ExecFrame::Stdout(data)) => ServiceExecOutput::Stdout(data),
+                    Ok(ExecFrame::Stderr(data)) => ServiceExecOutput::Stderr(data),
+                    Ok(ExecFrame::Exit(exit)) => {
+                        let _ = output.send(ServiceExecOutput::Exit(exit)).await;
+                        return;
+                    }
+                    Ok(frame) => {
+                        log::debug!(
+                            "Ignoring unexpected service-exec frame from {peer_id}: {frame:?}"
+                        );
+                        continue;
+                    }
+                    Err(error) => {
+                        let exit = ServiceExecExit::failed(format!(
+                            "lost service-exec stream to peer {peer_id}: {error}"
+                        ));
+                        let _ = output.send(ServiceExecOutput::Exit(exit)).await;
+                        return;
+                    }
+                };
+                if output.send(message).await.is_err() {
+                    return;
+                }
+            }
+        });
+
+        Ok(session)
+    }
+
+    async fn listen_from_incoming_streams(self, mut incoming_streams: IncomingStreams) {
+        while let Some(incoming_stream) = incoming_streams.next().await {
+            let peer_id = incoming_stream.peer_id;
+            let runtime_control = self.runtime_control.clone();
+            let allowed = self.config.lock().service_exec.allows(&peer_id);
+            tokio::spawn(async move {
+                if let Err(error) =
+                    serve_exec_session(runtime_control, peer_id, allowed, incoming_stream.stream)
+                        .await
+                {
+                    log::warn!("Service-exec session from peer {peer_id} failed: {error}");
+                }
+            });
+        }
+    }
+}
+
+async fn serve_exec_session<S>(
+    runtime_control: RuntimeControl,
+    peer_id: PeerId,
+    allowed: bool,
+    stream: S,
+) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let (mut reader, mut writer) = stream.split();
+    let request = match read_exec_frame(&mut reader).await? {
+        ExecFrame::Request(request) => request,
+        frame => bail!("expected a service-exec request, got {frame:?}"),
+    };
+    if !allowed {
+        log::warn!(
+            "Refused exec into service '{}' from peer {peer_id}: not in service_exec.allowed_peers",
+            request.service
+        );
+        write_exec_frame(
+            &mut writer,
+            &ExecFrame::Exit(ServiceExecExit::failed(format!(
+                "this device does not allow exec from {peer_id}; add it to service_exec.allowed_peers there"
+            ))),
+        )
+        .await?;
+        let _ = writer.close().await;
+        return Ok(());
+    }
+    log::info!(
+        "Peer {peer_id} is running {:?} in service '{}'",
+        request.command,
+        request.service
+    );
+
+    let ServiceExecSession { input, mut output } = match runtime_control.exec(&request).await {
+        Ok(session) => session,
+        Err(error) => {
+            write_exec_frame(
+                &mut writer,
+                &ExecFrame::Exit(ServiceExecExit::failed(format!("{error:#}"))),
+            )
+            .await?;
+            let _ = writer.close().await;
+            return Ok(());
+        }
+    };
+    write_exec_frame(&mut writer, &ExecFrame::Started).await?;
+
+    // Dropping `input` when the caller hangs up closes the command's stdin.
+    let input_task = tokio::spawn(async move {
+        while let Ok(frame) = read_exec_frame(&mut reader).await {
+            let message = match frame {
+                ExecFrame::Stdin(data) => ServiceExecInput::Stdin(data),
+                ExecFrame::CloseStdin => ServiceExecInput::CloseStdin,
+                ExecFrame::Resize(size) => ServiceExecInput::Resize(size),
+                _ => continue,
+            };
+            if input.send(message).await.is_err() {
+                return;
+            }
+        }
+    });
+
+    while let Some(message) = output.recv().await {
+        let (frame, last) = match message {
+            ServiceExecOutput::Stdout(data) => (ExecFrame::Stdout(data), false),
+            ServiceExecOutput::Stderr(data) => (ExecFrame::Stderr(data), false),
+            ServiceExecOutput::Exit(exit) => (ExecFrame::Exit(exit), true),
+        };
+        // A failed write means the caller is gone; returning drops `output`, which stops the command.
+        write_exec_frame(&mut writer, &frame).await?;
+        if last {
+            break;
+        }
+    }
+    input_task.abort();
+    let _ = writer.close().await;
+    Ok(())
+}
+
+impl ExecFrame {
+    fn kind(&self) -> u8 {
+        match self {
+            Self::Request(_) => 1,
+            Self::Started => 2,
+            Self::Stdin(_) => 3,
+            Self::CloseStdin => 4,
+            Self::Resize(_) => 5,
+            Self::Stdout(_) => 6,
+            Self::Stderr(_) => 7,
+            Self::Exit(_) => 8,
+        }
+    }
+
+    fn payload(&self) -> Result<Vec<u8>> {
+        Ok(match self {
+            Self::Request(request) => encode_json(request)?,
+            Self::Started | Self::CloseStdin => Vec::new(),
+            Self::Stdin(data) | Self::Stdout(data) | Self::Stderr(data) => data.clone(),
+            Self::Resize(size) => encode_json(size)?,
+            Self::Exit(exit) => encode_json(exit)?,
+        })
+    }
+
+    fn decode(kind: u8, payload: Vec<u8>) -> Result<Self> {
+        Ok(match kind {
+            1 => Self::Request(decode_json(&payload)?),
+            2 => Self::Started,
+            3 => Self::Stdin(payload),
+            4 => Self::CloseStdin,
+            5 => Self::Resize(decode_json(&payload)?),
+            6 => Self::Stdout(payload),
+            7 => Self::Stderr(payload),
+            8 => Self::Exit(decode_json(&payload)?),
+            kind => bail!("unknown service-exec frame kind {kind}"),
+        })
+    }
+}
+
+fn encode_json<T: Serialize>(value: &T) -> Result<Vec<u8>> {
+    serde_json::to_vec(value)
+        .map_err(|e| anyhow::anyhow!("Failed to serialize service-exec frame: {e}"))
+}
+
+fn decode_json<T: DeserializeOwned>(payload: &[u8]) -> Result<T> {
+    serde_json::from_slice(payload)
+        .map_err(|e| anyhow::anyhow!("Failed to decode service-exec frame: {e}"))
+}
+
+/// Frames are a kind byte, a big-endian u32 payload length and the payload.
+async fn write_exec_frame<W>(writer: &mut W, frame: &ExecFrame) -> Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    let payload = frame.payload()?;
+    if payload.len() > MAX_EXEC_FRAME_LEN {
+        bail!("Service-exec frame is too large");
+    }
+
+    let mut header = [0u8; 5];
+    header[0] = frame.kind();
+    header[1..].copy_from_slice(&(payload.len() as u32).to_be_bytes());
+    writer
+        .write_all(&header)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to write frame header: {e}"))?;
+    writer
+        .write_all(&payload)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to write frame payload: {e}"))?;
+    writer
+        .flush()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to flush frame payload: {e}"))?;
+    Ok(())
+}
+
+async fn read_exec_frame<R>(reader: &mut R) -> Result<ExecFrame>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut header = [0u8; 5];
+    reader
+        .read_exact(&mut header)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to read frame header: {e}"))?;
+    let payload_len = u32::from_be_bytes([header[1], header[2], header[3], header[4]]) as usize;
+    if payload_len > MAX_EXEC_FRAME_LEN {
+        bail!(
+            "Service-exec frame too large: {} bytes (max {})",
+            payload_len,
+            MAX_EXEC_FRAME_LEN
+        );
+    }
+
+    let mut payload = vec![0u8; payload_len];
+    reader
+        .read_exact(&mut payload)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to read frame payload: {e}"))?;
+    ExecFrame::decode(header[0], payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::io::Cursor;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn exec_frames_round_trip() {
+        let frames = vec![
+            ExecFrame::Request(ServiceExecRequest {
+                service: "web".into(),
+                command: vec!["sh".into(), "-c".into(), "id".into()],
+                tty: true,
+                size: Some(ServiceExecSize { rows: 24, cols: 80 }),
+            }),
+            ExecFrame::Started,
+            ExecFrame::Stdin(b"ls\n".to_vec()),
+            ExecFrame::CloseStdin,
+            ExecFrame::Resize(ServiceExecSize {
+                rows: 50,
+                cols: 120,
+            }),
+            ExecFrame::Stdout(b"out".to_vec()),
+            ExecFrame::Stderr(Vec::new()),
+            ExecFrame::Exit(ServiceExecExit {
+                exit_code: Some(3),
+                error: None,
+            }),
+        ];
+
+        let mut buffer = Cursor::new(Vec::new());
+        for frame in &frames {
+            write_exec_frame(&mut buffer, frame).await.unwrap();
+        }
+        buffer.set_position(0);
+        for frame in frames {
+            assert_eq!(read_exec_frame(&mut buffer).await.unwrap(), frame);
+        }
+        assert!(read_exec_frame(&mut buffer).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn rejects_unknown_and_oversized_frames() {
+        let mut unknown = Cursor::new(vec![42, 0, 0, 0, 0]);
+        assert!(read_exec_frame(&mut unknown).await.is_err());
+
+        let mut oversized = Cursor::new(vec![3, 0xff, 0xff, 0xff, 0xff]);
+        let error = read_exec_frame(&mut oversized).await.unwrap_err();
+        assert!(error.to_string().contains("too large"));
+    }
+}
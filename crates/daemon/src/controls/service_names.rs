@@ -0,0 +1,96 @@
+use std::{path::Path, sync::Arc};
+
+use fungi_config::{devices::DevicesConfig, service_cache::DeviceServiceSnapshotCache};
+use libp2p::PeerId;
+
+use crate::{DeviceService, DeviceServiceSnapshot};
+
+/// Cached services of one saved device, addressed by the device's name.
+#[derive(Debug, Clone)]
+pub struct NamedDeviceServices {
+    pub peer_id: PeerId,
+    pub device_name: String,
+    pub services: Vec<DeviceService>,
+}
+
+impl NamedDeviceServices {
+    pub fn device_label(&self) -> String {
+        dns_label(&self.device_name)
+    }
+}
+
+pub(crate) type DeviceServicesSource = Arc<dyn Fn() -> Vec<NamedDeviceServices> + Send + Sync>;
+
+/// Services of every named saved device whose snapshot is cached locally. Unnamed devices have
+/// no stable host name and are skipped.
+pub(crate) fn cached_named_device_services(
+    devices: &DevicesConfig,
+    fungi_dir: &Path,
+) -> Vec<NamedDeviceServices> {
+    let cache = match DeviceServiceSnapshotCache::apply_from_dir(fungi_dir) {
+        Ok(cache) => cache,
+        Err(error) => {
+            log::warn!("Failed to open the device service snapshot cache: {error}");
+            return Vec::new();
+        }
+    };
+
+    devices
+        .get_all_devices()
+        .iter()
+        .filter_map(|device| {
+            let device_name = device.name.clone()?;
+            let snapshot = cache
+                .get_device_snapshot_json(&device.peer_id.to_string())
+                .ok()
+                .flatten()
+                .and_then(|json| serde_json::from_str::<DeviceServiceSnapshot>(&json).ok())?;
+            Some(NamedDeviceServices {
+                peer_id: device.peer_id,
+                device_name,
+                services: snapshot.services,
+            })
+        })
+        .collect()
+}
+
+/// Lowercases `name` and folds everything outside `[a-z0-9]` into single dashes.
+pub(crate) fn dns_label(name: &str) -> String {
+    let mut label = String::new();
+    for ch in name.trim().chars() {
+        if ch.is_ascii_alphanumeric() {
+            label.push(ch.to_ascii_lowercase());
+        } else if !label.is_empty() && !label.ends_with('-') {
+            label.push('-');
+        }
+    }
+    label.trim_end_matches('-').to_string()
+}
+
+/// `host` without a trailing `:port`, keeping IPv6 brackets intact.
+pub(crate) fn strip_port(host: &str) -> &str {
+    if host.starts_with('[') {
+        return host
+            .split_once(']')
+            .map_or(host, |(ip, _)| &host[..=ip.len()]);
+    }
+    match host.rsplit_once(':') {
+        Some((name, port)) if port.chars().all(|ch| ch.is_ascii_digit()) => name,
+        _ => host,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dns_labels_fold_names_into_host_safe_form() {
+        assert_eq!(dns_label("Home NAS"), "home-nas");
+        assert_eq!(dns_label("  file_browser!! "), "file-browser");
+        assert_eq!(dns_label("--"), "");
+        assert_eq!(strip_port("files.nas.fungi:22"), "files.nas.fungi");
+        assert_eq!(strip_port("[::1]:5480"), "[::1]");
+        assert_eq!(strip_port("nas.fungi"), "nas.fungi");
+    }
+}
@@ -0,0 +1,468 @@
+use std::{net::SocketAddr, sync::Arc};
+
+use anyhow::{Context, Result, bail};
+use fungi_swarm::SwarmControl;
+use libp2p::{PeerId, Stream, StreamProtocol};
+use parking_lot::Mutex;
+use thiserror::Error;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    task::JoinHandle,
+};
+use tokio_util::compat::FuturesAsyncReadCompatExt;
+
+use super::http_gateway::{read_request_head, respond};
+use super::service_names::{DeviceServicesSource, NamedDeviceServices, dns_label, strip_port};
+
+const SOCKS5_VERSION: u8 = 0x05;
+const SOCKS5_NO_AUTH: u8 = 0x00;
+const SOCKS5_NO_ACCEPTABLE_METHOD: u8 = 0xff;
+const SOCKS5_CMD_CONNECT: u8 = 0x01;
+const SOCKS5_ATYP_IPV4: u8 = 0x01;
+const SOCKS5_ATYP_DOMAIN: u8 = 0x03;
+const SOCKS5_ATYP_IPV6: u8 = 0x04;
+
+const SOCKS5_REPLY_SUCCEEDED: u8 = 0x00;
+const SOCKS5_REPLY_NOT_ALLOWED: u8 = 0x02;
+const SOCKS5_REPLY_HOST_UNREACHABLE: u8 = 0x04;
+const SOCKS5_REPLY_COMMAND_NOT_SUPPORTED: u8 = 0x07;
+const SOCKS5_REPLY_ADDRESS_TYPE_NOT_SUPPORTED: u8 = 0x08;
+
+/// The published service entry a proxy destination name resolves to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceProxyTarget {
+    pub peer_id: PeerId,
+    pub device_name: String,
+    pub service_name: String,
+    pub entry: String,
+    pub protocol: String,
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+enum ServiceProxyError {
+    #[error("{0} is not a .{1} service name")]
+    OutsideDomain(String, String),
+    #[error("no saved device is named {0}")]
+    UnknownDevice(String),
+    #[error("device {device} has no service named {service}")]
+    UnknownService { device: String, service: String },
+    #[error("service {service} on {device} has no entry named {entry}")]
+    UnknownEntry {
+        device: String,
+        service: String,
+        entry: String,
+    },
+    #[error("service {service} on {device} has several entries; use <service>.<entry>.<device>")]
+    AmbiguousEntry { device: String, service: String },
+    #[error("device {0} is not trusted")]
+    Untrusted(String),
+    #[error("could not reach {0}")]
+    Unreachable(String),
+}
+
+impl ServiceProxyError {
+    fn socks5_reply(&self) -> u8 {
+        match self {
+            Self::Untrusted(_) => SOCKS5_REPLY_NOT_ALLOWED,
+            _ => SOCKS5_REPLY_HOST_UNREACHABLE,
+        }
+    }
+
+    fn http_status(&self) -> (u16, &'static str) {
+        match self {
+            Self::Untrusted(_) => (403, "Forbidden"),
+            Self::Unreachable(_) => (502, "Bad Gateway"),
+            _ => (404, "Not Found"),
+        }
+    }
+}
+
+/// SOCKS5 and HTTP CONNECT proxy resolving `<service>.<entry>.<device>.<domain>` against the
+/// cached device service snapshots. Each client connection gets its own service stream.
+#[derive(Clone)]
+pub struct ServiceProxyControl {
+    swarm_control: SwarmControl,
+    domain: String,
+    device_services: DeviceServicesSource,
+    local_addr: Arc<Mutex<Option<SocketAddr>>>,
+}
+
+impl ServiceProxyControl {
+    pub(crate) fn new(
+        swarm_control: SwarmControl,
+        domain: String,
+        device_services: DeviceServicesSource,
+    ) -> Self {
+        Self {
+            swarm_control,
+            domain: domain.trim_matches('.').to_ascii_lowercase(),
+            device_services,
+            local_addr: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    pub async fn start(&self, listen_address: &str) -> Result<JoinHandle<()>> {
+        let listener = TcpListener::bind(listen_address)
+            .await
+            .with_context(|| format!("failed to bind service proxy to {listen_address}"))?;
+        let local_addr = listener.local_addr()?;
+        *self.local_addr.lock() = Some(local_addr);
+        log::info!(
+            "Service proxy (SOCKS5, HTTP CONNECT) listening on {local_addr} for <service>.<entry>.<device>.{}",
+            self.domain
+        );
+
+        let this = self.clone();
+        Ok(tokio::spawn(async move {
+            loop {
+                let (tcp_stream, client_addr) = match listener.accept().await {
+                    Ok(accepted) => accepted,
+                    Err(error) => {
+                        log::error!("Service proxy failed to accept connection: {error}");
+                        continue;
+                    }
+                };
+                let this = this.clone();
+                tokio::spawn(async move {
+                    if let Err(error) = this.handle_connection(tcp_stream).await {
+                        log::debug!("Service proxy connection from {client_addr} failed: {error}");
+                    }
+                });
+            }
+        }))
+    }
+
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        *self.local_addr.lock()
+    }
+
+    async fn handle_connection(&self, tcp_stream: TcpStream) -> Result<()> {
+        let mut version = [0u8; 1];
+        if tcp_stream.peek(&mut version).await? == 0 {
+            return Ok(());
+        }
+        if version[0] == SOCKS5_VERSION {
+            self.handle_socks5(tcp_stream).await
+        } else {
+            self.handle_http_connect(tcp_stream).await
+        }
+    }
+
+    async fn handle_socks5(&self, mut tcp_stream: TcpStream) -> Result<()> {
+        let mut greeting = [0u8; 2];
+        tcp_stream.read_exact(&mut greeting).await?;
+        let mut methods = vec![0u8; usize::from(greeting[1])];
+        tcp_stream.read_exact(&mut methods).await?;
+        if !methods.contains(&SOCKS5_NO_AUTH) {
+            tcp_stream
+                .write_all(&[SOCKS5_VERSION, SOCKS5_NO_ACCEPTABLE_METHOD])
+                .await?;
+            bail!("SOCKS5 client does not offer unauthenticated access");
+        }
+        tcp_stream
+            .write_all(&[SOCKS5_VERSION, SOCKS5_NO_AUTH])
+            .await?;
+
+        let mut request = [0u8; 4];
+        tcp_stream.read_exact(&mut request).await?;
+        if request[1] != SOCKS5_CMD_CONNECT {
+            socks5_reply(&mut tcp_stream, SOCKS5_REPLY_COMMAND_NOT_SUPPORTED).await?;
+            bail!("unsupported SOCKS5 command {}", request[1]);
+        }
+        let host = match request[3] {
+            SOCKS5_ATYP_DOMAIN => {
+                let mut len = [0u8; 1];
+                tcp_stream.read_exact(&mut len).await?;
+                let mut host = vec![0u8; usize::from(len[0])];
+                tcp_stream.read_exact(&mut host).await?;
+                String::from_utf8_lossy(&host).into_owned()
+            }
+            SOCKS5_ATYP_IPV4 | SOCKS5_ATYP_IPV6 => {
+                // The client resolved the name itself (e.g. curl's socks5:// instead of
+                // socks5h://), so there is no service name left to route on.
+                socks5_reply(&mut tcp_stream, SOCKS5_REPLY_ADDRESS_TYPE_NOT_SUPPORTED).await?;
+                bail!("SOCKS5 destination is an IP address; let the proxy resolve names");
+            }
+            other => {
+                socks5_reply(&mut tcp_stream, SOCKS5_REPLY_ADDRESS_TYPE_NOT_SUPPORTED).await?;
+                bail!("unknown SOCKS5 address type {other}");
+            }
+        };
+        // The destination port is meaningless here: the name already selects the entry.
+        let mut port = [0u8; 2];
+        tcp_stream.read_exact(&mut port).await?;
+
+        match self.open_service_stream(&host).await {
+            Ok(p2p_stream) => {
+                socks5_reply(&mut tcp_stream, SOCKS5_REPLY_SUCCEEDED).await?;
+                splice(p2p_stream, tcp_stream, &[]).await
+            }
+            Err(error) => {
+                socks5_reply(&mut tcp_stream, error.socks5_reply()).await?;
+                bail!("{error}");
+            }
+        }
+    }
+
+    async fn handle_http_connect(&self, mut tcp_stream: TcpStream) -> Result<()> {
+        let head = read_request_head(&mut tcp_stream).await?;
+        let Some((authority, body_start)) = parse_connect_request(&head) else {
+            return respond(
+                &mut tcp_stream,
+                405,
+                "Method Not Allowed",
+                "This proxy only supports CONNECT.",
+            )
+            .await;
+        };
+
+        match self.open_service_stream(strip_port(&authority)).await {
+            Ok(p2p_stream) => {
+                tcp_stream
+                    .write_all(b"HTTP/1.1 200 Connection Established\r\n\r\n")
+                    .await?;
+                splice(p2p_stream, tcp_stream, &head[body_start..]).await
+            }
+            Err(error) => {
+                let (status, reason) = error.http_status();
+                respond(&mut tcp_stream, status, reason, &error.to_string()).await
+            }
+        }
+    }
+
+    async fn open_service_stream(&self, host: &str) -> Result<Stream, ServiceProxyError> {
+        let target = resolve_target(host, &self.domain, &(self.device_services)())?;
+        // The same allow list that gates inbound connections decides which devices are reachable.
+        if !self
+            .swarm_control
+            .state()
+            .incoming_allowed_peers()
+            .read()
+            .contains(&target.peer_id)
+        {
+            return Err(ServiceProxyError::Untrusted(target.device_name));
+        }
+
+        let unreachable = || {
+            ServiceProxyError::Unreachable(format!(
+                "{}.{} on {}",
+                target.service_name, target.entry, target.device_name
+            ))
+        };
+        let protocol =
+            StreamProtocol::try_from_owned(target.protocol.clone()).map_err(|_| unreachable())?;
+        match self
+            .swarm_control
+            .open_stream(target.peer_id, protocol)
+            .await
+        {
+            Ok((stream, _stream_observation_handle, _connection_id)) => Ok(stream),
+            Err(error) => {
+                log::warn!("Service proxy failed to open a stream to {host}: {error}");
+                Err(unreachable())
+            }
+        }
+    }
+}
+
+fn resolve_target(
+    host: &str,
+    domain: &str,
+    devices: &[NamedDeviceServices],
+) -> Result<ServiceProxyTarget, ServiceProxyError> {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    let labels = host
+        .strip_suffix(domain)
+        .and_then(|name| name.strip_suffix('.'))
+        .map(|name| name.split('.').collect::<Vec<_>>())
+        .ok_or_else(|| ServiceProxyError::OutsideDomain(host.clone(), domain.to_string()))?;
+    let (service_label, entry_label, device_label) = match labels.as_slice() {
+        [service, entry, device] => (*service, Some(*entry), *device),
+        [service, device] => (*service, None, *device),
+        _ => {
+            return Err(ServiceProxyError::OutsideDomain(
+                host.clone(),
+                domain.to_string(),
+            ));
+        }
+    };
+
+    let device = devices
+        .iter()
+        .find(|device| device.device_label() == device_label)
+        .ok_or_else(|| ServiceProxyError::UnknownDevice(device_label.to_string()))?;
+    let service = device
+        .services
+        .iter()
+        .find(|service| dns_label(&service.name) == service_label)
+        .ok_or_else(|| ServiceProxyError::UnknownService {
+            device: device.device_name.clone(),
+            service: service_label.to_string(),
+        })?;
+    let endpoint = match entry_label {
+        Some(entry_label) => service
+            .endpoints
+            .iter()
+            .find(|endpoint| dns_label(&endpoint.name) == entry_label)
+            .ok_or_else(|| ServiceProxyError::UnknownEntry {
+                device: device.device_name.clone(),
+                service: service.name.clone(),
+                entry: entry_label.to_string(),
+            })?,
+        None => match service.endpoints.as_slice() {
+            [endpoint] => endpoint,
+            [] => {
+                return Err(ServiceProxyError::UnknownEntry {
+                    device: device.device_name.clone(),
+                    service: service.name.clone(),
+                    entry: "default".to_string(),
+                });
+            }
+            _ => {
+                return Err(ServiceProxyError::AmbiguousEntry {
+                    device: device.device_name.clone(),
+                    service: service.name.clone(),
+                });
+            }
+        },
+    };
+
+    Ok(ServiceProxyTarget {
+        peer_id: device.peer_id,
+        device_name: device.device_name.clone(),
+        service_name: service.name.clone(),
+        entry: endpoint.name.clone(),
+        protocol: endpoint.protocol.clone(),
+    })
+}
+
+/// The CONNECT authority and where the tunneled bytes start within `head`.
+fn parse_connect_request(head: &[u8]) -> Option<(String, usize)> {
+    let body_start = head.windows(4).position(|window| window == b"\r\n\r\n")? + 4;
+    let request_line = std::str::from_utf8(&head[..body_start])
+        .ok()?
+        .lines()
+        .next()?;
+    let mut parts = request_line.split_whitespace();
+    if !parts.next()?.eq_ignore_ascii_case("CONNECT") {
+        return None;
+    }
+    Some((parts.next()?.to_string(), body_start))
+}
+
+async fn socks5_reply(tcp_stream: &mut TcpStream, reply: u8) -> Result<()> {
+    tcp_stream
+        .write_all(&[
+            SOCKS5_VERSION,
+            reply,
+            0x00,
+            SOCKS5_ATYP_IPV4,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+        ])
+        .await?;
+    Ok(())
+}
+
+async fn splice(p2p_stream: Stream, mut tcp_stream: TcpStream, pending: &[u8]) -> Result<()> {
+    let mut p2p_stream = p2p_stream.compat();
+    if !pending.is_empty() {
+        p2p_stream.write_all(pending).await?;
+    }
+    tokio::io::copy_bidirectional(&mut p2p_stream, &mut tcp_stream).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        DeviceService, DeviceServiceEndpoint, DeviceServiceMetadata, RuntimeKind, ServiceStatus,
+    };
+
+    fn device(name: &str, services: &[(&str, &[&str])]) -> NamedDeviceServices {
+        NamedDeviceServices {
+            peer_id: PeerId::random(),
+            device_name: name.to_string(),
+            services: services
+                .iter()
+                .map(|(service, entries)| DeviceService {
+                    name: service.to_string(),
+                    definition_id: None,
+                    runtime: RuntimeKind::Docker,
+                    metadata: DeviceServiceMetadata::default(),
+                    endpoints: entries
+                        .iter()
+                        .map(|entry| DeviceServiceEndpoint {
+                            name: entry.to_string(),
+                            protocol: fungi_util::protocols::service_port_protocol(service, entry),
+                        })
+                        .collect(),
+                    status: ServiceStatus::running(),
+                    resources: None,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn resolves_service_entry_and_device_labels() {
+        let devices = [
+            device("laptop", &[("postgres", &["sql"])]),
+            device("Home NAS", &[("files", &["web", "ssh"])]),
+        ];
+
+        let target = resolve_target("files.ssh.home-nas.fungi", "fungi", &devices).unwrap();
+        assert_eq!(target.peer_id, devices[1].peer_id);
+        assert_eq!(target.entry, "ssh");
+        assert_eq!(
+            target.protocol,
+            fungi_util::protocols::service_port_protocol("files", "ssh")
+        );
+
+        let target = resolve_target("Postgres.Laptop.fungi.", "fungi", &devices).unwrap();
+        assert_eq!(target.service_name, "postgres");
+        assert_eq!(target.entry, "sql");
+    }
+
+    #[test]
+    fn reports_why_a_name_does_not_resolve() {
+        let devices = [device("nas", &[("files", &["web", "ssh"])])];
+
+        assert!(matches!(
+            resolve_target("files.nas.fungi", "fungi", &devices),
+            Err(ServiceProxyError::AmbiguousEntry { .. })
+        ));
+        assert!(matches!(
+            resolve_target("files.ftp.nas.fungi", "fungi", &devices),
+            Err(ServiceProxyError::UnknownEntry { .. })
+        ));
+        assert_eq!(
+            resolve_target("files.web.laptop.fungi", "fungi", &devices),
+            Err(ServiceProxyError::UnknownDevice("laptop".to_string()))
+        );
+        assert!(matches!(
+            resolve_target("example.com", "fungi", &devices),
+            Err(ServiceProxyError::OutsideDomain(..))
+        ));
+    }
+
+    #[test]
+    fn parses_connect_authority_and_keeps_tunneled_bytes() {
+        let head = b"CONNECT files.ssh.nas.fungi:22 HTTP/1.1\r\nHost: files.ssh.nas.fungi:22\r\n\r\nSSH-2.0";
+
+        let (authority, body_start) = parse_connect_request(head).unwrap();
+
+        assert_eq!(authority, "files.ssh.nas.fungi:22");
+        assert_eq!(&head[body_start..], b"SSH-2.0");
+        assert_eq!(
+            parse_connect_request(b"GET / HTTP/1.1\r\nHost: nas\r\n\r\n"),
+            None
+        );
+    }
+}
@@ -0,0 +1,424 @@
+use std::{
+    fs,
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{Context as _, Result, bail};
+use fungi_config::{FungiConfig, paths::FungiPaths};
+use fungi_stream::IncomingStreams;
+use fungi_swarm::SwarmControl;
+use fungi_util::protocols::FUNGI_SERVICE_TRANSFER_PROTOCOL;
+use futures::{AsyncRead, AsyncWrite, StreamExt};
+use libp2p::{
+    PeerId,
+    futures::{AsyncReadExt, AsyncWriteExt},
+};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use tempfile::NamedTempFile;
+
+use crate::RuntimeControl;
+
+const MAX_TRANSFER_HEADER_LEN: usize = 2 * 1024 * 1024;
+const TRANSFER_CHUNK: usize = 64 * 1024;
+const APPDATA_ARCHIVE_DIR: &str = "appdata";
+const ARTIFACTS_ARCHIVE_DIR: &str = "artifacts";
+
+/// Hands a stopped service to another device. The fetching device sends the service name and
+/// gets back a JSON header with the service's portable manifest, followed by a gzipped tar of
+/// its appdata and artifacts directories. Only devices in `service_transfer.allowed_peers` are
+/// served.
+#[derive(Clone)]
+pub struct ServiceTransferProtocolControl {
+    swarm_control: SwarmControl,
+    fungi_home: PathBuf,
+    runtime_control: RuntimeControl,
+    config: Arc<Mutex<FungiConfig>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ServiceTransferRequest {
+    service: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ServiceTransferHeader {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    #[serde(default)]
+    manifest_yaml: String,
+    #[serde(default)]
+    archive_len: u64,
+}
+
+/// A service fetched from another device, waiting to be unpacked on this one.
+pub struct ServiceTransferSnapshot {
+    pub manifest_yaml: String,
+    archive: NamedTempFile,
+}
+
+impl ServiceTransferSnapshot {
+    /// Unpacks the transferred appdata and artifacts as the directories of `local_service_id`,
+    /// replacing whatever is there.
+    pub async fn unpack(self, fungi_home: &Path, local_service_id: &str) -> Result<()> {
+        let fungi_home = fungi_home.to_path_buf();
+        let local_service_id = local_service_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            unpack_service_archive(self.archive.path(), &fungi_home, &local_service_id)
+        })
+        .await
+        .context("service archive unpack task failed")?
+    }
+}
+
+impl ServiceTransferProtocolControl {
+    pub fn new(
+        swarm_control: SwarmControl,
+        fungi_home: PathBuf,
+        runtime_control: RuntimeControl,
+        config: Arc<Mutex<FungiConfig>>,
+    ) -> Self {
+        Self {
+            swarm_control,
+            fungi_home,
+            runtime_control,
+            config,
+        }
+    }
+
+    pub fn start(&self) -> Result<()> {
+        let incoming_streams = self
+            .swarm_control
+            .accept_incoming_streams(FUNGI_SERVICE_TRANSFER_PROTOCOL)
+            .map_err(anyhow::Error::from)?;
+        let this = self.clone();
+        tokio::spawn(async move {
+            this.listen_from_incoming_streams(incoming_streams).await;
+        });
+        Ok(())
+    }
+
+    /// Downloads `service` from `peer_id`, which must have stopped it first.
+    pub async fn fetch_from_peer(
+        &self,
+        peer_id: PeerId,
+        service: &str,
+    ) -> Result<ServiceTransferSnapshot> {
+        let (mut stream, _handle, _connection_id) = self
+            .swarm_control
+            .open_stream(peer_id, FUNGI_SERVICE_TRANSFER_PROTOCOL)
+            .await
+            .map_err(|e| {
+                anyhow::anyhow!("Failed to open service-transfer stream to peer {peer_id}: {e}")
+            })?;
+
+        let request = ServiceTransferRequest {
+            service: service.to_string(),
+        };
+        write_frame(&mut stream, &request).await.map_err(|e| {
+            anyhow::anyhow!("Failed to write service-transfer request to peer {peer_id}: {e}")
+        })?;
+        let header = read_frame::<_, ServiceTransferHeader>(&mut stream)
+            .await
+            .map_err(|e| {
+                anyhow::anyhow!("Failed to read service-transfer header from peer {peer_id}: {e}")
+            })?;
+        if let Some(error) = header.error {
+            bail!("peer {peer_id} refused to transfer service '{service}': {error}");
+        }
+
+        let archive = NamedTempFile::new_in(&self.fungi_home)
+            .context("Failed to create service transfer file")?;
+        let mut file = archive.reopen()?;
+        let mut remaining = header.archive_len;
+        let mut buffer = vec![0u8; TRANSFER_CHUNK];
+        while remaining > 0 {
+            let chunk = remaining.min(TRANSFER_CHUNK as u64) as usize;
+            stream.read_exact(&mut buffer[..chunk]).await.map_err(|e| {
+                anyhow::anyhow!("Lost service-transfer stream from peer {peer_id}: {e}")
+            })?;
+            file.write_all(&buffer[..chunk])
+                .context("Failed to write service transfer file")?;
+            remaining -= chunk as u64;
+        }
+        file.flush()?;
+        let _ = stream.close().await;
+
+        Ok(ServiceTransferSnapshot {
+            manifest_yaml: header.manifest_yaml,
+            archive,
+        })
+    }
+
+    async fn listen_from_incoming_streams(self, mut incoming_streams: IncomingStreams) {
+        while let Some(incoming_stream) = incoming_streams.next().await {
+            let peer_id = incoming_stream.peer_id;
+            let mut stream = incoming_stream.stream;
+            let this = self.clone();
+            tokio::spawn(async move {
+                if let Err(error) = this.serve_transfer(peer_id, &mut stream).await {
+                    log::warn!("Failed to transfer service to peer {peer_id}: {error}");
+                }
+                let _ = stream.close().await;
+            });
+        }
+    }
+
+    async fn serve_transfer<S>(&self, peer_id: PeerId, stream: &mut S) -> Result<()>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        let request = read_frame::<_, ServiceTransferRequest>(stream).await?;
+        if !self.config.lock().service_transfer.allows(&peer_id) {
+            log::warn!(
+                "Refused transfer of service '{}' to peer {peer_id}: not in service_transfer.allowed_peers",
+                request.service
+            );
+            let header = ServiceTransferHeader {
+                error: Some(format!(
+                    "this device does not allow transfers to {peer_id}; add it to service_transfer.allowed_peers there"
+                )),
+                ..Default::default()
+            };
+            return write_frame(stream, &header).await;
+        }
+        let (header, archive) = match self.pack_service(&request.service).await {
+            Ok((manifest_yaml, archive)) => {
+                let header = ServiceTransferHeader {
+                    error: None,
+                    manifest_yaml,
+                    archive_len: archive.as_file().metadata()?.len(),
+                };
+                (header, Some(archive))
+            }
+            Err(error) => {
+                let header = ServiceTransferHeader {
+                    error: Some(format!("{error:#}")),
+                    ..Default::default()
+                };
+                (header, None)
+            }
+        };
+        write_frame(stream, &header).await?;
+        let Some(archive) = archive else {
+            return Ok(());
+        };
+
+        let mut file = archive.reopen()?;
+        let mut buffer = vec![0u8; TRANSFER_CHUNK];
+        loop {
+            let size = file.read(&mut buffer)?;
+            if size == 0 {
+                break;
+            }
+            stream
+                .write_all(&buffer[..size])
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to write service archive: {e}"))?;
+        }
+        stream
+            .flush()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to flush service archive: {e}"))?;
+        log::info!(
+            "Transferred service '{}' to peer {peer_id}",
+            request.service
+        );
+        Ok(())
+    }
+
+    async fn pack_service(&self, service: &str) -> Result<(String, NamedTempFile)> {
+        let instance = self.runtime_control.inspect_by_name(service).await?;
+        if instance.status.is_running() {
+            bail!("service '{service}' must be stopped before it is transferred");
+        }
+        let manifest_yaml = self
+            .runtime_control
+            .portable_manifest_yaml(service, &self.fungi_home)?;
+        let local_service_id = self.runtime_control.local_service_id(service)?;
+        let fungi_home = self.fungi_home.clone();
+        let archive = tokio::task::spawn_blocking(move || {
+            pack_service_archive(&fungi_home, &local_service_id)
+        })
+        .await
+        .context("service archive task failed")??;
+        Ok((manifest_yaml, archive))
+    }
+}
+
+fn pack_service_archive(fungi_home: &Path, local_service_id: &str) -> Result<NamedTempFile> {
+    let archive =
+        NamedTempFile::new_in(fungi_home).context("Failed to create service archive file")?;
+    let encoder = flate2::write::GzEncoder::new(archive.reopen()?, flate2::Compression::default());
+    let mut builder = tar::Builder::new(encoder);
+    append_service_data(&mut builder, fungi_home, local_service_id)?;
+    builder.into_inner()?.finish()?.flush()?;
+    Ok(archive)
+}
+
+/// Adds the appdata and artifacts directories of `local_service_id` to `builder`, in the layout
+/// [`unpack_service_archive`] expects.
+pub(super) fn append_service_data<W: Write>(
+    builder: &mut tar::Builder<W>,
+    fungi_home: &Path,
+    local_service_id: &str,
+) -> Result<()> {
+    let paths = FungiPaths::from_fungi_home(fungi_home);
+    // Keep links as links; following them could pull in files from outside the service.
+    builder.follow_symlinks(false);
+    for (name, dir) in [
+        (
+            APPDATA_ARCHIVE_DIR,
+            paths.service_appdata_dir(local_service_id),
+        ),
+        (
+            ARTIFACTS_ARCHIVE_DIR,
+            paths.service_artifacts_dir(local_service_id),
+        ),
+    ] {
+        if dir.is_dir() {
+            builder
+                .append_dir_all(name, &dir)
+                .with_context(|| format!("Failed to archive {}", dir.display()))?;
+        }
+    }
+    Ok(())
+}
+
+/// Replaces the appdata and artifacts directories of `local_service_id` with the ones in
+/// `archive`. Other entries in the archive are ignored.
+pub(super) fn unpack_service_archive(
+    archive: &Path,
+    fungi_home: &Path,
+    local_service_id: &str,
+) -> Result<()> {
+    let paths = FungiPaths::from_fungi_home(fungi_home);
+    let staging = tempfile::Builder::new()
+        .prefix(".service-transfer-")
+        .tempdir_in(fungi_home)
+        .context("Failed to create service transfer staging dir")?;
+    let file = fs::File::open(archive)?;
+    tar::Archive::new(flate2::read::GzDecoder::new(file))
+        .unpack(staging.path())
+        .context("Failed to unpack service archive")?;
+
+    for (name, dir) in [
+        (
+            APPDATA_ARCHIVE_DIR,
+            paths.service_appdata_dir(local_service_id),
+        ),
+        (
+            ARTIFACTS_ARCHIVE_DIR,
+            paths.service_artifacts_dir(local_service_id),
+        ),
+    ] {
+        let unpacked = staging.path().join(name);
+        if !unpacked.is_dir() {
+            continue;
+        }
+        if dir.exists() {
+            fs::remove_dir_all(&dir)
+                .with_context(|| format!("Failed to replace {}", dir.display()))?;
+        }
+        if let Some(parent) = dir.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::rename(&unpacked, &dir)
+            .with_context(|| format!("Failed to move transferred data to {}", dir.display()))?;
+    }
+    Ok(())
+}
+
+async fn write_frame<S, T>(stream: &mut S, value: &T) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+    T: Serialize,
+{
+    let payload = serde_json::to_vec(value)
+        .map_err(|e| anyhow::anyhow!("Failed to serialize service-transfer frame: {e}"))?;
+    let payload_len = u32::try_from(payload.len())
+        .map_err(|_| anyhow::anyhow!("Service-transfer frame is too large"))?;
+    stream
+        .write_all(&payload_len.to_be_bytes())
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to write frame length: {e}"))?;
+    stream
+        .write_all(&payload)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to write frame payload: {e}"))?;
+    stream
+        .flush()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to flush frame payload: {e}"))?;
+    Ok(())
+}
+
+async fn read_frame<S, T>(stream: &mut S) -> Result<T>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+    T: DeserializeOwned,
+{
+    let mut len_buf = [0u8; 4];
+    stream
+        .read_exact(&mut len_buf)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to read frame length: {e}"))?;
+    let payload_len = u32::from_be_bytes(len_buf) as usize;
+    if payload_len > MAX_TRANSFER_HEADER_LEN {
+        anyhow::bail!(
+            "Service-transfer frame too large: {} bytes (max {})",
+            payload_len,
+            MAX_TRANSFER_HEADER_LEN
+        );
+    }
+
+    let mut payload = vec![0u8; payload_len];
+    stream
+        .read_exact(&mut payload)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to read frame payload: {e}"))?;
+    serde_json::from_slice(&payload)
+        .map_err(|e| anyhow::anyhow!("Failed to decode service-transfer frame: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn service_archive_round_trips_appdata_and_artifacts() -> Result<()> {
+        let source_home = tempfile::tempdir()?;
+        let source_paths = FungiPaths::from_fungi_home(source_home.path());
+        let appdata = source_paths.service_appdata_dir("source-id");
+        fs::create_dir_all(appdata.join("db"))?;
+        fs::write(appdata.join("db/notes.sqlite"), b"notes")?;
+        let artifacts = source_paths.service_artifacts_dir("source-id");
+        fs::create_dir_all(&artifacts)?;
+        fs::write(artifacts.join("app.wasm"), b"wasm")?;
+
+        let archive = pack_service_archive(source_home.path(), "source-id")?;
+
+        let target_home = tempfile::tempdir()?;
+        unpack_service_archive(archive.path(), target_home.path(), "target-id")?;
+        let target_paths = FungiPaths::from_fungi_home(target_home.path());
+        assert_eq!(
+            fs::read(
+                target_paths
+                    .service_appdata_dir("target-id")
+                    .join("db/notes.sqlite")
+            )?,
+            b"notes"
+        );
+        assert_eq!(
+            fs::read(
+                target_paths
+                    .service_artifacts_dir("target-id")
+                    .join("app.wasm")
+            )?,
+            b"wasm"
+        );
+        Ok(())
+    }
+}
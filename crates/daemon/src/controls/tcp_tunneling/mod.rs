@@ -1,7 +1,10 @@
 mod port_forward;
 mod port_listen;
 mod tcp_tunneling_control;
+mod throttle;
 
-pub(crate) use port_forward::forward_port_to_peer;
+pub(crate) use port_forward::{ForwardTarget, forward_port_to_peer};
 pub(crate) use port_listen::listen_p2p_to_port;
 pub use tcp_tunneling_control::TcpTunnelingControl;
+pub(crate) use tcp_tunneling_control::{StreamActivator, StreamActivators};
+pub(crate) use throttle::{BandwidthLimiter, ThrottledStream};
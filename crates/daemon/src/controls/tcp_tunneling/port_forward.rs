@@ -1,8 +1,11 @@
+use super::{BandwidthLimiter, ThrottledStream};
+use fungi_config::tcp_tunneling::ServiceRouting;
 use fungi_swarm::SwarmControl;
 use libp2p::{PeerId, StreamProtocol};
 use parking_lot::Mutex;
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use thiserror::Error;
 use tokio::task::JoinHandle;
 use tokio_util::compat::FuturesAsyncReadCompatExt;
@@ -24,17 +27,31 @@ pub enum PortForwardError {
         #[source]
         source: anyhow::Error,
     },
+    #[error("No forwarding target configured")]
+    NoTargets,
 }
 
 type Result<T> = std::result::Result<T, PortForwardError>;
 
+/// A peer and stream protocol a forwarded connection can be opened to.
+pub type ForwardTarget = (PeerId, StreamProtocol);
+
+/// Forwards every TCP connection accepted on `local_addr` to one of `targets`. The first target
+/// is the primary one; the rest are only used as fallbacks (failover) or rotated through
+/// (round-robin). Either way, a target is only skipped for a connection once opening a stream
+/// to it has failed.
 pub async fn forward_port_to_peer(
     swarm_control: SwarmControl,
     local_addr: SocketAddr,
-    target_peer: PeerId,
-    target_protocol: StreamProtocol,
+    targets: Vec<ForwardTarget>,
+    routing: ServiceRouting,
+    limiter: BandwidthLimiter,
     cancellation_token: CancellationToken,
 ) -> Result<()> {
+    if targets.is_empty() {
+        return Err(PortForwardError::NoTargets);
+    }
+
     let listener = tokio::net::TcpListener::bind(local_addr)
         .await
         .map_err(|source| PortForwardError::BindLocal {
@@ -54,6 +71,8 @@ pub async fn forward_port_to_peer(
     // Store active connection tasks for graceful shutdown
     let active_tasks: Arc<Mutex<Vec<JoinHandle<()>>>> = Arc::new(Mutex::new(Vec::new()));
     let active_tasks_for_cleanup = active_tasks.clone();
+    let targets = Arc::new(targets);
+    let next_target = AtomicUsize::new(0);
 
     loop {
         tokio::select! {
@@ -63,14 +82,23 @@ pub async fn forward_port_to_peer(
                         log::debug!("Accepted connection from {client_addr}");
 
                         let swarm_control = swarm_control.clone();
-                        let target_protocol = target_protocol.clone();
+                        let targets = targets.clone();
+                        let start = match routing {
+                            ServiceRouting::Failover => 0,
+                            ServiceRouting::RoundRobin => {
+                                next_target.fetch_add(1, Ordering::Relaxed)
+                            }
+                        };
+                        let limiter = limiter.clone();
 
                         let task = tokio::spawn(async move {
                             if let Err(e) = handle_tcp_connection(
                                 swarm_control,
                                 tcp_stream,
-                                target_peer,
-                                target_protocol,
+                                &targets,
+                                routing,
+                                start,
+                                limiter,
                             ).await {
                                 log::error!("Failed to handle connection from {client_addr}: {e}");
                             }
@@ -105,30 +133,259 @@ pub async fn forward_port_to_peer(
     Ok(())
 }
 
+/// Order in which a connection tries `targets`. Failover keeps the declared order, so the
+/// primary is dialed even while a fallback happens to be connected. Round-robin rotates to begin
+/// at `start`, then puts peers that currently have a connection ahead of those that would need a
+/// fresh dial.
+fn target_order(
+    targets: &[ForwardTarget],
+    routing: ServiceRouting,
+    start: usize,
+    is_connected: impl Fn(&PeerId) -> bool,
+) -> Vec<usize> {
+    let len = targets.len();
+    if routing == ServiceRouting::Failover {
+        return (0..len).collect();
+    }
+    let mut order = (0..len)
+        .map(|offset| (start + offset) % len.max(1))
+        .collect::<Vec<_>>();
+    order.sort_by_key(|index| !is_connected(&targets[*index].0));
+    order
+}
+
 async fn handle_tcp_connection(
     swarm_control: SwarmControl,
     mut tcp_stream: tokio::net::TcpStream,
-    target_peer: PeerId,
-    target_protocol: StreamProtocol,
+    targets: &[ForwardTarget],
+    routing: ServiceRouting,
+    start: usize,
+    limiter: BandwidthLimiter,
 ) -> Result<()> {
-    let (p2p_stream, _stream_observation_handle, _connection_id) = swarm_control
-        .open_stream(target_peer, target_protocol)
-        .await
-        .map_err(|source| PortForwardError::ConnectPeer {
-            peer: target_peer,
-            source,
-        })?;
+    let state = swarm_control.state();
+    let order = target_order(targets, routing, start, |peer_id| {
+        state.connection_len_for_peer(peer_id) > 0
+    });
+
+    let mut last_error = PortForwardError::NoTargets;
+    let mut opened = None;
+    for index in order {
+        let (peer, protocol) = &targets[index];
+        match swarm_control.open_stream(*peer, protocol.clone()).await {
+            Ok(stream) => {
+                opened = Some((*peer, stream));
+                break;
+            }
+            Err(source) => {
+                if targets.len() > 1 {
+                    log::warn!("Service access target {peer} unavailable, trying next: {source}");
+                }
+                last_error = PortForwardError::ConnectPeer {
+                    peer: *peer,
+                    source,
+                };
+            }
+        }
+    }
+    let Some((target_peer, (p2p_stream, _stream_observation_handle, connection_id))) = opened
+    else {
+        return Err(last_error);
+    };
 
     let tcp_peer_addr = tcp_stream
         .peer_addr()
         .map_err(PortForwardError::AcceptTcp)?;
-    log::debug!("Established service access stream from {tcp_peer_addr} to peer {target_peer}");
+    // Treat an unknown connection as relayed so the stricter limit applies.
+    let relayed = swarm_control
+        .state()
+        .connection_is_relay(&connection_id)
+        .unwrap_or(true);
+    log::debug!(
+        "Established service access stream from {tcp_peer_addr} to peer {target_peer} (relayed: {relayed})"
+    );
 
     // Bidirectional copy
-    tokio::io::copy_bidirectional(&mut p2p_stream.compat(), &mut tcp_stream)
+    let mut p2p_stream = ThrottledStream::new(p2p_stream.compat(), limiter, relayed);
+    tokio::io::copy_bidirectional(&mut p2p_stream, &mut tcp_stream)
         .await
         .map_err(PortForwardError::AcceptTcp)?;
 
     log::debug!("Tunnel from {tcp_peer_addr} to peer {target_peer} closed");
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{TestDaemon, TestDaemonBuilder, reserve_ephemeral_port};
+    use futures::{AsyncWriteExt as _, StreamExt};
+    use libp2p::identity::Keypair;
+    use std::time::Duration;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt as _};
+
+    fn targets(count: usize) -> Vec<ForwardTarget> {
+        (0..count)
+            .map(|_| (PeerId::random(), StreamProtocol::new("/fungi/test/0.1.0")))
+            .collect()
+    }
+
+    #[test]
+    fn failover_keeps_list_order_among_connected_peers() {
+        let targets = targets(3);
+        let order = target_order(&targets, ServiceRouting::Failover, 0, |_| true);
+        assert_eq!(order, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn failover_tries_a_disconnected_primary_first() {
+        let targets = targets(3);
+        let primary = targets[0].0;
+        let order = target_order(&targets, ServiceRouting::Failover, 0, |peer_id| {
+            *peer_id != primary
+        });
+        assert_eq!(order, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn round_robin_tries_disconnected_peers_last() {
+        let targets = targets(3);
+        let primary = targets[0].0;
+        let order = target_order(&targets, ServiceRouting::RoundRobin, 0, |peer_id| {
+            *peer_id != primary
+        });
+        assert_eq!(order, vec![1, 2, 0]);
+    }
+
+    #[test]
+    fn round_robin_rotates_the_starting_target() {
+        let targets = targets(3);
+        let round_robin = ServiceRouting::RoundRobin;
+        assert_eq!(
+            target_order(&targets, round_robin, 1, |_| true),
+            vec![1, 2, 0]
+        );
+        assert_eq!(
+            target_order(&targets, round_robin, 5, |_| true),
+            vec![2, 0, 1]
+        );
+    }
+
+    const NAME_PROTOCOL: StreamProtocol =
+        StreamProtocol::new("/fungi/test/port-forward-name/0.1.0");
+
+    /// Answers every stream on [`NAME_PROTOCOL`] with `name`.
+    fn serve_name(daemon: &TestDaemon, name: &'static [u8]) {
+        let mut incoming = daemon
+            .swarm_control()
+            .accept_incoming_streams(NAME_PROTOCOL)
+            .unwrap();
+        tokio::spawn(async move {
+            while let Some(incoming) = incoming.next().await {
+                let mut stream = incoming.stream;
+                let _ = stream.write_all(name).await;
+                let _ = stream.close().await;
+            }
+        });
+    }
+
+    fn spawn_forwarder(
+        client: &TestDaemon,
+        targets: Vec<ForwardTarget>,
+        routing: ServiceRouting,
+    ) -> (SocketAddr, CancellationToken) {
+        let local_addr = SocketAddr::from(([127, 0, 0, 1], reserve_ephemeral_port()));
+        let token = CancellationToken::new();
+        tokio::spawn(forward_port_to_peer(
+            client.swarm_control().clone(),
+            local_addr,
+            targets,
+            routing,
+            BandwidthLimiter::default(),
+            token.clone(),
+        ));
+        (local_addr, token)
+    }
+
+    /// Opens a forwarded connection and returns the name of the device that answered it.
+    async fn forwarded_name(local_addr: SocketAddr) -> String {
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+        let mut tcp_stream = loop {
+            match tokio::net::TcpStream::connect(local_addr).await {
+                Ok(tcp_stream) => break tcp_stream,
+                Err(error) if tokio::time::Instant::now() >= deadline => {
+                    panic!("forwarder at {local_addr} never listened: {error}")
+                }
+                Err(_) => tokio::time::sleep(Duration::from_millis(20)).await,
+            }
+        };
+        tcp_stream.shutdown().await.unwrap();
+        let mut name = String::new();
+        tokio::time::timeout(
+            Duration::from_secs(10),
+            tcp_stream.read_to_string(&mut name),
+        )
+        .await
+        .expect("forwarded connection timed out")
+        .unwrap();
+        name
+    }
+
+    #[tokio::test]
+    async fn forwards_through_a_group_by_routing() {
+        let client_keypair = Keypair::generate_ed25519();
+        let client_peer_id = client_keypair.public().to_peer_id();
+        let primary = TestDaemonBuilder::new()
+            .with_trusted_device(client_peer_id)
+            .build()
+            .await
+            .unwrap();
+        let backup = TestDaemonBuilder::new()
+            .with_trusted_device(client_peer_id)
+            .build()
+            .await
+            .unwrap();
+        let client = TestDaemonBuilder::new()
+            .with_keypair(client_keypair)
+            .with_trusted_device(primary.peer_id())
+            .with_trusted_device(backup.peer_id())
+            .build()
+            .await
+            .unwrap();
+        serve_name(&primary, b"primary");
+        serve_name(&backup, b"backup");
+
+        // Only the backup is connected; the primary is merely dialable.
+        client.connect_to(&backup).await.unwrap();
+        client
+            .wait_connected(backup.peer_id(), Duration::from_secs(5))
+            .await
+            .unwrap();
+        client.learn_address_of(&primary);
+
+        let group = vec![
+            (primary.peer_id(), NAME_PROTOCOL),
+            (backup.peer_id(), NAME_PROTOCOL),
+        ];
+        let (failover, failover_token) =
+            spawn_forwarder(&client, group.clone(), ServiceRouting::Failover);
+        assert_eq!(forwarded_name(failover).await, "primary");
+        assert_eq!(forwarded_name(failover).await, "primary");
+        failover_token.cancel();
+
+        // A target that cannot be reached is skipped in favour of the next one.
+        let unreachable = vec![(PeerId::random(), NAME_PROTOCOL), group[1].clone()];
+        let (fallback, fallback_token) =
+            spawn_forwarder(&client, unreachable, ServiceRouting::Failover);
+        assert_eq!(forwarded_name(fallback).await, "backup");
+        fallback_token.cancel();
+
+        let (round_robin, round_robin_token) =
+            spawn_forwarder(&client, group, ServiceRouting::RoundRobin);
+        let mut names = Vec::new();
+        for _ in 0..4 {
+            names.push(forwarded_name(round_robin).await);
+        }
+        assert_eq!(names, vec!["primary", "backup", "primary", "backup"]);
+        round_robin_token.cancel();
+    }
+}
@@ -1,6 +1,7 @@
-use fungi_stream::IncomingStreams;
+use super::{BandwidthLimiter, StreamActivators, ThrottledStream};
+use fungi_stream::{IncomingStream, IncomingStreams};
+use fungi_swarm::State;
 use futures::StreamExt;
-use libp2p::Stream;
 use parking_lot::Mutex;
 use std::net::SocketAddr;
 use std::sync::Arc;
@@ -17,6 +18,12 @@ pub enum TcpTunnelingError {
         #[source]
         source: std::io::Error,
     },
+    #[error("Failed to activate service for {protocol}: {source:#}")]
+    Activation {
+        protocol: String,
+        #[source]
+        source: anyhow::Error,
+    },
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 }
@@ -26,6 +33,9 @@ type Result<T> = std::result::Result<T, TcpTunnelingError>;
 pub async fn listen_p2p_to_port(
     mut incomings: IncomingStreams,
     target_addr: SocketAddr,
+    state: State,
+    limiter: BandwidthLimiter,
+    activators: StreamActivators,
     cancellation_token: CancellationToken,
 ) -> Result<()> {
     // Store active connection tasks for graceful shutdown
@@ -37,11 +47,15 @@ pub async fn listen_p2p_to_port(
             stream_result = incomings.next() => {
                 match stream_result {
                         Some(incoming_stream) => {
-                            let peer_id = incoming_stream.peer_id;
-                            let stream = incoming_stream.stream;
-                        log::debug!("Received stream from {peer_id:?}");
+                        log::debug!("Received stream from {:?}", incoming_stream.peer_id);
 
-                        let task = tokio::spawn(handle_incoming_stream(stream, target_addr));
+                        let task = tokio::spawn(handle_incoming_stream(
+                            incoming_stream,
+                            target_addr,
+                            state.clone(),
+                            limiter.clone(),
+                            activators.clone(),
+                        ));
                         active_tasks.lock().push(task);
 
                         // Clean up completed tasks
@@ -71,14 +85,48 @@ pub async fn listen_p2p_to_port(
     Ok(())
 }
 
-async fn handle_incoming_stream(p2p_stream: Stream, target_addr: SocketAddr) {
-    match handle_incoming_stream_inner(p2p_stream, target_addr).await {
+async fn handle_incoming_stream(
+    incoming_stream: IncomingStream,
+    target_addr: SocketAddr,
+    state: State,
+    limiter: BandwidthLimiter,
+    activators: StreamActivators,
+) {
+    // Registered before activation so an on-demand service isn't considered idle while it is
+    // still starting for this stream.
+    let _observation = state.track_inbound_stream_opened(
+        incoming_stream.peer_id,
+        incoming_stream.connection_id,
+        incoming_stream.protocol.clone(),
+    );
+    // Treat an unknown connection as relayed so the stricter limit applies.
+    let relayed = state
+        .connection_is_relay(&incoming_stream.connection_id)
+        .unwrap_or(true);
+
+    match handle_incoming_stream_inner(incoming_stream, target_addr, limiter, activators, relayed)
+        .await
+    {
         Ok(()) => log::debug!("Connection to {target_addr} closed successfully"),
         Err(e) => log::error!("Connection to {target_addr} failed: {e}"),
     }
 }
 
-async fn handle_incoming_stream_inner(p2p_stream: Stream, target_addr: SocketAddr) -> Result<()> {
+async fn handle_incoming_stream_inner(
+    incoming_stream: IncomingStream,
+    target_addr: SocketAddr,
+    limiter: BandwidthLimiter,
+    activators: StreamActivators,
+    relayed: bool,
+) -> Result<()> {
+    let protocol = incoming_stream.protocol.to_string();
+    let activator = activators.lock().get(&protocol).cloned();
+    if let Some(activator) = activator {
+        activator()
+            .await
+            .map_err(|source| TcpTunnelingError::Activation { protocol, source })?;
+    }
+
     let mut target_stream =
         tokio::net::TcpStream::connect(target_addr)
             .await
@@ -89,7 +137,8 @@ async fn handle_incoming_stream_inner(p2p_stream: Stream, target_addr: SocketAdd
 
     log::debug!("Established connection to {target_addr}");
 
-    tokio::io::copy_bidirectional(&mut p2p_stream.compat(), &mut target_stream)
+    let mut p2p_stream = ThrottledStream::new(incoming_stream.stream.compat(), limiter, relayed);
+    tokio::io::copy_bidirectional(&mut p2p_stream, &mut target_stream)
         .await
         .map_err(TcpTunnelingError::Io)?;
 
@@ -1,28 +1,47 @@
 use std::{collections::HashMap, net::SocketAddr, sync::Arc};
 
 use anyhow::{Result, bail};
-use fungi_config::tcp_tunneling::{ForwardingRule, ListeningRule, TcpTunneling};
+use fungi_config::{
+    bandwidth::BandwidthLimit,
+    tcp_tunneling::{ForwardingRule, ListeningRule, TcpTunneling},
+};
 use fungi_swarm::SwarmControl;
 use fungi_util::protocols::FUNGI_TUNNEL_PROTOCOL;
+use futures::future::BoxFuture;
 use libp2p::{PeerId, StreamProtocol};
 use parking_lot::Mutex;
 use tokio::task::JoinHandle;
 use tokio_util::sync::CancellationToken;
 
+use super::BandwidthLimiter;
+
+/// Runs before an incoming stream is spliced to its local port, e.g. to start a stopped
+/// on-demand service and wait for it to accept connections.
+pub(crate) type StreamActivator = Arc<dyn Fn() -> BoxFuture<'static, Result<()>> + Send + Sync>;
+
+/// Activators keyed by stream protocol, shared with every listening task so registrations
+/// apply to listeners that are already running.
+pub(crate) type StreamActivators = Arc<Mutex<HashMap<String, StreamActivator>>>;
+
 /// State for active forwarding rules
 #[derive(Debug)]
 struct ForwardingRuleState {
     rule: ForwardingRule,
     task_handle: JoinHandle<Result<(), super::port_forward::PortForwardError>>,
     cancellation_token: CancellationToken,
+    limiter: BandwidthLimiter,
 }
 
 /// State for active listening rules  
 #[derive(Debug)]
 struct ListeningRuleState {
+    /// Carries the limit in effect, which may come from the protocol limit.
     rule: ListeningRule,
+    /// The limit the rule was added with; a protocol limit only applies while this is unlimited.
+    own_limit: BandwidthLimit,
     task_handle: JoinHandle<Result<(), super::port_listen::TcpTunnelingError>>,
     cancellation_token: CancellationToken,
+    limiter: BandwidthLimiter,
 }
 
 /// Control interface for service endpoint forwarding.
@@ -32,6 +51,10 @@ pub struct TcpTunnelingControl {
     swarm_control: SwarmControl,
     forwarding_rules: Arc<Mutex<HashMap<String, ForwardingRuleState>>>,
     listening_rules: Arc<Mutex<HashMap<String, ListeningRuleState>>>,
+    /// Serving-side limits keyed by stream protocol, applied to listening rules that don't
+    /// carry their own limit.
+    protocol_bandwidth_limits: Arc<Mutex<HashMap<String, BandwidthLimit>>>,
+    stream_activators: StreamActivators,
 }
 
 impl TcpTunnelingControl {
@@ -40,6 +63,8 @@ impl TcpTunnelingControl {
             swarm_control,
             forwarding_rules: Arc::new(Mutex::new(HashMap::new())),
             listening_rules: Arc::new(Mutex::new(HashMap::new())),
+            protocol_bandwidth_limits: Arc::new(Mutex::new(HashMap::new())),
+            stream_activators: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
@@ -76,32 +101,48 @@ impl TcpTunnelingControl {
             .try_into()
             .map_err(|e| anyhow::anyhow!("Invalid local socket address: {}", e))?;
 
-        let target_peer: PeerId = rule
-            .remote_peer_id
-            .parse()
-            .map_err(|e| anyhow::anyhow!("Invalid peer ID: {}", e))?;
-
         let target_protocol_name = match (&rule.remote_protocol, rule.remote_port) {
             (Some(remote_protocol), _) => remote_protocol.clone(),
             (None, Some(remote_port)) => format!("{FUNGI_TUNNEL_PROTOCOL}/{remote_port}"),
             (None, None) => bail!("Forwarding rule requires a remote protocol or remote port"),
         };
-        let target_protocol = StreamProtocol::try_from_owned(target_protocol_name)
-            .map_err(|e| anyhow::anyhow!("Invalid protocol: {}", e))?;
+        let mut targets = vec![parse_forward_target(
+            &rule.remote_peer_id,
+            target_protocol_name,
+        )?];
+        for fallback in &rule.fallback_targets {
+            targets.push(parse_forward_target(
+                &fallback.peer_id,
+                fallback.protocol.clone(),
+            )?);
+        }
 
         let swarm_control = self.swarm_control.clone();
 
-        log::info!("Adding forwarding rule: {local_addr} -> {target_peer}/{target_protocol}");
+        let (target_peer, target_protocol) = &targets[0];
+        if targets.len() > 1 {
+            log::info!(
+                "Adding forwarding rule: {local_addr} -> {target_peer}/{target_protocol} (+{} {} targets)",
+                targets.len() - 1,
+                rule.routing.as_str()
+            );
+        } else {
+            log::info!("Adding forwarding rule: {local_addr} -> {target_peer}/{target_protocol}");
+        }
 
         let cancellation_token = CancellationToken::new();
         let cancellation_token_clone = cancellation_token.clone();
+        let limiter = BandwidthLimiter::new(rule.bandwidth_limit);
+        let limiter_clone = limiter.clone();
+        let routing = rule.routing;
 
         let task_handle = tokio::spawn(async move {
             super::forward_port_to_peer(
                 swarm_control,
                 local_addr,
-                target_peer,
-                target_protocol,
+                targets,
+                routing,
+                limiter_clone,
                 cancellation_token_clone,
             )
             .await
@@ -111,6 +152,7 @@ impl TcpTunnelingControl {
             rule,
             task_handle,
             cancellation_token,
+            limiter,
         };
 
         rules.insert(rule_id.clone(), rule_state);
@@ -130,9 +172,25 @@ impl TcpTunnelingControl {
         }
     }
 
+    /// Update the bandwidth limit of an active forwarding rule. Open connections pick up the new
+    /// limit immediately.
+    pub fn set_forwarding_rule_bandwidth_limit(
+        &self,
+        rule_id: &str,
+        limit: BandwidthLimit,
+    ) -> Result<()> {
+        let mut rules = self.forwarding_rules.lock();
+        let Some(rule_state) = rules.get_mut(rule_id) else {
+            bail!("Forwarding rule not found: {}", rule_id);
+        };
+        rule_state.rule.bandwidth_limit = limit;
+        rule_state.limiter.set_limit(limit);
+        Ok(())
+    }
+
     /// Add a new listening rule (remote peer -> local port)
     /// async is necessary for tokio::spawn
-    pub async fn add_listening_rule(&self, rule: ListeningRule) -> Result<String> {
+    pub async fn add_listening_rule(&self, mut rule: ListeningRule) -> Result<String> {
         let rule_id = self.generate_listening_rule_id(&rule);
 
         let mut rules = self.listening_rules.lock();
@@ -145,11 +203,24 @@ impl TcpTunnelingControl {
             .map_err(|e| anyhow::anyhow!("Invalid local socket address: {}", e))?;
 
         let listening_protocol = listening_protocol(&rule)?;
+        let own_limit = rule.bandwidth_limit;
+        if own_limit.is_unlimited()
+            && let Some(limit) = self
+                .protocol_bandwidth_limits
+                .lock()
+                .get(listening_protocol.as_ref())
+        {
+            rule.bandwidth_limit = *limit;
+        }
 
         log::info!("Adding listening rule: {local_addr} for {listening_protocol}");
 
         let cancellation_token = CancellationToken::new();
         let cancellation_token_clone = cancellation_token.clone();
+        let limiter = BandwidthLimiter::new(rule.bandwidth_limit);
+        let limiter_clone = limiter.clone();
+        let state = self.swarm_control.state().clone();
+        let activators = self.stream_activators.clone();
 
         // Accept incoming streams before spawning
         let incomings = self
@@ -158,13 +229,23 @@ impl TcpTunnelingControl {
             .map_err(|e| anyhow::anyhow!("Failed to accept incoming streams: {}", e))?;
 
         let task_handle = tokio::spawn(async move {
-            super::listen_p2p_to_port(incomings, local_addr, cancellation_token_clone).await
+            super::listen_p2p_to_port(
+                incomings,
+                local_addr,
+                state,
+                limiter_clone,
+                activators,
+                cancellation_token_clone,
+            )
+            .await
         });
 
         let rule_state = ListeningRuleState {
             rule,
+            own_limit,
             task_handle,
             cancellation_token,
+            limiter,
         };
 
         rules.insert(rule_id.clone(), rule_state);
@@ -188,6 +269,43 @@ impl TcpTunnelingControl {
         }
     }
 
+    /// Set the serving-side bandwidth limit for a stream protocol. Active listening rules for the
+    /// protocol are updated in place and future rules start with the same limit, except rules
+    /// that were added with a limit of their own.
+    pub fn set_protocol_bandwidth_limit(&self, protocol: &str, limit: BandwidthLimit) {
+        {
+            let mut limits = self.protocol_bandwidth_limits.lock();
+            if limit.is_unlimited() {
+                limits.remove(protocol);
+            } else {
+                limits.insert(protocol.to_string(), limit);
+            }
+        }
+
+        for rule_state in self.listening_rules.lock().values_mut() {
+            let matches = listening_protocol(&rule_state.rule)
+                .is_ok_and(|rule_protocol| rule_protocol.as_ref() == protocol);
+            // A rule's own limit wins over the protocol limit.
+            if matches && rule_state.own_limit.is_unlimited() {
+                rule_state.rule.bandwidth_limit = limit;
+                rule_state.limiter.set_limit(limit);
+            }
+        }
+    }
+
+    /// Install or clear the activator run for every incoming stream of `protocol`.
+    pub(crate) fn set_stream_activator(&self, protocol: &str, activator: Option<StreamActivator>) {
+        let mut activators = self.stream_activators.lock();
+        match activator {
+            Some(activator) => {
+                activators.insert(protocol.to_string(), activator);
+            }
+            None => {
+                activators.remove(protocol);
+            }
+        }
+    }
+
     /// Get all active forwarding rules
     pub fn get_forwarding_rules(&self) -> Vec<(String, ForwardingRule)> {
         self.forwarding_rules
@@ -277,6 +395,15 @@ fn listening_protocol(rule: &ListeningRule) -> Result<StreamProtocol> {
     .map_err(|e| anyhow::anyhow!("Invalid protocol: {}", e))
 }
 
+fn parse_forward_target(peer_id: &str, protocol: String) -> Result<super::ForwardTarget> {
+    let peer_id: PeerId = peer_id
+        .parse()
+        .map_err(|e| anyhow::anyhow!("Invalid peer ID: {}", e))?;
+    let protocol = StreamProtocol::try_from_owned(protocol)
+        .map_err(|e| anyhow::anyhow!("Invalid protocol: {}", e))?;
+    Ok((peer_id, protocol))
+}
+
 fn sanitize_rule_component(value: &str) -> String {
     value
         .chars()
@@ -289,3 +416,59 @@ fn sanitize_rule_component(value: &str) -> String {
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestDaemonBuilder;
+
+    fn listening_rule(port: u16, bandwidth_limit: BandwidthLimit) -> ListeningRule {
+        ListeningRule {
+            host: "127.0.0.1".into(),
+            port,
+            protocol: Some(format!("/fungi/test-limit/{port}")),
+            bandwidth_limit,
+        }
+    }
+
+    fn limit(bytes_per_sec: u64) -> BandwidthLimit {
+        BandwidthLimit {
+            direct_bytes_per_sec: Some(bytes_per_sec),
+            relayed_bytes_per_sec: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn protocol_limits_leave_rules_with_their_own_limit_alone() {
+        let daemon = TestDaemonBuilder::new().build().await.unwrap();
+        let control = daemon.daemon().tcp_tunneling_control();
+        let own = control
+            .add_listening_rule(listening_rule(7101, limit(1000)))
+            .await
+            .unwrap();
+        let inherited = control
+            .add_listening_rule(listening_rule(7102, BandwidthLimit::default()))
+            .await
+            .unwrap();
+
+        control.set_protocol_bandwidth_limit("/fungi/test-limit/7101", limit(50));
+        control.set_protocol_bandwidth_limit("/fungi/test-limit/7102", limit(50));
+        let effective = |id: &str| {
+            control
+                .get_listening_rules()
+                .into_iter()
+                .find(|(rule_id, _)| rule_id == id)
+                .unwrap()
+                .1
+                .bandwidth_limit
+        };
+        assert_eq!(effective(&own), limit(1000));
+        assert_eq!(effective(&inherited), limit(50));
+
+        // Clearing the protocol limit drops only what the rule inherited.
+        control.set_protocol_bandwidth_limit("/fungi/test-limit/7101", BandwidthLimit::default());
+        control.set_protocol_bandwidth_limit("/fungi/test-limit/7102", BandwidthLimit::default());
+        assert_eq!(effective(&own), limit(1000));
+        assert!(effective(&inherited).is_unlimited());
+    }
+}
@@ -0,0 +1,366 @@
+use std::{
+    future::Future,
+    io,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll, ready},
+    time::{Duration, Instant},
+};
+
+use fungi_config::bandwidth::BandwidthLimit;
+use parking_lot::Mutex;
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
+    time::Sleep,
+};
+
+/// Smallest slice of a second worth of tokens we wait for before resuming, so a saturated
+/// bucket doesn't wake up for every handful of bytes.
+const MIN_GRANT_FRACTION: f64 = 0.05;
+
+/// Direction of traffic relative to the p2p stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TrafficDirection {
+    Inbound,
+    Outbound,
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    bytes_per_sec: u64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Allowance {
+    Bytes(usize),
+    WaitFor(Duration),
+}
+
+impl TokenBucket {
+    fn new(bytes_per_sec: u64, now: Instant) -> Self {
+        Self {
+            bytes_per_sec,
+            tokens: bytes_per_sec as f64,
+            last_refill: now,
+        }
+    }
+
+    fn capacity(&self) -> f64 {
+        self.bytes_per_sec as f64
+    }
+
+    fn set_rate(&mut self, bytes_per_sec: u64, now: Instant) {
+        self.refill(now);
+        self.bytes_per_sec = bytes_per_sec;
+        self.tokens = self.tokens.min(self.capacity());
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        self.tokens =
+            (self.tokens + elapsed.as_secs_f64() * self.bytes_per_sec as f64).min(self.capacity());
+        self.last_refill = now;
+    }
+
+    fn allowance(&mut self, want: usize, now: Instant) -> Allowance {
+        self.refill(now);
+        let threshold = (want as f64)
+            .min(self.capacity() * MIN_GRANT_FRACTION)
+            .max(1.0);
+        if self.tokens >= threshold {
+            return Allowance::Bytes((self.tokens as usize).min(want).max(1));
+        }
+        let missing = threshold - self.tokens;
+        Allowance::WaitFor(Duration::from_secs_f64(
+            missing / self.bytes_per_sec.max(1) as f64,
+        ))
+    }
+
+    fn consume(&mut self, bytes: usize) {
+        self.tokens -= bytes as f64;
+    }
+}
+
+#[derive(Debug, Default)]
+struct LimiterState {
+    direct_inbound: Option<TokenBucket>,
+    direct_outbound: Option<TokenBucket>,
+    relayed_inbound: Option<TokenBucket>,
+    relayed_outbound: Option<TokenBucket>,
+}
+
+impl LimiterState {
+    fn bucket_mut(
+        &mut self,
+        relayed: bool,
+        direction: TrafficDirection,
+    ) -> &mut Option<TokenBucket> {
+        match (relayed, direction) {
+            (false, TrafficDirection::Inbound) => &mut self.direct_inbound,
+            (false, TrafficDirection::Outbound) => &mut self.direct_outbound,
+            (true, TrafficDirection::Inbound) => &mut self.relayed_inbound,
+            (true, TrafficDirection::Outbound) => &mut self.relayed_outbound,
+        }
+    }
+
+    fn apply(&mut self, limit: BandwidthLimit, now: Instant) {
+        for relayed in [false, true] {
+            let rate = limit.for_path(relayed).filter(|rate| *rate > 0);
+            for direction in [TrafficDirection::Inbound, TrafficDirection::Outbound] {
+                let bucket = self.bucket_mut(relayed, direction);
+                match (rate, bucket.as_mut()) {
+                    (None, _) => *bucket = None,
+                    (Some(rate), Some(existing)) => existing.set_rate(rate, now),
+                    (Some(rate), None) => *bucket = Some(TokenBucket::new(rate, now)),
+                }
+            }
+        }
+    }
+}
+
+/// Shared token buckets for one forwarding or listening rule. Every tunneled connection of the
+/// rule draws from the same buckets, so the limit caps the rule's aggregate throughput in each
+/// direction. Updating the limit takes effect immediately for open connections.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct BandwidthLimiter {
+    state: Arc<Mutex<LimiterState>>,
+}
+
+impl BandwidthLimiter {
+    pub(crate) fn new(limit: BandwidthLimit) -> Self {
+        let limiter = Self::default();
+        limiter.set_limit(limit);
+        limiter
+    }
+
+    pub(crate) fn set_limit(&self, limit: BandwidthLimit) {
+        self.state.lock().apply(limit, Instant::now());
+    }
+
+    /// Returns `None` when the path is unlimited.
+    fn allowance(
+        &self,
+        relayed: bool,
+        direction: TrafficDirection,
+        want: usize,
+    ) -> Option<Allowance> {
+        self.state
+            .lock()
+            .bucket_mut(relayed, direction)
+            .as_mut()
+            .map(|bucket| bucket.allowance(want, Instant::now()))
+    }
+
+    fn consume(&self, relayed: bool, direction: TrafficDirection, bytes: usize) {
+        if let Some(bucket) = self.state.lock().bucket_mut(relayed, direction).as_mut() {
+            bucket.consume(bytes);
+        }
+    }
+}
+
+/// Wraps the p2p side of a tunnel and paces reads and writes through a [`BandwidthLimiter`].
+pub(crate) struct ThrottledStream<S> {
+    inner: S,
+    limiter: BandwidthLimiter,
+    relayed: bool,
+    read_delay: Option<Pin<Box<Sleep>>>,
+    write_delay: Option<Pin<Box<Sleep>>>,
+}
+
+impl<S> ThrottledStream<S> {
+    pub(crate) fn new(inner: S, limiter: BandwidthLimiter, relayed: bool) -> Self {
+        Self {
+            inner,
+            limiter,
+            relayed,
+            read_delay: None,
+            write_delay: None,
+        }
+    }
+}
+
+/// Waits out `delay` if set, then asks the limiter how many bytes may pass.
+fn poll_allowance(
+    delay: &mut Option<Pin<Box<Sleep>>>,
+    limiter: &BandwidthLimiter,
+    relayed: bool,
+    direction: TrafficDirection,
+    want: usize,
+    cx: &mut Context<'_>,
+) -> Poll<Option<usize>> {
+    loop {
+        if let Some(sleep) = delay.as_mut() {
+            ready!(sleep.as_mut().poll(cx));
+            *delay = None;
+        }
+        match limiter.allowance(relayed, direction, want) {
+            None => return Poll::Ready(None),
+            Some(Allowance::Bytes(bytes)) => return Poll::Ready(Some(bytes)),
+            Some(Allowance::WaitFor(wait)) => {
+                *delay = Some(Box::pin(tokio::time::sleep(wait)));
+            }
+        }
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for ThrottledStream<S> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        if buf.remaining() == 0 {
+            return Pin::new(&mut this.inner).poll_read(cx, buf);
+        }
+
+        let allowed = ready!(poll_allowance(
+            &mut this.read_delay,
+            &this.limiter,
+            this.relayed,
+            TrafficDirection::Inbound,
+            buf.remaining(),
+            cx,
+        ));
+        let Some(allowed) = allowed else {
+            return Pin::new(&mut this.inner).poll_read(cx, buf);
+        };
+
+        let mut limited = ReadBuf::new(buf.initialize_unfilled_to(allowed));
+        let result = ready!(Pin::new(&mut this.inner).poll_read(cx, &mut limited));
+        let filled = limited.filled().len();
+        buf.advance(filled);
+        this.limiter
+            .consume(this.relayed, TrafficDirection::Inbound, filled);
+        Poll::Ready(result)
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for ThrottledStream<S> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        if buf.is_empty() {
+            return Pin::new(&mut this.inner).poll_write(cx, buf);
+        }
+
+        let allowed = ready!(poll_allowance(
+            &mut this.write_delay,
+            &this.limiter,
+            this.relayed,
+            TrafficDirection::Outbound,
+            buf.len(),
+            cx,
+        ));
+        let Some(allowed) = allowed else {
+            return Pin::new(&mut this.inner).poll_write(cx, buf);
+        };
+
+        let written = ready!(Pin::new(&mut this.inner).poll_write(cx, &buf[..allowed]))?;
+        this.limiter
+            .consume(this.relayed, TrafficDirection::Outbound, written);
+        Poll::Ready(Ok(written))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[test]
+    fn token_bucket_starts_full_and_refills_over_time() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(1000, start);
+
+        assert_eq!(bucket.allowance(4096, start), Allowance::Bytes(1000));
+        bucket.consume(1000);
+
+        match bucket.allowance(4096, start) {
+            Allowance::WaitFor(wait) => assert_eq!(wait, Duration::from_millis(50)),
+            other => panic!("expected wait, got {other:?}"),
+        }
+
+        let later = start + Duration::from_millis(500);
+        assert_eq!(bucket.allowance(4096, later), Allowance::Bytes(500));
+    }
+
+    #[test]
+    fn token_bucket_caps_burst_at_one_second_of_traffic() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(1000, start);
+
+        let later = start + Duration::from_secs(10);
+        assert_eq!(bucket.allowance(usize::MAX, later), Allowance::Bytes(1000));
+    }
+
+    #[test]
+    fn lowering_the_rate_clamps_accumulated_tokens() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(1000, start);
+
+        bucket.set_rate(100, start);
+
+        assert_eq!(bucket.allowance(4096, start), Allowance::Bytes(100));
+    }
+
+    #[test]
+    fn limiter_keeps_direct_and_relayed_paths_separate() {
+        let limiter = BandwidthLimiter::new(BandwidthLimit {
+            direct_bytes_per_sec: None,
+            relayed_bytes_per_sec: Some(1000),
+        });
+
+        assert_eq!(
+            limiter.allowance(false, TrafficDirection::Outbound, 4096),
+            None
+        );
+        assert_eq!(
+            limiter.allowance(true, TrafficDirection::Outbound, 4096),
+            Some(Allowance::Bytes(1000))
+        );
+
+        limiter.set_limit(BandwidthLimit::default());
+        assert_eq!(
+            limiter.allowance(true, TrafficDirection::Outbound, 4096),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn throttled_stream_paces_writes_beyond_the_initial_burst() {
+        let (client, mut server) = tokio::io::duplex(64 * 1024);
+        let limiter = BandwidthLimiter::new(BandwidthLimit {
+            direct_bytes_per_sec: Some(16 * 1024),
+            relayed_bytes_per_sec: None,
+        });
+        let mut throttled = ThrottledStream::new(client, limiter, false);
+
+        let payload = vec![7u8; 20 * 1024];
+        let started = Instant::now();
+        let writer = async {
+            throttled.write_all(&payload).await.unwrap();
+            throttled.shutdown().await.unwrap();
+        };
+        let mut received = Vec::new();
+        let reader = server.read_to_end(&mut received);
+        let (_, read) = tokio::join!(writer, reader);
+        read.unwrap();
+
+        assert_eq!(received.len(), payload.len());
+        assert!(started.elapsed() >= Duration::from_millis(200));
+    }
+}
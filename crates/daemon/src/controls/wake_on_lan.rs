@@ -0,0 +1,157 @@
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    sync::Arc,
+};
+
+use anyhow::{Result, bail};
+use fungi_config::devices::DeviceInfo;
+use libp2p::PeerId;
+use tokio::net::UdpSocket;
+
+const WOL_PORT: u16 = 9;
+const MAGIC_PACKET_LEN: usize = 6 + 16 * 6;
+/// Upper bound on MAC addresses accepted in one wake request, so a peer can't turn this node
+/// into a broadcast amplifier.
+pub(crate) const MAX_WAKE_MAC_ADDRESSES: usize = 8;
+
+/// MAC addresses this node has recorded for a fungi peer. Relayed wake requests name the target
+/// peer and are only served for addresses found here, never for MACs supplied by the caller.
+pub(crate) type KnownMacAddressesSource = Arc<dyn Fn(&PeerId) -> Vec<String> + Send + Sync>;
+
+/// MAC addresses recorded for a device, from its saved entry and from mDNS discovery.
+pub(crate) fn known_mac_addresses(
+    saved: Option<&DeviceInfo>,
+    discovered: Option<&DeviceInfo>,
+) -> Vec<String> {
+    let mut mac_addresses = saved
+        .into_iter()
+        .chain(discovered)
+        .flat_map(|device| device.mac_addresses.iter().cloned())
+        .collect::<Vec<_>>();
+    mac_addresses.sort();
+    mac_addresses.dedup();
+    mac_addresses
+}
+
+pub(crate) fn parse_mac_address(value: &str) -> Result<[u8; 6]> {
+    let parts = value
+        .trim()
+        .split([':', '-'])
+        .map(|part| u8::from_str_radix(part, 16))
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|_| anyhow::anyhow!("invalid MAC address: {value}"))?;
+    let mac: [u8; 6] = parts
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("invalid MAC address: {value}"))?;
+    if mac == [0; 6] || mac == [0xff; 6] {
+        bail!("invalid MAC address: {value}");
+    }
+    Ok(mac)
+}
+
+/// Six `0xff` bytes followed by the target MAC repeated sixteen times.
+pub(crate) fn magic_packet(mac: [u8; 6]) -> [u8; MAGIC_PACKET_LEN] {
+    let mut packet = [0xff; MAGIC_PACKET_LEN];
+    for chunk in packet[6..].chunks_exact_mut(6) {
+        chunk.copy_from_slice(&mac);
+    }
+    packet
+}
+
+/// Broadcasts a magic packet for each MAC address on the local network segment.
+pub(crate) async fn send_magic_packets(mac_addresses: &[String]) -> Result<usize> {
+    if mac_addresses.is_empty() {
+        bail!("no MAC addresses to wake");
+    }
+    if mac_addresses.len() > MAX_WAKE_MAC_ADDRESSES {
+        bail!(
+            "too many MAC addresses in one wake request: {} (max {})",
+            mac_addresses.len(),
+            MAX_WAKE_MAC_ADDRESSES
+        );
+    }
+    let macs = mac_addresses
+        .iter()
+        .map(|mac| parse_mac_address(mac))
+        .collect::<Result<Vec<_>>>()?;
+
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await?;
+    socket.set_broadcast(true)?;
+    let target = SocketAddr::from((Ipv4Addr::BROADCAST, WOL_PORT));
+    for mac in &macs {
+        socket.send_to(&magic_packet(*mac), target).await?;
+    }
+    Ok(macs.len())
+}
+
+/// Whether two sets of private addresses look like the same LAN. Without netmasks to go on this
+/// compares IPv4 /24 prefixes, which matches the common home and office setup.
+pub(crate) fn shares_lan(left: &[String], right: &[String]) -> bool {
+    let prefixes = |ips: &[String]| {
+        ips.iter()
+            .filter_map(|ip| match ip.parse::<IpAddr>() {
+                Ok(IpAddr::V4(ip)) if ip.is_private() => {
+                    Some([ip.octets()[0], ip.octets()[1], ip.octets()[2]])
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+    };
+    let right = prefixes(right);
+    prefixes(left).iter().any(|prefix| right.contains(prefix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_mac_address_accepts_colon_and_dash_separators() {
+        let expected = [0xaa, 0xbb, 0xcc, 0x01, 0x02, 0x03];
+        assert_eq!(parse_mac_address("aa:bb:cc:01:02:03").unwrap(), expected);
+        assert_eq!(parse_mac_address("AA-BB-CC-01-02-03").unwrap(), expected);
+        assert!(parse_mac_address("aa:bb:cc:01:02").is_err());
+        assert!(parse_mac_address("00:00:00:00:00:00").is_err());
+        assert!(parse_mac_address("ff:ff:ff:ff:ff:ff").is_err());
+        assert!(parse_mac_address("zz:bb:cc:01:02:03").is_err());
+    }
+
+    #[test]
+    fn magic_packet_repeats_mac_after_sync_stream() {
+        let mac = [1, 2, 3, 4, 5, 6];
+        let packet = magic_packet(mac);
+
+        assert_eq!(packet.len(), 102);
+        assert_eq!(&packet[..6], &[0xff; 6]);
+        assert!(packet[6..].chunks_exact(6).all(|chunk| chunk == mac));
+    }
+
+    #[test]
+    fn known_mac_addresses_merges_saved_and_discovered_devices() {
+        let device = |macs: &[&str]| {
+            let mut device = DeviceInfo::new_unknown(PeerId::random());
+            device.mac_addresses = macs.iter().map(|mac| mac.to_string()).collect();
+            device
+        };
+        let saved = device(&["aa:bb:cc:01:02:03"]);
+        let discovered = device(&["aa:bb:cc:01:02:04", "aa:bb:cc:01:02:03"]);
+
+        assert_eq!(
+            known_mac_addresses(Some(&saved), Some(&discovered)),
+            vec!["aa:bb:cc:01:02:03", "aa:bb:cc:01:02:04"]
+        );
+        assert!(known_mac_addresses(None, None).is_empty());
+    }
+
+    #[test]
+    fn shares_lan_compares_private_ipv4_prefixes() {
+        let home = vec!["192.168.1.20".to_string()];
+        assert!(shares_lan(&home, &["192.168.1.77".to_string()]));
+        assert!(!shares_lan(&home, &["192.168.2.77".to_string()]));
+        assert!(!shares_lan(&home, &[]));
+        assert!(!shares_lan(
+            &["8.8.8.8".to_string()],
+            &["8.8.8.9".to_string()]
+        ));
+    }
+}
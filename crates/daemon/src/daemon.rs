@@ -1,7 +1,7 @@
 use std::{
     collections::{BTreeMap, BTreeSet},
     env,
-    net::{Ipv4Addr, Ipv6Addr},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
     path::PathBuf,
     sync::Arc,
     time::Duration,
@@ -10,12 +10,21 @@ use std::{
 use crate::{
     DaemonArgs,
     controls::{
-        DockerControl, NodeCapabilitiesControl, ServiceControlProtocolControl,
-        ServiceDiscoveryControl, TcpTunnelingControl, mdns::MdnsControl,
+        DeviceServicesSource, DnsResponderControl, DockerControl, HttpGatewayControl,
+        NodeCapabilitiesControl, OnDemandControl, ServiceBackupProtocolControl,
+        ServiceControlProtocolControl, ServiceDiscoveryControl, ServiceExecProtocolControl,
+        ServiceProxyControl, ServiceTransferProtocolControl, TcpTunnelingControl,
+        cached_named_device_services,
+        mdns::MdnsControl,
+        wake_on_lan::{KnownMacAddressesSource, known_mac_addresses},
+    },
+    runtime::{
+        ProcessRuntimeProvider, RuntimeControl, ServiceJobTrigger, process_runtime_supported,
+        wasmtime_runtime_supported,
     },
-    runtime::{RuntimeControl, wasmtime_runtime_supported},
 };
 use anyhow::{Result, bail};
+use chrono::{Local, Timelike};
 use fungi_config::{
     FungiConfig,
     devices::{DeviceInfo, DevicesConfig},
@@ -23,7 +32,7 @@ use fungi_config::{
     trusted_devices::TrustedDevicesConfig,
 };
 use fungi_swarm::{
-    ConnectionDirection, FungiSwarm, PeerAddressSource, State, SwarmControl, TSwarm,
+    AnnouncePolicy, ConnectionDirection, FungiSwarm, PeerAddressSource, State, SwarmControl, TSwarm,
 };
 use fungi_util::keypair::get_keypair_from_dir;
 use libp2p::{Multiaddr, identity::Keypair, multiaddr::Protocol};
@@ -31,14 +40,18 @@ use parking_lot::Mutex;
 use tokio::{sync::Mutex as AsyncMutex, task::JoinHandle};
 
 const DIRECT_ADDRESS_CACHE_SYNC_INTERVAL: Duration = Duration::from_secs(30);
+const DEVICE_LAN_DETAILS_SYNC_INTERVAL: Duration = Duration::from_secs(60);
 
 #[allow(dead_code)]
 struct TaskHandles {
     swarm_task: JoinHandle<()>,
     direct_address_cache_sync_task: JoinHandle<()>,
+    device_lan_details_sync_task: JoinHandle<()>,
+    job_scheduler_task: JoinHandle<()>,
 }
 
 #[allow(dead_code)]
+#[derive(Clone)]
 pub struct FungiDaemon {
     config: Arc<Mutex<FungiConfig>>,
     devices_config: Arc<Mutex<DevicesConfig>>,
@@ -51,12 +64,19 @@ pub struct FungiDaemon {
     mdns_control: MdnsControl,
     docker_control: Option<DockerControl>,
     tcp_tunneling_control: TcpTunnelingControl,
+    on_demand_control: OnDemandControl,
+    http_gateway_control: Option<HttpGatewayControl>,
+    service_proxy_control: Option<ServiceProxyControl>,
+    dns_responder_control: Option<DnsResponderControl>,
     runtime_control: RuntimeControl,
     service_discovery_control: ServiceDiscoveryControl,
     node_capabilities_control: NodeCapabilitiesControl,
     service_control_protocol_control: ServiceControlProtocolControl,
+    service_exec_protocol_control: ServiceExecProtocolControl,
+    service_transfer_protocol_control: ServiceTransferProtocolControl,
+    service_backup_protocol_control: ServiceBackupProtocolControl,
 
-    task_handles: TaskHandles,
+    task_handles: Arc<AsyncMutex<TaskHandles>>,
 }
 
 impl FungiDaemon {
@@ -88,6 +108,22 @@ impl FungiDaemon {
         &self.tcp_tunneling_control
     }
 
+    pub fn on_demand_control(&self) -> &OnDemandControl {
+        &self.on_demand_control
+    }
+
+    pub fn http_gateway_control(&self) -> Option<&HttpGatewayControl> {
+        self.http_gateway_control.as_ref()
+    }
+
+    pub fn service_proxy_control(&self) -> Option<&ServiceProxyControl> {
+        self.service_proxy_control.as_ref()
+    }
+
+    pub fn dns_responder_control(&self) -> Option<&DnsResponderControl> {
+        self.dns_responder_control.as_ref()
+    }
+
     pub fn runtime_control(&self) -> &RuntimeControl {
         &self.runtime_control
     }
@@ -104,6 +140,18 @@ impl FungiDaemon {
         &self.service_control_protocol_control
     }
 
+    pub fn service_exec_protocol_control(&self) -> &ServiceExecProtocolControl {
+        &self.service_exec_protocol_control
+    }
+
+    pub fn service_transfer_protocol_control(&self) -> &ServiceTransferProtocolControl {
+        &self.service_transfer_protocol_control
+    }
+
+    pub fn service_backup_protocol_control(&self) -> &ServiceBackupProtocolControl {
+        &self.service_backup_protocol_control
+    }
+
     pub fn mdns_control(&self) -> &MdnsControl {
         &self.mdns_control
     }
@@ -169,6 +217,7 @@ impl FungiDaemon {
             state.clone(),
             relay_addrs,
             idle_connection_timeout,
+            announce_policy(&config),
             |swarm| {
                 apply_listen(swarm, &config).expect("failed to configure swarm listeners");
             },
@@ -177,7 +226,11 @@ impl FungiDaemon {
         let mdns_control = MdnsControl::new();
         // TODO duplicate with libp2p-mdns?
         let device_info = mdns_device_info(&config, swarm_control.local_peer_id());
-        mdns_control.start(device_info, state.clone())?;
+        mdns_control.start(
+            device_info,
+            state.clone(),
+            config.network.excluded_interface_patterns(),
+        )?;
 
         let fungi_home = config
             .config_file_path()
@@ -185,14 +238,25 @@ impl FungiDaemon {
             .unwrap_or_else(|| std::path::Path::new("."))
             .to_path_buf();
         let docker_control = DockerControl::from_config(&config.runtime, &fungi_home)?;
+        if let Some(docker_control) = &docker_control {
+            match docker_control.detect_engine().await {
+                Ok(engine) => log::info!(
+                    "Detected {} {} container engine (rootless: {})",
+                    engine.flavor,
+                    engine.version,
+                    engine.rootless
+                ),
+                Err(error) => log::warn!("Failed to detect container engine: {error:#}"),
+            }
+        }
         let shared_config = Arc::new(Mutex::new(config.clone()));
         let runtime_root = config
             .config_file_path()
             .parent()
             .unwrap_or_else(|| std::path::Path::new("."))
             .join("runtime");
-        let runtime_control = RuntimeControl::new(
-            runtime_root,
+        let mut runtime_control = RuntimeControl::new(
+            runtime_root.clone(),
             env::current_exe()
                 .map_err(|e| anyhow::anyhow!("Failed to resolve current executable: {e}"))?,
             fungi_home.clone(),
@@ -200,7 +264,16 @@ impl FungiDaemon {
             fungi_home.join("services"),
             config.runtime.allowed_host_paths.clone(),
             config.runtime.wasmtime_enabled() && wasmtime_runtime_supported(),
-        )?;
+        )?
+        .with_local_peer_id(swarm_control.local_peer_id());
+        if config.runtime.process_enabled() && process_runtime_supported() {
+            runtime_control = runtime_control.with_process_provider(ProcessRuntimeProvider::new(
+                runtime_root,
+                fungi_home.clone(),
+                config.runtime.allowed_host_paths.clone(),
+                config.runtime.process_sandbox,
+            ));
+        }
         runtime_control.restore_persisted_state().await?;
         let service_discovery_control =
             ServiceDiscoveryControl::new(swarm_control.clone(), runtime_control.clone());
@@ -213,19 +286,86 @@ impl FungiDaemon {
         node_capabilities_control.start()?;
 
         let tcp_tunneling_control = TcpTunnelingControl::new(swarm_control.clone());
+        for entry in &config.bandwidth.published_ports {
+            tcp_tunneling_control.set_protocol_bandwidth_limit(
+                &fungi_util::protocols::service_port_protocol(
+                    &entry.service_name,
+                    &entry.port_name,
+                ),
+                entry.limit,
+            );
+        }
 
+        let on_demand_control = OnDemandControl::new(
+            runtime_control.clone(),
+            tcp_tunneling_control.clone(),
+            swarm_control.state().clone(),
+        );
+
+        let service_transfer_protocol_control = ServiceTransferProtocolControl::new(
+            swarm_control.clone(),
+            fungi_home.clone(),
+            runtime_control.clone(),
+            shared_config.clone(),
+        );
+        service_transfer_protocol_control.start()?;
+
+        let service_backup_protocol_control = ServiceBackupProtocolControl::new(
+            swarm_control.clone(),
+            fungi_home.clone(),
+            shared_config.clone(),
+        );
+        service_backup_protocol_control.start()?;
+
+        let local_preferences_lock = Arc::new(AsyncMutex::new(()));
+        let devices_config = Arc::new(Mutex::new(devices_config));
+        let known_mac_addresses: KnownMacAddressesSource = {
+            let devices_config = devices_config.clone();
+            let mdns_control = mdns_control.clone();
+            Arc::new(move |peer_id| {
+                known_mac_addresses(
+                    devices_config.lock().get_device_info(peer_id),
+                    mdns_control.get_device(peer_id).as_ref(),
+                )
+            })
+        };
         let service_control_protocol_control = ServiceControlProtocolControl::new(
             swarm_control.clone(),
-            fungi_home,
+            fungi_home.clone(),
             runtime_control.clone(),
             tcp_tunneling_control.clone(),
-        );
+            on_demand_control.clone(),
+            service_transfer_protocol_control.clone(),
+            local_preferences_lock.clone(),
+        )
+        .with_known_mac_addresses(known_mac_addresses);
         service_control_protocol_control.start()?;
 
-        let devices_config = Arc::new(Mutex::new(devices_config));
+        let service_exec_protocol_control = ServiceExecProtocolControl::new(
+            swarm_control.clone(),
+            runtime_control.clone(),
+            shared_config.clone(),
+        );
+        service_exec_protocol_control.start()?;
+
         let trusted_devices_config = Arc::new(Mutex::new(trusted_devices_config));
         let direct_address_cache = Arc::new(Mutex::new(direct_address_cache));
-        let local_preferences_lock = Arc::new(AsyncMutex::new(()));
+        let device_services: DeviceServicesSource = {
+            let devices_config = devices_config.clone();
+            let fungi_home = fungi_home.clone();
+            Arc::new(move || cached_named_device_services(&devices_config.lock(), &fungi_home))
+        };
+        let http_gateway_control =
+            start_http_gateway(&config, &swarm_control, device_services.clone()).await;
+        let service_proxy_control =
+            start_service_proxy(&config, &swarm_control, device_services).await;
+        let dns_responder_control = start_dns_responder(
+            &config,
+            devices_config.clone(),
+            fungi_home,
+            http_gateway_control.clone(),
+        )
+        .await;
 
         let task_handles = TaskHandles {
             swarm_task,
@@ -233,6 +373,11 @@ impl FungiDaemon {
                 swarm_control.clone(),
                 direct_address_cache.clone(),
             ),
+            device_lan_details_sync_task: spawn_device_lan_details_sync_task(
+                mdns_control.clone(),
+                devices_config.clone(),
+            ),
+            job_scheduler_task: spawn_job_scheduler_task(runtime_control.clone()),
         };
         let daemon = Self {
             config: shared_config,
@@ -245,12 +390,22 @@ impl FungiDaemon {
             mdns_control,
             docker_control,
             tcp_tunneling_control,
+            on_demand_control,
+            http_gateway_control,
+            service_proxy_control,
+            dns_responder_control,
             runtime_control,
             service_discovery_control,
             node_capabilities_control,
             service_control_protocol_control,
-            task_handles,
+            service_exec_protocol_control,
+            service_transfer_protocol_control,
+            service_backup_protocol_control,
+            task_handles: Arc::new(AsyncMutex::new(task_handles)),
         };
+        daemon
+            .service_control_protocol_control
+            .set_service_accesses(Arc::new(daemon.clone()));
 
         daemon.restore_service_endpoint_listeners().await?;
         daemon.restore_saved_service_access_from_snapshots().await;
@@ -259,8 +414,9 @@ impl FungiDaemon {
     }
 
     pub async fn wait_all(self) {
+        let mut task_handles = self.task_handles.lock().await;
         tokio::select! {
-            _ = self.task_handles.swarm_task => {
+            _ = &mut task_handles.swarm_task => {
                 println!("Swarm task is closed");
             },
             // _ = self.task_handles.daemon_rpc_task => {
@@ -271,9 +427,16 @@ impl FungiDaemon {
 
     async fn restore_service_endpoint_listeners(&self) -> Result<()> {
         let mut listening_rules = self.tcp_tunneling_control.get_listening_rules();
-        let mut restored_protocols = std::collections::BTreeSet::new();
+        let mut restored_protocols = BTreeSet::new();
 
-        for service in self.runtime_control.list_services().await? {
+        let services = self.runtime_control.list_services().await?;
+        let running_services = services
+            .iter()
+            .filter(|service| service.status.is_running())
+            .map(|service| service.name.clone())
+            .collect::<BTreeSet<_>>();
+
+        for service in services {
             if !service.status.is_running() {
                 continue;
             }
@@ -301,6 +464,8 @@ impl FungiDaemon {
                 )
                 .await;
             }
+            self.on_demand_control
+                .restore_manifest(&manifest, running_services.contains(&manifest.name));
         }
 
         Ok(())
@@ -438,27 +603,77 @@ fn hydrate_direct_address_cache(state: &State, cache: &DirectAddressCache) {
 
 fn mdns_device_info(config: &FungiConfig, peer_id: libp2p::PeerId) -> DeviceInfo {
     let mut device_info = DeviceInfo::this_device(peer_id, config.get_hostname());
+    let policy = announce_policy(config);
+    device_info.private_ips.retain(|ip| {
+        ip.parse::<IpAddr>()
+            .map(|ip| !policy.hides_ip(&ip))
+            .unwrap_or(true)
+    });
 
-    for ip in &device_info.private_ips {
-        let ip_version = if ip.contains(':') { "6" } else { "4" };
-        if config.network.listen_tcp_port != 0 {
-            device_info.multiaddrs.push(format!(
-                "/ip{ip_version}/{ip}/tcp/{}/p2p/{peer_id}",
-                config.network.listen_tcp_port
-            ));
+    let private_ips = device_info
+        .private_ips
+        .iter()
+        .filter_map(|ip| ip.parse::<IpAddr>().ok())
+        .collect::<Vec<_>>();
+    for listen_addr in listen_addresses(config) {
+        // Ephemeral ports are only known once bound; libp2p mDNS announces those.
+        if listen_addr
+            .iter()
+            .any(|protocol| matches!(protocol, Protocol::Tcp(0) | Protocol::Udp(0)))
+        {
+            continue;
         }
-
-        if config.network.listen_udp_port != 0 {
-            device_info.multiaddrs.push(format!(
-                "/ip{ip_version}/{ip}/udp/{}/quic-v1/p2p/{peer_id}",
-                config.network.listen_udp_port
-            ));
+        let Some(listen_ip) = multiaddr_ip(&listen_addr) else {
+            continue;
+        };
+        let hosts = if listen_ip.is_unspecified() {
+            private_ips
+                .iter()
+                .filter(|ip| ip.is_ipv4() == listen_ip.is_ipv4())
+                .copied()
+                .collect::<Vec<_>>()
+        } else if policy.hides_ip(&listen_ip) {
+            Vec::new()
+        } else {
+            vec![listen_ip]
+        };
+        for host in hosts {
+            device_info.multiaddrs.push(
+                with_host_ip(&listen_addr, host)
+                    .with(Protocol::P2p(peer_id))
+                    .to_string(),
+            );
         }
     }
 
     device_info
 }
 
+fn announce_policy(config: &FungiConfig) -> AnnouncePolicy {
+    AnnouncePolicy {
+        announce_addresses: config.network.announce_addresses.clone(),
+        no_announce: config.network.no_announce.clone(),
+        exclude_interfaces: config.network.excluded_interface_patterns(),
+    }
+}
+
+fn multiaddr_ip(addr: &Multiaddr) -> Option<IpAddr> {
+    addr.iter().find_map(|protocol| match protocol {
+        Protocol::Ip4(ip) => Some(IpAddr::V4(ip)),
+        Protocol::Ip6(ip) => Some(IpAddr::V6(ip)),
+        _ => None,
+    })
+}
+
+fn with_host_ip(addr: &Multiaddr, ip: IpAddr) -> Multiaddr {
+    addr.iter()
+        .map(|protocol| match protocol {
+            Protocol::Ip4(_) | Protocol::Ip6(_) => Protocol::from(ip),
+            other => other,
+        })
+        .collect()
+}
+
 fn spawn_direct_address_cache_sync_task(
     swarm_control: SwarmControl,
     direct_address_cache: Arc<Mutex<DirectAddressCache>>,
@@ -498,6 +713,150 @@ fn spawn_direct_address_cache_sync_task(
     })
 }
 
+/// Fires scheduled job runs. Wakes at the start of every minute and runs each started job whose
+/// schedule matches; a job still busy with an earlier run skips the trigger.
+fn spawn_job_scheduler_task(runtime_control: RuntimeControl) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut last_minute = None;
+        loop {
+            let now = Local::now();
+            let into_minute = Duration::new(u64::from(now.second()), now.nanosecond());
+            tokio::time::sleep(Duration::from_secs(60).saturating_sub(into_minute)).await;
+
+            let now = Local::now();
+            let minute = now.with_second(0).and_then(|now| now.with_nanosecond(0));
+            if minute == last_minute {
+                continue;
+            }
+            last_minute = minute;
+
+            for name in runtime_control.due_scheduled_jobs(&now) {
+                let runtime_control = runtime_control.clone();
+                tokio::spawn(async move {
+                    match runtime_control
+                        .run_job(&name, ServiceJobTrigger::Schedule)
+                        .await
+                    {
+                        Ok(run) => log::info!(
+                            "Scheduled run {} of job '{}' {}",
+                            run.run,
+                            name,
+                            run.outcome
+                        ),
+                        Err(error) => {
+                            log::warn!("Scheduled run of job '{}' failed: {}", name, error)
+                        }
+                    }
+                });
+            }
+        }
+    })
+}
+
+/// Copies LAN details seen over mDNS into saved devices, so Wake-on-LAN still knows a device's
+/// MAC and subnet after it goes to sleep.
+fn spawn_device_lan_details_sync_task(
+    mdns_control: MdnsControl,
+    devices_config: Arc<Mutex<DevicesConfig>>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(DEVICE_LAN_DETAILS_SYNC_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+
+            for (peer_id, device) in mdns_control.get_all_devices() {
+                let mut devices_config = devices_config.lock();
+                match devices_config.record_lan_details(
+                    &peer_id,
+                    &device.private_ips,
+                    &device.mac_addresses,
+                ) {
+                    Ok(Some(updated)) => *devices_config = updated,
+                    Ok(None) => {}
+                    Err(error) => {
+                        log::warn!("Failed to save LAN details for device {peer_id}: {error}");
+                    }
+                }
+            }
+        }
+    })
+}
+
+/// A gateway that fails to bind (port in use by another daemon) only costs the shared URLs, so
+/// it is logged rather than failing startup.
+async fn start_http_gateway(
+    config: &FungiConfig,
+    swarm_control: &SwarmControl,
+    device_services: DeviceServicesSource,
+) -> Option<HttpGatewayControl> {
+    if !config.http_gateway.enabled {
+        return None;
+    }
+
+    let gateway = HttpGatewayControl::new(
+        swarm_control.clone(),
+        config.http_gateway.domain.clone(),
+        device_services,
+    );
+    match gateway.start(&config.http_gateway.listen_address).await {
+        Ok(_) => Some(gateway),
+        Err(error) => {
+            log::warn!("HTTP gateway disabled: {error:#}");
+            None
+        }
+    }
+}
+
+async fn start_service_proxy(
+    config: &FungiConfig,
+    swarm_control: &SwarmControl,
+    device_services: DeviceServicesSource,
+) -> Option<ServiceProxyControl> {
+    if !config.service_proxy.enabled {
+        return None;
+    }
+
+    let proxy = ServiceProxyControl::new(
+        swarm_control.clone(),
+        config.service_proxy.domain.clone(),
+        device_services,
+    );
+    match proxy.start(&config.service_proxy.listen_address).await {
+        Ok(_) => Some(proxy),
+        Err(error) => {
+            log::warn!("Service proxy disabled: {error:#}");
+            None
+        }
+    }
+}
+
+async fn start_dns_responder(
+    config: &FungiConfig,
+    devices_config: Arc<Mutex<DevicesConfig>>,
+    fungi_dir: PathBuf,
+    http_gateway_control: Option<HttpGatewayControl>,
+) -> Option<DnsResponderControl> {
+    if !config.dns_responder.enabled {
+        return None;
+    }
+
+    let responder = DnsResponderControl::new(
+        config.dns_responder.domain.clone(),
+        devices_config,
+        fungi_dir,
+        http_gateway_control,
+    );
+    match responder.start(&config.dns_responder.listen_address).await {
+        Ok(_) => Some(responder),
+        Err(error) => {
+            log::warn!("DNS responder disabled: {error:#}");
+            None
+        }
+    }
+}
+
 fn collect_direct_connection_addresses(state: &State) -> BTreeMap<String, Vec<String>> {
     let mut grouped = BTreeMap::<String, Vec<String>>::new();
     for peer_id in state.connected_peer_ids() {
@@ -566,6 +925,30 @@ fn normalize_direct_address_groups(
 mod tests {
     use super::*;
 
+    #[test]
+    fn listen_addresses_prefer_explicit_configuration() {
+        let mut config = FungiConfig::default();
+        config.network.listen_tcp_port = 4001;
+        let defaults = listen_addresses(&config);
+        assert_eq!(defaults.len(), 4);
+        assert!(defaults.contains(&"/ip6/::/tcp/4001".parse().unwrap()));
+
+        let explicit: Multiaddr = "/ip6/fd00::5/udp/4001/quic-v1".parse().unwrap();
+        config.network.listen_addresses = vec![explicit.clone()];
+        assert_eq!(listen_addresses(&config), vec![explicit]);
+    }
+
+    #[test]
+    fn with_host_ip_replaces_only_the_ip_component() {
+        let addr: Multiaddr = "/ip4/0.0.0.0/udp/4001/quic-v1".parse().unwrap();
+
+        assert_eq!(
+            with_host_ip(&addr, "192.168.1.20".parse().unwrap()).to_string(),
+            "/ip4/192.168.1.20/udp/4001/quic-v1"
+        );
+        assert_eq!(multiaddr_ip(&addr), Some(IpAddr::V4(Ipv4Addr::UNSPECIFIED)));
+    }
+
     #[test]
     fn new_direct_address_successes_only_returns_new_pairs() {
         let mut last_synced_pairs = BTreeSet::new();
@@ -668,6 +1051,7 @@ async fn restore_service_endpoint_listener(
         host: "127.0.0.1".to_string(),
         port: host_port,
         protocol: Some(protocol),
+        bandwidth_limit: Default::default(),
     };
 
     match tcp_tunneling_control.add_listening_rule(rule.clone()).await {
@@ -682,29 +1066,40 @@ async fn restore_service_endpoint_listener(
     }
 }
 
-fn apply_listen(swarm: &mut TSwarm, config: &FungiConfig) -> Result<()> {
-    let tcp_addrs = [
+/// The configured listen addresses, or every IPv4 and IPv6 interface on the configured ports.
+fn listen_addresses(config: &FungiConfig) -> Vec<Multiaddr> {
+    if !config.network.listen_addresses.is_empty() {
+        return config.network.listen_addresses.clone();
+    }
+
+    let tcp_port = config.network.listen_tcp_port;
+    let udp_port = config.network.listen_udp_port;
+    vec![
         Multiaddr::empty()
             .with(Protocol::from(Ipv4Addr::UNSPECIFIED))
-            .with(Protocol::Tcp(config.network.listen_tcp_port)),
+            .with(Protocol::Tcp(tcp_port)),
         Multiaddr::empty()
             .with(Protocol::from(Ipv6Addr::UNSPECIFIED))
-            .with(Protocol::Tcp(config.network.listen_tcp_port)),
-    ];
-    let quic_addrs = [
+            .with(Protocol::Tcp(tcp_port)),
         Multiaddr::empty()
             .with(Protocol::from(Ipv6Addr::UNSPECIFIED))
-            .with(Protocol::Udp(config.network.listen_udp_port))
+            .with(Protocol::Udp(udp_port))
             .with(Protocol::QuicV1),
         Multiaddr::empty()
             .with(Protocol::from(Ipv4Addr::UNSPECIFIED))
-            .with(Protocol::Udp(config.network.listen_udp_port))
+            .with(Protocol::Udp(udp_port))
             .with(Protocol::QuicV1),
-    ];
+    ]
+}
+
+fn apply_listen(swarm: &mut TSwarm, config: &FungiConfig) -> Result<()> {
+    let (quic_addrs, tcp_addrs): (Vec<_>, Vec<_>) = listen_addresses(config)
+        .into_iter()
+        .partition(|addr| addr.iter().any(|protocol| protocol == Protocol::QuicV1));
 
     let mut tcp_listening = false;
     let mut tcp_errors = Vec::new();
-    for addr in tcp_addrs {
+    for addr in &tcp_addrs {
         match swarm.listen_on(addr.clone()) {
             Ok(_) => tcp_listening = true,
             Err(error) => {
@@ -714,7 +1109,7 @@ fn apply_listen(swarm: &mut TSwarm, config: &FungiConfig) -> Result<()> {
         }
     }
 
-    if !tcp_listening {
+    if !tcp_addrs.is_empty() && !tcp_listening {
         bail!(
             "Failed to open any TCP listen address: {}",
             tcp_errors.join("; ")
@@ -723,7 +1118,7 @@ fn apply_listen(swarm: &mut TSwarm, config: &FungiConfig) -> Result<()> {
 
     let mut quic_listening = false;
     let mut quic_errors = Vec::new();
-    for addr in quic_addrs {
+    for addr in &quic_addrs {
         match swarm.listen_on(addr.clone()) {
             Ok(_) => quic_listening = true,
             Err(error) => {
@@ -734,6 +1129,12 @@ fn apply_listen(swarm: &mut TSwarm, config: &FungiConfig) -> Result<()> {
     }
 
     if !quic_listening && !quic_errors.is_empty() {
+        if !tcp_listening {
+            bail!(
+                "Failed to open any listen address: {}",
+                quic_errors.join("; ")
+            );
+        }
         log::warn!(
             "No QUIC listen address could be opened; continuing with TCP only: {}",
             quic_errors.join("; ")
@@ -0,0 +1,122 @@
+use anyhow::{Result, bail};
+use fungi_docker_agent::pinned_image_digest;
+use libp2p::identity::ed25519;
+use sha2::{Digest, Sha256};
+
+const SHA256_PREFIX: &str = "sha256:";
+
+/// Normalizes a pinned digest written as `sha256:<hex>` or bare `<hex>` to lowercase hex, the
+/// same way the Docker policy reads image digests.
+pub(crate) fn normalize_sha256_digest(value: &str) -> Result<String> {
+    fungi_docker_agent::normalize_sha256_digest(value).ok_or_else(|| {
+        anyhow::anyhow!(
+            "invalid sha256 digest `{}`: expected 64 hex characters",
+            value.trim()
+        )
+    })
+}
+
+pub(crate) fn sha256_hex(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+/// Fails unless `bytes` hash to the pinned digest. `what` names the artifact in the error.
+pub(crate) fn verify_sha256(bytes: &[u8], expected: &str, what: &str) -> Result<()> {
+    let expected = normalize_sha256_digest(expected)?;
+    let actual = sha256_hex(bytes);
+    if actual != expected {
+        bail!("{what} failed integrity check: expected sha256:{expected}, got sha256:{actual}");
+    }
+    Ok(())
+}
+
+/// Pins a Docker image reference to `digest`, or checks an already pinned reference agrees. The
+/// digest is always spelled in lowercase.
+pub(crate) fn pin_docker_image(image: &str, digest: &str) -> Result<String> {
+    let digest = normalize_sha256_digest(digest)?;
+    match image.rsplit_once('@') {
+        Some((name, _)) => {
+            let pinned = pinned_image_digest(image)
+                .ok_or_else(|| anyhow::anyhow!("image `{image}` is not pinned to a sha256 digest"))
+                .and_then(normalize_sha256_digest)?;
+            if pinned != digest {
+                bail!("image `{image}` is pinned to a different digest than source.sha256");
+            }
+            Ok(format!("{name}@{SHA256_PREFIX}{digest}"))
+        }
+        None => Ok(format!("{image}@{SHA256_PREFIX}{digest}")),
+    }
+}
+
+/// Verifies a detached signature file against hex-encoded Ed25519 publisher keys.
+///
+/// The file holds one hex-encoded signature per line; blank lines and `#` comments are ignored.
+/// Any signature made by any trusted key is accepted.
+pub(crate) fn verify_detached_signature(
+    message: &[u8],
+    signatures: &str,
+    trusted_keys: &[String],
+) -> Result<()> {
+    let keys = trusted_keys
+        .iter()
+        .map(|key| decode_ed25519_public_key(key))
+        .collect::<Result<Vec<_>>>()?;
+    let verified = signatures
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| hex::decode(line).ok())
+        .any(|signature| keys.iter().any(|key| key.verify(message, &signature)));
+    if !verified {
+        bail!("no signature from a trusted publisher key");
+    }
+    Ok(())
+}
+
+fn decode_ed25519_public_key(value: &str) -> Result<ed25519::PublicKey> {
+    let bytes = hex::decode(value.trim())
+        .map_err(|_| anyhow::anyhow!("trusted key `{value}` is not valid hex"))?;
+    ed25519::PublicKey::try_from_bytes(&bytes)
+        .map_err(|_| anyhow::anyhow!("trusted key `{value}` is not an Ed25519 public key"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verifies_pinned_digests() {
+        let digest = sha256_hex(b"component");
+        verify_sha256(b"component", &format!("sha256:{digest}"), "component").unwrap();
+        let error = verify_sha256(b"tampered", &digest, "component").unwrap_err();
+        assert!(error.to_string().contains("failed integrity check"));
+        assert!(normalize_sha256_digest("sha256:abc").is_err());
+    }
+
+    #[test]
+    fn pins_docker_images() {
+        let digest = "a".repeat(64);
+        let pinned = pin_docker_image("nginx:1.27", &digest).unwrap();
+        assert_eq!(pinned, format!("nginx:1.27@sha256:{digest}"));
+        assert_eq!(pinned_image_digest(&pinned), Some(digest.as_str()));
+        assert_eq!(pin_docker_image(&pinned, &digest).unwrap(), pinned);
+        let shouted = format!("nginx:1.27@sha256:{}", digest.to_uppercase());
+        assert_eq!(pin_docker_image(&shouted, &digest).unwrap(), pinned);
+        assert!(pin_docker_image(&pinned, &"b".repeat(64)).is_err());
+        assert_eq!(pinned_image_digest("nginx:1.27"), None);
+    }
+
+    #[test]
+    fn verifies_detached_signatures_against_trusted_keys() {
+        let publisher = ed25519::Keypair::generate();
+        let other = ed25519::Keypair::generate();
+        let message = br#"{"recipes":[]}"#;
+        let signatures = format!("# index.json\n{}\n", hex::encode(publisher.sign(message)));
+        let trusted = vec![hex::encode(publisher.public().to_bytes())];
+        let untrusted = vec![hex::encode(other.public().to_bytes())];
+
+        verify_detached_signature(message, &signatures, &trusted).unwrap();
+        assert!(verify_detached_signature(message, &signatures, &untrusted).is_err());
+        assert!(verify_detached_signature(b"tampered", &signatures, &trusted).is_err());
+    }
+}
@@ -1,9 +1,11 @@
 mod api;
 mod controls;
 mod daemon;
+mod integrity;
 mod node_capabilities;
 mod recipes;
 pub mod runtime;
+mod secrets;
 mod service_control;
 mod service_state;
 
@@ -13,9 +15,11 @@ mod service_state;
 /// with `features = ["test-support"]` to gate their own compilation on it.
 pub mod test_support;
 
-pub use api::{ServiceAccess, ServiceAccessEndpoint};
+pub use api::{ServiceAccess, ServiceAccessEndpoint, ServiceBackupOptions};
 use clap::Parser;
+pub use controls::{PrunedImage, ServiceBackupInfo};
 pub use daemon::FungiDaemon;
+pub use fungi_docker_agent::{EngineFlavor, EngineInfo, ImagePullProgress};
 pub use node_capabilities::{
     LocalRuntimeAvailability, LocalRuntimeStatus, NodeCapabilities, NodeRuntimeCapabilities,
     build_local_node_capabilities, build_local_runtime_status,
@@ -25,14 +29,20 @@ pub use recipes::{
 };
 pub use runtime::{
     DeviceService, DeviceServiceEndpoint, DeviceServiceMetadata, DeviceServiceSnapshot,
-    ManifestResolutionPolicy, RuntimeControl, RuntimeKind, ServiceExpose,
-    ServiceExposeEndpointBinding, ServiceExposeTransport, ServiceExposeTransportKind,
-    ServiceExposeUsage, ServiceExposeUsageKind, ServiceInstance, ServiceLogs, ServiceLogsOptions,
-    ServiceManifest, ServiceMount, ServicePhase, ServicePort, ServicePortAllocation,
-    ServicePortProtocol, ServiceRunMode, ServiceSource, ServiceStatus,
-    load_service_manifest_yaml_file, parse_service_manifest_yaml, peek_service_manifest_name,
-    service_expose_endpoint_bindings, service_manifest_with_instance_name,
+    ManifestResolutionPolicy, RuntimeControl, RuntimeKind, ServiceContainerOptions,
+    ServiceExecExit, ServiceExecInput, ServiceExecOutput, ServiceExecRequest, ServiceExecSession,
+    ServiceExecSize, ServiceExpose, ServiceExposeEndpointBinding, ServiceExposeTransport,
+    ServiceExposeTransportKind, ServiceExposeUsage, ServiceExposeUsageKind, ServiceInstance,
+    ServiceJob, ServiceJobOutcome, ServiceJobRun, ServiceJobStatus, ServiceJobTrigger,
+    ServiceLogMark, ServiceLogs, ServiceLogsOptions, ServiceManifest, ServiceMount, ServiceOrigin,
+    ServicePhase, ServicePort, ServicePortAllocation, ServicePortProtocol, ServiceResourceUsage,
+    ServiceRevision, ServiceRunMode, ServiceSecurity, ServiceSource, ServiceStatus, ServiceTmpfs,
+    ServiceVolume, load_service_manifest_yaml_file, manifest_yaml_digest,
+    parse_service_input_values_yaml, parse_service_manifest_yaml, peek_service_manifest_name,
+    service_expose_endpoint_bindings, service_manifest_with_inputs,
+    service_manifest_with_instance_name,
 };
+pub use secrets::{SecretInfo, SecretStore, validate_secret_name};
 pub use service_control::{
     ServiceControlError, ServiceControlRequest, ServiceControlResponse, ServiceControlServiceRef,
 };
@@ -1,8 +1,9 @@
 use fungi_config::FungiConfig;
+use fungi_docker_agent::{EngineFlavor, EngineInfo};
 use serde::{Deserialize, Serialize};
 
 use crate::controls::detect_socket_path;
-use crate::runtime::wasmtime_runtime_supported;
+use crate::runtime::{process_runtime_supported, wasmtime_runtime_supported};
 use crate::{RuntimeControl, RuntimeKind};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -15,12 +16,20 @@ pub struct NodeCapabilities {
 pub struct NodeRuntimeCapabilities {
     pub docker: bool,
     pub wasmtime: bool,
+    #[serde(default)]
+    pub process: bool,
+    /// Engine behind the Docker runtime; unset by older nodes and before detection.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub docker_engine: Option<EngineFlavor>,
+    #[serde(default)]
+    pub docker_rootless: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LocalRuntimeStatus {
     pub docker: LocalRuntimeAvailability,
     pub wasmtime: LocalRuntimeAvailability,
+    pub process: LocalRuntimeAvailability,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,16 +38,22 @@ pub struct LocalRuntimeAvailability {
     pub detected: bool,
     pub active: bool,
     pub endpoint: Option<String>,
+    #[serde(default)]
+    pub engine: Option<EngineInfo>,
 }
 
 pub fn build_local_node_capabilities(
     _config: &FungiConfig,
     runtime_control: &RuntimeControl,
 ) -> NodeCapabilities {
+    let docker_engine = runtime_control.docker_engine();
     NodeCapabilities {
         runtimes: NodeRuntimeCapabilities {
             docker: runtime_control.supports(crate::RuntimeKind::Docker),
             wasmtime: runtime_control.supports(crate::RuntimeKind::Wasmtime),
+            process: runtime_control.supports(crate::RuntimeKind::Process),
+            docker_engine: docker_engine.as_ref().map(|engine| engine.flavor),
+            docker_rootless: docker_engine.is_some_and(|engine| engine.rootless),
         },
         storage_roots: vec!["fungi_home".to_string()],
     }
@@ -57,12 +72,21 @@ pub fn build_local_runtime_status(
             detected: docker_endpoint.is_some(),
             active: runtime_control.supports(RuntimeKind::Docker),
             endpoint: docker_endpoint,
+            engine: runtime_control.docker_engine(),
         },
         wasmtime: LocalRuntimeAvailability {
             config_enabled: !config.runtime.disable_wasmtime,
             detected: wasmtime_runtime_supported(),
             active: runtime_control.supports(RuntimeKind::Wasmtime),
             endpoint: None,
+            engine: None,
+        },
+        process: LocalRuntimeAvailability {
+            config_enabled: config.runtime.process_enabled(),
+            detected: process_runtime_supported(),
+            active: runtime_control.supports(RuntimeKind::Process),
+            endpoint: None,
+            engine: None,
         },
     }
 }
@@ -1,14 +1,30 @@
-use std::path::{Path, PathBuf};
+use std::{
+    collections::BTreeMap,
+    io::Cursor,
+    path::{Path, PathBuf},
+};
 
 use anyhow::{Context as _, Result, bail};
-use fungi_config::recipe_cache::{RecipeCache, validate_asset_name};
+use fungi_config::{
+    recipe_cache::{RecipeCache, validate_asset_name},
+    recipe_sources::{OFFICIAL_RECIPE_NAMESPACE, RecipeSourceLocation, RecipeSources, is_http_url},
+};
 use serde::Deserialize;
+use sha2::{Digest, Sha256};
 
-use crate::{peek_service_manifest_name, service_manifest_with_instance_name};
+use crate::{
+    integrity::{verify_detached_signature, verify_sha256},
+    peek_service_manifest_name, service_manifest_with_inputs, service_manifest_with_instance_name,
+};
 
 const OFFICIAL_RECIPE_SOURCE_LABEL: &str = "enbop/fungi-service-recipes";
 const OFFICIAL_RECIPE_LATEST_RELEASE_URL: &str =
     "https://github.com/enbop/fungi-service-recipes/releases/latest";
+const RECIPE_INDEX_FILE: &str = "index.json";
+/// Detached publisher signatures over the exact bytes of `index.json`.
+const RECIPE_SIGNATURE_FILE: &str = "index.json.sig";
+/// Release version used for directory sources, which are read in place and never versioned.
+const LOCAL_RECIPE_RELEASE: &str = "local";
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ServiceRecipeRuntime {
@@ -50,62 +66,108 @@ pub struct ResolvedServiceRecipe {
 
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
-struct OfficialRecipeIndex {
-    recipes: Vec<OfficialRecipeRecord>,
+struct RecipeIndex {
+    recipes: Vec<RecipeRecord>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
-struct OfficialRecipeRecord {
+struct RecipeRecord {
     id: String,
     name: String,
     description: String,
     runtime: String,
     manifest_asset: String,
+    /// Pinned digest of the manifest asset, checked whenever it is fetched or read.
+    #[serde(default)]
+    manifest_sha256: Option<String>,
     readme_asset: Option<String>,
+    #[serde(default)]
+    readme_sha256: Option<String>,
     homepage: Option<String>,
     #[serde(default)]
     tags: Vec<String>,
     stability: String,
 }
 
-struct LoadedOfficialRecipeIndex {
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RecipeSourceSpec {
+    namespace: String,
+    priority: i32,
+    trusted_keys: Vec<String>,
+    location: RecipeSourceSpecLocation,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum RecipeSourceSpecLocation {
+    Official,
+    Configured(RecipeSourceLocation),
+}
+
+/// Where the manifest and readme assets named by a loaded index live.
+enum RecipeAssets {
+    /// Fetched into the cache on demand from the given base URL.
+    Remote { base_url: reqwest::Url },
+    /// Already on disk, either a directory source or an unpacked tarball.
+    Local { dir: PathBuf },
+}
+
+struct LoadedRecipeIndex {
+    source: RecipeSourceSpec,
     release_version: String,
-    index: OfficialRecipeIndex,
+    index: RecipeIndex,
     cache: RecipeCache,
+    assets: RecipeAssets,
 }
 
-pub async fn list_official_service_recipes(
+pub async fn list_service_recipes(
     fungi_dir: &Path,
+    sources: &RecipeSources,
     refresh: bool,
 ) -> Result<Vec<ServiceRecipeSummary>> {
-    let loaded = load_official_recipe_index(fungi_dir, refresh).await?;
-    loaded
-        .index
-        .recipes
-        .iter()
-        .map(|recipe| build_recipe_summary(recipe, &loaded.release_version))
-        .collect()
+    let mut summaries = Vec::new();
+    let mut first_error = None;
+    let mut loaded_any = false;
+    for source in recipe_source_specs(sources) {
+        match load_recipe_index(fungi_dir, &source, refresh).await {
+            Ok(loaded) => {
+                loaded_any = true;
+                for recipe in &loaded.index.recipes {
+                    summaries.push(build_recipe_summary(&loaded, recipe)?);
+                }
+            }
+            Err(error) => {
+                log::warn!("Skipping recipe source `{}`: {error:#}", source.namespace);
+                first_error.get_or_insert(error);
+            }
+        }
+    }
+    match first_error {
+        Some(error) if !loaded_any => Err(error),
+        _ => Ok(summaries),
+    }
 }
 
-pub async fn get_official_service_recipe(
+pub async fn get_service_recipe(
     fungi_dir: &Path,
-    recipe_id: &str,
+    sources: &RecipeSources,
+    recipe_ref: &str,
     refresh: bool,
 ) -> Result<ServiceRecipeDetail> {
-    let loaded = load_official_recipe_index(fungi_dir, refresh).await?;
-    let recipe = loaded.find_recipe(recipe_id)?;
-    build_recipe_detail(&loaded, recipe).await
+    let (loaded, recipe) = find_recipe(fungi_dir, sources, recipe_ref, refresh).await?;
+    build_recipe_detail(&loaded, &loaded.index.recipes[recipe]).await
 }
 
-pub async fn resolve_official_service_recipe(
+pub async fn resolve_service_recipe(
     fungi_dir: &Path,
-    recipe_id: &str,
+    sources: &RecipeSources,
+    recipe_ref: &str,
     service_name: Option<&str>,
+    inputs: &BTreeMap<String, String>,
     refresh: bool,
 ) -> Result<ResolvedServiceRecipe> {
-    let loaded = load_official_recipe_index(fungi_dir, refresh).await?;
-    let recipe = loaded.find_recipe(recipe_id)?;
+    let (loaded, recipe) = find_recipe(fungi_dir, sources, recipe_ref, refresh).await?;
+    let recipe = &loaded.index.recipes[recipe];
     let detail = build_recipe_detail(&loaded, recipe).await?;
     let manifest_yaml =
         std::fs::read_to_string(&detail.cached_manifest_path).with_context(|| {
@@ -115,6 +177,8 @@ pub async fn resolve_official_service_recipe(
             )
         })?;
     let resolved_name = resolved_service_name(recipe, service_name);
+    let manifest_yaml = service_manifest_with_inputs(&manifest_yaml, inputs)
+        .with_context(|| format!("failed to apply inputs to recipe `{}`", detail.summary.id))?;
     let resolved_manifest_yaml =
         service_manifest_with_instance_name(&manifest_yaml, &resolved_name).with_context(|| {
             format!(
@@ -131,7 +195,10 @@ pub async fn resolve_official_service_recipe(
         &resolved_name,
         &resolved_manifest_yaml,
     )?;
-    let manifest_base_dir = loaded.cache.ensure_asset_dir(&loaded.release_version)?;
+    let manifest_base_dir = match &loaded.assets {
+        RecipeAssets::Local { dir } => dir.clone(),
+        RecipeAssets::Remote { .. } => loaded.cache.ensure_asset_dir(&loaded.release_version)?,
+    };
 
     Ok(ResolvedServiceRecipe {
         detail,
@@ -142,45 +209,238 @@ pub async fn resolve_official_service_recipe(
     })
 }
 
-impl LoadedOfficialRecipeIndex {
-    fn find_recipe(&self, recipe_id: &str) -> Result<&OfficialRecipeRecord> {
+/// Sources in lookup order: descending priority, the official one before configured sources on
+/// ties, so a source added at the default priority cannot shadow official recipes, then config
+/// order.
+fn recipe_source_specs(sources: &RecipeSources) -> Vec<RecipeSourceSpec> {
+    let mut specs = Vec::new();
+    if sources.official {
+        specs.push(RecipeSourceSpec {
+            namespace: OFFICIAL_RECIPE_NAMESPACE.to_string(),
+            priority: 0,
+            trusted_keys: sources.official_trusted_keys.clone(),
+            location: RecipeSourceSpecLocation::Official,
+        });
+    }
+    specs.extend(sources.sources.iter().map(|source| RecipeSourceSpec {
+        namespace: source.namespace.clone(),
+        priority: source.priority,
+        trusted_keys: source.trusted_keys.clone(),
+        location: RecipeSourceSpecLocation::Configured(source.location.clone()),
+    }));
+    specs.sort_by_key(|spec| std::cmp::Reverse(spec.priority));
+    specs
+}
+
+/// Accepts `namespace/recipe-id`, or a bare `recipe-id` resolved against sources by priority.
+async fn find_recipe(
+    fungi_dir: &Path,
+    sources: &RecipeSources,
+    recipe_ref: &str,
+    refresh: bool,
+) -> Result<(LoadedRecipeIndex, usize)> {
+    let specs = recipe_source_specs(sources);
+    if let Some((namespace, recipe_id)) = recipe_ref.split_once('/') {
+        let source = specs
+            .into_iter()
+            .find(|spec| spec.namespace == namespace)
+            .ok_or_else(|| anyhow::anyhow!("unknown recipe source `{namespace}`"))?;
+        let loaded = load_recipe_index(fungi_dir, &source, refresh).await?;
+        let recipe = loaded
+            .position(recipe_id)
+            .ok_or_else(|| anyhow::anyhow!("unknown recipe `{recipe_ref}`"))?;
+        return Ok((loaded, recipe));
+    }
+
+    let mut first_error = None;
+    for source in specs {
+        match load_recipe_index(fungi_dir, &source, refresh).await {
+            Ok(loaded) => {
+                if let Some(recipe) = loaded.position(recipe_ref) {
+                    return Ok((loaded, recipe));
+                }
+            }
+            Err(error) => {
+                log::warn!("Skipping recipe source `{}`: {error:#}", source.namespace);
+                first_error.get_or_insert(error);
+            }
+        }
+    }
+    match first_error {
+        Some(error) => Err(error.context(format!(
+            "recipe `{recipe_ref}` was not found in any reachable source"
+        ))),
+        None => bail!("unknown recipe `{recipe_ref}`"),
+    }
+}
+
+impl LoadedRecipeIndex {
+    fn position(&self, recipe_id: &str) -> Option<usize> {
         self.index
             .recipes
             .iter()
-            .find(|recipe| recipe.id == recipe_id)
-            .ok_or_else(|| anyhow::anyhow!("unknown recipe `{recipe_id}`"))
+            .position(|recipe| recipe.id == recipe_id)
+    }
+
+    fn is_official(&self) -> bool {
+        self.source.location == RecipeSourceSpecLocation::Official
+    }
+
+    /// Official recipes keep their bare IDs; every other source is namespaced.
+    fn qualified_id(&self, recipe_id: &str) -> String {
+        if self.is_official() {
+            recipe_id.to_string()
+        } else {
+            format!("{}/{}", self.source.namespace, recipe_id)
+        }
+    }
+
+    fn source_label(&self) -> String {
+        match &self.source.location {
+            RecipeSourceSpecLocation::Official => OFFICIAL_RECIPE_SOURCE_LABEL.to_string(),
+            RecipeSourceSpecLocation::Configured(RecipeSourceLocation::Index { url })
+            | RecipeSourceSpecLocation::Configured(RecipeSourceLocation::Tarball { url }) => {
+                format!("{} ({url})", self.source.namespace)
+            }
+            RecipeSourceSpecLocation::Configured(RecipeSourceLocation::Directory { path }) => {
+                format!("{} ({})", self.source.namespace, path.display())
+            }
+        }
+    }
+
+    fn asset_location(&self, asset_name: &str) -> Result<String> {
+        let asset_name = validate_asset_name(asset_name)?;
+        Ok(match &self.source.location {
+            RecipeSourceSpecLocation::Configured(RecipeSourceLocation::Tarball { url }) => {
+                format!("{url}#{asset_name}")
+            }
+            _ => match &self.assets {
+                RecipeAssets::Remote { base_url } => base_url
+                    .join(asset_name)
+                    .with_context(|| format!("invalid recipe asset `{asset_name}`"))?
+                    .to_string(),
+                RecipeAssets::Local { dir } => dir.join(asset_name).display().to_string(),
+            },
+        })
+    }
+
+    /// Returns the on-disk path of an asset, fetching remote assets into the cache. With a pinned
+    /// digest, mismatched content is refused and never written to the cache.
+    async fn ensure_asset(&self, asset_name: &str, sha256: Option<&str>) -> Result<PathBuf> {
+        let asset_name = validate_asset_name(asset_name)?;
+        let what = format!("recipe asset `{asset_name}`");
+        match &self.assets {
+            RecipeAssets::Local { dir } => {
+                let path = dir.join(asset_name);
+                if !path.is_file() {
+                    bail!(
+                        "recipe asset `{asset_name}` is missing from {}",
+                        dir.display()
+                    );
+                }
+                if let Some(sha256) = sha256 {
+                    let bytes = std::fs::read(&path)
+                        .with_context(|| format!("failed to read {}", path.display()))?;
+                    verify_sha256(&bytes, sha256, &what)?;
+                }
+                Ok(path)
+            }
+            RecipeAssets::Remote { base_url } => {
+                let path = self.cache.asset_path(&self.release_version, asset_name)?;
+                if let Ok(bytes) = std::fs::read(&path)
+                    && !bytes.is_empty()
+                    && sha256.is_none_or(|sha256| verify_sha256(&bytes, sha256, &what).is_ok())
+                {
+                    return Ok(path);
+                }
+                let url = base_url
+                    .join(asset_name)
+                    .with_context(|| format!("invalid recipe asset `{asset_name}`"))?;
+                let bytes = fetch_bytes(url.as_str())
+                    .await
+                    .with_context(|| format!("failed to fetch recipe asset `{asset_name}`"))?;
+                if let Some(sha256) = sha256 {
+                    verify_sha256(&bytes, sha256, &what)?;
+                }
+                self.cache
+                    .write_asset(&self.release_version, asset_name, &bytes)
+            }
+        }
+    }
+
+    /// The digest an asset is checked against. A signed index vouches for its assets only through
+    /// their digests, so every asset of a source with trusted keys must have one.
+    fn pinned_sha256<'a>(
+        &self,
+        asset_name: &str,
+        sha256: Option<&'a str>,
+    ) -> Result<Option<&'a str>> {
+        if sha256.is_none() && !self.source.trusted_keys.is_empty() {
+            bail!(
+                "recipe asset `{asset_name}` from signed source `{}` has no sha256 digest",
+                self.source.namespace
+            );
+        }
+        Ok(sha256)
+    }
+
+    /// Checks `index.json.sig` against the source's trusted publisher keys, if any are configured.
+    async fn verify_index_signature(&self, index_bytes: &[u8]) -> Result<()> {
+        if self.source.trusted_keys.is_empty() {
+            return Ok(());
+        }
+        let signature_path = self
+            .ensure_asset(RECIPE_SIGNATURE_FILE, None)
+            .await
+            .with_context(|| {
+                format!(
+                    "recipe source `{}` requires a signed index",
+                    self.source.namespace
+                )
+            })?;
+        let signatures = std::fs::read_to_string(&signature_path)
+            .with_context(|| format!("failed to read {}", signature_path.display()))?;
+        verify_detached_signature(index_bytes, &signatures, &self.source.trusted_keys).with_context(
+            || {
+                format!(
+                    "recipe index from source `{}` failed signature verification",
+                    self.source.namespace
+                )
+            },
+        )
     }
 }
 
 fn build_recipe_summary(
-    recipe: &OfficialRecipeRecord,
-    release_version: &str,
+    loaded: &LoadedRecipeIndex,
+    recipe: &RecipeRecord,
 ) -> Result<ServiceRecipeSummary> {
     Ok(ServiceRecipeSummary {
-        id: recipe.id.clone(),
+        id: loaded.qualified_id(&recipe.id),
         name: recipe.name.clone(),
         description: recipe.description.clone(),
         runtime: parse_recipe_runtime(&recipe.runtime)?,
         stability: recipe.stability.clone(),
-        source_label: OFFICIAL_RECIPE_SOURCE_LABEL.to_string(),
-        release_version: release_version.to_string(),
+        source_label: loaded.source_label(),
+        release_version: loaded.release_version.clone(),
     })
 }
 
 async fn build_recipe_detail(
-    loaded: &LoadedOfficialRecipeIndex,
-    recipe: &OfficialRecipeRecord,
+    loaded: &LoadedRecipeIndex,
+    recipe: &RecipeRecord,
 ) -> Result<ServiceRecipeDetail> {
-    let summary = build_recipe_summary(recipe, &loaded.release_version)?;
-    let cached_manifest_path = ensure_cached_asset(
-        &loaded.cache,
-        &loaded.release_version,
-        &recipe.manifest_asset,
-    )
-    .await?;
+    let summary = build_recipe_summary(loaded, recipe)?;
+    let manifest_sha256 =
+        loaded.pinned_sha256(&recipe.manifest_asset, recipe.manifest_sha256.as_deref())?;
+    let cached_manifest_path = loaded
+        .ensure_asset(&recipe.manifest_asset, manifest_sha256)
+        .await?;
     let cached_readme_path = match &recipe.readme_asset {
         Some(readme_asset) => {
-            Some(ensure_cached_asset(&loaded.cache, &loaded.release_version, readme_asset).await?)
+            let readme_sha256 =
+                loaded.pinned_sha256(readme_asset, recipe.readme_sha256.as_deref())?;
+            Some(loaded.ensure_asset(readme_asset, readme_sha256).await?)
         }
         None => None,
     };
@@ -191,98 +451,194 @@ async fn build_recipe_detail(
         homepage: recipe.homepage.clone(),
         cached_manifest_path,
         cached_readme_path,
-        remote_manifest_url: release_asset_url(&loaded.release_version, &recipe.manifest_asset)?,
+        remote_manifest_url: loaded.asset_location(&recipe.manifest_asset)?,
         remote_readme_url: recipe
             .readme_asset
             .as_ref()
-            .map(|asset| release_asset_url(&loaded.release_version, asset))
+            .map(|asset| loaded.asset_location(asset))
             .transpose()?,
     })
 }
 
-async fn load_official_recipe_index(
+async fn load_recipe_index(
     fungi_dir: &Path,
+    source: &RecipeSourceSpec,
     refresh: bool,
-) -> Result<LoadedOfficialRecipeIndex> {
-    let cache = RecipeCache::apply_from_dir(fungi_dir)?;
-    let release_version = if refresh {
-        None
-    } else {
-        cache.latest_release_version()?
+) -> Result<LoadedRecipeIndex> {
+    let cache = RecipeCache::for_source(fungi_dir, &source.namespace)?;
+    let (release_version, index_path, assets) = match &source.location {
+        RecipeSourceSpecLocation::Official => {
+            let release_version = cached_release_version(&cache, refresh)?;
+            let release_version = match release_version {
+                Some(release_version) => release_version,
+                None => refresh_latest_official_recipe_index(&cache).await?,
+            };
+            let base_url =
+                reqwest::Url::parse(&release_asset_url(&release_version, RECIPE_INDEX_FILE)?)
+                    .context("invalid official recipe release url")?;
+            (
+                release_version.clone(),
+                cache.index_path(&release_version),
+                RecipeAssets::Remote { base_url },
+            )
+        }
+        RecipeSourceSpecLocation::Configured(RecipeSourceLocation::Index { url }) => {
+            let release_version = match cached_release_version(&cache, refresh)? {
+                Some(release_version) => release_version,
+                None => {
+                    let bytes = fetch_bytes(url)
+                        .await
+                        .with_context(|| format!("failed to fetch recipe index {url}"))?;
+                    let release_version = content_release_version(&bytes);
+                    cache.write_index(&release_version, &bytes)?;
+                    cache.set_latest_release_version(release_version.clone())?;
+                    release_version
+                }
+            };
+            let base_url = reqwest::Url::parse(url)
+                .with_context(|| format!("invalid recipe index url {url}"))?;
+            (
+                release_version.clone(),
+                cache.index_path(&release_version),
+                RecipeAssets::Remote { base_url },
+            )
+        }
+        RecipeSourceSpecLocation::Configured(RecipeSourceLocation::Directory { path }) => (
+            LOCAL_RECIPE_RELEASE.to_string(),
+            path.join(RECIPE_INDEX_FILE),
+            RecipeAssets::Local { dir: path.clone() },
+        ),
+        RecipeSourceSpecLocation::Configured(RecipeSourceLocation::Tarball { url }) => {
+            let release_version = load_recipe_tarball(&cache, url, refresh).await?;
+            let dir = recipe_tarball_root(&cache.asset_dir(&release_version))?;
+            (
+                release_version,
+                dir.join(RECIPE_INDEX_FILE),
+                RecipeAssets::Local { dir },
+            )
+        }
     };
 
-    let release_version = match release_version {
-        Some(release_version) if cache.index_path(&release_version).exists() => release_version,
-        _ => refresh_latest_official_recipe_index(&cache).await?,
-    };
-    let index_path = cache.index_path(&release_version);
-    let raw = std::fs::read_to_string(&index_path).with_context(|| {
-        format!(
-            "failed to read cached recipe index: {}",
-            index_path.display()
-        )
-    })?;
-    let index: OfficialRecipeIndex = serde_json::from_str(&raw).with_context(|| {
-        format!(
-            "failed to parse cached recipe index: {}",
-            index_path.display()
-        )
-    })?;
+    let raw = std::fs::read(&index_path)
+        .with_context(|| format!("failed to read recipe index: {}", index_path.display()))?;
+    let index: RecipeIndex = serde_json::from_slice(&raw)
+        .with_context(|| format!("failed to parse recipe index: {}", index_path.display()))?;
 
-    Ok(LoadedOfficialRecipeIndex {
+    let loaded = LoadedRecipeIndex {
+        source: source.clone(),
         release_version,
         index,
         cache,
-    })
+        assets,
+    };
+    loaded.verify_index_signature(&raw).await?;
+    Ok(loaded)
 }
 
-async fn refresh_latest_official_recipe_index(cache: &RecipeCache) -> Result<String> {
-    let latest_release_response = reqwest::get(OFFICIAL_RECIPE_LATEST_RELEASE_URL)
-        .await
-        .context("failed to resolve latest official recipe release")?
-        .error_for_status()
-        .context("latest official recipe release request failed")?;
-    let release_version = release_version_from_release_page_url(latest_release_response.url())?;
+fn cached_release_version(cache: &RecipeCache, refresh: bool) -> Result<Option<String>> {
+    if refresh {
+        return Ok(None);
+    }
+    Ok(cache
+        .latest_release_version()?
+        .filter(|release_version| cache.index_path(release_version).exists()))
+}
 
-    let response = reqwest::get(release_asset_url(&release_version, "index.json")?)
-        .await
-        .context("failed to fetch official recipe index")?
-        .error_for_status()
-        .context("official recipe index request failed")?;
-    let bytes = response
-        .bytes()
-        .await
-        .context("failed to read official recipe index response body")?;
-    cache.write_index(&release_version, bytes.as_ref())?;
+/// Unpacks a recipe tarball into the source cache, keyed by the archive digest. Local archives are
+/// re-read on every load so edits show up without `--refresh`.
+async fn load_recipe_tarball(cache: &RecipeCache, url: &str, refresh: bool) -> Result<String> {
+    let remote = is_http_url(url);
+    if remote
+        && !refresh
+        && let Some(release_version) = cache.latest_release_version()?
+        && cache.asset_dir(&release_version).is_dir()
+    {
+        return Ok(release_version);
+    }
+
+    let bytes = if remote {
+        fetch_bytes(url)
+            .await
+            .with_context(|| format!("failed to fetch recipe tarball {url}"))?
+    } else {
+        std::fs::read(url).with_context(|| format!("failed to read recipe tarball {url}"))?
+    };
+    let release_version = content_release_version(&bytes);
+    let asset_dir = cache.asset_dir(&release_version);
+    if !asset_dir.is_dir() {
+        let staging = asset_dir.with_extension("partial");
+        if staging.exists() {
+            std::fs::remove_dir_all(&staging)?;
+        }
+        std::fs::create_dir_all(&staging).with_context(|| {
+            format!(
+                "failed to create recipe tarball staging dir {}",
+                staging.display()
+            )
+        })?;
+        tar::Archive::new(flate2::read::GzDecoder::new(Cursor::new(&bytes)))
+            .unpack(&staging)
+            .with_context(|| format!("failed to unpack recipe tarball {url}"))?;
+        std::fs::rename(&staging, &asset_dir).with_context(|| {
+            format!(
+                "failed to move unpacked recipe tarball into {}",
+                asset_dir.display()
+            )
+        })?;
+    }
     cache.set_latest_release_version(release_version.clone())?;
     Ok(release_version)
 }
 
-async fn ensure_cached_asset(
-    cache: &RecipeCache,
-    release_version: &str,
-    asset_name: &str,
-) -> Result<PathBuf> {
-    let path = cache.asset_path(release_version, asset_name)?;
-    if path.exists()
-        && path
-            .metadata()
-            .map(|metadata| metadata.len() > 0)
-            .unwrap_or(false)
+/// Tarballs may hold `index.json` at the top or inside a single wrapping directory.
+fn recipe_tarball_root(dir: &Path) -> Result<PathBuf> {
+    if dir.join(RECIPE_INDEX_FILE).is_file() {
+        return Ok(dir.to_path_buf());
+    }
+    let entries = std::fs::read_dir(dir)
+        .with_context(|| format!("failed to read unpacked recipe tarball {}", dir.display()))?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .collect::<Vec<_>>();
+    if let [entry] = entries.as_slice()
+        && entry.path().join(RECIPE_INDEX_FILE).is_file()
     {
-        return Ok(path);
+        return Ok(entry.path());
     }
+    bail!("recipe tarball does not contain {RECIPE_INDEX_FILE}")
+}
 
-    let response = reqwest::get(release_asset_url(release_version, asset_name)?)
+fn content_release_version(bytes: &[u8]) -> String {
+    hex::encode(&Sha256::digest(bytes)[..8])
+}
+
+async fn fetch_bytes(url: &str) -> Result<Vec<u8>> {
+    let response = reqwest::get(url)
         .await
-        .with_context(|| format!("failed to fetch recipe asset `{asset_name}`"))?
+        .with_context(|| format!("request to {url} failed"))?
         .error_for_status()
-        .with_context(|| format!("recipe asset request failed for `{asset_name}`"))?;
+        .with_context(|| format!("request to {url} failed"))?;
     let bytes = response
         .bytes()
         .await
-        .with_context(|| format!("failed to read recipe asset `{asset_name}`"))?;
-    cache.write_asset(release_version, asset_name, bytes.as_ref())
+        .with_context(|| format!("failed to read response body from {url}"))?;
+    Ok(bytes.to_vec())
+}
+
+async fn refresh_latest_official_recipe_index(cache: &RecipeCache) -> Result<String> {
+    let latest_release_response = reqwest::get(OFFICIAL_RECIPE_LATEST_RELEASE_URL)
+        .await
+        .context("failed to resolve latest official recipe release")?
+        .error_for_status()
+        .context("latest official recipe release request failed")?;
+    let release_version = release_version_from_release_page_url(latest_release_response.url())?;
+
+    let bytes = fetch_bytes(&release_asset_url(&release_version, RECIPE_INDEX_FILE)?)
+        .await
+        .context("failed to fetch official recipe index")?;
+    cache.write_index(&release_version, &bytes)?;
+    cache.set_latest_release_version(release_version.clone())?;
+    Ok(release_version)
 }
 
 fn release_asset_url(release_version: &str, asset_name: &str) -> Result<String> {
@@ -315,7 +671,7 @@ fn parse_recipe_runtime(value: &str) -> Result<ServiceRecipeRuntime> {
     }
 }
 
-fn resolved_service_name(recipe: &OfficialRecipeRecord, service_name: Option<&str>) -> String {
+fn resolved_service_name(recipe: &RecipeRecord, service_name: Option<&str>) -> String {
     let value = service_name.unwrap_or_default().trim();
     if value.is_empty() {
         recipe.id.clone()
@@ -326,8 +682,62 @@ fn resolved_service_name(recipe: &OfficialRecipeRecord, service_name: Option<&st
 
 #[cfg(test)]
 mod tests {
+    use fungi_config::recipe_sources::RecipeSource;
+
     use super::*;
 
+    fn write_directory_source(dir: &Path) {
+        std::fs::write(
+            dir.join(RECIPE_INDEX_FILE),
+            r#"{
+  "recipes": [
+    {
+      "id": "wiki",
+      "name": "Team Wiki",
+      "description": "Internal wiki",
+      "runtime": "tcp",
+      "manifestAsset": "wiki.fungi.md",
+      "stability": "stable"
+    }
+  ]
+}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("wiki.fungi.md"),
+            r#"---
+fungi: service/v1
+id: wiki
+inputs:
+  port:
+    type: port
+    default: 3000
+publish:
+  main:
+    tcp:
+      host: 127.0.0.1
+      port: ${inputs.port}
+---
+"#,
+        )
+        .unwrap();
+    }
+
+    fn directory_sources(path: &Path) -> RecipeSources {
+        RecipeSources {
+            official: false,
+            official_trusted_keys: Vec::new(),
+            sources: vec![RecipeSource {
+                namespace: "team".to_string(),
+                priority: 0,
+                trusted_keys: Vec::new(),
+                location: RecipeSourceLocation::Directory {
+                    path: path.to_path_buf(),
+                },
+            }],
+        }
+    }
+
     #[test]
     fn maps_tcp_recipe_runtime_to_tcp() {
         assert_eq!(
@@ -348,4 +758,154 @@ mod tests {
             "v0.3.1"
         );
     }
+
+    #[test]
+    fn orders_sources_by_priority_with_official_first_on_ties() {
+        let mut sources = directory_sources(Path::new("/srv/recipes"));
+        sources.official = true;
+        sources.sources.push(RecipeSource {
+            namespace: "mirror".to_string(),
+            priority: -5,
+            trusted_keys: Vec::new(),
+            location: RecipeSourceLocation::Index {
+                url: "https://example.com/index.json".to_string(),
+            },
+        });
+
+        let namespaces = recipe_source_specs(&sources)
+            .into_iter()
+            .map(|spec| spec.namespace)
+            .collect::<Vec<_>>();
+        assert_eq!(namespaces, vec!["official", "team", "mirror"]);
+    }
+
+    #[tokio::test]
+    async fn lists_and_resolves_directory_recipes_offline() {
+        let fungi_dir = tempfile::TempDir::new().unwrap();
+        let recipes_dir = tempfile::TempDir::new().unwrap();
+        write_directory_source(recipes_dir.path());
+        let sources = directory_sources(recipes_dir.path());
+
+        let summaries = list_service_recipes(fungi_dir.path(), &sources, false)
+            .await
+            .unwrap();
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].id, "team/wiki");
+        assert_eq!(summaries[0].release_version, LOCAL_RECIPE_RELEASE);
+
+        let inputs = BTreeMap::from([("port".to_string(), "3100".to_string())]);
+        let resolved = resolve_service_recipe(
+            fungi_dir.path(),
+            &sources,
+            "wiki",
+            Some("docs"),
+            &inputs,
+            false,
+        )
+        .await
+        .unwrap();
+        assert_eq!(resolved.detail.summary.id, "team/wiki");
+        assert_eq!(resolved.manifest_base_dir, recipes_dir.path());
+        assert!(resolved.manifest_yaml.contains("port: 3100"));
+        assert!(resolved.manifest_yaml.contains("instance: docs"));
+        assert!(
+            resolved
+                .resolved_manifest_path
+                .starts_with(fungi_dir.path())
+        );
+
+        let error = get_service_recipe(fungi_dir.path(), &sources, "other/wiki", false)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("unknown recipe source `other`"));
+    }
+
+    #[tokio::test]
+    async fn unpacks_local_tarball_sources() {
+        let fungi_dir = tempfile::TempDir::new().unwrap();
+        let recipes_dir = tempfile::TempDir::new().unwrap();
+        write_directory_source(recipes_dir.path());
+
+        let tarball_path = fungi_dir.path().join("recipes.tar.gz");
+        let encoder = flate2::write::GzEncoder::new(
+            std::fs::File::create(&tarball_path).unwrap(),
+            flate2::Compression::default(),
+        );
+        let mut builder = tar::Builder::new(encoder);
+        builder
+            .append_dir_all("fungi-recipes", recipes_dir.path())
+            .unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+
+        let sources = RecipeSources {
+            official: false,
+            official_trusted_keys: Vec::new(),
+            sources: vec![RecipeSource {
+                namespace: "vendor".to_string(),
+                priority: 0,
+                trusted_keys: Vec::new(),
+                location: RecipeSourceLocation::Tarball {
+                    url: tarball_path.display().to_string(),
+                },
+            }],
+        };
+        let detail = get_service_recipe(fungi_dir.path(), &sources, "vendor/wiki", false)
+            .await
+            .unwrap();
+
+        assert_eq!(detail.summary.id, "vendor/wiki");
+        assert!(detail.cached_manifest_path.is_file());
+        assert!(detail.remote_manifest_url.ends_with("#wiki.fungi.md"));
+    }
+
+    #[tokio::test]
+    async fn requires_trusted_signature_and_matching_asset_digests() {
+        let fungi_dir = tempfile::TempDir::new().unwrap();
+        let recipes_dir = tempfile::TempDir::new().unwrap();
+        write_directory_source(recipes_dir.path());
+        let publisher = libp2p::identity::ed25519::Keypair::generate();
+        let mut sources = directory_sources(recipes_dir.path());
+        sources.sources[0].trusted_keys = vec![hex::encode(publisher.public().to_bytes())];
+
+        let error = list_service_recipes(fungi_dir.path(), &sources, false)
+            .await
+            .unwrap_err();
+        assert!(format!("{error:#}").contains("requires a signed index"));
+
+        let index_path = recipes_dir.path().join(RECIPE_INDEX_FILE);
+        let unpinned = std::fs::read(&index_path).unwrap();
+        std::fs::write(
+            recipes_dir.path().join(RECIPE_SIGNATURE_FILE),
+            hex::encode(publisher.sign(&unpinned)),
+        )
+        .unwrap();
+        let error = get_service_recipe(fungi_dir.path(), &sources, "team/wiki", false)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("has no sha256 digest"));
+
+        let manifest = std::fs::read(recipes_dir.path().join("wiki.fungi.md")).unwrap();
+        let index = std::fs::read_to_string(&index_path).unwrap().replace(
+            r#""manifestAsset": "wiki.fungi.md","#,
+            &format!(
+                r#""manifestAsset": "wiki.fungi.md", "manifestSha256": "{}","#,
+                hex::encode(Sha256::digest(&manifest))
+            ),
+        );
+        std::fs::write(&index_path, &index).unwrap();
+        std::fs::write(
+            recipes_dir.path().join(RECIPE_SIGNATURE_FILE),
+            hex::encode(publisher.sign(index.as_bytes())),
+        )
+        .unwrap();
+        get_service_recipe(fungi_dir.path(), &sources, "team/wiki", false)
+            .await
+            .unwrap();
+
+        std::fs::write(recipes_dir.path().join("wiki.fungi.md"), "tampered").unwrap();
+        let error = get_service_recipe(fungi_dir.path(), &sources, "team/wiki", false)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("failed integrity check"));
+    }
 }
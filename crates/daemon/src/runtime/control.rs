@@ -1,39 +1,56 @@
 use std::{
-    collections::{BTreeSet, HashMap},
+    collections::{BTreeSet, HashMap, HashSet},
     path::{Path, PathBuf},
     sync::Arc,
+    time::{Duration, Instant},
 };
 
 use anyhow::{Result, bail};
+use chrono::{DateTime, Local, SecondsFormat, Utc};
+use fungi_docker_agent::EngineInfo;
+use libp2p::PeerId;
 use parking_lot::Mutex;
 
 use crate::{
     controls::DockerControl,
+    secrets::{SecretStore, env_references_secrets},
     service_state::{DesiredServiceState, PersistedService, ServiceStateStore},
 };
 
 use super::{
+    exec::{ServiceExecRequest, ServiceExecSession},
     helpers::{
         enrich_instance_from_manifest, ensure_services_root_exists,
         is_missing_docker_container_error, missing_instance_from_manifest,
     },
     manifest::{
-        ManifestPathRoots, parse_service_manifest_yaml_with_policy,
-        service_expose_endpoint_bindings,
+        ManifestPathRoots, manifest_yaml_digest, parse_service_manifest_yaml_with_policy,
+        portable_service_manifest_yaml, service_expose_endpoint_bindings,
     },
     model::*,
     parse_service_manifest_yaml_with_policy_for_service_paths, peek_service_manifest_name,
-    providers::{DockerRuntimeProvider, RuntimeProvider, WasmtimeRuntimeProvider},
+    providers::{
+        DockerRuntimeProvider, ProcessRuntimeProvider, RuntimeProvider, WasmtimeRuntimeProvider,
+    },
+    schedule::CronSchedule,
 };
 
+/// How often a running job is checked for completion.
+const JOB_POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// Only the tail of a job run's output is kept in its run history.
+const MAX_JOB_RUN_LOG_BYTES: usize = 16 * 1024;
+
 #[derive(Clone)]
 pub struct RuntimeControl {
     docker: Option<DockerRuntimeProvider>,
     wasmtime: WasmtimeRuntimeProvider,
     wasmtime_enabled: bool,
+    process: Option<ProcessRuntimeProvider>,
     service_index: Arc<Mutex<HashMap<String, RuntimeKind>>>,
     service_manifests: Arc<Mutex<HashMap<String, ServiceManifest>>>,
     service_state: Arc<Mutex<ServiceStateStore>>,
+    running_jobs: Arc<Mutex<HashSet<String>>>,
+    local_peer_id: Option<PeerId>,
 }
 
 #[derive(Debug, Clone)]
@@ -41,6 +58,7 @@ pub struct AppliedService {
     pub instance: ServiceInstance,
     pub previous_manifest: Option<ServiceManifest>,
     pub desired_state: DesiredServiceState,
+    pub revision: ServiceRevision,
 }
 
 impl RuntimeControl {
@@ -54,19 +72,17 @@ impl RuntimeControl {
         wasmtime_enabled: bool,
     ) -> Result<Self> {
         ensure_services_root_exists(&fungi_home)?;
-        Ok(Self {
-            docker: docker.map(DockerRuntimeProvider::new),
-            wasmtime: WasmtimeRuntimeProvider::new(
+        Self::with_wasmtime_provider(
+            WasmtimeRuntimeProvider::new(
                 runtime_root,
                 launcher_path,
                 fungi_home,
                 allowed_host_paths,
             ),
+            docker,
+            service_state_file,
             wasmtime_enabled,
-            service_index: Arc::new(Mutex::new(HashMap::new())),
-            service_manifests: Arc::new(Mutex::new(HashMap::new())),
-            service_state: Arc::new(Mutex::new(ServiceStateStore::load(service_state_file)?)),
-        })
+        )
     }
 
     pub fn with_wasmtime_provider(
@@ -76,36 +92,130 @@ impl RuntimeControl {
         wasmtime_enabled: bool,
     ) -> Result<Self> {
         Ok(Self {
-            docker: docker.map(DockerRuntimeProvider::new),
+            docker: docker
+                .map(|docker| DockerRuntimeProvider::new(docker, wasmtime.secrets().clone())),
             wasmtime,
             wasmtime_enabled,
+            process: None,
             service_index: Arc::new(Mutex::new(HashMap::new())),
             service_manifests: Arc::new(Mutex::new(HashMap::new())),
             service_state: Arc::new(Mutex::new(ServiceStateStore::load(service_state_file)?)),
+            running_jobs: Arc::new(Mutex::new(HashSet::new())),
+            local_peer_id: None,
         })
     }
 
+    /// Enables the native process runtime; without it process services are refused.
+    pub fn with_process_provider(mut self, process: ProcessRuntimeProvider) -> Self {
+        self.process = Some(process);
+        self
+    }
+
+    /// Marks applies by any other peer as remote, so they get the remote Docker security rules.
+    pub fn with_local_peer_id(mut self, peer_id: PeerId) -> Self {
+        self.local_peer_id = Some(peer_id);
+        self
+    }
+
+    fn origin_of(&self, applied_by: Option<PeerId>) -> ServiceOrigin {
+        match applied_by {
+            Some(peer_id) if Some(peer_id) != self.local_peer_id => ServiceOrigin::Remote,
+            _ => ServiceOrigin::Local,
+        }
+    }
+
     pub fn supports(&self, runtime: RuntimeKind) -> bool {
         match runtime {
             RuntimeKind::Docker => self.docker.is_some(),
             RuntimeKind::Wasmtime => self.wasmtime_enabled,
+            RuntimeKind::Process => self.process.is_some(),
             RuntimeKind::External => true,
         }
     }
 
+    /// The container engine behind the Docker runtime, once it has been detected.
+    pub fn docker_engine(&self) -> Option<EngineInfo> {
+        self.docker.as_ref().and_then(DockerRuntimeProvider::engine)
+    }
+
     pub fn update_allowed_host_paths(&self, allowed_host_paths: Vec<PathBuf>) {
+        if let Some(process) = &self.process {
+            process.update_allowed_host_paths(allowed_host_paths.clone());
+        }
         self.wasmtime.update_allowed_host_paths(allowed_host_paths);
     }
 
     pub async fn pull(&self, manifest: &ServiceManifest) -> Result<ServiceInstance> {
         Ok(self
-            .apply_with_local_service_id(manifest, None)
+            .apply_with_local_service_id(manifest, None, None, None, None)
             .await?
             .instance)
     }
 
-    pub async fn apply(&self, manifest: &ServiceManifest) -> Result<AppliedService> {
-        self.apply_with_local_service_id(manifest, None).await
+    pub async fn apply(
+        &self,
+        manifest: &ServiceManifest,
+        applied_by: Option<PeerId>,
+    ) -> Result<AppliedService> {
+        self.apply_with_local_service_id(manifest, None, applied_by, None, None)
+            .await
+    }
+
+    pub fn secrets(&self) -> &SecretStore {
+        self.wasmtime.secrets()
+    }
+
+    /// Re-applies a recorded revision of `name`, by default the one before the current revision.
+    /// The rollback is recorded as a new revision, so it can be undone the same way.
+    pub async fn rollback(
+        &self,
+        name: &str,
+        revision: Option<u64>,
+        applied_by: Option<PeerId>,
+    ) -> Result<AppliedService> {
+        let (manifest, target, manifest_digest, local_service_id) = {
+            let state = self.service_state.lock();
+            let revisions = state.service_revisions(name)?;
+            let target = match revision {
+                Some(revision) => revision,
+                None => revisions
+                    .iter()
+                    .rev()
+                    .nth(1)
+                    .map(|revision| revision.revision)
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("service '{name}' has no earlier revision to roll back to")
+                    })?,
+            };
+            if revisions
+                .last()
+                .is_some_and(|latest| latest.revision == target)
+            {
+                bail!("revision {target} is already the current revision of service '{name}'");
+            }
+            let manifest_digest = revisions
+                .iter()
+                .find(|recorded| recorded.revision == target)
+                .and_then(|recorded| recorded.manifest_digest.clone());
+            (
+                state.revision_manifest(name, target)?,
+                target,
+                manifest_digest,
+                state.local_service_id(name)?,
+            )
+        };
+        self.apply_with_local_service_id(
+            &manifest,
+            Some(&local_service_id),
+            applied_by,
+            manifest_digest,
+            Some(target),
+        )
+        .await
+    }
+
+    pub fn service_revisions(&self, name: &str) -> Result<Vec<ServiceRevision>> {
+        self.service_state.lock().service_revisions(name)
     }
 
     #[cfg(test)]
@@ -122,8 +232,21 @@ impl RuntimeControl {
         &self,
         manifest: &ServiceManifest,
         local_service_id: Option<&str>,
+        applied_by: Option<PeerId>,
+        manifest_digest: Option<String>,
+        rollback_of: Option<u64>,
     ) -> Result<AppliedService> {
         self.ensure_runtime_enabled(manifest.runtime)?;
+        // A remote peer controls what runs from its manifest, so it could read back any secret
+        // the manifest names through logs, env dumps or exec.
+        if self.origin_of(applied_by) == ServiceOrigin::Remote
+            && env_references_secrets(&manifest.env)
+        {
+            bail!(
+                "service '{}' references local secrets; only this device can apply manifests that use ${{secret:...}}",
+                manifest.name
+            );
+        }
 
         let previous_service = { self.service_state.lock().persisted_service(&manifest.name) };
         let in_memory_manifest = self.service_manifests.lock().get(&manifest.name).cloned();
@@ -169,32 +292,106 @@ impl RuntimeControl {
             }
         };
 
+        let instance = match self
+            .replace_service_runtime(
+                manifest,
+                previous_runtime,
+                desired_state,
+                &resolved_local_service_id,
+                replacing_existing,
+                self.origin_of(applied_by),
+            )
+            .await
+        {
+            Ok(instance) => instance,
+            Err(error) => {
+                // Only an upgrade of a persisted service has a known-good manifest to go back to.
+                let Some(previous) = previous_service else {
+                    return Err(error);
+                };
+                log::warn!(
+                    "Applying service '{}' failed, restoring the previous manifest: {:#}",
+                    manifest.name,
+                    error
+                );
+                if let Err(restore_error) = self
+                    .replace_service_runtime(
+                        &previous.manifest,
+                        Some(manifest.runtime),
+                        desired_state,
+                        &resolved_local_service_id,
+                        true,
+                        // Restoring the node's own last known-good state.
+                        ServiceOrigin::Local,
+                    )
+                    .await
+                {
+                    bail!(
+                        "{error:#}; restoring the previous manifest also failed: {restore_error:#}"
+                    );
+                }
+                bail!("{error:#}; the previous manifest was restored");
+            }
+        };
+
+        let revision = self.service_state.lock().record_revision(
+            &manifest.name,
+            applied_by.map(|peer_id| peer_id.to_string()),
+            manifest_digest,
+            rollback_of,
+        )?;
+
+        Ok(AppliedService {
+            instance,
+            previous_manifest,
+            desired_state,
+            revision,
+        })
+    }
+
+    /// Swaps whatever runs under `manifest.name` for `manifest`, persists it, and starts it when
+    /// the service should be running. A start counts as failed unless the first status check
+    /// reports the service as running.
+    async fn replace_service_runtime(
+        &self,
+        manifest: &ServiceManifest,
+        previous_runtime: Option<RuntimeKind>,
+        desired_state: DesiredServiceState,
+        local_service_id: &str,
+        replacing_existing: bool,
+        origin: ServiceOrigin,
+    ) -> Result<ServiceInstance> {
         if let Some(previous_runtime) = previous_runtime {
             if desired_state == DesiredServiceState::Running {
                 self.stop_runtime_only(previous_runtime, &manifest.name)
                     .await?;
             }
-            self.remove_runtime_only(previous_runtime, &manifest.name, &resolved_local_service_id)
+            self.remove_runtime_only(previous_runtime, &manifest.name, local_service_id)
                 .await?;
         }
 
         let instance = match manifest.runtime {
             RuntimeKind::Docker => {
                 self.docker_provider()?
-                    .pull_with_container_name(manifest, &resolved_local_service_id)
+                    .pull_with_container_name(manifest, local_service_id, origin)
                     .await
             }
             RuntimeKind::Wasmtime => {
                 if replacing_existing {
                     self.wasmtime
-                        .replace_with_local_service_id(manifest, &resolved_local_service_id)
+                        .replace_with_local_service_id(manifest, local_service_id)
                         .await
                 } else {
                     self.wasmtime
-                        .pull_with_local_service_id(manifest, &resolved_local_service_id)
+                        .pull_with_local_service_id(manifest, local_service_id)
                         .await
                 }
             }
+            RuntimeKind::Process => {
+                self.process_provider()?
+                    .pull_with_local_service_id(manifest, local_service_id)
+                    .await
+            }
             RuntimeKind::External => Ok(self.external_instance_from_manifest(manifest, false)),
         }?;
 
@@ -204,19 +401,23 @@ impl RuntimeControl {
         self.service_manifests
             .lock()
             .insert(manifest.name.clone(), manifest.clone());
-        self.persist_service(manifest, desired_state, Some(&resolved_local_service_id))?;
+        self.persist_service(manifest, desired_state, Some(local_service_id))?;
 
         let mut instance = enrich_instance_from_manifest(instance, manifest);
-        if desired_state == DesiredServiceState::Running {
+        if desired_state == DesiredServiceState::Running && manifest.run_mode != ServiceRunMode::Job
+        {
             self.start(manifest.runtime, &manifest.name).await?;
             instance = self.inspect(manifest.runtime, &manifest.name).await?;
+            if !instance.status.is_running() {
+                bail!(
+                    "service '{}' is {} after start",
+                    manifest.name,
+                    instance.status.phase
+                );
+            }
         }
 
-        Ok(AppliedService {
-            instance,
-            previous_manifest,
-            desired_state,
-        })
+        Ok(instance)
     }
 
     pub async fn apply_manifest_yaml(
@@ -225,6 +426,7 @@ impl RuntimeControl {
         base_dir: &Path,
         fungi_home: &Path,
         policy: &ManifestResolutionPolicy,
+        applied_by: Option<PeerId>,
     ) -> Result<AppliedService> {
         let manifest_name = peek_service_manifest_name(content)?;
         let local_service_id = {
@@ -232,8 +434,50 @@ impl RuntimeControl {
                 .lock()
                 .preview_local_service_id(&manifest_name)?
         };
+        self.apply_manifest_yaml_with_local_service_id(
+            content,
+            base_dir,
+            fungi_home,
+            &local_service_id,
+            policy,
+            applied_by,
+        )
+        .await
+    }
+
+    /// Applies a service moved or cloned from another node. Its data must already be unpacked
+    /// under `local_service_id`, taken from [`RuntimeControl::unused_local_service_id`].
+    pub async fn import_manifest_yaml(
+        &self,
+        content: &str,
+        fungi_home: &Path,
+        local_service_id: &str,
+        policy: &ManifestResolutionPolicy,
+        applied_by: Option<PeerId>,
+    ) -> Result<AppliedService> {
+        self.apply_manifest_yaml_with_local_service_id(
+            content,
+            fungi_home,
+            fungi_home,
+            local_service_id,
+            policy,
+            applied_by,
+        )
+        .await
+    }
+
+    async fn apply_manifest_yaml_with_local_service_id(
+        &self,
+        content: &str,
+        base_dir: &Path,
+        fungi_home: &Path,
+        local_service_id: &str,
+        policy: &ManifestResolutionPolicy,
+        applied_by: Option<PeerId>,
+    ) -> Result<AppliedService> {
+        let manifest_name = peek_service_manifest_name(content)?;
         let used_host_ports = self.reserved_host_ports_except(&manifest_name);
-        let path_roots = ManifestPathRoots::for_local_service_id(fungi_home, &local_service_id);
+        let path_roots = ManifestPathRoots::for_local_service_id(fungi_home, local_service_id);
         let manifest = parse_service_manifest_yaml_with_policy_for_service_paths(
             content,
             base_dir,
@@ -241,8 +485,43 @@ impl RuntimeControl {
             policy,
             &used_host_ports,
         )?;
-        self.apply_with_local_service_id(&manifest, Some(&local_service_id))
-            .await
+        self.apply_with_local_service_id(
+            &manifest,
+            Some(local_service_id),
+            applied_by,
+            Some(manifest_yaml_digest(content)),
+            None,
+        )
+        .await
+    }
+
+    /// A fresh local service id for importing `name`, which must not exist on this node yet.
+    pub fn unused_local_service_id(&self, name: &str) -> Result<String> {
+        if self.service_index.lock().contains_key(name) {
+            bail!("service '{name}' already exists on this device");
+        }
+        let service_state = self.service_state.lock();
+        if service_state.persisted_service(name).is_some() {
+            bail!("service '{name}' already exists on this device");
+        }
+        service_state.preview_local_service_id(name)
+    }
+
+    pub fn local_service_id(&self, name: &str) -> Result<String> {
+        self.service_state.lock().local_service_id(name)
+    }
+
+    /// The manifest of `name` as YAML another node can apply, with paths under the service's
+    /// own directories and the user root written as `$fungi.*` placeholders.
+    pub fn portable_manifest_yaml(&self, name: &str, fungi_home: &Path) -> Result<String> {
+        let service = self
+            .service_state
+            .lock()
+            .persisted_service(name)
+            .ok_or_else(|| anyhow::anyhow!("service not found: {name}"))?;
+        let path_roots =
+            ManifestPathRoots::for_local_service_id(fungi_home, &service.local_service_id);
+        portable_service_manifest_yaml(&service.manifest, &path_roots)
     }
 
     pub async fn pull_manifest_yaml(
@@ -253,7 +532,7 @@ impl RuntimeControl {
         policy: &ManifestResolutionPolicy,
     ) -> Result<ServiceInstance> {
         Ok(self
-            .apply_manifest_yaml(content, base_dir, fungi_home, policy)
+            .apply_manifest_yaml(content, base_dir, fungi_home, policy, None)
             .await?
             .instance)
     }
@@ -275,7 +554,212 @@ impl RuntimeControl {
         )
     }
 
+    /// Starts a long-running service. For a job this only enables its schedule; runs are
+    /// triggered by [`RuntimeControl::run_job`].
     pub async fn start(&self, runtime: RuntimeKind, name: &str) -> Result<()> {
+        if !self.is_job(name) {
+            self.start_runtime_service(runtime, name).await?;
+        }
+        self.set_desired_state(name, DesiredServiceState::Running)
+    }
+
+    pub async fn stop(&self, runtime: RuntimeKind, name: &str) -> Result<()> {
+        self.stop_runtime_service(runtime, name).await?;
+        self.set_desired_state(name, DesiredServiceState::Stopped)
+    }
+
+    /// Starts an on-demand service for an incoming stream. The desired state is left at
+    /// running, so the service keeps being published while it is scaled to zero.
+    pub(crate) async fn start_on_demand(&self, name: &str) -> Result<()> {
+        let runtime = self.resolve_runtime(name)?;
+        self.start_runtime_service(runtime, name).await
+    }
+
+    /// Stops an idle on-demand service without changing its desired state.
+    pub(crate) async fn stop_idle(&self, name: &str) -> Result<()> {
+        let runtime = self.resolve_runtime(name)?;
+        self.stop_runtime_service(runtime, name).await
+    }
+
+    /// Runs the job `name` to completion and records the run in its history. Only one run of a
+    /// job happens at a time. A run that outlives the job's `timeout_secs` is stopped.
+    pub async fn run_job(&self, name: &str, trigger: ServiceJobTrigger) -> Result<ServiceJobRun> {
+        let manifest = self
+            .get_service_manifest(name)
+            .ok_or_else(|| anyhow::anyhow!("service not found: {name}"))?;
+        if manifest.run_mode != ServiceRunMode::Job {
+            bail!("service '{name}' is not a job");
+        }
+        let _running = RunningJob::claim(&self.running_jobs, name)?;
+        let runtime = manifest.runtime;
+
+        let log_mark = self.job_log_mark(runtime, name).await;
+        let run_number = {
+            let state = self.service_state.lock();
+            state.job_runs(name)?.last().map_or(1, |last| last.run + 1)
+        };
+        let mut run = ServiceJobRun {
+            run: run_number,
+            trigger,
+            started_at: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+            duration_ms: None,
+            exit_code: None,
+            outcome: ServiceJobOutcome::Running,
+            logs: String::new(),
+        };
+        self.service_state.lock().record_job_run(name, &run)?;
+
+        let started = Instant::now();
+        let result = self
+            .wait_for_job(runtime, name, manifest.job.timeout_secs)
+            .await;
+        run.duration_ms = Some(started.elapsed().as_millis() as u64);
+
+        run.logs = job_run_logs(&self.job_log_text(runtime, name, log_mark).await);
+        match result {
+            Ok(Some(exit_code)) => {
+                run.exit_code = exit_code;
+                run.outcome = if exit_code == Some(0) {
+                    ServiceJobOutcome::Succeeded
+                } else {
+                    ServiceJobOutcome::Failed
+                };
+            }
+            Ok(None) => run.outcome = ServiceJobOutcome::TimedOut,
+            Err(error) => {
+                run.outcome = ServiceJobOutcome::Failed;
+                run.logs
+                    .push_str(&format!("fungi: job run failed: {error:#}\n"));
+            }
+        }
+        self.service_state.lock().record_job_run(name, &run)?;
+        Ok(run)
+    }
+
+    /// Starts the job and waits for it to exit, returning its exit code, or `None` if it was
+    /// stopped for running past `timeout_secs`.
+    async fn wait_for_job(
+        &self,
+        runtime: RuntimeKind,
+        name: &str,
+        timeout_secs: Option<u64>,
+    ) -> Result<Option<Option<i32>>> {
+        self.start_runtime_service(runtime, name).await?;
+        let deadline = timeout_secs.map(|secs| Instant::now() + Duration::from_secs(secs));
+        loop {
+            tokio::time::sleep(JOB_POLL_INTERVAL).await;
+            let status = self.inspect(runtime, name).await?.status;
+            if !status.is_running() {
+                return Ok(Some(status.exit_code));
+            }
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                self.stop_runtime_service(runtime, name).await?;
+                return Ok(None);
+            }
+        }
+    }
+
+    /// Without a mark the whole log is attributed to the run, which beats losing its output.
+    async fn job_log_mark(&self, runtime: RuntimeKind, name: &str) -> Option<ServiceLogMark> {
+        let result = async {
+            self.ensure_runtime_enabled(runtime)?;
+            self.ensure_runtime_service(runtime, name).await?;
+            match runtime {
+                RuntimeKind::Docker => {
+                    self.docker_provider()?
+                        .log_mark(&self.docker_runtime_handle_or_name(name))
+                        .await
+                }
+                RuntimeKind::Wasmtime => self.wasmtime.log_mark(name).await,
+                RuntimeKind::Process => self.process_provider()?.log_mark(name).await,
+                RuntimeKind::External => bail!("external TCP services do not have runtime logs"),
+            }
+        }
+        .await;
+        match result {
+            Ok(mark) => Some(mark),
+            Err(error) => {
+                log::debug!("Failed to mark the log of job '{}': {}", name, error);
+                None
+            }
+        }
+    }
+
+    async fn job_log_text(
+        &self,
+        runtime: RuntimeKind,
+        name: &str,
+        since: Option<ServiceLogMark>,
+    ) -> String {
+        let options = ServiceLogsOptions { tail: None, since };
+        match self.logs(runtime, name, &options).await {
+            Ok(logs) => logs.text,
+            Err(error) => {
+                log::debug!("Failed to read logs of job '{}': {}", name, error);
+                String::new()
+            }
+        }
+    }
+
+    /// Schedule, enabled state and run history of `name`, or `None` if it is not a job.
+    pub fn job_status(&self, name: &str) -> Result<Option<ServiceJobStatus>> {
+        let manifest = self
+            .get_service_manifest(name)
+            .ok_or_else(|| anyhow::anyhow!("service not found: {name}"))?;
+        if manifest.run_mode != ServiceRunMode::Job {
+            return Ok(None);
+        }
+        let state = self.service_state.lock();
+        let enabled = state.desired_state(name) == Some(DesiredServiceState::Running);
+        let next_run_at = manifest
+            .job
+            .schedule
+            .as_deref()
+            .filter(|_| enabled)
+            .and_then(|schedule| CronSchedule::parse(schedule).ok())
+            .and_then(|schedule| schedule.next_after(Local::now()))
+            .map(|time| time.to_rfc3339_opts(SecondsFormat::Secs, false));
+        Ok(Some(ServiceJobStatus {
+            schedule: manifest.job.schedule.clone(),
+            enabled,
+            next_run_at,
+            runs: state.job_runs(name)?,
+        }))
+    }
+
+    /// Started jobs whose schedule fires in the minute of `at`.
+    pub(crate) fn due_scheduled_jobs(&self, at: &DateTime<Local>) -> Vec<String> {
+        self.service_state
+            .lock()
+            .persisted_services()
+            .into_iter()
+            .filter(|service| {
+                service.desired_state == DesiredServiceState::Running
+                    && service.manifest.run_mode == ServiceRunMode::Job
+            })
+            .filter_map(|service| {
+                let schedule = CronSchedule::parse(service.manifest.job.schedule.as_deref()?);
+                match schedule {
+                    Ok(schedule) => schedule.matches(at).then_some(service.manifest.name),
+                    Err(error) => {
+                        log::warn!(
+                            "Ignoring invalid schedule of job '{}': {}",
+                            service.manifest.name,
+                            error
+                        );
+                        None
+                    }
+                }
+            })
+            .collect()
+    }
+
+    fn is_job(&self, name: &str) -> bool {
+        self.get_service_manifest(name)
+            .is_some_and(|manifest| manifest.run_mode == ServiceRunMode::Job)
+    }
+
+    async fn start_runtime_service(&self, runtime: RuntimeKind, name: &str) -> Result<()> {
         self.ensure_runtime_enabled(runtime)?;
         self.ensure_runtime_service(runtime, name).await?;
         match runtime {
@@ -285,12 +769,12 @@ impl RuntimeControl {
                     .await
             }
             RuntimeKind::Wasmtime => self.wasmtime.start(name).await,
+            RuntimeKind::Process => self.process_provider()?.start(name).await,
             RuntimeKind::External => Ok(()),
-        }?;
-        self.set_desired_state(name, DesiredServiceState::Running)
+        }
     }
 
-    pub async fn stop(&self, runtime: RuntimeKind, name: &str) -> Result<()> {
+    async fn stop_runtime_service(&self, runtime: RuntimeKind, name: &str) -> Result<()> {
         let _ = self.ensure_runtime_service(runtime, name).await;
         let stop_result = match runtime {
             RuntimeKind::Docker => {
@@ -299,6 +783,7 @@ impl RuntimeControl {
                     .await
             }
             RuntimeKind::Wasmtime => self.wasmtime.stop(name).await,
+            RuntimeKind::Process => self.process_provider()?.stop(name).await,
             RuntimeKind::External => Ok(()),
         };
 
@@ -314,8 +799,7 @@ impl RuntimeControl {
             }
             Err(error) => return Err(error),
         }
-
-        self.set_desired_state(name, DesiredServiceState::Stopped)
+        Ok(())
     }
 
     pub async fn remove(&self, runtime: RuntimeKind, name: &str) -> Result<()> {
@@ -331,6 +815,12 @@ impl RuntimeControl {
                     .remove_with_local_service_id(name, &local_service_id)
                     .await
             }
+            RuntimeKind::Process => {
+                let local_service_id = self.service_state.lock().local_service_id(name)?;
+                self.process_provider()?
+                    .remove_with_local_service_id(name, &local_service_id)
+                    .await
+            }
             RuntimeKind::External => Ok(()),
         };
 
@@ -401,7 +891,7 @@ impl RuntimeControl {
             };
 
             let instance = match self.inspect(manifest.runtime, &manifest.name).await {
-                Ok(instance) => instance,
+                Ok(instance) => self.with_resources(instance).await,
                 Err(error) => {
                     log::warn!(
                         "Failed to inspect service '{}' for discovery: {}",
@@ -412,12 +902,16 @@ impl RuntimeControl {
                 }
             };
 
-            if !instance.status.is_running() {
+            let scaled_to_zero = manifest.on_demand.is_some()
+                && self.service_state.lock().desired_state(&manifest.name)
+                    == Some(DesiredServiceState::Running);
+            if !instance.status.is_running() && !scaled_to_zero {
                 continue;
             }
 
             services.push(DeviceService {
                 name: manifest.name.clone(),
+                definition_id: manifest.definition_id.clone(),
                 runtime: manifest.runtime,
                 metadata: crate::DeviceServiceMetadata {
                     usage: expose.usage,
@@ -431,6 +925,7 @@ impl RuntimeControl {
                     })
                     .collect(),
                 status: instance.status,
+                resources: instance.resources,
             });
         }
 
@@ -486,6 +981,7 @@ impl RuntimeControl {
                     .await
             }
             RuntimeKind::Wasmtime => self.wasmtime.inspect(name).await,
+            RuntimeKind::Process => self.process_provider()?.inspect(name).await,
             RuntimeKind::External => {
                 let manifest = self
                     .get_service_manifest(name)
@@ -516,11 +1012,56 @@ impl RuntimeControl {
             Err(error) => return Err(error),
         };
 
-        if let Some(manifest) = self.get_service_manifest(name) {
-            Ok(enrich_instance_from_manifest(instance, &manifest))
-        } else {
-            Ok(instance)
+        let Some(manifest) = self.get_service_manifest(name) else {
+            return Ok(instance);
+        };
+        let mut instance = enrich_instance_from_manifest(instance, &manifest);
+        instance.job = self.job_status(name)?;
+        Ok(instance)
+    }
+
+    /// Fills in [`ServiceInstance::resources`] for a running service. Sampling costs a stats
+    /// round trip per service, so only the API paths that show usage call this.
+    pub async fn with_resources(&self, mut instance: ServiceInstance) -> ServiceInstance {
+        if instance.status.is_running() {
+            instance.resources = self.resource_usage(instance.runtime, &instance.name).await;
+        }
+        instance
+    }
+
+    pub async fn list_services_with_resources(&self) -> Result<Vec<ServiceInstance>> {
+        let mut services = Vec::new();
+        for instance in self.list_services().await? {
+            services.push(self.with_resources(instance).await);
         }
+        Ok(services)
+    }
+
+    /// Stats are best effort: a failed sample leaves them out rather than failing the inspect.
+    async fn resource_usage(
+        &self,
+        runtime: RuntimeKind,
+        name: &str,
+    ) -> Option<ServiceResourceUsage> {
+        let result = async {
+            match runtime {
+                RuntimeKind::Docker => {
+                    self.docker_provider()?
+                        .resource_usage(&self.docker_runtime_handle_or_name(name))
+                        .await
+                }
+                RuntimeKind::Wasmtime => self.wasmtime.resource_usage(name).await,
+                RuntimeKind::Process => self.process_provider()?.resource_usage(name).await,
+                RuntimeKind::External => Ok(None),
+            }
+        }
+        .await;
+        result
+            .inspect_err(|error| {
+                log::debug!("Failed to sample resource usage of service '{name}': {error}")
+            })
+            .ok()
+            .flatten()
     }
 
     pub async fn logs(
@@ -538,10 +1079,31 @@ impl RuntimeControl {
                     .await
             }
             RuntimeKind::Wasmtime => self.wasmtime.logs(name, options).await,
+            RuntimeKind::Process => self.process_provider()?.logs(name, options).await,
             RuntimeKind::External => bail!("external TCP services do not have runtime logs"),
         }
     }
 
+    pub async fn exec(&self, request: &ServiceExecRequest) -> Result<ServiceExecSession> {
+        if request.command.is_empty() {
+            bail!("exec command must not be empty");
+        }
+        let name = request.service.as_str();
+        let runtime = self.resolve_runtime(name)?;
+        self.ensure_runtime_enabled(runtime)?;
+        self.ensure_runtime_service(runtime, name).await?;
+        match runtime {
+            RuntimeKind::Docker => {
+                self.docker_provider()?
+                    .exec(&self.docker_runtime_handle_or_name(name), request)
+                    .await
+            }
+            RuntimeKind::Wasmtime => self.wasmtime.exec(name, request).await,
+            RuntimeKind::Process => self.process_provider()?.exec(name, request).await,
+            RuntimeKind::External => bail!("external TCP services cannot run commands"),
+        }
+    }
+
     pub async fn restore_persisted_state(&self) -> Result<()> {
         let persisted_services = { self.service_state.lock().persisted_services() };
 
@@ -568,8 +1130,33 @@ impl RuntimeControl {
                     error
                 );
             }
+            if manifest.runtime == RuntimeKind::Process
+                && let Some(process) = &self.process
+                && let Err(error) = process.restore(&manifest, &local_service_id)
+            {
+                log::warn!(
+                    "Failed to restore persisted process service '{}': {}",
+                    manifest.name,
+                    error
+                );
+            }
 
+            if manifest.run_mode == ServiceRunMode::Job
+                && let Err(error) = self
+                    .service_state
+                    .lock()
+                    .interrupt_running_job_runs(&manifest.name)
+            {
+                log::warn!(
+                    "Failed to close unfinished runs of job '{}': {}",
+                    manifest.name,
+                    error
+                );
+            }
+
+            // On-demand services stay scaled to zero until their first incoming stream.
             if desired_state == DesiredServiceState::Running
+                && manifest.on_demand.is_none()
                 && let Err(error) = self.start(manifest.runtime, &manifest.name).await
             {
                 log::warn!(
@@ -600,6 +1187,12 @@ impl RuntimeControl {
             .ok_or_else(|| anyhow::anyhow!("docker runtime is not enabled in config"))
     }
 
+    fn process_provider(&self) -> Result<&ProcessRuntimeProvider> {
+        self.process
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("process runtime is not enabled on this node"))
+    }
+
     fn ensure_runtime_enabled(&self, runtime: RuntimeKind) -> Result<()> {
         match runtime {
             RuntimeKind::Docker => {
@@ -612,6 +1205,9 @@ impl RuntimeControl {
                     bail!("wasmtime runtime is disabled in config");
                 }
             }
+            RuntimeKind::Process => {
+                self.process_provider()?;
+            }
             RuntimeKind::External => {}
         }
         Ok(())
@@ -625,7 +1221,12 @@ impl RuntimeControl {
     }
 
     async fn ensure_runtime_service(&self, runtime: RuntimeKind, name: &str) -> Result<()> {
-        if runtime != RuntimeKind::Wasmtime || self.wasmtime.has_service(name) {
+        let restored = match runtime {
+            RuntimeKind::Wasmtime => self.wasmtime.has_service(name),
+            RuntimeKind::Process => self.process_provider()?.has_service(name),
+            RuntimeKind::Docker | RuntimeKind::External => true,
+        };
+        if restored {
             return Ok(());
         }
 
@@ -636,6 +1237,11 @@ impl RuntimeControl {
             .cloned()
             .ok_or_else(|| anyhow::anyhow!("service not found: {name}"))?;
         let local_service_id = self.service_state.lock().local_service_id(name)?;
+        if runtime == RuntimeKind::Process {
+            return self
+                .process_provider()?
+                .restore(&manifest, &local_service_id);
+        }
         self.wasmtime.restore(&manifest, &local_service_id).await
     }
 
@@ -686,6 +1292,7 @@ impl RuntimeControl {
                     .await
             }
             RuntimeKind::Wasmtime => self.wasmtime.stop(name).await,
+            RuntimeKind::Process => self.process_provider()?.stop(name).await,
             RuntimeKind::External => Ok(()),
         };
 
@@ -711,6 +1318,17 @@ impl RuntimeControl {
                 );
                 Ok(())
             }
+            Err(error)
+                if runtime == RuntimeKind::Process
+                    && error.to_string().contains("process service not found") =>
+            {
+                log::warn!(
+                    "Process service '{}' was not running during apply stop: {}",
+                    name,
+                    error
+                );
+                Ok(())
+            }
             Err(error) => Err(error),
         }
     }
@@ -728,6 +1346,11 @@ impl RuntimeControl {
                     .remove_with_local_service_id(name, local_service_id)
                     .await
             }
+            RuntimeKind::Process => {
+                self.process_provider()?
+                    .remove_with_local_service_id(name, local_service_id)
+                    .await
+            }
             RuntimeKind::External => Ok(()),
         };
 
@@ -768,8 +1391,43 @@ impl RuntimeControl {
             } else {
                 ServiceStatus::stopped()
             },
+            job: None,
+            resources: None,
+        }
+    }
+}
+
+/// Marks a job as running for as long as the value lives.
+struct RunningJob {
+    running_jobs: Arc<Mutex<HashSet<String>>>,
+    name: String,
+}
+
+impl RunningJob {
+    fn claim(running_jobs: &Arc<Mutex<HashSet<String>>>, name: &str) -> Result<Self> {
+        if !running_jobs.lock().insert(name.to_string()) {
+            bail!("job '{name}' is already running");
         }
+        Ok(Self {
+            running_jobs: running_jobs.clone(),
+            name: name.to_string(),
+        })
+    }
+}
+
+impl Drop for RunningJob {
+    fn drop(&mut self) {
+        self.running_jobs.lock().remove(&self.name);
+    }
+}
+
+/// The output of a run, truncated to its last [`MAX_JOB_RUN_LOG_BYTES`].
+fn job_run_logs(output: &str) -> String {
+    let mut start = output.len().saturating_sub(MAX_JOB_RUN_LOG_BYTES);
+    while !output.is_char_boundary(start) {
+        start += 1;
     }
+    output[start..].to_string()
 }
 
 fn ensure_definition_id_compatible(
@@ -0,0 +1,282 @@
+//! Interactive commands run inside a service, carried as channels so the same session can be
+//! served to a local client or relayed to a peer.
+
+use std::time::Duration;
+
+use fungi_docker_agent::{ExecOutput, ExecOutputDecoder, ExecSession};
+use serde::{Deserialize, Serialize};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWriteExt},
+    process::Child,
+    sync::mpsc,
+};
+
+use crate::controls::DockerControl;
+
+const EXEC_CHANNEL_CAPACITY: usize = 64;
+const EXEC_READ_CHUNK: usize = 16 * 1024;
+/// How long to wait for the engine to record the exit code after the output has ended.
+const DOCKER_EXIT_CODE_ATTEMPTS: usize = 20;
+const DOCKER_EXIT_CODE_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServiceExecRequest {
+    pub service: String,
+    pub command: Vec<String>,
+    /// Allocate a terminal. Only Docker services can; other runtimes run the command on pipes.
+    #[serde(default)]
+    pub tty: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<ServiceExecSize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServiceExecSize {
+    pub rows: u16,
+    pub cols: u16,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ServiceExecInput {
+    Stdin(Vec<u8>),
+    CloseStdin,
+    Resize(ServiceExecSize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ServiceExecOutput {
+    Stdout(Vec<u8>),
+    Stderr(Vec<u8>),
+    /// Always the last message of a session.
+    Exit(ServiceExecExit),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServiceExecExit {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+    /// Why the session ended without the command finishing, e.g. a lost peer connection.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ServiceExecExit {
+    pub fn failed(error: impl ToString) -> Self {
+        Self {
+            exit_code: None,
+            error: Some(error.to_string()),
+        }
+    }
+}
+
+/// The client side of a running exec session. Dropping both halves ends the session.
+pub struct ServiceExecSession {
+    pub input: mpsc::Sender<ServiceExecInput>,
+    pub output: mpsc::Receiver<ServiceExecOutput>,
+}
+
+/// The serving side of a session, handed to whatever runs or relays the command.
+pub(crate) struct ServiceExecBackend {
+    pub(crate) input: mpsc::Receiver<ServiceExecInput>,
+    pub(crate) output: mpsc::Sender<ServiceExecOutput>,
+}
+
+pub(crate) fn exec_session_channels() -> (ServiceExecSession, ServiceExecBackend) {
+    let (input_tx, input_rx) = mpsc::channel(EXEC_CHANNEL_CAPACITY);
+    let (output_tx, output_rx) = mpsc::channel(EXEC_CHANNEL_CAPACITY);
+    (
+        ServiceExecSession {
+            input: input_tx,
+            output: output_rx,
+        },
+        ServiceExecBackend {
+            input: input_rx,
+            output: output_tx,
+        },
+    )
+}
+
+pub(crate) fn attach_docker_exec(
+    docker: DockerControl,
+    session: ExecSession,
+) -> ServiceExecSession {
+    let (client, backend) = exec_session_channels();
+    let ServiceExecBackend { mut input, output } = backend;
+    let (mut reader, mut writer) = tokio::io::split(session.stream);
+
+    let exec_id = session.id.clone();
+    let resize_docker = docker.clone();
+    tokio::spawn(async move {
+        while let Some(message) = input.recv().await {
+            match message {
+                ServiceExecInput::Stdin(data) => {
+                    if writer.write_all(&data).await.is_err() {
+                        break;
+                    }
+                }
+                ServiceExecInput::CloseStdin => break,
+                ServiceExecInput::Resize(size) => {
+                    if let Err(error) = resize_docker
+                        .resize_exec(&exec_id, size.rows, size.cols)
+                        .await
+                    {
+                        log::debug!("Failed to resize exec {exec_id}: {error}");
+                    }
+                }
+            }
+        }
+        let _ = writer.shutdown().await;
+    });
+
+    tokio::spawn(async move {
+        let mut decoder = ExecOutputDecoder::new(session.tty);
+        let mut buffer = vec![0u8; EXEC_READ_CHUNK];
+        let read_error = loop {
+            match reader.read(&mut buffer).await {
+                Ok(0) => break None,
+                Ok(size) => {
+                    for chunk in decoder.push(&buffer[..size]) {
+                        if output.send(docker_exec_output(chunk)).await.is_err() {
+                            return;
+                        }
+                    }
+                }
+                Err(error) => break Some(error),
+            }
+        };
+        if let Some(chunk) = decoder.finish() {
+            let _ = output.send(docker_exec_output(chunk)).await;
+        }
+
+        let exit = match read_error {
+            Some(error) => ServiceExecExit::failed(format!("exec stream failed: {error}")),
+            None => docker_exec_exit(&docker, &session.id).await,
+        };
+        let _ = output.send(ServiceExecOutput::Exit(exit)).await;
+    });
+
+    client
+}
+
+fn docker_exec_output(output: ExecOutput) -> ServiceExecOutput {
+    match output {
+        ExecOutput::Stdout(data) => ServiceExecOutput::Stdout(data),
+        ExecOutput::Stderr(data) => ServiceExecOutput::Stderr(data),
+    }
+}
+
+async fn docker_exec_exit(docker: &DockerControl, exec_id: &str) -> ServiceExecExit {
+    for _ in 0..DOCKER_EXIT_CODE_ATTEMPTS {
+        match docker.exec_exit_code(exec_id).await {
+            Ok(Some(exit_code)) => {
+                return ServiceExecExit {
+                    exit_code: Some(exit_code),
+                    error: None,
+                };
+            }
+            Ok(None) => tokio::time::sleep(DOCKER_EXIT_CODE_POLL_INTERVAL).await,
+            Err(error) => return ServiceExecExit::failed(error),
+        }
+    }
+    ServiceExecExit::failed("exec output ended but the command is still running")
+}
+
+/// Serves a session from a spawned child with piped stdio. The child is killed when the client
+/// goes away before it exits.
+pub(crate) fn attach_child_exec(mut child: Child) -> ServiceExecSession {
+    let (client, backend) = exec_session_channels();
+    let ServiceExecBackend { mut input, output } = backend;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        tokio::spawn(async move {
+            while let Some(message) = input.recv().await {
+                match message {
+                    ServiceExecInput::Stdin(data) => {
+                        if stdin.write_all(&data).await.is_err() {
+                            break;
+                        }
+                    }
+                    ServiceExecInput::CloseStdin => break,
+                    // Commands outside Docker have no terminal to resize.
+                    ServiceExecInput::Resize(_) => {}
+                }
+            }
+        });
+    }
+
+    let stdout = child
+        .stdout
+        .take()
+        .map(|stdout| tokio::spawn(forward_output(stdout, output.clone(), false)));
+    let stderr = child
+        .stderr
+        .take()
+        .map(|stderr| tokio::spawn(forward_output(stderr, output.clone(), true)));
+
+    tokio::spawn(async move {
+        let status = tokio::select! {
+            status = child.wait() => status,
+            _ = output.closed() => {
+                let _ = child.kill().await;
+                return;
+            }
+        };
+        // Let the readers drain what the command wrote before it exited.
+        for reader in [stdout, stderr].into_iter().flatten() {
+            let _ = reader.await;
+        }
+        let exit = match status {
+            Ok(status) => ServiceExecExit {
+                exit_code: status.code(),
+                error: None,
+            },
+            Err(error) => ServiceExecExit::failed(format!("failed to wait for command: {error}")),
+        };
+        let _ = output.send(ServiceExecOutput::Exit(exit)).await;
+    });
+
+    client
+}
+
+async fn forward_output(
+    mut reader: impl AsyncRead + Unpin,
+    output: mpsc::Sender<ServiceExecOutput>,
+    stderr: bool,
+) {
+    let mut buffer = vec![0u8; EXEC_READ_CHUNK];
+    loop {
+        let size = match reader.read(&mut buffer).await {
+            Ok(0) | Err(_) => return,
+            Ok(size) => size,
+        };
+        let data = buffer[..size].to_vec();
+        let message = if stderr {
+            ServiceExecOutput::Stderr(data)
+        } else {
+            ServiceExecOutput::Stdout(data)
+        };
+        if output.send(message).await.is_err() {
+            return;
+        }
+    }
+}
+
+/// Drains a session into memory, for callers that only want the final result.
+pub(crate) async fn collect_exec_output(
+    mut session: ServiceExecSession,
+) -> (Vec<u8>, Vec<u8>, ServiceExecExit) {
+    let mut stdout = Vec::new();
+    let mut stderr = Vec::new();
+    while let Some(message) = session.output.recv().await {
+        match message {
+            ServiceExecOutput::Stdout(data) => stdout.extend(data),
+            ServiceExecOutput::Stderr(data) => stderr.extend(data),
+            ServiceExecOutput::Exit(exit) => return (stdout, stderr, exit),
+        }
+    }
+    (
+        stdout,
+        stderr,
+        ServiceExecExit::failed("session ended without an exit status"),
+    )
+}
@@ -4,17 +4,25 @@ use std::{
 };
 
 use anyhow::{Context, Result, bail};
-use fungi_config::paths::FungiPaths;
-use fungi_docker_agent::{ContainerSpec, DockerAgentError, PortProtocol};
-use tokio::process::Command;
+use fungi_config::{paths::FungiPaths, runtime::ProcessSandboxMode};
+use fungi_docker_agent::{ContainerSecurity, ContainerSpec, DockerAgentError, PortProtocol};
+use tokio::process::{Child, Command};
+
+use crate::{
+    integrity::{normalize_sha256_digest, verify_sha256},
+    secrets::SecretStore,
+};
 
 use super::{
-    manifest::service_expose_endpoint_bindings, model::*, providers::WasmtimeServiceState,
+    manifest::service_expose_endpoint_bindings,
+    model::*,
+    providers::{ProcessServiceState, WasmtimeServiceState},
 };
 
 pub(crate) fn docker_spec_from_manifest_with_name(
     manifest: &ServiceManifest,
     container_name: &str,
+    secrets: &SecretStore,
 ) -> Result<ContainerSpec> {
     if manifest.runtime != RuntimeKind::Docker {
         bail!("service manifest runtime does not match docker provider")
@@ -27,13 +35,14 @@ pub(crate) fn docker_spec_from_manifest_with_name(
     Ok(ContainerSpec {
         name: Some(container_name.to_string()),
         image: image.clone(),
-        env: manifest.env.clone(),
+        env: secrets.resolve_env(&manifest.env)?,
         mounts: manifest
             .mounts
             .iter()
             .map(|mount| fungi_docker_agent::BindMount {
                 host_path: mount.host_path.clone(),
                 container_path: mount.runtime_path.clone(),
+                read_only: mount.read_only,
             })
             .collect(),
         ports: manifest
@@ -52,34 +61,91 @@ pub(crate) fn docker_spec_from_manifest_with_name(
         entrypoint: manifest.entrypoint.clone(),
         working_dir: manifest.working_dir.clone(),
         labels: manifest.labels.clone(),
+        volumes: manifest
+            .container
+            .volumes
+            .iter()
+            .map(|volume| fungi_docker_agent::VolumeMount {
+                name: volume.name.clone(),
+                container_path: volume.runtime_path.clone(),
+                read_only: volume.read_only,
+            })
+            .collect(),
+        tmpfs: manifest
+            .container
+            .tmpfs
+            .iter()
+            .map(|tmpfs| fungi_docker_agent::TmpfsMount {
+                container_path: tmpfs.runtime_path.clone(),
+                size_bytes: tmpfs.size_bytes,
+            })
+            .collect(),
+        security: docker_security_from_manifest(&manifest.container.security),
     })
 }
 
+fn docker_security_from_manifest(security: &ServiceSecurity) -> ContainerSecurity {
+    ContainerSecurity {
+        user: security.user.clone(),
+        read_only_rootfs: security.read_only_rootfs,
+        cap_add: security.cap_add.clone(),
+        cap_drop: security.cap_drop.clone(),
+        privileged: security.privileged,
+        host_network: security.host_network,
+        seccomp_profile: security.seccomp_profile.clone(),
+        apparmor_profile: security.apparmor_profile.clone(),
+    }
+}
+
 fn ensure_wasmtime_manifest(manifest: &ServiceManifest) -> Result<()> {
     if manifest.runtime != RuntimeKind::Wasmtime {
         bail!("service manifest runtime does not match wasmtime provider")
     }
 
     match &manifest.source {
-        ServiceSource::WasmtimeFile { component } => {
+        ServiceSource::WasmtimeFile { component, sha256 } => {
             if component.as_os_str().is_empty() {
                 bail!("wasmtime component path must not be empty");
             }
+            if let Some(sha256) = sha256 {
+                normalize_sha256_digest(sha256)?;
+            }
             Ok(())
         }
-        ServiceSource::WasmtimeUrl { url } => {
+        ServiceSource::WasmtimeUrl { url, sha256 } => {
             if url.trim().is_empty() {
                 bail!("wasmtime source url must not be empty");
             }
+            if let Some(sha256) = sha256 {
+                normalize_sha256_digest(sha256)?;
+            }
             Ok(())
         }
-        ServiceSource::Docker { .. } => bail!("wasmtime runtime requires a wasm component source"),
-        ServiceSource::ExistingTcp { .. } => {
+        ServiceSource::Docker { .. }
+        | ServiceSource::Process { .. }
+        | ServiceSource::ExistingTcp { .. } => {
             bail!("wasmtime runtime requires a wasm component source")
         }
     }
 }
 
+fn ensure_process_manifest(manifest: &ServiceManifest) -> Result<()> {
+    if manifest.runtime != RuntimeKind::Process {
+        bail!("service manifest runtime does not match process provider")
+    }
+
+    let ServiceSource::Process { binary, sha256 } = &manifest.source else {
+        bail!("process runtime requires a binary source")
+    };
+    if !binary.is_absolute() {
+        bail!("process binary path must be absolute: {}", binary.display());
+    }
+    if let Some(sha256) = sha256 {
+        normalize_sha256_digest(sha256)?;
+    }
+    Ok(())
+}
+
 pub(crate) fn ensure_manifest_mount_dirs(manifest: &ServiceManifest) -> Result<()> {
     for mount in &manifest.mounts {
         fs::create_dir_all(&mount.host_path).with_context(|| {
@@ -142,37 +208,47 @@ fn validate_allowed_host_paths(
         .collect::<Result<Vec<_>>>()?;
 
     for mount in &manifest.mounts {
-        let host_path = normalize_absolute_path(&mount.host_path)?;
-        let allowed = normalized_roots
-            .iter()
-            .any(|allowed_root| host_path.starts_with(allowed_root));
-        if !allowed {
-            bail!(
-                "wasmtime host path is outside allowed roots: {}",
-                mount.host_path.display()
-            );
-        }
+        ensure_host_path_allowed(manifest.runtime, &mount.host_path, &normalized_roots)?;
+    }
+    if let ServiceSource::Process { binary, .. } = &manifest.source {
+        ensure_host_path_allowed(manifest.runtime, binary, &normalized_roots)?;
     }
 
     Ok(())
 }
 
+fn ensure_host_path_allowed(
+    runtime: RuntimeKind,
+    path: &Path,
+    normalized_roots: &[PathBuf],
+) -> Result<()> {
+    let host_path = normalize_absolute_path(path)?;
+    let allowed = normalized_roots
+        .iter()
+        .any(|allowed_root| host_path.starts_with(allowed_root));
+    if !allowed {
+        bail!(
+            "{} host path is outside allowed roots: {}",
+            runtime_name(runtime),
+            path.display()
+        );
+    }
+    Ok(())
+}
+
 async fn stage_wasmtime_component(
     manifest: &ServiceManifest,
     service_dir: &Path,
 ) -> Result<PathBuf> {
     let target_path = service_dir.join("component.wasm");
-    match &manifest.source {
-        ServiceSource::WasmtimeFile { component } => {
-            fs::copy(component, &target_path).with_context(|| {
-                format!(
-                    "Failed to copy WASI component from {} to {}",
-                    component.display(),
-                    target_path.display()
-                )
+    let (bytes, sha256) = match &manifest.source {
+        ServiceSource::WasmtimeFile { component, sha256 } => {
+            let bytes = fs::read(component).with_context(|| {
+                format!("Failed to read WASI component from {}", component.display())
             })?;
+            (bytes, sha256)
         }
-        ServiceSource::WasmtimeUrl { url } => {
+        ServiceSource::WasmtimeUrl { url, sha256 } => {
             let response = reqwest::get(url)
                 .await
                 .with_context(|| format!("Failed to download WASI component from {url}"))?
@@ -182,20 +258,49 @@ async fn stage_wasmtime_component(
                 .bytes()
                 .await
                 .with_context(|| format!("Failed to read WASI download body from {url}"))?;
-            fs::write(&target_path, &bytes).with_context(|| {
-                format!(
-                    "Failed to write staged WASI component: {}",
-                    target_path.display()
-                )
-            })?;
+            (bytes.to_vec(), sha256)
         }
-        ServiceSource::Docker { .. } | ServiceSource::ExistingTcp { .. } => {
+        ServiceSource::Docker { .. }
+        | ServiceSource::Process { .. }
+        | ServiceSource::ExistingTcp { .. } => {
             bail!("invalid wasmtime source type")
         }
+    };
+    // Verify before writing so a mismatched download never replaces a good staged component.
+    if let Some(sha256) = sha256 {
+        verify_sha256(
+            &bytes,
+            sha256,
+            &format!("WASI component for service '{}'", manifest.name),
+        )?;
     }
+    fs::write(&target_path, &bytes).with_context(|| {
+        format!(
+            "Failed to write staged WASI component: {}",
+            target_path.display()
+        )
+    })?;
     Ok(target_path)
 }
 
+/// A previously staged component is reused only if it still matches the manifest's pinned digest.
+fn staged_component_matches(manifest: &ServiceManifest, staged_component_path: &Path) -> bool {
+    let (ServiceSource::WasmtimeFile {
+        sha256: Some(sha256),
+        ..
+    }
+    | ServiceSource::WasmtimeUrl {
+        sha256: Some(sha256),
+        ..
+    }) = &manifest.source
+    else {
+        return true;
+    };
+    fs::read(staged_component_path)
+        .map(|bytes| verify_sha256(&bytes, sha256, "staged WASI component").is_ok())
+        .unwrap_or(false)
+}
+
 pub(crate) async fn build_wasmtime_state(
     runtime_root: &Path,
     service_artifacts_dir: &Path,
@@ -215,7 +320,10 @@ pub(crate) async fn build_wasmtime_state(
     ensure_manifest_mount_dirs(manifest)?;
 
     let staged_component_path = service_artifacts_dir.join("component.wasm");
-    let staged_component_path = if restage_component || !staged_component_path.exists() {
+    let staged_component_path = if restage_component
+        || !staged_component_path.exists()
+        || !staged_component_matches(manifest, &staged_component_path)
+    {
         stage_wasmtime_component(manifest, service_artifacts_dir).await?
     } else {
         staged_component_path
@@ -250,6 +358,7 @@ pub(crate) fn build_wasmtime_command(
     launcher_path: &Path,
     fungi_home: &Path,
     state: &WasmtimeServiceState,
+    secrets: &SecretStore,
 ) -> Result<Command> {
     let mut command = Command::new(launcher_path);
     command.kill_on_drop(true);
@@ -306,10 +415,201 @@ pub(crate) fn build_wasmtime_command(
     } else {
         command.current_dir(&state.service_dir);
     }
-    command.envs(&state.manifest.env);
+    command.envs(secrets.resolve_env(&state.manifest.env)?);
     Ok(command)
 }
 
+/// Process services run the host binary in place; only its digest is checked, on every start.
+pub(crate) fn build_process_state(
+    runtime_root: &Path,
+    service_artifacts_dir: &Path,
+    service_appdata_dir: &Path,
+    allowed_host_paths: &[PathBuf],
+    manifest: &ServiceManifest,
+) -> Result<ProcessServiceState> {
+    ensure_process_manifest(manifest)?;
+    validate_allowed_host_paths(manifest, allowed_host_paths)?;
+    let ServiceSource::Process { binary, .. } = &manifest.source else {
+        bail!("process runtime requires a binary source")
+    };
+
+    fs::create_dir_all(service_artifacts_dir).with_context(|| {
+        format!(
+            "Failed to create service artifacts directory: {}",
+            service_artifacts_dir.display()
+        )
+    })?;
+    ensure_manifest_mount_dirs(manifest)?;
+
+    let runtime_dir = runtime_root.join("process").join(&manifest.name);
+    let tmp_dir = runtime_dir.join("tmp");
+    fs::create_dir_all(&tmp_dir).with_context(|| {
+        format!(
+            "Failed to create runtime directory: {}",
+            runtime_dir.display()
+        )
+    })?;
+    let log_file_path = runtime_dir.join("runtime.log");
+    if !log_file_path.exists() {
+        fs::File::create(&log_file_path)
+            .with_context(|| format!("Failed to create log file: {}", log_file_path.display()))?;
+    }
+
+    Ok(ProcessServiceState {
+        manifest: manifest.clone(),
+        binary: normalize_absolute_path(binary)?,
+        service_dir: service_artifacts_dir.to_path_buf(),
+        appdata_dir: service_appdata_dir.to_path_buf(),
+        runtime_dir,
+        tmp_dir,
+        log_file_path,
+        child: None,
+        last_exit_code: None,
+        sandbox_degradation: None,
+    })
+}
+
+pub(crate) fn build_process_command(
+    state: &ProcessServiceState,
+    secrets: &SecretStore,
+) -> Result<Command> {
+    if let ServiceSource::Process {
+        sha256: Some(sha256),
+        ..
+    } = &state.manifest.source
+    {
+        let bytes = fs::read(&state.binary)
+            .with_context(|| format!("Failed to read process binary {}", state.binary.display()))?;
+        verify_sha256(
+            &bytes,
+            sha256,
+            &format!("binary for service '{}'", state.manifest.name),
+        )?;
+    }
+
+    let mut command = Command::new(&state.binary);
+    command.kill_on_drop(true);
+    command.args(&state.manifest.command);
+    command.current_dir(
+        state
+            .manifest
+            .working_dir
+            .as_deref()
+            .map(Path::new)
+            .unwrap_or(&state.service_dir),
+    );
+    command.env("TMPDIR", &state.tmp_dir);
+    command.envs(secrets.resolve_env(&state.manifest.env)?);
+    #[cfg(unix)]
+    command.process_group(0);
+    Ok(command)
+}
+
+/// A command exec'd into a process service: it sees the service's working directory,
+/// environment and temp dir, and is confined like the service when the caller applies the sandbox.
+pub(crate) fn build_process_exec_command(
+    state: &ProcessServiceState,
+    secrets: &SecretStore,
+    argv: &[String],
+) -> Result<Command> {
+    let mut command = exec_command(argv)?;
+    command.current_dir(
+        state
+            .manifest
+            .working_dir
+            .as_deref()
+            .map(Path::new)
+            .unwrap_or(&state.service_dir),
+    );
+    command.env("TMPDIR", &state.tmp_dir);
+    command.envs(secrets.resolve_env(&state.manifest.env)?);
+    Ok(command)
+}
+
+fn exec_command(argv: &[String]) -> Result<Command> {
+    let Some((program, args)) = argv.split_first() else {
+        bail!("exec command must not be empty")
+    };
+    let mut command = Command::new(program);
+    command
+        .args(args)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .kill_on_drop(true);
+    Ok(command)
+}
+
+/// Installs the sandbox into `command` and notes the enforced layers in the service log. Returns
+/// why the sandbox is weaker than intended, if it is.
+#[cfg(target_os = "linux")]
+pub(crate) fn confine_process_command(
+    state: &ProcessServiceState,
+    mode: ProcessSandboxMode,
+    log_file: &mut fs::File,
+    command: &mut Command,
+) -> Result<Option<String>> {
+    use std::io::Write;
+
+    let sandbox = super::sandbox::PreparedSandbox::prepare(&process_sandbox_spec(state), mode)?;
+    writeln!(log_file, "{}", sandbox.summary()).with_context(|| {
+        format!(
+            "Failed to write process log: {}",
+            state.log_file_path.display()
+        )
+    })?;
+    let degradation = sandbox.degradation();
+    sandbox.apply_to(command);
+    Ok(degradation)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn confine_process_command(
+    _state: &ProcessServiceState,
+    _mode: ProcessSandboxMode,
+    _log_file: &mut fs::File,
+    _command: &mut Command,
+) -> Result<Option<String>> {
+    bail!("process runtime is only supported on Linux")
+}
+
+/// The paths and ports a process service is confined to, on top of the system paths.
+#[cfg(target_os = "linux")]
+fn process_sandbox_spec(state: &ProcessServiceState) -> super::sandbox::SandboxSpec {
+    let mut read_only = Vec::new();
+    let mut read_write = vec![state.service_dir.clone(), state.tmp_dir.clone()];
+    if let Some(binary_dir) = state.binary.parent() {
+        read_only.push(binary_dir.to_path_buf());
+    }
+    if let Some(working_dir) = &state.manifest.working_dir {
+        read_only.push(PathBuf::from(working_dir));
+    }
+    for mount in &state.manifest.mounts {
+        if mount.read_only {
+            read_only.push(mount.host_path.clone());
+        } else {
+            read_write.push(mount.host_path.clone());
+        }
+    }
+    super::sandbox::SandboxSpec {
+        hostname: state.manifest.name.clone(),
+        read_only,
+        read_write,
+        owned: vec![
+            state.service_dir.clone(),
+            state.appdata_dir.clone(),
+            state.tmp_dir.clone(),
+        ],
+        bind_tcp_ports: state
+            .manifest
+            .ports
+            .iter()
+            .filter(|port| port.protocol == ServicePortProtocol::Tcp)
+            .map(|port| port.service_port)
+            .collect(),
+    }
+}
+
 fn should_serve_wasmtime_http(manifest: &ServiceManifest) -> bool {
     manifest.run_mode == ServiceRunMode::Http
 }
@@ -322,13 +622,24 @@ fn has_tcp_ports(manifest: &ServiceManifest) -> bool {
 }
 
 pub(crate) fn refresh_child_state(state: &mut WasmtimeServiceState) -> Result<()> {
-    if let Some(child) = state.child.as_mut()
-        && let Some(status) = child
-            .try_wait()
-            .context("Failed to query fungi WASI process status")?
+    reap_exited_child(&mut state.child, &mut state.last_exit_code)
+        .context("Failed to query fungi WASI process status")
+}
+
+pub(crate) fn refresh_process_child_state(state: &mut ProcessServiceState) -> Result<()> {
+    reap_exited_child(&mut state.child, &mut state.last_exit_code)
+        .context("Failed to query service process status")
+}
+
+fn reap_exited_child(
+    child: &mut Option<Child>,
+    last_exit_code: &mut Option<i32>,
+) -> std::io::Result<()> {
+    if let Some(running) = child.as_mut()
+        && let Some(status) = running.try_wait()?
     {
-        state.last_exit_code = status.code();
-        state.child = None;
+        *last_exit_code = status.code();
+        *child = None;
     }
     Ok(())
 }
@@ -345,20 +656,17 @@ pub(crate) fn map_docker_instance(
         labels: details.labels,
         ports: Vec::new(),
         exposed_endpoints: Vec::new(),
-        status: docker_service_status(details.state.status, details.state.running),
+        status: docker_service_status(
+            details.state.status,
+            details.state.running,
+            details.state.exit_code,
+        ),
+        job: None,
+        resources: None,
     }
 }
 
 pub(crate) fn map_wasmtime_instance(handle: &str, state: &WasmtimeServiceState) -> ServiceInstance {
-    let running = state.child.is_some();
-    let status = if running {
-        ServiceStatus::running()
-    } else if let Some(code) = state.last_exit_code {
-        ServiceStatus::exited(Some(code))
-    } else {
-        ServiceStatus::stopped()
-    };
-
     ServiceInstance {
         id: format!("wasmtime:{handle}"),
         runtime: RuntimeKind::Wasmtime,
@@ -368,11 +676,52 @@ pub(crate) fn map_wasmtime_instance(handle: &str, state: &WasmtimeServiceState)
         labels: state.manifest.labels.clone(),
         ports: Vec::new(),
         exposed_endpoints: Vec::new(),
-        status,
+        status: child_service_status(state.child.is_some(), state.last_exit_code),
+        job: None,
+        resources: None,
     }
 }
 
-fn docker_service_status(runtime_state: String, running: bool) -> ServiceStatus {
+pub(crate) fn map_process_instance(handle: &str, state: &ProcessServiceState) -> ServiceInstance {
+    ServiceInstance {
+        id: format!("process:{handle}"),
+        runtime: RuntimeKind::Process,
+        name: state.manifest.name.clone(),
+        definition_id: state.manifest.definition_id.clone(),
+        source: state.binary.display().to_string(),
+        labels: state.manifest.labels.clone(),
+        ports: Vec::new(),
+        exposed_endpoints: Vec::new(),
+        status: process_service_status(state),
+        job: None,
+        resources: None,
+    }
+}
+
+fn process_service_status(state: &ProcessServiceState) -> ServiceStatus {
+    let status = child_service_status(state.child.is_some(), state.last_exit_code);
+    if status.is_running() {
+        status.with_optional_detail(state.sandbox_degradation.clone())
+    } else {
+        status
+    }
+}
+
+fn child_service_status(running: bool, last_exit_code: Option<i32>) -> ServiceStatus {
+    if running {
+        ServiceStatus::running()
+    } else if let Some(code) = last_exit_code {
+        ServiceStatus::exited(Some(code))
+    } else {
+        ServiceStatus::stopped()
+    }
+}
+
+fn docker_service_status(
+    runtime_state: String,
+    running: bool,
+    exit_code: Option<i32>,
+) -> ServiceStatus {
     let phase = if running {
         ServicePhase::Running
     } else {
@@ -383,7 +732,11 @@ fn docker_service_status(runtime_state: String, running: bool) -> ServiceStatus
         }
     };
 
-    ServiceStatus::new(phase).with_detail(runtime_state)
+    let mut status = ServiceStatus::new(phase).with_detail(runtime_state);
+    if phase == ServicePhase::Exited {
+        status.exit_code = exit_code;
+    }
+    status
 }
 
 pub(crate) fn missing_instance_from_manifest(manifest: &ServiceManifest) -> ServiceInstance {
@@ -397,6 +750,8 @@ pub(crate) fn missing_instance_from_manifest(manifest: &ServiceManifest) -> Serv
         ports: manifest.ports.clone(),
         exposed_endpoints: service_expose_endpoint_bindings(manifest),
         status: ServiceStatus::missing(),
+        job: None,
+        resources: None,
     }
 }
 
@@ -415,19 +770,24 @@ pub(crate) fn enrich_instance_from_manifest(
 }
 
 fn service_instance_id(runtime: RuntimeKind, name: &str) -> String {
-    let runtime_name = match runtime {
+    format!("{}:{name}", runtime_name(runtime))
+}
+
+fn runtime_name(runtime: RuntimeKind) -> &'static str {
+    match runtime {
         RuntimeKind::Docker => "docker",
         RuntimeKind::Wasmtime => "wasmtime",
+        RuntimeKind::Process => "process",
         RuntimeKind::External => "external",
-    };
-    format!("{runtime_name}:{name}")
+    }
 }
 
 fn source_display(source: &ServiceSource) -> String {
     match source {
         ServiceSource::Docker { image } => image.clone(),
-        ServiceSource::WasmtimeFile { component } => component.display().to_string(),
-        ServiceSource::WasmtimeUrl { url } => url.clone(),
+        ServiceSource::WasmtimeFile { component, .. } => component.display().to_string(),
+        ServiceSource::WasmtimeUrl { url, .. } => url.clone(),
+        ServiceSource::Process { binary, .. } => binary.display().to_string(),
         ServiceSource::ExistingTcp { host, port } => format!("{host}:{port}"),
     }
 }
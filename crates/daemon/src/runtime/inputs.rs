@@ -0,0 +1,247 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use serde_yaml::Value;
+
+const INPUTS_KEY: &str = "inputs";
+const INPUT_REFERENCE_PREFIX: &str = "${inputs.";
+
+/// One entry of the `inputs:` section of a `fungi: service/v1` document.
+///
+/// An input without a `default` is required. `${inputs.<name>}` may appear in any string value of
+/// the document; when a value consists of nothing but the reference it takes the input's type, so
+/// `port: ${inputs.http_port}` stays an integer.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ServiceInputSpec {
+    #[serde(default, rename = "type")]
+    kind: ServiceInputKind,
+    #[serde(default)]
+    default: Option<Value>,
+    /// Documentation for recipe authors and readers; not used when rendering.
+    #[serde(default)]
+    #[allow(dead_code)]
+    description: Option<String>,
+    #[serde(default)]
+    choices: Vec<Value>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ServiceInputKind {
+    #[default]
+    String,
+    Integer,
+    Port,
+    Boolean,
+}
+
+impl ServiceInputKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::String => "string",
+            Self::Integer => "integer",
+            Self::Port => "port",
+            Self::Boolean => "boolean",
+        }
+    }
+}
+
+/// Parses a values file: a YAML mapping from input name to a scalar value.
+pub fn parse_service_input_values_yaml(content: &str) -> Result<BTreeMap<String, String>> {
+    let value: Value = serde_yaml::from_str(content).context("Failed to parse input values")?;
+    let mapping = match value {
+        Value::Null => return Ok(BTreeMap::new()),
+        Value::Mapping(mapping) => mapping,
+        _ => bail!("input values must be a mapping of input name to value"),
+    };
+    mapping
+        .into_iter()
+        .map(|(key, value)| {
+            let Value::String(key) = key else {
+                bail!("input value names must be strings");
+            };
+            let value = scalar_to_string(&value)
+                .ok_or_else(|| anyhow::anyhow!("input '{key}' must be a scalar value"))?;
+            Ok((key, value))
+        })
+        .collect()
+}
+
+/// Substitutes declared inputs into a service document and drops the `inputs:` section.
+/// Returns `None` when the document declares no inputs.
+pub(crate) fn render_service_inputs(
+    yaml: &str,
+    values: &BTreeMap<String, String>,
+) -> Result<Option<String>> {
+    let Ok(Value::Mapping(mut document)) = serde_yaml::from_str::<Value>(yaml) else {
+        return Ok(None);
+    };
+    let Some(inputs) = document.remove(INPUTS_KEY) else {
+        return Ok(None);
+    };
+    let specs: BTreeMap<String, ServiceInputSpec> =
+        serde_yaml::from_value(inputs).context("Failed to parse service inputs")?;
+    let resolved = resolve_input_values(&specs, values)?;
+
+    let mut document = Value::Mapping(document);
+    substitute_input_references(&mut document, &resolved)?;
+    let rendered =
+        serde_yaml::to_string(&document).context("Failed to encode rendered service YAML")?;
+    Ok(Some(rendered))
+}
+
+fn resolve_input_values(
+    specs: &BTreeMap<String, ServiceInputSpec>,
+    values: &BTreeMap<String, String>,
+) -> Result<BTreeMap<String, Value>> {
+    if let Some(unknown) = values.keys().find(|name| !specs.contains_key(*name)) {
+        let declared = specs.keys().cloned().collect::<Vec<_>>();
+        if declared.is_empty() {
+            bail!("unknown input '{unknown}': the service declares no inputs");
+        }
+        bail!(
+            "unknown input '{unknown}'; declared inputs: {}",
+            declared.join(", ")
+        );
+    }
+
+    let mut missing = BTreeSet::new();
+    let mut resolved = BTreeMap::new();
+    for (name, spec) in specs {
+        validate_input_name(name)?;
+        let raw = match values.get(name) {
+            Some(value) => value.clone(),
+            None => match spec.default.as_ref() {
+                Some(default) => scalar_to_string(default).ok_or_else(|| {
+                    anyhow::anyhow!("default of input '{name}' must be a scalar value")
+                })?,
+                None => {
+                    missing.insert(name.as_str());
+                    continue;
+                }
+            },
+        };
+        let value = typed_input_value(name, spec.kind, &raw)?;
+        if !spec.choices.is_empty()
+            && !spec
+                .choices
+                .iter()
+                .filter_map(scalar_to_string)
+                .any(|choice| choice == raw)
+        {
+            let choices = spec
+                .choices
+                .iter()
+                .filter_map(scalar_to_string)
+                .collect::<Vec<_>>();
+            bail!(
+                "input '{name}' must be one of: {} (got '{raw}')",
+                choices.join(", ")
+            );
+        }
+        resolved.insert(name.clone(), value);
+    }
+
+    if !missing.is_empty() {
+        bail!(
+            "missing required inputs: {}; pass them with --set NAME=VALUE or a values file",
+            missing.into_iter().collect::<Vec<_>>().join(", ")
+        );
+    }
+    Ok(resolved)
+}
+
+fn typed_input_value(name: &str, kind: ServiceInputKind, raw: &str) -> Result<Value> {
+    let invalid = || anyhow::anyhow!("input '{name}' must be a {}, got '{raw}'", kind.as_str());
+    Ok(match kind {
+        ServiceInputKind::String => Value::String(raw.to_string()),
+        ServiceInputKind::Integer => {
+            Value::Number(raw.trim().parse::<i64>().map_err(|_| invalid())?.into())
+        }
+        ServiceInputKind::Port => {
+            let port = raw.trim().parse::<u16>().map_err(|_| invalid())?;
+            if port == 0 {
+                return Err(invalid());
+            }
+            Value::Number(port.into())
+        }
+        ServiceInputKind::Boolean => Value::Bool(match raw.trim() {
+            "true" => true,
+            "false" => false,
+            _ => return Err(invalid()),
+        }),
+    })
+}
+
+fn substitute_input_references(value: &mut Value, inputs: &BTreeMap<String, Value>) -> Result<()> {
+    match value {
+        Value::String(text) => {
+            if let Some(replacement) = substitute_input_string(text, inputs)? {
+                *value = replacement;
+            }
+        }
+        Value::Sequence(items) => {
+            for item in items {
+                substitute_input_references(item, inputs)?;
+            }
+        }
+        Value::Mapping(mapping) => {
+            for (_, value) in mapping.iter_mut() {
+                substitute_input_references(value, inputs)?;
+            }
+        }
+        Value::Tagged(tagged) => substitute_input_references(&mut tagged.value, inputs)?,
+        Value::Null | Value::Bool(_) | Value::Number(_) => {}
+    }
+    Ok(())
+}
+
+/// Returns the replacement for a string scalar, or `None` when it references no inputs.
+fn substitute_input_string(text: &str, inputs: &BTreeMap<String, Value>) -> Result<Option<Value>> {
+    if !text.contains(INPUT_REFERENCE_PREFIX) {
+        return Ok(None);
+    }
+
+    let mut rendered = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(INPUT_REFERENCE_PREFIX) {
+        rendered.push_str(&rest[..start]);
+        let after_prefix = &rest[start + INPUT_REFERENCE_PREFIX.len()..];
+        let end = after_prefix
+            .find('}')
+            .ok_or_else(|| anyhow::anyhow!("unterminated input reference in '{text}'"))?;
+        let name = &after_prefix[..end];
+        let value = inputs
+            .get(name)
+            .ok_or_else(|| anyhow::anyhow!("reference to undeclared input '{name}'"))?;
+        if start == 0 && end + 1 == after_prefix.len() && rendered.is_empty() {
+            return Ok(Some(value.clone()));
+        }
+        rendered.push_str(&scalar_to_string(value).unwrap_or_default());
+        rest = &after_prefix[end + 1..];
+    }
+    rendered.push_str(rest);
+    Ok(Some(Value::String(rendered)))
+}
+
+fn validate_input_name(name: &str) -> Result<()> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '_' | '-'))
+    {
+        bail!("input name '{name}' may only contain letters, digits, '_' and '-'");
+    }
+    Ok(())
+}
+
+fn scalar_to_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(value) => Some(value.clone()),
+        Value::Number(value) => Some(value.to_string()),
+        Value::Bool(value) => Some(value.to_string()),
+        _ => None,
+    }
+}
@@ -7,9 +7,15 @@ use std::{
 
 use anyhow::{Context, Result, bail};
 use fungi_config::paths::FungiPaths;
+use fungi_docker_agent::UNCONFINED_PROFILE;
 use fungi_util::protocols::service_port_protocol;
+use sha2::{Digest, Sha256};
 
-use super::model::*;
+use super::{inputs::render_service_inputs, model::*, schedule::CronSchedule};
+use crate::integrity::{normalize_sha256_digest, pin_docker_image};
+use fungi_docker_agent::pinned_image_digest;
+
+const BYTES_PER_MB: u64 = 1024 * 1024;
 
 pub fn load_service_manifest_yaml_file(path: &Path, fungi_home: &Path) -> Result<ServiceManifest> {
     let content = fs::read_to_string(path)
@@ -78,6 +84,12 @@ pub fn peek_service_manifest_name(content: &str) -> Result<String> {
     parse_required_fungi_service_document(content)?.service_name()
 }
 
+/// SHA-256 of manifest YAML as submitted, recorded with each revision applied from it so callers
+/// can tell whether a service already runs a given manifest.
+pub fn manifest_yaml_digest(content: &str) -> String {
+    hex::encode(Sha256::digest(content.as_bytes()))
+}
+
 pub fn service_manifest_with_instance_name(content: &str, service_name: &str) -> Result<String> {
     let service_name = normalize_non_empty(service_name, "service name")?;
     if let Some(front_matter) = split_front_matter(content)? {
@@ -99,6 +111,39 @@ pub fn service_manifest_with_instance_name(content: &str, service_name: &str) ->
     bail!("service manifest must use fungi: service/v1")
 }
 
+/// Renders the document's declared `inputs:` with the supplied values (falling back to input
+/// defaults) and returns the document without its `inputs:` section.
+pub fn service_manifest_with_inputs(
+    content: &str,
+    values: &BTreeMap<String, String>,
+) -> Result<String> {
+    if let Some(front_matter) = split_front_matter(content)? {
+        let Some(yaml) = render_service_inputs(front_matter.yaml, values)? else {
+            ensure_no_input_values(values)?;
+            return Ok(content.to_string());
+        };
+        let yaml = yaml_without_document_start(yaml);
+        return Ok(format!("---\n{}---\n{}", yaml, front_matter.body));
+    }
+
+    if should_parse_as_fungi_service_yaml(content) {
+        let Some(yaml) = render_service_inputs(content, values)? else {
+            ensure_no_input_values(values)?;
+            return Ok(content.to_string());
+        };
+        return Ok(yaml);
+    }
+
+    bail!("service manifest must use fungi: service/v1")
+}
+
+fn ensure_no_input_values(values: &BTreeMap<String, String>) -> Result<()> {
+    if let Some(name) = values.keys().next() {
+        bail!("unknown input '{name}': the service declares no inputs");
+    }
+    Ok(())
+}
+
 fn yaml_without_document_start(yaml: String) -> String {
     let mut yaml = yaml
         .strip_prefix("---\n")
@@ -115,36 +160,70 @@ pub fn service_manifest_to_yaml(manifest: &ServiceManifest) -> Result<String> {
     let run = match &manifest.source {
         ServiceSource::Docker { image } => Some(FungiServiceRun {
             provider: FungiServiceProvider::Docker,
-            mode: None,
+            mode: manifest_run_mode_to_fungi(manifest.run_mode),
             source: FungiServiceSource {
                 image: Some(image.clone()),
                 ..FungiServiceSource::default()
             },
             args: manifest.command.clone(),
             env: manifest.env.clone(),
-            mounts: manifest_mounts_to_fungi(&manifest.mounts),
+            mounts: manifest_mounts_to_fungi(manifest),
+            tmpfs: manifest_tmpfs_to_fungi(&manifest.container.tmpfs),
+            security: manifest_security_to_fungi(&manifest.container.security),
+            on_demand: manifest_on_demand_to_fungi(manifest.on_demand),
+            schedule: manifest.job.schedule.clone(),
+            timeout_secs: manifest.job.timeout_secs,
         }),
-        ServiceSource::WasmtimeFile { component } => Some(FungiServiceRun {
+        ServiceSource::WasmtimeFile { component, sha256 } => Some(FungiServiceRun {
             provider: FungiServiceProvider::Wasmtime,
-            mode: (manifest.run_mode == ServiceRunMode::Http).then_some(FungiServiceRunMode::Http),
+            mode: manifest_run_mode_to_fungi(manifest.run_mode),
             source: FungiServiceSource {
                 file: Some(component.display().to_string()),
+                sha256: sha256.clone(),
                 ..FungiServiceSource::default()
             },
             args: manifest.command.clone(),
             env: manifest.env.clone(),
-            mounts: manifest_mounts_to_fungi(&manifest.mounts),
+            mounts: manifest_mounts_to_fungi(manifest),
+            tmpfs: manifest_tmpfs_to_fungi(&manifest.container.tmpfs),
+            security: manifest_security_to_fungi(&manifest.container.security),
+            on_demand: manifest_on_demand_to_fungi(manifest.on_demand),
+            schedule: manifest.job.schedule.clone(),
+            timeout_secs: manifest.job.timeout_secs,
         }),
-        ServiceSource::WasmtimeUrl { url } => Some(FungiServiceRun {
+        ServiceSource::WasmtimeUrl { url, sha256 } => Some(FungiServiceRun {
             provider: FungiServiceProvider::Wasmtime,
-            mode: (manifest.run_mode == ServiceRunMode::Http).then_some(FungiServiceRunMode::Http),
+            mode: manifest_run_mode_to_fungi(manifest.run_mode),
             source: FungiServiceSource {
                 url: Some(url.clone()),
+                sha256: sha256.clone(),
+                ..FungiServiceSource::default()
+            },
+            args: manifest.command.clone(),
+            env: manifest.env.clone(),
+            mounts: manifest_mounts_to_fungi(manifest),
+            tmpfs: manifest_tmpfs_to_fungi(&manifest.container.tmpfs),
+            security: manifest_security_to_fungi(&manifest.container.security),
+            on_demand: manifest_on_demand_to_fungi(manifest.on_demand),
+            schedule: manifest.job.schedule.clone(),
+            timeout_secs: manifest.job.timeout_secs,
+        }),
+        ServiceSource::Process { binary, sha256 } => Some(FungiServiceRun {
+            provider: FungiServiceProvider::Process,
+            mode: manifest_run_mode_to_fungi(manifest.run_mode),
+            source: FungiServiceSource {
+                file: Some(binary.display().to_string()),
+                sha256: sha256.clone(),
                 ..FungiServiceSource::default()
             },
             args: manifest.command.clone(),
             env: manifest.env.clone(),
-            mounts: manifest_mounts_to_fungi(&manifest.mounts),
+            mounts: manifest_mounts_to_fungi(manifest),
+            tmpfs: Vec::new(),
+            security: None,
+            on_demand: manifest_on_demand_to_fungi(manifest.on_demand),
+            schedule: manifest.job.schedule.clone(),
+            timeout_secs: manifest.job.timeout_secs,
         }),
         ServiceSource::ExistingTcp { .. } => None,
     };
@@ -175,16 +254,70 @@ pub fn service_manifest_to_yaml(manifest: &ServiceManifest) -> Result<String> {
     serde_yaml::to_string(&document).context("Failed to encode Fungi service YAML")
 }
 
-fn manifest_mounts_to_fungi(mounts: &[ServiceMount]) -> Vec<FungiServiceMount> {
-    mounts
+fn manifest_run_mode_to_fungi(run_mode: ServiceRunMode) -> Option<FungiServiceRunMode> {
+    match run_mode {
+        ServiceRunMode::Command => None,
+        ServiceRunMode::Http => Some(FungiServiceRunMode::Http),
+        ServiceRunMode::Job => Some(FungiServiceRunMode::Job),
+    }
+}
+
+fn manifest_on_demand_to_fungi(on_demand: Option<ServiceOnDemand>) -> Option<FungiServiceOnDemand> {
+    on_demand.map(|on_demand| {
+        if on_demand == ServiceOnDemand::default() {
+            FungiServiceOnDemand::Enabled(true)
+        } else {
+            FungiServiceOnDemand::Settings(FungiServiceOnDemandSettings {
+                idle_timeout_secs: on_demand.idle_timeout_secs,
+            })
+        }
+    })
+}
+
+fn manifest_mounts_to_fungi(manifest: &ServiceManifest) -> Vec<FungiServiceMount> {
+    // Process services see their mounts at the host path, so there is no `to` to render.
+    let binds = manifest.mounts.iter().map(|mount| FungiServiceMount {
+        from: Some(mount.host_path.display().to_string()),
+        volume: None,
+        to: (manifest.runtime != RuntimeKind::Process).then(|| mount.runtime_path.clone()),
+        read_only: mount.read_only,
+    });
+    let volumes = manifest
+        .container
+        .volumes
+        .iter()
+        .map(|volume| FungiServiceMount {
+            from: None,
+            volume: Some(volume.name.clone()),
+            to: Some(volume.runtime_path.clone()),
+            read_only: volume.read_only,
+        });
+    binds.chain(volumes).collect()
+}
+
+fn manifest_tmpfs_to_fungi(tmpfs: &[ServiceTmpfs]) -> Vec<FungiServiceTmpfs> {
+    tmpfs
         .iter()
-        .map(|mount| FungiServiceMount {
-            from: mount.host_path.display().to_string(),
-            to: mount.runtime_path.clone(),
+        .map(|tmpfs| FungiServiceTmpfs {
+            to: tmpfs.runtime_path.clone(),
+            size_mb: tmpfs.size_bytes.map(|size| size / BYTES_PER_MB),
         })
         .collect()
 }
 
+fn manifest_security_to_fungi(security: &ServiceSecurity) -> Option<FungiServiceSecurity> {
+    (!security.is_empty()).then(|| FungiServiceSecurity {
+        user: security.user.clone(),
+        read_only_rootfs: security.read_only_rootfs,
+        cap_add: security.cap_add.clone(),
+        cap_drop: security.cap_drop.clone(),
+        privileged: security.privileged,
+        host_network: security.host_network,
+        seccomp_profile: security.seccomp_profile.clone(),
+        apparmor_profile: security.apparmor_profile.clone(),
+    })
+}
+
 fn manifest_publish_to_fungi(
     manifest: &ServiceManifest,
 ) -> BTreeMap<String, FungiServicePublishEntry> {
@@ -265,6 +398,8 @@ struct FungiServiceDocument {
     instance: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     run: Option<FungiServiceRun>,
+    /// May only be empty for jobs, which serve nothing.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     publish: BTreeMap<String, FungiServicePublishEntry>,
 }
 
@@ -281,6 +416,34 @@ struct FungiServiceRun {
     env: BTreeMap<String, String>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     mounts: Vec<FungiServiceMount>,
+    /// Docker only.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tmpfs: Vec<FungiServiceTmpfs>,
+    /// Docker only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    security: Option<FungiServiceSecurity>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    on_demand: Option<FungiServiceOnDemand>,
+    /// Job only: cron expression for scheduled runs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    schedule: Option<String>,
+    /// Job only: a run still going after this long is stopped and recorded as timed out.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    timeout_secs: Option<u64>,
+}
+
+/// `on_demand: true` uses the default idle timeout; the mapping form overrides it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(untagged)]
+enum FungiServiceOnDemand {
+    Enabled(bool),
+    Settings(FungiServiceOnDemandSettings),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FungiServiceOnDemandSettings {
+    idle_timeout_secs: u64,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
@@ -288,12 +451,14 @@ struct FungiServiceRun {
 enum FungiServiceProvider {
     Docker,
     Wasmtime,
+    Process,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "lowercase")]
 enum FungiServiceRunMode {
     Http,
+    Job,
 }
 
 #[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
@@ -305,13 +470,58 @@ struct FungiServiceSource {
     file: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     image: Option<String>,
+    /// Pinned artifact digest. Docker images are rewritten to `image@sha256:<digest>`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    sha256: Option<String>,
 }
 
+/// A bind mount (`from`) or, for Docker, a named volume (`volume`). `to` is required except
+/// for process services, which access mounts at their host path.
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(deny_unknown_fields)]
 struct FungiServiceMount {
-    from: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    from: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    volume: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    to: Option<String>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    read_only: bool,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FungiServiceTmpfs {
     to: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    size_mb: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct FungiServiceSecurity {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    user: Option<String>,
+    #[serde(skip_serializing_if = "is_false")]
+    read_only_rootfs: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    cap_add: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    cap_drop: Vec<String>,
+    #[serde(skip_serializing_if = "is_false")]
+    privileged: bool,
+    #[serde(skip_serializing_if = "is_false")]
+    host_network: bool,
+    /// `unconfined` or a path to a seccomp profile JSON file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seccomp_profile: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    apparmor_profile: Option<String>,
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -390,6 +600,13 @@ fn split_front_matter(content: &str) -> Result<Option<FrontMatter<'_>>> {
 }
 
 fn parse_fungi_service_yaml(yaml: &str, label: &str) -> Result<FungiServiceDocument> {
+    // Documents that still declare inputs at this point are rendered with their defaults.
+    if let Some(rendered) = render_service_inputs(yaml, &BTreeMap::new())
+        .with_context(|| format!("Failed to render {label} inputs"))?
+    {
+        return serde_yaml::from_str(&rendered)
+            .map_err(|error| format_yaml_parse_error(label, error));
+    }
     serde_yaml::from_str(yaml).map_err(|error| format_yaml_parse_error(label, error))
 }
 
@@ -473,7 +690,11 @@ impl FungiServiceDocument {
 
         let definition_id = self.definition_id()?;
         let service_name = self.service_name()?;
-        if self.publish.is_empty() {
+        let is_job = self
+            .run
+            .as_ref()
+            .is_some_and(|run| run.mode == Some(FungiServiceRunMode::Job));
+        if self.publish.is_empty() && !is_job {
             bail!("service file requires at least one publish entry");
         }
 
@@ -486,25 +707,31 @@ impl FungiServiceDocument {
             publish,
         } = self;
 
-        let (runtime_and_source, env, mounts, command) = match run {
+        let (runtime_and_source, env, mounts, container, command, on_demand, job) = match run {
             Some(run) => {
                 let runtime_and_source = parse_fungi_run(&run, &publish, base_dir, path_roots)?;
-                let env = run.env;
-                let mounts = run
-                    .mounts
-                    .into_iter()
-                    .map(|mount| ServiceMount {
-                        host_path: resolve_manifest_path(&mount.from, base_dir, path_roots),
-                        runtime_path: mount.to,
-                    })
-                    .collect();
-                (runtime_and_source, env, mounts, run.args)
+                let on_demand = parse_fungi_on_demand(run.on_demand)?;
+                let job = parse_fungi_job(&run)?;
+                let (mounts, container) =
+                    parse_fungi_container_options(&run, base_dir, path_roots)?;
+                (
+                    runtime_and_source,
+                    run.env,
+                    mounts,
+                    container,
+                    run.args,
+                    on_demand,
+                    job,
+                )
             }
             None => (
                 parse_fungi_existing_tcp_run(&publish)?,
                 BTreeMap::new(),
                 Vec::new(),
+                ServiceContainerOptions::default(),
                 Vec::new(),
+                None,
+                ServiceJob::default(),
             ),
         };
 
@@ -529,6 +756,9 @@ impl FungiServiceDocument {
             entrypoint: Vec::new(),
             working_dir: None,
             labels: BTreeMap::new(),
+            on_demand,
+            container,
+            job,
         })
     }
 }
@@ -545,19 +775,37 @@ fn parse_fungi_run(
         }
     }
 
+    if run.mode == Some(FungiServiceRunMode::Http) && run.provider != FungiServiceProvider::Wasmtime
+    {
+        bail!("run.mode: http is currently supported only with provider: wasmtime");
+    }
+    let run_mode = match run.mode {
+        Some(FungiServiceRunMode::Http) => ServiceRunMode::Http,
+        Some(FungiServiceRunMode::Job) => ServiceRunMode::Job,
+        None => ServiceRunMode::Command,
+    };
+
     match run.provider {
         FungiServiceProvider::Docker => {
-            if run.mode.is_some() {
-                bail!("run.mode is currently supported only with provider: wasmtime");
-            }
             let image = exactly_one_source(&run.source, "run.source", SourceField::Image)?;
+            let image = match normalize_optional(run.source.sha256.clone()) {
+                Some(sha256) => pin_docker_image(&image, &sha256)?,
+                None => match pinned_image_digest(&image) {
+                    Some(digest) => pin_docker_image(&image, digest)?,
+                    None => image,
+                },
+            };
             Ok(RuntimeAndSource {
                 runtime: RuntimeKind::Docker,
-                run_mode: ServiceRunMode::Command,
+                run_mode,
                 source: ServiceSource::Docker { image },
             })
         }
         FungiServiceProvider::Wasmtime => {
+            let sha256 = normalize_optional(run.source.sha256.clone())
+                .map(|sha256| normalize_sha256_digest(&sha256))
+                .transpose()
+                .context("invalid run.source.sha256")?;
             let source = match (
                 normalize_optional(run.source.file.clone()),
                 normalize_optional(run.source.url.clone()),
@@ -565,8 +813,9 @@ fn parse_fungi_run(
             ) {
                 (Some(file), None, None) => ServiceSource::WasmtimeFile {
                     component: resolve_manifest_path(&file, base_dir, path_roots),
+                    sha256,
                 },
-                (None, Some(url), None) => ServiceSource::WasmtimeUrl { url },
+                (None, Some(url), None) => ServiceSource::WasmtimeUrl { url, sha256 },
                 (None, None, Some(_)) => {
                     bail!("provider: wasmtime requires source.url or source.file, not source.image")
                 }
@@ -577,13 +826,163 @@ fn parse_fungi_run(
             };
             Ok(RuntimeAndSource {
                 runtime: RuntimeKind::Wasmtime,
-                run_mode: match run.mode {
-                    Some(FungiServiceRunMode::Http) => ServiceRunMode::Http,
-                    None => ServiceRunMode::Command,
-                },
+                run_mode,
                 source,
             })
         }
+        FungiServiceProvider::Process => {
+            let sha256 = normalize_optional(run.source.sha256.clone())
+                .map(|sha256| normalize_sha256_digest(&sha256))
+                .transpose()
+                .context("invalid run.source.sha256")?;
+            let binary = match (
+                normalize_optional(run.source.file.clone()),
+                normalize_optional(run.source.url.clone()),
+                normalize_optional(run.source.image.clone()),
+            ) {
+                (Some(file), None, None) => resolve_manifest_path(&file, base_dir, path_roots),
+                _ => bail!("provider: process requires source.file and no other source"),
+            };
+            Ok(RuntimeAndSource {
+                runtime: RuntimeKind::Process,
+                run_mode,
+                source: ServiceSource::Process { binary, sha256 },
+            })
+        }
+    }
+}
+
+/// Splits `run.mounts` into bind mounts and named volumes and collects the Docker-only
+/// `tmpfs` and `security` settings, rejecting them for other providers.
+fn parse_fungi_container_options(
+    run: &FungiServiceRun,
+    base_dir: &Path,
+    path_roots: &ManifestPathRoots,
+) -> Result<(Vec<ServiceMount>, ServiceContainerOptions)> {
+    let mut mounts = Vec::new();
+    let mut container = ServiceContainerOptions::default();
+    for (index, mount) in run.mounts.iter().enumerate() {
+        let to = match (run.provider, mount.to.clone()) {
+            (FungiServiceProvider::Process, Some(_)) => bail!(
+                "run.mounts[{index}].to is not supported with provider: process; mounts are accessed at their host path"
+            ),
+            (FungiServiceProvider::Process, None) => None,
+            (_, Some(to)) => Some(to),
+            (_, None) => bail!("run.mounts[{index}].to is required"),
+        };
+        match (
+            normalize_optional(mount.from.clone()),
+            normalize_optional(mount.volume.clone()),
+        ) {
+            (Some(from), None) => {
+                let host_path = resolve_manifest_path(&from, base_dir, path_roots);
+                mounts.push(ServiceMount {
+                    runtime_path: to.unwrap_or_else(|| host_path.display().to_string()),
+                    host_path,
+                    read_only: mount.read_only,
+                });
+            }
+            (None, Some(volume)) => container.volumes.push(ServiceVolume {
+                name: volume,
+                runtime_path: to.unwrap_or_default(),
+                read_only: mount.read_only,
+            }),
+            _ => bail!("run.mounts[{index}] must set exactly one of from or volume"),
+        }
+    }
+    for tmpfs in &run.tmpfs {
+        if tmpfs.size_mb == Some(0) {
+            bail!("run.tmpfs size_mb must be greater than 0");
+        }
+        container.tmpfs.push(ServiceTmpfs {
+            runtime_path: tmpfs.to.clone(),
+            size_bytes: tmpfs.size_mb.map(|size| size * BYTES_PER_MB),
+        });
+    }
+    if let Some(security) = run.security.clone() {
+        container.security = ServiceSecurity {
+            user: normalize_optional(security.user),
+            read_only_rootfs: security.read_only_rootfs,
+            cap_add: security.cap_add,
+            cap_drop: security.cap_drop,
+            privileged: security.privileged,
+            host_network: security.host_network,
+            seccomp_profile: normalize_optional(security.seccomp_profile).map(|profile| {
+                if profile == UNCONFINED_PROFILE {
+                    profile
+                } else {
+                    resolve_manifest_path_string(&profile, base_dir, path_roots)
+                }
+            }),
+            apparmor_profile: normalize_optional(security.apparmor_profile),
+        };
+    }
+
+    if run.provider != FungiServiceProvider::Docker {
+        if !container.volumes.is_empty() {
+            bail!("run.mounts[].volume is currently supported only with provider: docker");
+        }
+        if run.provider == FungiServiceProvider::Wasmtime
+            && mounts.iter().any(|mount| mount.read_only)
+        {
+            bail!(
+                "run.mounts[].read_only is currently supported only with provider: docker or process"
+            );
+        }
+        if !container.tmpfs.is_empty() {
+            bail!("run.tmpfs is currently supported only with provider: docker");
+        }
+        if run.security.is_some() {
+            bail!("run.security is currently supported only with provider: docker");
+        }
+    }
+    Ok((mounts, container))
+}
+
+fn parse_fungi_job(run: &FungiServiceRun) -> Result<ServiceJob> {
+    if run.mode != Some(FungiServiceRunMode::Job) {
+        if run.schedule.is_some() {
+            bail!("run.schedule requires run.mode: job");
+        }
+        if run.timeout_secs.is_some() {
+            bail!("run.timeout_secs requires run.mode: job");
+        }
+        return Ok(ServiceJob::default());
+    }
+
+    if matches!(
+        run.on_demand,
+        Some(FungiServiceOnDemand::Enabled(true) | FungiServiceOnDemand::Settings(_))
+    ) {
+        bail!("run.on_demand is not supported with run.mode: job");
+    }
+    if run.timeout_secs == Some(0) {
+        bail!("run.timeout_secs must be greater than 0");
+    }
+    let schedule = normalize_optional(run.schedule.clone());
+    if let Some(schedule) = &schedule {
+        CronSchedule::parse(schedule).context("invalid run.schedule")?;
+    }
+    Ok(ServiceJob {
+        schedule,
+        timeout_secs: run.timeout_secs,
+    })
+}
+
+fn parse_fungi_on_demand(
+    on_demand: Option<FungiServiceOnDemand>,
+) -> Result<Option<ServiceOnDemand>> {
+    match on_demand {
+        None | Some(FungiServiceOnDemand::Enabled(false)) => Ok(None),
+        Some(FungiServiceOnDemand::Enabled(true)) => Ok(Some(ServiceOnDemand::default())),
+        Some(FungiServiceOnDemand::Settings(settings)) => {
+            if settings.idle_timeout_secs == 0 {
+                bail!("run.on_demand.idle_timeout_secs must be greater than 0");
+            }
+            Ok(Some(ServiceOnDemand {
+                idle_timeout_secs: settings.idle_timeout_secs,
+            }))
+        }
     }
 }
 
@@ -678,7 +1077,7 @@ fn parse_fungi_publish_entry(
                 protocol: ServicePortProtocol::Tcp,
             })
         }
-        RuntimeKind::Wasmtime | RuntimeKind::External => {
+        RuntimeKind::Wasmtime | RuntimeKind::Process | RuntimeKind::External => {
             let host = normalize_fungi_tcp_host(
                 entry.tcp.host.as_deref(),
                 &format!("publish.{name}.tcp.host"),
@@ -879,6 +1278,58 @@ impl ManifestPathRoots {
     }
 }
 
+/// Renders `manifest` with host paths under the service's data, artifacts, workspace or user
+/// root turned back into `$fungi.*` placeholders, so another node resolves them against its own
+/// directories. Other absolute paths are kept as they are.
+pub(crate) fn portable_service_manifest_yaml(
+    manifest: &ServiceManifest,
+    path_roots: &ManifestPathRoots,
+) -> Result<String> {
+    let mut manifest = manifest.clone();
+    for mount in &mut manifest.mounts {
+        mount.host_path = PathBuf::from(portable_manifest_path(&mount.host_path, path_roots));
+    }
+    match &mut manifest.source {
+        ServiceSource::WasmtimeFile { component, .. } => {
+            *component = PathBuf::from(portable_manifest_path(component, path_roots));
+        }
+        ServiceSource::Process { binary, .. } => {
+            *binary = PathBuf::from(portable_manifest_path(binary, path_roots));
+        }
+        _ => {}
+    }
+    if let Some(profile) = manifest.container.security.seccomp_profile.as_mut()
+        && profile != UNCONFINED_PROFILE
+    {
+        *profile = portable_manifest_path(Path::new(profile.as_str()), path_roots);
+    }
+    service_manifest_to_yaml(&manifest)
+}
+
+fn portable_manifest_path(path: &Path, path_roots: &ManifestPathRoots) -> String {
+    // The workspace lives inside the user root, so it has to be tried first.
+    let roots = [
+        (
+            "$fungi.service.artifacts",
+            &path_roots.service_artifacts_dir,
+        ),
+        ("$fungi.service.data", &path_roots.service_appdata_dir),
+        ("$fungi.workspace", &path_roots.user_home_dir),
+        ("$fungi.root", &path_roots.user_root_dir),
+    ];
+    for (placeholder, root) in roots {
+        let Ok(relative) = path.strip_prefix(root) else {
+            continue;
+        };
+        return if relative.as_os_str().is_empty() {
+            placeholder.to_string()
+        } else {
+            format!("{placeholder}/{}", relative.to_string_lossy())
+        };
+    }
+    path.to_string_lossy().to_string()
+}
+
 fn resolve_manifest_path(path: &str, base_dir: &Path, path_roots: &ManifestPathRoots) -> PathBuf {
     let expanded = resolve_manifest_path_string(path, base_dir, path_roots);
     PathBuf::from(expanded)
@@ -1,17 +1,29 @@
 mod control;
+mod exec;
 mod helpers;
+mod inputs;
 mod manifest;
 mod model;
 mod providers;
+mod resources;
+#[cfg(target_os = "linux")]
+mod sandbox;
+mod schedule;
 
 #[cfg(test)]
 mod tests;
 
-pub use control::RuntimeControl;
+pub use control::{AppliedService, RuntimeControl};
+pub(crate) use exec::{ServiceExecBackend, collect_exec_output, exec_session_channels};
+pub use exec::{
+    ServiceExecExit, ServiceExecInput, ServiceExecOutput, ServiceExecRequest, ServiceExecSession,
+    ServiceExecSize,
+};
+pub use inputs::parse_service_input_values_yaml;
 pub use manifest::{
-    load_service_manifest_yaml_file, parse_service_manifest_yaml,
+    load_service_manifest_yaml_file, manifest_yaml_digest, parse_service_manifest_yaml,
     parse_service_manifest_yaml_with_policy, peek_service_manifest_name,
-    service_expose_endpoint_bindings, service_manifest_to_yaml,
+    service_expose_endpoint_bindings, service_manifest_to_yaml, service_manifest_with_inputs,
     service_manifest_with_instance_name,
 };
 pub(crate) use manifest::{
@@ -19,5 +31,6 @@ pub(crate) use manifest::{
 };
 pub use model::*;
 pub use providers::{
-    DockerRuntimeProvider, RuntimeProvider, WasmtimeRuntimeProvider, wasmtime_runtime_supported,
+    DockerRuntimeProvider, ProcessRuntimeProvider, RuntimeProvider, WasmtimeRuntimeProvider,
+    process_runtime_supported, wasmtime_runtime_supported,
 };
@@ -7,6 +7,7 @@ use serde::{Deserialize, Serialize};
 pub enum RuntimeKind {
     Docker,
     Wasmtime,
+    Process,
     External,
 }
 
@@ -27,6 +28,95 @@ pub struct ServiceManifest {
     pub entrypoint: Vec<String>,
     pub working_dir: Option<String>,
     pub labels: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_demand: Option<ServiceOnDemand>,
+    /// Docker-only container settings; always empty for other runtimes.
+    #[serde(default, skip_serializing_if = "ServiceContainerOptions::is_empty")]
+    pub container: ServiceContainerOptions,
+    /// Job-only settings; always empty for long-running services.
+    #[serde(default, skip_serializing_if = "ServiceJob::is_empty")]
+    pub job: ServiceJob,
+}
+
+/// Who asked for a service to be applied. Docker containers requested by remote peers are held
+/// to the node's stricter `runtime.docker_security.remote` rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ServiceOrigin {
+    #[default]
+    Local,
+    Remote,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServiceContainerOptions {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub volumes: Vec<ServiceVolume>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tmpfs: Vec<ServiceTmpfs>,
+    #[serde(default, skip_serializing_if = "ServiceSecurity::is_empty")]
+    pub security: ServiceSecurity,
+}
+
+impl ServiceContainerOptions {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServiceVolume {
+    pub name: String,
+    pub runtime_path: String,
+    #[serde(default)]
+    pub read_only: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServiceTmpfs {
+    pub runtime_path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size_bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ServiceSecurity {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    pub read_only_rootfs: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub cap_add: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub cap_drop: Vec<String>,
+    pub privileged: bool,
+    pub host_network: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seccomp_profile: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub apparmor_profile: Option<String>,
+}
+
+impl ServiceSecurity {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+pub const DEFAULT_ON_DEMAND_IDLE_TIMEOUT_SECS: u64 = 600;
+
+/// Scale-to-zero settings: the service is started by the first incoming stream and stopped
+/// again once none of its endpoints has had an open stream for `idle_timeout_secs`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServiceOnDemand {
+    pub idle_timeout_secs: u64,
+}
+
+impl Default for ServiceOnDemand {
+    fn default() -> Self {
+        Self {
+            idle_timeout_secs: DEFAULT_ON_DEMAND_IDLE_TIMEOUT_SECS,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
@@ -35,14 +125,140 @@ pub enum ServiceRunMode {
     #[default]
     Command,
     Http,
+    /// Runs to completion when triggered manually or by its schedule instead of staying up.
+    Job,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServiceJob {
+    /// Standard five-field cron expression, evaluated in the node's local time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+}
+
+impl ServiceJob {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ServiceJobTrigger {
+    Manual,
+    Schedule,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ServiceJobOutcome {
+    Running,
+    Succeeded,
+    Failed,
+    TimedOut,
+    /// The daemon stopped while the run was in progress.
+    Interrupted,
+}
+
+impl ServiceJobOutcome {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Running => "running",
+            Self::Succeeded => "succeeded",
+            Self::Failed => "failed",
+            Self::TimedOut => "timed_out",
+            Self::Interrupted => "interrupted",
+        }
+    }
+}
+
+impl fmt::Display for ServiceJobOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// One execution of a job service. `logs` holds only the output produced during this run,
+/// truncated to its tail.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServiceJobRun {
+    pub run: u64,
+    pub trigger: ServiceJobTrigger,
+    pub started_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+    pub outcome: ServiceJobOutcome,
+    #[serde(default)]
+    pub logs: String,
+}
+
+/// Schedule and recent runs of a job service, oldest run first.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServiceJobStatus {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<String>,
+    /// Whether scheduled runs are enabled, i.e. the job has been started.
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_run_at: Option<String>,
+    #[serde(default)]
+    pub runs: Vec<ServiceJobRun>,
 }
 
+/// A point-in-time resource sample of a running service. CPU is a percentage of one core, so it
+/// can exceed 100 on multi-core hosts; counters are cumulative since the service started. Fields a
+/// runtime cannot measure are left out, e.g. network traffic of wasmtime and process services.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ServiceResourceUsage {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_percent: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_bytes: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_limit_bytes: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network_rx_bytes: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network_tx_bytes: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_read_bytes: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_write_bytes: Option<u64>,
+}
+
+/// Where a service's artifact comes from. Docker images are pinned by an `@sha256:` digest in the
+/// image reference; wasm components and native binaries carry an optional `sha256` checked before
+/// they are staged or launched.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ServiceSource {
-    Docker { image: String },
-    WasmtimeFile { component: PathBuf },
-    WasmtimeUrl { url: String },
-    ExistingTcp { host: String, port: u16 },
+    Docker {
+        image: String,
+    },
+    WasmtimeFile {
+        component: PathBuf,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        sha256: Option<String>,
+    },
+    WasmtimeUrl {
+        url: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        sha256: Option<String>,
+    },
+    /// A native binary already present on the host, launched in place.
+    Process {
+        binary: PathBuf,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        sha256: Option<String>,
+    },
+    ExistingTcp {
+        host: String,
+        port: u16,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -82,6 +298,8 @@ pub enum ServiceExposeUsageKind {
 pub struct ServiceMount {
     pub host_path: PathBuf,
     pub runtime_path: String,
+    #[serde(default)]
+    pub read_only: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -132,6 +350,12 @@ pub struct ServiceInstance {
     #[serde(default)]
     pub exposed_endpoints: Vec<ServiceExposeEndpointBinding>,
     pub status: ServiceStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub job: Option<ServiceJobStatus>,
+    /// Only sampled for running services, and only where the API shows it; see
+    /// [`super::RuntimeControl::with_resources`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resources: Option<ServiceResourceUsage>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -167,6 +391,8 @@ pub struct ServiceStatus {
     pub phase: ServicePhase,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub detail: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
 }
 
 impl ServiceStatus {
@@ -180,7 +406,9 @@ impl ServiceStatus {
 
     pub fn exited(exit_code: Option<i32>) -> Self {
         let detail = exit_code.map(|code| format!("exited({code})"));
-        Self::new(ServicePhase::Exited).with_optional_detail(detail)
+        let mut status = Self::new(ServicePhase::Exited).with_optional_detail(detail);
+        status.exit_code = exit_code;
+        status
     }
 
     pub fn missing() -> Self {
@@ -195,6 +423,7 @@ impl ServiceStatus {
         Self {
             phase,
             detail: None,
+            exit_code: None,
         }
     }
 
@@ -240,6 +469,28 @@ mod tests {
     }
 }
 
+/// One recorded apply of a managed service manifest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServiceRevision {
+    pub revision: u64,
+    /// RFC 3339 timestamp of the apply.
+    pub applied_at: String,
+    /// Peer that applied the revision, when known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub applied_by: Option<String>,
+    /// SHA-256 of the resolved manifest YAML stored for the revision, which is what a rollback
+    /// re-applies.
+    #[serde(alias = "source_digest")]
+    pub resolved_manifest_digest: String,
+    /// SHA-256 of the manifest YAML as it was submitted, before resolution. Unset for
+    /// revisions applied from an already parsed manifest.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub manifest_digest: Option<String>,
+    /// Revision this one restored, when it was created by a rollback.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rollback_of: Option<u64>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeviceServiceSnapshot {
     pub peer_id: String,
@@ -250,12 +501,18 @@ pub struct DeviceServiceSnapshot {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeviceService {
     pub name: String,
+    /// Stable identity of the recipe the service was installed from, shared by every device
+    /// running the same service under possibly different names.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub definition_id: Option<String>,
     pub runtime: RuntimeKind,
     #[serde(default)]
     pub metadata: DeviceServiceMetadata,
     #[serde(default)]
     pub endpoints: Vec<DeviceServiceEndpoint>,
     pub status: ServiceStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resources: Option<ServiceResourceUsage>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -281,6 +538,18 @@ pub struct ServiceExposeEndpointBinding {
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ServiceLogsOptions {
     pub tail: Option<String>,
+    /// Only output written after this mark, which [`super::RuntimeProvider::log_mark`] took.
+    #[serde(skip)]
+    pub since: Option<ServiceLogMark>,
+}
+
+/// How far a service log reached at some moment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServiceLogMark {
+    /// Docker logs are filtered by the engine's timestamps.
+    Time(SystemTime),
+    /// Log files only grow while a service exists, so a byte offset is exact.
+    Offset(u64),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1,28 +1,33 @@
 use std::{
     collections::HashMap,
     fs::{self, OpenOptions},
-    io::Read,
+    io::{Read, Seek, SeekFrom},
     path::{Path, PathBuf},
     process::Stdio,
     sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
 };
 
 use anyhow::{Context, Result, bail};
 use async_trait::async_trait;
-use fungi_config::paths::FungiPaths;
-use fungi_docker_agent::LogsOptions;
+use fungi_config::{paths::FungiPaths, runtime::ProcessSandboxMode};
+use fungi_docker_agent::{CpuSample, EngineInfo, ExecSpec, LogsOptions};
 use parking_lot::Mutex;
 use tokio::process::Child;
 
-use crate::controls::DockerControl;
+use crate::{controls::DockerControl, secrets::SecretStore};
 
 use super::{
+    exec::{ServiceExecRequest, ServiceExecSession, attach_child_exec, attach_docker_exec},
     helpers::{
-        build_wasmtime_command, build_wasmtime_state, docker_spec_from_manifest_with_name,
-        ensure_manifest_mount_dirs, map_docker_instance, map_wasmtime_instance,
-        refresh_child_state, tail_lines,
+        build_process_command, build_process_exec_command, build_process_state,
+        build_wasmtime_command, build_wasmtime_state, confine_process_command,
+        docker_spec_from_manifest_with_name, ensure_manifest_mount_dirs, map_docker_instance,
+        map_process_instance, map_wasmtime_instance, refresh_child_state,
+        refresh_process_child_state, tail_lines,
     },
     model::*,
+    resources::ChildResourceSampler,
 };
 
 #[async_trait]
@@ -34,6 +39,18 @@ pub trait RuntimeProvider: Send + Sync {
     async fn remove(&self, name: &str) -> Result<()>;
     async fn inspect(&self, name: &str) -> Result<ServiceInstance>;
     async fn logs(&self, name: &str, options: &ServiceLogsOptions) -> Result<ServiceLogs>;
+    /// Where the log of `name` ends now, for reading only what is written later.
+    async fn log_mark(&self, name: &str) -> Result<ServiceLogMark>;
+    /// Samples a running service; `None` when it is not running.
+    async fn resource_usage(&self, name: &str) -> Result<Option<ServiceResourceUsage>>;
+    /// Runs `request.command` alongside the running service `name`; `request.service` is the
+    /// user-facing name and is not consulted.
+    async fn exec(&self, name: &str, request: &ServiceExecRequest) -> Result<ServiceExecSession>;
+}
+
+/// The process runtime relies on Linux namespaces, Landlock and seccomp for confinement.
+pub const fn process_runtime_supported() -> bool {
+    cfg!(target_os = "linux")
 }
 
 pub const fn wasmtime_runtime_supported() -> bool {
@@ -43,21 +60,33 @@ pub const fn wasmtime_runtime_supported() -> bool {
 #[derive(Clone)]
 pub struct DockerRuntimeProvider {
     docker: DockerControl,
+    secrets: SecretStore,
+    /// Last CPU sample per container, for engines that skip the second sample of one-shot stats.
+    cpu_samples: Arc<Mutex<HashMap<String, CpuSample>>>,
 }
 
 impl DockerRuntimeProvider {
-    pub fn new(docker: DockerControl) -> Self {
-        Self { docker }
+    pub fn new(docker: DockerControl, secrets: SecretStore) -> Self {
+        Self {
+            docker,
+            secrets,
+            cpu_samples: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub fn engine(&self) -> Option<EngineInfo> {
+        self.docker.engine()
     }
 
     pub(crate) async fn pull_with_container_name(
         &self,
         manifest: &ServiceManifest,
         container_name: &str,
+        origin: ServiceOrigin,
     ) -> Result<ServiceInstance> {
         ensure_manifest_mount_dirs(manifest)?;
-        let spec = docker_spec_from_manifest_with_name(manifest, container_name)?;
-        let details = self.docker.create_container(&spec).await?;
+        let spec = docker_spec_from_manifest_with_name(manifest, container_name, &self.secrets)?;
+        let details = self.docker.create_container(&spec, origin).await?;
         Ok(map_docker_instance(details))
     }
 }
@@ -69,7 +98,7 @@ impl RuntimeProvider for DockerRuntimeProvider {
     }
 
     async fn pull(&self, manifest: &ServiceManifest) -> Result<ServiceInstance> {
-        self.pull_with_container_name(manifest, &manifest.name)
+        self.pull_with_container_name(manifest, &manifest.name, ServiceOrigin::Local)
             .await
     }
 
@@ -99,6 +128,13 @@ impl RuntimeProvider for DockerRuntimeProvider {
                     stdout: true,
                     stderr: true,
                     tail: options.tail.clone(),
+                    since: match options.since {
+                        Some(ServiceLogMark::Time(time)) => {
+                            let since = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+                            Some(format!("{}.{:09}", since.as_secs(), since.subsec_nanos()))
+                        }
+                        _ => None,
+                    },
                 },
             )
             .await?;
@@ -107,6 +143,48 @@ impl RuntimeProvider for DockerRuntimeProvider {
             text: logs.text,
         })
     }
+
+    async fn log_mark(&self, _name: &str) -> Result<ServiceLogMark> {
+        Ok(ServiceLogMark::Time(SystemTime::now()))
+    }
+
+    async fn resource_usage(&self, name: &str) -> Result<Option<ServiceResourceUsage>> {
+        let stats = self.docker.container_stats(name).await?;
+        let Some(cpu) = stats.cpu else {
+            self.cpu_samples.lock().remove(name);
+            return Ok(None);
+        };
+        let previous = self.cpu_samples.lock().insert(name.to_string(), cpu);
+        Ok(Some(ServiceResourceUsage {
+            cpu_percent: stats.cpu_percent(previous.as_ref()),
+            memory_bytes: stats.memory_usage_bytes,
+            memory_limit_bytes: stats.memory_limit_bytes,
+            network_rx_bytes: stats.network_rx_bytes,
+            network_tx_bytes: stats.network_tx_bytes,
+            block_read_bytes: stats.block_read_bytes,
+            block_write_bytes: stats.block_write_bytes,
+        }))
+    }
+
+    async fn exec(&self, name: &str, request: &ServiceExecRequest) -> Result<ServiceExecSession> {
+        let session = self
+            .docker
+            .exec(
+                name,
+                &ExecSpec {
+                    command: request.command.clone(),
+                    tty: request.tty,
+                    ..Default::default()
+                },
+            )
+            .await?;
+        if let (true, Some(size)) = (request.tty, request.size) {
+            self.docker
+                .resize_exec(&session.id, size.rows, size.cols)
+                .await?;
+        }
+        Ok(attach_docker_exec(self.docker.clone(), session))
+    }
 }
 
 #[derive(Clone)]
@@ -114,8 +192,10 @@ pub struct WasmtimeRuntimeProvider {
     runtime_root: PathBuf,
     launcher_path: PathBuf,
     fungi_home: PathBuf,
+    secrets: SecretStore,
     allowed_host_paths: Arc<Mutex<Vec<PathBuf>>>,
     services: Arc<Mutex<HashMap<String, WasmtimeServiceState>>>,
+    resources: ChildResourceSampler,
 }
 
 pub(crate) struct WasmtimeServiceState {
@@ -160,9 +240,11 @@ impl WasmtimeRuntimeProvider {
         Self {
             runtime_root,
             launcher_path,
+            secrets: SecretStore::new(&fungi_home),
             fungi_home,
             allowed_host_paths: Arc::new(Mutex::new(allowed_host_paths)),
             services: Arc::new(Mutex::new(HashMap::new())),
+            resources: ChildResourceSampler::default(),
         }
     }
 
@@ -171,6 +253,10 @@ impl WasmtimeRuntimeProvider {
             with_default_mount_roots(&self.fungi_home, allowed_host_paths);
     }
 
+    pub(crate) fn secrets(&self) -> &SecretStore {
+        &self.secrets
+    }
+
     pub fn has_service(&self, handle: &str) -> bool {
         self.services.lock().contains_key(handle)
     }
@@ -289,7 +375,8 @@ impl RuntimeProvider for WasmtimeRuntimeProvider {
             bail!("wasmtime service is already running: {handle}");
         }
 
-        let mut command = build_wasmtime_command(&self.launcher_path, &self.fungi_home, state)?;
+        let mut command =
+            build_wasmtime_command(&self.launcher_path, &self.fungi_home, state, &self.secrets)?;
         let stdout = OpenOptions::new()
             .create(true)
             .append(true)
@@ -377,18 +464,43 @@ impl RuntimeProvider for WasmtimeRuntimeProvider {
                 .clone()
         };
 
-        let mut raw = Vec::new();
-        if log_file_path.exists() {
-            fs::File::open(&log_file_path)
-                .and_then(|mut file| file.read_to_end(&mut raw))
-                .with_context(|| format!("Failed to read log file: {}", log_file_path.display()))?;
-        }
+        read_log_file(&log_file_path, options)
+    }
 
-        let text = String::from_utf8_lossy(&raw).to_string();
-        Ok(ServiceLogs {
-            raw,
-            text: tail_lines(&text, options.tail.as_deref()),
-        })
+    async fn log_mark(&self, handle: &str) -> Result<ServiceLogMark> {
+        let log_file_path = {
+            let services = self.services.lock();
+            services
+                .get(handle)
+                .ok_or_else(|| anyhow::anyhow!("wasmtime service not found: {handle}"))?
+                .log_file_path
+                .clone()
+        };
+        log_file_mark(&log_file_path)
+    }
+
+    async fn resource_usage(&self, handle: &str) -> Result<Option<ServiceResourceUsage>> {
+        let pid = {
+            let mut services = self.services.lock();
+            let state = services
+                .get_mut(handle)
+                .ok_or_else(|| anyhow::anyhow!("wasmtime service not found: {handle}"))?;
+            refresh_child_state(state)?;
+            state.child.as_ref().and_then(Child::id)
+        };
+        Ok(pid.and_then(|pid| self.resources.sample(pid)))
+    }
+
+    /// A WASI component cannot host a second program, and running the command on the host would
+    /// escape the component's sandbox, so wasmtime services do not support exec.
+    async fn exec(
+        &self,
+        handle: &str,
+        _request: &ServiceExecRequest,
+    ) -> Result<ServiceExecSession> {
+        bail!(
+            "wasmtime service {handle} cannot run commands; exec needs a docker or process service"
+        )
     }
 }
 
@@ -434,3 +546,337 @@ impl WasmtimeRuntimeProvider {
         Ok(())
     }
 }
+
+/// Runs native host binaries in place, confined by [`ProcessSandboxMode`]-controlled sandboxing.
+#[derive(Clone)]
+pub struct ProcessRuntimeProvider {
+    runtime_root: PathBuf,
+    fungi_home: PathBuf,
+    secrets: SecretStore,
+    sandbox_mode: ProcessSandboxMode,
+    allowed_host_paths: Arc<Mutex<Vec<PathBuf>>>,
+    services: Arc<Mutex<HashMap<String, ProcessServiceState>>>,
+    resources: ChildResourceSampler,
+}
+
+pub(crate) struct ProcessServiceState {
+    pub(crate) manifest: ServiceManifest,
+    pub(crate) binary: PathBuf,
+    pub(crate) service_dir: PathBuf,
+    pub(crate) appdata_dir: PathBuf,
+    pub(crate) runtime_dir: PathBuf,
+    pub(crate) tmp_dir: PathBuf,
+    pub(crate) log_file_path: PathBuf,
+    pub(crate) child: Option<Child>,
+    pub(crate) last_exit_code: Option<i32>,
+    /// Sandbox layers the running child goes without, shown in its status.
+    pub(crate) sandbox_degradation: Option<String>,
+}
+
+impl ProcessRuntimeProvider {
+    pub fn new(
+        runtime_root: PathBuf,
+        fungi_home: PathBuf,
+        allowed_host_paths: Vec<PathBuf>,
+        sandbox_mode: ProcessSandboxMode,
+    ) -> Self {
+        let allowed_host_paths = with_default_mount_roots(&fungi_home, allowed_host_paths);
+        Self {
+            runtime_root,
+            secrets: SecretStore::new(&fungi_home),
+            fungi_home,
+            sandbox_mode,
+            allowed_host_paths: Arc::new(Mutex::new(allowed_host_paths)),
+            services: Arc::new(Mutex::new(HashMap::new())),
+            resources: ChildResourceSampler::default(),
+        }
+    }
+
+    pub fn update_allowed_host_paths(&self, allowed_host_paths: Vec<PathBuf>) {
+        *self.allowed_host_paths.lock() =
+            with_default_mount_roots(&self.fungi_home, allowed_host_paths);
+    }
+
+    pub fn has_service(&self, handle: &str) -> bool {
+        self.services.lock().contains_key(handle)
+    }
+
+    pub(crate) fn restore(&self, manifest: &ServiceManifest, local_service_id: &str) -> Result<()> {
+        let state = self.build_state(manifest, local_service_id)?;
+        let mut services = self.services.lock();
+        services.entry(manifest.name.clone()).or_insert(state);
+        Ok(())
+    }
+
+    pub(crate) async fn pull_with_local_service_id(
+        &self,
+        manifest: &ServiceManifest,
+        local_service_id: &str,
+    ) -> Result<ServiceInstance> {
+        let state = self.build_state(manifest, local_service_id)?;
+        {
+            let mut services = self.services.lock();
+            if services.contains_key(&manifest.name) {
+                bail!("service already exists: {}", manifest.name);
+            }
+            services.insert(manifest.name.clone(), state);
+        }
+
+        self.inspect(&manifest.name).await
+    }
+
+    pub(crate) async fn remove_with_local_service_id(
+        &self,
+        handle: &str,
+        local_service_id: &str,
+    ) -> Result<()> {
+        self.stop(handle).await.ok();
+
+        let state = self.services.lock().remove(handle);
+        let (service_dir, runtime_dir) = state
+            .map(|state| (state.service_dir, state.runtime_dir))
+            .unwrap_or_else(|| {
+                (
+                    self.service_artifacts_dir(local_service_id),
+                    self.runtime_root.join("process").join(handle),
+                )
+            });
+
+        for dir in [service_dir, runtime_dir] {
+            if dir.exists() {
+                remove_dir_all_with_retry(&dir)
+                    .with_context(|| format!("Failed to remove directory: {}", dir.display()))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn build_state(
+        &self,
+        manifest: &ServiceManifest,
+        local_service_id: &str,
+    ) -> Result<ProcessServiceState> {
+        let allowed_host_paths = self.allowed_host_paths.lock().clone();
+        build_process_state(
+            &self.runtime_root,
+            &self.service_artifacts_dir(local_service_id),
+            &FungiPaths::from_fungi_home(&self.fungi_home).service_appdata_dir(local_service_id),
+            &allowed_host_paths,
+            manifest,
+        )
+    }
+
+    fn service_artifacts_dir(&self, local_service_id: &str) -> PathBuf {
+        FungiPaths::from_fungi_home(&self.fungi_home).service_artifacts_dir(local_service_id)
+    }
+}
+
+#[async_trait]
+impl RuntimeProvider for ProcessRuntimeProvider {
+    fn runtime_kind(&self) -> RuntimeKind {
+        RuntimeKind::Process
+    }
+
+    async fn pull(&self, manifest: &ServiceManifest) -> Result<ServiceInstance> {
+        self.pull_with_local_service_id(manifest, &manifest.name)
+            .await
+    }
+
+    async fn start(&self, handle: &str) -> Result<()> {
+        let mut services = self.services.lock();
+        let state = services
+            .get_mut(handle)
+            .ok_or_else(|| anyhow::anyhow!("process service not found: {handle}"))?;
+
+        refresh_process_child_state(state)?;
+        if state.child.is_some() {
+            bail!("process service is already running: {handle}");
+        }
+
+        let mut command = build_process_command(state, &self.secrets)?;
+        let mut log_file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&state.log_file_path)
+            .with_context(|| {
+                format!(
+                    "Failed to open process log: {}",
+                    state.log_file_path.display()
+                )
+            })?;
+
+        let sandbox_degradation =
+            confine_process_command(state, self.sandbox_mode, &mut log_file, &mut command)?;
+
+        let stderr = log_file.try_clone().with_context(|| {
+            format!(
+                "Failed to open process log: {}",
+                state.log_file_path.display()
+            )
+        })?;
+        command.stdout(Stdio::from(log_file));
+        command.stderr(Stdio::from(stderr));
+
+        let child = command
+            .spawn()
+            .with_context(|| format!("Failed to spawn process service {handle}"))?;
+        state.child = Some(child);
+        state.last_exit_code = None;
+        state.sandbox_degradation = sandbox_degradation;
+        Ok(())
+    }
+
+    async fn stop(&self, handle: &str) -> Result<()> {
+        let child = {
+            let mut services = self.services.lock();
+            let state = services
+                .get_mut(handle)
+                .ok_or_else(|| anyhow::anyhow!("process service not found: {handle}"))?;
+            refresh_process_child_state(state)?;
+            state.child.take()
+        };
+
+        let Some(mut child) = child else {
+            return Ok(());
+        };
+
+        // The service runs in its own process group, so helpers it forked go down with it.
+        #[cfg(target_os = "linux")]
+        if let Some(pid) = child.id() {
+            // SAFETY: signalling a process group we created; failure only means it already exited.
+            unsafe {
+                libc::kill(-(pid as libc::pid_t), libc::SIGKILL);
+            }
+        }
+        child
+            .kill()
+            .await
+            .context("Failed to kill process service")?;
+        let status = child
+            .wait()
+            .await
+            .context("Failed to wait for process service")?;
+
+        let mut services = self.services.lock();
+        let state = services
+            .get_mut(handle)
+            .ok_or_else(|| anyhow::anyhow!("process service not found after stop: {handle}"))?;
+        state.last_exit_code = status.code();
+        state.child = None;
+        Ok(())
+    }
+
+    async fn remove(&self, handle: &str) -> Result<()> {
+        self.remove_with_local_service_id(handle, handle).await
+    }
+
+    async fn inspect(&self, handle: &str) -> Result<ServiceInstance> {
+        let mut services = self.services.lock();
+        let state = services
+            .get_mut(handle)
+            .ok_or_else(|| anyhow::anyhow!("process service not found: {handle}"))?;
+        refresh_process_child_state(state)?;
+        Ok(map_process_instance(handle, state))
+    }
+
+    async fn logs(&self, handle: &str, options: &ServiceLogsOptions) -> Result<ServiceLogs> {
+        let log_file_path = {
+            let services = self.services.lock();
+            services
+                .get(handle)
+                .ok_or_else(|| anyhow::anyhow!("process service not found: {handle}"))?
+                .log_file_path
+                .clone()
+        };
+        read_log_file(&log_file_path, options)
+    }
+
+    async fn log_mark(&self, handle: &str) -> Result<ServiceLogMark> {
+        let log_file_path = {
+            let services = self.services.lock();
+            services
+                .get(handle)
+                .ok_or_else(|| anyhow::anyhow!("process service not found: {handle}"))?
+                .log_file_path
+                .clone()
+        };
+        log_file_mark(&log_file_path)
+    }
+
+    async fn resource_usage(&self, handle: &str) -> Result<Option<ServiceResourceUsage>> {
+        let pid = {
+            let mut services = self.services.lock();
+            let state = services
+                .get_mut(handle)
+                .ok_or_else(|| anyhow::anyhow!("process service not found: {handle}"))?;
+            refresh_process_child_state(state)?;
+            state.child.as_ref().and_then(Child::id)
+        };
+        Ok(pid.and_then(|pid| self.resources.sample(pid)))
+    }
+
+    async fn exec(&self, handle: &str, request: &ServiceExecRequest) -> Result<ServiceExecSession> {
+        let mut command = {
+            let mut services = self.services.lock();
+            let state = services
+                .get_mut(handle)
+                .ok_or_else(|| anyhow::anyhow!("process service not found: {handle}"))?;
+            refresh_process_child_state(state)?;
+            if state.child.is_none() {
+                bail!("process service is not running: {handle}");
+            }
+
+            let mut command = build_process_exec_command(state, &self.secrets, &request.command)?;
+            let mut log_file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&state.log_file_path)
+                .with_context(|| {
+                    format!(
+                        "Failed to open process log: {}",
+                        state.log_file_path.display()
+                    )
+                })?;
+            confine_process_command(state, self.sandbox_mode, &mut log_file, &mut command)?;
+            command
+        };
+        let child = command
+            .spawn()
+            .with_context(|| format!("Failed to exec into process service {handle}"))?;
+        Ok(attach_child_exec(child))
+    }
+}
+
+fn read_log_file(log_file_path: &Path, options: &ServiceLogsOptions) -> Result<ServiceLogs> {
+    let mut raw = Vec::new();
+    if log_file_path.exists() {
+        let offset = match options.since {
+            Some(ServiceLogMark::Offset(offset)) => offset,
+            _ => 0,
+        };
+        fs::File::open(log_file_path)
+            .and_then(|mut file| {
+                // A log shorter than the mark was recreated, so all of it is new.
+                if offset <= file.metadata()?.len() {
+                    file.seek(SeekFrom::Start(offset))?;
+                }
+                file.read_to_end(&mut raw)
+            })
+            .with_context(|| format!("Failed to read log file: {}", log_file_path.display()))?;
+    }
+
+    let text = String::from_utf8_lossy(&raw).to_string();
+    Ok(ServiceLogs {
+        raw,
+        text: tail_lines(&text, options.tail.as_deref()),
+    })
+}
+
+fn log_file_mark(log_file_path: &Path) -> Result<ServiceLogMark> {
+    match fs::metadata(log_file_path) {
+        Ok(metadata) => Ok(ServiceLogMark::Offset(metadata.len())),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(ServiceLogMark::Offset(0)),
+        Err(error) => Err(error)
+            .with_context(|| format!("Failed to read log file: {}", log_file_path.display())),
+    }
+}
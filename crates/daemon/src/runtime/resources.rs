@@ -0,0 +1,55 @@
+//! Resource sampling for services the daemon runs as child processes.
+
+use std::{collections::HashSet, sync::Arc};
+
+use parking_lot::Mutex;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+
+use super::model::ServiceResourceUsage;
+
+/// Samples child processes through one shared [`System`], which keeps the previous CPU time of
+/// every process it has seen so the next refresh yields usage over the interval in between.
+#[derive(Clone, Default)]
+pub(crate) struct ChildResourceSampler {
+    inner: Arc<Mutex<SamplerState>>,
+}
+
+#[derive(Default)]
+struct SamplerState {
+    system: System,
+    /// Processes refreshed at least once; their first sample has no CPU interval yet.
+    seen: HashSet<Pid>,
+}
+
+impl ChildResourceSampler {
+    pub(crate) fn sample(&self, pid: u32) -> Option<ServiceResourceUsage> {
+        let pid = Pid::from_u32(pid);
+        let mut state = self.inner.lock();
+        state.system.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&[pid]),
+            true,
+            ProcessRefreshKind::nothing()
+                .with_cpu()
+                .with_memory()
+                .with_disk_usage(),
+        );
+
+        let Some(process) = state.system.process(pid) else {
+            state.seen.remove(&pid);
+            return None;
+        };
+        let disk = process.disk_usage();
+        let usage = ServiceResourceUsage {
+            cpu_percent: state
+                .seen
+                .contains(&pid)
+                .then(|| f64::from(process.cpu_usage())),
+            memory_bytes: Some(process.memory()),
+            block_read_bytes: Some(disk.total_read_bytes),
+            block_write_bytes: Some(disk.total_written_bytes),
+            ..Default::default()
+        };
+        state.seen.insert(pid);
+        Some(usage)
+    }
+}
@@ -0,0 +1,403 @@
+//! Confinement for native process services.
+//!
+//! Everything that allocates or can fail with a rich error is prepared in the daemon before the
+//! fork. The `pre_exec` hook then only issues raw syscalls in the child: new IPC/UTS (and, when
+//! unprivileged, user) namespaces, dropping root to [`UNPRIVILEGED_ID`], `no_new_privs`, the
+//! prepared Landlock ruleset and finally a seccomp filter denying host-administration syscalls.
+//! The Landlock domain also scopes signals, so a service can only signal its own processes, not
+//! the daemon or anything else running as the same user.
+//!
+//! A daemon running as root hands the service's own directories to that user first; other
+//! writable mounts must already be writable by it.
+
+use std::{
+    fs, io,
+    os::fd::{AsRawFd, OwnedFd},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result, bail};
+use fungi_config::runtime::ProcessSandboxMode;
+use landlock::{
+    ABI, Access, AccessFs, AccessNet, CompatLevel, Compatible, NetPort, Ruleset, RulesetAttr,
+    RulesetCreatedAttr, Scope, path_beneath_rules,
+};
+use seccompiler::{BpfProgram, SeccompAction, SeccompFilter, TargetArch};
+use tokio::process::Command;
+
+const LANDLOCK_ABI: ABI = ABI::V6;
+
+/// System locations a dynamically linked binary needs to load, resolve names and verify TLS
+/// peers. The rest of /etc, /proc and /sys stays hidden.
+const SYSTEM_READ_PATHS: &[&str] = &[
+    "/usr",
+    "/lib",
+    "/lib64",
+    "/lib32",
+    "/bin",
+    "/sbin",
+    "/etc/ld.so.cache",
+    "/etc/ld.so.conf",
+    "/etc/ld.so.conf.d",
+    "/etc/localtime",
+    "/etc/hosts",
+    "/etc/host.conf",
+    "/etc/resolv.conf",
+    "/etc/nsswitch.conf",
+    "/etc/gai.conf",
+    "/etc/services",
+    "/etc/protocols",
+    "/etc/passwd",
+    "/etc/group",
+    "/etc/ssl",
+    "/etc/pki",
+    "/etc/ca-certificates",
+    "/proc/cpuinfo",
+    "/proc/meminfo",
+    "/sys/devices/system/cpu",
+];
+
+/// The `nobody` user and group, which services run as when the daemon runs as root.
+const UNPRIVILEGED_ID: u32 = 65534;
+
+const DEVICE_PATHS: &[&str] = &["/dev/null", "/dev/zero", "/dev/random", "/dev/urandom"];
+
+const DENIED_SYSCALLS: &[libc::c_long] = &[
+    libc::SYS_mount,
+    libc::SYS_umount2,
+    libc::SYS_pivot_root,
+    libc::SYS_swapon,
+    libc::SYS_swapoff,
+    libc::SYS_reboot,
+    libc::SYS_kexec_load,
+    libc::SYS_kexec_file_load,
+    libc::SYS_init_module,
+    libc::SYS_finit_module,
+    libc::SYS_delete_module,
+    libc::SYS_ptrace,
+    libc::SYS_process_vm_readv,
+    libc::SYS_process_vm_writev,
+    libc::SYS_bpf,
+    libc::SYS_perf_event_open,
+    libc::SYS_keyctl,
+    libc::SYS_add_key,
+    libc::SYS_request_key,
+    libc::SYS_setns,
+    libc::SYS_unshare,
+    libc::SYS_open_by_handle_at,
+    libc::SYS_userfaultfd,
+    libc::SYS_acct,
+    libc::SYS_quotactl,
+    libc::SYS_settimeofday,
+    libc::SYS_clock_settime,
+    libc::SYS_adjtimex,
+    libc::SYS_syslog,
+];
+
+/// What a sandboxed process may touch besides the system paths it needs to run.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SandboxSpec {
+    pub(crate) hostname: String,
+    pub(crate) read_only: Vec<PathBuf>,
+    pub(crate) read_write: Vec<PathBuf>,
+    /// Directories holding only the service's own data, handed to [`UNPRIVILEGED_ID`] when the
+    /// daemon runs as root.
+    pub(crate) owned: Vec<PathBuf>,
+    /// TCP ports the process may bind; every other port is refused.
+    pub(crate) bind_tcp_ports: Vec<u16>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LandlockSupport {
+    Full,
+    /// The kernel lacks some of the access rights the ruleset handles, so those go unenforced.
+    Partial,
+    Unavailable,
+}
+
+impl LandlockSupport {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Full => "enforced",
+            Self::Partial => "partial",
+            Self::Unavailable => "unavailable",
+        }
+    }
+}
+
+pub(crate) struct PreparedSandbox {
+    strict: bool,
+    namespace_flags: libc::c_int,
+    uid_map: Vec<u8>,
+    gid_map: Vec<u8>,
+    /// Set when the daemon runs as root; the child switches to these ids before anything else.
+    drop_to: Option<(libc::uid_t, libc::gid_t)>,
+    hostname: Vec<u8>,
+    landlock: Option<OwnedFd>,
+    landlock_support: LandlockSupport,
+    seccomp: Option<BpfProgram>,
+}
+
+impl PreparedSandbox {
+    pub(crate) fn prepare(spec: &SandboxSpec, mode: ProcessSandboxMode) -> Result<Self> {
+        let strict = mode == ProcessSandboxMode::Strict;
+
+        let (landlock, landlock_support) =
+            match build_landlock_ruleset(spec, CompatLevel::HardRequirement) {
+                Ok(landlock) => (landlock, LandlockSupport::Full),
+                Err(_) => match build_landlock_ruleset(spec, CompatLevel::BestEffort)? {
+                    Some(landlock) => (Some(landlock), LandlockSupport::Partial),
+                    None => (None, LandlockSupport::Unavailable),
+                },
+            };
+        match landlock_support {
+            LandlockSupport::Full => {}
+            _ if !strict => {}
+            LandlockSupport::Partial => bail!(
+                "process sandbox is strict but this kernel only enforces part of the Landlock rules"
+            ),
+            LandlockSupport::Unavailable => {
+                bail!("process sandbox is strict but Landlock is not available on this kernel")
+            }
+        }
+
+        let seccomp = match build_seccomp_filter() {
+            Ok(filter) => Some(filter),
+            Err(error) if strict => {
+                return Err(error.context("process sandbox is strict but seccomp is unavailable"));
+            }
+            Err(error) => {
+                log::warn!("Process services run without a seccomp filter: {error:#}");
+                None
+            }
+        };
+
+        // SAFETY: geteuid/getegid cannot fail.
+        let (uid, gid) = unsafe { (libc::geteuid(), libc::getegid()) };
+        let mut namespace_flags = libc::CLONE_NEWIPC | libc::CLONE_NEWUTS;
+        let drop_to = if uid == 0 {
+            for dir in &spec.owned {
+                chown_tree(dir, UNPRIVILEGED_ID, UNPRIVILEGED_ID).with_context(|| {
+                    format!(
+                        "Failed to hand {} to the unprivileged service user",
+                        dir.display()
+                    )
+                })?;
+            }
+            Some((UNPRIVILEGED_ID, UNPRIVILEGED_ID))
+        } else {
+            namespace_flags |= libc::CLONE_NEWUSER;
+            None
+        };
+
+        let mut hostname = spec.hostname.as_bytes().to_vec();
+        hostname.truncate(64);
+
+        Ok(Self {
+            strict,
+            namespace_flags,
+            uid_map: format!("{uid} {uid} 1\n").into_bytes(),
+            gid_map: format!("{gid} {gid} 1\n").into_bytes(),
+            drop_to,
+            hostname,
+            landlock,
+            landlock_support,
+            seccomp,
+        })
+    }
+
+    /// One line for the service log describing which layers will be enforced.
+    pub(crate) fn summary(&self) -> String {
+        format!(
+            "fungi: process sandbox (landlock: {}, seccomp: {}, user: {}, mode: {})",
+            self.landlock_support.as_str(),
+            if self.seccomp.is_some() {
+                "enforced"
+            } else {
+                "unavailable"
+            },
+            match self.drop_to {
+                Some((uid, _)) => uid.to_string(),
+                None => "daemon".to_string(),
+            },
+            if self.strict { "strict" } else { "best-effort" },
+        )
+    }
+
+    /// Why the service runs less confined than intended, shown with its status; `None` when every
+    /// layer is enforced.
+    pub(crate) fn degradation(&self) -> Option<String> {
+        if self.landlock_support == LandlockSupport::Full && self.seccomp.is_some() {
+            return None;
+        }
+        Some(format!(
+            "sandbox degraded: landlock {}, seccomp {}",
+            self.landlock_support.as_str(),
+            if self.seccomp.is_some() {
+                "enforced"
+            } else {
+                "unavailable"
+            },
+        ))
+    }
+
+    pub(crate) fn apply_to(self, command: &mut Command) {
+        // SAFETY: the hook only issues async-signal-safe syscalls on buffers prepared above.
+        unsafe {
+            command.pre_exec(move || self.enter());
+        }
+    }
+
+    fn enter(&self) -> io::Result<()> {
+        if let Err(error) = self.enter_namespaces() {
+            if self.strict {
+                return Err(error);
+            }
+            write_stderr(b"fungi: process sandbox: namespaces unavailable, continuing\n");
+        }
+
+        // Never optional: a service must not keep the daemon's root credentials.
+        if let Some((uid, gid)) = self.drop_to {
+            // SAFETY: plain credential syscalls; groups are cleared before the ids change.
+            unsafe {
+                if libc::setgroups(0, std::ptr::null()) != 0
+                    || libc::setgid(gid) != 0
+                    || libc::setuid(uid) != 0
+                {
+                    return Err(io::Error::last_os_error());
+                }
+            }
+        }
+
+        // SAFETY: plain prctl/syscall invocations with valid arguments.
+        unsafe {
+            if libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            if let Some(ruleset) = &self.landlock
+                && libc::syscall(libc::SYS_landlock_restrict_self, ruleset.as_raw_fd(), 0) != 0
+            {
+                return Err(io::Error::last_os_error());
+            }
+        }
+
+        if let Some(filter) = &self.seccomp
+            && seccompiler::apply_filter(filter).is_err()
+        {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    fn enter_namespaces(&self) -> io::Result<()> {
+        // SAFETY: the child is single threaded after fork, which unshare(CLONE_NEWUSER) requires.
+        unsafe {
+            if libc::unshare(self.namespace_flags) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+        }
+        if self.namespace_flags & libc::CLONE_NEWUSER != 0 {
+            write_proc_file(c"/proc/self/setgroups", b"deny")?;
+            write_proc_file(c"/proc/self/uid_map", &self.uid_map)?;
+            write_proc_file(c"/proc/self/gid_map", &self.gid_map)?;
+        }
+        if !self.hostname.is_empty() {
+            // SAFETY: the buffer outlives the call and its length is passed alongside.
+            unsafe {
+                if libc::sethostname(self.hostname.as_ptr().cast(), self.hostname.len()) != 0 {
+                    return Err(io::Error::last_os_error());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// With [`CompatLevel::HardRequirement`], fails unless the kernel enforces every handled access
+/// right; with [`CompatLevel::BestEffort`], drops what it doesn't support.
+fn build_landlock_ruleset(spec: &SandboxSpec, compat: CompatLevel) -> Result<Option<OwnedFd>> {
+    let read_only = AccessFs::from_read(LANDLOCK_ABI);
+    let read_write = AccessFs::from_all(LANDLOCK_ABI);
+
+    let system_paths = existing_paths(SYSTEM_READ_PATHS.iter().map(Path::new));
+    let device_paths = existing_paths(DEVICE_PATHS.iter().map(Path::new));
+    let read_only_paths = existing_paths(spec.read_only.iter().map(PathBuf::as_path));
+    let read_write_paths = existing_paths(spec.read_write.iter().map(PathBuf::as_path));
+
+    let mut ruleset = Ruleset::default()
+        .set_compatibility(compat)
+        .handle_access(read_write)?
+        .handle_access(AccessNet::BindTcp)?
+        .scope(Scope::Signal)?
+        .create()?
+        .add_rules(path_beneath_rules(system_paths, read_only))?
+        .add_rules(path_beneath_rules(device_paths, read_write))?
+        .add_rules(path_beneath_rules(read_only_paths, read_only))?
+        .add_rules(path_beneath_rules(read_write_paths, read_write))?;
+    for port in &spec.bind_tcp_ports {
+        ruleset = ruleset.add_rule(NetPort::new(*port, AccessNet::BindTcp))?;
+    }
+    Ok(ruleset.into())
+}
+
+// `c_long` is only 32 bits on some targets, so the conversion is not useless everywhere.
+#[allow(clippy::useless_conversion)]
+fn build_seccomp_filter() -> Result<BpfProgram> {
+    let arch = TargetArch::try_from(std::env::consts::ARCH)
+        .context("unsupported architecture for seccomp")?;
+    let rules = DENIED_SYSCALLS
+        .iter()
+        .map(|syscall| (i64::from(*syscall), Vec::new()))
+        .collect();
+    let filter = SeccompFilter::new(
+        rules,
+        SeccompAction::Allow,
+        SeccompAction::Errno(libc::EPERM as u32),
+        arch,
+    )?;
+    Ok(filter.try_into()?)
+}
+
+fn chown_tree(path: &Path, uid: u32, gid: u32) -> io::Result<()> {
+    if !path.exists() {
+        return Ok(());
+    }
+    std::os::unix::fs::lchown(path, Some(uid), Some(gid))?;
+    if fs::symlink_metadata(path)?.is_dir() {
+        for entry in fs::read_dir(path)? {
+            chown_tree(&entry?.path(), uid, gid)?;
+        }
+    }
+    Ok(())
+}
+
+fn existing_paths<'a>(paths: impl Iterator<Item = &'a Path>) -> Vec<PathBuf> {
+    paths
+        .filter(|path| path.exists())
+        .map(Path::to_path_buf)
+        .collect()
+}
+
+fn write_proc_file(path: &std::ffi::CStr, contents: &[u8]) -> io::Result<()> {
+    // SAFETY: `path` is NUL-terminated and `contents` outlives the write.
+    unsafe {
+        let fd = libc::open(path.as_ptr(), libc::O_WRONLY | libc::O_CLOEXEC);
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let result = if libc::write(fd, contents.as_ptr().cast(), contents.len()) < 0 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(())
+        };
+        libc::close(fd);
+        result
+    }
+}
+
+fn write_stderr(message: &[u8]) {
+    // SAFETY: writing a static buffer to an inherited descriptor.
+    unsafe {
+        libc::write(libc::STDERR_FILENO, message.as_ptr().cast(), message.len());
+    }
+}
@@ -0,0 +1,259 @@
+//! Cron expressions for scheduled job services.
+//!
+//! Supports the classic five fields (minute, hour, day of month, month, day of week) with `*`,
+//! lists, ranges, steps and three-letter month/day names, plus the `@hourly`-style macros. As in
+//! Vixie cron, when both day fields are restricted a time matches if either of them does.
+
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Timelike};
+
+const MONTH_NAMES: &[&str] = &[
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const DAY_NAMES: &[&str] = &["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+/// How far ahead [`CronSchedule::next_after`] looks before giving up, e.g. for `0 0 30 2 *`.
+const MAX_LOOKAHEAD_DAYS: i64 = 366 * 5;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    day_of_month_restricted: bool,
+    day_of_week_restricted: bool,
+}
+
+impl CronSchedule {
+    pub fn parse(expression: &str) -> Result<Self> {
+        let expression = expression.trim();
+        let expanded = match expression.to_ascii_lowercase().as_str() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            other if other.starts_with('@') => bail!("unknown schedule macro: {expression}"),
+            _ => expression,
+        };
+
+        let fields = expanded.split_whitespace().collect::<Vec<_>>();
+        let [minute, hour, day_of_month, month, day_of_week] = fields[..] else {
+            bail!("schedule must have 5 fields (minute hour day month weekday): {expression}");
+        };
+
+        let mut days_of_week = parse_field(day_of_week, 0, 7, DAY_NAMES, 0)
+            .with_context(|| format!("invalid day-of-week field in schedule: {expression}"))?;
+        // Both 0 and 7 mean Sunday.
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week | 1) & !(1 << 7);
+        }
+
+        Ok(Self {
+            minutes: parse_field(minute, 0, 59, &[], 0)
+                .with_context(|| format!("invalid minute field in schedule: {expression}"))?,
+            hours: parse_field(hour, 0, 23, &[], 0)
+                .with_context(|| format!("invalid hour field in schedule: {expression}"))?,
+            days_of_month: parse_field(day_of_month, 1, 31, &[], 0)
+                .with_context(|| format!("invalid day-of-month field in schedule: {expression}"))?,
+            months: parse_field(month, 1, 12, MONTH_NAMES, 1)
+                .with_context(|| format!("invalid month field in schedule: {expression}"))?,
+            days_of_week,
+            day_of_month_restricted: !day_of_month.starts_with('*'),
+            day_of_week_restricted: !day_of_week.starts_with('*'),
+        })
+    }
+
+    pub fn matches<Tz: TimeZone>(&self, time: &DateTime<Tz>) -> bool {
+        self.matches_naive(&time.naive_local())
+    }
+
+    /// The first matching minute strictly after `after`, skipping local times that do not exist
+    /// because of a daylight-saving jump.
+    pub fn next_after(&self, after: DateTime<Local>) -> Option<DateTime<Local>> {
+        let start = after.naive_local().with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let limit = start + Duration::days(MAX_LOOKAHEAD_DAYS);
+        let mut candidate = start;
+
+        while candidate < limit {
+            if !bit_set(self.months, candidate.month()) {
+                candidate = first_of_next_month(candidate.date())?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+            if !self.matches_day(candidate.date()) {
+                candidate = candidate.date().succ_opt()?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+            if !bit_set(self.hours, candidate.hour()) {
+                candidate = candidate.with_minute(0)? + Duration::hours(1);
+                continue;
+            }
+            if !bit_set(self.minutes, candidate.minute()) {
+                candidate += Duration::minutes(1);
+                continue;
+            }
+            if let Some(time) = Local.from_local_datetime(&candidate).earliest() {
+                return Some(time);
+            }
+            candidate += Duration::minutes(1);
+        }
+        None
+    }
+
+    fn matches_naive(&self, time: &NaiveDateTime) -> bool {
+        bit_set(self.minutes, time.minute())
+            && bit_set(self.hours, time.hour())
+            && bit_set(self.months, time.month())
+            && self.matches_day(time.date())
+    }
+
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        let day_of_month = bit_set(self.days_of_month, date.day());
+        let day_of_week = bit_set(self.days_of_week, date.weekday().num_days_from_sunday());
+        if self.day_of_month_restricted && self.day_of_week_restricted {
+            day_of_month || day_of_week
+        } else {
+            day_of_month && day_of_week
+        }
+    }
+}
+
+fn bit_set(bits: u64, value: u32) -> bool {
+    bits & (1 << value) != 0
+}
+
+fn first_of_next_month(date: NaiveDate) -> Option<NaiveDate> {
+    if date.month() == 12 {
+        NaiveDate::from_ymd_opt(date.year() + 1, 1, 1)
+    } else {
+        NaiveDate::from_ymd_opt(date.year(), date.month() + 1, 1)
+    }
+}
+
+/// Parses one field into a bitset indexed by value. `names[i]` stands for `name_base + i`.
+fn parse_field(field: &str, min: u32, max: u32, names: &[&str], name_base: u32) -> Result<u64> {
+    let mut bits = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step = step
+                    .parse::<u32>()
+                    .with_context(|| format!("invalid step: {step}"))?;
+                if step == 0 {
+                    bail!("step must be greater than zero");
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (
+                parse_value(start, names, name_base)?,
+                parse_value(end, names, name_base)?,
+            )
+        } else {
+            let value = parse_value(range, names, name_base)?;
+            // `5/15` means every 15 starting at 5, as in Vixie cron.
+            (value, if part.contains('/') { max } else { value })
+        };
+
+        if start < min || end > max || start > end {
+            bail!("{range} is outside {min}-{max}");
+        }
+        for value in (start..=end).step_by(step as usize) {
+            bits |= 1 << value;
+        }
+    }
+    Ok(bits)
+}
+
+fn parse_value(value: &str, names: &[&str], name_base: u32) -> Result<u32> {
+    if let Ok(number) = value.parse::<u32>() {
+        return Ok(number);
+    }
+    let lower = value.to_ascii_lowercase();
+    names
+        .iter()
+        .position(|name| *name == lower)
+        .map(|index| name_base + index as u32)
+        .with_context(|| format!("invalid value: {value}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(year, month, day, hour, minute, 0)
+            .earliest()
+            .unwrap()
+    }
+
+    #[test]
+    fn parses_fields_macros_and_names() {
+        let schedule = CronSchedule::parse("*/15 9-17 * jan-mar mon-fri").unwrap();
+        assert!(schedule.matches(&local(2026, 2, 2, 9, 45)));
+        assert!(!schedule.matches(&local(2026, 2, 2, 9, 50)));
+        assert!(!schedule.matches(&local(2026, 2, 1, 9, 45)));
+        assert!(!schedule.matches(&local(2026, 4, 6, 9, 45)));
+
+        assert_eq!(
+            CronSchedule::parse("@daily").unwrap(),
+            CronSchedule::parse("0 0 * * *").unwrap()
+        );
+        assert_eq!(
+            CronSchedule::parse("0 0 * * 7").unwrap(),
+            CronSchedule::parse("0 0 * * sun").unwrap()
+        );
+
+        for invalid in [
+            "",
+            "* * * *",
+            "60 * * * *",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "@often",
+        ] {
+            assert!(CronSchedule::parse(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn restricted_day_fields_match_either() {
+        let schedule = CronSchedule::parse("0 12 1 * fri").unwrap();
+        assert!(schedule.matches(&local(2026, 10, 1, 12, 0)));
+        assert!(schedule.matches(&local(2026, 10, 2, 12, 0)));
+        assert!(!schedule.matches(&local(2026, 10, 3, 12, 0)));
+    }
+
+    #[test]
+    fn finds_next_matching_minute() {
+        let schedule = CronSchedule::parse("30 4 * * *").unwrap();
+        assert_eq!(
+            schedule.next_after(local(2026, 10, 19, 4, 30)),
+            Some(local(2026, 10, 20, 4, 30))
+        );
+        assert_eq!(
+            schedule.next_after(local(2026, 10, 19, 3, 59)),
+            Some(local(2026, 10, 19, 4, 30))
+        );
+
+        let leap_day = CronSchedule::parse("0 0 29 2 *").unwrap();
+        assert_eq!(
+            leap_day.next_after(local(2026, 10, 19, 0, 0)),
+            Some(local(2028, 2, 29, 0, 0))
+        );
+        assert_eq!(
+            CronSchedule::parse("0 0 30 2 *")
+                .unwrap()
+                .next_after(local(2026, 1, 1, 0, 0)),
+            None
+        );
+    }
+}
@@ -1,8 +1,10 @@
 use super::*;
-use crate::service_state::DesiredServiceState;
+use crate::{secrets::SecretStore, service_state::DesiredServiceState};
 use anyhow::Result;
 use fungi_config::paths::FungiPaths;
 use fungi_docker_agent::DockerAgentError;
+use libp2p::PeerId;
+use sha2::{Digest, Sha256};
 #[cfg(unix)]
 use std::os::unix::fs::PermissionsExt;
 use std::{
@@ -40,6 +42,7 @@ fn docker_manifest_maps_to_container_spec() {
         mounts: vec![ServiceMount {
             host_path: PathBuf::from("/tmp/fungi/data"),
             runtime_path: "/srv".into(),
+            read_only: false,
         }],
         ports: vec![ServicePort {
             name: None,
@@ -52,14 +55,66 @@ fn docker_manifest_maps_to_container_spec() {
         entrypoint: Vec::new(),
         working_dir: None,
         labels: BTreeMap::new(),
+        on_demand: None,
+        container: Default::default(),
+        job: Default::default(),
     };
 
-    let spec = docker_spec_from_manifest_with_name(&manifest, &manifest.name).unwrap();
+    let spec = docker_spec_from_manifest_with_name(
+        &manifest,
+        &manifest.name,
+        &SecretStore::new("/nonexistent"),
+    )
+    .unwrap();
     assert_eq!(spec.name.as_deref(), Some("filebrowser"));
     assert_eq!(spec.image, "filebrowser/filebrowser:latest");
     assert_eq!(spec.ports[0].host_port, 8080);
 }
 
+#[test]
+fn docker_spec_resolves_secret_references_only_at_launch() {
+    let temp_dir = TempDir::new().unwrap();
+    fungi_util::keypair::init_keypair(temp_dir.path()).unwrap();
+    let secrets = SecretStore::new(temp_dir.path());
+    secrets.set("db_password", "hunter2").unwrap();
+
+    let manifest = ServiceManifest {
+        name: "db".into(),
+        definition_id: None,
+        runtime: RuntimeKind::Docker,
+        run_mode: ServiceRunMode::Command,
+        source: ServiceSource::Docker {
+            image: "postgres:16".into(),
+        },
+        expose: None,
+        env: BTreeMap::from([(
+            String::from("POSTGRES_PASSWORD"),
+            String::from("${secret:db_password}"),
+        )]),
+        mounts: Vec::new(),
+        ports: Vec::new(),
+        command: Vec::new(),
+        entrypoint: Vec::new(),
+        working_dir: None,
+        labels: BTreeMap::new(),
+        on_demand: None,
+        container: Default::default(),
+        job: Default::default(),
+    };
+
+    let spec = docker_spec_from_manifest_with_name(&manifest, &manifest.name, &secrets).unwrap();
+    assert_eq!(spec.env["POSTGRES_PASSWORD"], "hunter2");
+    assert_eq!(manifest.env["POSTGRES_PASSWORD"], "${secret:db_password}");
+    assert!(
+        !service_manifest_to_yaml(&manifest)
+            .unwrap()
+            .contains("hunter2")
+    );
+
+    secrets.remove("db_password").unwrap();
+    assert!(docker_spec_from_manifest_with_name(&manifest, &manifest.name, &secrets).is_err());
+}
+
 #[test]
 fn docker_manifest_can_use_internal_container_name() {
     let manifest = ServiceManifest {
@@ -78,10 +133,17 @@ fn docker_manifest_can_use_internal_container_name() {
         entrypoint: Vec::new(),
         working_dir: None,
         labels: BTreeMap::new(),
+        on_demand: None,
+        container: Default::default(),
+        job: Default::default(),
     };
 
-    let spec =
-        docker_spec_from_manifest_with_name(&manifest, "svc_01hz7j7n3evh1q4j1a8g9c2d3e").unwrap();
+    let spec = docker_spec_from_manifest_with_name(
+        &manifest,
+        "svc_01hz7j7n3evh1q4j1a8g9c2d3e",
+        &SecretStore::new("/nonexistent"),
+    )
+    .unwrap();
 
     assert_eq!(spec.name.as_deref(), Some("svc_01hz7j7n3evh1q4j1a8g9c2d3e"));
 }
@@ -97,18 +159,23 @@ fn ensure_manifest_mount_dirs_creates_missing_host_paths() {
         run_mode: ServiceRunMode::Command,
         source: ServiceSource::WasmtimeFile {
             component: temp_dir.path().join("demo.wasm"),
+            sha256: None,
         },
         expose: None,
         env: BTreeMap::new(),
         mounts: vec![ServiceMount {
             host_path: mount_path.clone(),
             runtime_path: "data".into(),
+            read_only: false,
         }],
         ports: Vec::new(),
         command: Vec::new(),
         entrypoint: Vec::new(),
         working_dir: None,
         labels: BTreeMap::new(),
+        on_demand: None,
+        container: Default::default(),
+        job: Default::default(),
     };
 
     ensure_manifest_mount_dirs(&manifest).unwrap();
@@ -149,6 +216,7 @@ fn docker_manifest_rejects_wrong_source_type() {
         run_mode: ServiceRunMode::Command,
         source: ServiceSource::WasmtimeFile {
             component: PathBuf::from("/tmp/app.wasm"),
+            sha256: None,
         },
         expose: None,
         env: BTreeMap::new(),
@@ -158,9 +226,19 @@ fn docker_manifest_rejects_wrong_source_type() {
         entrypoint: Vec::new(),
         working_dir: None,
         labels: BTreeMap::new(),
+        on_demand: None,
+        container: Default::default(),
+        job: Default::default(),
     };
 
-    assert!(docker_spec_from_manifest_with_name(&manifest, &manifest.name).is_err());
+    assert!(
+        docker_spec_from_manifest_with_name(
+            &manifest,
+            &manifest.name,
+            &SecretStore::new("/nonexistent")
+        )
+        .is_err()
+    );
 }
 
 #[tokio::test]
@@ -183,6 +261,7 @@ async fn wasmtime_provider_runs_fake_launcher_and_collects_logs() {
         run_mode: ServiceRunMode::Http,
         source: ServiceSource::WasmtimeFile {
             component: component.clone(),
+            sha256: None,
         },
         expose: Some(ServiceExpose {
             transport: ServiceExposeTransport {
@@ -198,6 +277,7 @@ async fn wasmtime_provider_runs_fake_launcher_and_collects_logs() {
         mounts: vec![ServiceMount {
             host_path: temp_dir.path().join("data"),
             runtime_path: "data".into(),
+            read_only: false,
         }],
         ports: vec![ServicePort {
             name: None,
@@ -210,6 +290,9 @@ async fn wasmtime_provider_runs_fake_launcher_and_collects_logs() {
         entrypoint: Vec::new(),
         working_dir: None,
         labels: BTreeMap::new(),
+        on_demand: None,
+        container: Default::default(),
+        job: Default::default(),
     };
 
     provider.pull(&manifest).await.unwrap();
@@ -232,6 +315,7 @@ async fn wasmtime_provider_runs_fake_launcher_and_collects_logs() {
                 "demo-service",
                 &ServiceLogsOptions {
                     tail: Some("10".into()),
+                    since: None,
                 },
             )
             .await
@@ -266,6 +350,7 @@ fn wasmtime_tcp_entry_runs_command_with_network_permissions() {
         run_mode: ServiceRunMode::Command,
         source: ServiceSource::WasmtimeFile {
             component: component.clone(),
+            sha256: None,
         },
         expose: Some(ServiceExpose {
             transport: ServiceExposeTransport {
@@ -290,6 +375,9 @@ fn wasmtime_tcp_entry_runs_command_with_network_permissions() {
         entrypoint: Vec::new(),
         working_dir: None,
         labels: BTreeMap::new(),
+        on_demand: None,
+        container: Default::default(),
+        job: Default::default(),
     };
     let state = WasmtimeServiceState {
         manifest,
@@ -302,7 +390,13 @@ fn wasmtime_tcp_entry_runs_command_with_network_permissions() {
         last_exit_code: None,
     };
 
-    let command = build_wasmtime_command(Path::new("/bin/fungi"), temp_dir.path(), &state).unwrap();
+    let command = build_wasmtime_command(
+        Path::new("/bin/fungi"),
+        temp_dir.path(),
+        &state,
+        &SecretStore::new(temp_dir.path()),
+    )
+    .unwrap();
     let args = command
         .as_std()
         .get_args()
@@ -329,6 +423,7 @@ fn wasmtime_command_android_home_override_matches_target_behavior() {
         run_mode: ServiceRunMode::Command,
         source: ServiceSource::WasmtimeFile {
             component: component.clone(),
+            sha256: None,
         },
         expose: None,
         env: BTreeMap::new(),
@@ -338,6 +433,9 @@ fn wasmtime_command_android_home_override_matches_target_behavior() {
         entrypoint: Vec::new(),
         working_dir: None,
         labels: BTreeMap::new(),
+        on_demand: None,
+        container: Default::default(),
+        job: Default::default(),
     };
     let state = WasmtimeServiceState {
         manifest,
@@ -351,7 +449,13 @@ fn wasmtime_command_android_home_override_matches_target_behavior() {
     };
 
     let fungi_home = temp_dir.path().join(".fungi");
-    let command = build_wasmtime_command(Path::new("/bin/fungi"), &fungi_home, &state).unwrap();
+    let command = build_wasmtime_command(
+        Path::new("/bin/fungi"),
+        &fungi_home,
+        &state,
+        &SecretStore::new(&fungi_home),
+    )
+    .unwrap();
     let home_env = command
         .as_std()
         .get_envs()
@@ -382,6 +486,7 @@ fn wasmtime_http_mode_without_tcp_port_returns_error() {
         run_mode: ServiceRunMode::Http,
         source: ServiceSource::WasmtimeFile {
             component: component.clone(),
+            sha256: None,
         },
         expose: Some(ServiceExpose {
             transport: ServiceExposeTransport {
@@ -400,6 +505,9 @@ fn wasmtime_http_mode_without_tcp_port_returns_error() {
         entrypoint: Vec::new(),
         working_dir: None,
         labels: BTreeMap::new(),
+        on_demand: None,
+        container: Default::default(),
+        job: Default::default(),
     };
     let state = WasmtimeServiceState {
         manifest,
@@ -412,8 +520,13 @@ fn wasmtime_http_mode_without_tcp_port_returns_error() {
         last_exit_code: None,
     };
 
-    let error = build_wasmtime_command(Path::new("/bin/fungi"), temp_dir.path(), &state)
-        .expect_err("http mode without a TCP port should be rejected");
+    let error = build_wasmtime_command(
+        Path::new("/bin/fungi"),
+        temp_dir.path(),
+        &state,
+        &SecretStore::new(temp_dir.path()),
+    )
+    .expect_err("http mode without a TCP port should be rejected");
 
     assert!(error.to_string().contains("requires at least one TCP port"));
 }
@@ -507,6 +620,67 @@ publish:
     assert_eq!(manifest.mounts[2].host_path, paths.user_root());
 }
 
+#[test]
+fn portable_manifest_resolves_service_paths_under_another_service_id() {
+    let yaml = r#"
+fungi: service/v1
+id: notes
+run:
+  provider: docker
+  source:
+    image: notes/notes:latest
+  mounts:
+    - from: $fungi.service.data/db
+      to: /data
+    - from: $fungi.workspace/notes
+      to: /workspace
+    - from: /srv/shared
+      to: /shared
+publish:
+  http:
+    tcp:
+      port: 80
+"#;
+
+    let source_home = PathBuf::from("/tmp/fungi-source");
+    let source_roots =
+        super::manifest::ManifestPathRoots::for_local_service_id(&source_home, "svc_source");
+    let manifest = parse_service_manifest_yaml_with_policy_for_service_paths(
+        yaml,
+        Path::new("."),
+        &source_roots,
+        &ManifestResolutionPolicy,
+        &BTreeSet::new(),
+    )
+    .unwrap();
+
+    let portable =
+        super::manifest::portable_service_manifest_yaml(&manifest, &source_roots).unwrap();
+    assert!(portable.contains("$fungi.service.data/db"));
+    assert!(!portable.contains("fungi-source"));
+
+    let target_home = PathBuf::from("/tmp/fungi-target");
+    let target_paths = FungiPaths::from_fungi_home(&target_home);
+    let moved = parse_service_manifest_yaml_with_policy_for_service_paths(
+        &portable,
+        Path::new("."),
+        &super::manifest::ManifestPathRoots::for_local_service_id(&target_home, "svc_target"),
+        &ManifestResolutionPolicy,
+        &BTreeSet::new(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        moved.mounts[0].host_path,
+        target_paths.service_appdata_dir("svc_target").join("db")
+    );
+    assert_eq!(
+        moved.mounts[1].host_path,
+        target_paths.user_home().join("notes")
+    );
+    assert_eq!(moved.mounts[2].host_path, PathBuf::from("/srv/shared"));
+}
+
 #[test]
 fn fungi_service_file_maps_docker_workload_port_and_workspace_mount() {
     let content = r#"---
@@ -746,6 +920,106 @@ publish:
     assert_eq!(manifest.runtime, RuntimeKind::External);
 }
 
+#[test]
+fn service_manifest_inputs_render_typed_values_and_defaults() {
+    let content = r#"---
+fungi: service/v1
+id: blog
+inputs:
+  image_tag:
+    default: "5"
+  http_port:
+    type: port
+    default: 2368
+  data_dir:
+    description: Where posts are stored
+run:
+  provider: docker
+  source:
+    image: ghost:${inputs.image_tag}
+  env:
+    url: http://localhost:${inputs.http_port}
+  mounts:
+    - from: ${inputs.data_dir}
+      to: /var/lib/ghost/content
+publish:
+  main:
+    tcp:
+      port: ${inputs.http_port}
+    client:
+      kind: web
+---
+
+# Blog
+"#;
+
+    let error = service_manifest_with_inputs(content, &BTreeMap::new()).unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("missing required inputs: data_dir")
+    );
+
+    let values = BTreeMap::from([
+        ("data_dir".to_string(), "/srv/blog".to_string()),
+        ("http_port".to_string(), "8081".to_string()),
+    ]);
+    let rendered = service_manifest_with_inputs(content, &values).unwrap();
+    assert!(!rendered.contains("inputs"));
+    assert!(rendered.ends_with("---\n\n# Blog\n"));
+
+    let manifest =
+        parse_service_manifest_yaml(&rendered, Path::new("."), Path::new("/tmp/fungi-home"))
+            .unwrap();
+    assert!(matches!(
+        manifest.source,
+        ServiceSource::Docker { ref image } if image == "ghost:5"
+    ));
+    assert_eq!(manifest.ports[0].service_port, 8081);
+    assert_eq!(manifest.env["url"], "http://localhost:8081");
+    assert_eq!(manifest.mounts[0].host_path, PathBuf::from("/srv/blog"));
+}
+
+#[test]
+fn service_manifest_inputs_reject_invalid_values() {
+    let content = r#"
+fungi: service/v1
+id: web
+inputs:
+  http_port:
+    type: port
+    default: 8080
+  mode:
+    default: prod
+    choices: [prod, dev]
+publish:
+  main:
+    tcp:
+      port: ${inputs.http_port}
+"#;
+
+    let invalid_port = BTreeMap::from([("http_port".to_string(), "70000".to_string())]);
+    assert!(
+        service_manifest_with_inputs(content, &invalid_port)
+            .unwrap_err()
+            .to_string()
+            .contains("must be a port")
+    );
+    let invalid_choice = BTreeMap::from([("mode".to_string(), "staging".to_string())]);
+    assert!(service_manifest_with_inputs(content, &invalid_choice).is_err());
+    let unknown = BTreeMap::from([("image".to_string(), "nginx".to_string())]);
+    assert!(
+        service_manifest_with_inputs(content, &unknown)
+            .unwrap_err()
+            .to_string()
+            .contains("unknown input 'image'")
+    );
+
+    let manifest =
+        parse_service_manifest_yaml(content, Path::new("."), Path::new("/tmp/fungi-home")).unwrap();
+    assert_eq!(manifest.ports[0].service_port, 8080);
+}
+
 #[test]
 fn fungi_service_docker_publish_allocates_host_port() {
     let yaml = r#"
@@ -815,134 +1089,967 @@ publish:
 }
 
 #[test]
-fn service_manifest_to_yaml_preserves_fixed_wasmtime_publish_port() {
-    let yaml = r#"
+fn fungi_service_document_parses_on_demand_settings() {
+    let manifest_with = |on_demand: &str| {
+        let yaml = format!(
+            r#"
 fungi: service/v1
-id: fixed-web
+id: notebook
 run:
-  provider: wasmtime
+  provider: docker
   source:
-    url: https://example.test/fixed-web.wasm
+    image: jupyter/base-notebook:latest
+  on_demand: {on_demand}
 publish:
   http:
     tcp:
-      port: 8080
-    client:
-      kind: web
-"#;
+      port: 8888
+"#
+        );
+        parse_service_manifest_yaml(&yaml, Path::new("."), Path::new("/tmp/fungi-home"))
+    };
 
-    let manifest =
-        parse_service_manifest_yaml(yaml, Path::new("."), Path::new("/tmp/fungi-home")).unwrap();
-    assert_eq!(manifest.ports[0].service_port, 8080);
-    assert_eq!(manifest.ports[0].host_port, 8080);
     assert_eq!(
-        manifest.ports[0].host_port_allocation,
-        ServicePortAllocation::Fixed
+        manifest_with("true").unwrap().on_demand,
+        Some(ServiceOnDemand::default())
     );
+    assert_eq!(manifest_with("false").unwrap().on_demand, None);
 
+    let manifest = manifest_with("{ idle_timeout_secs: 120 }").unwrap();
+    assert_eq!(
+        manifest.on_demand,
+        Some(ServiceOnDemand {
+            idle_timeout_secs: 120
+        })
+    );
     let rendered = service_manifest_to_yaml(&manifest).unwrap();
-    assert!(rendered.contains("fungi: service/v1"));
-    assert!(rendered.contains("port: 8080"));
+    let reparsed =
+        parse_service_manifest_yaml(&rendered, Path::new("."), Path::new("/tmp/fungi-home"))
+            .unwrap();
+    assert_eq!(reparsed.on_demand, manifest.on_demand);
 
-    let reparsed = parse_managed_service_manifest_yaml(
-        &rendered,
-        Path::new("."),
-        Path::new("/tmp/fungi-home"),
-        "svc_fixed_web",
-    )
-    .unwrap();
-    assert_eq!(reparsed.ports[0].host_port, 8080);
+    let error = manifest_with("{ idle_timeout_secs: 0 }").unwrap_err();
+    assert!(error.to_string().contains("idle_timeout_secs"));
+}
+
+#[test]
+fn fungi_service_document_parses_container_mounts_and_security() {
+    let content = r#"
+fungi: service/v1
+id: postgres
+run:
+  provider: docker
+  source:
+    image: postgres:16
+  mounts:
+    - from: $fungi.workspace
+      to: /import
+      read_only: true
+    - volume: pgdata
+      to: /var/lib/postgresql/data
+  tmpfs:
+    - to: /tmp
+      size_mb: 64
+  security:
+    user: "999:999"
+    read_only_rootfs: true
+    cap_drop: [ALL]
+    cap_add: [CHOWN]
+    seccomp_profile: unconfined
+publish:
+  db:
+    tcp:
+      port: 5432
+"#;
+
+    let fungi_home = PathBuf::from("/tmp/fungi-home");
+    let manifest = parse_service_manifest_yaml(content, Path::new("."), &fungi_home).unwrap();
+
+    assert_eq!(manifest.mounts.len(), 1);
+    assert!(manifest.mounts[0].read_only);
     assert_eq!(
-        reparsed.ports[0].host_port_allocation,
-        ServicePortAllocation::Fixed
+        manifest.container.volumes,
+        vec![ServiceVolume {
+            name: "pgdata".into(),
+            runtime_path: "/var/lib/postgresql/data".into(),
+            read_only: false,
+        }]
+    );
+    assert_eq!(
+        manifest.container.tmpfs,
+        vec![ServiceTmpfs {
+            runtime_path: "/tmp".into(),
+            size_bytes: Some(64 * 1024 * 1024),
+        }]
     );
+    let security = &manifest.container.security;
+    assert_eq!(security.user.as_deref(), Some("999:999"));
+    assert!(security.read_only_rootfs);
+    assert_eq!(security.cap_drop, vec!["ALL".to_string()]);
+    assert_eq!(security.seccomp_profile.as_deref(), Some("unconfined"));
+
+    let rendered = service_manifest_to_yaml(&manifest).unwrap();
+    let reparsed = parse_service_manifest_yaml(&rendered, Path::new("."), &fungi_home).unwrap();
+    assert_eq!(reparsed.container, manifest.container);
+    assert!(reparsed.mounts[0].read_only);
 }
 
 #[test]
-fn fungi_service_rejects_duplicate_fixed_publish_ports() {
-    let yaml = r#"
+fn fungi_service_document_rejects_container_options_for_wasmtime() {
+    let content = r#"
 fungi: service/v1
-id: duplicate-host-port
+id: web
 run:
   provider: wasmtime
   source:
     url: https://example.test/web.wasm
+  security:
+    privileged: true
 publish:
   http:
     tcp:
-      port: 18080
-    client:
-      kind: web
-  metrics:
-    tcp:
-      port: 18080
+      port: 8080
 "#;
 
-    let error = parse_service_manifest_yaml(yaml, Path::new("."), Path::new("/tmp/fungi-home"))
-        .expect_err("duplicate fixed publish ports should fail validation");
-    assert!(
-        error
-            .to_string()
-            .contains("publish.metrics.tcp.port 18080 is already reserved")
-    );
+    let error = parse_service_manifest_yaml(content, Path::new("."), Path::new("/tmp/fungi-home"))
+        .unwrap_err();
+    assert!(error.to_string().contains("provider: docker"));
+
+    let mount_error = parse_service_manifest_yaml(
+        &content.replace(
+            "  security:\n    privileged: true\n",
+            "  mounts:\n    - volume: data\n      to: /data\n",
+        ),
+        Path::new("."),
+        Path::new("/tmp/fungi-home"),
+    )
+    .unwrap_err();
+    assert!(mount_error.to_string().contains("provider: docker"));
 }
 
 #[test]
-fn fungi_service_document_supports_external_tcp_service() {
-    let yaml = r#"
+fn fungi_service_document_parses_process_provider() {
+    let content = r#"
 fungi: service/v1
-id: home-ssh
+id: indexer
+run:
+  provider: process
+  source:
+    file: /opt/indexer/bin/indexer
+  args: ["--port", "7000"]
+  mounts:
+    - from: $fungi.workspace
+      read_only: true
 publish:
-  ssh:
+  api:
     tcp:
-      host: 127.0.0.1
-      port: 22
-    client:
-      kind: ssh
+      port: 7000
 "#;
 
-    let manifest =
-        parse_service_manifest_yaml(yaml, Path::new("."), Path::new("/tmp/fungi-home")).unwrap();
+    let fungi_home = PathBuf::from("/tmp/fungi-home");
+    let manifest = parse_service_manifest_yaml(content, Path::new("."), &fungi_home).unwrap();
 
-    assert_eq!(manifest.runtime, RuntimeKind::External);
-    assert!(matches!(
-        manifest.source,
-        ServiceSource::ExistingTcp { ref host, port } if host == "127.0.0.1" && port == 22
-    ));
-    assert_eq!(manifest.ports[0].name.as_deref(), Some("ssh"));
+    assert_eq!(manifest.runtime, RuntimeKind::Process);
+    let ServiceSource::Process { binary, sha256 } = &manifest.source else {
+        panic!("expected a process source");
+    };
+    assert_eq!(binary, Path::new("/opt/indexer/bin/indexer"));
+    assert!(sha256.is_none());
+    assert_eq!(manifest.command, vec!["--port", "7000"]);
+    assert_eq!(
+        manifest.mounts[0].runtime_path,
+        manifest.mounts[0].host_path.display().to_string()
+    );
+    assert!(manifest.mounts[0].read_only);
+    assert_eq!(manifest.ports[0].host_port, 7000);
+    assert_eq!(manifest.ports[0].service_port, 7000);
+
+    let rendered = service_manifest_to_yaml(&manifest).unwrap();
+    assert!(rendered.contains("provider: process"));
+    assert!(!rendered.contains("to:"));
+    let reparsed = parse_service_manifest_yaml(&rendered, Path::new("."), &fungi_home).unwrap();
+    assert_eq!(reparsed.runtime, RuntimeKind::Process);
+    assert!(reparsed.mounts[0].read_only);
+}
+
+#[test]
+fn fungi_service_document_checks_mount_targets_per_provider() {
+    let process = r#"
+fungi: service/v1
+id: indexer
+run:
+  provider: process
+  source:
+    file: /opt/indexer/bin/indexer
+  mounts:
+    - from: $fungi.workspace
+      to: /data
+publish:
+  api:
+    tcp:
+      port: 7000
+"#;
+    let error = parse_service_manifest_yaml(process, Path::new("."), Path::new("/tmp/fungi-home"))
+        .unwrap_err();
+    assert!(error.to_string().contains("host path"));
+
+    let docker = process
+        .replace("provider: process", "provider: docker")
+        .replace("file: /opt/indexer/bin/indexer", "image: indexer:1")
+        .replace("      to: /data\n", "");
+    let error = parse_service_manifest_yaml(&docker, Path::new("."), Path::new("/tmp/fungi-home"))
+        .unwrap_err();
+    assert!(error.to_string().contains("to is required"));
+
+    let with_image = process.replace("file: /opt/indexer/bin/indexer", "image: indexer:1");
+    let error =
+        parse_service_manifest_yaml(&with_image, Path::new("."), Path::new("/tmp/fungi-home"))
+            .unwrap_err();
+    assert!(error.to_string().contains("requires source.file"));
 }
 
+#[cfg(target_os = "linux")]
 #[tokio::test]
-async fn wasmtime_provider_downloads_remote_component() {
+async fn process_provider_runs_sandboxed_binary_and_collects_logs() {
     let temp_dir = TempDir::new().unwrap();
-    let launcher = create_fake_launcher(temp_dir.path()).unwrap();
-    let server = spawn_http_server(b"downloaded-wasm".to_vec()).await;
+    let outside_dir = TempDir::new().unwrap();
+    let bin_dir = temp_dir.path().join("bin");
+    fs::create_dir_all(&bin_dir).unwrap();
+    let data_dir = temp_dir.path().join("data");
+    let outside_file = outside_dir.path().join("escaped");
+    let script = bin_dir.join("service.sh");
+    fs::write(
+        &script,
+        format!(
+            "#!/bin/sh\necho hello-$GREETING\necho uid-$(id -u)\nls /etc >/dev/null 2>&1 && echo etc-listed\necho inside > {}/written\necho outside > {} 2>/dev/null\nexec sleep 30\n",
+            data_dir.display(),
+            outside_file.display()
+        ),
+    )
+    .unwrap();
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
 
-    let provider = WasmtimeRuntimeProvider::new(
+    let provider = ProcessRuntimeProvider::new(
         temp_dir.path().join("runtime"),
-        launcher,
         temp_dir.path().to_path_buf(),
         vec![temp_dir.path().to_path_buf()],
+        fungi_config::runtime::ProcessSandboxMode::BestEffort,
     );
     let manifest = ServiceManifest {
-        name: "download-service".into(),
+        name: "native-service".into(),
         definition_id: None,
-        runtime: RuntimeKind::Wasmtime,
+        runtime: RuntimeKind::Process,
         run_mode: ServiceRunMode::Command,
-        source: ServiceSource::WasmtimeUrl {
-            url: server.url.clone(),
+        source: ServiceSource::Process {
+            binary: script.clone(),
+            sha256: None,
         },
         expose: None,
-        env: BTreeMap::new(),
-        mounts: Vec::new(),
+        env: BTreeMap::from([("GREETING".to_string(), "fungi".to_string())]),
+        mounts: vec![ServiceMount {
+            host_path: data_dir.clone(),
+            runtime_path: data_dir.display().to_string(),
+            read_only: false,
+        }],
         ports: Vec::new(),
-        command: vec!["--help".into()],
+        command: Vec::new(),
         entrypoint: Vec::new(),
         working_dir: None,
         labels: BTreeMap::new(),
+        on_demand: None,
+        container: Default::default(),
+        job: Default::default(),
     };
 
-    let pulled = provider
+    provider.pull(&manifest).await.unwrap();
+    assert_eq!(
+        provider
+            .inspect("native-service")
+            .await
+            .unwrap()
+            .status
+            .phase,
+        ServicePhase::Stopped
+    );
+
+    // SAFETY: geteuid cannot fail.
+    let running_as_root = unsafe { libc::geteuid() } == 0;
+    if running_as_root {
+        // Services of a root daemon run as nobody, so the mount must be writable by it.
+        fs::set_permissions(&data_dir, fs::Permissions::from_mode(0o777)).unwrap();
+    }
+    provider.start("native-service").await.unwrap();
+    let mut logs = String::new();
+    for _ in 0..20 {
+        logs = provider
+            .logs("native-service", &ServiceLogsOptions::default())
+            .await
+            .unwrap()
+            .text;
+        if logs.contains("hello-fungi") && data_dir.join("written").exists() {
+            break;
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+    assert!(logs.contains("fungi: process sandbox"));
+    assert!(logs.contains("hello-fungi"));
+    assert!(data_dir.join("written").exists());
+    if running_as_root {
+        assert!(logs.contains("uid-65534"));
+    }
+    if logs.contains("landlock: enforced") {
+        assert!(!outside_file.exists());
+        assert!(!logs.contains("etc-listed"));
+    }
+
+    let running = provider.inspect("native-service").await.unwrap();
+    assert!(running.status.is_running());
+    assert_eq!(running.id, "process:native-service");
+
+    // The first sample only primes the CPU counters.
+    let first = provider
+        .resource_usage("native-service")
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(first.cpu_percent.is_none());
+    assert!(first.memory_bytes.is_some_and(|bytes| bytes > 0));
+    assert!(first.network_rx_bytes.is_none());
+    let second = provider
+        .resource_usage("native-service")
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(second.cpu_percent.is_some());
+
+    // Exec'd commands share the service environment and get stdin until it is closed.
+    let session = provider
+        .exec(
+            "native-service",
+            &ServiceExecRequest {
+                service: "native-service".into(),
+                command: vec![
+                    "/bin/sh".into(),
+                    "-c".into(),
+                    "cat; echo exec-$GREETING >&2; exit 4".into(),
+                ],
+                tty: false,
+                size: None,
+            },
+        )
+        .await
+        .unwrap();
+    session
+        .input
+        .send(ServiceExecInput::Stdin(b"ping\n".to_vec()))
+        .await
+        .unwrap();
+    session
+        .input
+        .send(ServiceExecInput::CloseStdin)
+        .await
+        .unwrap();
+    let (stdout, stderr, exit) = super::exec::collect_exec_output(session).await;
+    assert_eq!(stdout, b"ping\n");
+    assert!(String::from_utf8_lossy(&stderr).contains("exec-fungi"));
+    assert_eq!(exit.exit_code, Some(4));
+
+    provider.stop("native-service").await.unwrap();
+    assert!(
+        provider
+            .resource_usage("native-service")
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        !provider
+            .inspect("native-service")
+            .await
+            .unwrap()
+            .status
+            .is_running()
+    );
+
+    provider.remove("native-service").await.unwrap();
+    assert!(provider.inspect("native-service").await.is_err());
+}
+
+#[cfg(target_os = "linux")]
+#[tokio::test]
+async fn sandboxed_process_cannot_signal_outside_its_own_processes() {
+    use std::os::unix::process::CommandExt;
+
+    let temp_dir = TempDir::new().unwrap();
+    // SAFETY: geteuid cannot fail.
+    let running_as_root = unsafe { libc::geteuid() } == 0;
+    // A bystander running as the same user the service ends up with.
+    let mut bystander = std::process::Command::new("sleep");
+    bystander.arg("30");
+    if running_as_root {
+        bystander.uid(65534).gid(65534);
+    }
+    let mut bystander = bystander.spawn().unwrap();
+
+    let script = temp_dir.path().join("service.sh");
+    fs::write(
+        &script,
+        format!(
+            "#!/bin/sh\nkill -0 $$ && echo self-signalled\nkill -0 {} 2>/dev/null && echo outside-signalled || echo outside-refused\nexec sleep 30\n",
+            bystander.id()
+        ),
+    )
+    .unwrap();
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let provider = ProcessRuntimeProvider::new(
+        temp_dir.path().join("runtime"),
+        temp_dir.path().to_path_buf(),
+        vec![temp_dir.path().to_path_buf()],
+        fungi_config::runtime::ProcessSandboxMode::BestEffort,
+    );
+    let manifest = ServiceManifest {
+        name: "signaller".into(),
+        definition_id: None,
+        runtime: RuntimeKind::Process,
+        run_mode: ServiceRunMode::Command,
+        source: ServiceSource::Process {
+            binary: script,
+            sha256: None,
+        },
+        expose: None,
+        env: BTreeMap::new(),
+        mounts: Vec::new(),
+        ports: Vec::new(),
+        command: Vec::new(),
+        entrypoint: Vec::new(),
+        working_dir: None,
+        labels: BTreeMap::new(),
+        on_demand: None,
+        container: Default::default(),
+        job: Default::default(),
+    };
+    provider.pull(&manifest).await.unwrap();
+    provider.start("signaller").await.unwrap();
+
+    let mut logs = String::new();
+    for _ in 0..20 {
+        logs = provider
+            .logs("signaller", &ServiceLogsOptions::default())
+            .await
+            .unwrap()
+            .text;
+        if logs.contains("-signalled") || logs.contains("-refused") {
+            break;
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+    provider.remove("signaller").await.unwrap();
+    let bystander_alive = bystander.try_wait().unwrap().is_none();
+    bystander.kill().unwrap();
+    bystander.wait().unwrap();
+
+    assert!(logs.contains("self-signalled"), "{logs}");
+    assert!(bystander_alive);
+    if logs.contains("landlock: enforced") {
+        assert!(logs.contains("outside-refused"), "{logs}");
+    }
+}
+
+#[test]
+fn fungi_service_document_parses_job_mode() {
+    let content = r#"
+fungi: service/v1
+id: backup
+run:
+  provider: process
+  mode: job
+  schedule: "30 3 * * mon-fri"
+  timeout_secs: 600
+  source:
+    file: /opt/backup/bin/backup
+"#;
+
+    let fungi_home = PathBuf::from("/tmp/fungi-home");
+    let manifest = parse_service_manifest_yaml(content, Path::new("."), &fungi_home).unwrap();
+    assert_eq!(manifest.run_mode, ServiceRunMode::Job);
+    assert_eq!(manifest.job.schedule.as_deref(), Some("30 3 * * mon-fri"));
+    assert_eq!(manifest.job.timeout_secs, Some(600));
+    assert!(manifest.ports.is_empty());
+    assert!(manifest.expose.is_none());
+
+    let rendered = service_manifest_to_yaml(&manifest).unwrap();
+    assert!(rendered.contains("mode: job"));
+    assert!(!rendered.contains("publish"));
+    let reparsed = parse_service_manifest_yaml(&rendered, Path::new("."), &fungi_home).unwrap();
+    assert_eq!(reparsed.job, manifest.job);
+
+    let parse_error = |content: String| {
+        parse_service_manifest_yaml(&content, Path::new("."), &fungi_home)
+            .unwrap_err()
+            .to_string()
+    };
+    let published = format!("{content}publish:\n  api:\n    tcp:\n      port: 7000\n");
+    assert!(
+        parse_error(published.replace("  mode: job\n", ""))
+            .contains("run.schedule requires run.mode: job")
+    );
+    assert!(parse_error(content.replace("30 3 * * mon-fri", "30 3 * *")).contains("run.schedule"));
+    assert!(
+        parse_error(content.replace("  timeout_secs: 600\n", "  on_demand: true\n"))
+            .contains("run.on_demand is not supported")
+    );
+    assert!(
+        parse_error(
+            content
+                .replace("  mode: job\n", "")
+                .replace("  schedule: \"30 3 * * mon-fri\"\n", "")
+                .replace("  timeout_secs: 600\n", "")
+        )
+        .contains("at least one publish entry")
+    );
+}
+
+#[cfg(target_os = "linux")]
+#[tokio::test]
+async fn runtime_control_samples_resources_only_on_request() {
+    let temp_dir = TempDir::new().unwrap();
+    let fungi_home = temp_dir.path().join("fungi-home");
+    let script = temp_dir.path().join("service.sh");
+    fs::write(&script, "#!/bin/sh\nexec sleep 30\n").unwrap();
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let allowed = vec![temp_dir.path().to_path_buf()];
+    let control = RuntimeControl::new(
+        fungi_home.join("runtime"),
+        PathBuf::from("/bin/echo"),
+        fungi_home.clone(),
+        None,
+        fungi_home.join("services"),
+        allowed.clone(),
+        false,
+    )
+    .unwrap()
+    .with_process_provider(ProcessRuntimeProvider::new(
+        fungi_home.join("runtime"),
+        fungi_home.clone(),
+        allowed,
+        fungi_config::runtime::ProcessSandboxMode::BestEffort,
+    ));
+
+    let mut manifest = existing_tcp_manifest("sampled", "127.0.0.1", 7003);
+    manifest.runtime = RuntimeKind::Process;
+    manifest.source = ServiceSource::Process {
+        binary: script,
+        sha256: None,
+    };
+    manifest.ports = Vec::new();
+    manifest.expose = None;
+    control.apply(&manifest, None).await.unwrap();
+    control
+        .start(RuntimeKind::Process, "sampled")
+        .await
+        .unwrap();
+
+    let instance = control
+        .inspect(RuntimeKind::Process, "sampled")
+        .await
+        .unwrap();
+    assert!(instance.status.is_running());
+    assert!(instance.resources.is_none());
+    assert!(
+        control.list_services().await.unwrap()[0]
+            .resources
+            .is_none()
+    );
+
+    let sampled = control.with_resources(instance).await;
+    assert!(
+        sampled
+            .resources
+            .is_some_and(|usage| usage.memory_bytes.is_some())
+    );
+    assert!(
+        control.list_services_with_resources().await.unwrap()[0]
+            .resources
+            .is_some()
+    );
+
+    control
+        .remove(RuntimeKind::Process, "sampled")
+        .await
+        .unwrap();
+}
+
+#[cfg(target_os = "linux")]
+#[tokio::test]
+async fn failed_apply_restores_the_previous_manifest() {
+    let temp_dir = TempDir::new().unwrap();
+    let fungi_home = temp_dir.path().join("fungi-home");
+    let script = temp_dir.path().join("service.sh");
+    fs::write(&script, "#!/bin/sh\nexec sleep 30\n").unwrap();
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let allowed = vec![temp_dir.path().to_path_buf()];
+    let control = RuntimeControl::new(
+        fungi_home.join("runtime"),
+        PathBuf::from("/bin/echo"),
+        fungi_home.clone(),
+        None,
+        fungi_home.join("services"),
+        allowed.clone(),
+        false,
+    )
+    .unwrap()
+    .with_process_provider(ProcessRuntimeProvider::new(
+        fungi_home.join("runtime"),
+        fungi_home.clone(),
+        allowed,
+        fungi_config::runtime::ProcessSandboxMode::BestEffort,
+    ));
+
+    let mut manifest = existing_tcp_manifest("upgraded", "127.0.0.1", 7004);
+    manifest.runtime = RuntimeKind::Process;
+    manifest.source = ServiceSource::Process {
+        binary: script.clone(),
+        sha256: None,
+    };
+    manifest.ports = Vec::new();
+    manifest.expose = None;
+    control.apply(&manifest, None).await.unwrap();
+    control
+        .start(RuntimeKind::Process, "upgraded")
+        .await
+        .unwrap();
+
+    // The new binary cannot be started, so the upgrade must fall back to the running one.
+    let mut broken = manifest.clone();
+    broken.source = ServiceSource::Process {
+        binary: temp_dir.path().join("missing.sh"),
+        sha256: None,
+    };
+    let error = control.apply(&broken, None).await.unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("the previous manifest was restored"),
+        "{error:#}"
+    );
+
+    let restored = control.get_service_manifest("upgraded").unwrap();
+    assert!(matches!(
+        restored.source,
+        ServiceSource::Process { ref binary, .. } if *binary == script
+    ));
+    assert!(
+        control
+            .inspect(RuntimeKind::Process, "upgraded")
+            .await
+            .unwrap()
+            .status
+            .is_running()
+    );
+    // Only the successful apply became a revision.
+    assert_eq!(control.service_revisions("upgraded").unwrap().len(), 1);
+
+    control
+        .remove(RuntimeKind::Process, "upgraded")
+        .await
+        .unwrap();
+}
+
+#[cfg(target_os = "linux")]
+#[tokio::test]
+async fn runtime_control_runs_process_job_and_records_history() {
+    let temp_dir = TempDir::new().unwrap();
+    let fungi_home = temp_dir.path().join("fungi-home");
+    let bin_dir = temp_dir.path().join("bin");
+    let data_dir = temp_dir.path().join("data");
+    fs::create_dir_all(&bin_dir).unwrap();
+    fs::create_dir_all(&data_dir).unwrap();
+    let script = bin_dir.join("job.sh");
+    fs::write(
+        &script,
+        format!(
+            "#!/bin/sh
+if [ -f {dir}/ran ]; then echo second-run; exit 0; fi
+touch {dir}/ran
+echo first-run
+exit 3
+",
+            dir = data_dir.display()
+        ),
+    )
+    .unwrap();
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let allowed = vec![temp_dir.path().to_path_buf()];
+    let control = RuntimeControl::new(
+        fungi_home.join("runtime"),
+        PathBuf::from("/bin/echo"),
+        fungi_home.clone(),
+        None,
+        fungi_home.join("services"),
+        allowed.clone(),
+        false,
+    )
+    .unwrap()
+    .with_process_provider(ProcessRuntimeProvider::new(
+        fungi_home.join("runtime"),
+        fungi_home.clone(),
+        allowed,
+        fungi_config::runtime::ProcessSandboxMode::BestEffort,
+    ));
+
+    let mut manifest = existing_tcp_manifest("nightly", "127.0.0.1", 7002);
+    manifest.runtime = RuntimeKind::Process;
+    manifest.run_mode = ServiceRunMode::Job;
+    manifest.source = ServiceSource::Process {
+        binary: script,
+        sha256: None,
+    };
+    manifest.ports = Vec::new();
+    manifest.expose = None;
+    manifest.mounts = vec![ServiceMount {
+        host_path: data_dir.clone(),
+        runtime_path: data_dir.display().to_string(),
+        read_only: false,
+    }];
+    manifest.job.schedule = Some("@daily".into());
+    control.apply(&manifest, None).await.unwrap();
+    // SAFETY: geteuid cannot fail.
+    if unsafe { libc::geteuid() } == 0 {
+        // Services of a root daemon run as nobody, so the mount must be writable by it.
+        fs::set_permissions(&data_dir, fs::Permissions::from_mode(0o777)).unwrap();
+    }
+
+    // Starting a job only enables its schedule.
+    control
+        .start(RuntimeKind::Process, "nightly")
+        .await
+        .unwrap();
+    assert!(!data_dir.join("ran").exists());
+    let job = control.job_status("nightly").unwrap().unwrap();
+    assert!(job.enabled);
+    assert!(job.next_run_at.is_some());
+    assert!(job.runs.is_empty());
+
+    let failed = control
+        .run_job("nightly", ServiceJobTrigger::Manual)
+        .await
+        .unwrap();
+    assert_eq!(failed.run, 1);
+    assert_eq!(failed.outcome, ServiceJobOutcome::Failed);
+    assert_eq!(failed.exit_code, Some(3));
+    assert!(failed.logs.contains("first-run"));
+
+    let succeeded = control
+        .run_job("nightly", ServiceJobTrigger::Schedule)
+        .await
+        .unwrap();
+    assert_eq!(succeeded.run, 2);
+    assert_eq!(succeeded.outcome, ServiceJobOutcome::Succeeded);
+    assert_eq!(succeeded.exit_code, Some(0));
+    assert!(succeeded.logs.contains("second-run"));
+    assert!(!succeeded.logs.contains("first-run"));
+
+    let instance = control
+        .inspect(RuntimeKind::Process, "nightly")
+        .await
+        .unwrap();
+    assert_eq!(instance.status.exit_code, Some(0));
+    let job = instance.job.unwrap();
+    assert_eq!(job.runs, vec![failed, succeeded]);
+
+    control.stop(RuntimeKind::Process, "nightly").await.unwrap();
+    let job = control.job_status("nightly").unwrap().unwrap();
+    assert!(!job.enabled);
+    assert!(job.next_run_at.is_none());
+
+    // A run the daemon never saw finish is closed when the daemon comes back.
+    let unfinished = ServiceJobRun {
+        run: 3,
+        trigger: ServiceJobTrigger::Schedule,
+        started_at: "2026-01-01T00:00:00Z".into(),
+        duration_ms: None,
+        exit_code: None,
+        outcome: ServiceJobOutcome::Running,
+        logs: String::new(),
+    };
+    crate::service_state::ServiceStateStore::load(fungi_home.join("services"))
+        .unwrap()
+        .record_job_run("nightly", &unfinished)
+        .unwrap();
+    let restarted = RuntimeControl::new(
+        fungi_home.join("runtime"),
+        PathBuf::from("/bin/echo"),
+        fungi_home.clone(),
+        None,
+        fungi_home.join("services"),
+        vec![temp_dir.path().to_path_buf()],
+        false,
+    )
+    .unwrap();
+    restarted.restore_persisted_state().await.unwrap();
+    let runs = restarted.job_status("nightly").unwrap().unwrap().runs;
+    assert_eq!(runs.len(), 3);
+    assert_eq!(runs[2].outcome, ServiceJobOutcome::Interrupted);
+    assert_eq!(runs[1].outcome, ServiceJobOutcome::Succeeded);
+}
+
+#[test]
+fn process_provider_refuses_binary_outside_allowed_roots() {
+    let temp_dir = TempDir::new().unwrap();
+    let provider = ProcessRuntimeProvider::new(
+        temp_dir.path().join("runtime"),
+        temp_dir.path().to_path_buf(),
+        Vec::new(),
+        fungi_config::runtime::ProcessSandboxMode::BestEffort,
+    );
+    let mut manifest = existing_tcp_manifest("native", "127.0.0.1", 7001);
+    manifest.runtime = RuntimeKind::Process;
+    manifest.source = ServiceSource::Process {
+        binary: PathBuf::from("/usr/bin/env"),
+        sha256: None,
+    };
+
+    let error = provider.restore(&manifest, "native").unwrap_err();
+    assert!(error.to_string().contains("outside allowed roots"));
+}
+
+#[test]
+fn service_manifest_to_yaml_preserves_fixed_wasmtime_publish_port() {
+    let yaml = r#"
+fungi: service/v1
+id: fixed-web
+run:
+  provider: wasmtime
+  source:
+    url: https://example.test/fixed-web.wasm
+publish:
+  http:
+    tcp:
+      port: 8080
+    client:
+      kind: web
+"#;
+
+    let manifest =
+        parse_service_manifest_yaml(yaml, Path::new("."), Path::new("/tmp/fungi-home")).unwrap();
+    assert_eq!(manifest.ports[0].service_port, 8080);
+    assert_eq!(manifest.ports[0].host_port, 8080);
+    assert_eq!(
+        manifest.ports[0].host_port_allocation,
+        ServicePortAllocation::Fixed
+    );
+
+    let rendered = service_manifest_to_yaml(&manifest).unwrap();
+    assert!(rendered.contains("fungi: service/v1"));
+    assert!(rendered.contains("port: 8080"));
+
+    let reparsed = parse_managed_service_manifest_yaml(
+        &rendered,
+        Path::new("."),
+        Path::new("/tmp/fungi-home"),
+        "svc_fixed_web",
+    )
+    .unwrap();
+    assert_eq!(reparsed.ports[0].host_port, 8080);
+    assert_eq!(
+        reparsed.ports[0].host_port_allocation,
+        ServicePortAllocation::Fixed
+    );
+}
+
+#[test]
+fn fungi_service_rejects_duplicate_fixed_publish_ports() {
+    let yaml = r#"
+fungi: service/v1
+id: duplicate-host-port
+run:
+  provider: wasmtime
+  source:
+    url: https://example.test/web.wasm
+publish:
+  http:
+    tcp:
+      port: 18080
+    client:
+      kind: web
+  metrics:
+    tcp:
+      port: 18080
+"#;
+
+    let error = parse_service_manifest_yaml(yaml, Path::new("."), Path::new("/tmp/fungi-home"))
+        .expect_err("duplicate fixed publish ports should fail validation");
+    assert!(
+        error
+            .to_string()
+            .contains("publish.metrics.tcp.port 18080 is already reserved")
+    );
+}
+
+#[test]
+fn fungi_service_document_supports_external_tcp_service() {
+    let yaml = r#"
+fungi: service/v1
+id: home-ssh
+publish:
+  ssh:
+    tcp:
+      host: 127.0.0.1
+      port: 22
+    client:
+      kind: ssh
+"#;
+
+    let manifest =
+        parse_service_manifest_yaml(yaml, Path::new("."), Path::new("/tmp/fungi-home")).unwrap();
+
+    assert_eq!(manifest.runtime, RuntimeKind::External);
+    assert!(matches!(
+        manifest.source,
+        ServiceSource::ExistingTcp { ref host, port } if host == "127.0.0.1" && port == 22
+    ));
+    assert_eq!(manifest.ports[0].name.as_deref(), Some("ssh"));
+}
+
+#[tokio::test]
+async fn wasmtime_provider_downloads_remote_component() {
+    let temp_dir = TempDir::new().unwrap();
+    let launcher = create_fake_launcher(temp_dir.path()).unwrap();
+    let server = spawn_http_server(b"downloaded-wasm".to_vec()).await;
+
+    let provider = WasmtimeRuntimeProvider::new(
+        temp_dir.path().join("runtime"),
+        launcher,
+        temp_dir.path().to_path_buf(),
+        vec![temp_dir.path().to_path_buf()],
+    );
+    let manifest = ServiceManifest {
+        name: "download-service".into(),
+        definition_id: None,
+        runtime: RuntimeKind::Wasmtime,
+        run_mode: ServiceRunMode::Command,
+        source: ServiceSource::WasmtimeUrl {
+            url: server.url.clone(),
+            sha256: None,
+        },
+        expose: None,
+        env: BTreeMap::new(),
+        mounts: Vec::new(),
+        ports: Vec::new(),
+        command: vec!["--help".into()],
+        entrypoint: Vec::new(),
+        working_dir: None,
+        labels: BTreeMap::new(),
+        on_demand: None,
+        container: Default::default(),
+        job: Default::default(),
+    };
+
+    let pulled = provider
         .pull_with_local_service_id(&manifest, "svc_download")
         .await
         .unwrap();
@@ -956,6 +2063,89 @@ async fn wasmtime_provider_downloads_remote_component() {
     drop(server);
 }
 
+#[tokio::test]
+async fn wasmtime_provider_refuses_component_with_mismatched_digest() {
+    let temp_dir = TempDir::new().unwrap();
+    let launcher = create_fake_launcher(temp_dir.path()).unwrap();
+    let server = spawn_http_server(b"tampered-wasm".to_vec()).await;
+
+    let provider = WasmtimeRuntimeProvider::new(
+        temp_dir.path().join("runtime"),
+        launcher,
+        temp_dir.path().to_path_buf(),
+        vec![temp_dir.path().to_path_buf()],
+    );
+    let manifest = ServiceManifest {
+        name: "pinned-service".into(),
+        definition_id: None,
+        runtime: RuntimeKind::Wasmtime,
+        run_mode: ServiceRunMode::Command,
+        source: ServiceSource::WasmtimeUrl {
+            url: server.url.clone(),
+            sha256: Some(hex::encode(Sha256::digest(b"downloaded-wasm"))),
+        },
+        expose: None,
+        env: BTreeMap::new(),
+        mounts: Vec::new(),
+        ports: Vec::new(),
+        command: Vec::new(),
+        entrypoint: Vec::new(),
+        working_dir: None,
+        labels: BTreeMap::new(),
+        on_demand: None,
+        container: Default::default(),
+        job: Default::default(),
+    };
+
+    let error = provider
+        .pull_with_local_service_id(&manifest, "svc_pinned")
+        .await
+        .unwrap_err();
+    assert!(format!("{error:#}").contains("failed integrity check"));
+    assert!(
+        !temp_dir
+            .path()
+            .join("artifacts/services/svc_pinned/component.wasm")
+            .exists()
+    );
+    drop(server);
+}
+
+#[test]
+fn fungi_service_document_pins_sources_by_digest() {
+    let digest = "c".repeat(64);
+    let yaml = format!(
+        r#"
+fungi: service/v1
+id: pinned-web
+run:
+  provider: docker
+  source:
+    image: nginx:1.27
+    sha256: sha256:{digest}
+publish:
+  http:
+    tcp:
+      port: 80
+"#
+    );
+    let manifest =
+        parse_service_manifest_yaml(&yaml, Path::new("."), Path::new("/tmp/fungi-home")).unwrap();
+    assert!(matches!(
+        manifest.source,
+        ServiceSource::Docker { ref image } if *image == format!("nginx:1.27@sha256:{digest}")
+    ));
+
+    let rendered = service_manifest_to_yaml(&manifest).unwrap();
+    assert!(rendered.contains(&format!("image: nginx:1.27@sha256:{digest}")));
+
+    let invalid = yaml.replace(&format!("sha256:{digest}"), "sha256:1234");
+    assert!(
+        parse_service_manifest_yaml(&invalid, Path::new("."), Path::new("/tmp/fungi-home"))
+            .is_err()
+    );
+}
+
 #[tokio::test]
 async fn runtime_control_apply_reuses_local_id_and_restages_wasmtime_component() {
     let temp_dir = TempDir::new().unwrap();
@@ -998,6 +2188,7 @@ publish:
             temp_dir.path(),
             &fungi_home,
             &ManifestResolutionPolicy::default(),
+            None,
         )
         .await
         .unwrap();
@@ -1040,6 +2231,7 @@ publish:
             temp_dir.path(),
             &fungi_home,
             &ManifestResolutionPolicy::default(),
+            None,
         )
         .await
         .unwrap();
@@ -1078,7 +2270,7 @@ async fn runtime_control_apply_uses_in_memory_manifest_when_persisted_state_is_m
     control.seed_in_memory_service_for_test(previous_manifest);
 
     let applied = control
-        .apply(&existing_tcp_manifest("demo", "127.0.0.1", 23))
+        .apply(&existing_tcp_manifest("demo", "127.0.0.1", 23), None)
         .await
         .unwrap();
 
@@ -1090,6 +2282,85 @@ async fn runtime_control_apply_uses_in_memory_manifest_when_persisted_state_is_m
     assert_eq!(applied.instance.source, "127.0.0.1:23");
 }
 
+#[tokio::test]
+async fn remote_applies_cannot_reference_local_secrets() {
+    let temp_dir = TempDir::new().unwrap();
+    let fungi_home = temp_dir.path().join("fungi-home");
+    fs::create_dir_all(&fungi_home).unwrap();
+    fungi_util::keypair::init_keypair(&fungi_home).unwrap();
+    let local_peer_id = PeerId::random();
+    let control = RuntimeControl::new(
+        fungi_home.join("runtime"),
+        PathBuf::from("/bin/echo"),
+        fungi_home.clone(),
+        None,
+        fungi_home.join("services"),
+        Vec::new(),
+        false,
+    )
+    .unwrap()
+    .with_local_peer_id(local_peer_id);
+    control.secrets().set("db_password", "hunter2").unwrap();
+
+    let mut manifest = existing_tcp_manifest("demo", "127.0.0.1", 22);
+    manifest
+        .env
+        .insert("PASSWORD".to_string(), "${secret:db_password}".to_string());
+
+    let Err(error) = control.apply(&manifest, Some(PeerId::random())).await else {
+        panic!("remote apply should not resolve local secrets");
+    };
+    assert!(error.to_string().contains("references local secrets"));
+    assert!(control.service_revisions("demo").is_err());
+
+    let applied = control.apply(&manifest, Some(local_peer_id)).await.unwrap();
+    assert_eq!(applied.revision.revision, 1);
+}
+
+#[tokio::test]
+async fn runtime_control_rollback_reapplies_previous_revision() {
+    let temp_dir = TempDir::new().unwrap();
+    let fungi_home = temp_dir.path().join("fungi-home");
+    let control = RuntimeControl::new(
+        fungi_home.join("runtime"),
+        PathBuf::from("/bin/echo"),
+        fungi_home.clone(),
+        None,
+        fungi_home.join("services"),
+        Vec::new(),
+        false,
+    )
+    .unwrap();
+
+    let first = control
+        .apply(&existing_tcp_manifest("demo", "127.0.0.1", 22), None)
+        .await
+        .unwrap();
+    assert_eq!(first.revision.revision, 1);
+    let second = control
+        .apply(&existing_tcp_manifest("demo", "127.0.0.1", 23), None)
+        .await
+        .unwrap();
+    assert_eq!(second.revision.revision, 2);
+    assert_ne!(
+        first.revision.resolved_manifest_digest,
+        second.revision.resolved_manifest_digest
+    );
+
+    let rolled_back = control.rollback("demo", None, None).await.unwrap();
+    assert_eq!(rolled_back.revision.revision, 3);
+    assert_eq!(rolled_back.revision.rollback_of, Some(1));
+    assert_eq!(
+        rolled_back.revision.resolved_manifest_digest,
+        first.revision.resolved_manifest_digest
+    );
+    assert_eq!(rolled_back.instance.source, "127.0.0.1:22");
+    assert_eq!(control.service_revisions("demo").unwrap().len(), 3);
+
+    let error = control.rollback("demo", Some(3), None).await.unwrap_err();
+    assert!(error.to_string().contains("current revision"));
+}
+
 #[tokio::test]
 async fn apply_manifest_yaml_allows_same_service_fixed_host_port_reapply_only() {
     let temp_dir = TempDir::new().unwrap();
@@ -1116,6 +2387,7 @@ async fn apply_manifest_yaml_allows_same_service_fixed_host_port_reapply_only()
             temp_dir.path(),
             &fungi_home,
             &ManifestResolutionPolicy::default(),
+            None,
         )
         .await
         .unwrap();
@@ -1125,6 +2397,7 @@ async fn apply_manifest_yaml_allows_same_service_fixed_host_port_reapply_only()
             temp_dir.path(),
             &fungi_home,
             &ManifestResolutionPolicy::default(),
+            None,
         )
         .await
         .unwrap();
@@ -1135,6 +2408,7 @@ async fn apply_manifest_yaml_allows_same_service_fixed_host_port_reapply_only()
             temp_dir.path(),
             &fungi_home,
             &ManifestResolutionPolicy::default(),
+            None,
         )
         .await
         .err()
@@ -1147,6 +2421,58 @@ async fn apply_manifest_yaml_allows_same_service_fixed_host_port_reapply_only()
     );
 }
 
+#[tokio::test]
+async fn apply_manifest_yaml_records_submitted_manifest_digest() {
+    let temp_dir = TempDir::new().unwrap();
+    let fungi_home = temp_dir.path().join("fungi-home");
+    let component = temp_dir.path().join("component.wasm");
+    fs::write(&component, b"wasm").unwrap();
+    let launcher = create_fake_launcher(temp_dir.path()).unwrap();
+
+    let control = RuntimeControl::new(
+        fungi_home.join("runtime"),
+        launcher,
+        fungi_home.clone(),
+        None,
+        fungi_home.join("services"),
+        vec![temp_dir.path().to_path_buf()],
+        true,
+    )
+    .unwrap();
+
+    let first_manifest = wasmtime_manifest_yaml("demo", &component, 19110);
+    let second_manifest = wasmtime_manifest_yaml("demo", &component, 19111);
+    for manifest in [&first_manifest, &second_manifest] {
+        control
+            .apply_manifest_yaml(
+                manifest,
+                temp_dir.path(),
+                &fungi_home,
+                &ManifestResolutionPolicy,
+                None,
+            )
+            .await
+            .unwrap();
+    }
+    let rolled_back = control.rollback("demo", None, None).await.unwrap();
+
+    let digests = control
+        .service_revisions("demo")
+        .unwrap()
+        .into_iter()
+        .map(|revision| revision.manifest_digest)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        digests,
+        vec![
+            Some(manifest_yaml_digest(&first_manifest)),
+            Some(manifest_yaml_digest(&second_manifest)),
+            Some(manifest_yaml_digest(&first_manifest)),
+        ]
+    );
+    assert_eq!(rolled_back.revision.rollback_of, Some(1));
+}
+
 #[tokio::test]
 async fn apply_manifest_yaml_rejects_definition_id_mismatch() {
     let temp_dir = TempDir::new().unwrap();
@@ -1179,6 +2505,7 @@ publish:
             temp_dir.path(),
             &fungi_home,
             &ManifestResolutionPolicy::default(),
+            None,
         )
         .await
         .unwrap();
@@ -1201,6 +2528,7 @@ publish:
             temp_dir.path(),
             &fungi_home,
             &ManifestResolutionPolicy::default(),
+            None,
         )
         .await
         .expect_err("different definition ids should not replace an existing service");
@@ -1435,6 +2763,9 @@ fn existing_tcp_manifest(name: &str, host: &str, port: u16) -> ServiceManifest {
         entrypoint: Vec::new(),
         working_dir: None,
         labels: BTreeMap::new(),
+        on_demand: None,
+        container: Default::default(),
+        job: Default::default(),
     }
 }
 
@@ -0,0 +1,358 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{Context, Result, bail};
+use chacha20poly1305::{
+    ChaCha20Poly1305, Key, KeyInit, Nonce,
+    aead::{Aead, Payload},
+};
+use chrono::{SecondsFormat, Utc};
+use fungi_util::keypair::get_keypair_from_dir;
+use parking_lot::Mutex;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use tempfile::NamedTempFile;
+
+const SECRETS_FILE: &str = "secrets.json";
+const SECRETS_SCHEMA_VERSION: u32 = 1;
+/// HKDF domain used to derive the store key from the node keypair.
+const SECRETS_KEY_DOMAIN: &[u8] = b"fungi/secrets/v1";
+const SECRET_REFERENCE_PREFIX: &str = "${secret:";
+const MAX_SECRET_NAME_LEN: usize = 128;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SecretInfo {
+    pub name: String,
+    pub updated_at: String,
+}
+
+/// Per-daemon store of secret values referenced from service `env` entries as `${secret:name}`.
+///
+/// Values are encrypted with ChaCha20-Poly1305 under a key derived from the node keypair, so the
+/// file on disk is useless without `.keys/keypair`. Manifests and service state only ever hold the
+/// references; plaintext is produced by [`SecretStore::resolve_env`] when a runtime launches.
+/// Only manifests applied on this device may hold references; remote applies are refused.
+#[derive(Clone)]
+pub struct SecretStore {
+    fungi_home: PathBuf,
+    write_lock: Arc<Mutex<()>>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SecretsFile {
+    #[serde(default = "default_schema_version")]
+    schema_version: u32,
+    #[serde(default)]
+    secrets: BTreeMap<String, EncryptedSecret>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct EncryptedSecret {
+    nonce: String,
+    ciphertext: String,
+    updated_at: String,
+}
+
+fn default_schema_version() -> u32 {
+    SECRETS_SCHEMA_VERSION
+}
+
+impl SecretStore {
+    pub fn new(fungi_home: impl Into<PathBuf>) -> Self {
+        Self {
+            fungi_home: fungi_home.into(),
+            write_lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    pub fn set(&self, name: &str, value: &str) -> Result<SecretInfo> {
+        validate_secret_name(name)?;
+        let cipher = self.cipher()?;
+        let mut nonce = [0u8; 12];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let ciphertext = cipher
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: value.as_bytes(),
+                    aad: name.as_bytes(),
+                },
+            )
+            .map_err(|_| anyhow::anyhow!("Failed to encrypt secret '{name}'"))?;
+        let updated_at = Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true);
+
+        let _guard = self.write_lock.lock();
+        let mut file = self.load_file()?;
+        file.secrets.insert(
+            name.to_string(),
+            EncryptedSecret {
+                nonce: hex::encode(nonce),
+                ciphertext: hex::encode(ciphertext),
+                updated_at: updated_at.clone(),
+            },
+        );
+        self.save_file(&file)?;
+        Ok(SecretInfo {
+            name: name.to_string(),
+            updated_at,
+        })
+    }
+
+    /// Returns whether a secret with that name existed.
+    pub fn remove(&self, name: &str) -> Result<bool> {
+        let _guard = self.write_lock.lock();
+        let mut file = self.load_file()?;
+        if file.secrets.remove(name).is_none() {
+            return Ok(false);
+        }
+        self.save_file(&file)?;
+        Ok(true)
+    }
+
+    pub fn list(&self) -> Result<Vec<SecretInfo>> {
+        Ok(self
+            .load_file()?
+            .secrets
+            .into_iter()
+            .map(|(name, secret)| SecretInfo {
+                name,
+                updated_at: secret.updated_at,
+            })
+            .collect())
+    }
+
+    /// Decrypts one secret, or returns `None` when no secret has that name.
+    pub fn get(&self, name: &str) -> Result<Option<String>> {
+        let file = self.load_file()?;
+        let Some(secret) = file.secrets.get(name) else {
+            return Ok(None);
+        };
+        decrypt_secret(&self.cipher()?, name, secret).map(Some)
+    }
+
+    /// Replaces every `${secret:name}` reference in the env values with the decrypted secret.
+    /// The store is only read when at least one value contains a reference.
+    pub fn resolve_env(&self, env: &BTreeMap<String, String>) -> Result<BTreeMap<String, String>> {
+        if !env_references_secrets(env) {
+            return Ok(env.clone());
+        }
+
+        let cipher = self.cipher()?;
+        let file = self.load_file()?;
+        env.iter()
+            .map(|(key, value)| {
+                let resolved = substitute_secret_references(value, |name| {
+                    let secret = file.secrets.get(name).ok_or_else(|| {
+                        anyhow::anyhow!("env '{key}' references unknown secret '{name}'")
+                    })?;
+                    decrypt_secret(&cipher, name, secret)
+                })?;
+                Ok((key.clone(), resolved))
+            })
+            .collect()
+    }
+
+    fn cipher(&self) -> Result<ChaCha20Poly1305> {
+        let keypair = get_keypair_from_dir(&self.fungi_home)
+            .context("Failed to load the node keypair for the secrets store")?;
+        let key = keypair
+            .derive_secret(SECRETS_KEY_DOMAIN)
+            .ok_or_else(|| anyhow::anyhow!("node keypair type cannot derive a secrets key"))?;
+        Ok(ChaCha20Poly1305::new(Key::from_slice(&key)))
+    }
+
+    fn path(&self) -> PathBuf {
+        self.fungi_home.join(SECRETS_FILE)
+    }
+
+    fn load_file(&self) -> Result<SecretsFile> {
+        let path = self.path();
+        if !path.exists() {
+            return Ok(SecretsFile::default());
+        }
+        let bytes = fs::read(&path)
+            .with_context(|| format!("Failed to read secrets store: {}", path.display()))?;
+        let file: SecretsFile = serde_json::from_slice(&bytes)
+            .with_context(|| format!("Failed to parse secrets store: {}", path.display()))?;
+        if file.schema_version > SECRETS_SCHEMA_VERSION {
+            bail!(
+                "secrets store {} uses unsupported schema version {}",
+                path.display(),
+                file.schema_version
+            );
+        }
+        Ok(file)
+    }
+
+    fn save_file(&self, file: &SecretsFile) -> Result<()> {
+        let path = self.path();
+        let parent = path.parent().unwrap_or_else(|| Path::new("."));
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create secrets directory: {}", parent.display()))?;
+        let bytes = serde_json::to_vec_pretty(&SecretsFile {
+            schema_version: SECRETS_SCHEMA_VERSION,
+            secrets: file.secrets.clone(),
+        })
+        .context("Failed to encode secrets store")?;
+
+        let mut temp = NamedTempFile::new_in(parent).with_context(|| {
+            format!(
+                "Failed to create temporary secrets file in {}",
+                parent.display()
+            )
+        })?;
+        temp.write_all(&bytes)
+            .context("Failed to write secrets store")?;
+        temp.as_file()
+            .sync_all()
+            .context("Failed to sync secrets store")?;
+        temp.persist(&path)
+            .map_err(|error| error.error)
+            .with_context(|| format!("Failed to persist secrets store: {}", path.display()))?;
+        Ok(())
+    }
+}
+
+pub fn validate_secret_name(name: &str) -> Result<()> {
+    if name.is_empty() || name.len() > MAX_SECRET_NAME_LEN {
+        bail!("secret name must be 1-{MAX_SECRET_NAME_LEN} characters");
+    }
+    if !name
+        .chars()
+        .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '_' | '-' | '.'))
+    {
+        bail!("secret name '{name}' may only contain letters, digits, '_', '-' and '.'");
+    }
+    Ok(())
+}
+
+fn decrypt_secret(
+    cipher: &ChaCha20Poly1305,
+    name: &str,
+    secret: &EncryptedSecret,
+) -> Result<String> {
+    let nonce = hex::decode(&secret.nonce)
+        .ok()
+        .filter(|nonce| nonce.len() == 12)
+        .ok_or_else(|| anyhow::anyhow!("secret '{name}' has an invalid nonce"))?;
+    let ciphertext = hex::decode(&secret.ciphertext)
+        .with_context(|| format!("secret '{name}' has invalid ciphertext"))?;
+    let plaintext = cipher
+        .decrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: &ciphertext,
+                aad: name.as_bytes(),
+            },
+        )
+        .map_err(|_| {
+            anyhow::anyhow!("Failed to decrypt secret '{name}'; was the node keypair replaced?")
+        })?;
+    String::from_utf8(plaintext).with_context(|| format!("secret '{name}' is not valid UTF-8"))
+}
+
+fn substitute_secret_references(
+    value: &str,
+    mut lookup: impl FnMut(&str) -> Result<String>,
+) -> Result<String> {
+    let mut resolved = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find(SECRET_REFERENCE_PREFIX) {
+        resolved.push_str(&rest[..start]);
+        let after_prefix = &rest[start + SECRET_REFERENCE_PREFIX.len()..];
+        let end = after_prefix
+            .find('}')
+            .ok_or_else(|| anyhow::anyhow!("unterminated secret reference in '{value}'"))?;
+        let name = &after_prefix[..end];
+        validate_secret_name(name)?;
+        resolved.push_str(&lookup(name)?);
+        rest = &after_prefix[end + 1..];
+    }
+    resolved.push_str(rest);
+    Ok(resolved)
+}
+
+/// Whether any env value contains a `${secret:name}` reference.
+pub(crate) fn env_references_secrets(env: &BTreeMap<String, String>) -> bool {
+    env.values()
+        .any(|value| value.contains(SECRET_REFERENCE_PREFIX))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store_with_keypair() -> (tempfile::TempDir, SecretStore) {
+        let dir = tempfile::TempDir::new().unwrap();
+        let keys_dir = dir.path().join(".keys");
+        fs::create_dir_all(&keys_dir).unwrap();
+        let keypair = libp2p::identity::Keypair::generate_ed25519();
+        fs::write(
+            keys_dir.join("keypair"),
+            keypair.to_protobuf_encoding().unwrap(),
+        )
+        .unwrap();
+        let store = SecretStore::new(dir.path());
+        (dir, store)
+    }
+
+    #[test]
+    fn resolves_references_without_storing_plaintext() {
+        let (dir, store) = store_with_keypair();
+        store.set("db_password", "hunter2").unwrap();
+        store.set("db_user", "admin").unwrap();
+
+        let on_disk = fs::read_to_string(dir.path().join(SECRETS_FILE)).unwrap();
+        assert!(!on_disk.contains("hunter2"));
+
+        let env = BTreeMap::from([
+            ("PASSWORD".to_string(), "${secret:db_password}".to_string()),
+            (
+                "URL".to_string(),
+                "postgres://${secret:db_user}:${secret:db_password}@db/app".to_string(),
+            ),
+            ("PLAIN".to_string(), "value".to_string()),
+        ]);
+        let resolved = store.resolve_env(&env).unwrap();
+        assert_eq!(resolved["PASSWORD"], "hunter2");
+        assert_eq!(resolved["URL"], "postgres://admin:hunter2@db/app");
+        assert_eq!(resolved["PLAIN"], "value");
+        assert_eq!(store.get("db_user").unwrap().as_deref(), Some("admin"));
+        assert_eq!(store.get("missing").unwrap(), None);
+
+        let names = store
+            .list()
+            .unwrap()
+            .into_iter()
+            .map(|secret| secret.name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["db_password", "db_user"]);
+    }
+
+    #[test]
+    fn rejects_unknown_and_removed_secrets() {
+        let (_dir, store) = store_with_keypair();
+        store.set("token", "abc").unwrap();
+        assert!(store.remove("token").unwrap());
+        assert!(!store.remove("token").unwrap());
+
+        let env = BTreeMap::from([("TOKEN".to_string(), "${secret:token}".to_string())]);
+        let error = store.resolve_env(&env).unwrap_err();
+        assert!(error.to_string().contains("unknown secret 'token'"));
+    }
+
+    #[test]
+    fn env_without_references_does_not_need_a_keypair() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let store = SecretStore::new(dir.path());
+        let env = BTreeMap::from([("A".to_string(), "1".to_string())]);
+        assert_eq!(store.resolve_env(&env).unwrap(), env);
+        assert!(store.set("name", "value").is_err());
+        assert!(validate_secret_name("bad name").is_err());
+    }
+}
@@ -22,6 +22,69 @@ pub enum ServiceControlRequest {
         request_id: Option<String>,
         service: String,
     },
+    ServiceHistory {
+        request_id: Option<String>,
+        service: String,
+    },
+    /// Restores a recorded revision, or the one before the current revision when unset.
+    RollbackService {
+        request_id: Option<String>,
+        service: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        revision: Option<u64>,
+    },
+    /// Runs a job service once and answers when the run has finished.
+    RunJob {
+        request_id: Option<String>,
+        service: String,
+    },
+    /// Schedule and run history of a job service.
+    JobStatus {
+        request_id: Option<String>,
+        service: String,
+    },
+    /// Fetches a stopped service, with its appdata, from another device and applies it here.
+    ImportService {
+        request_id: Option<String>,
+        service: String,
+        from_peer_id: String,
+        /// Start the service once it is applied.
+        #[serde(default)]
+        start: bool,
+    },
+    /// Points the receiving device's saved accesses to a service at the device it moved to.
+    RepointServiceAccess {
+        request_id: Option<String>,
+        service: String,
+        from_peer_id: String,
+        to_peer_id: String,
+    },
+    /// Asks the receiving device to broadcast Wake-on-LAN packets on its LAN for `peer_id`,
+    /// using the MAC addresses it has recorded for that device itself.
+    WakeDevice {
+        request_id: Option<String>,
+        peer_id: String,
+    },
+    /// Creates or updates the receiving device's access to `service` published by `peer_id`.
+    AttachServiceAccess {
+        request_id: Option<String>,
+        peer_id: String,
+        service: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        entry: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        local_port: Option<u16>,
+    },
+    /// Forgets the receiving device's saved access to `service` published by `peer_id`.
+    ForgetServiceAccess {
+        request_id: Option<String>,
+        peer_id: String,
+        service: String,
+    },
+    /// Lists the service accesses saved on the receiving device.
+    ListServiceAccesses {
+        request_id: Option<String>,
+    },
 }
 
 impl ServiceControlRequest {
@@ -31,7 +94,17 @@ impl ServiceControlRequest {
             | Self::ListServices { request_id, .. }
             | Self::StartService { request_id, .. }
             | Self::StopService { request_id, .. }
-            | Self::RemoveService { request_id, .. } => request_id.as_deref(),
+            | Self::RemoveService { request_id, .. }
+            | Self::ServiceHistory { request_id, .. }
+            | Self::RollbackService { request_id, .. }
+            | Self::RunJob { request_id, .. }
+            | Self::JobStatus { request_id, .. }
+            | Self::ImportService { request_id, .. }
+            | Self::RepointServiceAccess { request_id, .. }
+            | Self::WakeDevice { request_id, .. }
+            | Self::AttachServiceAccess { request_id, .. }
+            | Self::ForgetServiceAccess { request_id, .. }
+            | Self::ListServiceAccesses { request_id, .. } => request_id.as_deref(),
         }
     }
 
@@ -39,9 +112,19 @@ impl ServiceControlRequest {
         match self {
             Self::PullService { .. } => None,
             Self::ListServices { .. } => None,
+            Self::WakeDevice { .. } => None,
+            Self::ListServiceAccesses { .. } => None,
             Self::StartService { service, .. }
             | Self::StopService { service, .. }
-            | Self::RemoveService { service, .. } => Some(service.clone()),
+            | Self::RemoveService { service, .. }
+            | Self::ServiceHistory { service, .. }
+            | Self::RollbackService { service, .. }
+            | Self::RunJob { service, .. }
+            | Self::JobStatus { service, .. }
+            | Self::ImportService { service, .. }
+            | Self::RepointServiceAccess { service, .. }
+            | Self::AttachServiceAccess { service, .. }
+            | Self::ForgetServiceAccess { service, .. } => Some(service.clone()),
         }
     }
 }
@@ -56,6 +139,13 @@ pub struct ServiceControlResponse {
     pub service: Option<ServiceControlServiceRef>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub services_json: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revisions_json: Option<String>,
+    /// A job run for `RunJob`, or the job status for `JobStatus`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub job_json: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub service_accesses_json: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<ServiceControlError>,
 }
@@ -68,6 +158,9 @@ impl ServiceControlResponse {
             forgotten_locally: false,
             service: Some(ServiceControlServiceRef { name: service_name }),
             services_json: None,
+            revisions_json: None,
+            job_json: None,
+            service_accesses_json: None,
             error: None,
         }
     }
@@ -79,6 +172,23 @@ impl ServiceControlResponse {
             forgotten_locally: true,
             service: Some(ServiceControlServiceRef { name: service_name }),
             services_json: None,
+            revisions_json: None,
+            job_json: None,
+            service_accesses_json: None,
+            error: None,
+        }
+    }
+
+    pub fn acknowledged(request_id: Option<String>) -> Self {
+        Self {
+            request_id,
+            ok: true,
+            forgotten_locally: false,
+            service: None,
+            services_json: None,
+            revisions_json: None,
+            job_json: None,
+            service_accesses_json: None,
             error: None,
         }
     }
@@ -90,6 +200,58 @@ impl ServiceControlResponse {
             forgotten_locally: false,
             service: None,
             services_json: Some(services_json),
+            revisions_json: None,
+            job_json: None,
+            service_accesses_json: None,
+            error: None,
+        }
+    }
+
+    pub fn success_revisions(
+        request_id: Option<String>,
+        service_name: String,
+        revisions_json: String,
+    ) -> Self {
+        Self {
+            request_id,
+            ok: true,
+            forgotten_locally: false,
+            service: Some(ServiceControlServiceRef { name: service_name }),
+            services_json: None,
+            revisions_json: Some(revisions_json),
+            job_json: None,
+            service_accesses_json: None,
+            error: None,
+        }
+    }
+
+    pub fn success_job(request_id: Option<String>, service_name: String, job_json: String) -> Self {
+        Self {
+            request_id,
+            ok: true,
+            forgotten_locally: false,
+            service: Some(ServiceControlServiceRef { name: service_name }),
+            services_json: None,
+            revisions_json: None,
+            job_json: Some(job_json),
+            service_accesses_json: None,
+            error: None,
+        }
+    }
+
+    pub fn success_service_accesses(
+        request_id: Option<String>,
+        service_accesses_json: String,
+    ) -> Self {
+        Self {
+            request_id,
+            ok: true,
+            forgotten_locally: false,
+            service: None,
+            services_json: None,
+            revisions_json: None,
+            job_json: None,
+            service_accesses_json: Some(service_accesses_json),
             error: None,
         }
     }
@@ -101,6 +263,9 @@ impl ServiceControlResponse {
             forgotten_locally: false,
             service: None,
             services_json: None,
+            revisions_json: None,
+            job_json: None,
+            service_accesses_json: None,
             error: Some(ServiceControlError {
                 code: code.to_string(),
                 message,
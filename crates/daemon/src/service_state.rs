@@ -6,17 +6,26 @@ use std::{
 };
 
 use anyhow::{Context, Result, bail};
-use chrono::Utc;
+use chrono::{SecondsFormat, Utc};
 use fungi_config::paths::FungiPaths;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use tempfile::NamedTempFile;
 use ulid::Ulid;
 
 use crate::runtime::{
-    ServiceManifest, parse_managed_service_manifest_yaml, service_manifest_to_yaml,
+    ServiceJobOutcome, ServiceJobRun, ServiceManifest, ServiceRevision,
+    parse_managed_service_manifest_yaml, service_manifest_to_yaml,
 };
 
 const SERVICE_STATE_SCHEMA_VERSION: u32 = 2;
+const SERVICE_REVISIONS_FILE: &str = "revisions.json";
+const SERVICE_REVISIONS_DIR: &str = "revisions";
+/// Older revisions are pruned once a service has recorded more than this many.
+const MAX_SERVICE_REVISIONS: usize = 10;
+const SERVICE_JOB_RUNS_FILE: &str = "runs.json";
+/// Older job runs are dropped once a job has recorded more than this many.
+const MAX_SERVICE_JOB_RUNS: usize = 20;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -232,6 +241,125 @@ impl ServiceStateStore {
         Ok(())
     }
 
+    /// Records the current manifest of `service_name` as its next revision.
+    pub fn record_revision(
+        &mut self,
+        service_name: &str,
+        applied_by: Option<String>,
+        manifest_digest: Option<String>,
+        rollback_of: Option<u64>,
+    ) -> Result<ServiceRevision> {
+        let local_service_id = self.lookup_local_service_id(service_name)?;
+        let service = self
+            .state
+            .get(&local_service_id)
+            .ok_or_else(|| anyhow::anyhow!("persisted service not found: {service_name}"))?;
+        let manifest_yaml = service_manifest_to_yaml(&service.manifest)?;
+        let service_dir = self.service_dir(&local_service_id);
+        let mut revisions = load_revisions(&service_dir)?;
+
+        let revision = ServiceRevision {
+            revision: revisions.last().map_or(1, |latest| latest.revision + 1),
+            applied_at: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+            applied_by,
+            resolved_manifest_digest: hex::encode(Sha256::digest(manifest_yaml.as_bytes())),
+            manifest_digest,
+            rollback_of,
+        };
+        atomic_write(
+            &revision_manifest_path(&service_dir, revision.revision),
+            manifest_yaml.as_bytes(),
+        )?;
+        revisions.push(revision.clone());
+
+        let excess = revisions.len().saturating_sub(MAX_SERVICE_REVISIONS);
+        for pruned in revisions.drain(..excess) {
+            let path = revision_manifest_path(&service_dir, pruned.revision);
+            if let Err(error) = fs::remove_file(&path) {
+                log::warn!(
+                    "Failed to prune service revision {}: {}",
+                    path.display(),
+                    error
+                );
+            }
+        }
+
+        let bytes =
+            serde_json::to_vec_pretty(&revisions).context("Failed to encode service revisions")?;
+        atomic_write(&service_dir.join(SERVICE_REVISIONS_FILE), &bytes)?;
+        Ok(revision)
+    }
+
+    /// Recorded revisions of `service_name`, oldest first.
+    pub fn service_revisions(&self, service_name: &str) -> Result<Vec<ServiceRevision>> {
+        let local_service_id = self.lookup_local_service_id(service_name)?;
+        load_revisions(&self.service_dir(&local_service_id))
+    }
+
+    /// Inserts `run` into the run history of `service_name`, replacing an earlier record of the
+    /// same run number.
+    pub fn record_job_run(&self, service_name: &str, run: &ServiceJobRun) -> Result<()> {
+        let local_service_id = self.lookup_local_service_id(service_name)?;
+        let service_dir = self.service_dir(&local_service_id);
+        let mut runs = load_job_runs(&service_dir)?;
+        match runs.iter_mut().find(|existing| existing.run == run.run) {
+            Some(existing) => *existing = run.clone(),
+            None => runs.push(run.clone()),
+        }
+        let excess = runs.len().saturating_sub(MAX_SERVICE_JOB_RUNS);
+        runs.drain(..excess);
+
+        let bytes = serde_json::to_vec_pretty(&runs).context("Failed to encode job runs")?;
+        atomic_write(&service_dir.join(SERVICE_JOB_RUNS_FILE), &bytes)
+    }
+
+    /// Marks runs the daemon was still waiting on when it stopped as interrupted.
+    pub fn interrupt_running_job_runs(&self, service_name: &str) -> Result<()> {
+        let local_service_id = self.lookup_local_service_id(service_name)?;
+        let service_dir = self.service_dir(&local_service_id);
+        let mut runs = load_job_runs(&service_dir)?;
+        let mut changed = false;
+        for run in &mut runs {
+            if run.outcome == ServiceJobOutcome::Running {
+                run.outcome = ServiceJobOutcome::Interrupted;
+                changed = true;
+            }
+        }
+        if !changed {
+            return Ok(());
+        }
+        let bytes = serde_json::to_vec_pretty(&runs).context("Failed to encode job runs")?;
+        atomic_write(&service_dir.join(SERVICE_JOB_RUNS_FILE), &bytes)
+    }
+
+    /// Recorded runs of the job `service_name`, oldest first.
+    pub fn job_runs(&self, service_name: &str) -> Result<Vec<ServiceJobRun>> {
+        let local_service_id = self.lookup_local_service_id(service_name)?;
+        load_job_runs(&self.service_dir(&local_service_id))
+    }
+
+    pub fn revision_manifest(&self, service_name: &str, revision: u64) -> Result<ServiceManifest> {
+        let local_service_id = self.lookup_local_service_id(service_name)?;
+        let service_dir = self.service_dir(&local_service_id);
+        let path = revision_manifest_path(&service_dir, revision);
+        if !path.is_file() {
+            bail!("revision {revision} of service '{service_name}' not found");
+        }
+        let manifest_yaml = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read service revision: {}", path.display()))?;
+        let fungi_home = self
+            .services_root
+            .parent()
+            .unwrap_or_else(|| Path::new("."));
+        parse_managed_service_manifest_yaml(
+            &manifest_yaml,
+            &service_dir,
+            fungi_home,
+            &local_service_id,
+        )
+        .with_context(|| format!("Failed to parse service revision: {}", path.display()))
+    }
+
     fn save_service(&mut self, local_service_id: &str) -> Result<()> {
         let service = self
             .state
@@ -374,6 +502,34 @@ fn load_service_state_file(path: &Path, service_dir: &Path) -> Result<ServiceSta
     })
 }
 
+fn load_revisions(service_dir: &Path) -> Result<Vec<ServiceRevision>> {
+    let path = service_dir.join(SERVICE_REVISIONS_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read service revisions: {}", path.display()))?;
+    serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse service revisions: {}", path.display()))
+}
+
+fn load_job_runs(service_dir: &Path) -> Result<Vec<ServiceJobRun>> {
+    let path = service_dir.join(SERVICE_JOB_RUNS_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read job runs: {}", path.display()))?;
+    serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse job runs: {}", path.display()))
+}
+
+fn revision_manifest_path(service_dir: &Path, revision: u64) -> PathBuf {
+    service_dir
+        .join(SERVICE_REVISIONS_DIR)
+        .join(format!("{revision}.yaml"))
+}
+
 fn atomic_write(path: &Path, bytes: &[u8]) -> Result<()> {
     let parent = path.parent().unwrap_or_else(|| Path::new("."));
     fs::create_dir_all(parent).with_context(|| {
@@ -468,6 +624,9 @@ mod tests {
             entrypoint: Vec::new(),
             working_dir: None,
             labels: BTreeMap::new(),
+            on_demand: None,
+            container: Default::default(),
+            job: Default::default(),
         };
 
         store
@@ -541,6 +700,9 @@ mod tests {
             entrypoint: Vec::new(),
             working_dir: None,
             labels: BTreeMap::new(),
+            on_demand: None,
+            container: Default::default(),
+            job: Default::default(),
         };
 
         let local_service_id = store
@@ -557,4 +719,72 @@ mod tests {
         assert!(appdata_dir.is_dir());
         assert_eq!(fs::read_to_string(&appdata_file).unwrap(), "persist me");
     }
+
+    #[test]
+    fn records_numbered_revisions_and_prunes_old_ones() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let services_root = dir.path().join("services");
+        let mut store = ServiceStateStore::load(services_root.clone()).unwrap();
+
+        for version in 1..=12 {
+            store
+                .upsert_service_with_local_service_id(
+                    &revision_manifest(&format!("nginx:1.{version}")),
+                    DesiredServiceState::Stopped,
+                    None,
+                )
+                .unwrap();
+            store
+                .record_revision("demo", Some("peer".to_string()), None, None)
+                .unwrap();
+        }
+
+        let revisions = store.service_revisions("demo").unwrap();
+        let numbers = revisions
+            .iter()
+            .map(|revision| revision.revision)
+            .collect::<Vec<_>>();
+        assert_eq!(numbers, (3..=12).collect::<Vec<_>>());
+        assert_eq!(revisions[0].applied_by.as_deref(), Some("peer"));
+        assert_eq!(revisions[0].resolved_manifest_digest.len(), 64);
+        assert!(store.revision_manifest("demo", 2).is_err());
+
+        let restored = store.revision_manifest("demo", 3).unwrap();
+        assert!(matches!(
+            restored.source,
+            ServiceSource::Docker { ref image } if image == "nginx:1.3"
+        ));
+
+        let reloaded = ServiceStateStore::load(services_root).unwrap();
+        assert_eq!(reloaded.service_revisions("demo").unwrap(), revisions);
+    }
+
+    fn revision_manifest(image: &str) -> ServiceManifest {
+        ServiceManifest {
+            name: "demo".into(),
+            definition_id: None,
+            runtime: RuntimeKind::Docker,
+            run_mode: ServiceRunMode::Command,
+            source: ServiceSource::Docker {
+                image: image.into(),
+            },
+            expose: None,
+            env: BTreeMap::new(),
+            mounts: Vec::new(),
+            ports: vec![ServicePort {
+                name: Some("http".into()),
+                host_port: 18080,
+                host_port_allocation: ServicePortAllocation::Fixed,
+                service_port: 80,
+                protocol: ServicePortProtocol::Tcp,
+            }],
+            command: Vec::new(),
+            entrypoint: Vec::new(),
+            working_dir: None,
+            labels: BTreeMap::new(),
+            on_demand: None,
+            container: Default::default(),
+            job: Default::default(),
+        }
+    }
 }
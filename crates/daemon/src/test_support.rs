@@ -47,6 +47,7 @@ use fungi_config::{
     FungiConfig, devices::DevicesConfig, direct_addresses::DirectAddressCache,
     trusted_devices::TrustedDevicesConfig,
 };
+use fungi_swarm::PeerAddressSource;
 use libp2p::{Multiaddr, PeerId, identity::Keypair, multiaddr::Protocol};
 use tempfile::TempDir;
 
@@ -81,6 +82,7 @@ fn minimal_test_config(dir: &TempDir, tcp_port: u16) -> FungiConfig {
     cfg.network.listen_udp_port = tcp_port.wrapping_add(1000);
     cfg.network.relay_enabled = false;
     cfg.network.custom_relay_addresses.clear();
+    cfg.http_gateway.enabled = false;
     cfg
 }
 
@@ -238,14 +240,7 @@ impl TestDaemon {
     /// both daemons keep running while the connection is being established.
     pub async fn connect_to(&self, other: &TestDaemon) -> Result<()> {
         let target_peer_id = other.peer_id();
-        let target_addr = other.tcp_multiaddr();
-
-        // Register the address with the dialling swarm so it knows where to reach the peer.
-        self.swarm_control()
-            .invoke_swarm(move |swarm| {
-                swarm.add_peer_address(target_peer_id, target_addr);
-            })
-            .await?;
+        self.learn_address_of(other);
 
         // Initiate the dial.
         self.swarm_control()
@@ -256,6 +251,16 @@ impl TestDaemon {
         Ok(())
     }
 
+    /// Record `other`'s address the way a manually added device address is, so that opening a
+    /// stream to it dials on demand.
+    pub fn learn_address_of(&self, other: &TestDaemon) {
+        self.swarm_control().state().record_peer_address(
+            other.peer_id(),
+            other.tcp_multiaddr(),
+            PeerAddressSource::Manual,
+        );
+    }
+
     /// Poll (up to `timeout`) until this daemon reports `peer_id` as connected.
     ///
     /// Useful after calling [`connect_to`] when you need to wait for the handshake to complete.
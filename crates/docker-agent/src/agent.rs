@@ -1,10 +1,15 @@
 use crate::{
     AgentPolicy, DockerAgentError, Result,
     client::{
-        CreateContainerBody, CreateContainerRequest, DockerClient, HostConfig, HostPortBinding,
-        InspectContainerResponse,
+        CpuStatsResponse, CreateContainerBody, CreateContainerRequest, CreateExecBody,
+        DockerClient, HostConfig, HostPortBinding, InspectContainerResponse, StatsResponse,
     },
-    spec::{ContainerSpec, LogsOptions, PortProtocol},
+    digest::{canonical_image_reference, pinned_image_digest},
+    engine::{EngineFlavor, EngineInfo},
+    exec::ExecSession,
+    image::{ImageDetails, ImagePullOptions},
+    policy::normalize_capability,
+    spec::{ContainerSpec, ExecSpec, LogsOptions, PortProtocol, UNCONFINED_PROFILE},
 };
 use hyper::StatusCode;
 use serde::Serialize;
@@ -13,28 +18,59 @@ use std::collections::BTreeMap;
 pub struct DockerAgent {
     policy: AgentPolicy,
     client: DockerClient,
+    flavor: EngineFlavor,
 }
 
 impl DockerAgent {
+    /// Creates an agent for the policy's socket, guessing the engine flavour from the socket
+    /// path. Use [`DockerAgent::with_flavor`] once [`DockerAgent::detect_engine`] has run.
     pub fn new(policy: AgentPolicy) -> Self {
         let client = DockerClient::new(&policy.socket_path);
-        Self { policy, client }
+        let flavor = EngineFlavor::guess_from_socket_path(&policy.socket_path);
+        Self {
+            policy,
+            client,
+            flavor,
+        }
+    }
+
+    pub fn with_flavor(mut self, flavor: EngineFlavor) -> Self {
+        self.flavor = flavor;
+        self
+    }
+
+    pub fn flavor(&self) -> EngineFlavor {
+        self.flavor
+    }
+
+    /// Asks the engine what it is, including whether it runs rootless.
+    pub async fn detect_engine(&self) -> Result<EngineInfo> {
+        let version = self.client.version().await?;
+        let info = self.client.info().await?;
+        Ok(EngineInfo::from_responses(version, info))
     }
 
     pub async fn create_container(&self, spec: &ContainerSpec) -> Result<ContainerDetails> {
         self.policy.validate_create_spec(spec)?;
+        let image = canonical_image_reference(&spec.image);
+        let image = self.flavor.image_reference(&image);
+        if let Some(digest) = pinned_image_digest(&image) {
+            self.ensure_pinned_image(&image, digest).await?;
+        }
 
+        let mut body = to_create_body(spec, &self.policy)?;
+        body.image = image.to_string();
         let request = CreateContainerRequest {
             name: spec.name.clone(),
-            body: to_create_body(spec, &self.policy),
+            body,
         };
         let created = match self.client.create_container(&request).await {
             Ok(created) => created,
             Err(DockerAgentError::DockerApi { status, message })
-                if status == StatusCode::NOT_FOUND
-                    && missing_image_message(&message, &spec.image) =>
+                if status == StatusCode::NOT_FOUND && missing_image_message(&message, &image) =>
             {
-                self.client.pull_image(&spec.image).await?;
+                self.pull_image(&image, &ImagePullOptions::default())
+                    .await?;
                 self.client.create_container(&request).await?
             }
             Err(error) => return Err(error),
@@ -42,6 +78,61 @@ impl DockerAgent {
         self.inspect_container(&created.id).await
     }
 
+    pub async fn pull_image(&self, image: &str, options: &ImagePullOptions) -> Result<()> {
+        let image = self.flavor.image_reference(image);
+        self.client
+            .pull_image(&image, options.auth.as_ref(), |progress| {
+                if let Some(report) = &options.progress {
+                    report(progress);
+                }
+            })
+            .await
+    }
+
+    /// Pulls `image` unless it is already present. Returns whether a pull happened.
+    pub async fn ensure_image(&self, image: &str, options: &ImagePullOptions) -> Result<bool> {
+        match self
+            .client
+            .inspect_image(&self.flavor.image_reference(image))
+            .await
+        {
+            Ok(_) => Ok(false),
+            Err(DockerAgentError::DockerApi { status, .. }) if status == StatusCode::NOT_FOUND => {
+                self.pull_image(image, options).await?;
+                Ok(true)
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    pub async fn inspect_image(&self, image: &str) -> Result<ImageDetails> {
+        let image = self
+            .client
+            .inspect_image(&self.flavor.image_reference(image))
+            .await?;
+        Ok(ImageDetails {
+            id: image.id,
+            repo_tags: image.repo_tags,
+            repo_digests: image.repo_digests,
+            size: image.size,
+        })
+    }
+
+    /// Whether any container, running or stopped and managed or not, was created from `image`.
+    pub async fn image_in_use(&self, image: &str) -> Result<bool> {
+        Ok(!self
+            .client
+            .list_containers_by_ancestor(&self.flavor.image_reference(image))
+            .await?
+            .is_empty())
+    }
+
+    pub async fn remove_image(&self, image: &str) -> Result<()> {
+        self.client
+            .remove_image(&self.flavor.image_reference(image))
+            .await
+    }
+
     pub async fn start_container(&self, id: &str) -> Result<()> {
         self.ensure_managed(id).await?;
         self.client.start_container(id).await
@@ -65,23 +156,110 @@ impl DockerAgent {
 
     pub async fn container_logs(&self, id: &str, options: &LogsOptions) -> Result<ContainerLogs> {
         self.ensure_managed(id).await?;
-        let raw = self.client.container_logs(id, options).await?;
+        let logs = self.client.container_logs(id, options).await?;
+        let text = match logs.multiplexed {
+            Some(true) => decode_log_frames(&logs.body),
+            Some(false) => String::from_utf8_lossy(&logs.body).to_string(),
+            None => sniff_log_frames(&logs.body),
+        };
         Ok(ContainerLogs {
-            text: decode_log_frames(&raw),
-            raw,
+            text,
+            raw: logs.body,
+        })
+    }
+
+    pub async fn container_stats(&self, id: &str) -> Result<ContainerStats> {
+        self.ensure_managed(id).await?;
+        let stats = self.client.container_stats(id).await?;
+        Ok(map_container_stats(stats))
+    }
+
+    /// Runs a command in a running managed container and attaches to it.
+    pub async fn exec(&self, id: &str, spec: &ExecSpec) -> Result<ExecSession> {
+        if spec.command.is_empty() {
+            return Err(DockerAgentError::InvalidSpec(
+                "exec command must not be empty".into(),
+            ));
+        }
+        self.ensure_managed(id).await?;
+        let created = self
+            .client
+            .create_exec(
+                id,
+                &CreateExecBody {
+                    cmd: spec.command.clone(),
+                    attach_stdin: true,
+                    attach_stdout: true,
+                    attach_stderr: true,
+                    tty: spec.tty,
+                    env: spec
+                        .env
+                        .iter()
+                        .map(|(key, value)| format!("{key}={value}"))
+                        .collect(),
+                    working_dir: spec.working_dir.clone(),
+                },
+            )
+            .await?;
+        let stream = self.client.start_exec(&created.id, spec.tty).await?;
+        Ok(ExecSession {
+            id: created.id,
+            tty: spec.tty,
+            stream,
         })
     }
 
+    /// Resizes the pseudo-terminal of an exec instance started with a TTY.
+    pub async fn resize_exec(&self, exec_id: &str, rows: u16, cols: u16) -> Result<()> {
+        self.client.resize_exec(exec_id, rows, cols).await
+    }
+
+    /// Exit code of a finished exec instance; `None` while it is still running.
+    pub async fn exec_exit_code(&self, exec_id: &str) -> Result<Option<i32>> {
+        let inspected = self.client.inspect_exec(exec_id).await?;
+        Ok(if inspected.running {
+            None
+        } else {
+            inspected.exit_code
+        })
+    }
+
+    /// Pulls a digest-pinned image if needed and refuses to continue unless the local image was
+    /// recorded under that digest.
+    async fn ensure_pinned_image(&self, image: &str, digest: &str) -> Result<()> {
+        let inspected = match self.client.inspect_image(image).await {
+            Ok(inspected) => inspected,
+            Err(DockerAgentError::DockerApi { status, .. }) if status == StatusCode::NOT_FOUND => {
+                self.pull_image(image, &ImagePullOptions::default()).await?;
+                self.client.inspect_image(image).await?
+            }
+            Err(error) => return Err(error),
+        };
+        let pinned = inspected
+            .repo_digests
+            .iter()
+            .any(|repo_digest| pinned_image_digest(repo_digest) == Some(digest));
+        if !pinned {
+            return Err(DockerAgentError::IntegrityMismatch(format!(
+                "image {} ({}) does not match pinned digest sha256:{digest}",
+                image, inspected.id
+            )));
+        }
+        Ok(())
+    }
+
     async fn ensure_managed(&self, id: &str) -> Result<()> {
         let details = self.client.inspect_container(id).await?;
         ensure_managed_labels(&self.policy, &details)
     }
 }
 
+/// Docker says `No such image: <ref>`, Podman `<ref>: image not known`.
 fn missing_image_message(message: &str, image: &str) -> bool {
     let normalized_message = message.to_ascii_lowercase();
     let normalized_image = image.to_ascii_lowercase();
-    normalized_message.contains("no such image") && normalized_message.contains(&normalized_image)
+    (normalized_message.contains("no such image") || normalized_message.contains("image not known"))
+        && normalized_message.contains(&normalized_image)
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -97,6 +275,54 @@ pub struct ContainerDetails {
 pub struct ContainerState {
     pub status: String,
     pub running: bool,
+    /// Reported by the engine once the container has exited.
+    pub exit_code: Option<i32>,
+}
+
+/// Cumulative CPU time of a container next to the host's at the same moment, both in
+/// nanoseconds. Two samples give the container's CPU share in between.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct CpuSample {
+    pub total_usage: u64,
+    pub system_usage: u64,
+    pub online_cpus: u32,
+}
+
+impl CpuSample {
+    /// CPU use since `previous` as a percentage of one core, so a busy container on four cores
+    /// can reach 400%.
+    pub fn percent_since(&self, previous: &CpuSample) -> Option<f64> {
+        let used = self.total_usage.checked_sub(previous.total_usage)?;
+        let elapsed = self.system_usage.checked_sub(previous.system_usage)?;
+        if elapsed == 0 {
+            return None;
+        }
+        Some(used as f64 / elapsed as f64 * f64::from(self.online_cpus) * 100.0)
+    }
+}
+
+/// One stats sample of a container. Fields the engine did not report, e.g. for a stopped
+/// container or under cgroup v2 without IO accounting, are `None`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ContainerStats {
+    pub cpu: Option<CpuSample>,
+    /// The engine's own earlier sample, when it took one.
+    pub previous_cpu: Option<CpuSample>,
+    /// Usage without the reclaimable page cache, as `docker stats` reports it.
+    pub memory_usage_bytes: Option<u64>,
+    pub memory_limit_bytes: Option<u64>,
+    pub network_rx_bytes: Option<u64>,
+    pub network_tx_bytes: Option<u64>,
+    pub block_read_bytes: Option<u64>,
+    pub block_write_bytes: Option<u64>,
+}
+
+impl ContainerStats {
+    /// CPU use since the engine's earlier sample, or since `previous` when there is none.
+    pub fn cpu_percent(&self, previous: Option<&CpuSample>) -> Option<f64> {
+        let cpu = self.cpu?;
+        cpu.percent_since(self.previous_cpu.as_ref().or(previous)?)
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -127,11 +353,76 @@ fn map_container_details(details: InspectContainerResponse) -> ContainerDetails
         state: ContainerState {
             status: details.state.status,
             running: details.state.running,
+            exit_code: (!details.state.running)
+                .then_some(details.state.exit_code)
+                .flatten(),
         },
     }
 }
 
-fn to_create_body(spec: &ContainerSpec, policy: &AgentPolicy) -> CreateContainerBody {
+fn map_container_stats(stats: StatsResponse) -> ContainerStats {
+    let memory = &stats.memory_stats;
+    let reclaimable = memory.stats.as_ref().and_then(|stats| {
+        ["inactive_file", "total_inactive_file", "cache"]
+            .iter()
+            .find_map(|key| stats.get(*key).copied())
+    });
+    let memory_usage_bytes = memory
+        .usage
+        .map(|usage| usage.saturating_sub(reclaimable.unwrap_or(0)));
+
+    let (network_rx_bytes, network_tx_bytes) = match &stats.networks {
+        Some(networks) => (
+            Some(networks.values().map(|network| network.rx_bytes).sum()),
+            Some(networks.values().map(|network| network.tx_bytes).sum()),
+        ),
+        None => (None, None),
+    };
+
+    let block_bytes = |op: &str| {
+        stats
+            .blkio_stats
+            .io_service_bytes_recursive
+            .as_ref()
+            .map(|entries| {
+                entries
+                    .iter()
+                    .filter(|entry| entry.op.eq_ignore_ascii_case(op))
+                    .map(|entry| entry.value)
+                    .sum()
+            })
+    };
+
+    ContainerStats {
+        cpu: cpu_sample(&stats.cpu_stats),
+        previous_cpu: cpu_sample(&stats.precpu_stats),
+        memory_usage_bytes,
+        memory_limit_bytes: memory.limit,
+        network_rx_bytes,
+        network_tx_bytes,
+        block_read_bytes: block_bytes("read"),
+        block_write_bytes: block_bytes("write"),
+    }
+}
+
+fn cpu_sample(stats: &CpuStatsResponse) -> Option<CpuSample> {
+    let system_usage = stats.system_cpu_usage.filter(|usage| *usage > 0)?;
+    let online_cpus = stats
+        .online_cpus
+        .filter(|cpus| *cpus > 0)
+        .or_else(|| {
+            let cpus = stats.cpu_usage.percpu_usage.as_ref()?.len();
+            u32::try_from(cpus).ok().filter(|cpus| *cpus > 0)
+        })
+        .unwrap_or(1);
+    Some(CpuSample {
+        total_usage: stats.cpu_usage.total_usage,
+        system_usage,
+        online_cpus,
+    })
+}
+
+fn to_create_body(spec: &ContainerSpec, policy: &AgentPolicy) -> Result<CreateContainerBody> {
     let mut labels = spec.labels.clone();
     labels.insert(
         policy.managed_label_key.clone(),
@@ -146,8 +437,45 @@ fn to_create_body(spec: &ContainerSpec, policy: &AgentPolicy) -> CreateContainer
     let binds = spec
         .mounts
         .iter()
-        .map(|mount| format!("{}:{}", mount.host_path.display(), mount.container_path))
+        .map(|mount| {
+            bind_entry(
+                &mount.host_path.display().to_string(),
+                &mount.container_path,
+                mount.read_only,
+            )
+        })
+        .chain(
+            spec.volumes
+                .iter()
+                .map(|volume| bind_entry(&volume.name, &volume.container_path, volume.read_only)),
+        )
         .collect();
+    let tmpfs = spec
+        .tmpfs
+        .iter()
+        .map(|tmpfs| {
+            let options = tmpfs
+                .size_bytes
+                .map(|size| format!("size={size}"))
+                .unwrap_or_default();
+            (tmpfs.container_path.clone(), options)
+        })
+        .collect();
+
+    let security = &spec.security;
+    let mut security_opt = Vec::new();
+    if let Some(profile) = &security.seccomp_profile {
+        let profile = if profile == UNCONFINED_PROFILE {
+            profile.clone()
+        } else {
+            // The engine API takes the profile JSON itself rather than a path.
+            std::fs::read_to_string(profile)?
+        };
+        security_opt.push(format!("seccomp={profile}"));
+    }
+    if let Some(profile) = &security.apparmor_profile {
+        security_opt.push(format!("apparmor={profile}"));
+    }
 
     let mut exposed_ports = BTreeMap::new();
     let mut port_bindings = BTreeMap::new();
@@ -162,18 +490,42 @@ fn to_create_body(spec: &ContainerSpec, policy: &AgentPolicy) -> CreateContainer
         );
     }
 
-    CreateContainerBody {
+    Ok(CreateContainerBody {
         image: spec.image.clone(),
         env,
         cmd: spec.command.clone(),
         entrypoint: spec.entrypoint.clone(),
         working_dir: spec.working_dir.clone(),
+        user: security.user.clone(),
         labels,
         exposed_ports,
         host_config: HostConfig {
             binds,
             port_bindings,
+            tmpfs,
+            cap_add: security
+                .cap_add
+                .iter()
+                .map(|cap| normalize_capability(cap))
+                .collect(),
+            cap_drop: security
+                .cap_drop
+                .iter()
+                .map(|cap| normalize_capability(cap))
+                .collect(),
+            readonly_rootfs: security.read_only_rootfs,
+            privileged: security.privileged,
+            network_mode: security.host_network.then(|| "host".to_string()),
+            security_opt,
         },
+    })
+}
+
+fn bind_entry(source: &str, container_path: &str, read_only: bool) -> String {
+    if read_only {
+        format!("{source}:{container_path}:ro")
+    } else {
+        format!("{source}:{container_path}")
     }
 }
 
@@ -184,10 +536,31 @@ fn protocol_name(protocol: PortProtocol) -> &'static str {
     }
 }
 
+/// Decodes frames of a stream the engine declared multiplexed, keeping the payload of a trailing
+/// frame that was cut short.
 fn decode_log_frames(bytes: &[u8]) -> String {
+    let (mut output, offset) = read_log_frames(bytes);
+    if let Some(partial) = bytes.get(offset + 8..) {
+        output.push_str(&String::from_utf8_lossy(partial));
+    }
+    output
+}
+
+/// Treats the stream as multiplexed only when it parses cleanly as frames, for engines that do
+/// not label their log streams.
+fn sniff_log_frames(bytes: &[u8]) -> String {
+    let (output, offset) = read_log_frames(bytes);
+    if offset > 0 && offset == bytes.len() {
+        output
+    } else {
+        String::from_utf8_lossy(bytes).to_string()
+    }
+}
+
+/// Reads whole frames from the start of `bytes`, returning their text and the consumed length.
+fn read_log_frames(bytes: &[u8]) -> (String, usize) {
     let mut output = String::new();
     let mut offset = 0;
-    let mut parsed_frame = false;
 
     while bytes.len().saturating_sub(offset) >= 8 {
         let frame_len = u32::from_be_bytes([
@@ -203,14 +576,8 @@ fn decode_log_frames(bytes: &[u8]) -> String {
         }
         output.push_str(&String::from_utf8_lossy(&bytes[frame_start..frame_end]));
         offset = frame_end;
-        parsed_frame = true;
-    }
-
-    if parsed_frame && offset == bytes.len() {
-        output
-    } else {
-        String::from_utf8_lossy(bytes).to_string()
     }
+    (output, offset)
 }
 
 #[cfg(test)]
@@ -219,12 +586,82 @@ mod tests {
 
     #[test]
     fn decodes_plain_logs() {
-        assert_eq!(decode_log_frames(b"hello\n"), "hello\n");
+        assert_eq!(sniff_log_frames(b"hello\n"), "hello\n");
     }
 
     #[test]
     fn decodes_multiplexed_logs() {
         let payload = [1, 0, 0, 0, 0, 0, 0, 6, b'h', b'e', b'l', b'l', b'o', b'\n'];
+        assert_eq!(sniff_log_frames(&payload), "hello\n");
         assert_eq!(decode_log_frames(&payload), "hello\n");
     }
+
+    #[test]
+    fn recognizes_missing_image_messages_of_both_engines() {
+        assert!(missing_image_message(
+            "No such image: nginx:1.27",
+            "nginx:1.27"
+        ));
+        assert!(missing_image_message(
+            "docker.io/library/nginx:1.27: image not known",
+            "docker.io/library/nginx:1.27"
+        ));
+        assert!(!missing_image_message("network not known", "nginx"));
+    }
+
+    #[test]
+    fn maps_mounts_and_security_into_host_config() {
+        let policy = AgentPolicy {
+            socket_path: "/var/run/docker.sock".into(),
+            managed_label_key: "managed_by".into(),
+            managed_label_value: "fungi".into(),
+            allowed_host_paths: Vec::new(),
+            allowed_ports: Vec::new(),
+            security: Default::default(),
+        };
+        let spec = ContainerSpec {
+            image: "postgres:17".into(),
+            mounts: vec![crate::BindMount {
+                host_path: "/srv/conf".into(),
+                container_path: "/etc/app".into(),
+                read_only: true,
+            }],
+            volumes: vec![crate::VolumeMount {
+                name: "pgdata".into(),
+                container_path: "/var/lib/postgresql/data".into(),
+                read_only: false,
+            }],
+            tmpfs: vec![crate::TmpfsMount {
+                container_path: "/tmp".into(),
+                size_bytes: Some(64 << 20),
+            }],
+            security: crate::ContainerSecurity {
+                user: Some("999:999".into()),
+                read_only_rootfs: true,
+                cap_drop: vec!["all".into()],
+                cap_add: vec!["CAP_CHOWN".into()],
+                apparmor_profile: Some("docker-default".into()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let body = serde_json::to_value(to_create_body(&spec, &policy).unwrap()).unwrap();
+        assert_eq!(body["User"], "999:999");
+        let host_config = &body["HostConfig"];
+        assert_eq!(
+            host_config["Binds"],
+            serde_json::json!(["/srv/conf:/etc/app:ro", "pgdata:/var/lib/postgresql/data"])
+        );
+        assert_eq!(host_config["Tmpfs"]["/tmp"], "size=67108864");
+        assert_eq!(host_config["CapDrop"], serde_json::json!(["ALL"]));
+        assert_eq!(host_config["CapAdd"], serde_json::json!(["CHOWN"]));
+        assert_eq!(host_config["ReadonlyRootfs"], true);
+        assert_eq!(
+            host_config["SecurityOpt"],
+            serde_json::json!(["apparmor=docker-default"])
+        );
+        assert!(host_config.get("Privileged").is_none());
+        assert!(host_config.get("NetworkMode").is_none());
+    }
 }
@@ -60,7 +60,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         managed_label_value: args.label_value.clone(),
         allowed_host_paths: vec![args.allowed_root.clone()],
         allowed_ports: vec![PortRule::Single(args.host_port)],
+        security: Default::default(),
     });
+    let engine = agent.detect_engine().await?;
+    eprintln!(
+        "engine: {} {} (api {}, rootless: {})",
+        engine.flavor, engine.version, engine.api_version, engine.rootless
+    );
+    let agent = agent.with_flavor(engine.flavor);
 
     match args.command {
         Command::Create => {
@@ -144,6 +151,7 @@ fn build_spec(args: &Args) -> ContainerSpec {
         mounts: vec![BindMount {
             host_path: args.mount_host.clone(),
             container_path: args.mount_target.clone(),
+            read_only: false,
         }],
         ports: vec![PortBinding {
             host_port: args.host_port,
@@ -154,6 +162,7 @@ fn build_spec(args: &Args) -> ContainerSpec {
         entrypoint: Vec::new(),
         working_dir: None,
         labels: BTreeMap::new(),
+        ..Default::default()
     }
 }
 
@@ -192,6 +201,7 @@ fn resolve_socket_path(explicit: Option<&Path>) -> Result<PathBuf, Box<dyn std::
 
     if let Some(path) = docker_host
         .as_deref()
+        .or(env::var("CONTAINER_HOST").ok().as_deref())
         .and_then(|host| host.strip_prefix("unix://"))
     {
         return Ok(PathBuf::from(path));
@@ -208,7 +218,16 @@ fn resolve_socket_path(explicit: Option<&Path>) -> Result<PathBuf, Box<dyn std::
             .ok()
             .map(PathBuf::from)
             .map(|home| home.join(".docker/run/docker.sock"));
-        let candidates = [home_socket, Some(PathBuf::from("/var/run/docker.sock"))];
+        let podman_socket = env::var("XDG_RUNTIME_DIR")
+            .ok()
+            .map(PathBuf::from)
+            .map(|runtime_dir| runtime_dir.join("podman/podman.sock"));
+        let candidates = [
+            home_socket,
+            Some(PathBuf::from("/var/run/docker.sock")),
+            podman_socket,
+            Some(PathBuf::from("/run/podman/podman.sock")),
+        ];
 
         for candidate in candidates.into_iter().flatten() {
             if candidate.exists() {
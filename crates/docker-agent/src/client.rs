@@ -1,9 +1,16 @@
-use crate::{DockerAgentError, Result, spec::LogsOptions};
+use crate::{
+    DockerAgentError, Result,
+    image::{ImagePullProgress, RegistryAuth, parse_pull_line},
+    spec::LogsOptions,
+};
 use bytes::Bytes;
 use http_body_util::{BodyExt, Full};
-use hyper::{Method, Request, StatusCode, client::conn::http1, header};
+use hyper::{
+    Method, Request, Response, StatusCode, body::Incoming, client::conn::http1, header,
+    upgrade::Upgraded,
+};
 use hyper_util::rt::TokioIo;
-use percent_encoding::{AsciiSet, CONTROLS, utf8_percent_encode};
+use percent_encoding::{AsciiSet, CONTROLS, NON_ALPHANUMERIC, utf8_percent_encode};
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use std::{collections::BTreeMap, path::Path};
 #[cfg(unix)]
@@ -45,16 +52,77 @@ impl DockerClient {
             .await
     }
 
-    pub async fn pull_image(&self, image: &str) -> Result<()> {
+    /// Pulls an image, reporting each progress message of the engine's JSON stream as it arrives.
+    pub async fn pull_image(
+        &self,
+        image: &str,
+        auth: Option<&RegistryAuth>,
+        mut on_progress: impl FnMut(ImagePullProgress),
+    ) -> Result<()> {
         let mut path = String::from("/images/create?fromImage=");
         path.push_str(&utf8_percent_encode(image, QUERY_ENCODE_SET).to_string());
-        let response = self.send(Method::POST, &path, Vec::new(), None).await?;
-        if !response.status.is_success() {
-            return Err(api_error(response.status, &response.body)?);
+        let mut headers = Vec::new();
+        if let Some(auth) = auth {
+            headers.push(("X-Registry-Auth", auth.header_value()?));
+        }
+        let response = self
+            .send_streaming(Method::POST, &path, Vec::new(), None, &headers)
+            .await?;
+        let status = response.status();
+        let mut body = response.into_body();
+        if !status.is_success() {
+            let body = body.collect().await?.to_bytes();
+            return Err(api_error(status, &body)?);
+        }
+
+        let mut pending = Vec::new();
+        while let Some(frame) = body.frame().await {
+            let Ok(data) = frame?.into_data() else {
+                continue;
+            };
+            pending.extend_from_slice(&data);
+            while let Some(line_end) = pending.iter().position(|byte| *byte == b'\n') {
+                let line = pending.drain(..=line_end).collect::<Vec<_>>();
+                if let Some(progress) =
+                    parse_pull_line(image, &line).map_err(DockerAgentError::PullFailed)?
+                {
+                    on_progress(progress);
+                }
+            }
+        }
+        if let Some(progress) =
+            parse_pull_line(image, &pending).map_err(DockerAgentError::PullFailed)?
+        {
+            on_progress(progress);
         }
         Ok(())
     }
 
+    pub async fn inspect_image(&self, image: &str) -> Result<InspectImageResponse> {
+        let path = format!("/images/{image}/json");
+        self.send_json(Method::GET, &path, Option::<&()>::None)
+            .await
+    }
+
+    pub async fn remove_image(&self, image: &str) -> Result<()> {
+        let path = format!("/images/{image}");
+        self.send_bytes(Method::DELETE, &path).await.map(|_| ())
+    }
+
+    /// Lists containers, running or not, created from `image`.
+    pub async fn list_containers_by_ancestor(
+        &self,
+        image: &str,
+    ) -> Result<Vec<ContainerSummaryResponse>> {
+        let filters = serde_json::json!({ "ancestor": [image] }).to_string();
+        let path = format!(
+            "/containers/json?all=true&filters={}",
+            utf8_percent_encode(&filters, NON_ALPHANUMERIC)
+        );
+        self.send_json(Method::GET, &path, Option::<&()>::None)
+            .await
+    }
+
     pub async fn start_container(&self, id: &str) -> Result<()> {
         let path = format!("/containers/{id}/start");
         self.send_empty(Method::POST, &path).await
@@ -76,7 +144,76 @@ impl DockerClient {
             .await
     }
 
-    pub async fn container_logs(&self, id: &str, options: &LogsOptions) -> Result<Vec<u8>> {
+    /// A single stats sample. Docker skips the second sample it would otherwise wait for, so
+    /// `precpu_stats` is usually empty; Podman fills it regardless.
+    pub async fn container_stats(&self, id: &str) -> Result<StatsResponse> {
+        let path = format!("/containers/{id}/stats?stream=false&one-shot=true");
+        self.send_json(Method::GET, &path, Option::<&()>::None)
+            .await
+    }
+
+    pub async fn create_exec(&self, id: &str, body: &CreateExecBody) -> Result<CreateExecResponse> {
+        let path = format!("/containers/{id}/exec");
+        self.send_json(Method::POST, &path, Some(body)).await
+    }
+
+    /// Starts an exec instance and takes over the connection, which then carries the process's
+    /// stdin one way and its output the other.
+    pub async fn start_exec(&self, exec_id: &str, tty: bool) -> Result<TokioIo<Upgraded>> {
+        let path = format!("/exec/{exec_id}/start");
+        let body = serde_json::to_vec(&StartExecBody { detach: false, tty })?;
+        let io = self.connect().await?;
+        let (mut sender, connection) = http1::handshake(io).await?;
+        tokio::spawn(async move {
+            let _ = connection.with_upgrades().await;
+        });
+
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri(path)
+            .header(header::HOST, "docker")
+            .header(header::CONTENT_TYPE, "application/json")
+            .header(header::CONTENT_LENGTH, body.len())
+            .header(header::CONNECTION, "Upgrade")
+            .header(header::UPGRADE, "tcp")
+            .body(Full::new(Bytes::from(body)))?;
+        let response = sender.send_request(request).await?;
+        let status = response.status();
+        if status != StatusCode::SWITCHING_PROTOCOLS {
+            let body = response.into_body().collect().await?.to_bytes();
+            if status.is_success() {
+                return Err(DockerAgentError::DockerApi {
+                    status,
+                    message: "engine did not upgrade the exec connection".into(),
+                });
+            }
+            return Err(api_error(status, &body)?);
+        }
+        Ok(TokioIo::new(hyper::upgrade::on(response).await?))
+    }
+
+    pub async fn resize_exec(&self, exec_id: &str, rows: u16, cols: u16) -> Result<()> {
+        let path = format!("/exec/{exec_id}/resize?h={rows}&w={cols}");
+        self.send_bytes(Method::POST, &path).await.map(|_| ())
+    }
+
+    pub async fn inspect_exec(&self, exec_id: &str) -> Result<InspectExecResponse> {
+        let path = format!("/exec/{exec_id}/json");
+        self.send_json(Method::GET, &path, Option::<&()>::None)
+            .await
+    }
+
+    pub async fn version(&self) -> Result<VersionResponse> {
+        self.send_json(Method::GET, "/version", Option::<&()>::None)
+            .await
+    }
+
+    pub async fn info(&self) -> Result<InfoResponse> {
+        self.send_json(Method::GET, "/info", Option::<&()>::None)
+            .await
+    }
+
+    pub async fn container_logs(&self, id: &str, options: &LogsOptions) -> Result<LogsResponse> {
         let mut path = format!(
             "/containers/{id}/logs?stdout={}&stderr={}",
             options.stdout, options.stderr
@@ -87,8 +224,22 @@ impl DockerClient {
         } else {
             path.push_str("&tail=all");
         }
+        if let Some(since) = &options.since {
+            path.push_str("&since=");
+            path.push_str(&utf8_percent_encode(since, QUERY_ENCODE_SET).to_string());
+        }
 
-        self.send_bytes(Method::GET, &path).await
+        let response = self.send(Method::GET, &path, Vec::new(), None).await?;
+        if !response.status.is_success() {
+            return Err(api_error(response.status, &response.body)?);
+        }
+        Ok(LogsResponse {
+            multiplexed: response
+                .content_type
+                .as_deref()
+                .and_then(multiplexed_content_type),
+            body: response.body,
+        })
     }
 
     async fn send_empty(&self, method: Method, path: &str) -> Result<()> {
@@ -142,10 +293,32 @@ impl DockerClient {
         body: Vec<u8>,
         content_type: Option<&str>,
     ) -> Result<HttpResponse> {
-        #[cfg(unix)]
-        let io = TokioIo::new(UnixStream::connect(&self.socket_path).await?);
-        #[cfg(windows)]
-        let io = TokioIo::new(connect_named_pipe(&self.socket_path)?);
+        let response = self
+            .send_streaming(method, path, body, content_type, &[])
+            .await?;
+        let status = response.status();
+        let content_type = response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let body = response.into_body().collect().await?.to_bytes().to_vec();
+        Ok(HttpResponse {
+            status,
+            content_type,
+            body,
+        })
+    }
+
+    async fn send_streaming(
+        &self,
+        method: Method,
+        path: &str,
+        body: Vec<u8>,
+        content_type: Option<&str>,
+        headers: &[(&str, String)],
+    ) -> Result<Response<Incoming>> {
+        let io = self.connect().await?;
         let (mut sender, connection) = http1::handshake(io).await?;
         tokio::spawn(async move {
             let _ = connection.await;
@@ -159,13 +332,25 @@ impl DockerClient {
         if let Some(content_type) = content_type {
             request = request.header(header::CONTENT_TYPE, content_type);
         }
+        for (name, value) in headers {
+            request = request.header(*name, value);
+        }
 
-        let response = sender
+        Ok(sender
             .send_request(request.body(Full::new(Bytes::from(body)))?)
-            .await?;
-        let status = response.status();
-        let body = response.into_body().collect().await?.to_bytes().to_vec();
-        Ok(HttpResponse { status, body })
+            .await?)
+    }
+}
+
+impl DockerClient {
+    #[cfg(unix)]
+    async fn connect(&self) -> Result<TokioIo<UnixStream>> {
+        Ok(TokioIo::new(UnixStream::connect(&self.socket_path).await?))
+    }
+
+    #[cfg(windows)]
+    async fn connect(&self) -> Result<TokioIo<NamedPipeClient>> {
+        Ok(TokioIo::new(connect_named_pipe(&self.socket_path)?))
     }
 }
 
@@ -186,11 +371,27 @@ fn api_error(status: StatusCode, body: &[u8]) -> Result<DockerAgentError> {
     Ok(DockerAgentError::DockerApi { status, message })
 }
 
+/// Whether a logs content type announces multiplexed frames; `None` when it says nothing either
+/// way, as with `application/octet-stream` or engines predating the dedicated types.
+fn multiplexed_content_type(content_type: &str) -> Option<bool> {
+    match content_type.split(';').next().unwrap_or_default().trim() {
+        "application/vnd.docker.multiplexed-stream" => Some(true),
+        "application/vnd.docker.raw-stream" => Some(false),
+        _ => None,
+    }
+}
+
 struct HttpResponse {
     status: StatusCode,
+    content_type: Option<String>,
     body: Vec<u8>,
 }
 
+pub struct LogsResponse {
+    pub multiplexed: Option<bool>,
+    pub body: Vec<u8>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct CreateContainerRequest {
     pub name: Option<String>,
@@ -210,6 +411,8 @@ pub struct CreateContainerBody {
     pub entrypoint: Vec<String>,
     #[serde(rename = "WorkingDir", skip_serializing_if = "Option::is_none")]
     pub working_dir: Option<String>,
+    #[serde(rename = "User", skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
     #[serde(rename = "Labels")]
     pub labels: BTreeMap<String, String>,
     #[serde(rename = "ExposedPorts", skip_serializing_if = "BTreeMap::is_empty")]
@@ -224,6 +427,24 @@ pub struct HostConfig {
     pub binds: Vec<String>,
     #[serde(rename = "PortBindings", skip_serializing_if = "BTreeMap::is_empty")]
     pub port_bindings: BTreeMap<String, Vec<HostPortBinding>>,
+    #[serde(rename = "Tmpfs", skip_serializing_if = "BTreeMap::is_empty")]
+    pub tmpfs: BTreeMap<String, String>,
+    #[serde(rename = "CapAdd", skip_serializing_if = "Vec::is_empty")]
+    pub cap_add: Vec<String>,
+    #[serde(rename = "CapDrop", skip_serializing_if = "Vec::is_empty")]
+    pub cap_drop: Vec<String>,
+    #[serde(rename = "ReadonlyRootfs", skip_serializing_if = "is_false")]
+    pub readonly_rootfs: bool,
+    #[serde(rename = "Privileged", skip_serializing_if = "is_false")]
+    pub privileged: bool,
+    #[serde(rename = "NetworkMode", skip_serializing_if = "Option::is_none")]
+    pub network_mode: Option<String>,
+    #[serde(rename = "SecurityOpt", skip_serializing_if = "Vec::is_empty")]
+    pub security_opt: Vec<String>,
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
 }
 
 #[derive(Debug, Serialize)]
@@ -266,4 +487,158 @@ pub struct InspectContainerState {
     pub status: String,
     #[serde(rename = "Running")]
     pub running: bool,
+    #[serde(rename = "ExitCode", default)]
+    pub exit_code: Option<i32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreateExecBody {
+    #[serde(rename = "Cmd")]
+    pub cmd: Vec<String>,
+    #[serde(rename = "AttachStdin")]
+    pub attach_stdin: bool,
+    #[serde(rename = "AttachStdout")]
+    pub attach_stdout: bool,
+    #[serde(rename = "AttachStderr")]
+    pub attach_stderr: bool,
+    #[serde(rename = "Tty")]
+    pub tty: bool,
+    #[serde(rename = "Env", skip_serializing_if = "Vec::is_empty")]
+    pub env: Vec<String>,
+    #[serde(rename = "WorkingDir", skip_serializing_if = "Option::is_none")]
+    pub working_dir: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateExecResponse {
+    #[serde(rename = "Id")]
+    pub id: String,
+}
+
+#[derive(Debug, Serialize)]
+struct StartExecBody {
+    #[serde(rename = "Detach")]
+    detach: bool,
+    #[serde(rename = "Tty")]
+    tty: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InspectExecResponse {
+    #[serde(rename = "Running", default)]
+    pub running: bool,
+    #[serde(rename = "ExitCode", default)]
+    pub exit_code: Option<i32>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct StatsResponse {
+    #[serde(default)]
+    pub cpu_stats: CpuStatsResponse,
+    #[serde(default)]
+    pub precpu_stats: CpuStatsResponse,
+    #[serde(default)]
+    pub memory_stats: MemoryStatsResponse,
+    #[serde(default)]
+    pub networks: Option<BTreeMap<String, NetworkStatsResponse>>,
+    #[serde(default)]
+    pub blkio_stats: BlkioStatsResponse,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct CpuStatsResponse {
+    #[serde(default)]
+    pub cpu_usage: CpuUsageResponse,
+    #[serde(default)]
+    pub system_cpu_usage: Option<u64>,
+    #[serde(default)]
+    pub online_cpus: Option<u32>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct CpuUsageResponse {
+    #[serde(default)]
+    pub total_usage: u64,
+    #[serde(default)]
+    pub percpu_usage: Option<Vec<u64>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct MemoryStatsResponse {
+    #[serde(default)]
+    pub usage: Option<u64>,
+    #[serde(default)]
+    pub limit: Option<u64>,
+    #[serde(default)]
+    pub stats: Option<BTreeMap<String, u64>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct NetworkStatsResponse {
+    #[serde(default)]
+    pub rx_bytes: u64,
+    #[serde(default)]
+    pub tx_bytes: u64,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct BlkioStatsResponse {
+    #[serde(default)]
+    pub io_service_bytes_recursive: Option<Vec<BlkioEntryResponse>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BlkioEntryResponse {
+    #[serde(default)]
+    pub op: String,
+    #[serde(default)]
+    pub value: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InspectImageResponse {
+    #[serde(rename = "Id")]
+    pub id: String,
+    #[serde(rename = "RepoTags", default)]
+    pub repo_tags: Vec<String>,
+    #[serde(rename = "RepoDigests", default)]
+    pub repo_digests: Vec<String>,
+    #[serde(rename = "Size", default)]
+    pub size: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ContainerSummaryResponse {
+    #[serde(rename = "Id")]
+    pub _id: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VersionResponse {
+    #[serde(rename = "Version", default)]
+    pub version: String,
+    #[serde(rename = "ApiVersion", default)]
+    pub api_version: String,
+    #[serde(rename = "Platform", default)]
+    pub platform: Option<VersionPlatform>,
+    #[serde(rename = "Components", default)]
+    pub components: Vec<VersionComponent>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VersionPlatform {
+    #[serde(rename = "Name", default)]
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VersionComponent {
+    #[serde(rename = "Name", default)]
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InfoResponse {
+    #[serde(rename = "SecurityOptions", default)]
+    pub security_options: Vec<String>,
 }
@@ -0,0 +1,52 @@
+const SHA256_PREFIX: &str = "sha256:";
+
+/// Normalizes a digest written as `sha256:<hex>` or bare `<hex>`, in either case, to lowercase
+/// hex. `None` unless it is exactly 64 hex characters.
+pub fn normalize_sha256_digest(value: &str) -> Option<String> {
+    let value = value.trim();
+    let hex_digest = value.strip_prefix(SHA256_PREFIX).unwrap_or(value);
+    (hex_digest.len() == 64 && hex_digest.chars().all(|ch| ch.is_ascii_hexdigit()))
+        .then(|| hex_digest.to_ascii_lowercase())
+}
+
+/// The digest an image reference such as `nginx:1.27@sha256:<hex>` is pinned to, as written.
+pub fn pinned_image_digest(image: &str) -> Option<&str> {
+    image
+        .rsplit_once('@')
+        .and_then(|(_, digest)| digest.strip_prefix(SHA256_PREFIX))
+}
+
+/// Spells the digest of a pinned reference in lowercase, the only form engines accept. Other
+/// references are returned unchanged.
+pub fn canonical_image_reference(image: &str) -> String {
+    match (image.rsplit_once('@'), pinned_image_digest(image)) {
+        (Some((name, _)), Some(digest)) => match normalize_sha256_digest(digest) {
+            Some(digest) => format!("{name}@{SHA256_PREFIX}{digest}"),
+            None => image.to_string(),
+        },
+        _ => image.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_digests_in_either_case() {
+        let digest = "ab".repeat(32);
+        assert_eq!(
+            normalize_sha256_digest(&format!("sha256:{}", digest.to_uppercase())),
+            Some(digest.clone())
+        );
+        assert_eq!(normalize_sha256_digest(&digest), Some(digest.clone()));
+        assert_eq!(normalize_sha256_digest("sha256:abc"), None);
+
+        let image = format!("nginx:1.27@sha256:{}", digest.to_uppercase());
+        assert_eq!(
+            canonical_image_reference(&image),
+            format!("nginx:1.27@sha256:{digest}")
+        );
+        assert_eq!(canonical_image_reference("nginx:1.27"), "nginx:1.27");
+    }
+}
@@ -0,0 +1,136 @@
+use serde::{Deserialize, Serialize};
+use std::{borrow::Cow, fmt, path::Path};
+
+use crate::client::{InfoResponse, VersionResponse};
+
+const DEFAULT_REGISTRY: &str = "docker.io";
+
+/// The engine behind the Docker-compatible socket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EngineFlavor {
+    #[default]
+    Docker,
+    Podman,
+}
+
+impl EngineFlavor {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Docker => "docker",
+            Self::Podman => "podman",
+        }
+    }
+
+    /// Best guess before the engine has been asked: Podman sockets live under a `podman`
+    /// directory or are named `podman.sock`.
+    pub fn guess_from_socket_path(path: &Path) -> Self {
+        let is_podman = path
+            .components()
+            .any(|component| component.as_os_str().to_string_lossy().contains("podman"));
+        if is_podman {
+            Self::Podman
+        } else {
+            Self::Docker
+        }
+    }
+
+    /// Podman refuses short image names without a TTY to pick a registry, so they are qualified
+    /// the way Docker resolves them.
+    pub(crate) fn image_reference(self, image: &str) -> Cow<'_, str> {
+        if self == Self::Docker {
+            return Cow::Borrowed(image);
+        }
+        match image.split_once('/') {
+            Some((first, _))
+                if first.contains('.') || first.contains(':') || first == "localhost" =>
+            {
+                Cow::Borrowed(image)
+            }
+            Some(_) => Cow::Owned(format!("{DEFAULT_REGISTRY}/{image}")),
+            None => Cow::Owned(format!("{DEFAULT_REGISTRY}/library/{image}")),
+        }
+    }
+}
+
+impl fmt::Display for EngineFlavor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// What the engine reports about itself through `/version` and `/info`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EngineInfo {
+    pub flavor: EngineFlavor,
+    pub version: String,
+    pub api_version: String,
+    /// Whether the engine runs without root, e.g. rootless Podman or rootless Docker.
+    pub rootless: bool,
+}
+
+impl EngineInfo {
+    pub(crate) fn from_responses(version: VersionResponse, info: InfoResponse) -> Self {
+        let is_podman = version
+            .platform
+            .iter()
+            .map(|platform| platform.name.as_str())
+            .chain(
+                version
+                    .components
+                    .iter()
+                    .map(|component| component.name.as_str()),
+            )
+            .any(|name| name.to_ascii_lowercase().contains("podman"));
+        let rootless = info
+            .security_options
+            .iter()
+            .any(|option| option.split(',').any(|part| part == "name=rootless"));
+        Self {
+            flavor: if is_podman {
+                EngineFlavor::Podman
+            } else {
+                EngineFlavor::Docker
+            },
+            version: version.version,
+            api_version: version.api_version,
+            rootless,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn guesses_flavor_from_socket_path() {
+        assert_eq!(
+            EngineFlavor::guess_from_socket_path(Path::new("/run/user/1000/podman/podman.sock")),
+            EngineFlavor::Podman
+        );
+        assert_eq!(
+            EngineFlavor::guess_from_socket_path(Path::new("/var/run/docker.sock")),
+            EngineFlavor::Docker
+        );
+    }
+
+    #[test]
+    fn qualifies_short_image_names_for_podman() {
+        let podman = EngineFlavor::Podman;
+        assert_eq!(
+            podman.image_reference("nginx:1.27"),
+            "docker.io/library/nginx:1.27"
+        );
+        assert_eq!(
+            podman.image_reference("filebrowser/filebrowser"),
+            "docker.io/filebrowser/filebrowser"
+        );
+        assert_eq!(
+            podman.image_reference("ghcr.io/enbop/app:1"),
+            "ghcr.io/enbop/app:1"
+        );
+        assert_eq!(podman.image_reference("localhost/app"), "localhost/app");
+        assert_eq!(EngineFlavor::Docker.image_reference("nginx"), "nginx");
+    }
+}
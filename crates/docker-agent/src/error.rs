@@ -7,6 +7,10 @@ pub enum DockerAgentError {
     InvalidSpec(String),
     #[error("policy denied: {0}")]
     PolicyDenied(String),
+    #[error("integrity check failed: {0}")]
+    IntegrityMismatch(String),
+    #[error("image pull failed: {0}")]
+    PullFailed(String),
     #[error("docker api error ({status}): {message}")]
     DockerApi { status: StatusCode, message: String },
     #[error("io error: {0}")]
@@ -19,4 +23,11 @@ pub enum DockerAgentError {
     Json(#[from] serde_json::Error),
 }
 
+impl DockerAgentError {
+    /// Whether the engine reported the container or image as missing.
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, Self::DockerApi { status, .. } if *status == StatusCode::NOT_FOUND)
+    }
+}
+
 pub type Result<T> = std::result::Result<T, DockerAgentError>;
@@ -0,0 +1,108 @@
+use hyper::upgrade::Upgraded;
+use hyper_util::rt::TokioIo;
+
+/// The connection of a started exec instance: writes go to the process's stdin, reads return
+/// its output, raw with a TTY and multiplexed otherwise (see [`ExecOutputDecoder`]). Shutting
+/// down the write side closes stdin; the read side ends when the process exits.
+pub type ExecStream = TokioIo<Upgraded>;
+
+pub struct ExecSession {
+    pub id: String,
+    pub tty: bool,
+    pub stream: ExecStream,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExecOutput {
+    Stdout(Vec<u8>),
+    Stderr(Vec<u8>),
+}
+
+/// Splits exec output into stdout and stderr as it arrives, buffering frames that span reads.
+#[derive(Debug)]
+pub struct ExecOutputDecoder {
+    tty: bool,
+    pending: Vec<u8>,
+}
+
+impl ExecOutputDecoder {
+    pub fn new(tty: bool) -> Self {
+        Self {
+            tty,
+            pending: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, bytes: &[u8]) -> Vec<ExecOutput> {
+        if self.tty {
+            return vec![ExecOutput::Stdout(bytes.to_vec())];
+        }
+
+        self.pending.extend_from_slice(bytes);
+        let mut output = Vec::new();
+        let mut offset = 0;
+        while self.pending.len() - offset >= 8 {
+            let header = &self.pending[offset..offset + 8];
+            let frame_len =
+                u32::from_be_bytes([header[4], header[5], header[6], header[7]]) as usize;
+            let frame_start = offset + 8;
+            let frame_end = frame_start + frame_len;
+            if frame_end > self.pending.len() {
+                break;
+            }
+            let payload = self.pending[frame_start..frame_end].to_vec();
+            // Stream 2 is stderr; 0 (stdin) and 1 both carry regular output.
+            output.push(if header[0] == 2 {
+                ExecOutput::Stderr(payload)
+            } else {
+                ExecOutput::Stdout(payload)
+            });
+            offset = frame_end;
+        }
+        self.pending.drain(..offset);
+        output
+    }
+
+    /// Output of a frame cut short when the stream ended.
+    pub fn finish(self) -> Option<ExecOutput> {
+        let payload = self.pending.get(8..)?;
+        (!payload.is_empty()).then(|| {
+            if self.pending[0] == 2 {
+                ExecOutput::Stderr(payload.to_vec())
+            } else {
+                ExecOutput::Stdout(payload.to_vec())
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_frames_across_reads() {
+        let mut decoder = ExecOutputDecoder::new(false);
+        assert!(decoder.push(&[1, 0, 0, 0, 0, 0]).is_empty());
+        assert_eq!(
+            decoder.push(&[0, 3, b'o', b'u', b't', 2, 0, 0, 0, 0, 0, 0, 4, b'e']),
+            vec![ExecOutput::Stdout(b"out".to_vec())]
+        );
+        assert_eq!(
+            decoder.push(b"rr\n"),
+            vec![ExecOutput::Stderr(b"err\n".to_vec())]
+        );
+        assert!(decoder.push(&[1, 0, 0, 0, 0, 0, 0, 5, b'p']).is_empty());
+        assert_eq!(decoder.finish(), Some(ExecOutput::Stdout(b"p".to_vec())));
+    }
+
+    #[test]
+    fn passes_tty_output_through() {
+        let mut decoder = ExecOutputDecoder::new(true);
+        assert_eq!(
+            decoder.push(&[1, 0, 0, 0]),
+            vec![ExecOutput::Stdout(vec![1, 0, 0, 0])]
+        );
+        assert_eq!(decoder.finish(), None);
+    }
+}
@@ -0,0 +1,165 @@
+use base64::{Engine as _, engine::general_purpose::URL_SAFE};
+use serde::{Deserialize, Serialize};
+use std::{fmt, sync::Arc};
+
+/// Credentials sent to the Docker engine as `X-Registry-Auth` when pulling from a private registry.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RegistryAuth {
+    pub username: String,
+    pub password: String,
+    #[serde(rename = "serveraddress")]
+    pub server_address: String,
+}
+
+impl RegistryAuth {
+    pub(crate) fn header_value(&self) -> crate::Result<String> {
+        Ok(URL_SAFE.encode(serde_json::to_vec(self)?))
+    }
+}
+
+impl fmt::Debug for RegistryAuth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RegistryAuth")
+            .field("username", &self.username)
+            .field("password", &"<redacted>")
+            .field("server_address", &self.server_address)
+            .finish()
+    }
+}
+
+/// One progress message of an image pull, as streamed by `POST /images/create`.
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+pub struct ImagePullProgress {
+    pub image: String,
+    /// Layer the message refers to; empty for image-level messages such as `Pulling from ...`.
+    pub layer_id: String,
+    pub status: String,
+    /// Docker's rendered progress bar, e.g. `[=====>    ]  12.5MB/40MB`.
+    pub progress: String,
+    pub current: u64,
+    pub total: u64,
+}
+
+pub type ImagePullProgressFn = Arc<dyn Fn(ImagePullProgress) + Send + Sync>;
+
+#[derive(Clone, Default)]
+pub struct ImagePullOptions {
+    pub auth: Option<RegistryAuth>,
+    pub progress: Option<ImagePullProgressFn>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImageDetails {
+    pub id: String,
+    pub repo_tags: Vec<String>,
+    pub repo_digests: Vec<String>,
+    pub size: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct PullMessage {
+    #[serde(default)]
+    id: String,
+    #[serde(default)]
+    status: String,
+    /// Podman reports registry resolution as free text, e.g. `Trying to pull docker.io/...`.
+    #[serde(default)]
+    stream: String,
+    #[serde(default)]
+    progress: String,
+    #[serde(rename = "progressDetail", default)]
+    progress_detail: PullProgressDetail,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PullProgressDetail {
+    #[serde(default)]
+    current: u64,
+    #[serde(default)]
+    total: u64,
+}
+
+/// Parses one line of the pull stream. The engine reports failures in-band with a 200 status, so
+/// an `error` message is returned as `Err`.
+pub(crate) fn parse_pull_line(
+    image: &str,
+    line: &[u8],
+) -> std::result::Result<Option<ImagePullProgress>, String> {
+    let line = line.trim_ascii();
+    if line.is_empty() {
+        return Ok(None);
+    }
+    let Ok(message) = serde_json::from_slice::<PullMessage>(line) else {
+        return Ok(None);
+    };
+    if let Some(error) = message.error {
+        return Err(error);
+    }
+    let status = if message.status.is_empty() {
+        message.stream.trim().to_string()
+    } else {
+        message.status
+    };
+    if status.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(ImagePullProgress {
+        image: image.to_string(),
+        layer_id: message.id,
+        status,
+        progress: message.progress,
+        current: message.progress_detail.current,
+        total: message.progress_detail.total,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_progress_and_in_band_errors() {
+        let progress = parse_pull_line(
+            "nginx:1.27",
+            br#"{"status":"Downloading","progressDetail":{"current":512,"total":2048},"progress":"[==>  ]","id":"a1b2"}"#,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(progress.layer_id, "a1b2");
+        assert_eq!((progress.current, progress.total), (512, 2048));
+
+        let error = parse_pull_line(
+            "nginx:1.27",
+            br#"{"errorDetail":{"message":"denied"},"error":"denied"}"#,
+        )
+        .unwrap_err();
+        assert_eq!(error, "denied");
+        assert_eq!(parse_pull_line("nginx", b"  ").unwrap(), None);
+
+        let podman = parse_pull_line(
+            "docker.io/library/nginx:1.27",
+            br#"{"stream":"Trying to pull docker.io/library/nginx:1.27...\n"}"#,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(
+            podman.status,
+            "Trying to pull docker.io/library/nginx:1.27..."
+        );
+    }
+
+    #[test]
+    fn encodes_registry_auth_header() {
+        let auth = RegistryAuth {
+            username: "me".into(),
+            password: "token".into(),
+            server_address: "ghcr.io".into(),
+        };
+        let decoded = URL_SAFE.decode(auth.header_value().unwrap()).unwrap();
+        let value: serde_json::Value = serde_json::from_slice(&decoded).unwrap();
+        assert_eq!(value["serveraddress"], "ghcr.io");
+        assert!(!format!("{auth:?}").contains("token"));
+    }
+}
@@ -1,10 +1,25 @@
 mod agent;
 mod client;
+mod digest;
+mod engine;
 mod error;
+mod exec;
+mod image;
 mod policy;
 mod spec;
 
-pub use agent::{ContainerDetails, ContainerLogs, ContainerState, DockerAgent};
+pub use agent::{
+    ContainerDetails, ContainerLogs, ContainerState, ContainerStats, CpuSample, DockerAgent,
+};
+pub use digest::{canonical_image_reference, normalize_sha256_digest, pinned_image_digest};
+pub use engine::{EngineFlavor, EngineInfo};
 pub use error::{DockerAgentError, Result};
-pub use policy::{AgentPolicy, PortRule};
-pub use spec::{BindMount, ContainerSpec, LogsOptions, PortBinding, PortProtocol};
+pub use exec::{ExecOutput, ExecOutputDecoder, ExecSession, ExecStream};
+pub use image::{
+    ImageDetails, ImagePullOptions, ImagePullProgress, ImagePullProgressFn, RegistryAuth,
+};
+pub use policy::{AgentPolicy, PortRule, SecurityRules};
+pub use spec::{
+    BindMount, ContainerSecurity, ContainerSpec, ExecSpec, LogsOptions, PortBinding, PortProtocol,
+    TmpfsMount, UNCONFINED_PROFILE, VolumeMount,
+};
@@ -1,6 +1,12 @@
-use crate::{DockerAgentError, Result, spec::ContainerSpec};
+use crate::{
+    DockerAgentError, Result,
+    digest::{normalize_sha256_digest, pinned_image_digest},
+    spec::{ContainerSecurity, ContainerSpec, UNCONFINED_PROFILE},
+};
 use std::path::{Component, Path, PathBuf};
 
+const DROP_ALL_CAPS: &str = "ALL";
+
 #[derive(Debug, Clone)]
 pub struct AgentPolicy {
     pub socket_path: PathBuf,
@@ -8,6 +14,91 @@ pub struct AgentPolicy {
     pub managed_label_value: String,
     pub allowed_host_paths: Vec<PathBuf>,
     pub allowed_ports: Vec<PortRule>,
+    pub security: SecurityRules,
+}
+
+/// Limits on the privileges a container spec may request. The default allows everything
+/// `docker run` allows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecurityRules {
+    pub allow_privileged: bool,
+    pub allow_host_network: bool,
+    /// Whether `seccomp_profile` and `apparmor_profile` may be `unconfined`.
+    pub allow_unconfined: bool,
+    /// Whether `volumes` may name engine volumes. Volumes are shared engine-wide, so a
+    /// container allowed to mount one can read any other container's volume of that name.
+    pub allow_named_volumes: bool,
+    /// Require `cap_drop` to include `ALL`.
+    pub require_drop_all_caps: bool,
+    /// Capabilities `cap_add` may name; `None` allows any.
+    pub allowed_cap_add: Option<Vec<String>>,
+}
+
+impl Default for SecurityRules {
+    fn default() -> Self {
+        Self {
+            allow_privileged: true,
+            allow_host_network: true,
+            allow_unconfined: true,
+            allow_named_volumes: true,
+            require_drop_all_caps: false,
+            allowed_cap_add: None,
+        }
+    }
+}
+
+impl SecurityRules {
+    fn validate(&self, security: &ContainerSecurity) -> Result<()> {
+        if security.privileged && !self.allow_privileged {
+            return Err(DockerAgentError::PolicyDenied(
+                "privileged containers are not allowed".into(),
+            ));
+        }
+        if security.host_network && !self.allow_host_network {
+            return Err(DockerAgentError::PolicyDenied(
+                "host network is not allowed".into(),
+            ));
+        }
+        let unconfined = [&security.seccomp_profile, &security.apparmor_profile]
+            .into_iter()
+            .flatten()
+            .any(|profile| profile == UNCONFINED_PROFILE);
+        if unconfined && !self.allow_unconfined {
+            return Err(DockerAgentError::PolicyDenied(
+                "unconfined seccomp or apparmor profiles are not allowed".into(),
+            ));
+        }
+        if self.require_drop_all_caps
+            && !security
+                .cap_drop
+                .iter()
+                .any(|cap| normalize_capability(cap) == DROP_ALL_CAPS)
+        {
+            return Err(DockerAgentError::PolicyDenied(
+                "containers must drop all capabilities (cap_drop: [ALL])".into(),
+            ));
+        }
+        if let Some(allowed) = &self.allowed_cap_add {
+            for cap in &security.cap_add {
+                let cap = normalize_capability(cap);
+                if !allowed
+                    .iter()
+                    .any(|allowed| normalize_capability(allowed) == cap)
+                {
+                    return Err(DockerAgentError::PolicyDenied(format!(
+                        "capability is not allowed: {cap}"
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Normalizes `cap_net_bind_service` and `NET_BIND_SERVICE` to the engine's spelling.
+pub(crate) fn normalize_capability(cap: &str) -> String {
+    let cap = cap.trim().to_ascii_uppercase();
+    cap.strip_prefix("CAP_").map(str::to_string).unwrap_or(cap)
 }
 
 impl AgentPolicy {
@@ -18,6 +109,17 @@ impl AgentPolicy {
             ));
         }
 
+        if spec.image.contains('@')
+            && pinned_image_digest(&spec.image)
+                .and_then(normalize_sha256_digest)
+                .is_none()
+        {
+            return Err(DockerAgentError::InvalidSpec(format!(
+                "image digest must be sha256:<64 hex>: {}",
+                spec.image
+            )));
+        }
+
         if let Some(name) = &spec.name
             && name.trim().is_empty()
         {
@@ -27,28 +129,67 @@ impl AgentPolicy {
         }
 
         for mount in &spec.mounts {
-            if mount.container_path.trim().is_empty() || !mount.container_path.starts_with('/') {
+            validate_container_path(&mount.container_path)?;
+            self.ensure_allowed_host_path(&mount.host_path)?;
+        }
+
+        if !spec.volumes.is_empty() && !self.security.allow_named_volumes {
+            return Err(DockerAgentError::PolicyDenied(
+                "named volumes are not allowed".into(),
+            ));
+        }
+        for volume in &spec.volumes {
+            validate_container_path(&volume.container_path)?;
+            if !is_valid_volume_name(&volume.name) {
                 return Err(DockerAgentError::InvalidSpec(format!(
-                    "container mount path must be absolute: {}",
-                    mount.container_path
+                    "invalid volume name: {}",
+                    volume.name
                 )));
             }
+        }
 
-            let host_path = normalize_absolute_path(&mount.host_path)?;
-            let allowed = self
-                .allowed_host_paths
-                .iter()
-                .map(|path| normalize_absolute_path(path))
-                .collect::<Result<Vec<_>>>()?
-                .into_iter()
-                .any(|allowed_root| host_path.starts_with(&allowed_root));
-            if !allowed {
-                return Err(DockerAgentError::PolicyDenied(format!(
-                    "host path is outside allowed roots: {}",
-                    mount.host_path.display()
+        for tmpfs in &spec.tmpfs {
+            validate_container_path(&tmpfs.container_path)?;
+            if tmpfs.size_bytes == Some(0) {
+                return Err(DockerAgentError::InvalidSpec(
+                    "tmpfs size must be greater than 0".into(),
+                ));
+            }
+        }
+
+        let security = &spec.security;
+        if let Some(user) = &security.user
+            && (user.trim().is_empty() || user.contains(char::is_whitespace))
+        {
+            return Err(DockerAgentError::InvalidSpec(format!(
+                "invalid container user: {user:?}"
+            )));
+        }
+        for cap in security.cap_add.iter().chain(&security.cap_drop) {
+            let normalized = normalize_capability(cap);
+            if normalized.is_empty()
+                || !normalized
+                    .chars()
+                    .all(|ch| ch.is_ascii_uppercase() || ch == '_')
+            {
+                return Err(DockerAgentError::InvalidSpec(format!(
+                    "invalid capability: {cap}"
                 )));
             }
         }
+        if let Some(profile) = &security.seccomp_profile
+            && profile != UNCONFINED_PROFILE
+        {
+            self.ensure_allowed_host_path(Path::new(profile))?;
+        }
+        if let Some(profile) = &security.apparmor_profile
+            && (profile.trim().is_empty() || profile.contains(char::is_whitespace))
+        {
+            return Err(DockerAgentError::InvalidSpec(format!(
+                "invalid apparmor profile: {profile:?}"
+            )));
+        }
+        self.security.validate(security)?;
 
         for port in &spec.ports {
             if port.host_port == 0 || port.container_port == 0 {
@@ -76,6 +217,40 @@ impl AgentPolicy {
     pub fn managed_label(&self) -> (&str, &str) {
         (&self.managed_label_key, &self.managed_label_value)
     }
+
+    fn ensure_allowed_host_path(&self, path: &Path) -> Result<()> {
+        let host_path = normalize_absolute_path(path)?;
+        let allowed = self
+            .allowed_host_paths
+            .iter()
+            .map(|path| normalize_absolute_path(path))
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .any(|allowed_root| host_path.starts_with(&allowed_root));
+        if !allowed {
+            return Err(DockerAgentError::PolicyDenied(format!(
+                "host path is outside allowed roots: {}",
+                path.display()
+            )));
+        }
+        Ok(())
+    }
+}
+
+fn validate_container_path(path: &str) -> Result<()> {
+    if path.trim().is_empty() || !path.starts_with('/') {
+        return Err(DockerAgentError::InvalidSpec(format!(
+            "container mount path must be absolute: {path}"
+        )));
+    }
+    Ok(())
+}
+
+/// Docker's volume name rule: `[a-zA-Z0-9][a-zA-Z0-9_.-]*`.
+fn is_valid_volume_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|ch| ch.is_ascii_alphanumeric())
+        && chars.all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '_' | '.' | '-'))
 }
 
 #[derive(Debug, Clone)]
@@ -119,7 +294,7 @@ fn normalize_absolute_path(path: &Path) -> Result<PathBuf> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{BindMount, ContainerSpec, PortBinding};
+    use crate::{BindMount, ContainerSpec, PortBinding, VolumeMount};
     use std::collections::BTreeMap;
 
     fn allowed_root() -> PathBuf {
@@ -146,6 +321,7 @@ mod tests {
                     end: 20100,
                 },
             ],
+            security: SecurityRules::default(),
         }
     }
 
@@ -157,6 +333,7 @@ mod tests {
             mounts: vec![BindMount {
                 host_path: allowed_root().join("data"),
                 container_path: "/data".into(),
+                read_only: true,
             }],
             ports: vec![PortBinding {
                 host_port: 20010,
@@ -178,6 +355,7 @@ mod tests {
             mounts: vec![BindMount {
                 host_path: disallowed_root(),
                 container_path: "/data".into(),
+                read_only: false,
             }],
             ..Default::default()
         };
@@ -206,4 +384,122 @@ mod tests {
             Err(DockerAgentError::PolicyDenied(_))
         ));
     }
+
+    #[test]
+    fn enforces_security_rules() {
+        let mut policy = sample_policy();
+        policy.security = SecurityRules {
+            allow_privileged: false,
+            allow_host_network: false,
+            allow_unconfined: false,
+            allow_named_volumes: false,
+            require_drop_all_caps: true,
+            allowed_cap_add: Some(vec!["NET_BIND_SERVICE".into()]),
+        };
+        let hardened = ContainerSpec {
+            image: "img".into(),
+            security: ContainerSecurity {
+                user: Some("1000:1000".into()),
+                read_only_rootfs: true,
+                cap_drop: vec!["all".into()],
+                cap_add: vec!["cap_net_bind_service".into()],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(policy.validate_create_spec(&hardened).is_ok());
+
+        for security in [
+            ContainerSecurity {
+                privileged: true,
+                ..hardened.security.clone()
+            },
+            ContainerSecurity {
+                host_network: true,
+                ..hardened.security.clone()
+            },
+            ContainerSecurity {
+                cap_drop: vec!["NET_RAW".into()],
+                ..hardened.security.clone()
+            },
+            ContainerSecurity {
+                cap_add: vec!["SYS_ADMIN".into()],
+                ..hardened.security.clone()
+            },
+            ContainerSecurity {
+                seccomp_profile: Some(UNCONFINED_PROFILE.into()),
+                ..hardened.security.clone()
+            },
+        ] {
+            let spec = ContainerSpec {
+                security,
+                ..hardened.clone()
+            };
+            assert!(matches!(
+                policy.validate_create_spec(&spec),
+                Err(DockerAgentError::PolicyDenied(_))
+            ));
+        }
+
+        let with_volume = ContainerSpec {
+            volumes: vec![VolumeMount {
+                name: "shared-data".into(),
+                container_path: "/data".into(),
+                read_only: true,
+            }],
+            ..hardened
+        };
+        assert!(matches!(
+            policy.validate_create_spec(&with_volume),
+            Err(DockerAgentError::PolicyDenied(_))
+        ));
+    }
+
+    #[test]
+    fn validates_pinned_image_digests() {
+        let policy = sample_policy();
+        for (image, valid) in [
+            (format!("nginx@sha256:{}", "ab".repeat(32)), true),
+            (format!("nginx@sha256:{}", "AB".repeat(32)), true),
+            ("nginx@sha256:abc".to_string(), false),
+            (format!("nginx@{}", "ab".repeat(32)), false),
+        ] {
+            let spec = ContainerSpec {
+                image,
+                ..Default::default()
+            };
+            assert_eq!(policy.validate_create_spec(&spec).is_ok(), valid);
+        }
+    }
+
+    #[test]
+    fn validates_volumes_and_seccomp_profiles() {
+        let policy = sample_policy();
+        let spec = ContainerSpec {
+            image: "img".into(),
+            volumes: vec![VolumeMount {
+                name: "-bad".into(),
+                container_path: "/data".into(),
+                read_only: false,
+            }],
+            ..Default::default()
+        };
+        assert!(matches!(
+            policy.validate_create_spec(&spec),
+            Err(DockerAgentError::InvalidSpec(_))
+        ));
+
+        let spec = ContainerSpec {
+            image: "img".into(),
+            security: ContainerSecurity {
+                seccomp_profile: Some(disallowed_root().join("seccomp.json").display().to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(matches!(
+            policy.validate_create_spec(&spec),
+            Err(DockerAgentError::PolicyDenied(_))
+        ));
+    }
 }
@@ -18,12 +18,74 @@ pub struct ContainerSpec {
     pub working_dir: Option<String>,
     #[serde(default)]
     pub labels: BTreeMap<String, String>,
+    #[serde(default)]
+    pub volumes: Vec<VolumeMount>,
+    #[serde(default)]
+    pub tmpfs: Vec<TmpfsMount>,
+    #[serde(default)]
+    pub security: ContainerSecurity,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BindMount {
     pub host_path: PathBuf,
     pub container_path: String,
+    #[serde(default)]
+    pub read_only: bool,
+}
+
+/// A Docker named volume, created by the engine on first use.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VolumeMount {
+    pub name: String,
+    pub container_path: String,
+    #[serde(default)]
+    pub read_only: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TmpfsMount {
+    pub container_path: String,
+    /// Size limit; the engine default (half the host memory) applies when unset.
+    pub size_bytes: Option<u64>,
+}
+
+/// Privilege and confinement settings of a container. The defaults match `docker run`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct ContainerSecurity {
+    /// `user`, `user:group`, `uid` or `uid:gid` the container process runs as.
+    pub user: Option<String>,
+    #[serde(default)]
+    pub read_only_rootfs: bool,
+    #[serde(default)]
+    pub cap_add: Vec<String>,
+    /// Capabilities to drop; `ALL` drops every capability not re-added by `cap_add`.
+    #[serde(default)]
+    pub cap_drop: Vec<String>,
+    #[serde(default)]
+    pub privileged: bool,
+    /// Share the host network namespace instead of the default bridge network.
+    #[serde(default)]
+    pub host_network: bool,
+    /// `unconfined`, or a host path to a seccomp profile JSON file.
+    pub seccomp_profile: Option<String>,
+    /// Name of an AppArmor profile loaded on the host, or `unconfined`.
+    pub apparmor_profile: Option<String>,
+}
+
+pub const UNCONFINED_PROFILE: &str = "unconfined";
+
+/// A command to run inside a running container, attached to its stdin and output.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ExecSpec {
+    pub command: Vec<String>,
+    /// Allocate a pseudo-terminal; output then arrives as one raw stream instead of
+    /// multiplexed stdout and stderr frames.
+    #[serde(default)]
+    pub tty: bool,
+    pub working_dir: Option<String>,
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -50,6 +112,9 @@ pub struct LogsOptions {
     #[serde(default = "default_true")]
     pub stderr: bool,
     pub tail: Option<String>,
+    /// Unix timestamp, with optional fractional seconds, of the oldest entry to return.
+    #[serde(default)]
+    pub since: Option<String>,
 }
 
 impl Default for LogsOptions {
@@ -58,6 +123,7 @@ impl Default for LogsOptions {
             stdout: true,
             stderr: true,
             tail: None,
+            since: None,
         }
     }
 }
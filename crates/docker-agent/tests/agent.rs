@@ -1,7 +1,9 @@
 #![cfg(unix)]
 
 use fungi_docker_agent::{
-    AgentPolicy, BindMount, ContainerSpec, DockerAgent, LogsOptions, PortBinding, PortRule,
+    AgentPolicy, BindMount, ContainerSpec, DockerAgent, DockerAgentError, EngineFlavor, ExecOutput,
+    ExecOutputDecoder, ExecSpec, ImagePullOptions, LogsOptions, PortBinding, PortRule,
+    RegistryAuth,
 };
 use std::{collections::BTreeMap, path::PathBuf, sync::Arc};
 use tempfile::{TempDir, tempdir};
@@ -11,13 +13,27 @@ use tokio::{
     sync::Mutex,
 };
 
+/// TTY output that happens to parse as a single multiplexed frame.
+const RAW_TTY_LOGS: &[u8] = &[1, 0, 0, 0, 0, 0, 0, 2, b'o', b'k'];
+const PINNED_DIGEST: &str = "4f3c6c1b1a2b3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d5e6f708192a3b4c5";
+
 #[derive(Clone, Debug)]
 struct RecordedRequest {
     method: String,
     path: String,
+    headers: Vec<(String, String)>,
     body: Vec<u8>,
 }
 
+impl RecordedRequest {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
 #[tokio::test]
 async fn creates_and_starts_managed_container() {
     let fixture = ServerFixture::start().await;
@@ -30,6 +46,7 @@ async fn creates_and_starts_managed_container() {
         mounts: vec![BindMount {
             host_path: PathBuf::from("/tmp/fungi/data"),
             container_path: "/srv".into(),
+            read_only: false,
         }],
         ports: vec![PortBinding {
             host_port: 8080,
@@ -80,6 +97,77 @@ async fn fetches_logs() {
     assert!(logs.text.contains("hello"));
 }
 
+#[tokio::test]
+async fn samples_container_stats() {
+    let fixture = ServerFixture::start().await;
+    let agent = DockerAgent::new(sample_policy(fixture.socket_path.clone()));
+
+    let stats = agent.container_stats("container-1").await.unwrap();
+    assert_eq!(stats.cpu_percent(None), Some(10.0));
+    assert_eq!(stats.memory_usage_bytes, Some(41943040));
+    assert_eq!(stats.memory_limit_bytes, Some(268435456));
+    assert_eq!(stats.network_rx_bytes, Some(1024));
+    assert_eq!(stats.network_tx_bytes, Some(400));
+    assert_eq!(stats.block_read_bytes, Some(5120));
+    assert_eq!(stats.block_write_bytes, Some(8192));
+
+    let requests = fixture.requests.lock().await.clone();
+    assert_eq!(
+        requests.last().unwrap().path,
+        "/containers/container-1/stats?stream=false&one-shot=true"
+    );
+}
+
+#[tokio::test]
+async fn attaches_exec_session_and_reads_exit_code() {
+    let fixture = ServerFixture::start().await;
+    let agent = DockerAgent::new(sample_policy(fixture.socket_path.clone()));
+
+    let spec = ExecSpec {
+        command: vec!["cat".into()],
+        env: BTreeMap::from([(String::from("TERM"), String::from("dumb"))]),
+        ..Default::default()
+    };
+    let mut session = agent.exec("container-1", &spec).await.unwrap();
+    assert_eq!(session.id, "exec-1");
+    session.stream.write_all(b"ping\n").await.unwrap();
+    session.stream.shutdown().await.unwrap();
+    let mut raw = Vec::new();
+    session.stream.read_to_end(&mut raw).await.unwrap();
+
+    let mut decoder = ExecOutputDecoder::new(session.tty);
+    assert_eq!(
+        decoder.push(&raw),
+        vec![
+            ExecOutput::Stdout(b"ping\n".to_vec()),
+            ExecOutput::Stderr(b"done\n".to_vec()),
+        ]
+    );
+    assert_eq!(agent.exec_exit_code("exec-1").await.unwrap(), Some(3));
+
+    let requests = fixture.requests.lock().await.clone();
+    let create = requests
+        .iter()
+        .find(|request| request.path == "/containers/container-1/exec")
+        .unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&create.body).unwrap();
+    assert_eq!(body["Cmd"], serde_json::json!(["cat"]));
+    assert_eq!(body["AttachStdin"], true);
+    assert_eq!(body["Env"], serde_json::json!(["TERM=dumb"]));
+    let start = requests
+        .iter()
+        .find(|request| request.path == "/exec/exec-1/start")
+        .unwrap();
+    assert_eq!(start.header("upgrade"), Some("tcp"));
+
+    let error = agent
+        .exec("container-1", &ExecSpec::default())
+        .await
+        .err()
+        .unwrap();
+    assert!(matches!(error, DockerAgentError::InvalidSpec(_)));
+}
+
 #[tokio::test]
 async fn pulls_missing_image_and_retries_create() {
     let fixture = ServerFixture::start_missing_image_once().await;
@@ -91,6 +179,7 @@ async fn pulls_missing_image_and_retries_create() {
         mounts: vec![BindMount {
             host_path: PathBuf::from("/tmp/fungi/data"),
             container_path: "/srv".into(),
+            read_only: false,
         }],
         ports: vec![PortBinding {
             host_port: 8080,
@@ -122,6 +211,181 @@ async fn pulls_missing_image_and_retries_create() {
     );
 }
 
+#[tokio::test]
+async fn verifies_pinned_image_digest_before_create() {
+    let pinned = ContainerSpec {
+        name: Some("filebrowser".into()),
+        image: format!("filebrowser/filebrowser@sha256:{PINNED_DIGEST}"),
+        ..Default::default()
+    };
+
+    let fixture = ServerFixture::start().await;
+    let agent = DockerAgent::new(sample_policy(fixture.socket_path.clone()));
+    agent.create_container(&pinned).await.unwrap();
+    let requests = fixture.requests.lock().await.clone();
+    assert!(requests[0].path.starts_with("/images/filebrowser"));
+    assert!(
+        requests[1]
+            .path
+            .starts_with("/containers/create?name=filebrowser")
+    );
+
+    let fixture = ServerFixture::start_mismatched_image().await;
+    let agent = DockerAgent::new(sample_policy(fixture.socket_path.clone()));
+    let err = agent.create_container(&pinned).await.unwrap_err();
+    assert!(matches!(err, DockerAgentError::IntegrityMismatch(_)));
+    let requests = fixture.requests.lock().await.clone();
+    assert!(
+        requests
+            .iter()
+            .all(|request| !request.path.starts_with("/containers/create"))
+    );
+}
+
+#[tokio::test]
+async fn streams_pull_progress_with_registry_auth() {
+    let fixture = ServerFixture::start().await;
+    let agent = DockerAgent::new(sample_policy(fixture.socket_path.clone()));
+    let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let collected = events.clone();
+    let options = ImagePullOptions {
+        auth: Some(RegistryAuth {
+            username: "me".into(),
+            password: "token".into(),
+            server_address: "ghcr.io".into(),
+        }),
+        progress: Some(Arc::new(move |progress| {
+            collected.lock().unwrap().push(progress)
+        })),
+    };
+
+    agent
+        .pull_image("ghcr.io/enbop/app:1", &options)
+        .await
+        .unwrap();
+
+    let events = events.lock().unwrap().clone();
+    let statuses = events
+        .iter()
+        .map(|event| (event.layer_id.as_str(), event.status.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        statuses,
+        vec![
+            ("1", "Pulling from enbop/app"),
+            ("a1b2", "Downloading"),
+            ("a1b2", "Pull complete"),
+        ]
+    );
+    assert_eq!((events[1].current, events[1].total), (512, 2048));
+    assert!(
+        events
+            .iter()
+            .all(|event| event.image == "ghcr.io/enbop/app:1")
+    );
+
+    let requests = fixture.requests.lock().await.clone();
+    assert!(requests[0].header("x-registry-auth").is_some());
+}
+
+#[tokio::test]
+async fn reports_in_band_pull_errors() {
+    let fixture = ServerFixture::start().await;
+    let agent = DockerAgent::new(sample_policy(fixture.socket_path.clone()));
+
+    let err = agent
+        .pull_image("ghcr.io/enbop/private:1", &ImagePullOptions::default())
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, DockerAgentError::PullFailed(message) if message.contains("denied")));
+    let requests = fixture.requests.lock().await.clone();
+    assert!(requests[0].header("x-registry-auth").is_none());
+}
+
+#[tokio::test]
+async fn detects_engine_flavor_and_rootless_mode() {
+    let fixture = ServerFixture::start().await;
+    let agent = DockerAgent::new(sample_policy(fixture.socket_path.clone()));
+    assert_eq!(agent.flavor(), EngineFlavor::Docker);
+    let engine = agent.detect_engine().await.unwrap();
+    assert_eq!(engine.flavor, EngineFlavor::Docker);
+    assert_eq!(engine.version, "27.3.1");
+    assert!(!engine.rootless);
+
+    let fixture = ServerFixture::start_podman().await;
+    let agent = DockerAgent::new(sample_policy(fixture.socket_path.clone()));
+    assert_eq!(agent.flavor(), EngineFlavor::Podman);
+    let engine = agent.detect_engine().await.unwrap();
+    assert_eq!(engine.flavor, EngineFlavor::Podman);
+    assert_eq!(engine.api_version, "1.41");
+    assert!(engine.rootless);
+}
+
+#[tokio::test]
+async fn podman_qualifies_short_images_and_pulls_when_image_not_known() {
+    let fixture = ServerFixture::start_podman().await;
+    let agent = DockerAgent::new(sample_policy(fixture.socket_path.clone()));
+    let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let collected = events.clone();
+
+    let spec = ContainerSpec {
+        name: Some("filebrowser".into()),
+        image: "filebrowser/filebrowser:latest".into(),
+        ..Default::default()
+    };
+    let details = agent.create_container(&spec).await.unwrap();
+    assert_eq!(details.name, "filebrowser");
+
+    let requests = fixture.requests.lock().await.clone();
+    let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+    assert_eq!(body["Image"], "docker.io/filebrowser/filebrowser:latest");
+    assert!(
+        requests[1]
+            .path
+            .starts_with("/images/create?fromImage=docker.io/filebrowser/filebrowser")
+    );
+    assert!(requests[2].path.starts_with("/containers/create"));
+
+    let options = ImagePullOptions {
+        progress: Some(Arc::new(move |progress| {
+            collected.lock().unwrap().push(progress.status)
+        })),
+        ..Default::default()
+    };
+    agent
+        .pull_image("filebrowser/filebrowser:latest", &options)
+        .await
+        .unwrap();
+    assert_eq!(
+        events.lock().unwrap().clone(),
+        vec![
+            "Trying to pull docker.io/filebrowser/filebrowser:latest...".to_string(),
+            "Download complete".to_string(),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn decodes_logs_by_declared_stream_type() {
+    let fixture = ServerFixture::start_podman().await;
+    let agent = DockerAgent::new(sample_policy(fixture.socket_path.clone()));
+    let logs = agent
+        .container_logs("container-1", &LogsOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(logs.text, "hello\nwarn\npart");
+
+    let fixture = ServerFixture::start_raw_logs().await;
+    let agent = DockerAgent::new(sample_policy(fixture.socket_path.clone()));
+    let logs = agent
+        .container_logs("container-1", &LogsOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(logs.raw, RAW_TTY_LOGS);
+    assert_eq!(logs.text, String::from_utf8_lossy(RAW_TTY_LOGS));
+}
+
 fn sample_policy(socket_path: PathBuf) -> AgentPolicy {
     AgentPolicy {
         socket_path,
@@ -129,6 +393,7 @@ fn sample_policy(socket_path: PathBuf) -> AgentPolicy {
         managed_label_value: "fungi".into(),
         allowed_host_paths: vec![PathBuf::from("/tmp/fungi")],
         allowed_ports: vec![PortRule::Single(8080)],
+        security: Default::default(),
     }
 }
 
@@ -151,9 +416,27 @@ impl ServerFixture {
         Self::spawn(ServerMode::MissingImageOnce).await
     }
 
+    async fn start_mismatched_image() -> Self {
+        Self::spawn(ServerMode::MismatchedImage).await
+    }
+
+    async fn start_podman() -> Self {
+        Self::spawn(ServerMode::Podman).await
+    }
+
+    async fn start_raw_logs() -> Self {
+        Self::spawn(ServerMode::RawLogs).await
+    }
+
     async fn spawn(mode: ServerMode) -> Self {
         let dir = tempdir().unwrap();
-        let socket_path = dir.path().join("docker.sock");
+        let socket_path = match mode {
+            ServerMode::Podman => {
+                std::fs::create_dir(dir.path().join("podman")).unwrap();
+                dir.path().join("podman/podman.sock")
+            }
+            _ => dir.path().join("docker.sock"),
+        };
         let listener = UnixListener::bind(&socket_path).unwrap();
         let requests = Arc::new(Mutex::new(Vec::new()));
         let recorded = requests.clone();
@@ -170,6 +453,10 @@ impl ServerFixture {
                 tokio::spawn(async move {
                     let request = read_request(&mut stream).await.unwrap();
                     recorded.lock().await.push(request.clone());
+                    if request.path == "/exec/exec-1/start" {
+                        serve_exec_session(&mut stream).await;
+                        return;
+                    }
                     let response = response_for(&request, &mode, &create_calls).await;
                     let _ = stream.write_all(response.as_bytes()).await;
                 });
@@ -188,6 +475,12 @@ enum ServerMode {
     Default,
     Unmanaged,
     MissingImageOnce,
+    MismatchedImage,
+    /// Rootless Podman's compat API: qualified image names, its own error messages and
+    /// labelled multiplexed logs.
+    Podman,
+    /// A Docker engine returning TTY logs labelled as a raw stream.
+    RawLogs,
 }
 
 async fn read_request(stream: &mut tokio::net::UnixStream) -> std::io::Result<RecordedRequest> {
@@ -212,14 +505,16 @@ async fn read_request(stream: &mut tokio::net::UnixStream) -> std::io::Result<Re
     let method = request_parts.next().unwrap().to_string();
     let path = request_parts.next().unwrap().to_string();
 
-    let content_length = lines
-        .find_map(|line| {
+    let headers = lines
+        .filter_map(|line| {
             let (name, value) = line.split_once(':')?;
-            if name.eq_ignore_ascii_case("content-length") {
-                return value.trim().parse::<usize>().ok();
-            }
-            None
+            Some((name.to_string(), value.trim().to_string()))
         })
+        .collect::<Vec<_>>();
+    let content_length = headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.parse::<usize>().ok())
         .unwrap_or(0);
 
     let mut body = buffer[header_end + 4..].to_vec();
@@ -233,7 +528,28 @@ async fn read_request(stream: &mut tokio::net::UnixStream) -> std::io::Result<Re
     }
     body.truncate(content_length);
 
-    Ok(RecordedRequest { method, path, body })
+    Ok(RecordedRequest {
+        method,
+        path,
+        headers,
+        body,
+    })
+}
+
+/// Takes over the connection like the engine does for an attached exec: echoes stdin as one
+/// stdout frame once the client closes it, then reports on stderr and hangs up.
+async fn serve_exec_session(stream: &mut tokio::net::UnixStream) {
+    let _ = stream
+        .write_all(b"HTTP/1.1 101 UPGRADED\r\nConnection: Upgrade\r\nUpgrade: tcp\r\n\r\n")
+        .await;
+    let mut input = Vec::new();
+    let _ = stream.read_to_end(&mut input).await;
+    let mut output = vec![1, 0, 0, 0];
+    output.extend_from_slice(&(input.len() as u32).to_be_bytes());
+    output.extend_from_slice(&input);
+    output.extend_from_slice(&[2, 0, 0, 0, 0, 0, 0, 5]);
+    output.extend_from_slice(b"done\n");
+    let _ = stream.write_all(&output).await;
 }
 
 fn find_header_end(bytes: &[u8]) -> Option<usize> {
@@ -245,7 +561,18 @@ async fn response_for(
     mode: &ServerMode,
     create_calls: &Arc<Mutex<usize>>,
 ) -> String {
+    if matches!(mode, ServerMode::Podman) {
+        return podman_response_for(request, create_calls).await;
+    }
     match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/version") => http_response(
+            200,
+            r#"{"Platform":{"Name":"Docker Engine - Community"},"Components":[{"Name":"Engine","Version":"27.3.1"}],"Version":"27.3.1","ApiVersion":"1.47"}"#,
+        ),
+        ("GET", "/info") => http_response(
+            200,
+            r#"{"SecurityOptions":["name=apparmor","name=seccomp,profile=builtin"]}"#,
+        ),
         ("POST", path) if path.starts_with("/containers/create") => http_response(
             match mode {
                 ServerMode::MissingImageOnce => {
@@ -266,15 +593,60 @@ async fn response_for(
         ("POST", path) if path.starts_with("/images/create?fromImage=filebrowser") => {
             http_response(200, r#"{"status":"Pulling from filebrowser/filebrowser"}"#)
         }
+        ("POST", path) if path.starts_with("/images/create?fromImage=ghcr.io/enbop/app") => {
+            http_response(
+                200,
+                concat!(
+                    r#"{"status":"Pulling from enbop/app","id":"1"}"#,
+                    "\n",
+                    r#"{"status":"Downloading","progressDetail":{"current":512,"total":2048},"progress":"[==>  ]","id":"a1b2"}"#,
+                    "\n",
+                    r#"{"status":"Pull complete","progressDetail":{},"id":"a1b2"}"#,
+                    "\n",
+                ),
+            )
+        }
+        ("POST", path) if path.starts_with("/images/create?fromImage=ghcr.io/enbop/private") => {
+            http_response(
+                200,
+                r#"{"errorDetail":{"message":"pull access denied"},"error":"pull access denied"}"#,
+            )
+        }
         ("GET", "/containers/container-1/json") => http_response(
             200,
             r#"{"Id":"container-1","Name":"/filebrowser","Config":{"Image":"filebrowser/filebrowser:latest","Labels":{"managed_by":"fungi"}},"State":{"Status":"created","Running":false}}"#,
         ),
+        ("GET", path) if path.starts_with("/images/filebrowser") => {
+            let digest = match mode {
+                ServerMode::MismatchedImage => "0".repeat(64),
+                _ => PINNED_DIGEST.to_string(),
+            };
+            http_response(
+                200,
+                &format!(
+                    r#"{{"Id":"sha256:image-1","RepoDigests":["filebrowser/filebrowser@sha256:{digest}"]}}"#
+                ),
+            )
+        }
         ("POST", "/containers/container-1/start") => http_response(204, ""),
+        ("GET", path)
+            if path.starts_with("/containers/container-1/logs")
+                && matches!(mode, ServerMode::RawLogs) =>
+        {
+            typed_response_bytes(200, "application/vnd.docker.raw-stream", RAW_TTY_LOGS)
+        }
         ("GET", path) if path.starts_with("/containers/container-1/logs") => http_response_bytes(
             200,
             &[1, 0, 0, 0, 0, 0, 0, 6, b'h', b'e', b'l', b'l', b'o', b'\n'],
         ),
+        ("GET", path) if path.starts_with("/containers/container-1/stats") => http_response(
+            200,
+            r#"{"cpu_stats":{"cpu_usage":{"total_usage":3000000000},"system_cpu_usage":120000000000,"online_cpus":2},"precpu_stats":{"cpu_usage":{"total_usage":2000000000},"system_cpu_usage":100000000000,"online_cpus":2},"memory_stats":{"usage":52428800,"limit":268435456,"stats":{"inactive_file":10485760}},"networks":{"eth0":{"rx_bytes":1000,"tx_bytes":400},"eth1":{"rx_bytes":24,"tx_bytes":0}},"blkio_stats":{"io_service_bytes_recursive":[{"major":8,"minor":0,"op":"read","value":4096},{"major":8,"minor":0,"op":"write","value":8192},{"major":8,"minor":16,"op":"Read","value":1024}]}}"#,
+        ),
+        ("POST", "/containers/container-1/exec") => http_response(201, r#"{"Id":"exec-1"}"#),
+        ("GET", "/exec/exec-1/json") => {
+            http_response(200, r#"{"ID":"exec-1","Running":false,"ExitCode":3}"#)
+        }
         ("GET", "/containers/legacy/json") if matches!(mode, ServerMode::Unmanaged) => {
             http_response(
                 200,
@@ -285,6 +657,61 @@ async fn response_for(
     }
 }
 
+async fn podman_response_for(
+    request: &RecordedRequest,
+    create_calls: &Arc<Mutex<usize>>,
+) -> String {
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/version") => http_response(
+            200,
+            r#"{"Platform":{"Name":"linux/amd64/fedora-40"},"Components":[{"Name":"Podman Engine","Version":"5.2.3"}],"Version":"5.2.3","ApiVersion":"1.41"}"#,
+        ),
+        ("GET", "/info") => http_response(
+            200,
+            r#"{"SecurityOptions":["name=seccomp,profile=default","name=rootless","name=selinux"]}"#,
+        ),
+        ("POST", path) if path.starts_with("/containers/create") => {
+            let mut calls = create_calls.lock().await;
+            *calls += 1;
+            if *calls == 1 {
+                return http_response(
+                    404,
+                    r#"{"cause":"image not known","message":"docker.io/filebrowser/filebrowser:latest: image not known","response":404}"#,
+                );
+            }
+            http_response(201, r#"{"Id":"container-1","Warnings":[]}"#)
+        }
+        ("POST", path)
+            if path.starts_with("/images/create?fromImage=docker.io/filebrowser/filebrowser") =>
+        {
+            http_response(
+                200,
+                concat!(
+                    r#"{"stream":"Trying to pull docker.io/filebrowser/filebrowser:latest...\n"}"#,
+                    "\n",
+                    r#"{"status":"Download complete","progressDetail":{},"id":"f1e2d3"}"#,
+                    "\n",
+                ),
+            )
+        }
+        ("GET", "/containers/container-1/json") => http_response(
+            200,
+            r#"{"Id":"container-1","Name":"filebrowser","Config":{"Image":"docker.io/filebrowser/filebrowser:latest","Labels":{"managed_by":"fungi"}},"State":{"Status":"created","Running":false}}"#,
+        ),
+        ("GET", path) if path.starts_with("/containers/container-1/logs") => {
+            // stdout and stderr frames followed by a frame cut off mid-payload.
+            let mut body = vec![1, 0, 0, 0, 0, 0, 0, 6];
+            body.extend_from_slice(b"hello\n");
+            body.extend_from_slice(&[2, 0, 0, 0, 0, 0, 0, 5]);
+            body.extend_from_slice(b"warn\n");
+            body.extend_from_slice(&[1, 0, 0, 0, 0, 0, 0, 9]);
+            body.extend_from_slice(b"part");
+            typed_response_bytes(200, "application/vnd.docker.multiplexed-stream", &body)
+        }
+        _ => http_response(404, r#"{"message":"not found"}"#),
+    }
+}
+
 fn http_response(status: u16, body: &str) -> String {
     format!(
         "HTTP/1.1 {} OK\r\nContent-Length: {}\r\nContent-Type: application/json\r\nConnection: close\r\n\r\n{}",
@@ -304,3 +731,15 @@ fn http_response_bytes(status: u16, body: &[u8]) -> String {
     response.extend_from_slice(body);
     String::from_utf8_lossy(&response).to_string()
 }
+
+fn typed_response_bytes(status: u16, content_type: &str, body: &[u8]) -> String {
+    let mut response = format!(
+        "HTTP/1.1 {} OK\r\nContent-Length: {}\r\nContent-Type: {}\r\nConnection: close\r\n\r\n",
+        status,
+        body.len(),
+        content_type
+    )
+    .into_bytes();
+    response.extend_from_slice(body);
+    String::from_utf8_lossy(&response).to_string()
+}
@@ -0,0 +1,230 @@
+use std::{
+    net::IpAddr,
+    ops::{Deref, DerefMut},
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use fungi_util::net::{IpCidr, matching_interface_ips};
+use libp2p::{
+    Multiaddr, PeerId,
+    core::{Endpoint, transport::PortUse},
+    multiaddr::Protocol,
+    swarm::{
+        ConnectionDenied, ConnectionId, FromSwarm, NetworkBehaviour, THandler, THandlerInEvent,
+        THandlerOutEvent, ToSwarm,
+    },
+};
+
+/// Which local addresses peers get to learn about through identify and mDNS.
+#[derive(Debug, Clone, Default)]
+pub struct AnnouncePolicy {
+    /// Advertised in addition to the listen addresses and never filtered.
+    pub announce_addresses: Vec<Multiaddr>,
+    /// Addresses inside these networks are never advertised.
+    pub no_announce: Vec<IpCidr>,
+    /// Addresses assigned to interfaces matching these patterns are never advertised.
+    pub exclude_interfaces: Vec<String>,
+}
+
+impl AnnouncePolicy {
+    pub fn hides(&self, address: &Multiaddr) -> bool {
+        self.filterable_ip(address).is_some()
+            && self.hides_with_interface_ips(address, &self.excluded_interface_ips())
+    }
+
+    pub fn hides_ip(&self, ip: &IpAddr) -> bool {
+        self.no_announce.iter().any(|network| network.contains(ip))
+            || self.excluded_interface_ips().contains(ip)
+    }
+
+    /// Interfaces come and go (docker creates bridges on demand), so they are resolved per check.
+    fn excluded_interface_ips(&self) -> Vec<IpAddr> {
+        matching_interface_ips(&self.exclude_interfaces)
+    }
+
+    fn hides_with_interface_ips(&self, address: &Multiaddr, interface_ips: &[IpAddr]) -> bool {
+        let Some(ip) = self.filterable_ip(address) else {
+            return false;
+        };
+        self.no_announce.iter().any(|network| network.contains(&ip)) || interface_ips.contains(&ip)
+    }
+
+    /// Relay circuits and explicitly announced addresses always pass.
+    fn filterable_ip(&self, address: &Multiaddr) -> Option<IpAddr> {
+        if self.announce_addresses.contains(address)
+            || address
+                .iter()
+                .any(|protocol| protocol == Protocol::P2pCircuit)
+        {
+            return None;
+        }
+        address.iter().find_map(|protocol| match protocol {
+            Protocol::Ip4(ip) => Some(IpAddr::V4(ip)),
+            Protocol::Ip6(ip) => Some(IpAddr::V6(ip)),
+            _ => None,
+        })
+    }
+}
+
+/// Wraps a behaviour that advertises the local listen addresses (identify, mDNS) and hides the
+/// addresses rejected by an [`AnnouncePolicy`] from it. Only what is advertised changes; the
+/// wrapped mDNS behaviour still queries on every interface.
+pub struct AnnounceFilter<B> {
+    inner: B,
+    policy: Arc<AnnouncePolicy>,
+}
+
+impl<B> AnnounceFilter<B> {
+    pub fn new(inner: B, policy: Arc<AnnouncePolicy>) -> Self {
+        Self { inner, policy }
+    }
+}
+
+impl<B> Deref for AnnounceFilter<B> {
+    type Target = B;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<B> DerefMut for AnnounceFilter<B> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+impl<B: NetworkBehaviour> NetworkBehaviour for AnnounceFilter<B> {
+    type ConnectionHandler = B::ConnectionHandler;
+    type ToSwarm = B::ToSwarm;
+
+    fn handle_pending_inbound_connection(
+        &mut self,
+        connection_id: ConnectionId,
+        local_addr: &Multiaddr,
+        remote_addr: &Multiaddr,
+    ) -> Result<(), ConnectionDenied> {
+        self.inner
+            .handle_pending_inbound_connection(connection_id, local_addr, remote_addr)
+    }
+
+    fn handle_established_inbound_connection(
+        &mut self,
+        connection_id: ConnectionId,
+        peer: PeerId,
+        local_addr: &Multiaddr,
+        remote_addr: &Multiaddr,
+    ) -> Result<THandler<Self>, ConnectionDenied> {
+        self.inner.handle_established_inbound_connection(
+            connection_id,
+            peer,
+            local_addr,
+            remote_addr,
+        )
+    }
+
+    fn handle_pending_outbound_connection(
+        &mut self,
+        connection_id: ConnectionId,
+        maybe_peer: Option<PeerId>,
+        addresses: &[Multiaddr],
+        effective_role: Endpoint,
+    ) -> Result<Vec<Multiaddr>, ConnectionDenied> {
+        self.inner.handle_pending_outbound_connection(
+            connection_id,
+            maybe_peer,
+            addresses,
+            effective_role,
+        )
+    }
+
+    fn handle_established_outbound_connection(
+        &mut self,
+        connection_id: ConnectionId,
+        peer: PeerId,
+        addr: &Multiaddr,
+        role_override: Endpoint,
+        port_use: PortUse,
+    ) -> Result<THandler<Self>, ConnectionDenied> {
+        self.inner.handle_established_outbound_connection(
+            connection_id,
+            peer,
+            addr,
+            role_override,
+            port_use,
+        )
+    }
+
+    fn on_swarm_event(&mut self, event: FromSwarm) {
+        let hidden = match &event {
+            FromSwarm::NewListenAddr(listen) => Some(listen.addr),
+            FromSwarm::ExternalAddrConfirmed(external) => Some(external.addr),
+            _ => None,
+        }
+        .filter(|address| self.policy.hides(address));
+        if let Some(address) = hidden {
+            log::debug!("Not announcing local address {address}");
+            return;
+        }
+        self.inner.on_swarm_event(event);
+    }
+
+    fn on_connection_handler_event(
+        &mut self,
+        peer_id: PeerId,
+        connection_id: ConnectionId,
+        event: THandlerOutEvent<Self>,
+    ) {
+        self.inner
+            .on_connection_handler_event(peer_id, connection_id, event);
+    }
+
+    fn poll(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<ToSwarm<Self::ToSwarm, THandlerInEvent<Self>>> {
+        self.inner.poll(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addr(value: &str) -> Multiaddr {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn policy_hides_no_announce_networks_and_excluded_interface_addresses() {
+        let policy = AnnouncePolicy {
+            announce_addresses: vec![addr("/ip4/100.64.1.1/tcp/4001")],
+            no_announce: vec!["100.64.0.0/10".parse().unwrap()],
+            exclude_interfaces: vec!["docker*".to_string()],
+        };
+        let docker_ips = ["172.17.0.1".parse().unwrap()];
+
+        assert!(policy.hides_with_interface_ips(&addr("/ip4/100.100.2.3/tcp/4001"), &docker_ips));
+        assert!(
+            policy.hides_with_interface_ips(&addr("/ip4/172.17.0.1/udp/4001/quic-v1"), &docker_ips)
+        );
+        assert!(!policy.hides_with_interface_ips(&addr("/ip4/192.168.1.5/tcp/4001"), &docker_ips));
+        assert!(!policy.hides_with_interface_ips(&addr("/ip6/fe80::1/tcp/4001"), &docker_ips));
+    }
+
+    #[test]
+    fn policy_never_hides_announced_or_relayed_addresses() {
+        let policy = AnnouncePolicy {
+            announce_addresses: vec![addr("/ip4/100.64.1.1/tcp/4001")],
+            no_announce: vec!["100.64.0.0/10".parse().unwrap()],
+            exclude_interfaces: Vec::new(),
+        };
+
+        assert!(!policy.hides(&addr("/ip4/100.64.1.1/tcp/4001")));
+        assert!(!policy.hides(&addr(
+            "/ip4/100.64.9.9/tcp/4001/p2p/16Uiu2HAmGXFS6aYsKKYRkEDo1tNigZKN8TAYrsfSnEdC5sZLNkiE/p2p-circuit"
+        )));
+        assert!(!policy.hides(&addr("/dns4/example.com/tcp/4001")));
+    }
+}
@@ -1,9 +1,11 @@
+pub mod announce;
 pub mod ext;
 pub mod relay_refresh;
 
 use std::collections::HashSet;
 
 use std::ops::Deref;
+use std::sync::Arc;
 
 use libp2p::{
     PeerId, dcutr, identify, identity::Keypair, mdns, ping as libp2p_ping, relay,
@@ -12,6 +14,8 @@ use libp2p::{
 
 use crate::State;
 
+use announce::{AnnounceFilter, AnnouncePolicy};
+
 // default identify protocol name for libp2p
 const IDENTIFY_PROTOCOL: &str = "/fungi/id/0.1.0";
 
@@ -23,9 +27,9 @@ fn identify_user_agent() -> String {
 pub struct FungiBehaviours {
     pub stream: fungi_stream::Behaviour,
     relay_refresh: relay_refresh::Behaviour,
-    pub mdns: mdns::tokio::Behaviour,
+    pub mdns: AnnounceFilter<mdns::tokio::Behaviour>,
     ping: libp2p_ping::Behaviour,
-    identify: identify::Behaviour,
+    identify: AnnounceFilter<identify::Behaviour>,
     relay: relay::client::Behaviour,
     dcutr: dcutr::Behaviour,
 
@@ -47,6 +51,7 @@ impl FungiBehaviours {
         mdns: mdns::tokio::Behaviour,
         state: State,
         trusted_relay_peer_ids: Vec<PeerId>,
+        announce_policy: Arc<AnnouncePolicy>,
     ) -> Self {
         let peer_id = keypair.public().to_peer_id();
         let global_allow_list = state.incoming_allowed_peers();
@@ -62,9 +67,9 @@ impl FungiBehaviours {
         Self {
             stream: fungi_stream::Behaviour::new(global_allow_list),
             relay_refresh: relay_refresh::Behaviour::new_trusted_relays(trusted_relay_peer_ids),
-            mdns,
+            mdns: AnnounceFilter::new(mdns, announce_policy.clone()),
             ping: libp2p_ping::Behaviour::new(libp2p_ping::Config::new()),
-            identify,
+            identify: AnnounceFilter::new(identify, announce_policy),
             relay,
             dcutr: dcutr::Behaviour::new(peer_id),
             fungi_ext: ext::Behaviour::new(state),
@@ -0,0 +1,185 @@
+use super::{SwarmControl, dial_plan::DialPlan};
+use crate::{AddressFreshness, AddressTransportKind, HolePunchRecord, PeerAddressSource};
+use anyhow::Result;
+use libp2p::{
+    Multiaddr, PeerId,
+    swarm::{
+        ConnectionId,
+        dial_opts::{DialOpts, PeerCondition},
+    },
+};
+use std::time::{Duration, Instant, SystemTime};
+
+const PROBE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum DialProbeOutcome {
+    Connected,
+    Failed(String),
+    TimedOut,
+}
+
+impl DialProbeOutcome {
+    pub fn is_connected(&self) -> bool {
+        matches!(self, DialProbeOutcome::Connected)
+    }
+}
+
+/// Result of dialing a single direct candidate from the peer's dial plan.
+#[derive(Debug, Clone)]
+pub struct DirectDialProbe {
+    pub address: Multiaddr,
+    pub transport_kind: AddressTransportKind,
+    pub source: PeerAddressSource,
+    pub freshness: AddressFreshness,
+    pub outcome: DialProbeOutcome,
+    pub elapsed: Duration,
+}
+
+/// Result of dialing the peer through the configured relays and waiting for DCUtR.
+#[derive(Debug, Clone)]
+pub struct RelayCircuitProbe {
+    pub circuit_addresses: Vec<Multiaddr>,
+    pub outcome: DialProbeOutcome,
+    pub elapsed: Duration,
+    /// Hole-punch result observed after the probe started, if DCUtR reported one in time.
+    pub hole_punch: Option<HolePunchRecord>,
+}
+
+impl SwarmControl {
+    pub async fn listen_addresses(&self) -> Result<Vec<Multiaddr>> {
+        self.invoke_swarm(|swarm| swarm.listeners().cloned().collect())
+            .await
+    }
+
+    /// Dials up to `limit` known direct candidates for `peer_id` one at a time, so each address
+    /// gets its own outcome instead of the first success masking the rest.
+    pub async fn probe_direct_dial_candidates(
+        &self,
+        peer_id: PeerId,
+        timeout: Duration,
+        limit: usize,
+    ) -> Vec<DirectDialProbe> {
+        let plan = DialPlan::for_peer(self.state(), peer_id);
+        let candidates = plan
+            .direct_candidates
+            .into_iter()
+            .chain(plan.stale_direct_candidates)
+            .take(limit);
+
+        let mut probes = Vec::new();
+        for candidate in candidates {
+            let started = Instant::now();
+            let outcome = self
+                .probe_dial(peer_id, vec![candidate.addr.clone()], timeout)
+                .await;
+            probes.push(DirectDialProbe {
+                transport_kind: crate::address_transport_kind(&candidate.addr),
+                address: candidate.addr,
+                source: candidate.source,
+                freshness: candidate.freshness,
+                outcome,
+                elapsed: started.elapsed(),
+            });
+        }
+        probes
+    }
+
+    /// Returns `None` when no relays are configured.
+    pub async fn probe_relay_circuit(
+        &self,
+        peer_id: PeerId,
+        timeout: Duration,
+        hole_punch_timeout: Duration,
+    ) -> Option<RelayCircuitProbe> {
+        if self.relay_peers.is_empty() {
+            return None;
+        }
+
+        let circuit_addresses = self
+            .relay_peers
+            .circuit_addresses_for_target(peer_id, self.state());
+        let probe_started_at = SystemTime::now();
+        let started = Instant::now();
+        let outcome = self
+            .probe_dial(peer_id, circuit_addresses.clone(), timeout)
+            .await;
+        let elapsed = started.elapsed();
+
+        let hole_punch = if outcome.is_connected() {
+            self.wait_for_hole_punch(peer_id, probe_started_at, hole_punch_timeout)
+                .await
+        } else {
+            None
+        };
+
+        Some(RelayCircuitProbe {
+            circuit_addresses,
+            outcome,
+            elapsed,
+            hole_punch,
+        })
+    }
+
+    async fn probe_dial(
+        &self,
+        peer_id: PeerId,
+        addresses: Vec<Multiaddr>,
+        timeout: Duration,
+    ) -> DialProbeOutcome {
+        let dial_opts = DialOpts::peer_id(peer_id)
+            .condition(PeerCondition::Always)
+            .addresses(addresses)
+            .build();
+        let connection_id = dial_opts.connection_id();
+
+        self.state().begin_dial_probe(connection_id);
+        let outcome = match self.invoke_swarm(move |swarm| swarm.dial(dial_opts)).await {
+            Ok(Ok(())) => self.wait_for_dial_probe(connection_id, timeout).await,
+            Ok(Err(error)) => DialProbeOutcome::Failed(error.to_string()),
+            Err(error) => DialProbeOutcome::Failed(error.to_string()),
+        };
+        self.state().finish_dial_probe(&connection_id);
+        outcome
+    }
+
+    async fn wait_for_dial_probe(
+        &self,
+        connection_id: ConnectionId,
+        timeout: Duration,
+    ) -> DialProbeOutcome {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if self.state().has_connection(&connection_id) {
+                return DialProbeOutcome::Connected;
+            }
+            if let Some(error) = self.state().dial_probe_failure(&connection_id) {
+                return DialProbeOutcome::Failed(error);
+            }
+            if Instant::now() >= deadline {
+                return DialProbeOutcome::TimedOut;
+            }
+            tokio::time::sleep(PROBE_POLL_INTERVAL).await;
+        }
+    }
+
+    async fn wait_for_hole_punch(
+        &self,
+        peer_id: PeerId,
+        since: SystemTime,
+        timeout: Duration,
+    ) -> Option<HolePunchRecord> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(record) = self.state().hole_punch_outcome(&peer_id)
+                && record.observed_at >= since
+            {
+                return Some(record);
+            }
+            if Instant::now() >= deadline {
+                return None;
+            }
+            tokio::time::sleep(PROBE_POLL_INTERVAL).await;
+        }
+    }
+}
@@ -1,4 +1,5 @@
 mod control;
+mod diagnostics;
 mod dial_plan;
 mod governance;
 mod relay;
@@ -8,9 +9,11 @@ mod tests;
 mod types;
 
 use crate::behaviours::FungiBehaviours;
+pub use crate::behaviours::announce::AnnouncePolicy;
 use libp2p::Swarm;
 
 pub use control::{ConnectError, SwarmAsyncCall, SwarmControl};
+pub use diagnostics::{DialProbeOutcome, DirectDialProbe, RelayCircuitProbe};
 pub use relay::{get_default_relay_addrs, peer_addr_with_relay};
 pub use runtime::FungiSwarm;
 pub(crate) use types::ConnectionRecordSliceExt;
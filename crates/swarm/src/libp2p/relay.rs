@@ -3,7 +3,8 @@
 // Relay reservation is UDP-first within each relay peer. If the UDP candidate
 // does not become ready during the current reconciliation attempt, we fall back
 // to TCP for availability. A healthy TCP fallback is not interrupted just to
-// upgrade back to UDP; the next reconcile starts from UDP again.
+// upgrade back to UDP; the next reconcile starts from UDP again. WebSocket
+// endpoints come last: they exist for networks that only allow outbound HTTPS.
 use super::SwarmControl;
 use crate::{State, behaviours::relay_refresh};
 use anyhow::{Result, bail};
@@ -80,6 +81,7 @@ impl RefreshThrottle {
 pub(super) enum RelayTransportKind {
     Tcp,
     Udp,
+    WebSocket,
 }
 
 #[derive(Clone)]
@@ -153,9 +155,14 @@ impl RelayPeerGroup {
         self.task_flag.clone()
     }
 
+    /// WebSocket endpoints ride on TCP, so they count as TCP here.
     fn contains_transport(&self, transport_kind: RelayTransportKind) -> bool {
-        self.endpoints()
-            .any(|endpoint| endpoint.transport_kind() == Some(transport_kind))
+        self.endpoints().any(|endpoint| {
+            let endpoint_kind = endpoint.transport_kind();
+            endpoint_kind == Some(transport_kind)
+                || (transport_kind == RelayTransportKind::Tcp
+                    && endpoint_kind == Some(RelayTransportKind::WebSocket))
+        })
     }
 
     fn active_endpoint(&self, state: &State) -> Option<RelayEndpoint> {
@@ -219,17 +226,19 @@ impl RelayPeers {
             .iter()
             .filter_map(|peer_id| {
                 let endpoints = endpoints_by_peer.remove(peer_id)?;
-                let mut udp_endpoints = endpoints
-                    .iter()
-                    .filter(|endpoint| endpoint.transport_kind() == Some(RelayTransportKind::Udp))
-                    .cloned()
-                    .collect::<Vec<_>>();
-                udp_endpoints.extend(
-                    endpoints.into_iter().filter(|endpoint| {
-                        endpoint.transport_kind() == Some(RelayTransportKind::Tcp)
-                    }),
-                );
-                let endpoints = udp_endpoints;
+                let endpoints = [
+                    RelayTransportKind::Udp,
+                    RelayTransportKind::Tcp,
+                    RelayTransportKind::WebSocket,
+                ]
+                .into_iter()
+                .flat_map(|transport_kind| {
+                    endpoints
+                        .iter()
+                        .filter(move |endpoint| endpoint.transport_kind() == Some(transport_kind))
+                        .cloned()
+                })
+                .collect::<Vec<_>>();
                 Some(RelayPeerGroup::new(*peer_id, endpoints))
             })
             .collect::<Vec<_>>();
@@ -393,7 +402,19 @@ fn push_relay_addr_once(
 }
 
 pub(super) fn relay_transport_kind(addr: &Multiaddr) -> Option<RelayTransportKind> {
-    for protocol in addr.iter() {
+    // Only the part before a circuit describes how the relay itself is reached.
+    let relay_part = addr
+        .iter()
+        .take_while(|protocol| *protocol != Protocol::P2pCircuit)
+        .collect::<Vec<_>>();
+    if relay_part
+        .iter()
+        .any(|protocol| matches!(protocol, Protocol::Ws(_) | Protocol::Wss(_)))
+    {
+        return Some(RelayTransportKind::WebSocket);
+    }
+
+    for protocol in relay_part {
         match protocol {
             Protocol::Tcp(_) => return Some(RelayTransportKind::Tcp),
             Protocol::Udp(_) => return Some(RelayTransportKind::Udp),
@@ -1193,6 +1214,7 @@ fn transport_name(kind: Option<RelayTransportKind>) -> &'static str {
     match kind {
         Some(RelayTransportKind::Tcp) => "tcp",
         Some(RelayTransportKind::Udp) => "udp",
+        Some(RelayTransportKind::WebSocket) => "websocket",
         None => "unknown",
     }
 }
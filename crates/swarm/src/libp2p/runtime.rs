@@ -9,7 +9,7 @@ use super::{
 };
 use crate::{
     ExternalAddressSource, State,
-    behaviours::{FungiBehaviours, FungiBehavioursEvent},
+    behaviours::{FungiBehaviours, FungiBehavioursEvent, announce::AnnouncePolicy},
     ping::probe_pong_loop,
     state,
 };
@@ -37,11 +37,13 @@ impl FungiSwarm {
         state: State,
         relay_addresses: Vec<Multiaddr>,
         idle_connection_timeout: Duration,
+        announce_policy: AnnouncePolicy,
         apply: impl FnOnce(&mut TSwarm),
     ) -> Result<(SwarmControl, JoinHandle<()>)> {
         let mdns =
             mdns::tokio::Behaviour::new(mdns::Config::default(), keypair.public().to_peer_id())?;
         let relay_peers = RelayPeers::new(relay_addresses);
+        let announce_policy = Arc::new(announce_policy);
 
         let mut swarm = libp2p::SwarmBuilder::with_existing_identity(keypair)
             .with_tokio()
@@ -51,6 +53,9 @@ impl FungiSwarm {
                 yamux::Config::default,
             )?
             .with_quic()
+            .with_dns()?
+            .with_websocket(noise::Config::new, yamux::Config::default)
+            .await?
             .with_relay_client(noise::Config::new, yamux::Config::default)?
             .with_behaviour(|keypair, relay| {
                 FungiBehaviours::new(
@@ -59,6 +64,7 @@ impl FungiSwarm {
                     mdns,
                     state.clone(),
                     relay_peers.peer_ids().to_vec(),
+                    announce_policy.clone(),
                 )
             })?
             .with_swarm_config(|config| {
@@ -70,6 +76,9 @@ impl FungiSwarm {
         let stream_control = swarm.behaviour().stream.new_control();
         let refresh_throttle = RefreshThrottle::default();
 
+        for address in &announce_policy.announce_addresses {
+            swarm.add_external_address(address.clone());
+        }
         apply(&mut swarm);
 
         let (swarm_caller_tx, swarm_caller_rx) = mpsc::unbounded_channel::<SwarmAsyncCall>();
@@ -85,7 +94,8 @@ impl FungiSwarm {
             relay_peers,
             state,
         );
-        let event_handle_future = handle_swarm_event(swarm_control.clone(), swarm_event_rx);
+        let event_handle_future =
+            handle_swarm_event(swarm_control.clone(), swarm_event_rx, announce_policy);
         let relay_health_future = relay_management_loop(swarm_control.clone());
         let connection_governance_future = connection_governance_loop(swarm_control.clone());
         let probe_pong_future = probe_pong_loop(swarm_control.clone());
@@ -133,6 +143,7 @@ async fn swarm_loop(
 async fn handle_swarm_event(
     swarm_control: SwarmControl,
     mut event_rx: UnboundedReceiver<SwarmEvent<FungiBehavioursEvent>>,
+    announce_policy: Arc<AnnouncePolicy>,
 ) {
     loop {
         let Some(event) = event_rx.recv().await else {
@@ -170,9 +181,13 @@ async fn handle_swarm_event(
                 handle_relay_refresh_behaviour_event(&swarm_control, event);
             }
             SwarmEvent::Behaviour(FungiBehavioursEvent::Dcutr(event)) => {
-                handle_dcutr_behaviour_event(event);
+                handle_dcutr_behaviour_event(&swarm_control, event);
             }
             SwarmEvent::NewExternalAddrCandidate { address, .. } => {
+                if announce_policy.hides(&address) {
+                    log::debug!("[Swarm event] Ignoring hidden external candidate {address:?}");
+                    continue;
+                }
                 swarm_control.state().record_external_address_candidate(
                     address.clone(),
                     ExternalAddressSource::SwarmCandidate,
@@ -217,8 +232,15 @@ async fn handle_swarm_event(
                     endpoint.get_remote_address(),
                 );
             }
-            SwarmEvent::OutgoingConnectionError { peer_id, error, .. } => {
+            SwarmEvent::OutgoingConnectionError {
+                connection_id,
+                peer_id,
+                error,
+            } => {
                 log::info!("[Swarm event] OutgoingConnectionError {peer_id:?}: {error:?}");
+                swarm_control
+                    .state()
+                    .record_dial_probe_failure(connection_id, error.to_string());
                 let Some(peer_id) = peer_id else {
                     continue;
                 };
@@ -379,14 +401,15 @@ fn handle_identify_behaviour_event(swarm_control: &SwarmControl, event: identify
     }
 }
 
-fn handle_dcutr_behaviour_event(event: libp2p::dcutr::Event) {
-    match event.result {
+fn handle_dcutr_behaviour_event(swarm_control: &SwarmControl, event: libp2p::dcutr::Event) {
+    let outcome = match event.result {
         Ok(connection_id) => {
             log::info!(
                 "Hole punch succeeded for peer {} on connection {:?}",
                 event.remote_peer_id,
                 connection_id
             );
+            Ok(connection_id)
         }
         Err(error) => {
             log::warn!(
@@ -394,8 +417,12 @@ fn handle_dcutr_behaviour_event(event: libp2p::dcutr::Event) {
                 event.remote_peer_id,
                 error
             );
+            Err(error.to_string())
         }
-    }
+    };
+    swarm_control
+        .state()
+        .record_hole_punch_outcome(event.remote_peer_id, outcome);
 }
 
 fn summarize_multiaddrs(addrs: &[Multiaddr]) -> String {
@@ -52,6 +52,53 @@ fn relay_transport_kind_detects_quic_as_udp() {
     assert_eq!(relay_transport_kind(&addr), Some(RelayTransportKind::Udp));
 }
 
+#[test]
+fn relay_transport_kind_detects_websocket_before_tcp() {
+    let ws = "/dns4/relay.example.com/tcp/443/tls/ws/p2p/16Uiu2HAmGXFS6aYsKKYRkEDo1tNigZKN8TAYrsfSnEdC5sZLNkiE"
+        .parse()
+        .unwrap();
+    let plain_ws =
+        "/ip4/160.16.206.21/tcp/30002/ws/p2p/16Uiu2HAmGXFS6aYsKKYRkEDo1tNigZKN8TAYrsfSnEdC5sZLNkiE"
+            .parse()
+            .unwrap();
+
+    assert_eq!(
+        relay_transport_kind(&ws),
+        Some(RelayTransportKind::WebSocket)
+    );
+    assert_eq!(
+        relay_transport_kind(&plain_ws),
+        Some(RelayTransportKind::WebSocket)
+    );
+}
+
+#[test]
+fn relay_peer_groups_try_websocket_endpoints_last() {
+    let relay_peer = libp2p::identity::Keypair::generate_ed25519()
+        .public()
+        .to_peer_id();
+    let ws_addr: Multiaddr = format!("/dns4/relay.example.com/tcp/443/tls/ws/p2p/{relay_peer}")
+        .parse()
+        .unwrap();
+    let tcp_addr: Multiaddr = format!("/ip4/160.16.206.21/tcp/30001/p2p/{relay_peer}")
+        .parse()
+        .unwrap();
+    let udp_addr: Multiaddr = format!("/ip4/160.16.206.21/udp/30001/quic-v1/p2p/{relay_peer}")
+        .parse()
+        .unwrap();
+
+    let relay_peers = RelayPeers::new(vec![ws_addr.clone(), tcp_addr.clone(), udp_addr.clone()]);
+    let group = relay_peers.group_for_peer(relay_peer).unwrap();
+
+    assert_eq!(
+        group
+            .endpoints()
+            .map(|endpoint| endpoint.addr().clone())
+            .collect::<Vec<_>>(),
+        vec![udp_addr, tcp_addr, ws_addr]
+    );
+}
+
 #[test]
 fn relay_endpoint_matches_transport_by_protocol_kind() {
     let relay_endpoint = RelayEndpoint::new(
@@ -209,6 +209,16 @@ impl PeerAddressRecord {
     }
 }
 
+/// Latest DCUtR hole-punch outcome observed for a remote peer.
+#[derive(Debug, Clone)]
+pub struct HolePunchRecord {
+    pub peer_id: PeerId,
+    pub succeeded: bool,
+    pub connection_id: Option<ConnectionId>,
+    pub error: Option<String>,
+    pub observed_at: SystemTime,
+}
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum PeerAddressObservation {
     New,
@@ -233,6 +243,7 @@ pub struct ConnectivityState {
     relay_endpoint_statuses: HashMap<Multiaddr, RelayEndpointStatusRecord>,
     peer_address_records: HashMap<(PeerId, Multiaddr), PeerAddressRecord>,
     peer_address_revision: u64,
+    hole_punch_outcomes: HashMap<PeerId, HolePunchRecord>,
 }
 
 impl ConnectivityState {
@@ -564,6 +575,31 @@ impl ConnectivityState {
         self.peer_address_revision
     }
 
+    pub fn record_hole_punch_outcome(
+        &mut self,
+        peer_id: PeerId,
+        result: Result<ConnectionId, String>,
+    ) {
+        let (succeeded, connection_id, error) = match result {
+            Ok(connection_id) => (true, Some(connection_id), None),
+            Err(error) => (false, None, Some(error)),
+        };
+        self.hole_punch_outcomes.insert(
+            peer_id,
+            HolePunchRecord {
+                peer_id,
+                succeeded,
+                connection_id,
+                error,
+                observed_at: SystemTime::now(),
+            },
+        );
+    }
+
+    pub fn hole_punch_outcome(&self, peer_id: &PeerId) -> Option<HolePunchRecord> {
+        self.hole_punch_outcomes.get(peer_id).cloned()
+    }
+
     fn record_external_address(
         &mut self,
         address: Multiaddr,
@@ -1072,4 +1108,24 @@ mod tests {
         assert_eq!(tcp_status.current_direct_connection_id, None);
         assert!(tcp_status.last_reservation_established_at.is_none());
     }
+
+    #[test]
+    fn hole_punch_outcome_keeps_latest_result_per_peer() {
+        let mut state = ConnectivityState::default();
+        let peer_id = PeerId::random();
+
+        assert!(state.hole_punch_outcome(&peer_id).is_none());
+
+        state.record_hole_punch_outcome(peer_id, Err("no addresses".to_string()));
+        let failed = state.hole_punch_outcome(&peer_id).unwrap();
+        assert!(!failed.succeeded);
+        assert_eq!(failed.error.as_deref(), Some("no addresses"));
+
+        let connection_id = ConnectionId::new_unchecked(7);
+        state.record_hole_punch_outcome(peer_id, Ok(connection_id));
+        let succeeded = state.hole_punch_outcome(&peer_id).unwrap();
+        assert!(succeeded.succeeded);
+        assert_eq!(succeeded.connection_id, Some(connection_id));
+        assert!(succeeded.error.is_none());
+    }
 }
@@ -1,7 +1,7 @@
 use crate::{
     AddressTransportKind, ConnectivityState, ExternalAddressCandidateRecord, ExternalAddressSource,
-    PeerAddressRecord, PeerAddressSource, RelayDirectConnectionSnapshot, RelayEndpointStatusRecord,
-    SwarmControl,
+    HolePunchRecord, PeerAddressRecord, PeerAddressSource, RelayDirectConnectionSnapshot,
+    RelayEndpointStatusRecord, SwarmControl,
 };
 use async_result::Completer;
 use libp2p::{
@@ -152,6 +152,9 @@ pub struct State {
     next_stream_id: Arc<AtomicU64>,
     stream_state: Arc<Mutex<StreamObservationState>>,
     connectivity_state: Arc<Mutex<ConnectivityState>>,
+    /// Dials issued by diagnostics probes, keyed by the dial's connection id. The value holds
+    /// the dial error once the swarm reports the attempt as failed.
+    dial_probes: Arc<Mutex<HashMap<ConnectionId, Option<String>>>>,
 }
 
 impl State {
@@ -163,6 +166,7 @@ impl State {
             next_stream_id: Arc::new(AtomicU64::new(0)),
             stream_state: Arc::new(Mutex::new(StreamObservationState::default())),
             connectivity_state: Arc::new(Mutex::new(ConnectivityState::default())),
+            dial_probes: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
@@ -341,6 +345,50 @@ impl State {
         self.connectivity_state.lock().peer_address_revision()
     }
 
+    pub fn record_hole_punch_outcome(&self, peer_id: PeerId, result: Result<ConnectionId, String>) {
+        self.connectivity_state
+            .lock()
+            .record_hole_punch_outcome(peer_id, result);
+    }
+
+    pub fn hole_punch_outcome(&self, peer_id: &PeerId) -> Option<HolePunchRecord> {
+        self.connectivity_state.lock().hole_punch_outcome(peer_id)
+    }
+
+    pub(crate) fn begin_dial_probe(&self, connection_id: ConnectionId) {
+        self.dial_probes.lock().insert(connection_id, None);
+    }
+
+    pub(crate) fn record_dial_probe_failure(&self, connection_id: ConnectionId, error: String) {
+        if let Some(failure) = self.dial_probes.lock().get_mut(&connection_id) {
+            *failure = Some(error);
+        }
+    }
+
+    pub(crate) fn dial_probe_failure(&self, connection_id: &ConnectionId) -> Option<String> {
+        self.dial_probes
+            .lock()
+            .get(connection_id)
+            .cloned()
+            .flatten()
+    }
+
+    pub(crate) fn finish_dial_probe(&self, connection_id: &ConnectionId) {
+        self.dial_probes.lock().remove(connection_id);
+    }
+
+    pub fn has_connection(&self, connection_id: &ConnectionId) -> bool {
+        self.connections.lock().by_id.contains_key(connection_id)
+    }
+
+    pub fn connection_is_relay(&self, connection_id: &ConnectionId) -> Option<bool> {
+        self.connections
+            .lock()
+            .by_id
+            .get(connection_id)
+            .map(ConnectionRecord::is_relay)
+    }
+
     pub fn get_incoming_allowed_peers_list(&self) -> Vec<PeerId> {
         self.incoming_allowed_peers.read().iter().cloned().collect()
     }
@@ -408,6 +456,26 @@ impl State {
         peer_id: PeerId,
         connection_id: ConnectionId,
         protocol: StreamProtocol,
+    ) -> StreamObservationHandle {
+        self.track_stream_opened(peer_id, connection_id, protocol)
+    }
+
+    /// Counts a stream accepted from `peer_id` towards the per-protocol totals until the
+    /// returned handle is dropped.
+    pub fn track_inbound_stream_opened(
+        &self,
+        peer_id: PeerId,
+        connection_id: ConnectionId,
+        protocol: StreamProtocol,
+    ) -> StreamObservationHandle {
+        self.track_stream_opened(peer_id, connection_id, protocol)
+    }
+
+    fn track_stream_opened(
+        &self,
+        peer_id: PeerId,
+        connection_id: ConnectionId,
+        protocol: StreamProtocol,
     ) -> StreamObservationHandle {
         let stream_id: u64 = self.next_stream_id.fetch_add(1, Ordering::Relaxed) + 1;
 
@@ -1,4 +1,5 @@
 pub mod keypair;
+pub mod net;
 pub mod protocols;
 
 #[cfg(target_os = "android")]
@@ -16,6 +17,26 @@ pub fn get_local_ip() -> Option<String> {
     None
 }
 
+/// Hardware addresses of the interfaces that carry a private IPv4 address, i.e. the ones a
+/// Wake-on-LAN packet could reach, formatted as `aa:bb:cc:dd:ee:ff`.
+pub fn get_local_mac_addresses() -> Vec<String> {
+    let networks = sysinfo::Networks::new_with_refreshed_list();
+    let mut mac_addresses = networks
+        .list()
+        .values()
+        .filter(|network| !network.mac_address().is_unspecified())
+        .filter(|network| {
+            network.ip_networks().iter().any(
+                |ip_network| matches!(ip_network.addr, std::net::IpAddr::V4(ip) if ip.is_private()),
+            )
+        })
+        .map(|network| network.mac_address().to_string())
+        .collect::<Vec<_>>();
+    mac_addresses.sort();
+    mac_addresses.dedup();
+    mac_addresses
+}
+
 pub fn get_hostname() -> Option<String> {
     #[cfg(target_os = "android")]
     {
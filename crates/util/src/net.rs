@@ -0,0 +1,189 @@
+use std::{fmt, net::IpAddr, str::FromStr};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// An IP network written as `addr/prefix_len`, e.g. `100.64.0.0/10` or `fd00::/8`. A bare
+/// address is treated as a single-host network.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IpCidr {
+    addr: IpAddr,
+    prefix_len: u8,
+}
+
+impl IpCidr {
+    pub fn new(addr: IpAddr, prefix_len: u8) -> anyhow::Result<Self> {
+        let max_len = max_prefix_len(&addr);
+        if prefix_len > max_len {
+            anyhow::bail!("prefix length {prefix_len} exceeds {max_len} for {addr}");
+        }
+        Ok(Self { addr, prefix_len })
+    }
+
+    pub fn addr(&self) -> IpAddr {
+        self.addr
+    }
+
+    pub fn prefix_len(&self) -> u8 {
+        self.prefix_len
+    }
+
+    pub fn contains(&self, ip: &IpAddr) -> bool {
+        match (self.addr, ip) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                prefix_matches(&network.octets(), &ip.octets(), self.prefix_len)
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                prefix_matches(&network.octets(), &ip.octets(), self.prefix_len)
+            }
+            _ => false,
+        }
+    }
+}
+
+fn max_prefix_len(addr: &IpAddr) -> u8 {
+    match addr {
+        IpAddr::V4(_) => 32,
+        IpAddr::V6(_) => 128,
+    }
+}
+
+fn prefix_matches(network: &[u8], ip: &[u8], prefix_len: u8) -> bool {
+    let full_bytes = usize::from(prefix_len / 8);
+    if network[..full_bytes] != ip[..full_bytes] {
+        return false;
+    }
+    let remaining_bits = prefix_len % 8;
+    if remaining_bits == 0 {
+        return true;
+    }
+    let mask = u8::MAX << (8 - remaining_bits);
+    network[full_bytes] & mask == ip[full_bytes] & mask
+}
+
+impl FromStr for IpCidr {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        let (addr, prefix_len) = match value.split_once('/') {
+            Some((addr, prefix_len)) => (addr, Some(prefix_len)),
+            None => (value, None),
+        };
+        let addr = addr
+            .parse::<IpAddr>()
+            .map_err(|_| anyhow::anyhow!("invalid IP network: {value}"))?;
+        let prefix_len = match prefix_len {
+            Some(prefix_len) => prefix_len
+                .parse::<u8>()
+                .map_err(|_| anyhow::anyhow!("invalid IP network: {value}"))?,
+            None => max_prefix_len(&addr),
+        };
+        Self::new(addr, prefix_len)
+    }
+}
+
+impl fmt::Display for IpCidr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix_len)
+    }
+}
+
+impl Serialize for IpCidr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for IpCidr {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        value.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Whether an interface name matches a pattern. A trailing `*` matches any suffix, so
+/// `docker*` covers `docker0` and `br-*` covers every docker network bridge.
+pub fn interface_name_matches(pattern: &str, name: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => name.starts_with(prefix),
+        None => name == pattern,
+    }
+}
+
+/// Names of the local network interfaces matching any of `patterns`.
+pub fn matching_interface_names(patterns: &[String]) -> Vec<String> {
+    if patterns.is_empty() {
+        return Vec::new();
+    }
+    let networks = sysinfo::Networks::new_with_refreshed_list();
+    let mut names = networks
+        .list()
+        .keys()
+        .filter(|name| {
+            patterns
+                .iter()
+                .any(|pattern| interface_name_matches(pattern, name))
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+/// Addresses currently assigned to the local interfaces matching any of `patterns`.
+pub fn matching_interface_ips(patterns: &[String]) -> Vec<IpAddr> {
+    if patterns.is_empty() {
+        return Vec::new();
+    }
+    let networks = sysinfo::Networks::new_with_refreshed_list();
+    let mut ips = networks
+        .list()
+        .iter()
+        .filter(|(name, _)| {
+            patterns
+                .iter()
+                .any(|pattern| interface_name_matches(pattern, name))
+        })
+        .flat_map(|(_, network)| network.ip_networks().iter().map(|network| network.addr))
+        .collect::<Vec<_>>();
+    ips.sort();
+    ips.dedup();
+    ips
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cidr_contains_addresses_under_the_prefix() {
+        let cgnat = "100.64.0.0/10".parse::<IpCidr>().unwrap();
+        assert!(cgnat.contains(&"100.100.1.2".parse().unwrap()));
+        assert!(!cgnat.contains(&"100.128.0.1".parse().unwrap()));
+        assert!(!cgnat.contains(&"::1".parse().unwrap()));
+
+        let ula = "fd00::/8".parse::<IpCidr>().unwrap();
+        assert!(ula.contains(&"fd7a:115c:a1e0::1".parse().unwrap()));
+        assert!(!ula.contains(&"fe80::1".parse().unwrap()));
+
+        let host = "192.168.1.10".parse::<IpCidr>().unwrap();
+        assert_eq!(host.to_string(), "192.168.1.10/32");
+        assert!(!host.contains(&"192.168.1.11".parse().unwrap()));
+    }
+
+    #[test]
+    fn cidr_rejects_invalid_prefixes() {
+        assert!("10.0.0.0/33".parse::<IpCidr>().is_err());
+        assert!("10.0.0.0/x".parse::<IpCidr>().is_err());
+        assert!("docker0".parse::<IpCidr>().is_err());
+    }
+
+    #[test]
+    fn interface_patterns_support_trailing_wildcards() {
+        assert!(interface_name_matches("docker*", "docker0"));
+        assert!(interface_name_matches("br-*", "br-4f2a9c"));
+        assert!(interface_name_matches("tailscale0", "tailscale0"));
+        assert!(!interface_name_matches("tailscale0", "tailscale1"));
+        assert!(!interface_name_matches("docker*", "eth0"));
+    }
+}
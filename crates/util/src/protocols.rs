@@ -15,6 +15,12 @@ pub const FUNGI_NODE_CAPABILITIES_PROTOCOL: StreamProtocol =
     StreamProtocol::new("/fungi/node-capabilities/0.1.0");
 pub const FUNGI_SERVICE_CONTROL_PROTOCOL: StreamProtocol =
     StreamProtocol::new("/fungi/service-control/0.1.0");
+pub const FUNGI_SERVICE_EXEC_PROTOCOL: StreamProtocol =
+    StreamProtocol::new("/fungi/service-exec/0.1.0");
+pub const FUNGI_SERVICE_TRANSFER_PROTOCOL: StreamProtocol =
+    StreamProtocol::new("/fungi/service-transfer/0.1.0");
+pub const FUNGI_SERVICE_BACKUP_PROTOCOL: StreamProtocol =
+    StreamProtocol::new("/fungi/service-backup/0.1.0");
 
 pub const FUNGI_TUNNEL_PROTOCOL: &str = "/fungi/tunnel/0.1.0";
 pub const FUNGI_SERVICE_PORT_PROTOCOL_PREFIX: &str = "/fungi/service-port";
@@ -4,6 +4,7 @@ use fungi_daemon_grpc::{
     Request,
     fungi_daemon_grpc::{
         DeviceInfo, Empty, GetDeviceRequest, RemoveDeviceRequest, UpdateDeviceRequest,
+        WakeDeviceRequest,
     },
 };
 use libp2p::PeerId;
@@ -75,6 +76,17 @@ pub enum DeviceCommands {
         /// Device name or device ID to remove
         device: String,
     },
+    /// Wake a sleeping device with Wake-on-LAN
+    ///
+    /// The device must have been seen on the LAN while awake so its MAC address is known. When
+    /// this device is not on the same LAN, a connected trusted device on that LAN sends the packet.
+    Wake {
+        /// Device name or device ID to wake
+        device: String,
+        /// Wait until the device is reachable again
+        #[arg(long)]
+        wait: bool,
+    },
 }
 
 #[derive(Subcommand, Debug, Clone)]
@@ -300,6 +312,39 @@ pub async fn execute_device(args: CommonArgs, device_args: DeviceArgs) {
                 Err(e) => fatal_grpc(e),
             }
         }
+        DeviceCommands::Wake { device, wait } => {
+            let peer_id = match resolve_peer_value(&args, &device) {
+                Ok(peer) => peer.peer_id,
+                Err(error) => fatal(error),
+            };
+            let req = WakeDeviceRequest { peer_id, wait };
+            let resp = match client.wake_device(Request::new(req)).await {
+                Ok(resp) => resp.into_inner(),
+                Err(e) => fatal_grpc(e),
+            };
+            println!("MAC addresses: {}", resp.mac_addresses.join(", "));
+            for attempt in &resp.attempts {
+                let via = if attempt.relay_peer_id.is_empty() {
+                    "this device".to_string()
+                } else if attempt.relay_name.is_empty() {
+                    attempt.relay_peer_id.clone()
+                } else {
+                    format!("{} ({})", attempt.relay_name, attempt.relay_peer_id)
+                };
+                if attempt.error.is_empty() {
+                    println!("Sent wake packet via {via}");
+                } else {
+                    println!("Failed to send wake packet via {via}: {}", attempt.error);
+                }
+            }
+            if wait {
+                if resp.online {
+                    println!("Device is online");
+                } else {
+                    fatal("Device did not come online after the wake packet was sent")
+                }
+            }
+        }
     }
 }
 
@@ -369,6 +414,7 @@ fn new_minimal_device_info(peer_id: String, name: String, multiaddrs: Vec<String
         os: "Unknown".to_string(),
         public_ip: String::new(),
         private_ips: Vec::new(),
+        mac_addresses: Vec::new(),
         created_at: now,
         last_connected: now,
         version: String::new(),
@@ -424,6 +470,9 @@ fn print_device_info_detailed(device: &DeviceInfo) {
     if !device.private_ips.is_empty() {
         println!("Private IPs: {}", device.private_ips.join(", "));
     }
+    if !device.mac_addresses.is_empty() {
+        println!("MAC addresses: {}", device.mac_addresses.join(", "));
+    }
     if !device.multiaddrs.is_empty() {
         println!("Manual addresses:");
         for address in &device.multiaddrs {
@@ -0,0 +1,57 @@
+use fungi_daemon_grpc::{Request, fungi_daemon_grpc::DiagnoseRequest};
+
+use crate::commands::CommonArgs;
+
+use super::{
+    client::get_rpc_client,
+    shared::{PeerInput, fatal, fatal_grpc, print_target_peer, resolve_peer_input},
+};
+
+pub async fn execute_doctor(args: CommonArgs, peer: PeerInput, verbose: bool) {
+    let mut client = match get_rpc_client(&args).await {
+        Some(c) => c,
+        None => fatal("Cannot connect to Fungi daemon. Is it running?"),
+    };
+
+    let resolved = match resolve_peer_input(&args, &peer) {
+        Ok(peer) => peer,
+        Err(error) => fatal(error),
+    };
+    print_target_peer(&resolved);
+    println!("Running connectivity checks (this dials the device and may take a few seconds)...");
+
+    let req = DiagnoseRequest {
+        peer_id: resolved.peer_id.clone(),
+    };
+    let report = match client.diagnose(Request::new(req)).await {
+        Ok(resp) => resp.into_inner(),
+        Err(error) => fatal_grpc(error),
+    };
+
+    println!();
+    println!("{:<6} {:<20} SUMMARY", "STATUS", "CHECK");
+    for check in &report.checks {
+        println!(
+            "{:<6} {:<20} {}",
+            check.status.to_uppercase(),
+            check.name,
+            check.summary
+        );
+        if verbose || check.status == "fail" {
+            for detail in &check.details {
+                println!("{:<6} {:<20}   {}", "", "", detail);
+            }
+        }
+    }
+
+    println!();
+    println!("NAT:     {}", report.nat_type);
+    println!("Verdict: {}", report.verdict);
+    if !report.remediation.is_empty() {
+        println!();
+        println!("Suggestions:");
+        for hint in &report.remediation {
+            println!("  - {hint}");
+        }
+    }
+}
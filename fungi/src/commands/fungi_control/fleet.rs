@@ -0,0 +1,1105 @@
+use std::{collections::BTreeMap, path::Path};
+
+use clap::Subcommand;
+use fungi_config::devices::LOCAL_DEVICE_NAME;
+use fungi_daemon::{ServiceAccess, ServiceInstance, ServiceRevision, manifest_yaml_digest};
+use fungi_daemon_grpc::{
+    Request, Status,
+    fungi_daemon_grpc::{
+        AttachServiceAccessRequest, Empty, ForgetServiceAccessRequest, ListServiceAccessesRequest,
+        PullServiceRequest, RemoteAttachServiceAccessRequest, RemoteForgetServiceAccessRequest,
+        RemotePeerRequest, RemotePullServiceRequest, RemoteServiceNameRequest,
+        ResolveRecipeRequest, ServiceNameRequest,
+    },
+};
+use serde::Deserialize;
+
+use crate::commands::CommonArgs;
+
+use super::{
+    client::get_rpc_client,
+    service::{
+        CreatedServiceManifest, apply_manifest_inputs, apply_manifest_instance_name,
+        prompt_yes_no_default, pull_local_service, read_manifest_yaml_file,
+        refresh_remote_device_services,
+    },
+    shared::{fatal, fatal_grpc, resolve_peer_value, shorten_peer_id},
+};
+
+type RpcClient = fungi_daemon_grpc::fungi_daemon_grpc::fungi_daemon_client::FungiDaemonClient<
+    tonic::transport::Channel,
+>;
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum FleetCommands {
+    /// Converge devices to the services and accesses declared in a fleet file
+    Apply {
+        /// Fleet file (YAML) declaring services and accesses per device
+        #[arg(value_name = "FLEET_FILE")]
+        file: String,
+        /// Show the plan without changing anything
+        #[arg(long, default_value_t = false)]
+        dry_run: bool,
+        /// Also remove services and forget accesses the fleet file does not declare
+        #[arg(long, default_value_t = false)]
+        prune: bool,
+        /// Apply the plan without asking for confirmation
+        #[arg(short, long, default_value_t = false)]
+        yes: bool,
+    },
+}
+
+pub async fn execute_fleet(args: CommonArgs, cmd: FleetCommands) {
+    let FleetCommands::Apply {
+        file,
+        dry_run,
+        prune,
+        yes,
+    } = cmd;
+
+    let fleet = load_fleet_file(&file);
+    let mut client = match get_rpc_client(&args).await {
+        Some(c) => c,
+        None => fatal("Cannot connect to Fungi daemon. Is it running?"),
+    };
+    let local_peer_id = match client.peer_id(Request::new(Empty {})).await {
+        Ok(resp) => resp.into_inner().peer_id,
+        Err(error) => fatal_grpc(error),
+    };
+    let fleet_dir = Path::new(&file)
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+
+    let mut plans = Vec::new();
+    for (device_key, device) in &fleet.devices {
+        let target = resolve_fleet_device(&args, device_key, &local_peer_id);
+        let desired = resolve_desired_services(&mut client, &fleet_dir, &target, device).await;
+        let accesses = resolve_desired_accesses(&args, device_key, device);
+        plans.push(plan_device(&mut client, target, desired, accesses, prune).await);
+    }
+    // Local accesses may point at services created on other devices by this same run.
+    plans.sort_by_key(|plan| plan.target.local);
+
+    print_fleet_plan(&plans);
+    let pending = plans.iter().map(|plan| plan.changes.len()).sum::<usize>();
+    if dry_run {
+        return;
+    }
+    if pending == 0 {
+        if plans.iter().any(|plan| plan.error.is_some()) {
+            fatal("Some devices could not be planned");
+        }
+        println!("Fleet is up to date");
+        return;
+    }
+    if !yes && !prompt_yes_no_default(&format!("Apply {pending} change(s)? [Y/n]"), true) {
+        println!("Cancelled");
+        return;
+    }
+
+    println!();
+    let mut failed_devices = 0;
+    for plan in &plans {
+        if !converge_device(&mut client, plan).await {
+            failed_devices += 1;
+        }
+    }
+    println!();
+    if failed_devices > 0 {
+        fatal(format!(
+            "{failed_devices} of {} device(s) did not converge",
+            plans.len()
+        ));
+    }
+    println!("Fleet converged: {} device(s)", plans.len());
+}
+
+/// Desired state of a device fleet. Devices are keyed by device name, device ID or `local`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FleetFile {
+    #[serde(default)]
+    devices: BTreeMap<String, FleetDevice>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FleetDevice {
+    /// Services that should exist on the device, keyed by service name.
+    #[serde(default)]
+    services: BTreeMap<String, FleetService>,
+    /// Accesses the device attaches to services published by other devices.
+    #[serde(default)]
+    accesses: Vec<FleetAccess>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FleetService {
+    /// Service file, relative to the fleet file.
+    #[serde(default)]
+    manifest: Option<String>,
+    /// Recipe ID (`ID` or `SOURCE/ID`) to resolve instead of a service file.
+    #[serde(default)]
+    recipe: Option<String>,
+    #[serde(default)]
+    inputs: BTreeMap<String, serde_yaml::Value>,
+    #[serde(default = "default_running")]
+    running: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FleetAccess {
+    /// Device publishing the service.
+    device: String,
+    service: String,
+    #[serde(default)]
+    entry: Option<String>,
+    #[serde(default)]
+    local_port: Option<u16>,
+}
+
+fn default_running() -> bool {
+    true
+}
+
+#[derive(Debug, Clone)]
+struct FleetDeviceTarget {
+    /// Device key as written in the fleet file.
+    label: String,
+    peer_id: String,
+    local: bool,
+}
+
+#[derive(Debug, Clone)]
+struct DesiredService {
+    name: String,
+    manifest_yaml: String,
+    manifest_base_dir: String,
+    /// Digest of `manifest_yaml`, compared with the one recorded by the latest revision.
+    digest: String,
+    running: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DesiredAccess {
+    device_label: String,
+    peer_id: String,
+    service: String,
+    entry: Option<String>,
+    local_port: Option<u16>,
+}
+
+#[derive(Debug, Clone)]
+struct LiveService {
+    name: String,
+    /// Running, or for a job, scheduled.
+    active: bool,
+    manifest_digest: Option<String>,
+}
+
+#[derive(Debug)]
+enum FleetChange {
+    Create(DesiredService),
+    Update {
+        service: DesiredService,
+        active: bool,
+    },
+    Start(String),
+    Stop(String),
+    Remove(String),
+    /// Attaches `access`, replacing the `previous` access to the same service when set.
+    Attach {
+        access: DesiredAccess,
+        previous: Option<ServiceAccess>,
+    },
+    Forget {
+        peer_id: String,
+        service: String,
+    },
+}
+
+impl FleetChange {
+    fn describe(&self) -> String {
+        match self {
+            Self::Create(service) if service.running => {
+                format!("+ service {} (start)", service.name)
+            }
+            Self::Create(service) => format!("+ service {}", service.name),
+            Self::Update { service, .. } => format!("~ service {}: manifest changed", service.name),
+            Self::Start(name) => format!("~ service {name}: start"),
+            Self::Stop(name) => format!("~ service {name}: stop"),
+            Self::Remove(name) => format!("- service {name}"),
+            Self::Attach { access, previous } => {
+                let replace = previous.is_some();
+                let mut line = format!(
+                    "{} access {}@{}",
+                    if replace { "~" } else { "+" },
+                    access.service,
+                    access.device_label
+                );
+                if let Some(entry) = &access.entry {
+                    line.push_str(&format!(" entry={entry}"));
+                }
+                if let Some(port) = access.local_port {
+                    line.push_str(&format!(" port={port}"));
+                }
+                if replace {
+                    line.push_str(": reattach");
+                }
+                line
+            }
+            Self::Forget { peer_id, service } => {
+                format!("- access {service}@{}", shorten_peer_id(peer_id))
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+struct DevicePlan {
+    target: FleetDeviceTarget,
+    changes: Vec<FleetChange>,
+    notes: Vec<String>,
+    /// Why the live state of the device could not be read; nothing is applied then.
+    error: Option<String>,
+}
+
+fn load_fleet_file(path: &str) -> FleetFile {
+    let content = std::fs::read_to_string(path)
+        .unwrap_or_else(|error| fatal(format!("Failed to read fleet file {path}: {error}")));
+    parse_fleet_file(&content)
+        .unwrap_or_else(|error| fatal(format!("Failed to parse fleet file {path}: {error}")))
+}
+
+fn parse_fleet_file(content: &str) -> Result<FleetFile, String> {
+    let fleet: FleetFile = serde_yaml::from_str(content).map_err(|error| error.to_string())?;
+    for (device, entry) in &fleet.devices {
+        for (name, service) in &entry.services {
+            match (&service.manifest, &service.recipe) {
+                (Some(_), None) | (None, Some(_)) => {}
+                _ => {
+                    return Err(format!(
+                        "service '{name}' on device '{device}' needs exactly one of `manifest` or `recipe`"
+                    ));
+                }
+            }
+        }
+    }
+    Ok(fleet)
+}
+
+fn resolve_fleet_device(args: &CommonArgs, key: &str, local_peer_id: &str) -> FleetDeviceTarget {
+    let peer_id = if key.eq_ignore_ascii_case(LOCAL_DEVICE_NAME) || key == local_peer_id {
+        local_peer_id.to_string()
+    } else {
+        match resolve_peer_value(args, key) {
+            Ok(device) => device.peer_id,
+            Err(error) => fatal(error),
+        }
+    };
+    FleetDeviceTarget {
+        label: key.to_string(),
+        local: peer_id == local_peer_id,
+        peer_id,
+    }
+}
+
+/// Reads or resolves the manifest of every service declared for `target`, with inputs and the
+/// declared service name applied, exactly as it will be pulled.
+async fn resolve_desired_services(
+    client: &mut RpcClient,
+    fleet_dir: &Path,
+    target: &FleetDeviceTarget,
+    device: &FleetDevice,
+) -> Vec<DesiredService> {
+    let mut desired = Vec::new();
+    for (name, service) in &device.services {
+        let inputs = fleet_input_values(name, &service.inputs).unwrap_or_else(|error| fatal(error));
+        let created = match (&service.manifest, &service.recipe) {
+            (Some(manifest), _) => {
+                let path = fleet_dir.join(manifest);
+                let mut created = read_manifest_yaml_file(&path.to_string_lossy());
+                if !inputs.is_empty() {
+                    apply_manifest_inputs(&mut created, &inputs);
+                }
+                apply_manifest_instance_name(&mut created, name);
+                created
+            }
+            (None, Some(recipe_id)) => {
+                let req = ResolveRecipeRequest {
+                    recipe_id: recipe_id.clone(),
+                    service_name: name.clone(),
+                    peer_id: if target.local {
+                        String::new()
+                    } else {
+                        target.peer_id.clone()
+                    },
+                    refresh: false,
+                    inputs: inputs.into_iter().collect(),
+                };
+                let resolved = match client.resolve_recipe(Request::new(req)).await {
+                    Ok(resp) => resp.into_inner(),
+                    Err(error) => fatal(format!(
+                        "Failed to resolve recipe '{recipe_id}' for service '{name}': {}",
+                        error.message()
+                    )),
+                };
+                CreatedServiceManifest {
+                    manifest_yaml: resolved.manifest_yaml,
+                    manifest_base_dir: resolved.manifest_base_dir,
+                    start_now: false,
+                }
+            }
+            (None, None) => unreachable!("validated when the fleet file was parsed"),
+        };
+        desired.push(DesiredService {
+            name: name.clone(),
+            digest: manifest_yaml_digest(&created.manifest_yaml),
+            manifest_yaml: created.manifest_yaml,
+            manifest_base_dir: created.manifest_base_dir,
+            running: service.running,
+        });
+    }
+    desired
+}
+
+fn fleet_input_values(
+    service: &str,
+    inputs: &BTreeMap<String, serde_yaml::Value>,
+) -> Result<BTreeMap<String, String>, String> {
+    inputs
+        .iter()
+        .map(|(name, value)| {
+            let value = match value {
+                serde_yaml::Value::String(value) => value.clone(),
+                serde_yaml::Value::Bool(value) => value.to_string(),
+                serde_yaml::Value::Number(value) => value.to_string(),
+                _ => {
+                    return Err(format!(
+                        "input '{name}' of service '{service}' must be a scalar value"
+                    ));
+                }
+            };
+            Ok((name.clone(), value))
+        })
+        .collect()
+}
+
+fn resolve_desired_accesses(
+    args: &CommonArgs,
+    device_key: &str,
+    device: &FleetDevice,
+) -> Vec<DesiredAccess> {
+    device
+        .accesses
+        .iter()
+        .map(|access| {
+            if access.device.eq_ignore_ascii_case(LOCAL_DEVICE_NAME) {
+                fatal(format!(
+                    "access to '{}' on device '{device_key}' must name the device publishing it",
+                    access.service
+                ));
+            }
+            let peer_id = match resolve_peer_value(args, &access.device) {
+                Ok(device) => device.peer_id,
+                Err(error) => fatal(error),
+            };
+            DesiredAccess {
+                device_label: access.device.clone(),
+                peer_id,
+                service: access.service.clone(),
+                entry: access.entry.clone(),
+                local_port: access.local_port,
+            }
+        })
+        .collect()
+}
+
+async fn plan_device(
+    client: &mut RpcClient,
+    target: FleetDeviceTarget,
+    desired: Vec<DesiredService>,
+    accesses: Vec<DesiredAccess>,
+    prune: bool,
+) -> DevicePlan {
+    let live = match list_live_services(client, &target, &desired).await {
+        Ok(live) => live,
+        Err(error) => {
+            return DevicePlan {
+                target,
+                changes: Vec::new(),
+                notes: Vec::new(),
+                error: Some(error.message().to_string()),
+            };
+        }
+    };
+    let live_accesses = match list_live_accesses(client, &target).await {
+        Ok(live_accesses) => live_accesses,
+        Err(error) => {
+            return DevicePlan {
+                target,
+                changes: Vec::new(),
+                notes: Vec::new(),
+                error: Some(error.message().to_string()),
+            };
+        }
+    };
+    let (mut changes, mut notes) = plan_services(&desired, &live, prune);
+    let (access_changes, access_notes) = plan_accesses(&accesses, &live_accesses, prune);
+    changes.extend(access_changes);
+    notes.extend(access_notes);
+
+    DevicePlan {
+        target,
+        changes,
+        notes,
+        error: None,
+    }
+}
+
+/// Lists the service accesses saved on `target` itself.
+async fn list_live_accesses(
+    client: &mut RpcClient,
+    target: &FleetDeviceTarget,
+) -> Result<Vec<ServiceAccess>, Status> {
+    let service_accesses_json = if target.local {
+        let req = ListServiceAccessesRequest {
+            peer_id: String::new(),
+        };
+        client
+            .list_service_accesses(Request::new(req))
+            .await?
+            .into_inner()
+            .service_accesses_json
+    } else {
+        let req = RemotePeerRequest {
+            peer_id: target.peer_id.clone(),
+        };
+        client
+            .remote_list_service_accesses(Request::new(req))
+            .await?
+            .into_inner()
+            .service_accesses_json
+    };
+    serde_json::from_str(&service_accesses_json)
+        .map_err(|error| Status::internal(format!("Failed to decode access list: {error}")))
+}
+
+/// Lists the services on `target`, with the manifest digest of the latest revision of each
+/// service the fleet file declares.
+async fn list_live_services(
+    client: &mut RpcClient,
+    target: &FleetDeviceTarget,
+    desired: &[DesiredService],
+) -> Result<Vec<LiveService>, Status> {
+    let services_json = if target.local {
+        client
+            .list_services(Request::new(Empty {}))
+            .await?
+            .into_inner()
+            .services_json
+    } else {
+        let req = RemotePeerRequest {
+            peer_id: target.peer_id.clone(),
+        };
+        client
+            .remote_list_services(Request::new(req))
+            .await?
+            .into_inner()
+            .services_json
+    };
+    let instances = serde_json::from_str::<Vec<ServiceInstance>>(&services_json)
+        .map_err(|error| Status::internal(format!("Failed to decode service list: {error}")))?;
+
+    let mut live = Vec::with_capacity(instances.len());
+    for instance in instances {
+        let manifest_digest = if desired.iter().any(|service| service.name == instance.name) {
+            latest_manifest_digest(client, target, &instance.name).await?
+        } else {
+            None
+        };
+        live.push(LiveService {
+            active: instance
+                .job
+                .as_ref()
+                .map_or(instance.status.is_running(), |job| job.enabled),
+            name: instance.name,
+            manifest_digest,
+        });
+    }
+    Ok(live)
+}
+
+async fn latest_manifest_digest(
+    client: &mut RpcClient,
+    target: &FleetDeviceTarget,
+    name: &str,
+) -> Result<Option<String>, Status> {
+    let revisions_json = if target.local {
+        let req = ServiceNameRequest {
+            runtime: 0,
+            name: name.to_string(),
+        };
+        client
+            .service_history(Request::new(req))
+            .await?
+            .into_inner()
+            .revisions_json
+    } else {
+        let req = RemoteServiceNameRequest {
+            peer_id: target.peer_id.clone(),
+            name: name.to_string(),
+        };
+        client
+            .remote_service_history(Request::new(req))
+            .await?
+            .into_inner()
+            .revisions_json
+    };
+    let revisions = serde_json::from_str::<Vec<ServiceRevision>>(&revisions_json)
+        .map_err(|error| Status::internal(format!("Failed to decode revisions: {error}")))?;
+    Ok(revisions
+        .into_iter()
+        .last()
+        .and_then(|revision| revision.manifest_digest))
+}
+
+/// Services are updated when the latest revision was not applied from the desired manifest,
+/// which includes services applied before revisions recorded a manifest digest.
+fn plan_services(
+    desired: &[DesiredService],
+    live: &[LiveService],
+    prune: bool,
+) -> (Vec<FleetChange>, Vec<String>) {
+    let mut changes = Vec::new();
+    for service in desired {
+        match live.iter().find(|current| current.name == service.name) {
+            None => changes.push(FleetChange::Create(service.clone())),
+            Some(current) if current.manifest_digest.as_deref() != Some(&service.digest) => {
+                changes.push(FleetChange::Update {
+                    service: service.clone(),
+                    active: current.active,
+                });
+            }
+            Some(current) if service.running && !current.active => {
+                changes.push(FleetChange::Start(service.name.clone()));
+            }
+            Some(current) if !service.running && current.active => {
+                changes.push(FleetChange::Stop(service.name.clone()));
+            }
+            Some(_) => {}
+        }
+    }
+
+    let mut notes = Vec::new();
+    for current in live {
+        if desired.iter().any(|service| service.name == current.name) {
+            continue;
+        }
+        if prune {
+            changes.push(FleetChange::Remove(current.name.clone()));
+        } else {
+            notes.push(format!(
+                "service {} is not in the fleet file; --prune removes it",
+                current.name
+            ));
+        }
+    }
+    (changes, notes)
+}
+
+fn plan_accesses(
+    desired: &[DesiredAccess],
+    live: &[ServiceAccess],
+    prune: bool,
+) -> (Vec<FleetChange>, Vec<String>) {
+    let mut changes = Vec::new();
+    for access in desired {
+        let current = live.iter().find(|current| {
+            current.peer_id == access.peer_id && current.service_name == access.service
+        });
+        match current {
+            None => changes.push(FleetChange::Attach {
+                access: access.clone(),
+                previous: None,
+            }),
+            Some(current) if !access_matches(access, current) => {
+                changes.push(FleetChange::Attach {
+                    access: access.clone(),
+                    previous: Some(current.clone()),
+                });
+            }
+            Some(_) => {}
+        }
+    }
+
+    let mut notes = Vec::new();
+    for current in live {
+        if desired.iter().any(|access| {
+            access.peer_id == current.peer_id && access.service == current.service_name
+        }) {
+            continue;
+        }
+        if prune {
+            changes.push(FleetChange::Forget {
+                peer_id: current.peer_id.clone(),
+                service: current.service_name.clone(),
+            });
+        } else {
+            notes.push(format!(
+                "access {}@{} is not in the fleet file; --prune forgets it",
+                current.service_name,
+                shorten_peer_id(&current.peer_id)
+            ));
+        }
+    }
+    (changes, notes)
+}
+
+fn access_matches(access: &DesiredAccess, current: &ServiceAccess) -> bool {
+    let endpoint = match &access.entry {
+        Some(entry) => current
+            .endpoints
+            .iter()
+            .find(|endpoint| &endpoint.name == entry),
+        None => current.endpoints.first(),
+    };
+    endpoint.is_some_and(|endpoint| {
+        access
+            .local_port
+            .is_none_or(|port| endpoint.local_port == port)
+    })
+}
+
+fn print_fleet_plan(plans: &[DevicePlan]) {
+    for plan in plans {
+        println!("{}", device_heading(&plan.target));
+        if let Some(error) = &plan.error {
+            println!("  ! unreachable: {error}");
+        } else if plan.changes.is_empty() {
+            println!("  up to date");
+        }
+        for change in &plan.changes {
+            println!("  {}", change.describe());
+        }
+        for note in &plan.notes {
+            println!("  note: {note}");
+        }
+    }
+}
+
+fn device_heading(target: &FleetDeviceTarget) -> String {
+    if target.local {
+        format!("{} (this device)", target.label)
+    } else {
+        format!("{} ({})", target.label, shorten_peer_id(&target.peer_id))
+    }
+}
+
+/// Applies the changes planned for one device, continuing past failed changes. Returns whether
+/// every change was applied.
+async fn converge_device(client: &mut RpcClient, plan: &DevicePlan) -> bool {
+    println!("{}", device_heading(&plan.target));
+    if let Some(error) = &plan.error {
+        println!("  failed: device unreachable: {error}");
+        return false;
+    }
+    if plan.changes.is_empty() {
+        println!("  ok: up to date");
+        return true;
+    }
+
+    let mut failed = 0;
+    for change in &plan.changes {
+        match apply_change(client, &plan.target, change).await {
+            Ok(()) => println!("  {} ... done", change.describe()),
+            Err(error) => {
+                failed += 1;
+                println!("  {} ... failed: {}", change.describe(), error.message());
+            }
+        }
+    }
+    if !plan.target.local {
+        refresh_remote_device_services(client, &plan.target.peer_id).await;
+    }
+
+    if failed == 0 {
+        println!("  ok: {} change(s) applied", plan.changes.len());
+    } else {
+        println!(
+            "  failed: {failed} of {} change(s) failed",
+            plan.changes.len()
+        );
+    }
+    failed == 0
+}
+
+async fn apply_change(
+    client: &mut RpcClient,
+    target: &FleetDeviceTarget,
+    change: &FleetChange,
+) -> Result<(), Status> {
+    match change {
+        FleetChange::Create(service) => {
+            pull_service(client, target, service).await?;
+            if service.running {
+                start_service(client, target, &service.name).await?;
+            }
+            Ok(())
+        }
+        FleetChange::Update { service, active } => {
+            // Pulling keeps the service running if it was, so only a state mismatch is left.
+            pull_service(client, target, service).await?;
+            match (service.running, *active) {
+                (true, false) => start_service(client, target, &service.name).await,
+                (false, true) => stop_service(client, target, &service.name).await,
+                _ => Ok(()),
+            }
+        }
+        FleetChange::Start(name) => start_service(client, target, name).await,
+        FleetChange::Stop(name) => stop_service(client, target, name).await,
+        FleetChange::Remove(name) => {
+            if target.local {
+                let req = ServiceNameRequest {
+                    runtime: 0,
+                    name: name.clone(),
+                };
+                client.remove_service(Request::new(req)).await?;
+            } else {
+                client
+                    .remote_remove_service(Request::new(remote_name_request(target, name)))
+                    .await?;
+            }
+            Ok(())
+        }
+        FleetChange::Attach {
+            access,
+            previous: None,
+        } => attach_access(client, target, access).await,
+        FleetChange::Attach {
+            access,
+            previous: Some(previous),
+        } => {
+            // Forgetting drops entries the fleet file no longer names; the previous access is
+            // attached again when the new one fails, so a working access is not lost.
+            forget_access(client, target, &access.peer_id, &access.service).await?;
+            let Err(error) = attach_access(client, target, access).await else {
+                return Ok(());
+            };
+            for endpoint in &previous.endpoints {
+                let restored = DesiredAccess {
+                    entry: Some(endpoint.name.clone()),
+                    local_port: Some(endpoint.local_port),
+                    ..access.clone()
+                };
+                if let Err(restore_error) = attach_access(client, target, &restored).await {
+                    return Err(Status::new(
+                        error.code(),
+                        format!(
+                            "{}; restoring the previous access also failed: {}",
+                            error.message(),
+                            restore_error.message()
+                        ),
+                    ));
+                }
+            }
+            Err(error)
+        }
+        FleetChange::Forget { peer_id, service } => {
+            forget_access(client, target, peer_id, service).await
+        }
+    }
+}
+
+async fn pull_service(
+    client: &mut RpcClient,
+    target: &FleetDeviceTarget,
+    service: &DesiredService,
+) -> Result<(), Status> {
+    if target.local {
+        let req = PullServiceRequest {
+            manifest_yaml: service.manifest_yaml.clone(),
+            manifest_base_dir: service.manifest_base_dir.clone(),
+        };
+        pull_local_service(client, req).await?;
+    } else {
+        let req = RemotePullServiceRequest {
+            peer_id: target.peer_id.clone(),
+            manifest_yaml: service.manifest_yaml.clone(),
+        };
+        client.remote_pull_service(Request::new(req)).await?;
+    }
+    Ok(())
+}
+
+async fn start_service(
+    client: &mut RpcClient,
+    target: &FleetDeviceTarget,
+    name: &str,
+) -> Result<(), Status> {
+    if target.local {
+        let req = ServiceNameRequest {
+            runtime: 0,
+            name: name.to_string(),
+        };
+        client.start_service(Request::new(req)).await?;
+    } else {
+        client
+            .remote_start_service(Request::new(remote_name_request(target, name)))
+            .await?;
+    }
+    Ok(())
+}
+
+async fn stop_service(
+    client: &mut RpcClient,
+    target: &FleetDeviceTarget,
+    name: &str,
+) -> Result<(), Status> {
+    if target.local {
+        let req = ServiceNameRequest {
+            runtime: 0,
+            name: name.to_string(),
+        };
+        client.stop_service(Request::new(req)).await?;
+    } else {
+        client
+            .remote_stop_service(Request::new(remote_name_request(target, name)))
+            .await?;
+    }
+    Ok(())
+}
+
+/// Attaches `access` on `target` itself, which then reaches the publishing device directly.
+async fn attach_access(
+    client: &mut RpcClient,
+    target: &FleetDeviceTarget,
+    access: &DesiredAccess,
+) -> Result<(), Status> {
+    let entry = access.entry.clone().unwrap_or_default();
+    let local_port = access.local_port.unwrap_or_default() as i32;
+    if target.local {
+        let req = AttachServiceAccessRequest {
+            peer_id: access.peer_id.clone(),
+            service_name: access.service.clone(),
+            entry,
+            local_port,
+            wake: false,
+            wake_timeout_secs: 0,
+        };
+        client.attach_service_access(Request::new(req)).await?;
+    } else {
+        let req = RemoteAttachServiceAccessRequest {
+            peer_id: target.peer_id.clone(),
+            publisher_peer_id: access.peer_id.clone(),
+            service_name: access.service.clone(),
+            entry,
+            local_port,
+        };
+        client
+            .remote_attach_service_access(Request::new(req))
+            .await?;
+    }
+    Ok(())
+}
+
+async fn forget_access(
+    client: &mut RpcClient,
+    target: &FleetDeviceTarget,
+    peer_id: &str,
+    service: &str,
+) -> Result<(), Status> {
+    if target.local {
+        let req = ForgetServiceAccessRequest {
+            peer_id: peer_id.to_string(),
+            service_name: service.to_string(),
+        };
+        client.forget_service_access(Request::new(req)).await?;
+    } else {
+        let req = RemoteForgetServiceAccessRequest {
+            peer_id: target.peer_id.clone(),
+            publisher_peer_id: peer_id.to_string(),
+            service_name: service.to_string(),
+        };
+        client
+            .remote_forget_service_access(Request::new(req))
+            .await?;
+    }
+    Ok(())
+}
+
+fn remote_name_request(target: &FleetDeviceTarget, name: &str) -> RemoteServiceNameRequest {
+    RemoteServiceNameRequest {
+        peer_id: target.peer_id.clone(),
+        name: name.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fungi_daemon::ServiceAccessEndpoint;
+
+    fn desired(name: &str, digest: &str, running: bool) -> DesiredService {
+        DesiredService {
+            name: name.to_string(),
+            manifest_yaml: String::new(),
+            manifest_base_dir: String::new(),
+            digest: digest.to_string(),
+            running,
+        }
+    }
+
+    fn live(name: &str, digest: Option<&str>, active: bool) -> LiveService {
+        LiveService {
+            name: name.to_string(),
+            active,
+            manifest_digest: digest.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn parses_fleet_file_and_rejects_ambiguous_services() {
+        let fleet = parse_fleet_file(
+            r#"
+devices:
+  local:
+    services:
+      notes:
+        manifest: services/notes.fungi.md
+        inputs:
+          port: 8080
+          debug: true
+    accesses:
+      - device: nas
+        service: media
+        entry: web
+        local_port: 18096
+  nas:
+    services:
+      media:
+        recipe: jellyfin
+        running: false
+"#,
+        )
+        .unwrap();
+
+        let local = &fleet.devices["local"];
+        let notes = &local.services["notes"];
+        assert!(notes.running);
+        assert_eq!(
+            fleet_input_values("notes", &notes.inputs).unwrap(),
+            BTreeMap::from([
+                ("debug".to_string(), "true".to_string()),
+                ("port".to_string(), "8080".to_string()),
+            ])
+        );
+        assert_eq!(local.accesses[0].local_port, Some(18096));
+        assert!(!fleet.devices["nas"].services["media"].running);
+
+        let error = parse_fleet_file(
+            "devices:\n  nas:\n    services:\n      media:\n        recipe: jellyfin\n        manifest: media.fungi.md\n",
+        )
+        .unwrap_err();
+        assert!(error.contains("exactly one of"));
+        assert!(parse_fleet_file("devices:\n  nas:\n    service: {}\n").is_err());
+    }
+
+    #[test]
+    fn plans_only_the_changes_needed_to_converge() {
+        let desired = vec![
+            desired("new", "a", true),
+            desired("changed", "b", true),
+            desired("stopped", "c", true),
+            desired("idle", "d", false),
+            desired("same", "e", true),
+        ];
+        let live = vec![
+            live("changed", Some("old"), true),
+            live("stopped", Some("c"), false),
+            live("idle", Some("d"), true),
+            live("same", Some("e"), true),
+            live("extra", None, true),
+        ];
+
+        let (changes, notes) = plan_services(&desired, &live, false);
+        let described = changes
+            .iter()
+            .map(FleetChange::describe)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            described,
+            vec![
+                "+ service new (start)",
+                "~ service changed: manifest changed",
+                "~ service stopped: start",
+                "~ service idle: stop",
+            ]
+        );
+        assert_eq!(notes.len(), 1);
+        assert!(notes[0].contains("extra"));
+
+        let (changes, notes) = plan_services(&desired[4..], &live[3..], true);
+        assert!(matches!(&changes[..], [FleetChange::Remove(name)] if name == "extra"));
+        assert!(notes.is_empty());
+    }
+
+    #[test]
+    fn plans_access_attach_reattach_and_forget() {
+        let access = |service: &str, local_port: Option<u16>| DesiredAccess {
+            device_label: "nas".to_string(),
+            peer_id: "peer".to_string(),
+            service: service.to_string(),
+            entry: Some("web".to_string()),
+            local_port,
+        };
+        let current = |service: &str, local_port: u16| ServiceAccess {
+            peer_id: "peer".to_string(),
+            service_name: service.to_string(),
+            endpoints: vec![ServiceAccessEndpoint {
+                name: "web".to_string(),
+                protocol: String::new(),
+                local_host: "127.0.0.1".to_string(),
+                local_port,
+                bandwidth_limit: Default::default(),
+            }],
+            group: Vec::new(),
+            routing: Default::default(),
+        };
+
+        let desired = vec![
+            access("media", Some(18096)),
+            access("notes", None),
+            access("wiki", Some(18080)),
+        ];
+        let live = vec![
+            current("media", 18096),
+            current("wiki", 18081),
+            current("old", 18000),
+        ];
+        let (changes, _) = plan_accesses(&desired, &live, true);
+        let described = changes
+            .iter()
+            .map(FleetChange::describe)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            described,
+            vec![
+                "+ access notes@nas entry=web",
+                "~ access wiki@nas entry=web port=18080: reattach",
+                "- access old@peer",
+            ]
+        );
+        let FleetChange::Attach {
+            previous: Some(previous),
+            ..
+        } = &changes[1]
+        else {
+            panic!("expected the wiki access to be reattached");
+        };
+        assert_eq!(previous.endpoints[0].local_port, 18081);
+    }
+}
@@ -121,6 +121,7 @@ fn print_build_info(json_output: bool) {
 fn print_runtime_status(status: &fungi_daemon_grpc::fungi_daemon_grpc::LocalRuntimeStatusResponse) {
     print_runtime_entry("docker", status.docker.as_ref());
     print_runtime_entry("wasmtime", status.wasmtime.as_ref());
+    print_runtime_entry("process", status.process.as_ref());
 }
 
 fn print_runtime_entry(
@@ -139,4 +140,8 @@ fn print_runtime_entry(
     if !status.endpoint.is_empty() {
         println!("  endpoint: {}", status.endpoint);
     }
+    if !status.engine.is_empty() {
+        println!("  engine: {} {}", status.engine, status.engine_version);
+        println!("  rootless: {}", status.rootless);
+    }
 }
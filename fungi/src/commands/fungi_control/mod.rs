@@ -1,21 +1,28 @@
 mod client;
 mod connection;
 mod device;
+mod doctor;
+mod fleet;
 mod info;
 mod peer;
 mod ping;
 mod relay_config;
+mod secret;
 mod security;
 mod service;
+mod service_exec;
 mod shared;
 mod trusted_devices;
 
 pub use connection::{ConnectionCommands, execute_connection};
 pub use device::{DeviceAddressCommands, DeviceArgs, DeviceCommands, execute_device};
+pub use doctor::execute_doctor;
+pub use fleet::{FleetCommands, execute_fleet};
 pub use info::{InfoCommands, execute_info};
 pub use peer::{PeerCommands, execute_peer};
 pub use ping::execute_ping;
 pub use relay_config::{RelayCommands, execute_relay};
+pub use secret::{SecretCommands, execute_secret};
 pub use security::{SecurityCommands, execute_security};
 pub use service::{
     DynamicServiceInvocation, DynamicServiceTarget, ServiceArgs, ServiceCommands,
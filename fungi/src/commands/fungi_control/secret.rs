@@ -0,0 +1,92 @@
+use std::io::{self, BufRead, IsTerminal, Write};
+
+use clap::Subcommand;
+use fungi_daemon_grpc::{
+    Request,
+    fungi_daemon_grpc::{Empty, SecretNameRequest, SetSecretRequest},
+};
+
+use crate::commands::CommonArgs;
+
+use super::{
+    client::get_rpc_client,
+    shared::{fatal, fatal_grpc},
+};
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum SecretCommands {
+    /// Store an encrypted secret, referenced from service env as ${secret:NAME}
+    Set {
+        /// Secret name (letters, digits, '_', '-' and '.')
+        name: String,
+        /// Secret value; read from stdin when omitted so it stays out of shell history
+        #[arg(long)]
+        value: Option<String>,
+    },
+    /// List stored secret names
+    #[command(visible_alias = "ls")]
+    List,
+    /// Remove a stored secret
+    #[command(visible_alias = "remove")]
+    Rm {
+        /// Secret name
+        name: String,
+    },
+}
+
+pub async fn execute_secret(args: CommonArgs, cmd: SecretCommands) {
+    let mut client = match get_rpc_client(&args).await {
+        Some(c) => c,
+        None => fatal("Cannot connect to Fungi daemon. Is it running?"),
+    };
+
+    match cmd {
+        SecretCommands::Set { name, value } => {
+            let value = match value {
+                Some(value) => value,
+                None => match read_secret_value(&name) {
+                    Ok(value) => value,
+                    Err(error) => fatal(format!("Failed to read secret value: {error}")),
+                },
+            };
+            let req = SetSecretRequest { name, value };
+            match client.set_secret(Request::new(req)).await {
+                Ok(resp) => println!("Secret '{}' saved", resp.into_inner().name),
+                Err(e) => fatal_grpc(e),
+            }
+        }
+        SecretCommands::List => match client.list_secrets(Request::new(Empty {})).await {
+            Ok(resp) => {
+                let secrets = resp.into_inner().secrets;
+                if secrets.is_empty() {
+                    println!("No secrets");
+                    return;
+                }
+                println!("{:<32} UPDATED", "NAME");
+                for secret in secrets {
+                    println!("{:<32} {}", secret.name, secret.updated_at);
+                }
+            }
+            Err(e) => fatal_grpc(e),
+        },
+        SecretCommands::Rm { name } => {
+            let req = SecretNameRequest { name: name.clone() };
+            match client.remove_secret(Request::new(req)).await {
+                Ok(resp) if resp.get_ref().removed => println!("Secret '{name}' removed"),
+                Ok(_) => fatal(format!("Secret '{name}' not found")),
+                Err(e) => fatal_grpc(e),
+            }
+        }
+    }
+}
+
+fn read_secret_value(name: &str) -> io::Result<String> {
+    let stdin = io::stdin();
+    if stdin.is_terminal() {
+        print!("Value for secret '{name}': ");
+        io::stdout().flush()?;
+    }
+    let mut value = String::new();
+    stdin.lock().read_line(&mut value)?;
+    Ok(value.trim_end_matches(['\r', '\n']).to_string())
+}
@@ -39,6 +39,7 @@ pub async fn execute_security(args: CommonArgs, cmd: SecurityCommands) {
                 let config = resp.into_inner();
                 println!("disable_docker: {}", config.disable_docker);
                 println!("disable_wasmtime: {}", config.disable_wasmtime);
+                println!("disable_process: {}", config.disable_process);
                 println!("allowed_host_paths:");
                 for path in config.allowed_host_paths {
                     println!("  {}", path);
@@ -1,23 +1,33 @@
-use std::io::{self, Write};
 #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
 use std::process::Command;
+use std::{
+    collections::BTreeMap,
+    io::{self, Write},
+};
 
 use clap::{Args, Subcommand};
 use fungi_config::{FungiDir, devices::LOCAL_DEVICE_NAME, paths::FungiPaths};
 use fungi_daemon::{
     DeviceService, DeviceServiceSnapshot, RuntimeKind, ServiceAccess, ServiceExposeUsageKind,
-    ServiceInstance, ServicePhase, ServicePortProtocol, ServiceStatus, parse_service_manifest_yaml,
+    ServiceInstance, ServiceJobOutcome, ServiceJobRun, ServiceJobStatus, ServicePhase,
+    ServicePortProtocol, ServiceResourceUsage, ServiceRevision, ServiceStatus,
+    parse_service_input_values_yaml, parse_service_manifest_yaml, service_manifest_with_inputs,
     service_manifest_with_instance_name,
 };
 use fungi_daemon_grpc::{
-    Request,
+    Request, Status,
     fungi_daemon_grpc::{
-        AttachServiceAccessRequest, DetachServiceAccessRequest, DeviceInfo,
-        DeviceServiceSnapshotRequest, Empty, GetRecipeRequest, GetServiceLogsRequest,
-        ListRecipesRequest, ListRecipesResponse, ListServiceAccessesRequest, ListServicesResponse,
-        PullServiceRequest, RecipeDetail, RecipeRuntimeKind, RecipeSummary,
-        RemotePullServiceRequest, RemoteServiceControlResponse, RemoteServiceNameRequest,
-        ResolveRecipeRequest, ServiceInstanceResponse, ServiceNameRequest,
+        AttachServiceAccessRequest, BackupServiceRequest, BandwidthLimit,
+        DetachServiceAccessRequest, DeviceInfo, DeviceServiceSnapshotRequest, Empty,
+        GetRecipeRequest, GetServiceLogsRequest, HttpGatewayService, ListRecipesRequest,
+        ListRecipesResponse, ListServiceAccessesRequest, ListServiceBackupsRequest,
+        ListServicesResponse, MoveServiceRequest, PruneServiceImagesRequest,
+        PruneServiceImagesResponse, PullServiceRequest, RecipeDetail, RecipeRuntimeKind,
+        RecipeSummary, RemotePullServiceRequest, RemoteRollbackServiceRequest,
+        RemoteServiceControlResponse, RemoteServiceNameRequest, ResolveRecipeRequest,
+        RestoreServiceBackupRequest, RollbackServiceRequest, ServiceBackupInfo,
+        ServiceInstanceResponse, ServiceNameRequest, SetServiceAccessBandwidthLimitRequest,
+        SetServiceAccessGroupRequest, SetServicePortBandwidthLimitRequest, pull_service_event,
     },
 };
 use serde::Serialize;
@@ -26,9 +36,10 @@ use crate::commands::CommonArgs;
 
 use super::{
     client::get_rpc_client,
+    service_exec::run_service_exec,
     shared::{
         DeviceInput, OptionalDeviceTargetArg, fatal, fatal_grpc, print_target_device,
-        resolve_optional_device,
+        resolve_optional_device, shorten_peer_id,
     },
 };
 
@@ -69,13 +80,19 @@ pub enum ServiceCommands {
         /// Path to a .fungi.md service file
         #[arg(value_name = "SERVICE_FILE", conflicts_with_all = ["recipe", "create"])]
         manifest: Option<String>,
-        /// Apply a service from an official recipe ID instead of a local file
+        /// Apply a service from a recipe (`ID` or `SOURCE/ID`) instead of a local file
         #[arg(long, conflicts_with_all = ["manifest", "create"])]
         recipe: Option<String>,
         /// Create a simple service interactively
         #[arg(long, conflicts_with_all = ["manifest", "recipe"], default_value_t = false)]
         create: bool,
-        /// Refresh the official recipe index before resolving the recipe
+        /// Set a declared service input; repeat for multiple inputs
+        #[arg(long = "set", value_name = "NAME=VALUE", conflicts_with = "create")]
+        set: Vec<String>,
+        /// YAML file of input values; --set entries take precedence
+        #[arg(long = "values", value_name = "VALUES_FILE", conflicts_with = "create")]
+        values_file: Option<String>,
+        /// Refresh recipe source indexes before resolving the recipe
         #[arg(long, default_value_t = false)]
         refresh: bool,
         /// Preview parsing, validation, and runtime intent without changing state
@@ -88,7 +105,7 @@ pub enum ServiceCommands {
         #[arg(long, default_value_t = false)]
         yes: bool,
     },
-    /// Inspect official service recipes managed by the local daemon
+    /// Inspect service recipes from the configured recipe sources
     Recipe {
         #[command(subcommand)]
         command: ServiceRecipeCommands,
@@ -106,9 +123,45 @@ pub enum ServiceCommands {
         /// Pin or move the local forwarding port for this service entry
         #[arg(long)]
         local_port: Option<u16>,
+        /// Wake the device with Wake-on-LAN first if it is asleep
+        #[arg(long)]
+        wake: bool,
+        /// Seconds to wait for a woken device to come online (default 30, at most 300)
+        #[arg(long, value_name = "SECS", requires = "wake")]
+        wake_timeout: Option<u32>,
     },
     /// Disconnect a remote service's local listener while keeping its saved port
     Disconnect { service: String },
+    /// Limit the bandwidth of a service's forwarded traffic
+    ///
+    /// For a service on another device this limits the local connection; for a local service it
+    /// limits what the published entry serves to other devices. Rates are bytes per second per
+    /// direction with optional K/M/G suffixes; an omitted rate or 0 leaves that path unlimited.
+    Limit {
+        service: String,
+        entry: Option<String>,
+        /// Limit for direct connections, e.g. 512K or 4M
+        #[arg(long, value_parser = parse_bandwidth_rate)]
+        direct: Option<u64>,
+        /// Limit for connections through a relay, e.g. 256K
+        #[arg(long, value_parser = parse_bandwidth_rate)]
+        relayed: Option<u64>,
+    },
+    /// Spread a connected service across devices running the same service
+    ///
+    /// Connections to the service's local address go to the first reachable device (failover)
+    /// or rotate across the group (round-robin). Without --with the group is removed.
+    Group {
+        service: String,
+        /// Another device offering the same service; repeat to add more
+        #[arg(long = "with", value_name = "DEVICE")]
+        members: Vec<DeviceInput>,
+        /// Rotate new connections across the group instead of preferring the first device
+        #[arg(long)]
+        round_robin: bool,
+    },
+    /// List web services reachable through the local HTTP gateway
+    Gateway,
     /// Start a service
     Start { name: String },
     /// Stop a service
@@ -126,6 +179,91 @@ pub enum ServiceCommands {
         #[arg(long)]
         tail: Option<String>,
     },
+    /// Run a job service once and wait for it to finish
+    Run { name: String },
+    /// Run a command inside a running Docker or process service, e.g. `fungi service exec web -- sh`.
+    /// Another device only accepts this if it lists this device in `service_exec.allowed_peers`
+    Exec {
+        /// Service name, or name@device for a service on another device
+        name: String,
+        /// Target device, like name@device
+        #[arg(short = 'd', long = "device", value_name = "DEVICE")]
+        device: Option<DeviceInput>,
+        /// Allocate a terminal (Docker services only) and pass keystrokes through as typed
+        #[arg(short = 't', long, default_value_t = false)]
+        tty: bool,
+        /// Command and arguments to run
+        #[arg(last = true, required = true, value_name = "COMMAND")]
+        command: Vec<String>,
+    },
+    /// Show live CPU, memory, network and disk usage of running services
+    Top {
+        /// Seconds between refreshes
+        #[arg(long, default_value_t = 2, value_parser = clap::value_parser!(u64).range(1..))]
+        interval: u64,
+        /// Print a single sample and exit
+        #[arg(long, default_value_t = false)]
+        once: bool,
+    },
+    /// List the recorded manifest revisions of a service
+    History { name: String },
+    /// Restore an earlier manifest revision of a service
+    Rollback {
+        name: String,
+        /// Revision to restore; defaults to the one before the current revision
+        #[arg(long, value_name = "REVISION")]
+        to: Option<u64>,
+    },
+    /// Move a service and its appdata to another device, e.g. `fungi service move notes --from laptop --to nas`.
+    /// The source device must list the target in `service_transfer.allowed_peers`
+    Move {
+        /// Service name, or name@device instead of --from
+        name: String,
+        /// Device the service runs on now; defaults to this device
+        #[arg(long, value_name = "DEVICE")]
+        from: Option<DeviceInput>,
+        /// Device to move the service to; defaults to this device
+        #[arg(long, value_name = "DEVICE")]
+        to: Option<DeviceInput>,
+        /// Copy the service instead, keeping the original where it is
+        #[arg(long, default_value_t = false)]
+        clone: bool,
+    },
+    /// Back up the appdata, manifest and state of a service on this device, e.g.
+    /// `fungi service backup notes --to nas -- sqlite3 /data/notes.db .dump`
+    Backup {
+        name: String,
+        /// Stop the service while it is archived and start it again afterwards
+        #[arg(long, default_value_t = false)]
+        stop: bool,
+        /// Backup target to keep the archive on; defaults to this device
+        #[arg(long, value_name = "DEVICE")]
+        to: Option<DeviceInput>,
+        /// Command to run inside the service before archiving
+        #[arg(last = true, value_name = "HOOK")]
+        hook: Vec<String>,
+    },
+    /// List backups of this device's services
+    Backups {
+        /// Only list backups of this service
+        name: Option<String>,
+        /// Backup target to list; defaults to this device
+        #[arg(long, value_name = "DEVICE")]
+        on: Option<DeviceInput>,
+    },
+    /// Restore a service on this device from a backup
+    Restore {
+        name: String,
+        /// Backup target the backup is kept on; defaults to this device
+        #[arg(long, value_name = "DEVICE")]
+        from: Option<DeviceInput>,
+        /// Backup to restore; defaults to the newest one
+        #[arg(long = "backup", value_name = "BACKUP_ID")]
+        backup_id: Option<String>,
+        /// Start the service after restoring it, even if it wasn't running
+        #[arg(long, default_value_t = false)]
+        start: bool,
+    },
     /// Remove a service
     Remove {
         name: String,
@@ -136,6 +274,12 @@ pub enum ServiceCommands {
         #[arg(short, long, default_value_t = false)]
         yes: bool,
     },
+    /// Remove Docker images pulled for services that no container uses anymore
+    PruneImages {
+        /// List the images that would be removed without removing them
+        #[arg(long, default_value_t = false)]
+        dry_run: bool,
+    },
     /// Deprecated: pull a service manifest onto the local node; use `service apply`
     #[command(hide = true)]
     Pull {
@@ -146,16 +290,16 @@ pub enum ServiceCommands {
 
 #[derive(Subcommand, Debug, Clone)]
 pub enum ServiceRecipeCommands {
-    /// List official service recipes known to the local daemon
+    /// List service recipes from every configured source
     List {
-        /// Refresh the official recipe index before listing
+        /// Refresh recipe source indexes before listing
         #[arg(long, default_value_t = false)]
         refresh: bool,
     },
-    /// Show detailed metadata and audit paths for one official recipe
+    /// Show detailed metadata and audit paths for one recipe
     Show {
         recipe: String,
-        /// Refresh the official recipe index before showing the recipe
+        /// Refresh recipe source indexes before showing the recipe
         #[arg(long, default_value_t = false)]
         refresh: bool,
     },
@@ -196,6 +340,8 @@ pub async fn execute_service(args: CommonArgs, service_args: ServiceArgs) {
             manifest,
             recipe,
             create,
+            set,
+            values_file,
             refresh,
             dry_run,
             start,
@@ -231,19 +377,18 @@ pub async fn execute_service(args: CommonArgs, service_args: ServiceArgs) {
             reject_service_entry(&target, "apply");
             let device = resolve_service_device_target(&args, device, target.device);
             let service_name = target.name;
+            let inputs = collect_service_input_values(&set, values_file.as_deref());
             if let Some(recipe_id) = recipe {
-                apply_service_from_recipe(
-                    &mut client,
-                    &args,
-                    device,
+                let options = RecipeApplyOptions {
                     service_name,
                     recipe_id,
+                    inputs,
                     refresh,
                     dry_run,
                     start,
                     yes,
-                )
-                .await;
+                };
+                apply_service_from_recipe(&mut client, &args, device, options).await;
                 return;
             }
             let Some(manifest_path) = manifest else {
@@ -251,6 +396,7 @@ pub async fn execute_service(args: CommonArgs, service_args: ServiceArgs) {
             };
             let mut created = read_manifest_yaml_file(&manifest_path);
             created.start_now = start;
+            apply_manifest_inputs(&mut created, &inputs);
             apply_manifest_instance_name(&mut created, &service_name);
             if dry_run {
                 print_service_apply_dry_run(&created, &args);
@@ -271,14 +417,24 @@ pub async fn execute_service(args: CommonArgs, service_args: ServiceArgs) {
                 }
             }
         }
+        ServiceCommands::PruneImages { dry_run } => {
+            if device.is_some() {
+                fatal("Image pruning is local-only. Run it without --device.")
+            }
+            let req = PruneServiceImagesRequest { dry_run };
+            match client.prune_service_images(Request::new(req)).await {
+                Ok(resp) => print_pruned_images(resp.into_inner()),
+                Err(e) => fatal_grpc(e),
+            }
+        }
         ServiceCommands::Pull { manifest } => {
             let created = read_manifest_yaml_file(&manifest);
             let req = PullServiceRequest {
                 manifest_yaml: created.manifest_yaml,
                 manifest_base_dir: created.manifest_base_dir,
             };
-            match client.pull_service(Request::new(req)).await {
-                Ok(resp) => print_service_instance(resp.into_inner(), false),
+            match pull_local_service(&mut client, req).await {
+                Ok(resp) => print_service_instance(resp, false),
                 Err(e) => fatal_grpc(e),
             }
         }
@@ -316,9 +472,24 @@ pub async fn execute_service(args: CommonArgs, service_args: ServiceArgs) {
             let device = resolve_service_device_target(&args, device, target.device);
             if let Some(device) = device {
                 print_target_device(&device);
-                let service =
-                    inspect_remote_service(&mut client, &device.peer_id, target.name).await;
-                print_remote_service_inspect_value(service, verbose);
+                match inspect_remote_service(&mut client, &device.peer_id, &target.name).await {
+                    Some(service) => print_remote_service_inspect_value(service, verbose),
+                    // Jobs publish nothing, so they are missing from the device snapshot.
+                    None => {
+                        let req = RemoteServiceNameRequest {
+                            peer_id: device.peer_id,
+                            name: target.name.clone(),
+                        };
+                        match client.remote_service_job_status(Request::new(req)).await {
+                            Ok(resp) => print_remote_job_inspect(
+                                target.name,
+                                resp.into_inner().job_json,
+                                verbose,
+                            ),
+                            Err(_) => fatal(format!("Remote service not found: {}", target.name)),
+                        }
+                    }
+                }
             } else {
                 let req = ServiceNameRequest {
                     runtime: 0,
@@ -347,6 +518,275 @@ pub async fn execute_service(args: CommonArgs, service_args: ServiceArgs) {
                 Err(e) => fatal_grpc(e),
             }
         }
+        ServiceCommands::Run { name } => {
+            let target = parse_service_reference(name);
+            reject_service_entry(&target, "run");
+            let device = resolve_service_device_target(&args, device, target.device);
+            let result = if let Some(device) = device {
+                print_target_device(&device);
+                let req = RemoteServiceNameRequest {
+                    peer_id: device.peer_id,
+                    name: target.name,
+                };
+                client.remote_run_service_job(Request::new(req)).await
+            } else {
+                let req = ServiceNameRequest {
+                    runtime: 0,
+                    name: target.name,
+                };
+                client.run_service_job(Request::new(req)).await
+            };
+            let job_json = match result {
+                Ok(resp) => resp.into_inner().job_json,
+                Err(error) => fatal_grpc(error),
+            };
+            match serde_json::from_str::<ServiceJobRun>(&job_json) {
+                Ok(run) => {
+                    print_job_run(&run);
+                    if run.outcome != ServiceJobOutcome::Succeeded {
+                        fatal(format!("Job run {} {}", run.run, run.outcome));
+                    }
+                }
+                Err(error) => fatal(format!("Failed to decode job run: {error}")),
+            }
+        }
+        ServiceCommands::Exec {
+            name,
+            device: exec_device,
+            tty,
+            command,
+        } => {
+            let target = parse_service_reference(name);
+            reject_service_entry(&target, "exec");
+            let target_device = match (exec_device, target.device) {
+                (Some(_), Some(_)) => {
+                    fatal("Device specified twice. Use either --device <device> or service@device.")
+                }
+                (exec_device, target_device) => exec_device.or(target_device),
+            };
+            let device = resolve_service_device_target(&args, device, target_device);
+            if let Some(device) = &device {
+                print_target_device(device);
+            }
+            let peer_id = device.map(|device| device.peer_id);
+            let exit_code = run_service_exec(&mut client, peer_id, target.name, command, tty).await;
+            std::process::exit(exit_code);
+        }
+        ServiceCommands::Top { interval, once } => {
+            if let Some(device) = &device {
+                print_target_device(device);
+            }
+            let peer_id = device.map(|device| device.peer_id);
+            loop {
+                let rows = match &peer_id {
+                    Some(peer_id) => list_remote_services(&mut client, peer_id)
+                        .await
+                        .into_iter()
+                        .map(ServiceTopRow::from_remote)
+                        .collect(),
+                    None => list_local_service_instances(&mut client)
+                        .await
+                        .into_iter()
+                        .map(ServiceTopRow::from_local)
+                        .collect::<Vec<_>>(),
+                };
+                if once {
+                    print_service_top_rows(&rows);
+                    break;
+                }
+                // Clear the screen and move the cursor home before each refresh.
+                print!("\x1b[2J\x1b[H");
+                print_service_top_rows(&rows);
+                let _ = io::stdout().flush();
+                tokio::time::sleep(std::time::Duration::from_secs(interval)).await;
+            }
+        }
+        ServiceCommands::History { name } => {
+            let target = parse_service_reference(name);
+            reject_service_entry(&target, "history");
+            let device = resolve_service_device_target(&args, device, target.device);
+            let result = if let Some(device) = device {
+                print_target_device(&device);
+                let req = RemoteServiceNameRequest {
+                    peer_id: device.peer_id,
+                    name: target.name,
+                };
+                client.remote_service_history(Request::new(req)).await
+            } else {
+                let req = ServiceNameRequest {
+                    runtime: 0,
+                    name: target.name,
+                };
+                client.service_history(Request::new(req)).await
+            };
+            let revisions_json = match result {
+                Ok(resp) => resp.into_inner().revisions_json,
+                Err(error) => fatal_grpc(error),
+            };
+            match serde_json::from_str::<Vec<ServiceRevision>>(&revisions_json) {
+                Ok(revisions) => print_service_revisions(&revisions),
+                Err(error) => fatal(format!("Failed to decode service history: {error}")),
+            }
+        }
+        ServiceCommands::Rollback { name, to } => {
+            let target = parse_service_reference(name);
+            reject_service_entry(&target, "rollback");
+            let device = resolve_service_device_target(&args, device, target.device);
+            if let Some(device) = device {
+                print_target_device(&device);
+                let req = RemoteRollbackServiceRequest {
+                    peer_id: device.peer_id.clone(),
+                    name: target.name,
+                    revision: to.unwrap_or_default(),
+                };
+                match client.remote_rollback_service(Request::new(req)).await {
+                    Ok(resp) => {
+                        print_remote_service_result("rolled back", resp.into_inner());
+                        refresh_remote_device_services(&mut client, &device.peer_id).await;
+                    }
+                    Err(error) => fatal_grpc(error),
+                }
+            } else {
+                let req = RollbackServiceRequest {
+                    name: target.name,
+                    revision: to.unwrap_or_default(),
+                };
+                match client.rollback_service(Request::new(req)).await {
+                    Ok(resp) => print_service_instance(resp.into_inner(), false),
+                    Err(error) => fatal_grpc(error),
+                }
+            }
+        }
+        ServiceCommands::Move {
+            name,
+            from,
+            to,
+            clone,
+        } => {
+            let target = parse_service_reference(name);
+            reject_service_entry(&target, "move");
+            let from_device = match (from, target.device) {
+                (Some(_), Some(_)) => fatal(
+                    "Source device specified twice. Use either --from <device> or service@device.",
+                ),
+                (from, target_device) => from.or(target_device),
+            };
+            let from = resolve_service_device_target(&args, device, from_device);
+            let to = match resolve_optional_device(&args, to.as_ref()) {
+                Ok(to) => to,
+                Err(error) => fatal(error),
+            };
+            if from.is_none() && to.is_none() {
+                fatal(
+                    "Specify the device to move the service to with --to, or where it runs with --from.",
+                );
+            }
+            let device_label = |device: &Option<super::shared::ResolvedPeerTarget>| {
+                device
+                    .as_ref()
+                    .map(|device| {
+                        device
+                            .name
+                            .clone()
+                            .unwrap_or_else(|| shorten_peer_id(&device.peer_id))
+                    })
+                    .unwrap_or_else(|| "this device".to_string())
+            };
+            let (from_label, to_label) = (device_label(&from), device_label(&to));
+            let req = MoveServiceRequest {
+                name: target.name.clone(),
+                from_peer_id: from.map(|device| device.peer_id).unwrap_or_default(),
+                to_peer_id: to.map(|device| device.peer_id).unwrap_or_default(),
+                clone,
+            };
+            let response = match client.move_service(Request::new(req)).await {
+                Ok(resp) => resp.into_inner(),
+                Err(error) => fatal_grpc(error),
+            };
+            let action = if clone { "cloned" } else { "moved" };
+            println!(
+                "Service {} {action} from {from_label} to {to_label}",
+                target.name
+            );
+            for peer_id in response.unreachable_peer_ids {
+                eprintln!(
+                    "Warning: device {} was not reachable; its saved accesses to {} still point at {from_label}",
+                    shorten_peer_id(&peer_id),
+                    target.name
+                );
+            }
+        }
+        ServiceCommands::Backup {
+            name,
+            stop,
+            to,
+            hook,
+        } => {
+            let name = local_backup_service_name(device, name, "backed up");
+            let to = resolve_backup_target(&args, to.as_ref());
+            let req = BackupServiceRequest {
+                name,
+                stop,
+                pre_backup_hook: hook,
+                target_peer_id: to.as_ref().map(|to| to.peer_id.clone()).unwrap_or_default(),
+            };
+            match client.backup_service(Request::new(req)).await {
+                Ok(resp) => {
+                    let backup = resp.into_inner();
+                    println!(
+                        "Backup {} of service {} stored on {} ({})",
+                        backup.backup_id,
+                        backup.service,
+                        backup_target_label(&to),
+                        format_bytes(backup.size_bytes)
+                    );
+                }
+                Err(error) => fatal_grpc(error),
+            }
+        }
+        ServiceCommands::Backups { name, on } => {
+            let name = name.map(|name| local_backup_service_name(device, name, "listed"));
+            let on = resolve_backup_target(&args, on.as_ref());
+            let req = ListServiceBackupsRequest {
+                name: name.unwrap_or_default(),
+                peer_id: on.map(|on| on.peer_id).unwrap_or_default(),
+            };
+            match client.list_service_backups(Request::new(req)).await {
+                Ok(resp) => print_service_backups(&resp.into_inner().backups),
+                Err(error) => fatal_grpc(error),
+            }
+        }
+        ServiceCommands::Restore {
+            name,
+            from,
+            backup_id,
+            start,
+        } => {
+            let name = local_backup_service_name(device, name, "restored");
+            let from = resolve_backup_target(&args, from.as_ref());
+            let req = RestoreServiceBackupRequest {
+                name,
+                peer_id: from
+                    .as_ref()
+                    .map(|from| from.peer_id.clone())
+                    .unwrap_or_default(),
+                backup_id: backup_id.unwrap_or_default(),
+                start,
+            };
+            match client.restore_service_backup(Request::new(req)).await {
+                Ok(resp) => {
+                    let backup = resp.into_inner();
+                    println!(
+                        "Service {} restored from backup {} ({}) on {}",
+                        backup.service,
+                        backup.backup_id,
+                        backup.created_at,
+                        backup_target_label(&from)
+                    );
+                }
+                Err(error) => fatal_grpc(error),
+            }
+        }
         ServiceCommands::Stop { name } => {
             let target = parse_service_reference(name);
             reject_service_entry(&target, "stop");
@@ -476,6 +916,8 @@ pub async fn execute_service(args: CommonArgs, service_args: ServiceArgs) {
                     &target.name,
                     None,
                     None,
+                    false,
+                    None,
                 )
                 .await;
                 let device_name = resolved_device_display_name(&device);
@@ -494,6 +936,8 @@ pub async fn execute_service(args: CommonArgs, service_args: ServiceArgs) {
             service,
             entry,
             local_port,
+            wake,
+            wake_timeout,
         } => {
             let target = parse_service_reference(service);
             let entry = merge_entry(target.entry.as_deref(), entry.as_deref(), "connect");
@@ -509,6 +953,8 @@ pub async fn execute_service(args: CommonArgs, service_args: ServiceArgs) {
                     &target.name,
                     entry.as_deref(),
                     local_port,
+                    wake,
+                    wake_timeout,
                 )
                 .await;
                 select_access_endpoint(&access, entry.as_deref())
@@ -519,6 +965,9 @@ pub async fn execute_service(args: CommonArgs, service_args: ServiceArgs) {
                         "--local-port can only be used when connecting to a service on another device",
                     )
                 }
+                if wake {
+                    fatal("--wake can only be used when connecting to a service on another device")
+                }
                 let instance = inspect_local_service(&mut client, target.name).await;
                 require_local_service_running(&instance);
                 select_local_port(&instance, entry.as_deref())
@@ -543,6 +992,196 @@ pub async fn execute_service(args: CommonArgs, service_args: ServiceArgs) {
                 Err(error) => fatal_grpc(error),
             }
         }
+        ServiceCommands::Limit {
+            service,
+            entry,
+            direct,
+            relayed,
+        } => {
+            let target = parse_service_reference(service);
+            let entry = merge_entry(target.entry.as_deref(), entry.as_deref(), "limit");
+            let device = resolve_service_device_target(&args, device, target.device);
+            let limit = BandwidthLimit {
+                direct_bytes_per_sec: direct.unwrap_or_default(),
+                relayed_bytes_per_sec: relayed.unwrap_or_default(),
+            };
+
+            let result = if let Some(device) = device {
+                print_target_device(&device);
+                let req = SetServiceAccessBandwidthLimitRequest {
+                    peer_id: device.peer_id,
+                    service_name: target.name,
+                    entry: entry.unwrap_or_default(),
+                    limit: Some(limit),
+                };
+                client
+                    .set_service_access_bandwidth_limit(Request::new(req))
+                    .await
+            } else {
+                let Some(entry) = entry else {
+                    fatal(
+                        "Choose the published entry to limit: `fungi service limit <service> <entry>`",
+                    )
+                };
+                let req = SetServicePortBandwidthLimitRequest {
+                    service_name: target.name,
+                    port_name: entry,
+                    limit: Some(limit),
+                };
+                client
+                    .set_service_port_bandwidth_limit(Request::new(req))
+                    .await
+            };
+
+            match result {
+                Ok(_) => println!(
+                    "Bandwidth limit updated (direct: {}, relayed: {})",
+                    format_bandwidth_rate(direct),
+                    format_bandwidth_rate(relayed)
+                ),
+                Err(error) => fatal_grpc(error),
+            }
+        }
+        ServiceCommands::Group {
+            service,
+            members,
+            round_robin,
+        } => {
+            let (service, _entry, device) =
+                resolve_remote_service_reference(&args, device, service, false, "group");
+            let members = members
+                .iter()
+                .map(
+                    |member| match resolve_optional_device(&args, Some(member)) {
+                        Ok(Some(member)) => member,
+                        Ok(None) => fatal(format!("Unknown device: {member}")),
+                        Err(error) => fatal(error),
+                    },
+                )
+                .collect::<Vec<_>>();
+
+            let req = SetServiceAccessGroupRequest {
+                peer_id: device.peer_id.clone(),
+                service_name: service,
+                member_peer_ids: members
+                    .iter()
+                    .map(|member| member.peer_id.clone())
+                    .collect(),
+                round_robin,
+            };
+            let access = match client.set_service_access_group(Request::new(req)).await {
+                Ok(resp) => match serde_json::from_str::<ServiceAccess>(
+                    &resp.into_inner().service_access_json,
+                ) {
+                    Ok(access) => access,
+                    Err(error) => fatal(format!("Failed to decode local address: {error}")),
+                },
+                Err(error) => fatal_grpc(error),
+            };
+            print_service_group(&access, &device, &members);
+        }
+        ServiceCommands::Gateway => {
+            let response = match client
+                .list_http_gateway_services(Request::new(Empty {}))
+                .await
+            {
+                Ok(response) => response.into_inner(),
+                Err(error) => fatal_grpc(error),
+            };
+            let services = response
+                .services
+                .into_iter()
+                .filter(|service| {
+                    device
+                        .as_ref()
+                        .is_none_or(|device| device.peer_id == service.peer_id)
+                })
+                .collect::<Vec<_>>();
+            print_http_gateway_services(&response.index_url, &services);
+        }
+    }
+}
+
+fn print_service_group(
+    access: &ServiceAccess,
+    device: &super::shared::ResolvedPeerTarget,
+    members: &[super::shared::ResolvedPeerTarget],
+) {
+    if access.group.is_empty() {
+        println!(
+            "{} now connects only to {}",
+            access.service_name,
+            resolved_device_display_name(device)
+        );
+        return;
+    }
+
+    println!(
+        "{} routing: {}",
+        access.service_name,
+        access.routing.as_str().replace('_', "-")
+    );
+    println!(
+        "  {} ({})",
+        resolved_device_display_name(device),
+        access.service_name
+    );
+    for member in &access.group {
+        let name = members
+            .iter()
+            .find(|target| target.peer_id == member.peer_id)
+            .map(resolved_device_display_name)
+            .unwrap_or_else(|| shorten_peer_id(&member.peer_id));
+        println!("  {name} ({})", member.service_name);
+    }
+}
+
+fn print_http_gateway_services(index_url: &str, services: &[HttpGatewayService]) {
+    println!("Gateway: {index_url}");
+    if services.is_empty() {
+        println!(
+            "No web services known yet. Run `fungi service list --device <name>` to discover them."
+        );
+        return;
+    }
+    for service in services {
+        println!(
+            "  {}@{}  {}",
+            service.service_name, service.device_name, service.url
+        );
+    }
+}
+
+fn parse_bandwidth_rate(value: &str) -> Result<u64, String> {
+    let value = value.trim();
+    let (digits, multiplier) = match value.char_indices().last() {
+        Some((index, suffix)) if suffix.is_ascii_alphabetic() => {
+            let multiplier = match suffix.to_ascii_uppercase() {
+                'K' => 1024,
+                'M' => 1024 * 1024,
+                'G' => 1024 * 1024 * 1024,
+                _ => return Err(format!("unknown rate suffix '{suffix}', use K, M or G")),
+            };
+            (&value[..index], multiplier)
+        }
+        _ => (value, 1),
+    };
+    let amount = digits
+        .parse::<u64>()
+        .map_err(|_| format!("invalid rate '{value}', expected e.g. 512K or 4M"))?;
+    amount
+        .checked_mul(multiplier)
+        .ok_or_else(|| format!("rate '{value}' is too large"))
+}
+
+fn format_bandwidth_rate(rate: Option<u64>) -> String {
+    match rate {
+        None | Some(0) => "unlimited".to_string(),
+        Some(rate) if rate >= 1024 * 1024 && rate % (1024 * 1024) == 0 => {
+            format!("{}M/s", rate / (1024 * 1024))
+        }
+        Some(rate) if rate >= 1024 && rate % 1024 == 0 => format!("{}K/s", rate / 1024),
+        Some(rate) => format!("{rate}B/s"),
     }
 }
 
@@ -750,17 +1389,32 @@ fn parse_service_reference(value: String) -> DynamicServiceTarget {
     target
 }
 
-async fn apply_service_from_recipe(
-    client: &mut RpcClient,
-    args: &CommonArgs,
-    scoped_device: Option<super::shared::ResolvedPeerTarget>,
+/// What `service apply --recipe` resolves and how the result is applied.
+struct RecipeApplyOptions {
     service_name: String,
     recipe_id: String,
+    inputs: BTreeMap<String, String>,
     refresh: bool,
     dry_run: bool,
     start: bool,
     yes: bool,
+}
+
+async fn apply_service_from_recipe(
+    client: &mut RpcClient,
+    args: &CommonArgs,
+    scoped_device: Option<super::shared::ResolvedPeerTarget>,
+    options: RecipeApplyOptions,
 ) {
+    let RecipeApplyOptions {
+        service_name,
+        recipe_id,
+        inputs,
+        refresh,
+        dry_run,
+        start,
+        yes,
+    } = options;
     let device = scoped_device;
     let target_device_name = device.as_ref().map(resolved_device_display_name);
     let req = ResolveRecipeRequest {
@@ -771,6 +1425,7 @@ async fn apply_service_from_recipe(
             .map(|device| device.peer_id.clone())
             .unwrap_or_default(),
         refresh,
+        inputs: inputs.into_iter().collect(),
     };
     eprintln!("Resolving recipe; downloading recipe assets if needed...");
     let resolved = match client.resolve_recipe(Request::new(req)).await {
@@ -845,9 +1500,9 @@ async fn apply_service_from_recipe(
             manifest_yaml: resolved.manifest_yaml,
             manifest_base_dir: resolved.manifest_base_dir,
         };
-        match client.pull_service(Request::new(req)).await {
+        match pull_local_service(client, req).await {
             Ok(resp) => {
-                let instance = decode_service_instance(resp.into_inner());
+                let instance = decode_service_instance(resp);
                 let name = instance.name.clone();
                 print_service_instance_value(instance, false);
                 if start {
@@ -1057,9 +1712,9 @@ async fn apply_created_service(
             manifest_yaml: created.manifest_yaml,
             manifest_base_dir: created.manifest_base_dir,
         };
-        match client.pull_service(Request::new(req)).await {
+        match pull_local_service(client, req).await {
             Ok(resp) => {
-                let instance = decode_service_instance(resp.into_inner());
+                let instance = decode_service_instance(resp);
                 let name = instance.name.clone();
                 print_service_instance_value(instance, false);
                 if created.start_now {
@@ -1075,6 +1730,132 @@ async fn apply_created_service(
     }
 }
 
+/// Pulls a service on the local node, printing Docker image pull progress to stderr as each
+/// layer changes state.
+pub(super) async fn pull_local_service(
+    client: &mut RpcClient,
+    req: PullServiceRequest,
+) -> Result<ServiceInstanceResponse, Status> {
+    let mut stream = client
+        .pull_service_with_progress(Request::new(req))
+        .await?
+        .into_inner();
+    let mut layer_status = BTreeMap::new();
+    while let Some(event) = stream.message().await? {
+        match event.event {
+            Some(pull_service_event::Event::Progress(progress))
+                if layer_status.get(&progress.layer_id) != Some(&progress.status) =>
+            {
+                if progress.layer_id.is_empty() {
+                    eprintln!("{}", progress.status);
+                } else {
+                    eprintln!("{}: {}", progress.layer_id, progress.status);
+                    layer_status.insert(progress.layer_id, progress.status);
+                }
+            }
+            Some(pull_service_event::Event::Instance(instance)) => return Ok(instance),
+            _ => {}
+        }
+    }
+    Err(Status::internal(
+        "Service pull ended without returning a service instance",
+    ))
+}
+
+fn print_pruned_images(response: PruneServiceImagesResponse) {
+    if response.images.is_empty() {
+        println!("No unused service images");
+        return;
+    }
+    let verb = if response.dry_run {
+        "Would remove"
+    } else {
+        "Removed"
+    };
+    let mut reclaimed = 0;
+    for image in &response.images {
+        reclaimed += image.size_bytes;
+        println!(
+            "{verb} {} ({})",
+            image.image,
+            format_bytes(image.size_bytes)
+        );
+    }
+    println!(
+        "{verb} {} image(s), {} in total",
+        response.images.len(),
+        format_bytes(reclaimed)
+    );
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1000 {
+        return format!("{bytes} B");
+    }
+    let mut value = bytes as f64 / 1000.0;
+    let mut unit = 0;
+    while value >= 1000.0 && unit + 1 < UNITS.len() {
+        value /= 1000.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
+
+/// Backups are taken and restored by the device running the service, which a backup target
+/// then only stores.
+fn local_backup_service_name(
+    scoped_device: Option<super::shared::ResolvedPeerTarget>,
+    name: String,
+    action: &str,
+) -> String {
+    let target = parse_service_reference(name);
+    reject_service_entry(&target, "backup");
+    if scoped_device.is_some() || target.device.is_some() {
+        fatal(format!(
+            "Services are {action} on the device that runs them. Run this there, and use --to/--from/--on to pick the backup target."
+        ));
+    }
+    target.name
+}
+
+fn resolve_backup_target(
+    args: &CommonArgs,
+    device: Option<&DeviceInput>,
+) -> Option<super::shared::ResolvedPeerTarget> {
+    resolve_optional_device(args, device).unwrap_or_else(|error| fatal(error))
+}
+
+fn backup_target_label(device: &Option<super::shared::ResolvedPeerTarget>) -> String {
+    device
+        .as_ref()
+        .map(|device| {
+            device
+                .name
+                .clone()
+                .unwrap_or_else(|| shorten_peer_id(&device.peer_id))
+        })
+        .unwrap_or_else(|| "this device".to_string())
+}
+
+fn print_service_backups(backups: &[ServiceBackupInfo]) {
+    if backups.is_empty() {
+        println!("No backups found.");
+        return;
+    }
+
+    println!("{:<24} {:<28} {:<21} SIZE", "SERVICE", "BACKUP", "CREATED");
+    for backup in backups {
+        println!(
+            "{:<24} {:<28} {:<21} {}",
+            backup.service,
+            backup.backup_id,
+            backup.created_at,
+            format_bytes(backup.size_bytes)
+        );
+    }
+}
+
 fn reject_service_entry(target: &DynamicServiceTarget, action: &str) {
     if target.entry.is_some() {
         fatal(format!("Entry-specific {action} is not implemented yet"))
@@ -1153,6 +1934,84 @@ fn print_remote_service_applied(resp: RemoteServiceControlResponse) {
     println!("Remote service applied: {service_name}");
 }
 
+fn print_service_revisions(revisions: &[ServiceRevision]) {
+    if revisions.is_empty() {
+        println!("No revisions recorded yet; they are kept from the next apply on.");
+        return;
+    }
+
+    println!(
+        "{:<9} {:<21} {:<14} {:<12} NOTE",
+        "REVISION", "APPLIED", "BY", "DIGEST"
+    );
+    let current = revisions.last().map(|revision| revision.revision);
+    for revision in revisions {
+        let marker = if Some(revision.revision) == current {
+            "*"
+        } else {
+            " "
+        };
+        let note = revision
+            .rollback_of
+            .map(|target| format!("rollback to {target}"))
+            .unwrap_or_default();
+        println!(
+            "{marker}{:<8} {:<21} {:<14} {:<12} {note}",
+            revision.revision,
+            revision.applied_at,
+            revision
+                .applied_by
+                .as_deref()
+                .map(shorten_peer_id)
+                .unwrap_or_else(|| "-".to_string()),
+            &revision.resolved_manifest_digest[..revision.resolved_manifest_digest.len().min(12)],
+        );
+    }
+}
+
+fn print_job_run(run: &ServiceJobRun) {
+    let duration = run
+        .duration_ms
+        .map(|ms| format!("{:.1}s", ms as f64 / 1000.0))
+        .unwrap_or_else(|| "-".to_string());
+    let exit_code = run
+        .exit_code
+        .map(|code| code.to_string())
+        .unwrap_or_else(|| "-".to_string());
+    println!(
+        "Run {}: {} (duration {duration}, exit code {exit_code})",
+        run.run, run.outcome
+    );
+    if !run.logs.is_empty() {
+        print!("{}", run.logs);
+        if !run.logs.ends_with('\n') {
+            println!();
+        }
+    }
+}
+
+fn print_remote_job_inspect(name: String, job_json: String, verbose: bool) {
+    let job = match serde_json::from_str::<ServiceJobStatus>(&job_json) {
+        Ok(job) => job,
+        Err(error) => fatal(format!("Failed to decode job status: {error}")),
+    };
+    let pretty = if verbose {
+        serde_json::to_string_pretty(&RemoteJobInspectView {
+            name,
+            job: JobInspectView::Verbose(job),
+        })
+    } else {
+        serde_json::to_string_pretty(&RemoteJobInspectView {
+            name,
+            job: JobInspectView::from_status(job),
+        })
+    };
+    match pretty {
+        Ok(pretty) => println!("{}", pretty),
+        Err(error) => fatal(format!("Failed to format job status: {error}")),
+    }
+}
+
 fn print_remote_service_result(action: &str, resp: RemoteServiceControlResponse) {
     let service_name = response_service_name(&resp);
     if resp.forgotten_locally {
@@ -1171,7 +2030,7 @@ fn is_remote_device_reachability_error(error: &tonic::Status) -> bool {
         || message.contains("No connections available to peer")
 }
 
-async fn refresh_remote_device_services(client: &mut RpcClient, peer_id: &str) {
+pub(super) async fn refresh_remote_device_services(client: &mut RpcClient, peer_id: &str) {
     match fetch_device_service_snapshot(client, peer_id, true).await {
         Ok(snapshot) => {
             if let Some(error) = snapshot.error {
@@ -1381,15 +2240,14 @@ async fn cached_device_service_exists(
 async fn inspect_remote_service(
     client: &mut RpcClient,
     peer_id: &str,
-    name: String,
-) -> RemoteService {
+    name: &str,
+) -> Option<RemoteService> {
     match fetch_device_service_snapshot(client, peer_id, true).await {
         Ok(snapshot) => snapshot
             .snapshot
             .services
             .into_iter()
-            .find(|service| service.name == name)
-            .unwrap_or_else(|| fatal(format!("Remote service not found: {name}"))),
+            .find(|service| service.name == name),
         Err(error) => fatal(error),
     }
 }
@@ -1415,12 +2273,16 @@ async fn attach_access_with_options(
     service_name: &str,
     entry: Option<&str>,
     local_port: Option<u16>,
+    wake: bool,
+    wake_timeout_secs: Option<u32>,
 ) -> ServiceAccess {
     let req = AttachServiceAccessRequest {
         peer_id: peer_id.to_string(),
         service_name: service_name.to_string(),
         entry: entry.unwrap_or_default().to_string(),
         local_port: local_port.unwrap_or_default() as i32,
+        wake,
+        wake_timeout_secs: wake_timeout_secs.unwrap_or_default(),
     };
     match client.attach_service_access(Request::new(req)).await {
         Ok(resp) => {
@@ -1743,7 +2605,7 @@ fn open_url(_url: &str) {
 pub(crate) struct CreatedServiceManifest {
     pub(crate) manifest_yaml: String,
     pub(crate) manifest_base_dir: String,
-    start_now: bool,
+    pub(crate) start_now: bool,
 }
 
 pub(crate) fn read_manifest_yaml_file(path: &str) -> CreatedServiceManifest {
@@ -1925,10 +2787,55 @@ fn wasmtime_run_mode_label(mode: fungi_daemon::ServiceRunMode) -> &'static str {
     match mode {
         fungi_daemon::ServiceRunMode::Command => "command (default)",
         fungi_daemon::ServiceRunMode::Http => "http",
+        fungi_daemon::ServiceRunMode::Job => "job",
+    }
+}
+
+/// Merges `--values` file entries with `--set NAME=VALUE` pairs; `--set` wins on conflicts.
+fn collect_service_input_values(
+    set: &[String],
+    values_file: Option<&str>,
+) -> BTreeMap<String, String> {
+    let mut values = match values_file {
+        Some(path) => {
+            let content = std::fs::read_to_string(path).unwrap_or_else(|error| {
+                fatal(format!("Failed to read values file {path}: {error}"))
+            });
+            parse_service_input_values_yaml(&content).unwrap_or_else(|error| {
+                fatal(format!("Failed to parse values file {path}: {error:#}"))
+            })
+        }
+        None => BTreeMap::new(),
+    };
+    for entry in set {
+        let Some((name, value)) = entry.split_once('=') else {
+            fatal(format!(
+                "Invalid --set value '{entry}', expected NAME=VALUE"
+            ));
+        };
+        let name = name.trim();
+        if name.is_empty() {
+            fatal(format!(
+                "Invalid --set value '{entry}', expected NAME=VALUE"
+            ));
+        }
+        values.insert(name.to_string(), value.to_string());
     }
+    values
+}
+
+pub(super) fn apply_manifest_inputs(
+    created: &mut CreatedServiceManifest,
+    inputs: &BTreeMap<String, String>,
+) {
+    created.manifest_yaml = service_manifest_with_inputs(&created.manifest_yaml, inputs)
+        .unwrap_or_else(|error| fatal(format!("Failed to apply service inputs: {error:#}")));
 }
 
-fn apply_manifest_instance_name(created: &mut CreatedServiceManifest, service_name: &str) {
+pub(super) fn apply_manifest_instance_name(
+    created: &mut CreatedServiceManifest,
+    service_name: &str,
+) {
     created.manifest_yaml =
         service_manifest_with_instance_name(&created.manifest_yaml, service_name)
             .unwrap_or_else(|error| fatal(format!("Failed to set service instance name: {error}")));
@@ -2112,6 +3019,7 @@ fn runtime_kind_label(runtime: RuntimeKind) -> &'static str {
     match runtime {
         RuntimeKind::Docker => "docker",
         RuntimeKind::Wasmtime => "wasmtime",
+        RuntimeKind::Process => "process",
         RuntimeKind::External => "external",
     }
 }
@@ -2125,7 +3033,7 @@ fn prompt_with_default(label: &str, default: &str) -> String {
     }
 }
 
-fn prompt_yes_no_default(label: &str, default: bool) -> bool {
+pub(super) fn prompt_yes_no_default(label: &str, default: bool) -> bool {
     let default_value = if default { "y" } else { "n" };
     let value = prompt_with_default(label, default_value);
     match value.trim().to_ascii_lowercase().as_str() {
@@ -2476,6 +3384,103 @@ fn print_service_overview_rows(rows: &[ServiceOverviewRow]) {
     }
 }
 
+#[derive(Debug, Clone)]
+struct ServiceTopRow {
+    name: String,
+    runtime: &'static str,
+    state: String,
+    resources: ServiceResourceUsage,
+}
+
+impl ServiceTopRow {
+    fn from_local(service: ServiceInstance) -> Self {
+        Self {
+            name: service.name,
+            runtime: runtime_kind_label(service.runtime),
+            state: overview_state_label(&service.status),
+            resources: service.resources.unwrap_or_default(),
+        }
+    }
+
+    fn from_remote(service: RemoteService) -> Self {
+        Self {
+            name: service.name,
+            runtime: runtime_kind_label(service.runtime),
+            state: overview_state_label(&service.status),
+            resources: service.resources.unwrap_or_default(),
+        }
+    }
+
+    fn cells(&self) -> [String; 7] {
+        let resources = &self.resources;
+        let memory = match (resources.memory_bytes, resources.memory_limit_bytes) {
+            (Some(usage), Some(limit)) => {
+                format!("{} / {}", format_bytes(usage), format_bytes(limit))
+            }
+            (Some(usage), None) => format_bytes(usage),
+            (None, _) => "-".to_string(),
+        };
+        [
+            self.name.clone(),
+            self.runtime.to_string(),
+            self.state.clone(),
+            resources
+                .cpu_percent
+                .map(|percent| format!("{percent:.1}%"))
+                .unwrap_or_else(|| "-".to_string()),
+            memory,
+            format_byte_pair(resources.network_rx_bytes, resources.network_tx_bytes),
+            format_byte_pair(resources.block_read_bytes, resources.block_write_bytes),
+        ]
+    }
+}
+
+fn format_byte_pair(first: Option<u64>, second: Option<u64>) -> String {
+    if first.is_none() && second.is_none() {
+        return "-".to_string();
+    }
+    let format = |bytes: Option<u64>| bytes.map(format_bytes).unwrap_or_else(|| "-".to_string());
+    format!("{} / {}", format(first), format(second))
+}
+
+fn print_service_top_rows(rows: &[ServiceTopRow]) {
+    if rows.is_empty() {
+        println!("No services found");
+        return;
+    }
+
+    const HEADERS: [&str; 7] = [
+        "NAME",
+        "RUNTIME",
+        "STATE",
+        "CPU%",
+        "MEM",
+        "NET RX / TX",
+        "BLOCK R / W",
+    ];
+    let cells = rows.iter().map(ServiceTopRow::cells).collect::<Vec<_>>();
+    let mut widths = HEADERS.map(str::len);
+    for row in &cells {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    let print_line = |values: [&str; 7]| {
+        let line = values
+            .iter()
+            .zip(widths)
+            .map(|(value, width)| format!("{value:<width$}"))
+            .collect::<Vec<_>>()
+            .join("  ");
+        println!("{}", line.trim_end());
+    };
+    print_line(HEADERS);
+    for row in &cells {
+        print_line(std::array::from_fn(|index| row[index].as_str()));
+    }
+}
+
 fn device_display_name(device: &DeviceInfo) -> String {
     if !device.name.trim().is_empty() {
         device.name.clone()
@@ -2531,6 +3536,8 @@ struct RemoteServiceListVerboseEntry {
     #[serde(skip_serializing_if = "Option::is_none")]
     detail: Option<String>,
     entries: Vec<RemoteServiceEntryVerboseView>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    resources: Option<ServiceResourceUsage>,
 }
 
 #[derive(Debug, Serialize)]
@@ -2541,6 +3548,10 @@ struct LocalServiceInspectView {
     detail: Option<String>,
     entries: Vec<ServiceEntryView>,
     published_entries: Vec<ServiceEntryView>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    resources: Option<ServiceResourceUsage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    job: Option<JobInspectView>,
 }
 
 #[derive(Debug, Serialize)]
@@ -2555,6 +3566,65 @@ struct LocalServiceInspectVerboseView {
     detail: Option<String>,
     local_endpoints: Vec<LocalServiceEndpointVerboseView>,
     published_endpoints: Vec<PublishedEndpointVerboseView>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    resources: Option<ServiceResourceUsage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    job: Option<JobInspectView>,
+}
+
+#[derive(Debug, Serialize)]
+struct RemoteJobInspectView {
+    name: String,
+    job: JobInspectView,
+}
+
+/// Job details for inspect; only the verbose form carries the logs of each run.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+enum JobInspectView {
+    Summary {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        schedule: Option<String>,
+        enabled: bool,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        next_run_at: Option<String>,
+        runs: Vec<JobRunView>,
+    },
+    Verbose(ServiceJobStatus),
+}
+
+impl JobInspectView {
+    fn from_status(job: ServiceJobStatus) -> Self {
+        Self::Summary {
+            schedule: job.schedule,
+            enabled: job.enabled,
+            next_run_at: job.next_run_at,
+            runs: job
+                .runs
+                .into_iter()
+                .map(|run| JobRunView {
+                    run: run.run,
+                    trigger: run.trigger,
+                    started_at: run.started_at,
+                    duration_ms: run.duration_ms,
+                    exit_code: run.exit_code,
+                    outcome: run.outcome,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct JobRunView {
+    run: u64,
+    trigger: fungi_daemon::ServiceJobTrigger,
+    started_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duration_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    exit_code: Option<i32>,
+    outcome: ServiceJobOutcome,
 }
 
 #[derive(Debug, Serialize)]
@@ -2564,6 +3634,8 @@ struct RemoteServiceInspectView {
     #[serde(skip_serializing_if = "Option::is_none")]
     detail: Option<String>,
     entries: Vec<ServiceEntryView>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    resources: Option<ServiceResourceUsage>,
 }
 
 #[derive(Debug, Serialize)]
@@ -2576,6 +3648,8 @@ struct RemoteServiceInspectVerboseView {
     #[serde(skip_serializing_if = "Option::is_none")]
     detail: Option<String>,
     entries: Vec<RemoteServiceEntryVerboseView>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    resources: Option<ServiceResourceUsage>,
 }
 
 #[derive(Debug, Serialize)]
@@ -2660,6 +3734,7 @@ impl From<RemoteService> for RemoteServiceListVerboseEntry {
                     protocol: endpoint.protocol,
                 })
                 .collect(),
+            resources: service.resources,
         }
     }
 }
@@ -2680,6 +3755,8 @@ impl From<ServiceInstance> for LocalServiceInspectView {
                     name: Some(endpoint.name),
                 })
                 .collect(),
+            resources: instance.resources,
+            job: instance.job.map(JobInspectView::from_status),
         }
     }
 }
@@ -2708,6 +3785,8 @@ impl From<ServiceInstance> for LocalServiceInspectVerboseView {
                     service_port: endpoint.service_port,
                 })
                 .collect(),
+            resources: instance.resources,
+            job: instance.job.map(JobInspectView::Verbose),
         }
     }
 }
@@ -2726,6 +3805,7 @@ impl From<RemoteService> for RemoteServiceInspectView {
                     name: Some(endpoint.name),
                 })
                 .collect(),
+            resources: service.resources,
         }
     }
 }
@@ -2747,6 +3827,7 @@ impl From<RemoteService> for RemoteServiceInspectVerboseView {
                     protocol: endpoint.protocol,
                 })
                 .collect(),
+            resources: service.resources,
         }
     }
 }
@@ -2795,6 +3876,58 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn parse_bandwidth_rate_accepts_binary_suffixes() {
+        assert_eq!(parse_bandwidth_rate("0"), Ok(0));
+        assert_eq!(parse_bandwidth_rate("1500"), Ok(1500));
+        assert_eq!(parse_bandwidth_rate("512K"), Ok(512 * 1024));
+        assert_eq!(parse_bandwidth_rate("4m"), Ok(4 * 1024 * 1024));
+        assert!(parse_bandwidth_rate("4T").is_err());
+        assert!(parse_bandwidth_rate("fast").is_err());
+    }
+
+    #[test]
+    fn format_bandwidth_rate_uses_largest_whole_unit() {
+        assert_eq!(format_bandwidth_rate(None), "unlimited");
+        assert_eq!(format_bandwidth_rate(Some(0)), "unlimited");
+        assert_eq!(format_bandwidth_rate(Some(2 * 1024 * 1024)), "2M/s");
+        assert_eq!(format_bandwidth_rate(Some(1536)), "1536B/s");
+        assert_eq!(format_bandwidth_rate(Some(512 * 1024)), "512K/s");
+    }
+
+    #[test]
+    fn service_top_row_formats_missing_stats_as_dashes() {
+        let mut row = ServiceTopRow {
+            name: "web".to_string(),
+            runtime: "docker",
+            state: "running".to_string(),
+            resources: ServiceResourceUsage {
+                cpu_percent: Some(12.345),
+                memory_bytes: Some(41_943_040),
+                memory_limit_bytes: Some(268_435_456),
+                network_rx_bytes: Some(1024),
+                network_tx_bytes: Some(400),
+                ..Default::default()
+            },
+        };
+        assert_eq!(
+            row.cells(),
+            [
+                "web",
+                "docker",
+                "running",
+                "12.3%",
+                "41.9 MB / 268.4 MB",
+                "1.0 KB / 400 B",
+                "-",
+            ]
+            .map(String::from)
+        );
+
+        row.resources = ServiceResourceUsage::default();
+        assert_eq!(row.cells()[3..], ["-", "-", "-", "-"].map(String::from));
+    }
+
     #[test]
     fn select_access_endpoint_prefers_requested_entry() {
         let access = service_access(vec![
@@ -3125,6 +4258,8 @@ publish:
             peer_id: "peer".to_string(),
             service_name: "demo".to_string(),
             endpoints,
+            group: Vec::new(),
+            routing: Default::default(),
         }
     }
 
@@ -3134,12 +4269,14 @@ publish:
             protocol: format!("/fungi/service/demo/{name}/0.2.0"),
             local_host: "127.0.0.1".to_string(),
             local_port,
+            bandwidth_limit: Default::default(),
         }
     }
 
     fn remote_web_service(path: &str) -> RemoteService {
         RemoteService {
             name: "demo".to_string(),
+            definition_id: None,
             runtime: RuntimeKind::Docker,
             metadata: fungi_daemon::DeviceServiceMetadata {
                 usage: Some(ServiceExposeUsage {
@@ -3153,6 +4290,7 @@ publish:
                 protocol: "/fungi/service/demo/web/0.2.0".to_string(),
             }],
             status: ServiceStatus::running(),
+            resources: None,
         }
     }
 
@@ -3167,6 +4305,8 @@ publish:
             ports,
             exposed_endpoints: Vec::new(),
             status: ServiceStatus::running(),
+            job: None,
+            resources: None,
         }
     }
 
@@ -3178,6 +4318,7 @@ publish:
             os: String::new(),
             public_ip: String::new(),
             private_ips: Vec::new(),
+            mac_addresses: Vec::new(),
             created_at: 0,
             last_connected: 0,
             version: String::new(),
@@ -0,0 +1,199 @@
+use std::io::{self, Write};
+
+use fungi_daemon_grpc::{
+    Request,
+    fungi_daemon_grpc::{
+        ServiceExecInput, ServiceExecResize, ServiceExecStart,
+        fungi_daemon_client::FungiDaemonClient, service_exec_input, service_exec_output,
+    },
+};
+use futures::{SinkExt, channel::mpsc};
+use tokio::io::AsyncReadExt;
+
+use super::shared::fatal_grpc;
+
+const STDIN_CHUNK: usize = 8 * 1024;
+
+/// Runs `command` in a service and relays the terminal to it until the command exits, returning
+/// the exit code to leave with. `peer_id` selects a remote device.
+pub(super) async fn run_service_exec(
+    client: &mut FungiDaemonClient<tonic::transport::Channel>,
+    peer_id: Option<String>,
+    name: String,
+    command: Vec<String>,
+    tty: bool,
+) -> i32 {
+    let (rows, cols) = terminal::size().unwrap_or_default();
+    let (mut input_tx, input_rx) = mpsc::channel(64);
+    let start = exec_input(service_exec_input::Input::Start(ServiceExecStart {
+        peer_id: peer_id.unwrap_or_default(),
+        name,
+        command,
+        tty,
+        rows: rows.into(),
+        cols: cols.into(),
+    }));
+    if input_tx.send(start).await.is_err() {
+        return 1;
+    }
+
+    let mut outputs = match client.exec_service(Request::new(input_rx)).await {
+        Ok(resp) => resp.into_inner(),
+        Err(error) => fatal_grpc(error),
+    };
+
+    // Keystrokes go to the remote terminal as they are typed, not line by line.
+    let raw_mode = tty.then(terminal::RawMode::enable).flatten();
+    spawn_stdin_pump(input_tx.clone());
+    if tty {
+        spawn_resize_pump(input_tx);
+    } else {
+        drop(input_tx);
+    }
+
+    let mut stdout = io::stdout();
+    let mut stderr = io::stderr();
+    let (exit_code, error) = loop {
+        match outputs.message().await {
+            Ok(Some(message)) => match message.output {
+                Some(service_exec_output::Output::Stdout(data)) => {
+                    let _ = stdout.write_all(&data);
+                    let _ = stdout.flush();
+                }
+                Some(service_exec_output::Output::Stderr(data)) => {
+                    let _ = stderr.write_all(&data);
+                    let _ = stderr.flush();
+                }
+                Some(service_exec_output::Output::Exit(exit)) => {
+                    let code = if exit.has_exit_code {
+                        exit.exit_code
+                    } else {
+                        1
+                    };
+                    break (code, (!exit.error.is_empty()).then_some(exit.error));
+                }
+                None => {}
+            },
+            Ok(None) => break (1, Some("exec session ended without an exit status".into())),
+            Err(error) => break (1, Some(error.to_string())),
+        }
+    };
+    drop(raw_mode);
+    if let Some(error) = error {
+        eprintln!("Error: {error}");
+    }
+    exit_code
+}
+
+fn exec_input(input: service_exec_input::Input) -> ServiceExecInput {
+    ServiceExecInput { input: Some(input) }
+}
+
+fn spawn_stdin_pump(mut input_tx: mpsc::Sender<ServiceExecInput>) {
+    tokio::spawn(async move {
+        let mut stdin = tokio::io::stdin();
+        let mut buffer = vec![0u8; STDIN_CHUNK];
+        loop {
+            let input = match stdin.read(&mut buffer).await {
+                Ok(0) | Err(_) => service_exec_input::Input::CloseStdin(true),
+                Ok(size) => service_exec_input::Input::Stdin(buffer[..size].to_vec()),
+            };
+            let closed = matches!(input, service_exec_input::Input::CloseStdin(_));
+            if input_tx.send(exec_input(input)).await.is_err() || closed {
+                return;
+            }
+        }
+    });
+}
+
+#[cfg(unix)]
+fn spawn_resize_pump(mut input_tx: mpsc::Sender<ServiceExecInput>) {
+    use tokio::signal::unix::{SignalKind, signal};
+
+    let Ok(mut window_changes) = signal(SignalKind::window_change()) else {
+        return;
+    };
+    tokio::spawn(async move {
+        while window_changes.recv().await.is_some() {
+            let Some((rows, cols)) = terminal::size() else {
+                continue;
+            };
+            let resize = exec_input(service_exec_input::Input::Resize(ServiceExecResize {
+                rows: rows.into(),
+                cols: cols.into(),
+            }));
+            if input_tx.send(resize).await.is_err() {
+                return;
+            }
+        }
+    });
+}
+
+#[cfg(not(unix))]
+fn spawn_resize_pump(_input_tx: mpsc::Sender<ServiceExecInput>) {}
+
+#[cfg(unix)]
+mod terminal {
+    use std::io::IsTerminal;
+
+    /// Puts the controlling terminal in raw mode until dropped.
+    pub(super) struct RawMode(libc::termios);
+
+    impl RawMode {
+        pub(super) fn enable() -> Option<Self> {
+            if !std::io::stdin().is_terminal() {
+                return None;
+            }
+            // SAFETY: termios is plain data and both calls only read or write the struct we own.
+            unsafe {
+                let mut termios = std::mem::zeroed::<libc::termios>();
+                if libc::tcgetattr(libc::STDIN_FILENO, &mut termios) != 0 {
+                    return None;
+                }
+                let original = termios;
+                libc::cfmakeraw(&mut termios);
+                if libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &termios) != 0 {
+                    return None;
+                }
+                Some(Self(original))
+            }
+        }
+    }
+
+    impl Drop for RawMode {
+        fn drop(&mut self) {
+            // SAFETY: restores the settings read in `enable`.
+            unsafe {
+                libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &self.0);
+            }
+        }
+    }
+
+    /// Rows and columns of the terminal on stdout.
+    pub(super) fn size() -> Option<(u16, u16)> {
+        // SAFETY: TIOCGWINSZ fills the winsize struct we pass.
+        let size = unsafe {
+            let mut size = std::mem::zeroed::<libc::winsize>();
+            if libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) != 0 {
+                return None;
+            }
+            size
+        };
+        (size.ws_row > 0 && size.ws_col > 0).then_some((size.ws_row, size.ws_col))
+    }
+}
+
+#[cfg(not(unix))]
+mod terminal {
+    pub(super) struct RawMode;
+
+    impl RawMode {
+        pub(super) fn enable() -> Option<Self> {
+            None
+        }
+    }
+
+    pub(super) fn size() -> Option<(u16, u16)> {
+        None
+    }
+}
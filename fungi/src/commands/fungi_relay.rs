@@ -1,17 +1,25 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Parser;
 use fungi_swarm::behaviours::relay_refresh;
 use libp2p::{
-    Swarm,
-    core::{Multiaddr, multiaddr::Protocol},
+    PeerId, Swarm, Transport,
+    core::{
+        Multiaddr,
+        multiaddr::Protocol,
+        muxing::StreamMuxerBox,
+        transport::{Boxed, OptionalTransport, upgrade::Version},
+    },
+    dns,
     futures::StreamExt,
     identify,
     identity::Keypair,
     noise, ping, relay,
     swarm::{NetworkBehaviour, SwarmEvent},
-    tcp, yamux,
+    tcp, websocket, yamux,
 };
+use rustls_pki_types::{CertificateDer, PrivateKeyDer, pem::PemObject};
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 use tokio::time::Instant;
 
@@ -55,6 +63,34 @@ pub struct RelayArgs {
         default_value_t = DEFAULT_MAX_CIRCUIT_BYTES
     )]
     pub max_circuit_bytes: u64,
+
+    #[clap(
+        long,
+        help = "TCP listen port for plain WebSocket relay traffic, e.g. behind a TLS-terminating proxy"
+    )]
+    pub ws_listen_port: Option<u16>,
+
+    #[clap(
+        long,
+        help = "TCP listen port for secure WebSocket relay traffic, usually 443",
+        requires_all = ["tls_cert", "tls_key", "public_domain"]
+    )]
+    pub wss_listen_port: Option<u16>,
+
+    #[clap(
+        long,
+        help = "PEM certificate chain served on the secure WebSocket port"
+    )]
+    pub tls_cert: Option<PathBuf>,
+
+    #[clap(long, help = "PEM private key for the secure WebSocket certificate")]
+    pub tls_key: Option<PathBuf>,
+
+    #[clap(
+        long,
+        help = "Domain name the secure WebSocket certificate was issued for"
+    )]
+    pub public_domain: Option<String>,
 }
 
 #[derive(NetworkBehaviour)]
@@ -72,6 +108,11 @@ pub async fn run(args: RelayArgs) -> Result<()> {
     let max_circuit_duration = Duration::from_secs(args.max_circuit_duration_secs);
     let max_circuit_bytes = args.max_circuit_bytes;
 
+    let wss_tls_config = match (args.wss_listen_port, &args.tls_cert, &args.tls_key) {
+        (Some(_), Some(cert), Some(key)) => Some(load_wss_tls_config(cert, key)?),
+        _ => None,
+    };
+
     let keypair = get_or_init_keypair()?;
     let relay_config = relay::Config {
         max_circuit_duration,
@@ -87,6 +128,10 @@ pub async fn run(args: RelayArgs) -> Result<()> {
             yamux::Config::default,
         )?
         .with_quic()
+        .with_other_transport(|key| secure_websocket_transport(key, wss_tls_config))?
+        .with_dns()?
+        .with_websocket(noise::Config::new, yamux::Config::default)
+        .await?
         .with_behaviour(|key| Behaviour {
             relay: relay::Behaviour::new(key.public().to_peer_id(), relay_config),
             ping: ping::Behaviour::new(ping::Config::new()),
@@ -125,6 +170,29 @@ pub async fn run(args: RelayArgs) -> Result<()> {
     println!("{tcp_listen_addr}");
     println!("{udp_listen_addr}");
 
+    // WebSocket endpoints are for networks that only let HTTP(S) out. Clients try
+    // them last, after QUIC and raw TCP.
+    if let Some(ws_listen_port) = args.ws_listen_port {
+        let ws_external_addr = listen_websocket(
+            &mut swarm,
+            Protocol::from(public_ip),
+            ws_listen_port,
+            Protocol::Ws("/".into()),
+        )?;
+        println!("{}", with_peer(ws_external_addr, peer_id));
+    }
+    if let (Some(wss_listen_port), Some(public_domain)) =
+        (args.wss_listen_port, args.public_domain.clone())
+    {
+        let wss_external_addr = listen_websocket(
+            &mut swarm,
+            Protocol::Dns(public_domain.into()),
+            wss_listen_port,
+            Protocol::Wss("/".into()),
+        )?;
+        println!("{}", with_peer(wss_external_addr, peer_id));
+    }
+
     let mut last_refresh_at = None;
 
     loop {
@@ -184,6 +252,79 @@ fn add_external_address(
     Ok(())
 }
 
+/// Listens on every interface for `websocket` on `port` and advertises it under `host`.
+fn listen_websocket(
+    swarm: &mut Swarm<Behaviour>,
+    host: Protocol<'static>,
+    port: u16,
+    websocket: Protocol<'static>,
+) -> Result<Multiaddr> {
+    for ip in [
+        IpAddr::from(Ipv4Addr::UNSPECIFIED),
+        Ipv6Addr::UNSPECIFIED.into(),
+    ] {
+        swarm.listen_on(
+            Multiaddr::empty()
+                .with(Protocol::from(ip))
+                .with(Protocol::Tcp(port))
+                .with(websocket.clone()),
+        )?;
+    }
+    let external_addr = Multiaddr::empty()
+        .with(host)
+        .with(Protocol::Tcp(port))
+        .with(websocket);
+    swarm.add_external_address(external_addr.clone());
+    Ok(external_addr)
+}
+
+fn with_peer(addr: Multiaddr, peer_id: PeerId) -> Multiaddr {
+    addr.with_p2p(peer_id).expect("with_p2p failed")
+}
+
+type SecureWebSocketTransport = OptionalTransport<Boxed<(PeerId, StreamMuxerBox)>>;
+
+/// The swarm builder's WebSocket transport can only dial `/tls/ws`, so serving it needs a
+/// dedicated transport carrying the server certificate.
+fn secure_websocket_transport(
+    key: &Keypair,
+    tls_config: Option<websocket::tls::Config>,
+) -> Result<SecureWebSocketTransport, Box<dyn std::error::Error + Send + Sync>> {
+    let Some(tls_config) = tls_config else {
+        return Ok(OptionalTransport::none());
+    };
+    let tcp = dns::tokio::Transport::system(tcp::tokio::Transport::new(tcp::Config::default()))?;
+    let mut transport = websocket::Config::new(tcp);
+    transport.set_tls_config(tls_config);
+    let transport = transport
+        .upgrade(Version::V1Lazy)
+        .authenticate(noise::Config::new(key)?)
+        .multiplex(yamux::Config::default())
+        .map(|(peer_id, muxer), _| (peer_id, StreamMuxerBox::new(muxer)))
+        .boxed();
+    Ok(OptionalTransport::some(transport))
+}
+
+fn load_wss_tls_config(cert_path: &Path, key_path: &Path) -> Result<websocket::tls::Config> {
+    let cert_pem = std::fs::read(cert_path)
+        .with_context(|| format!("Failed to read TLS certificate {}", cert_path.display()))?;
+    let certs = CertificateDer::pem_slice_iter(&cert_pem)
+        .map(|cert| cert.map(|cert| websocket::tls::Certificate::new(cert.to_vec())))
+        .collect::<Result<Vec<_>, _>>()
+        .with_context(|| format!("Invalid TLS certificate {}", cert_path.display()))?;
+    if certs.is_empty() {
+        anyhow::bail!("No certificate found in {}", cert_path.display());
+    }
+
+    let key_pem = std::fs::read(key_path)
+        .with_context(|| format!("Failed to read TLS key {}", key_path.display()))?;
+    let key = PrivateKeyDer::from_pem_slice(&key_pem)
+        .with_context(|| format!("Invalid TLS key {}", key_path.display()))?;
+    let key = websocket::tls::PrivateKey::new(key.secret_der().to_vec());
+
+    websocket::tls::Config::new(key, certs).context("Invalid TLS certificate or key")
+}
+
 fn relay_refresh_is_rate_limited(last_refresh_at: &mut Option<Instant>) -> bool {
     let now = Instant::now();
     if let Some(last_refresh_at_value) = *last_refresh_at
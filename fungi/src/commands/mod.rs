@@ -73,9 +73,15 @@ pub enum Commands {
     /// Manage runtime safety boundary settings
     #[command(subcommand, visible_alias = "sec")]
     Security(fungi_control::SecurityCommands),
+    /// Manage encrypted secrets referenced by service env values
+    #[command(subcommand)]
+    Secret(fungi_control::SecretCommands),
     /// Manage services
     #[command(visible_alias = "svc")]
     Service(fungi_control::ServiceArgs),
+    /// Converge a fleet of devices to a declared desired state
+    #[command(subcommand)]
+    Fleet(fungi_control::FleetCommands),
     /// Query and administer remote peers
     #[command(subcommand, hide = true)]
     Peer(fungi_control::PeerCommands),
@@ -95,6 +101,14 @@ pub enum Commands {
         #[arg(short, long, default_value_t = false)]
         verbose: bool,
     },
+    /// Diagnose connectivity to a device and suggest fixes
+    Doctor {
+        /// Device name or device ID to diagnose
+        peer: fungi_control::PeerInput,
+        /// Show details for every check
+        #[arg(short, long, default_value_t = false)]
+        verbose: bool,
+    },
     #[cfg(feature = "wasi")]
     /// [WASI runtime] Run a WebAssembly module (re-exported wasmtime command)
     Run(wasmtime_cli::commands::RunCommand),
@@ -30,7 +30,9 @@ fn main() -> Result<()> {
         // control commands
         Commands::Info(cmd) => block_on(execute_info(fungi_args.common, cmd)),
         Commands::Security(cmd) => block_on(execute_security(fungi_args.common, cmd)),
+        Commands::Secret(cmd) => block_on(execute_secret(fungi_args.common, cmd)),
         Commands::Service(cmd) => block_on(execute_service(fungi_args.common, cmd)),
+        Commands::Fleet(cmd) => block_on(execute_fleet(fungi_args.common, cmd)),
         Commands::Peer(cmd) => block_on(execute_peer(fungi_args.common, cmd)),
         Commands::Device(cmd) => block_on(execute_device(fungi_args.common, cmd)),
         Commands::Connection(cmd) => block_on(execute_connection(fungi_args.common, cmd)),
@@ -39,6 +41,9 @@ fn main() -> Result<()> {
             interval_ms,
             verbose,
         } => block_on(execute_ping(fungi_args.common, peer, interval_ms, verbose)),
+        Commands::Doctor { peer, verbose } => {
+            block_on(execute_doctor(fungi_args.common, peer, verbose))
+        }
         Commands::Dynamic(tokens) => block_on(execute_dynamic_service(fungi_args.common, tokens)),
     }
 
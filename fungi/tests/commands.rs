@@ -2,9 +2,10 @@ use clap::{CommandFactory, Parser};
 use fungi::commands::{
     Commands, FungiArgs,
     fungi_control::{
-        DeviceAddressCommands, DeviceCommands, DeviceInput, ServiceArgs, ServiceCommands,
-        ServiceRecipeCommands,
+        DeviceAddressCommands, DeviceCommands, DeviceInput, FleetCommands, SecretCommands,
+        ServiceArgs, ServiceCommands, ServiceRecipeCommands,
     },
+    fungi_daemon::DaemonSubcommand,
 };
 
 #[test]
@@ -102,6 +103,8 @@ fn parses_service_apply_recipe_flags() {
                 manifest,
                 recipe,
                 create,
+                set,
+                values_file,
                 refresh,
                 dry_run,
                 start,
@@ -118,12 +121,46 @@ fn parses_service_apply_recipe_flags() {
     assert!(manifest.is_none());
     assert_eq!(recipe.as_deref(), Some("ssh-tunnel"));
     assert!(!create);
+    assert!(set.is_empty());
+    assert!(values_file.is_none());
     assert!(refresh);
     assert!(dry_run);
     assert!(start);
     assert!(yes);
 }
 
+#[test]
+fn parses_service_apply_input_values() {
+    let args = FungiArgs::try_parse_from([
+        "fungi",
+        "service",
+        "apply",
+        "blog",
+        "--recipe",
+        "ghost",
+        "--set",
+        "http_port=8081",
+        "--set",
+        "image_tag=5.2",
+        "--values",
+        "blog.values.yaml",
+    ])
+    .unwrap();
+
+    let Commands::Service(ServiceArgs {
+        command: Some(ServiceCommands::Apply {
+            set, values_file, ..
+        }),
+        ..
+    }) = args.command
+    else {
+        panic!("expected service apply command");
+    };
+
+    assert_eq!(set, vec!["http_port=8081", "image_tag=5.2"]);
+    assert_eq!(values_file.as_deref(), Some("blog.values.yaml"));
+}
+
 #[test]
 fn rejects_service_apply_recipe_with_manifest() {
     let result = FungiArgs::try_parse_from([
@@ -239,6 +276,8 @@ fn parses_service_connect_with_device() {
                 service,
                 entry,
                 local_port,
+                wake,
+                wake_timeout,
             }),
         ..
     }) = args.command
@@ -249,6 +288,8 @@ fn parses_service_connect_with_device() {
     assert_eq!(service, "home-ssh");
     assert!(entry.is_none());
     assert!(local_port.is_none());
+    assert!(!wake);
+    assert!(wake_timeout.is_none());
     assert!(matches!(device.device, Some(DeviceInput::Name(name)) if name == "home"));
 }
 
@@ -273,6 +314,8 @@ fn parses_service_connect_with_fixed_local_port() {
                 service,
                 entry,
                 local_port,
+                wake,
+                wake_timeout,
             }),
         ..
     }) = args.command
@@ -283,6 +326,60 @@ fn parses_service_connect_with_fixed_local_port() {
     assert_eq!(service, "home-ssh");
     assert_eq!(entry.as_deref(), Some("ssh"));
     assert_eq!(local_port, Some(2222));
+    assert!(!wake);
+    assert!(wake_timeout.is_none());
+}
+
+#[test]
+fn parses_service_connect_with_wake() {
+    let args = FungiArgs::try_parse_from(["fungi", "service", "connect", "home-ssh@nas", "--wake"])
+        .unwrap();
+
+    let Commands::Service(ServiceArgs {
+        command: Some(ServiceCommands::Connect { service, wake, .. }),
+        ..
+    }) = args.command
+    else {
+        panic!("expected service connect command");
+    };
+
+    assert_eq!(service, "home-ssh@nas");
+    assert!(wake);
+}
+
+#[test]
+fn parses_service_connect_wake_timeout_only_with_wake() {
+    let args = FungiArgs::try_parse_from([
+        "fungi",
+        "service",
+        "connect",
+        "home-ssh@nas",
+        "--wake",
+        "--wake-timeout",
+        "120",
+    ])
+    .unwrap();
+
+    let Commands::Service(ServiceArgs {
+        command: Some(ServiceCommands::Connect { wake_timeout, .. }),
+        ..
+    }) = args.command
+    else {
+        panic!("expected service connect command");
+    };
+    assert_eq!(wake_timeout, Some(120));
+
+    assert!(
+        FungiArgs::try_parse_from([
+            "fungi",
+            "service",
+            "connect",
+            "home-ssh@nas",
+            "--wake-timeout",
+            "120",
+        ])
+        .is_err()
+    );
 }
 
 #[test]
@@ -301,6 +398,257 @@ fn parses_service_disconnect_reference() {
     assert_eq!(service, "home-ssh@nas");
 }
 
+#[test]
+fn parses_secret_set_without_inline_value() {
+    let args = FungiArgs::try_parse_from(["fungi", "secret", "set", "db_password"]).unwrap();
+
+    let Commands::Secret(SecretCommands::Set { name, value }) = args.command else {
+        panic!("expected secret set command");
+    };
+
+    assert_eq!(name, "db_password");
+    assert_eq!(value, None);
+}
+
+#[test]
+fn parses_service_rollback_target_revision() {
+    let args =
+        FungiArgs::try_parse_from(["fungi", "service", "rollback", "web", "--to", "2"]).unwrap();
+
+    let Commands::Service(ServiceArgs {
+        command: Some(ServiceCommands::Rollback { name, to }),
+        ..
+    }) = args.command
+    else {
+        panic!("expected service rollback command");
+    };
+
+    assert_eq!(name, "web");
+    assert_eq!(to, Some(2));
+}
+
+#[test]
+fn parses_service_prune_images_dry_run() {
+    let args =
+        FungiArgs::try_parse_from(["fungi", "service", "prune-images", "--dry-run"]).unwrap();
+
+    let Commands::Service(ServiceArgs {
+        command: Some(ServiceCommands::PruneImages { dry_run }),
+        ..
+    }) = args.command
+    else {
+        panic!("expected service prune-images command");
+    };
+
+    assert!(dry_run);
+}
+
+#[test]
+fn parses_service_top_interval() {
+    let args = FungiArgs::try_parse_from(["fungi", "service", "top", "--interval", "5", "--once"])
+        .unwrap();
+
+    let Commands::Service(ServiceArgs {
+        command: Some(ServiceCommands::Top { interval, once }),
+        ..
+    }) = args.command
+    else {
+        panic!("expected service top command");
+    };
+
+    assert_eq!(interval, 5);
+    assert!(once);
+    assert!(FungiArgs::try_parse_from(["fungi", "service", "top", "--interval", "0"]).is_err());
+}
+
+#[test]
+fn parses_service_exec_command() {
+    let args = FungiArgs::try_parse_from([
+        "fungi", "service", "exec", "web", "--device", "nas", "-t", "--", "sh", "-c", "ls -la",
+    ])
+    .unwrap();
+
+    let Commands::Service(ServiceArgs {
+        command:
+            Some(ServiceCommands::Exec {
+                name,
+                device,
+                tty,
+                command,
+            }),
+        ..
+    }) = args.command
+    else {
+        panic!("expected service exec command");
+    };
+
+    assert_eq!(name, "web");
+    assert!(matches!(device, Some(DeviceInput::Name(name)) if name == "nas"));
+    assert!(tty);
+    assert_eq!(command, vec!["sh", "-c", "ls -la"]);
+    assert!(FungiArgs::try_parse_from(["fungi", "service", "exec", "web"]).is_err());
+}
+
+#[test]
+fn parses_service_move_command() {
+    let args = FungiArgs::try_parse_from([
+        "fungi", "service", "move", "notes", "--from", "laptop", "--to", "nas", "--clone",
+    ])
+    .unwrap();
+
+    let Commands::Service(ServiceArgs {
+        command:
+            Some(ServiceCommands::Move {
+                name,
+                from,
+                to,
+                clone,
+            }),
+        ..
+    }) = args.command
+    else {
+        panic!("expected service move command");
+    };
+
+    assert_eq!(name, "notes");
+    assert!(matches!(from, Some(DeviceInput::Name(name)) if name == "laptop"));
+    assert!(matches!(to, Some(DeviceInput::Name(name)) if name == "nas"));
+    assert!(clone);
+}
+
+#[test]
+fn parses_service_backup_command_with_hook() {
+    let args = FungiArgs::try_parse_from([
+        "fungi",
+        "service",
+        "backup",
+        "notes",
+        "--stop",
+        "--to",
+        "nas",
+        "--",
+        "sqlite3",
+        "/data/notes.db",
+        ".dump",
+    ])
+    .unwrap();
+
+    let Commands::Service(ServiceArgs {
+        command:
+            Some(ServiceCommands::Backup {
+                name,
+                stop,
+                to,
+                hook,
+            }),
+        ..
+    }) = args.command
+    else {
+        panic!("expected service backup command");
+    };
+
+    assert_eq!(name, "notes");
+    assert!(stop);
+    assert!(matches!(to, Some(DeviceInput::Name(name)) if name == "nas"));
+    assert_eq!(hook, vec!["sqlite3", "/data/notes.db", ".dump"]);
+}
+
+#[test]
+fn parses_service_restore_command() {
+    let args = FungiArgs::try_parse_from([
+        "fungi",
+        "service",
+        "restore",
+        "notes",
+        "--from",
+        "nas",
+        "--backup",
+        "01JBACKUP",
+        "--start",
+    ])
+    .unwrap();
+
+    let Commands::Service(ServiceArgs {
+        command:
+            Some(ServiceCommands::Restore {
+                name,
+                from,
+                backup_id,
+                start,
+            }),
+        ..
+    }) = args.command
+    else {
+        panic!("expected service restore command");
+    };
+
+    assert_eq!(name, "notes");
+    assert!(matches!(from, Some(DeviceInput::Name(name)) if name == "nas"));
+    assert_eq!(backup_id.as_deref(), Some("01JBACKUP"));
+    assert!(start);
+}
+
+#[test]
+fn parses_fleet_apply_command() {
+    let args =
+        FungiArgs::try_parse_from(["fungi", "fleet", "apply", "fleet.yaml", "--prune", "-y"])
+            .unwrap();
+
+    let Commands::Fleet(FleetCommands::Apply {
+        file,
+        dry_run,
+        prune,
+        yes,
+    }) = args.command
+    else {
+        panic!("expected fleet apply command");
+    };
+
+    assert_eq!(file, "fleet.yaml");
+    assert!(!dry_run);
+    assert!(prune);
+    assert!(yes);
+}
+
+#[test]
+fn parses_service_group_members_and_routing() {
+    let args = FungiArgs::try_parse_from([
+        "fungi",
+        "service",
+        "group",
+        "files@nas",
+        "--with",
+        "backup-nas",
+        "--with",
+        "laptop",
+        "--round-robin",
+    ])
+    .unwrap();
+
+    let Commands::Service(ServiceArgs {
+        command:
+            Some(ServiceCommands::Group {
+                service,
+                members,
+                round_robin,
+            }),
+        ..
+    }) = args.command
+    else {
+        panic!("expected service group command");
+    };
+
+    assert_eq!(service, "files@nas");
+    assert_eq!(
+        members,
+        vec![
+            DeviceInput::Name("backup-nas".to_string()),
+            DeviceInput::Name("laptop".to_string()),
+        ]
+    );
+    assert!(round_robin);
+}
+
 #[test]
 fn parses_service_list_with_device() {
     let args = FungiArgs::try_parse_from(["fungi", "service", "--device", "home", "list"]).unwrap();
@@ -587,6 +935,21 @@ fn parses_device_remove_by_name() {
     assert_eq!(device, "nas");
 }
 
+#[test]
+fn parses_device_wake_with_wait() {
+    let args = FungiArgs::try_parse_from(["fungi", "device", "wake", "nas", "--wait"]).unwrap();
+
+    let Commands::Device(device_args) = args.command else {
+        panic!("expected device command");
+    };
+    let Some(DeviceCommands::Wake { device, wait }) = device_args.command else {
+        panic!("expected device wake command");
+    };
+
+    assert_eq!(device, "nas");
+    assert!(wait);
+}
+
 #[test]
 fn parses_device_address_add() {
     let args = FungiArgs::try_parse_from([
@@ -677,3 +1040,69 @@ fn service_command_help_prefers_device_language() {
     assert!(!help.contains("Peer ID"));
     assert!(!help.contains("this node"));
 }
+
+#[test]
+fn parses_relay_server_with_secure_websocket() {
+    let args = FungiArgs::try_parse_from([
+        "fungi",
+        "daemon",
+        "relay-server",
+        "--public-ip",
+        "203.0.113.7",
+        "--wss-listen-port",
+        "443",
+        "--tls-cert",
+        "/etc/fungi/relay.crt",
+        "--tls-key",
+        "/etc/fungi/relay.key",
+        "--public-domain",
+        "relay.example.com",
+    ])
+    .unwrap();
+
+    let Commands::Daemon(daemon_args) = args.command else {
+        panic!("expected daemon command");
+    };
+    let Some(DaemonSubcommand::RelayServer(relay_args)) = daemon_args.subcommand else {
+        panic!("expected relay server command");
+    };
+
+    assert_eq!(relay_args.wss_listen_port, Some(443));
+    assert_eq!(relay_args.ws_listen_port, None);
+    assert_eq!(
+        relay_args.public_domain.as_deref(),
+        Some("relay.example.com")
+    );
+}
+
+#[test]
+fn rejects_secure_websocket_relay_without_certificate() {
+    let result = FungiArgs::try_parse_from([
+        "fungi",
+        "daemon",
+        "relay-server",
+        "--public-ip",
+        "203.0.113.7",
+        "--wss-listen-port",
+        "443",
+    ]);
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn parses_service_gateway_with_device() {
+    let args =
+        FungiArgs::try_parse_from(["fungi", "service", "--device", "nas", "gateway"]).unwrap();
+
+    let Commands::Service(ServiceArgs {
+        device,
+        command: Some(ServiceCommands::Gateway),
+        ..
+    }) = args.command
+    else {
+        panic!("expected service gateway command");
+    };
+
+    assert!(matches!(device.device, Some(DeviceInput::Name(name)) if name == "nas"));
+}